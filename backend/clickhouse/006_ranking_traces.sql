@@ -0,0 +1,31 @@
+-- ============================================
+-- ClickHouse Ranking Trace Table
+-- 排序解釋 (Ranking Explainability)
+-- ============================================
+
+-- ============================================
+-- 1. Ranking Traces (排序追蹤表)
+-- ============================================
+-- Sampled RankFeed explanations written by ranking-service.
+-- `trace` holds the full JSON: recall sources, coarse score, feature
+-- contributions, objective scores, session boost, exploration and
+-- diversity decisions for every returned post.
+
+CREATE TABLE IF NOT EXISTS ranking_traces (
+    request_id String,
+    user_id String,
+    created_at DateTime64(3),
+    surface LowCardinality(String) DEFAULT '',
+    value_model_variant LowCardinality(String) DEFAULT '',
+    exploration_policy LowCardinality(String) DEFAULT '',
+    post_count UInt32,
+    post_ids Array(String),              -- Returned posts, in response order
+    trace String,                        -- RankingTrace JSON
+    -- Date for partitioning
+    event_date Date DEFAULT toDate(created_at),
+    INDEX idx_user_id user_id TYPE bloom_filter GRANULARITY 4
+) ENGINE = MergeTree()
+PARTITION BY toYYYYMM(event_date)
+ORDER BY (request_id)
+TTL event_date + INTERVAL 90 DAY
+SETTINGS index_granularity = 8192;
@@ -0,0 +1,18 @@
+-- Migration: Add audience_list_id to posts table
+-- Description: Posts can be limited to one of the author's audience lists
+-- (close friends or a custom list). Lists and their membership are owned by
+-- graph-service; this column only stores the target list id.
+
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS audience_list_id UUID NULL;
+
+-- Global discovery surfaces (recent/trending) skip list-limited posts
+CREATE INDEX IF NOT EXISTS idx_posts_audience_list_id
+    ON posts(audience_list_id)
+    WHERE audience_list_id IS NOT NULL;
+
+COMMENT ON COLUMN posts.audience_list_id IS
+    'graph-service audience list the post is limited to; NULL = default audience';
+
+-- Note: story close friends are now read from the owner's graph-service
+-- close friends list; story_close_friends is no longer read or written.
@@ -0,0 +1,17 @@
+-- Migration: Track the story close friends backfill into graph-service
+-- Description: Story close friends moved to the owner's graph-service close
+-- friends list (20261017_add_post_audience_list). The close_friends_backfill
+-- job copies existing story_close_friends rows into that list and stamps
+-- migrated_at once a row has been copied.
+
+DO $$
+BEGIN
+    IF to_regclass('public.story_close_friends') IS NOT NULL THEN
+        ALTER TABLE story_close_friends
+            ADD COLUMN IF NOT EXISTS migrated_at TIMESTAMPTZ NULL;
+
+        CREATE INDEX IF NOT EXISTS idx_story_close_friends_pending
+            ON story_close_friends(owner_id)
+            WHERE migrated_at IS NULL;
+    END IF;
+END $$;
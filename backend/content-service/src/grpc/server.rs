@@ -1,9 +1,11 @@
 use crate::cache::{ContentCache, FeedCache};
 use crate::db::channel_repo;
+use crate::error::AppError;
 use crate::grpc::AuthClient;
 use crate::models::{Channel as DbChannel, Post as DbPost};
 use crate::services::feed_ranking::FeedRankingService;
 use crate::services::posts::PostService;
+use crate::services::AudienceClient;
 use base64::Engine;
 use grpc_clients::nova::content_service::v2::content_service_server::{
     ContentService, ContentServiceServer,
@@ -23,6 +25,7 @@ use grpc_clients::nova::feed_service::v2::InvalidateFeedCacheRequest;
 use grpc_clients::GrpcClientPool;
 use grpc_metrics::layer::RequestGuard;
 use sqlx::{PgPool, QueryBuilder, Row};
+use std::collections::HashSet;
 use std::fs;
 use std::sync::Arc;
 use tokio::sync::broadcast;
@@ -69,6 +72,10 @@ fn convert_post_to_proto(post: &DbPost) -> Post {
             .author_account_type
             .clone()
             .unwrap_or_else(|| "primary".to_string()),
+        audience_list_id: post
+            .audience_list_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
     }
 }
 
@@ -94,6 +101,65 @@ fn convert_channel_to_proto(channel: &DbChannel) -> Channel {
     }
 }
 
+/// Viewer of a read request; empty or malformed ids read as anonymous
+fn parse_viewer_id(viewer_id: &str) -> Option<Uuid> {
+    Uuid::parse_str(viewer_id).ok()
+}
+
+/// Whether the viewer may see a post given the audience lists they are on
+fn audience_visible(post: &DbPost, viewer_id: Option<Uuid>, visible_lists: &HashSet<Uuid>) -> bool {
+    match post.audience_list_id {
+        None => true,
+        Some(list_id) => viewer_id == Some(post.user_id) || visible_lists.contains(&list_id),
+    }
+}
+
+impl ContentServiceImpl {
+    /// Audience lists (among `list_ids`) that include the viewer
+    ///
+    /// Without a viewer, or if graph-service is unavailable, no list is
+    /// visible and list-limited posts are hidden (fail closed).
+    async fn visible_audience_lists(
+        &self,
+        viewer_id: Option<Uuid>,
+        list_ids: Vec<Uuid>,
+    ) -> HashSet<Uuid> {
+        let Some(viewer_id) = viewer_id else {
+            return HashSet::new();
+        };
+        if list_ids.is_empty() {
+            return HashSet::new();
+        }
+        match AudienceClient::new(self.grpc_pool.clone())
+            .member_of(viewer_id, &list_ids)
+            .await
+        {
+            Ok(visible) => visible,
+            Err(e) => {
+                tracing::warn!(
+                    "audience membership check failed (hiding list-limited posts): {}",
+                    e
+                );
+                HashSet::new()
+            }
+        }
+    }
+
+    /// Resolve an audience list reference against the author's lists in graph-service
+    async fn resolve_audience_list(&self, owner_id: Uuid, list_ref: &str) -> Result<Uuid, Status> {
+        AudienceClient::new(self.grpc_pool.clone())
+            .resolve_owned_list(owner_id, list_ref)
+            .await
+            .map_err(|e| match e {
+                AppError::BadRequest(msg) => Status::invalid_argument(msg),
+                other => {
+                    tracing::error!("audience list lookup failed: {}", other);
+                    Status::unavailable("audience lists unavailable")
+                }
+            })
+    }
+}
+
 #[tonic::async_trait]
 impl ContentService for ContentServiceImpl {
     async fn create_post(
@@ -165,6 +231,15 @@ impl ContentService for ContentServiceImpl {
             &req.author_account_type
         };
 
+        let audience_list_id = if req.audience_list_id.is_empty() {
+            None
+        } else {
+            Some(
+                self.resolve_audience_list(author_id, &req.audience_list_id)
+                    .await?,
+            )
+        };
+
         let post = post_service
             .create_post_with_urls_and_channels(
                 author_id,
@@ -174,6 +249,7 @@ impl ContentService for ContentServiceImpl {
                 &req.media_urls,
                 &resolved_channel_ids,
                 Some(account_type),
+                audience_list_id,
             )
             .await
             .map_err(|e| {
@@ -212,9 +288,19 @@ impl ContentService for ContentServiceImpl {
         let req = request.into_inner();
         let post_id = Uuid::parse_str(&req.post_id)
             .map_err(|_| Status::invalid_argument("invalid post_id"))?;
+        let viewer_id = parse_viewer_id(&req.viewer_id);
 
         let post_service = PostService::with_cache(self.db_pool.clone(), self.cache.clone());
-        match post_service.get_post(post_id).await {
+        let post = match post_service.get_post(post_id).await {
+            Ok(Some(post)) => {
+                let visible_lists = self
+                    .visible_audience_lists(viewer_id, post.audience_list_id.into_iter().collect())
+                    .await;
+                Ok(Some(post).filter(|p| audience_visible(p, viewer_id, &visible_lists)))
+            }
+            other => other,
+        };
+        match post {
             Ok(Some(post)) => {
                 guard.complete("0");
                 Ok(Response::new(GetPostResponse {
@@ -249,6 +335,7 @@ impl ContentService for ContentServiceImpl {
             }));
         }
 
+        let viewer_id = parse_viewer_id(&req.viewer_id);
         let mut post_ids = Vec::with_capacity(req.post_ids.len());
         for pid in &req.post_ids {
             post_ids.push(Uuid::parse_str(pid).map_err(|_| Status::invalid_argument("bad id"))?);
@@ -278,7 +365,7 @@ impl ContentService for ContentServiceImpl {
         let mut db_posts = Vec::new();
         if !cache_misses.is_empty() {
             db_posts = sqlx::query_as::<_, DbPost>(
-                "SELECT id, user_id, content, caption, media_key, media_type, media_urls, status, created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type \
+                "SELECT id, user_id, content, caption, media_key, media_type, media_urls, status, created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type, audience_list_id \
                  FROM posts WHERE deleted_at IS NULL AND id = ANY($1::uuid[])",
             )
             .bind(&cache_misses)
@@ -323,6 +410,17 @@ impl ContentService for ContentServiceImpl {
             all_posts.insert(post.id, post);
         }
 
+        // Hide posts limited to audience lists the viewer is not on (reported as not found)
+        let list_ids: Vec<Uuid> = all_posts
+            .values()
+            .filter(|post| viewer_id != Some(post.user_id))
+            .filter_map(|post| post.audience_list_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let visible_lists = self.visible_audience_lists(viewer_id, list_ids).await;
+        all_posts.retain(|_, post| audience_visible(post, viewer_id, &visible_lists));
+
         // Step 6: Load media URLs from post_images for posts that don't yet have media_urls.
         let media_rows = sqlx::query(
             r#"
@@ -392,6 +490,10 @@ impl ContentService for ContentServiceImpl {
                             .author_account_type
                             .clone()
                             .unwrap_or_else(|| "primary".to_string()),
+                        audience_list_id: post
+                            .audience_list_id
+                            .map(|id| id.to_string())
+                            .unwrap_or_default(),
                     }
                 })
             })
@@ -417,27 +519,52 @@ impl ContentService for ContentServiceImpl {
         let req = request.into_inner();
         let user_id = Uuid::parse_str(&req.user_id)
             .map_err(|_| Status::invalid_argument("invalid user_id"))?;
+        let viewer_id = parse_viewer_id(&req.viewer_id);
 
         let limit = req.limit.clamp(1, 100) as i64;
         let offset = req.offset.max(0) as i64;
 
+        // Audience lists the viewer may see; None (no filter) for the author
+        let visible_lists: Option<Vec<Uuid>> = if viewer_id == Some(user_id) {
+            None
+        } else {
+            let list_ids: Vec<Uuid> = sqlx::query_scalar(
+                "SELECT DISTINCT audience_list_id FROM posts WHERE user_id = $1 AND audience_list_id IS NOT NULL AND deleted_at IS NULL",
+            )
+            .bind(user_id)
+            .fetch_all(&self.db_pool)
+            .await
+            .map_err(|e| Status::internal(format!("db error: {}", e)))?;
+            Some(
+                self.visible_audience_lists(viewer_id, list_ids)
+                    .await
+                    .into_iter()
+                    .collect(),
+            )
+        };
+
         let (posts, total): (Vec<DbPost>, i64) = if req.status == ContentStatus::Unspecified as i32
         {
             let posts = sqlx::query_as::<_, DbPost>(
-                "SELECT id, user_id, content, caption, media_key, media_type, media_urls, status, created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type \
-                 FROM posts WHERE user_id = $1 AND deleted_at IS NULL ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+                "SELECT id, user_id, content, caption, media_key, media_type, media_urls, status, created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type, audience_list_id \
+                 FROM posts WHERE user_id = $1 AND deleted_at IS NULL \
+                 AND ($4::uuid[] IS NULL OR audience_list_id IS NULL OR audience_list_id = ANY($4)) \
+                 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
             )
             .bind(user_id)
             .bind(limit)
             .bind(offset)
+            .bind(&visible_lists)
             .fetch_all(&self.db_pool)
             .await
             .map_err(|e| Status::internal(format!("db error: {}", e)))?;
 
             let total = sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM posts WHERE user_id = $1 AND deleted_at IS NULL",
+                "SELECT COUNT(*) FROM posts WHERE user_id = $1 AND deleted_at IS NULL \
+                 AND ($2::uuid[] IS NULL OR audience_list_id IS NULL OR audience_list_id = ANY($2))",
             )
             .bind(user_id)
+            .bind(&visible_lists)
             .fetch_one(&self.db_pool)
             .await
             .map_err(|e| Status::internal(format!("db error: {}", e)))?;
@@ -453,22 +580,27 @@ impl ContentService for ContentServiceImpl {
             };
 
             let posts = sqlx::query_as::<_, DbPost>(
-                "SELECT id, user_id, content, caption, media_key, media_type, media_urls, status, created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type \
-                 FROM posts WHERE user_id = $1 AND status = $2 AND deleted_at IS NULL ORDER BY created_at DESC LIMIT $3 OFFSET $4",
+                "SELECT id, user_id, content, caption, media_key, media_type, media_urls, status, created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type, audience_list_id \
+                 FROM posts WHERE user_id = $1 AND status = $2 AND deleted_at IS NULL \
+                 AND ($5::uuid[] IS NULL OR audience_list_id IS NULL OR audience_list_id = ANY($5)) \
+                 ORDER BY created_at DESC LIMIT $3 OFFSET $4",
             )
             .bind(user_id)
             .bind(status_filter)
             .bind(limit)
             .bind(offset)
+            .bind(&visible_lists)
             .fetch_all(&self.db_pool)
             .await
             .map_err(|e| Status::internal(format!("db error: {}", e)))?;
 
             let total = sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM posts WHERE user_id = $1 AND status = $2 AND deleted_at IS NULL",
+                "SELECT COUNT(*) FROM posts WHERE user_id = $1 AND status = $2 AND deleted_at IS NULL \
+                 AND ($3::uuid[] IS NULL OR audience_list_id IS NULL OR audience_list_id = ANY($3))",
             )
             .bind(user_id)
             .bind(status_filter)
+            .bind(&visible_lists)
             .fetch_one(&self.db_pool)
             .await
             .map_err(|e| Status::internal(format!("db error: {}", e)))?;
@@ -494,14 +626,14 @@ impl ContentService for ContentServiceImpl {
 
         let rows = if req.exclude_user_id.is_empty() {
             sqlx::query(
-                "SELECT id::text AS id FROM posts WHERE status = 'published' AND deleted_at IS NULL ORDER BY created_at DESC LIMIT $1",
+                "SELECT id::text AS id FROM posts WHERE status = 'published' AND deleted_at IS NULL AND audience_list_id IS NULL ORDER BY created_at DESC LIMIT $1",
             )
             .bind(limit)
             .fetch_all(&self.db_pool)
             .await
         } else {
             sqlx::query(
-                "SELECT id::text AS id FROM posts WHERE status = 'published' AND deleted_at IS NULL AND user_id <> $1::uuid ORDER BY created_at DESC LIMIT $2",
+                "SELECT id::text AS id FROM posts WHERE status = 'published' AND deleted_at IS NULL AND audience_list_id IS NULL AND user_id <> $1::uuid ORDER BY created_at DESC LIMIT $2",
             )
             .bind(&req.exclude_user_id)
             .bind(limit)
@@ -530,14 +662,14 @@ impl ContentService for ContentServiceImpl {
 
         let rows = if req.exclude_user_id.is_empty() {
             sqlx::query(
-                "SELECT p.id::text AS id FROM posts p JOIN post_metadata pm ON pm.post_id = p.id WHERE p.status = 'published' AND p.deleted_at IS NULL ORDER BY (pm.like_count * 3 + pm.comment_count * 2 + pm.view_count) DESC, p.created_at DESC LIMIT $1",
+                "SELECT p.id::text AS id FROM posts p JOIN post_metadata pm ON pm.post_id = p.id WHERE p.status = 'published' AND p.deleted_at IS NULL AND p.audience_list_id IS NULL ORDER BY (pm.like_count * 3 + pm.comment_count * 2 + pm.view_count) DESC, p.created_at DESC LIMIT $1",
             )
             .bind(limit)
             .fetch_all(&self.db_pool)
             .await
         } else {
             sqlx::query(
-                "SELECT p.id::text AS id FROM posts p JOIN post_metadata pm ON pm.post_id = p.id WHERE p.status = 'published' AND p.deleted_at IS NULL AND p.user_id <> $1::uuid ORDER BY (pm.like_count * 3 + pm.comment_count * 2 + pm.view_count) DESC, p.created_at DESC LIMIT $2",
+                "SELECT p.id::text AS id FROM posts p JOIN post_metadata pm ON pm.post_id = p.id WHERE p.status = 'published' AND p.deleted_at IS NULL AND p.audience_list_id IS NULL AND p.user_id <> $1::uuid ORDER BY (pm.like_count * 3 + pm.comment_count * 2 + pm.view_count) DESC, p.created_at DESC LIMIT $2",
             )
             .bind(&req.exclude_user_id)
             .bind(limit)
@@ -581,8 +713,32 @@ impl ContentService for ContentServiceImpl {
             .push(", comments_enabled = ")
             .push_bind(req.comments_enabled);
 
+        if req.clear_audience_list {
+            builder.push(", audience_list_id = NULL");
+        } else if !req.audience_list_id.is_empty() {
+            // Lists are owner-scoped, so resolve against the post's author
+            let author_id: Option<Uuid> = sqlx::query_scalar(
+                "SELECT user_id FROM posts WHERE id = $1 AND deleted_at IS NULL",
+            )
+            .bind(post_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("update_post db error: {}", e);
+                Status::internal("failed to update post")
+            })?;
+            let Some(author_id) = author_id else {
+                guard.complete("5");
+                return Err(Status::not_found("post not found"));
+            };
+            let list_id = self
+                .resolve_audience_list(author_id, &req.audience_list_id)
+                .await?;
+            builder.push(", audience_list_id = ").push_bind(list_id);
+        }
+
         builder.push(" WHERE id = ").push_bind(post_id);
-        builder.push(" AND deleted_at IS NULL RETURNING id, user_id, content, caption, media_key, media_type, media_urls, status, created_at, updated_at, deleted_at, soft_delete::text AS soft_delete");
+        builder.push(" AND deleted_at IS NULL RETURNING id, user_id, content, caption, media_key, media_type, media_urls, status, created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, audience_list_id");
 
         let post = builder
             .build_query_as::<DbPost>()
@@ -958,7 +1114,7 @@ impl ContentService for ContentServiceImpl {
             r#"
             SELECT p.id, p.user_id, p.content, p.caption, p.media_key, p.media_type,
                    p.media_urls, p.status, p.created_at, p.updated_at, p.deleted_at,
-                   p.soft_delete::text AS soft_delete, p.author_account_type
+                   p.soft_delete::text AS soft_delete, p.author_account_type, p.audience_list_id
             FROM posts p
             INNER JOIN likes l ON p.id = l.post_id
             WHERE l.user_id = $1 AND p.deleted_at IS NULL
@@ -1024,7 +1180,7 @@ impl ContentService for ContentServiceImpl {
             r#"
             SELECT p.id, p.user_id, p.content, p.caption, p.media_key, p.media_type,
                    p.media_urls, p.status, p.created_at, p.updated_at, p.deleted_at,
-                   p.soft_delete::text AS soft_delete, p.author_account_type
+                   p.soft_delete::text AS soft_delete, p.author_account_type, p.audience_list_id
             FROM posts p
             INNER JOIN bookmarks b ON p.id = b.post_id
             WHERE b.user_id = $1 AND p.deleted_at IS NULL
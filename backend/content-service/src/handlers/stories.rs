@@ -1,11 +1,13 @@
 /// Story handlers - HTTP endpoints for story operations
 use crate::error::{AppError, Result};
-use crate::services::{PrivacyLevel, StoriesService};
+use crate::services::{AudienceClient, PrivacyLevel, StoriesService};
 use actix_web::{dev::Payload, web, FromRequest, HttpRequest, HttpResponse};
 use chrono::{DateTime, Utc};
 use futures::future::{ready, Ready};
+use grpc_clients::GrpcClientPool;
 use serde::Deserialize;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[derive(Deserialize)]
@@ -62,13 +64,24 @@ impl FromRequest for AuthenticatedUser {
     }
 }
 
+fn stories_service(
+    pool: &web::Data<PgPool>,
+    grpc_pool: &web::Data<Arc<GrpcClientPool>>,
+) -> StoriesService {
+    StoriesService::new(
+        (***pool).clone(),
+        AudienceClient::new((***grpc_pool).clone()),
+    )
+}
+
 /// Create a new story
 pub async fn create_story(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     auth: AuthenticatedUser,
     req: web::Json<CreateStoryRequest>,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     let privacy = PrivacyLevel::try_from(req.privacy_level.as_str())?;
 
     let story = service
@@ -88,10 +101,11 @@ pub async fn create_story(
 /// Get a story
 pub async fn get_story(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     story_id: web::Path<Uuid>,
     auth: AuthenticatedUser,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     match service.get_story_for_viewer(*story_id, auth.0).await? {
         Some(story) => Ok(HttpResponse::Ok().json(story)),
         None => Ok(HttpResponse::NotFound().finish()),
@@ -101,10 +115,11 @@ pub async fn get_story(
 /// Get stories feed for user
 pub async fn get_stories_feed(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     auth: AuthenticatedUser,
     query: web::Query<PaginationParams>,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     let stories = service.list_feed(auth.0, query.limit).await?;
 
     Ok(HttpResponse::Ok().json(stories))
@@ -113,11 +128,12 @@ pub async fn get_stories_feed(
 /// Get user's stories
 pub async fn get_user_stories(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     owner_id: web::Path<Uuid>,
     auth: AuthenticatedUser,
     query: web::Query<PaginationParams>,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     let stories = service
         .list_user_stories(*owner_id, auth.0, query.limit)
         .await?;
@@ -128,10 +144,11 @@ pub async fn get_user_stories(
 /// Track story view
 pub async fn track_story_view(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     story_id: web::Path<Uuid>,
     auth: AuthenticatedUser,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     service.track_view(*story_id, auth.0).await?;
 
     Ok(HttpResponse::Ok().finish())
@@ -140,11 +157,12 @@ pub async fn track_story_view(
 /// Update story privacy
 pub async fn update_story_privacy(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     story_id: web::Path<Uuid>,
     auth: AuthenticatedUser,
     req: web::Json<UpdatePrivacyRequest>,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     let privacy = PrivacyLevel::try_from(req.privacy_level.as_str())?;
 
     let updated = service.update_privacy(auth.0, *story_id, privacy).await?;
@@ -159,10 +177,11 @@ pub async fn update_story_privacy(
 /// Delete a story
 pub async fn delete_story(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     story_id: web::Path<Uuid>,
     auth: AuthenticatedUser,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     let deleted = service.delete_story(auth.0, *story_id).await?;
 
     if deleted {
@@ -175,10 +194,11 @@ pub async fn delete_story(
 /// Add a close friend
 pub async fn add_close_friend(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     auth: AuthenticatedUser,
     req: web::Json<AddCloseFreindRequest>,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     service.add_close_friend(auth.0, req.friend_id).await?;
 
     Ok(HttpResponse::Ok().finish())
@@ -187,10 +207,11 @@ pub async fn add_close_friend(
 /// Remove a close friend
 pub async fn remove_close_friend(
     pool: web::Data<PgPool>,
+    grpc_pool: web::Data<Arc<GrpcClientPool>>,
     auth: AuthenticatedUser,
     friend_id: web::Path<Uuid>,
 ) -> Result<HttpResponse> {
-    let service = StoriesService::new((**pool).clone());
+    let service = stories_service(&pool, &grpc_pool);
     service.remove_close_friend(auth.0, *friend_id).await?;
 
     Ok(HttpResponse::Ok().finish())
@@ -0,0 +1,123 @@
+//! Story Close Friends Backfill Job
+//!
+//! Story close friends moved from content-service's `story_close_friends`
+//! table to the owner's graph-service close friends list. This job copies the
+//! rows not yet stamped `migrated_at` into graph-service, owner by owner, and
+//! exits once every row has been copied. Owners that fail are retried on the
+//! next pass.
+//!
+//! Adding list members is idempotent, so replicas running the job at the same
+//! time only repeat work.
+
+use crate::error::Result;
+use crate::services::AudienceClient;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::time::sleep;
+use uuid::Uuid;
+
+/// Owners copied per page
+const OWNER_BATCH: i64 = 100;
+
+/// graph-service accepts at most 1000 members per call
+const MEMBER_BATCH: usize = 1000;
+
+/// Pause before retrying owners that failed
+const RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+pub async fn start_close_friends_backfill(db: PgPool, audience: AudienceClient) {
+    loop {
+        match backfill_pass(&db, &audience).await {
+            Ok((copied, 0)) => {
+                tracing::info!(copied, "Story close friends backfill complete");
+                return;
+            }
+            Ok((copied, failed)) => tracing::warn!(
+                copied,
+                failed_owners = failed,
+                "Story close friends backfill incomplete, retrying"
+            ),
+            Err(e) => {
+                tracing::warn!(error = %e, "Story close friends backfill pass failed, retrying")
+            }
+        }
+        sleep(RETRY_INTERVAL).await;
+    }
+}
+
+/// Copy every pending owner once; returns (rows copied, owners failed)
+async fn backfill_pass(db: &PgPool, audience: &AudienceClient) -> Result<(u64, u64)> {
+    // Fresh installs never had the legacy table
+    let legacy_table: bool =
+        sqlx::query_scalar("SELECT to_regclass('public.story_close_friends') IS NOT NULL")
+            .fetch_one(db)
+            .await?;
+    if !legacy_table {
+        return Ok((0, 0));
+    }
+
+    let mut copied = 0;
+    let mut failed = 0;
+    let mut cursor = Uuid::nil();
+
+    loop {
+        let owners: Vec<(Uuid, Vec<Uuid>)> = sqlx::query_as(
+            r#"
+            SELECT owner_id, array_agg(friend_id)
+            FROM story_close_friends
+            WHERE migrated_at IS NULL AND owner_id > $1
+            GROUP BY owner_id
+            ORDER BY owner_id
+            LIMIT $2
+            "#,
+        )
+        .bind(cursor)
+        .bind(OWNER_BATCH)
+        .fetch_all(db)
+        .await?;
+
+        let Some(&(last, _)) = owners.last() else {
+            break;
+        };
+
+        for (owner_id, friend_ids) in &owners {
+            match copy_owner(db, audience, *owner_id, friend_ids).await {
+                Ok(rows) => copied += rows,
+                Err(e) => {
+                    tracing::warn!(owner_id = %owner_id, error = %e, "Failed to copy close friends to graph-service");
+                    failed += 1;
+                }
+            }
+        }
+
+        cursor = last;
+        if (owners.len() as i64) < OWNER_BATCH {
+            break;
+        }
+    }
+
+    Ok((copied, failed))
+}
+
+/// Copy one owner's close friends and stamp the copied rows
+async fn copy_owner(
+    db: &PgPool,
+    audience: &AudienceClient,
+    owner_id: Uuid,
+    friend_ids: &[Uuid],
+) -> Result<u64> {
+    let mut copied = 0;
+    for chunk in friend_ids.chunks(MEMBER_BATCH) {
+        audience.add_close_friends(owner_id, chunk).await?;
+
+        let result = sqlx::query(
+            "UPDATE story_close_friends SET migrated_at = NOW() WHERE owner_id = $1 AND friend_id = ANY($2)",
+        )
+        .bind(owner_id)
+        .bind(chunk)
+        .execute(db)
+        .await?;
+        copied += result.rows_affected();
+    }
+    Ok(copied)
+}
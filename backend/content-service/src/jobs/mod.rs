@@ -1,2 +1,3 @@
+pub mod close_friends_backfill;
 pub mod content_cleaner;
 pub mod feed_candidates;
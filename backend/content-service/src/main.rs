@@ -729,6 +729,19 @@ async fn main() -> io::Result<()> {
     });
     tracing::info!("✅ Content cleaner background job started");
 
+    // Copy legacy story close friends into graph-service audience lists
+    let backfill_db = db_pool.clone();
+    let backfill_audience = content_service::services::AudienceClient::new(grpc_pool.clone());
+    tasks.spawn(async move {
+        content_service::jobs::close_friends_backfill::start_close_friends_backfill(
+            backfill_db,
+            backfill_audience,
+        )
+        .await;
+        Ok(())
+    });
+    tracing::info!("✅ Story close friends backfill job started");
+
     let mut first_error: Option<io::Error> = None;
 
     let shutdown = shutdown_signal();
@@ -29,6 +29,9 @@ pub struct Post {
     /// Account type used when post was created: "primary" or "alias" (Issue #259)
     #[sqlx(default)]
     pub author_account_type: Option<String>,
+    /// Audience list the post is limited to (graph-service list id); None = default audience
+    #[sqlx(default)]
+    pub audience_list_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -0,0 +1,181 @@
+/// Audience lists (close friends / custom lists) owned by graph-service
+///
+/// Posts can target one of the author's lists and stories with
+/// `close_friends` privacy target the owner's close friends list. This client
+/// resolves list references at write time and answers visibility checks at
+/// read time; membership itself lives only in graph-service.
+use crate::error::{AppError, Result};
+use grpc_clients::nova::graph_service::v2::{
+    AddAudienceListMembersRequest, BatchCheckAudienceMembershipRequest,
+    GetAudienceListMembersRequest, GetAudienceListsRequest, RemoveAudienceListMembersRequest,
+};
+use grpc_clients::GrpcClientPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::Request;
+use uuid::Uuid;
+
+/// List reference for the owner's close friends list
+pub const CLOSE_FRIENDS_LIST: &str = "close_friends";
+
+/// graph-service caps batch checks at 1000 ids
+const MAX_BATCH: usize = 1000;
+
+#[derive(Clone)]
+pub struct AudienceClient {
+    grpc_pool: Arc<GrpcClientPool>,
+}
+
+impl AudienceClient {
+    pub fn new(grpc_pool: Arc<GrpcClientPool>) -> Self {
+        Self { grpc_pool }
+    }
+
+    /// Attach the internal token graph-service requires for list mutations
+    fn write_request<T>(message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Ok(token) = std::env::var("INTERNAL_GRAPH_WRITE_TOKEN") {
+            if let Ok(token_value) = token.parse() {
+                request
+                    .metadata_mut()
+                    .insert("x-internal-token", token_value);
+            }
+        }
+        request
+    }
+
+    /// Resolve a list id or `close_friends` to one of the owner's lists
+    pub async fn resolve_owned_list(&self, owner_id: Uuid, list_ref: &str) -> Result<Uuid> {
+        let lists = self
+            .grpc_pool
+            .graph()
+            .get_audience_lists(GetAudienceListsRequest {
+                owner_id: owner_id.to_string(),
+            })
+            .await
+            .map_err(|e| AppError::Internal(format!("graph-service audience lists: {}", e)))?
+            .into_inner()
+            .lists;
+
+        lists
+            .iter()
+            .find(|l| {
+                if list_ref == CLOSE_FRIENDS_LIST {
+                    l.kind == CLOSE_FRIENDS_LIST
+                } else {
+                    l.id == list_ref
+                }
+            })
+            .and_then(|l| Uuid::parse_str(&l.id).ok())
+            .ok_or_else(|| AppError::BadRequest("unknown audience list".into()))
+    }
+
+    pub async fn add_close_friend(&self, owner_id: Uuid, friend_id: Uuid) -> Result<()> {
+        self.add_close_friends(owner_id, &[friend_id]).await
+    }
+
+    /// Add up to 1000 members to the owner's close friends list (idempotent)
+    pub async fn add_close_friends(&self, owner_id: Uuid, friend_ids: &[Uuid]) -> Result<()> {
+        self.grpc_pool
+            .graph()
+            .add_audience_list_members(Self::write_request(AddAudienceListMembersRequest {
+                owner_id: owner_id.to_string(),
+                list_id: CLOSE_FRIENDS_LIST.to_string(),
+                member_ids: friend_ids.iter().map(|id| id.to_string()).collect(),
+            }))
+            .await
+            .map_err(|e| AppError::Internal(format!("graph-service add close friend: {}", e)))?;
+        Ok(())
+    }
+
+    pub async fn remove_close_friend(&self, owner_id: Uuid, friend_id: Uuid) -> Result<()> {
+        self.grpc_pool
+            .graph()
+            .remove_audience_list_members(Self::write_request(RemoveAudienceListMembersRequest {
+                owner_id: owner_id.to_string(),
+                list_id: CLOSE_FRIENDS_LIST.to_string(),
+                member_ids: vec![friend_id.to_string()],
+            }))
+            .await
+            .map_err(|e| AppError::Internal(format!("graph-service remove close friend: {}", e)))?;
+        Ok(())
+    }
+
+    /// First 1000 close friends, most recently added first
+    pub async fn list_close_friends(&self, owner_id: Uuid) -> Result<Vec<Uuid>> {
+        let response = self
+            .grpc_pool
+            .graph()
+            .get_audience_list_members(GetAudienceListMembersRequest {
+                owner_id: owner_id.to_string(),
+                list_id: CLOSE_FRIENDS_LIST.to_string(),
+                limit: MAX_BATCH as i32,
+                offset: 0,
+            })
+            .await
+            .map_err(|e| AppError::Internal(format!("graph-service close friends: {}", e)))?
+            .into_inner();
+
+        Ok(response
+            .member_ids
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect())
+    }
+
+    /// Lists (among `list_ids`) that include the viewer
+    pub async fn member_of(&self, viewer_id: Uuid, list_ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        let mut visible = HashSet::new();
+        for chunk in list_ids.chunks(MAX_BATCH) {
+            let response = self
+                .grpc_pool
+                .graph()
+                .batch_check_audience_membership(BatchCheckAudienceMembershipRequest {
+                    viewer_id: viewer_id.to_string(),
+                    list_ids: chunk.iter().map(|id| id.to_string()).collect(),
+                    close_friends_of: vec![],
+                })
+                .await
+                .map_err(|e| AppError::Internal(format!("graph-service audience check: {}", e)))?
+                .into_inner();
+            visible.extend(
+                response
+                    .lists
+                    .into_iter()
+                    .filter(|(_, is_member)| *is_member)
+                    .filter_map(|(id, _)| Uuid::parse_str(&id).ok()),
+            );
+        }
+        Ok(visible)
+    }
+
+    /// Owners (among `owner_ids`) whose close friends list includes the viewer
+    pub async fn close_friend_of(
+        &self,
+        viewer_id: Uuid,
+        owner_ids: &[Uuid],
+    ) -> Result<HashSet<Uuid>> {
+        let mut visible = HashSet::new();
+        for chunk in owner_ids.chunks(MAX_BATCH) {
+            let response = self
+                .grpc_pool
+                .graph()
+                .batch_check_audience_membership(BatchCheckAudienceMembershipRequest {
+                    viewer_id: viewer_id.to_string(),
+                    list_ids: vec![],
+                    close_friends_of: chunk.iter().map(|id| id.to_string()).collect(),
+                })
+                .await
+                .map_err(|e| AppError::Internal(format!("graph-service audience check: {}", e)))?
+                .into_inner();
+            visible.extend(
+                response
+                    .close_friends_of
+                    .into_iter()
+                    .filter(|(_, is_member)| *is_member)
+                    .filter_map(|(id, _)| Uuid::parse_str(&id).ok()),
+            );
+        }
+        Ok(visible)
+    }
+}
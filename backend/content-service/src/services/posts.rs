@@ -62,7 +62,7 @@ impl PostService {
         let post = sqlx::query_as::<_, Post>(
             r#"
             SELECT id, user_id, content, caption, media_key, media_type, media_urls, status,
-                   created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type
+                   created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type, audience_list_id
             FROM posts
             WHERE id = $1 AND deleted_at IS NULL
             "#,
@@ -90,7 +90,7 @@ impl PostService {
         let posts = sqlx::query_as::<_, Post>(
             r#"
             SELECT id, user_id, content, caption, media_key, media_type, media_urls, status,
-                   created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type
+                   created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type, audience_list_id
             FROM posts
             WHERE user_id = $1 AND deleted_at IS NULL
             ORDER BY created_at DESC
@@ -167,7 +167,7 @@ impl PostService {
                 $6
             )
             RETURNING id, user_id, content, caption, media_key, media_type, media_urls, status,
-                      created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type
+                      created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type, audience_list_id
             "#,
         )
         .bind(user_id)
@@ -218,6 +218,7 @@ impl PostService {
         media_urls: &[String],
         channel_ids: &[Uuid],
         author_account_type: Option<&str>,
+        audience_list_id: Option<Uuid>,
     ) -> Result<Post> {
         // Validate: posts must have either media or non-empty content
         // This prevents "empty posts" that have no content and no images
@@ -242,7 +243,7 @@ impl PostService {
         let account_type = author_account_type.unwrap_or("primary");
         let post = sqlx::query_as::<_, Post>(
             r#"
-            INSERT INTO posts (user_id, caption, media_key, media_type, media_urls, status, author_account_type)
+            INSERT INTO posts (user_id, caption, media_key, media_type, media_urls, status, author_account_type, audience_list_id)
             VALUES (
                 $1,
                 $2,
@@ -250,10 +251,11 @@ impl PostService {
                 $4,
                 CASE WHEN $5::jsonb = '[]'::jsonb AND $4 <> 'text' THEN jsonb_build_array($3) ELSE $5::jsonb END,
                 'published',
-                $6
+                $6,
+                $7
             )
             RETURNING id, user_id, content, caption, media_key, media_type, media_urls, status,
-                      created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type
+                      created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type, audience_list_id
             "#,
         )
         .bind(user_id)
@@ -262,6 +264,7 @@ impl PostService {
         .bind(media_type)
         .bind(media_urls_json)
         .bind(account_type)
+        .bind(audience_list_id)
         .fetch_one(&mut *tx)
         .await?;
 
@@ -389,7 +392,7 @@ impl PostService {
         let posts = sqlx::query_as::<_, Post>(
             r#"
             SELECT p.id, p.user_id, p.content, p.caption, p.media_key, p.media_type, p.media_urls, p.status,
-                   p.created_at, p.updated_at, p.deleted_at, p.soft_delete::text AS soft_delete, p.author_account_type
+                   p.created_at, p.updated_at, p.deleted_at, p.soft_delete::text AS soft_delete, p.author_account_type, p.audience_list_id
             FROM posts p
             INNER JOIN likes l ON p.id = l.post_id
             WHERE l.user_id = $1 AND p.deleted_at IS NULL
@@ -439,7 +442,7 @@ impl PostService {
         let posts = sqlx::query_as::<_, Post>(
             r#"
             SELECT p.id, p.user_id, p.content, p.caption, p.media_key, p.media_type, p.media_urls, p.status,
-                   p.created_at, p.updated_at, p.deleted_at, p.soft_delete::text AS soft_delete, p.author_account_type
+                   p.created_at, p.updated_at, p.deleted_at, p.soft_delete::text AS soft_delete, p.author_account_type, p.audience_list_id
             FROM posts p
             INNER JOIN bookmarks b ON p.id = b.post_id
             WHERE b.user_id = $1 AND p.deleted_at IS NULL
@@ -524,7 +527,7 @@ impl PostService {
             let posts = sqlx::query_as::<_, Post>(
                 r#"
                 SELECT id, user_id, content, caption, media_key, media_type, media_urls, status,
-                       created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type
+                       created_at, updated_at, deleted_at, soft_delete::text AS soft_delete, author_account_type, audience_list_id
                 FROM posts
                 WHERE id = ANY($1) AND deleted_at IS NULL
                 "#,
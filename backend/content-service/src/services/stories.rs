@@ -1,7 +1,9 @@
 use crate::error::{AppError, Result};
+use crate::services::audience::AudienceClient;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use sqlx::{postgres::PgRow, PgPool, Row};
+use std::collections::HashSet;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -48,11 +50,25 @@ pub struct Story {
 
 pub struct StoriesService {
     pool: PgPool,
+    /// Close friends lists live in graph-service
+    audience: AudienceClient,
 }
 
 impl StoriesService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audience: AudienceClient) -> Self {
+        Self { pool, audience }
+    }
+
+    /// Owners whose close friends list includes the viewer; empty when
+    /// graph-service is unavailable so close friends stories stay hidden
+    async fn close_friend_owners(&self, viewer_id: Uuid, owner_ids: &[Uuid]) -> HashSet<Uuid> {
+        match self.audience.close_friend_of(viewer_id, owner_ids).await {
+            Ok(owners) => owners,
+            Err(e) => {
+                tracing::warn!(viewer_id = %viewer_id, "close friends check failed: {}", e);
+                HashSet::new()
+            }
+        }
     }
 
     pub async fn create_story(
@@ -123,6 +139,27 @@ impl StoriesService {
         // - followers: 仅粉丝可见
         // - close_friends: 仅 owner 的 close_friends 列表可见
 
+        // close_friends 名单在 graph-service：先找出 viewer 关注的、有 close_friends 故事的作者，再批量校验
+        let close_friends_owners: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT s.user_id FROM follows f
+            JOIN stories s ON s.user_id = f.following_id
+            WHERE f.follower_id = $1
+              AND s.privacy_level = 'close_friends' AND s.deleted_at IS NULL AND s.expires_at > NOW()
+            "#,
+        )
+        .bind(viewer_id)
+        .fetch_all(&self.pool)
+        .await?;
+        let visible_owners: Vec<Uuid> = if close_friends_owners.is_empty() {
+            Vec::new()
+        } else {
+            self.close_friend_owners(viewer_id, &close_friends_owners)
+                .await
+                .into_iter()
+                .collect()
+        };
+
         // 简化：一次性查出所有未过期故事，再在 SQL 中应用可见性规则
         let rows = sqlx::query(
             r#"
@@ -135,10 +172,7 @@ impl StoriesService {
                         SELECT 1 FROM follows f
                         WHERE f.follower_id = $1 AND f.following_id = s.user_id
                     ))
-                 OR (s.privacy_level = 'close_friends' AND EXISTS (
-                        SELECT 1 FROM story_close_friends cf
-                        WHERE cf.owner_id = s.user_id AND cf.friend_id = $1
-                    ))
+                 OR (s.privacy_level = 'close_friends' AND s.user_id = ANY($3))
                  OR s.user_id = $1
               )
             ORDER BY s.created_at DESC
@@ -147,6 +181,7 @@ impl StoriesService {
         )
         .bind(viewer_id)
         .bind(limit.clamp(1, 100))
+        .bind(&visible_owners)
         .fetch_all(&self.pool)
         .await?;
 
@@ -193,37 +228,20 @@ impl StoriesService {
     }
 
     pub async fn add_close_friend(&self, owner_id: Uuid, friend_id: Uuid) -> Result<()> {
-        sqlx::query(
-            r#"INSERT INTO story_close_friends (owner_id, friend_id) VALUES ($1, $2)
-               ON CONFLICT (owner_id, friend_id) DO NOTHING"#,
-        )
-        .bind(owner_id)
-        .bind(friend_id)
-        .execute(&self.pool)
-        .await?;
-        Ok(())
+        if owner_id == friend_id {
+            return Err(AppError::BadRequest(
+                "cannot add yourself as a close friend".into(),
+            ));
+        }
+        self.audience.add_close_friend(owner_id, friend_id).await
     }
 
     pub async fn remove_close_friend(&self, owner_id: Uuid, friend_id: Uuid) -> Result<()> {
-        sqlx::query(r#"DELETE FROM story_close_friends WHERE owner_id = $1 AND friend_id = $2"#)
-            .bind(owner_id)
-            .bind(friend_id)
-            .execute(&self.pool)
-            .await?;
-        Ok(())
+        self.audience.remove_close_friend(owner_id, friend_id).await
     }
 
     pub async fn list_close_friends(&self, owner_id: Uuid) -> Result<Vec<Uuid>> {
-        let rows = sqlx::query(
-            r#"SELECT friend_id FROM story_close_friends WHERE owner_id = $1 ORDER BY added_at DESC"#,
-        )
-        .bind(owner_id)
-        .fetch_all(&self.pool)
-        .await?;
-        Ok(rows
-            .into_iter()
-            .map(|r| r.get::<Uuid, _>("friend_id"))
-            .collect())
+        self.audience.list_close_friends(owner_id).await
     }
 
     pub async fn delete_story(&self, owner_id: Uuid, story_id: Uuid) -> Result<bool> {
@@ -260,6 +278,12 @@ impl StoriesService {
         viewer_id: Uuid,
         limit: i64,
     ) -> Result<Vec<Story>> {
+        let is_close_friend = owner_id != viewer_id
+            && self
+                .close_friend_owners(viewer_id, &[owner_id])
+                .await
+                .contains(&owner_id);
+
         let rows = sqlx::query(
             r#"
             SELECT s.id, s.user_id, s.content_url, s.thumbnail_url, s.caption, s.content_type, s.privacy_level, s.expires_at, s.created_at
@@ -270,9 +294,7 @@ impl StoriesService {
                  OR (s.privacy_level = 'followers' AND EXISTS (
                         SELECT 1 FROM follows f WHERE f.follower_id = $2 AND f.following_id = s.user_id
                     ))
-                 OR (s.privacy_level = 'close_friends' AND EXISTS (
-                        SELECT 1 FROM story_close_friends cf WHERE cf.owner_id = s.user_id AND cf.friend_id = $2
-                    ))
+                 OR (s.privacy_level = 'close_friends' AND $4)
                  OR s.user_id = $2
               )
             ORDER BY s.created_at DESC
@@ -282,6 +304,7 @@ impl StoriesService {
         .bind(owner_id)
         .bind(viewer_id)
         .bind(limit.clamp(1, 100))
+        .bind(is_close_friend)
         .fetch_all(&self.pool)
         .await?;
 
@@ -304,16 +327,10 @@ impl StoriesService {
                 .await?;
                 Ok(exists.map(|t| t.0).unwrap_or(false))
             }
-            "close_friends" => {
-                let exists: Option<(bool,)> = sqlx::query_as(
-                    r#"SELECT EXISTS(SELECT 1 FROM story_close_friends WHERE owner_id = $1 AND friend_id = $2)"#,
-                )
-                .bind(owner_id)
-                .bind(viewer_id)
-                .fetch_optional(&self.pool)
-                .await?;
-                Ok(exists.map(|t| t.0).unwrap_or(false))
-            }
+            "close_friends" => Ok(self
+                .close_friend_owners(viewer_id, &[owner_id])
+                .await
+                .contains(&owner_id)),
             _ => Ok(false),
         }
     }
@@ -0,0 +1,37 @@
+-- Migration: Layered experiments, holdouts, targeting and ramp schedules
+-- Experiments in the same layer own disjoint bucket ranges (mutual exclusion);
+-- experiments in different layers overlap orthogonally.
+
+-- Create experiment_layers table
+CREATE TABLE experiment_layers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    -- Buckets [0, holdout_buckets) of the layer never enter an experiment
+    holdout_buckets INTEGER NOT NULL DEFAULT 0 CHECK (holdout_buckets BETWEEN 0 AND 10000),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create experiment_holdouts table (global holdout groups)
+CREATE TABLE experiment_holdouts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL UNIQUE,
+    buckets INTEGER NOT NULL CHECK (buckets BETWEEN 0 AND 10000),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create experiment_traffic table (one row per experiment)
+CREATE TABLE experiment_traffic (
+    experiment_id UUID PRIMARY KEY REFERENCES experiments(id) ON DELETE CASCADE,
+    layer_id UUID REFERENCES experiment_layers(id) ON DELETE RESTRICT,
+    -- [{"start": 0, "end": 5000}, ...] over 10,000 layer buckets
+    bucket_ranges JSONB NOT NULL DEFAULT '[]'::jsonb,
+    -- Targeting predicate tree, e.g. {"op": "eq", "attribute": "country", "value": "TW"}
+    targeting JSONB,
+    -- [{"starts_at": "...", "traffic_bps": 500}, ...]
+    ramp JSONB NOT NULL DEFAULT '[]'::jsonb,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_experiment_traffic_layer_id ON experiment_traffic(layer_id) WHERE layer_id IS NOT NULL;
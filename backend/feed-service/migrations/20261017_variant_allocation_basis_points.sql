@@ -0,0 +1,70 @@
+-- Migration: Variant traffic allocation in basis points
+-- Percent allocations only allowed 1% splits; basis points (10000 = 100%)
+-- match the 10,000-bucket hash space used for assignment.
+--
+-- experiment_variants was created by the legacy experiments schema script
+-- (migrations/scripts/pending_review/033_experiments_schema.sql), not by a
+-- feed-service migration. Convert it where it exists, create it otherwise.
+
+-- Sum check on the renamed column. Replaced first: the existing trigger calls
+-- this function for every row the conversion below touches.
+CREATE OR REPLACE FUNCTION validate_traffic_allocation()
+RETURNS TRIGGER AS $$
+DECLARE
+    total_allocation INT;
+BEGIN
+    SELECT COALESCE(SUM(traffic_allocation_bps), 0) INTO total_allocation
+    FROM experiment_variants
+    WHERE experiment_id = NEW.experiment_id AND id != COALESCE(NEW.id, gen_random_uuid());
+
+    IF (total_allocation + NEW.traffic_allocation_bps) > 10000 THEN
+        RAISE EXCEPTION 'Total traffic allocation exceeds 10000 bps for experiment %', NEW.experiment_id;
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+BEGIN
+    IF to_regclass('public.experiment_variants') IS NULL THEN
+        CREATE TABLE experiment_variants (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            experiment_id UUID NOT NULL REFERENCES experiments(id) ON DELETE CASCADE,
+            variant_name VARCHAR(100) NOT NULL,
+            variant_config JSONB NOT NULL DEFAULT '{}',
+            traffic_allocation_bps INT NOT NULL
+                CONSTRAINT experiment_variants_traffic_allocation_bps_check
+                CHECK (traffic_allocation_bps BETWEEN 0 AND 10000),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            CONSTRAINT unique_variant_per_experiment UNIQUE (experiment_id, variant_name)
+        );
+
+        CREATE TRIGGER validate_variant_allocation_trigger
+        BEFORE INSERT OR UPDATE ON experiment_variants
+        FOR EACH ROW
+        EXECUTE FUNCTION validate_traffic_allocation();
+    ELSIF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = 'public' AND table_name = 'experiment_variants'
+          AND column_name = 'traffic_allocation'
+    ) THEN
+        ALTER TABLE experiment_variants
+            DROP CONSTRAINT IF EXISTS experiment_variants_traffic_allocation_check;
+
+        ALTER TABLE experiment_variants
+            RENAME COLUMN traffic_allocation TO traffic_allocation_bps;
+
+        -- The sum check and the running-experiment guard would reject the
+        -- rescale of half-converted or running experiments
+        ALTER TABLE experiment_variants DISABLE TRIGGER USER;
+        UPDATE experiment_variants SET traffic_allocation_bps = traffic_allocation_bps * 100;
+        ALTER TABLE experiment_variants ENABLE TRIGGER USER;
+
+        ALTER TABLE experiment_variants
+            ADD CONSTRAINT experiment_variants_traffic_allocation_bps_check
+            CHECK (traffic_allocation_bps BETWEEN 0 AND 10000);
+    END IF;
+END $$;
+
+COMMENT ON COLUMN experiment_variants.traffic_allocation_bps IS 'Share of sampled users assigned to this variant, in basis points (10000 = 100%)';
@@ -9,10 +9,12 @@
 //! - feed:{user_id}:{algorithm} → serialized GetFeedResponse
 //! - post:{post_id} → serialized post metadata
 //! - user_context:{user_id} → serialized user context/interests
+//! - feed:private_accounts → set of private account ids (graph-service events)
 
 use crate::error::{AppError, Result};
 use redis::aio::ConnectionManager;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tracing::{debug, warn};
 
@@ -338,6 +340,54 @@ impl FeedCache {
         Ok(())
     }
 
+    /// Set of private accounts, kept in sync from `graph.account.privacy_changed`
+    const PRIVATE_ACCOUNTS_KEY: &'static str = "feed:private_accounts";
+
+    /// Record whether an account is private
+    pub async fn set_account_private(&self, user_id: &str, is_private: bool) -> Result<()> {
+        let cmd = if is_private { "SADD" } else { "SREM" };
+        redis::cmd(cmd)
+            .arg(Self::PRIVATE_ACCOUNTS_KEY)
+            .arg(user_id)
+            .query_async::<_, ()>(&mut self.client.as_ref().clone())
+            .await
+            .map_err(|e| {
+                warn!("Redis {} failed for private accounts: {}", cmd, e);
+                AppError::Internal(format!("Redis error: {}", e))
+            })?;
+
+        debug!("Account {} is_private={}", user_id, is_private);
+        Ok(())
+    }
+
+    /// Which of `user_ids` are private accounts
+    pub async fn private_accounts(&self, user_ids: &[String]) -> Result<HashSet<String>> {
+        if user_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for user_id in user_ids {
+            pipe.cmd("SISMEMBER")
+                .arg(Self::PRIVATE_ACCOUNTS_KEY)
+                .arg(user_id);
+        }
+        let flags: Vec<bool> = pipe
+            .query_async(&mut self.client.as_ref().clone())
+            .await
+            .map_err(|e| {
+                warn!("Redis SISMEMBER failed for private accounts: {}", e);
+                AppError::Internal(format!("Redis error: {}", e))
+            })?;
+
+        Ok(user_ids
+            .iter()
+            .zip(flags)
+            .filter(|(_, is_private)| *is_private)
+            .map(|(id, _)| id.clone())
+            .collect())
+    }
+
     /// Clear all feed caches (for testing/maintenance)
     pub async fn clear_all(&self) -> Result<()> {
         redis::cmd("FLUSHDB")
@@ -0,0 +1,225 @@
+//! Engagement Events Consumer
+//!
+//! Consumes social.like.created events published by social-service and feeds
+//! them to the online collaborative filtering trainer, which keeps item-item
+//! similarities fresh between batch retrains.
+
+use crate::services::recommendation_v2::OnlineCfTrainer;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Headers, Message};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+const LIKE_CREATED_EVENT: &str = "social.like.created";
+
+/// Configuration for the engagement events Kafka consumer
+#[derive(Debug, Clone)]
+pub struct EngagementConsumerConfig {
+    pub brokers: String,
+    pub group_id: String,
+    pub social_events_topic: String,
+}
+
+impl EngagementConsumerConfig {
+    /// Load configuration from environment variables
+    pub fn from_env() -> Option<Self> {
+        let brokers = std::env::var("KAFKA_BOOTSTRAP_SERVERS").ok()?;
+
+        if brokers.trim().is_empty() {
+            return None;
+        }
+
+        let topic_prefix =
+            std::env::var("KAFKA_TOPIC_PREFIX").unwrap_or_else(|_| "nova".to_string());
+
+        Some(Self {
+            brokers,
+            group_id: std::env::var("KAFKA_ONLINE_CF_GROUP_ID")
+                .unwrap_or_else(|_| "nova-feed-online-cf".to_string()),
+            social_events_topic: std::env::var("KAFKA_SOCIAL_EVENTS_TOPIC")
+                .unwrap_or_else(|_| format!("{}.social.events", topic_prefix)),
+        })
+    }
+}
+
+/// Envelope published by social-service (event-schema `EventEnvelope`)
+#[derive(Debug, Deserialize)]
+struct EventEnvelope<T> {
+    #[serde(default)]
+    event_type: Option<String>,
+    data: T,
+}
+
+/// Payload of social.like.created
+#[derive(Debug, Deserialize)]
+struct LikeCreatedEvent {
+    target_id: Uuid,
+    target_type: String,
+    user_id: Uuid,
+}
+
+/// Engagement consumer that trains the online CF model
+pub struct EngagementConsumer {
+    config: EngagementConsumerConfig,
+    trainer: Arc<OnlineCfTrainer>,
+}
+
+impl EngagementConsumer {
+    pub fn new(config: EngagementConsumerConfig, trainer: Arc<OnlineCfTrainer>) -> Self {
+        Self { config, trainer }
+    }
+
+    /// Run the consumer loop
+    pub async fn run(self) {
+        if let Err(err) = self.run_inner().await {
+            error!("Engagement events consumer terminated with error: {err}");
+        }
+    }
+
+    async fn run_inner(self) -> Result<(), KafkaError> {
+        info!(
+            "Starting engagement events consumer (topic: {}, group: {})",
+            self.config.social_events_topic, self.config.group_id
+        );
+
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.config.brokers)
+            .set("group.id", &self.config.group_id)
+            .set("enable.auto.commit", "true")
+            .set("auto.offset.reset", "latest")
+            .set("enable.partition.eof", "false")
+            .set("session.timeout.ms", "45000")
+            .set("max.poll.interval.ms", "300000")
+            .create()?;
+
+        consumer.subscribe(&[&self.config.social_events_topic])?;
+
+        loop {
+            match consumer.recv().await {
+                Ok(record) => {
+                    let Some(data) = record.payload() else {
+                        debug!(
+                            "Received Kafka message with empty payload (topic: {})",
+                            record.topic()
+                        );
+                        continue;
+                    };
+
+                    let header_type = Self::header_value(&record, "event_type");
+                    if header_type.is_none() || header_type == Some(LIKE_CREATED_EVENT) {
+                        if let Err(e) = self.handle_like_created(data).await {
+                            warn!("Failed to handle like created event: {}", e);
+                        }
+                    } else {
+                        debug!("Ignoring event type: {:?}", header_type);
+                    }
+
+                    if let Err(commit_err) = consumer.commit_message(&record, CommitMode::Async) {
+                        warn!("Failed to commit Kafka offset: {}", commit_err);
+                    }
+                }
+                Err(err) => {
+                    error!("Kafka error: {}", err);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    /// Record a post like as positive engagement
+    ///
+    /// Unlikes are not subtracted; time decay retires stale co-occurrences.
+    async fn handle_like_created(&self, data: &[u8]) -> anyhow::Result<()> {
+        let Some(event) = parse_like_created(data)? else {
+            return Ok(());
+        };
+
+        self.trainer
+            .record(event.user_id, event.target_id, "like", None)
+            .await;
+        Ok(())
+    }
+
+    fn header_value<'a>(
+        message: &'a rdkafka::message::BorrowedMessage<'a>,
+        key: &str,
+    ) -> Option<&'a str> {
+        message
+            .headers()
+            .and_then(|headers| {
+                headers
+                    .iter()
+                    .find(|header| header.key == key)
+                    .and_then(|header| header.value)
+            })
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+}
+
+/// Parse a like event; `None` for other event types and non-post targets
+fn parse_like_created(data: &[u8]) -> anyhow::Result<Option<LikeCreatedEvent>> {
+    let envelope: EventEnvelope<serde_json::Value> = serde_json::from_slice(data)?;
+    if envelope
+        .event_type
+        .as_deref()
+        .is_some_and(|t| t != LIKE_CREATED_EVENT)
+    {
+        return Ok(None);
+    }
+
+    let event: LikeCreatedEvent = serde_json::from_value(envelope.data)?;
+    Ok((event.target_type == "post").then_some(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_like_created() {
+        let json = r#"{
+            "event_id": "6f1b7c1e-6a59-4c65-9f0e-0a4b0c3c2d11",
+            "timestamp": "2026-10-16T00:00:00Z",
+            "schema_version": 1,
+            "source": "social-service",
+            "correlation_id": null,
+            "event_type": "social.like.created",
+            "data": {
+                "like_id": "0d7d4f0c-2d4e-4c2e-9f53-7a4d6f1f3b10",
+                "target_id": "123e4567-e89b-12d3-a456-426614174000",
+                "target_type": "post",
+                "user_id": "987fcdeb-51a2-4bc4-a567-890123456789",
+                "created_at": "2026-10-16T00:00:00Z"
+            }
+        }"#;
+
+        let event = parse_like_created(json.as_bytes()).unwrap().unwrap();
+        assert_eq!(
+            event.target_id.to_string(),
+            "123e4567-e89b-12d3-a456-426614174000"
+        );
+    }
+
+    #[test]
+    fn test_parse_skips_comment_likes_and_other_events() {
+        let comment_like = r#"{
+            "event_type": "social.like.created",
+            "data": {
+                "target_id": "123e4567-e89b-12d3-a456-426614174000",
+                "target_type": "comment",
+                "user_id": "987fcdeb-51a2-4bc4-a567-890123456789"
+            }
+        }"#;
+        assert!(parse_like_created(comment_like.as_bytes())
+            .unwrap()
+            .is_none());
+
+        let follow = r#"{"event_type": "social.follow.created", "data": {}}"#;
+        assert!(parse_like_created(follow.as_bytes()).unwrap().is_none());
+    }
+}
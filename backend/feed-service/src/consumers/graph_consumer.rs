@@ -0,0 +1,212 @@
+//! Graph Events Consumer
+//!
+//! Consumes graph.account.privacy_changed events published by graph-service
+//! and mirrors them into the `feed:private_accounts` set, which feed reads
+//! use to hide posts by private accounts from viewers who do not follow them.
+
+use crate::cache::FeedCache;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Headers, Message};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+const PRIVACY_CHANGED_EVENT: &str = "graph.account.privacy_changed";
+
+/// Configuration for the graph events Kafka consumer
+#[derive(Debug, Clone)]
+pub struct GraphConsumerConfig {
+    pub brokers: String,
+    pub group_id: String,
+    pub graph_events_topic: String,
+}
+
+impl GraphConsumerConfig {
+    /// Load configuration from environment variables
+    pub fn from_env() -> Option<Self> {
+        let brokers = std::env::var("KAFKA_BOOTSTRAP_SERVERS").ok()?;
+
+        if brokers.trim().is_empty() {
+            return None;
+        }
+
+        let topic_prefix =
+            std::env::var("KAFKA_TOPIC_PREFIX").unwrap_or_else(|_| "nova".to_string());
+
+        Some(Self {
+            brokers,
+            group_id: std::env::var("KAFKA_FEED_GRAPH_GROUP_ID")
+                .unwrap_or_else(|_| "nova-feed-graph-events".to_string()),
+            graph_events_topic: std::env::var("KAFKA_GRAPH_EVENTS_TOPIC")
+                .unwrap_or_else(|_| format!("{}.graph.events", topic_prefix)),
+        })
+    }
+}
+
+/// Envelope published by graph-service (event-schema `EventEnvelope`)
+#[derive(Debug, Deserialize)]
+struct EventEnvelope<T> {
+    #[serde(default)]
+    event_type: Option<String>,
+    data: T,
+}
+
+/// Payload of graph.account.privacy_changed
+#[derive(Debug, Deserialize)]
+struct PrivacyChangedEvent {
+    user_id: Uuid,
+    is_private: bool,
+}
+
+/// Graph events consumer that tracks private accounts
+pub struct GraphConsumer {
+    config: GraphConsumerConfig,
+    cache: Arc<FeedCache>,
+}
+
+impl GraphConsumer {
+    pub fn new(config: GraphConsumerConfig, cache: Arc<FeedCache>) -> Self {
+        Self { config, cache }
+    }
+
+    /// Run the consumer loop
+    pub async fn run(self) {
+        if let Err(err) = self.run_inner().await {
+            error!("Graph events consumer terminated with error: {err}");
+        }
+    }
+
+    async fn run_inner(self) -> Result<(), KafkaError> {
+        info!(
+            "Starting graph events consumer (topic: {}, group: {})",
+            self.config.graph_events_topic, self.config.group_id
+        );
+
+        // A fresh group replays the topic so accounts made private before
+        // this consumer first ran are picked up
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.config.brokers)
+            .set("group.id", &self.config.group_id)
+            .set("enable.auto.commit", "false")
+            .set("auto.offset.reset", "earliest")
+            .set("enable.partition.eof", "false")
+            .set("session.timeout.ms", "45000")
+            .set("max.poll.interval.ms", "300000")
+            .create()?;
+
+        consumer.subscribe(&[&self.config.graph_events_topic])?;
+
+        loop {
+            match consumer.recv().await {
+                Ok(record) => {
+                    let Some(data) = record.payload() else {
+                        debug!(
+                            "Received Kafka message with empty payload (topic: {})",
+                            record.topic()
+                        );
+                        continue;
+                    };
+
+                    let header_type = Self::header_value(&record, "event_type");
+                    if header_type.is_none() || header_type == Some(PRIVACY_CHANGED_EVENT) {
+                        if let Err(e) = self.handle_privacy_changed(data).await {
+                            warn!("Failed to handle privacy changed event: {}", e);
+                        }
+                    } else {
+                        debug!("Ignoring event type: {:?}", header_type);
+                    }
+
+                    if let Err(commit_err) = consumer.commit_message(&record, CommitMode::Async) {
+                        warn!("Failed to commit Kafka offset: {}", commit_err);
+                    }
+                }
+                Err(err) => {
+                    error!("Kafka error: {}", err);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    /// Apply a privacy change, retrying Redis until it succeeds
+    ///
+    /// Skipping an update would leave an account wrongly public or private
+    /// until its next change, so the consumer blocks instead.
+    async fn handle_privacy_changed(&self, data: &[u8]) -> anyhow::Result<()> {
+        let Some(event) = parse_privacy_changed(data)? else {
+            return Ok(());
+        };
+
+        let user_id = event.user_id.to_string();
+        while let Err(e) = self
+            .cache
+            .set_account_private(&user_id, event.is_private)
+            .await
+        {
+            warn!("Failed to record privacy of {} (retrying): {}", user_id, e);
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        Ok(())
+    }
+
+    fn header_value<'a>(
+        message: &'a rdkafka::message::BorrowedMessage<'a>,
+        key: &str,
+    ) -> Option<&'a str> {
+        message
+            .headers()
+            .and_then(|headers| {
+                headers
+                    .iter()
+                    .find(|header| header.key == key)
+                    .and_then(|header| header.value)
+            })
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+}
+
+/// Parse a privacy change; `None` for other graph events
+fn parse_privacy_changed(data: &[u8]) -> anyhow::Result<Option<PrivacyChangedEvent>> {
+    let envelope: EventEnvelope<serde_json::Value> = serde_json::from_slice(data)?;
+    if envelope.event_type.as_deref() != Some(PRIVACY_CHANGED_EVENT) {
+        return Ok(None);
+    }
+
+    Ok(Some(serde_json::from_value(envelope.data)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_privacy_changed() {
+        let json = r#"{
+            "event_id": "6f1b7c1e-6a59-4c65-9f0e-0a4b0c3c2d11",
+            "timestamp": "2026-10-17T00:00:00Z",
+            "schema_version": 1,
+            "source": "graph-service",
+            "correlation_id": null,
+            "event_type": "graph.account.privacy_changed",
+            "data": {
+                "user_id": "123e4567-e89b-12d3-a456-426614174000",
+                "is_private": true,
+                "changed_at": "2026-10-17T00:00:00Z"
+            }
+        }"#;
+
+        let event = parse_privacy_changed(json.as_bytes()).unwrap().unwrap();
+        assert_eq!(
+            event.user_id.to_string(),
+            "123e4567-e89b-12d3-a456-426614174000"
+        );
+        assert!(event.is_private);
+
+        let request = r#"{"event_type": "graph.follow_request.created", "data": {}}"#;
+        assert!(parse_privacy_changed(request.as_bytes()).unwrap().is_none());
+    }
+}
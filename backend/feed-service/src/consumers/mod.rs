@@ -1 +1,3 @@
 pub mod content_consumer;
+pub mod engagement_consumer;
+pub mod graph_consumer;
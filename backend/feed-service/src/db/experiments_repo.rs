@@ -47,7 +47,8 @@ pub struct ExperimentVariant {
     pub experiment_id: Uuid,
     pub variant_name: String,
     pub variant_config: serde_json::Value,
-    pub traffic_allocation: i32,
+    /// Share of sampled users in basis points (10000 = 100%)
+    pub traffic_allocation_bps: i32,
     pub created_at: DateTime<Utc>,
 }
 
@@ -73,6 +74,48 @@ pub struct ExperimentMetric {
     pub recorded_at: DateTime<Utc>,
 }
 
+/// Per-user metric totals within one variant (input to experiment analysis)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserMetricAggregate {
+    pub user_id: Uuid,
+    pub variant_id: Uuid,
+    pub exposures: f64,
+    pub outcomes: f64,
+}
+
+/// Experiment layer from database (experiments in a layer are mutually exclusive)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ExperimentLayer {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub holdout_buckets: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Global holdout group from database
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ExperimentHoldout {
+    pub id: Uuid,
+    pub name: String,
+    pub buckets: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Experiment traffic settings from database (JSONB columns are
+/// interpreted by the A/B testing framework)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ExperimentTraffic {
+    pub experiment_id: Uuid,
+    pub layer_id: Option<Uuid>,
+    pub layer_name: Option<String>,
+    pub bucket_ranges: serde_json::Value,
+    pub targeting: Option<serde_json::Value>,
+    pub ramp: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// Experiments Repository with AuthService validation
 pub struct ExperimentsRepo {
     pool: PgPool,
@@ -343,7 +386,7 @@ impl ExperimentsRepo {
         experiment_id: Uuid,
         variant_name: String,
         variant_config: serde_json::Value,
-        traffic_allocation: i32,
+        traffic_allocation_bps: i32,
     ) -> Result<ExperimentVariant> {
         let variant = sqlx::query_as::<_, ExperimentVariant>(
             r#"
@@ -351,17 +394,17 @@ impl ExperimentsRepo {
                 experiment_id,
                 variant_name,
                 variant_config,
-                traffic_allocation
+                traffic_allocation_bps
             )
             VALUES ($1, $2, $3, $4)
             RETURNING id, experiment_id, variant_name, variant_config,
-                      traffic_allocation, created_at
+                      traffic_allocation_bps, created_at
             "#,
         )
         .bind(experiment_id)
         .bind(&variant_name)
         .bind(&variant_config)
-        .bind(traffic_allocation)
+        .bind(traffic_allocation_bps)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| {
@@ -385,7 +428,7 @@ impl ExperimentsRepo {
         let variants = sqlx::query_as::<_, ExperimentVariant>(
             r#"
             SELECT id, experiment_id, variant_name, variant_config,
-                   traffic_allocation, created_at
+                   traffic_allocation_bps, created_at
             FROM experiment_variants
             WHERE experiment_id = $1
             ORDER BY created_at ASC
@@ -405,6 +448,262 @@ impl ExperimentsRepo {
         Ok(variants)
     }
 
+    /// Aggregate exposure and outcome metrics per user and variant
+    ///
+    /// Metrics without a variant (user not yet bucketed) are ignored.
+    pub async fn get_user_metric_aggregates(
+        &self,
+        experiment_id: Uuid,
+        exposure_metric: &str,
+        outcome_metric: &str,
+    ) -> Result<Vec<UserMetricAggregate>> {
+        let rows = sqlx::query_as::<_, UserMetricAggregate>(
+            r#"
+            SELECT user_id,
+                   variant_id,
+                   COALESCE(SUM(metric_value) FILTER (WHERE metric_name = $2), 0)::FLOAT8
+                       AS exposures,
+                   COALESCE(SUM(metric_value) FILTER (WHERE metric_name = $3), 0)::FLOAT8
+                       AS outcomes
+            FROM experiment_metrics
+            WHERE experiment_id = $1
+              AND variant_id IS NOT NULL
+              AND metric_name IN ($2, $3)
+            GROUP BY user_id, variant_id
+            "#,
+        )
+        .bind(experiment_id)
+        .bind(exposure_metric)
+        .bind(outcome_metric)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to aggregate metrics for experiment {}: {}",
+                experiment_id, e
+            );
+            AppError::Database(e.to_string())
+        })?;
+
+        Ok(rows)
+    }
+
+    /// Sum a metric per user over [from, to) across all experiments
+    ///
+    /// Used as the pre-experiment covariate for CUPED.
+    pub async fn get_pre_period_metric(
+        &self,
+        user_ids: &[Uuid],
+        metric_name: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(Uuid, f64)>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query_as::<_, (Uuid, f64)>(
+            r#"
+            SELECT user_id, SUM(metric_value)::FLOAT8
+            FROM experiment_metrics
+            WHERE user_id = ANY($1)
+              AND metric_name = $2
+              AND recorded_at >= $3
+              AND recorded_at < $4
+            GROUP BY user_id
+            "#,
+        )
+        .bind(user_ids)
+        .bind(metric_name)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to load pre-period metric '{}': {}", metric_name, e);
+            AppError::Database(e.to_string())
+        })?;
+
+        Ok(rows)
+    }
+
+    /// Create an experiment layer
+    pub async fn create_layer(
+        &self,
+        name: String,
+        description: Option<String>,
+        holdout_buckets: i32,
+    ) -> Result<ExperimentLayer> {
+        let layer = sqlx::query_as::<_, ExperimentLayer>(
+            r#"
+            INSERT INTO experiment_layers (name, description, holdout_buckets)
+            VALUES ($1, $2, $3)
+            RETURNING id, name, description, holdout_buckets, created_at
+            "#,
+        )
+        .bind(&name)
+        .bind(&description)
+        .bind(holdout_buckets)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to create experiment layer '{}': {}", name, e);
+            AppError::Database(e.to_string())
+        })?;
+
+        info!("Created experiment layer '{}' (id={})", name, layer.id);
+
+        Ok(layer)
+    }
+
+    /// List all experiment layers
+    pub async fn list_layers(&self) -> Result<Vec<ExperimentLayer>> {
+        let layers = sqlx::query_as::<_, ExperimentLayer>(
+            r#"
+            SELECT id, name, description, holdout_buckets, created_at
+            FROM experiment_layers
+            ORDER BY name ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to list experiment layers: {}", e);
+            AppError::Database(e.to_string())
+        })?;
+
+        Ok(layers)
+    }
+
+    /// Create a global holdout group
+    pub async fn create_holdout(&self, name: String, buckets: i32) -> Result<ExperimentHoldout> {
+        let holdout = sqlx::query_as::<_, ExperimentHoldout>(
+            r#"
+            INSERT INTO experiment_holdouts (name, buckets)
+            VALUES ($1, $2)
+            RETURNING id, name, buckets, is_active, created_at
+            "#,
+        )
+        .bind(&name)
+        .bind(buckets)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to create experiment holdout '{}': {}", name, e);
+            AppError::Database(e.to_string())
+        })?;
+
+        info!(
+            "Created experiment holdout '{}' ({} buckets)",
+            name, holdout.buckets
+        );
+
+        Ok(holdout)
+    }
+
+    /// List active global holdout groups
+    pub async fn list_active_holdouts(&self) -> Result<Vec<ExperimentHoldout>> {
+        let holdouts = sqlx::query_as::<_, ExperimentHoldout>(
+            r#"
+            SELECT id, name, buckets, is_active, created_at
+            FROM experiment_holdouts
+            WHERE is_active = TRUE
+            ORDER BY name ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to list experiment holdouts: {}", e);
+            AppError::Database(e.to_string())
+        })?;
+
+        Ok(holdouts)
+    }
+
+    /// Deactivate a holdout group (its users become eligible again)
+    pub async fn deactivate_holdout(&self, holdout_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE experiment_holdouts SET is_active = FALSE WHERE id = $1")
+            .bind(holdout_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("Failed to deactivate holdout {}: {}", holdout_id, e);
+                AppError::Database(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Traffic settings of experiments that are not completed or cancelled
+    pub async fn list_live_traffic(&self) -> Result<Vec<ExperimentTraffic>> {
+        let rows = sqlx::query_as::<_, ExperimentTraffic>(
+            r#"
+            SELECT t.experiment_id, t.layer_id, l.name AS layer_name,
+                   t.bucket_ranges, t.targeting, t.ramp, t.updated_at
+            FROM experiment_traffic t
+            JOIN experiments e ON e.id = t.experiment_id
+            LEFT JOIN experiment_layers l ON l.id = t.layer_id
+            WHERE e.status NOT IN ('completed', 'cancelled')
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to list experiment traffic: {}", e);
+            AppError::Database(e.to_string())
+        })?;
+
+        Ok(rows)
+    }
+
+    /// Insert or replace the traffic settings of an experiment
+    pub async fn upsert_traffic(
+        &self,
+        experiment_id: Uuid,
+        layer_id: Option<Uuid>,
+        bucket_ranges: serde_json::Value,
+        targeting: Option<serde_json::Value>,
+        ramp: serde_json::Value,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO experiment_traffic (
+                experiment_id,
+                layer_id,
+                bucket_ranges,
+                targeting,
+                ramp
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (experiment_id) DO UPDATE
+            SET layer_id = EXCLUDED.layer_id,
+                bucket_ranges = EXCLUDED.bucket_ranges,
+                targeting = EXCLUDED.targeting,
+                ramp = EXCLUDED.ramp,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(experiment_id)
+        .bind(layer_id)
+        .bind(&bucket_ranges)
+        .bind(&targeting)
+        .bind(&ramp)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to save traffic for experiment {}: {}",
+                experiment_id, e
+            );
+            AppError::Database(e.to_string())
+        })?;
+
+        info!("Updated traffic settings for experiment {}", experiment_id);
+
+        Ok(())
+    }
+
     /// Update experiment status
     pub async fn update_status(
         &self,
@@ -554,7 +554,7 @@ impl RecommendationServiceImpl {
     async fn fetch_posts_from_content_service(
         &self,
         limit: i32,
-        _user_id: &str,
+        user_id: &str,
         channel_id: &str,
     ) -> Result<Vec<CachedFeedPost>, Status> {
         use grpc_clients::nova::content_service::v2::{
@@ -638,6 +638,7 @@ impl RecommendationServiceImpl {
         // Step 2: Get full post details
         let get_request = GetPostsByIdsRequest {
             post_ids: post_ids.clone(),
+            viewer_id: user_id.to_string(),
         };
 
         let get_response = content_client
@@ -826,9 +827,10 @@ impl RecommendationServiceImpl {
         );
 
         // Step 3: Get full post details
-        let get_response = content_client
+        let mut get_response = content_client
             .get_posts_by_ids(GetPostsByIdsRequest {
                 post_ids: post_ids.clone(),
+                viewer_id: user_id.to_string(),
             })
             .await
             .map_err(|e| {
@@ -837,6 +839,22 @@ impl RecommendationServiceImpl {
             })?
             .into_inner();
 
+        // Hide posts limited to an audience list the viewer is not on
+        clients::retain_audience_visible(
+            &self.grpc_pool,
+            uuid::Uuid::parse_str(user_id).ok(),
+            &mut get_response.posts,
+        )
+        .await;
+        // Hide posts by private accounts the viewer does not follow
+        clients::retain_author_visible(
+            &self.grpc_pool,
+            &self.cache,
+            uuid::Uuid::parse_str(user_id).ok(),
+            &mut get_response.posts,
+        )
+        .await;
+
         // Step 4: Fetch social stats from social-service
         let mut social_client = self.grpc_pool.social();
         let social_counts = match social_client
@@ -1,15 +1,18 @@
-use grpc_clients::nova::content_service::v2::{
-    GetPostsByIdsRequest, GetPostsByIdsResponse, GetUserPostsRequest, GetUserPostsResponse,
-    ListPostsByUsersRequest, ListPostsByUsersResponse, ListRecentPostsRequest,
-    ListRecentPostsResponse,
-};
 /// gRPC clients for calling other services (centralized)
 ///
 /// Feed Service orchestrates data from SocialService (profiles/relations), ContentService, and GraphService
 /// to generate personalized feeds without direct database queries.
+use crate::cache::FeedCache;
+use grpc_clients::nova::content_service::v2::{
+    GetPostsByIdsRequest, GetPostsByIdsResponse, GetUserPostsRequest, GetUserPostsResponse,
+    ListPostsByUsersRequest, ListPostsByUsersResponse, ListRecentPostsRequest,
+    ListRecentPostsResponse, Post,
+};
 use grpc_clients::{config::GrpcConfig, GrpcClientPool};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tonic::Status;
+use tracing::warn;
 use uuid::Uuid;
 
 /// Content Service gRPC Client
@@ -109,85 +112,170 @@ impl GraphServiceClient {
         self.enabled
     }
 
-    /// Get suggested friends using Friends-of-Friends (FoF) algorithm via gRPC
+    /// Get suggested follows (people you may know) via graph-service gRPC
     ///
-    /// Returns list of (user_id, mutual_count) tuples
+    /// graph-service ranks friends-of-friends and drops blocked, muted and
+    /// already-followed users. Returns (user_id, score, reason) tuples.
     pub async fn suggested_friends(
         &self,
         user_id: Uuid,
         limit: usize,
-    ) -> Result<Vec<(Uuid, u64)>, Status> {
-        use grpc_clients::nova::graph_service::v2::GetFollowingRequest;
+    ) -> Result<Vec<(Uuid, f64, String)>, Status> {
+        use grpc_clients::nova::graph_service::v2::GetSuggestedFollowsRequest;
 
         if !self.enabled {
             return Ok(Vec::new());
         }
 
-        // Step 1: Get all users that the current user follows
-        let following = {
-            let mut client = self.pool.graph();
-            let response = client
-                .get_following(GetFollowingRequest {
-                    user_id: user_id.to_string(),
-                    limit: 1000,
-                    offset: 0,
-                    viewer_id: String::new(),
-                })
-                .await?
-                .into_inner();
+        let mut client = self.pool.graph();
+        let response = client
+            .get_suggested_follows(GetSuggestedFollowsRequest {
+                user_id: user_id.to_string(),
+                limit: limit as i32,
+            })
+            .await?
+            .into_inner();
+
+        let result = response
+            .suggestions
+            .into_iter()
+            .filter_map(|s| {
+                Uuid::parse_str(&s.user_id)
+                    .ok()
+                    .map(|uid| (uid, s.score, s.reason))
+            })
+            .collect();
+
+        Ok(result)
+    }
+}
 
-            response.user_ids
-        };
+/// Drop posts limited to an audience list the viewer is not on
+///
+/// The viewer's own posts are always kept. Membership is checked in one
+/// batched graph-service call; without a viewer, or if graph-service is
+/// unavailable, every list-limited post is dropped (fail closed).
+pub async fn retain_audience_visible(
+    pool: &GrpcClientPool,
+    viewer_id: Option<Uuid>,
+    posts: &mut Vec<Post>,
+) {
+    use grpc_clients::nova::graph_service::v2::BatchCheckAudienceMembershipRequest;
 
-        if following.is_empty() {
-            return Ok(Vec::new());
+    let viewer = viewer_id.map(|id| id.to_string()).unwrap_or_default();
+    let list_ids: HashSet<String> = posts
+        .iter()
+        .filter(|p| !p.audience_list_id.is_empty() && p.author_id != viewer)
+        .map(|p| p.audience_list_id.clone())
+        .collect();
+    if list_ids.is_empty() {
+        return;
+    }
+
+    let visible: HashSet<String> = if viewer.is_empty() {
+        HashSet::new()
+    } else {
+        let mut client = pool.graph();
+        match client
+            .batch_check_audience_membership(BatchCheckAudienceMembershipRequest {
+                viewer_id: viewer.clone(),
+                list_ids: list_ids.into_iter().collect(),
+                close_friends_of: vec![],
+            })
+            .await
+        {
+            Ok(resp) => resp
+                .into_inner()
+                .lists
+                .into_iter()
+                .filter(|(_, is_member)| *is_member)
+                .map(|(id, _)| id)
+                .collect(),
+            Err(e) => {
+                warn!(
+                    "Audience membership check failed (hiding list-limited posts): {}",
+                    e
+                );
+                HashSet::new()
+            }
         }
+    };
+
+    posts.retain(|p| {
+        p.audience_list_id.is_empty()
+            || p.author_id == viewer
+            || visible.contains(&p.audience_list_id)
+    });
+}
+
+/// Drop posts by private accounts the viewer does not follow
+///
+/// The viewer's own posts are always kept. Private accounts come from the
+/// `feed:private_accounts` set; follows are checked in one batched
+/// graph-service call, and every private author's post is dropped without a
+/// viewer or if graph-service is unavailable (fail closed). If the set cannot
+/// be read, posts are kept.
+pub async fn retain_author_visible(
+    pool: &GrpcClientPool,
+    cache: &FeedCache,
+    viewer_id: Option<Uuid>,
+    posts: &mut Vec<Post>,
+) {
+    use grpc_clients::nova::graph_service::v2::BatchCheckFollowingRequest;
 
-        // Step 2: For each followed user, get their followings (friends of friends)
-        let mut fof_map: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+    let viewer = viewer_id.map(|id| id.to_string()).unwrap_or_default();
+    let authors: Vec<String> = posts
+        .iter()
+        .filter(|p| p.author_id != viewer)
+        .map(|p| p.author_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
 
-        for followed_id in &following {
-            let mut client = self.pool.graph();
+    let private = match cache.private_accounts(&authors).await {
+        Ok(private) => private,
+        Err(e) => {
+            warn!("Private account lookup failed (keeping posts): {}", e);
+            return;
+        }
+    };
+    if private.is_empty() {
+        return;
+    }
+
+    let mut followed: HashSet<String> = HashSet::new();
+    if !viewer.is_empty() {
+        let private: Vec<String> = private.iter().cloned().collect();
+        // graph-service accepts at most 100 followee ids per batch
+        for chunk in private.chunks(100) {
+            let mut client = pool.graph();
             match client
-                .get_following(GetFollowingRequest {
-                    user_id: followed_id.clone(),
-                    limit: 1000,
-                    offset: 0,
-                    viewer_id: String::new(),
+                .batch_check_following(BatchCheckFollowingRequest {
+                    follower_id: viewer.clone(),
+                    followee_ids: chunk.to_vec(),
                 })
                 .await
             {
-                Ok(resp) => {
-                    let fof_list = resp.into_inner().user_ids;
-                    for fof_id in fof_list {
-                        if fof_id != user_id.to_string() {
-                            *fof_map.entry(fof_id).or_insert(0) += 1;
-                        }
-                    }
+                Ok(resp) => followed.extend(
+                    resp.into_inner()
+                        .results
+                        .into_iter()
+                        .filter(|(_, is_following)| *is_following)
+                        .map(|(id, _)| id),
+                ),
+                Err(e) => {
+                    warn!(
+                        "Follow check failed (hiding posts by private accounts): {}",
+                        e
+                    );
+                    followed.clear();
+                    break;
                 }
-                Err(_) => continue, // Skip on error
             }
         }
-
-        // Step 3: Filter out users that current user already follows
-        let already_following_set: std::collections::HashSet<String> =
-            following.iter().cloned().collect();
-
-        let mut candidates: Vec<(String, u64)> = fof_map
-            .into_iter()
-            .filter(|(uid, _)| !already_following_set.contains(uid))
-            .collect();
-
-        // Step 4: Sort by mutual count (descending) and take top N
-        candidates.sort_by(|a, b| b.1.cmp(&a.1));
-        candidates.truncate(limit);
-
-        // Step 5: Convert to (Uuid, u64)
-        let result = candidates
-            .into_iter()
-            .filter_map(|(uid_str, count)| Uuid::parse_str(&uid_str).ok().map(|uid| (uid, count)))
-            .collect();
-
-        Ok(result)
     }
+
+    posts.retain(|p| {
+        p.author_id == viewer || !private.contains(&p.author_id) || followed.contains(&p.author_id)
+    });
 }
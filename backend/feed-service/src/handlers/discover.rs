@@ -20,7 +20,7 @@ pub struct DiscoverHandlerState {
 pub struct UserWithScore {
     pub user_id: String, // UUID as string
     pub score: f64,
-    pub reason: String, // e.g., "3 mutual connections"
+    pub reason: String, // e.g., "Followed by alice and 2 others"
 }
 
 /// Suggested users response
@@ -34,10 +34,10 @@ pub struct SuggestedUsersResponse {
 ///
 /// Get personalized user suggestions for the authenticated user
 ///
-/// Based on:
+/// Ranked by graph-service `GetSuggestedFollows`:
 /// - Second-degree connections (friends of friends)
-/// - Mutual follow counts
-/// - User activity (last 7 days)
+/// - Adamic-Adar / Jaccard over mutual follows
+/// - Personalized PageRank from the viewer
 ///
 /// Response:
 /// ```json
@@ -46,7 +46,7 @@ pub struct SuggestedUsersResponse {
 ///     {
 ///       "user_id": "uuid1",
 ///       "score": 0.95,
-///       "reason": "3 mutual connections"
+///       "reason": "Followed by alice and 2 others"
 ///     },
 ///     ...
 ///   ],
@@ -100,14 +100,10 @@ pub async fn get_suggested_users(
             Ok(list) => {
                 debug!("Neo4j suggestions retrieved for user {}", user_id);
                 list.into_iter()
-                    .map(|(uid, mutuals)| UserWithScore {
+                    .map(|(uid, score, reason)| UserWithScore {
                         user_id: uid.to_string(),
-                        score: mutuals as f64,
-                        reason: if mutuals == 1 {
-                            "1 mutual connection".to_string()
-                        } else {
-                            format!("{} mutual connections", mutuals)
-                        },
+                        score,
+                        reason,
                     })
                     .collect()
             }
@@ -0,0 +1,152 @@
+/// Experiment Analysis API Handlers
+///
+/// HTTP endpoints for reading A/B experiment results
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::db::experiments_repo::ExperimentsRepo;
+use crate::error::{AppError, Result};
+use crate::services::recommendation_v2::experiment_analysis::{
+    analyze_experiment, AnalysisConfig, MetricKind,
+};
+use crate::services::recommendation_v2::Experiment;
+
+/// Query parameters for GET /experiments/{id}/analysis
+#[derive(Debug, Deserialize)]
+pub struct AnalysisQuery {
+    /// Metric kind: "ctr" (default), "conversion", "mean"
+    pub metric: Option<String>,
+
+    /// Logged action counted as exposure (default: "impression")
+    pub exposure: Option<String>,
+
+    /// Logged action counted as outcome (default: "click")
+    pub outcome: Option<String>,
+
+    /// Control variant name (default: "control")
+    pub control: Option<String>,
+
+    /// Significance level (default: 0.05)
+    pub alpha: Option<f64>,
+
+    /// Apply CUPED variance reduction (default: true)
+    pub cuped: Option<bool>,
+
+    /// CUPED pre-period length in days (default: 14)
+    pub cuped_lookback_days: Option<i64>,
+}
+
+impl AnalysisQuery {
+    fn into_config(self) -> Result<AnalysisConfig> {
+        let mut config = AnalysisConfig::default();
+
+        if let Some(metric) = self.metric {
+            config.metric = MetricKind::parse(&metric)
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown metric: {}", metric)))?;
+        }
+        if let Some(exposure) = self.exposure {
+            config.exposure_action = exposure;
+        }
+        if let Some(outcome) = self.outcome {
+            config.outcome_action = outcome;
+        }
+        if let Some(control) = self.control {
+            config.control_variant = control;
+        }
+        if let Some(alpha) = self.alpha {
+            config.alpha = alpha;
+        }
+        if let Some(cuped) = self.cuped {
+            config.use_cuped = cuped;
+        }
+        if let Some(days) = self.cuped_lookback_days {
+            if !(1..=90).contains(&days) {
+                return Err(AppError::BadRequest(
+                    "cuped_lookback_days must be between 1 and 90".to_string(),
+                ));
+            }
+            config.cuped_lookback = chrono::Duration::days(days);
+        }
+
+        Ok(config)
+    }
+}
+
+/// Handler state for experiment analysis
+pub struct ExperimentHandlerState {
+    pub repo: Arc<ExperimentsRepo>,
+}
+
+/// GET /api/v2/experiments/{experiment_id}/analysis
+/// Per-variant deltas, confidence intervals, sequential test and SRM check
+/// Requires service-to-service authentication
+#[get("/api/v2/experiments/{experiment_id}/analysis")]
+pub async fn get_experiment_analysis(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<AnalysisQuery>,
+    state: web::Data<ExperimentHandlerState>,
+) -> Result<HttpResponse> {
+    // Verify internal service authentication
+    if !req.headers().contains_key("x-service-token") {
+        return Err(AppError::Authentication(
+            "Missing service authentication token".to_string(),
+        ));
+    }
+
+    let experiment_id = path.into_inner();
+    let config = query.into_inner().into_config()?;
+
+    let db_experiment = state
+        .repo
+        .get_experiment(experiment_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Experiment: {}", experiment_id)))?;
+    let variants = state.repo.get_variants(experiment_id).await?;
+    let experiment = Experiment::from_db(db_experiment, variants);
+
+    debug!(
+        "Running {:?} analysis for experiment {}",
+        config.metric, experiment.name
+    );
+
+    let analysis = analyze_experiment(&state.repo, &experiment, &config).await?;
+    Ok(HttpResponse::Ok().json(analysis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_query() -> AnalysisQuery {
+        AnalysisQuery {
+            metric: None,
+            exposure: None,
+            outcome: None,
+            control: None,
+            alpha: None,
+            cuped: None,
+            cuped_lookback_days: None,
+        }
+    }
+
+    #[test]
+    fn test_query_defaults() {
+        let config = empty_query().into_config().unwrap();
+        assert_eq!(config.metric, MetricKind::Ctr);
+        assert_eq!(config.exposure_action, "impression");
+        assert_eq!(config.outcome_action, "click");
+    }
+
+    #[test]
+    fn test_query_rejects_unknown_metric() {
+        let query = AnalysisQuery {
+            metric: Some("revenue".to_string()),
+            ..empty_query()
+        };
+        assert!(query.into_config().is_err());
+    }
+}
@@ -6,8 +6,11 @@ use std::sync::Arc;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::cache::FeedCache;
 use crate::error::{AppError, Result};
-use crate::grpc::clients::{ContentServiceClient, GraphServiceClient};
+use crate::grpc::clients::{
+    retain_audience_visible, retain_author_visible, ContentServiceClient, GraphServiceClient,
+};
 use crate::middleware::jwt_auth::UserId;
 use crate::models::{FeedPostFull, FeedResponse};
 use grpc_clients::nova::content_service::v2::{
@@ -102,6 +105,7 @@ pub struct FeedHandlerState {
     pub content_client: Arc<ContentServiceClient>,
     pub graph_client: Arc<GraphServiceClient>,
     pub grpc_pool: Arc<grpc_clients::GrpcClientPool>,
+    pub feed_cache: Arc<FeedCache>,
 }
 
 #[get("")]
@@ -233,6 +237,7 @@ pub async fn get_feed(
                                         limit: remaining as i32,
                                         offset: 0,
                                         status: ContentStatus::Published as i32,
+                                        viewer_id: user_id.to_string(),
                                     })
                                     .await
                                 {
@@ -324,14 +329,26 @@ async fn fetch_full_posts(
     let post_id_strings: Vec<String> = post_ids.iter().map(|id| id.to_string()).collect();
 
     // Fetch full post details from content-service
-    let posts_resp = state
+    let mut posts_resp = state
         .content_client
         .get_posts_by_ids(GetPostsByIdsRequest {
             post_ids: post_id_strings.clone(),
+            viewer_id: user_id.map(|id| id.to_string()).unwrap_or_default(),
         })
         .await
         .map_err(|e| AppError::Internal(format!("Failed to fetch post details: {}", e)))?;
 
+    // Hide posts limited to an audience list the viewer is not on
+    retain_audience_visible(&state.grpc_pool, user_id, &mut posts_resp.posts).await;
+    // Hide posts by private accounts the viewer does not follow
+    retain_author_visible(
+        &state.grpc_pool,
+        &state.feed_cache,
+        user_id,
+        &mut posts_resp.posts,
+    )
+    .await;
+
     // Batch fetch author profiles from identity-service (graceful degradation if unavailable)
     let author_ids: Vec<String> = posts_resp
         .posts
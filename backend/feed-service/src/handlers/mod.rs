@@ -1,4 +1,5 @@
 pub mod discover;
+pub mod experiments;
 pub mod feed;
 pub mod recommendation;
 pub mod trending;
@@ -7,6 +8,7 @@ pub mod trending;
 pub use discover::{
     get_suggested_users, DiscoverHandlerState, SuggestedUsersResponse, UserWithScore,
 };
+pub use experiments::{get_experiment_analysis, AnalysisQuery, ExperimentHandlerState};
 pub use feed::{
     get_feed, get_guest_feed, get_liked_feed, get_saved_feed, FeedHandlerState, FeedQueryParams,
 };
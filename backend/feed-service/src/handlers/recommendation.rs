@@ -200,6 +200,7 @@ async fn fetch_posts_with_metadata(user_id: Uuid, limit: usize) -> Result<Vec<Ca
     let get_resp = content_client
         .get_posts_by_ids(grpc_clients::nova::content_service::GetPostsByIdsRequest {
             post_ids: list_resp.post_ids.clone(),
+            viewer_id: user_id.to_string(),
         })
         .await
         .map_err(|e| AppError::Internal(format!("get_posts_by_ids fallback failed: {}", e)))?
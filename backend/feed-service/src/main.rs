@@ -12,8 +12,9 @@ use utoipa_swagger_ui::SwaggerUi;
 
 use recommendation_service::config::Config;
 use recommendation_service::handlers::{
-    get_feed, get_guest_feed, get_liked_feed, get_model_info, get_recommendations, get_saved_feed,
-    rank_candidates, semantic_search, FeedHandlerState, RecommendationHandlerState,
+    get_experiment_analysis, get_feed, get_guest_feed, get_liked_feed, get_model_info,
+    get_recommendations, get_saved_feed, rank_candidates, semantic_search, ExperimentHandlerState,
+    FeedHandlerState, RecommendationHandlerState,
 };
 use tracing::info;
 
@@ -186,6 +187,44 @@ async fn main() -> io::Result<()> {
 
     let rec_handler_state = web::Data::new(RecommendationHandlerState { ranking_client });
 
+    // Experiment analysis reads logged A/B metrics from PostgreSQL
+    let experiment_handler_state = web::Data::new(ExperimentHandlerState {
+        repo: Arc::new(
+            recommendation_service::db::experiments_repo::ExperimentsRepo::new(
+                db_pool.get_ref().clone(),
+                auth_client.clone(),
+            ),
+        ),
+    });
+
+    // Create shared FeedCache for gRPC service (reuse existing if available)
+    let grpc_cache = match recommendation_service::FeedCache::new(
+        &config.redis.url,
+        recommendation_service::CacheConfig::default(),
+    )
+    .await
+    {
+        Ok(cache) => Arc::new(cache),
+        Err(e) => {
+            tracing::error!("Failed to initialize FeedCache for gRPC service: {}", e);
+            panic!("Failed to initialize FeedCache: {}", e);
+        }
+    };
+
+    // Track private accounts from graph-service events for feed visibility
+    if let Some(graph_consumer_config) =
+        recommendation_service::consumers::graph_consumer::GraphConsumerConfig::from_env()
+    {
+        let consumer = recommendation_service::consumers::graph_consumer::GraphConsumer::new(
+            graph_consumer_config,
+            grpc_cache.clone(),
+        );
+        tokio::spawn(consumer.run());
+        info!("✅ Graph events consumer started");
+    } else {
+        tracing::warn!("Graph events consumer disabled - KAFKA_BOOTSTRAP_SERVERS not set");
+    }
+
     // Initialize FeedHandlerState with gRPC clients
     let feed_handler_state = web::Data::new(FeedHandlerState {
         content_client: Arc::new(
@@ -198,6 +237,7 @@ async fn main() -> io::Result<()> {
             enabled: true,
         }),
         grpc_pool: grpc_pool.clone(),
+        feed_cache: grpc_cache.clone(),
     });
     tracing::info!("FeedHandlerState initialized with content, graph, and social gRPC clients");
 
@@ -258,20 +298,6 @@ async fn main() -> io::Result<()> {
         .expect("Invalid gRPC bind address");
     let grpc_db_pool = db_pool.get_ref().clone();
 
-    // Create shared FeedCache for gRPC service (reuse existing if available)
-    let grpc_cache = match recommendation_service::FeedCache::new(
-        &config.redis.url,
-        recommendation_service::CacheConfig::default(),
-    )
-    .await
-    {
-        Ok(cache) => Arc::new(cache),
-        Err(e) => {
-            tracing::error!("Failed to initialize FeedCache for gRPC service: {}", e);
-            panic!("Failed to initialize FeedCache: {}", e);
-        }
-    };
-
     // Start Redis health check background job to prevent broken pipe errors
     let health_cache = Arc::clone(&grpc_cache);
     tokio::spawn(async move {
@@ -399,6 +425,7 @@ async fn main() -> io::Result<()> {
             .app_data(db_pool.clone())
             .app_data(rec_handler_state.clone())
             .app_data(feed_handler_state.clone())
+            .app_data(experiment_handler_state.clone())
             .route("/health", web::get().to(|| async { "OK" }))
             // Health endpoints for K8s probes
             .route("/api/v1/health", web::get().to(|| async { "OK" }))
@@ -444,6 +471,7 @@ async fn main() -> io::Result<()> {
             .service(get_model_info)
             .service(rank_candidates)
             .service(semantic_search)
+            .service(get_experiment_analysis)
             // Guest feed - public endpoint at /api/v2/guest/feed/trending (NO authentication)
             // Separated from authenticated feed to avoid Actix-web scope conflicts
             .service(web::scope("/api/v2/guest/feed").service(get_guest_feed))
@@ -199,11 +199,15 @@ impl RecommendationEventConsumer {
     /// Process model update event
     async fn handle_model_update(&self, model_type: String, model_path: String) -> Result<()> {
         match model_type.as_str() {
-            "collaborative" | "content_based" => {
+            "collaborative" => {
+                info!("Hot-reloading collaborative model from: {}", model_path);
+                // The CF model sits behind a swappable handle shared with the ranker
+                self.service.reload_collaborative_model(&model_path).await?;
+            }
+            "content_based" => {
                 info!("Hot-reloading {} model from: {}", model_type, model_path);
-                // TODO: Implement hot-reload of specific model
-                // For now, we log the event but cannot reload immutably
-                // The service would need interior mutability for true hot-reload
+                // TODO: Implement hot-reload of the content-based model
+                // It is still owned by value and cannot be reloaded immutably
                 debug!("Model update logged: {} from {}", model_type, model_path);
             }
             "onnx" => {
@@ -222,13 +226,15 @@ impl RecommendationEventConsumer {
     /// Process user feedback event
     async fn handle_user_feedback(
         &self,
-        _user_id: Uuid,
-        _post_id: Uuid,
-        _feedback_type: String,
-        _duration_ms: Option<u32>,
+        user_id: Uuid,
+        post_id: Uuid,
+        feedback_type: String,
+        duration_ms: Option<u32>,
     ) -> Result<()> {
-        // TODO: Send to ClickHouse or training pipeline for model updates
-        // For now, just log it
+        // Online CF training (item-item similarity); no-op when disabled
+        self.service
+            .record_engagement(user_id, post_id, &feedback_type, duration_ms)
+            .await;
         debug!("User feedback recorded for tracking");
         Ok(())
     }
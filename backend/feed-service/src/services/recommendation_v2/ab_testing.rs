@@ -6,19 +6,26 @@
 // recommendation algorithm variants.
 //
 // Features:
-// - Consistent hashing for deterministic user assignment
+// - Consistent hashing for deterministic user assignment (10,000 buckets)
+// - Layers with mutually exclusive experiments, holdouts, targeting and
+//   ramp schedules (see `traffic`)
 // - Experiment configuration from PostgreSQL (via ExperimentsRepo)
 // - Event logging to ClickHouse
 // - Redis caching for fast variant lookup
 
-use crate::db::experiments_repo::{ExperimentStatus as DbExperimentStatus, ExperimentsRepo};
+use super::traffic::{
+    bucket_for, Eligibility, Holdout, Layer, TrafficConfig, UserAttributes, TOTAL_BUCKETS,
+};
+use crate::db::experiments_repo::{
+    Experiment as DbExperiment, ExperimentStatus as DbExperimentStatus,
+    ExperimentTraffic as DbExperimentTraffic, ExperimentVariant as DbExperimentVariant,
+    ExperimentsRepo,
+};
 use crate::error::{AppError, Result};
 use chrono::{DateTime, Utc};
 use redis::aio::ConnectionManager;
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
@@ -35,6 +42,12 @@ pub struct ABTestingFramework {
     /// In-memory cache of active experiments (refreshed periodically)
     experiments: Arc<RwLock<HashMap<String, Experiment>>>,
 
+    /// Experiment layers by name
+    layers: Arc<RwLock<HashMap<String, Layer>>>,
+
+    /// Active global holdout groups
+    holdouts: Arc<RwLock<Vec<Holdout>>>,
+
     /// PostgreSQL repository for persistence
     repo: Option<Arc<ExperimentsRepo>>,
 
@@ -55,6 +68,48 @@ pub struct Experiment {
     pub end_date: Option<DateTime<Utc>>,
     pub variants: Vec<Variant>,
     pub status: ExperimentStatus,
+    /// Layer, bucket ranges, targeting and ramp schedule
+    #[serde(default)]
+    pub traffic: TrafficConfig,
+}
+
+impl Experiment {
+    /// Build the in-memory experiment from its database rows
+    pub fn from_db(db_exp: DbExperiment, db_variants: Vec<DbExperimentVariant>) -> Self {
+        let variants = db_variants
+            .into_iter()
+            .map(|v| Variant {
+                id: v.id,
+                name: v.variant_name,
+                allocation_bps: v.traffic_allocation_bps as u32,
+                config: v.variant_config,
+            })
+            .collect();
+
+        Self {
+            id: db_exp.id,
+            name: db_exp.name,
+            description: db_exp.description.unwrap_or_default(),
+            start_date: db_exp.start_date.unwrap_or_else(Utc::now),
+            end_date: db_exp.end_date,
+            variants,
+            status: db_exp.status.into(),
+            traffic: TrafficConfig::default(),
+        }
+    }
+}
+
+impl TryFrom<DbExperimentTraffic> for TrafficConfig {
+    type Error = AppError;
+
+    fn try_from(row: DbExperimentTraffic) -> Result<Self> {
+        Ok(Self {
+            layer: row.layer_name,
+            bucket_ranges: serde_json::from_value(row.bucket_ranges)?,
+            targeting: row.targeting.map(serde_json::from_value).transpose()?,
+            ramp: serde_json::from_value(row.ramp)?,
+        })
+    }
 }
 
 /// Experiment variant
@@ -62,7 +117,7 @@ pub struct Experiment {
 pub struct Variant {
     pub id: Uuid,
     pub name: String,
-    pub allocation: u8,            // Percentage (0-100)
+    pub allocation_bps: u32,       // Basis points (0-10000, 100 = 1%)
     pub config: serde_json::Value, // Variant-specific config
 }
 
@@ -117,6 +172,8 @@ impl ABTestingFramework {
     ) -> Result<Self> {
         let framework = Self {
             experiments: Arc::new(RwLock::new(HashMap::new())),
+            layers: Arc::new(RwLock::new(HashMap::new())),
+            holdouts: Arc::new(RwLock::new(Vec::new())),
             repo: Some(repo),
             redis: redis.map(|r| Arc::new(RwLock::new(r))),
             last_refresh: Arc::new(RwLock::new(Utc::now())),
@@ -134,6 +191,8 @@ impl ABTestingFramework {
     pub async fn new() -> Result<Self> {
         Ok(Self {
             experiments: Arc::new(RwLock::new(HashMap::new())),
+            layers: Arc::new(RwLock::new(HashMap::new())),
+            holdouts: Arc::new(RwLock::new(Vec::new())),
             repo: None,
             redis: None,
             last_refresh: Arc::new(RwLock::new(Utc::now())),
@@ -155,40 +214,68 @@ impl ABTestingFramework {
             .list_experiments(Some(DbExperimentStatus::Running))
             .await?;
 
+        // Load traffic settings (layer, ranges, targeting, ramp)
+        let mut traffic: HashMap<Uuid, TrafficConfig> = HashMap::new();
+        for row in repo.list_live_traffic().await? {
+            let experiment_id = row.experiment_id;
+            match TrafficConfig::try_from(row) {
+                Ok(config) => {
+                    traffic.insert(experiment_id, config);
+                }
+                Err(e) => {
+                    warn!(
+                        "Invalid traffic settings for experiment {}: {}",
+                        experiment_id, e
+                    );
+                }
+            }
+        }
+
         let mut experiments = HashMap::new();
 
         for db_exp in db_experiments {
             // Load variants for this experiment
             let db_variants = repo.get_variants(db_exp.id).await?;
+            let mut experiment = Experiment::from_db(db_exp, db_variants);
+            if let Some(config) = traffic.remove(&experiment.id) {
+                experiment.traffic = config;
+            }
 
-            let variants: Vec<Variant> = db_variants
-                .into_iter()
-                .map(|v| Variant {
-                    id: v.id,
-                    name: v.variant_name,
-                    allocation: v.traffic_allocation as u8,
-                    config: v.variant_config,
-                })
-                .collect();
-
-            let experiment = Experiment {
-                id: db_exp.id,
-                name: db_exp.name.clone(),
-                description: db_exp.description.unwrap_or_default(),
-                start_date: db_exp.start_date.unwrap_or_else(Utc::now),
-                end_date: db_exp.end_date,
-                variants,
-                status: db_exp.status.into(),
-            };
-
-            experiments.insert(db_exp.name, experiment);
+            experiments.insert(experiment.name.clone(), experiment);
         }
 
+        let layers = repo
+            .list_layers()
+            .await?
+            .into_iter()
+            .map(|l| {
+                let layer = Layer {
+                    id: l.id,
+                    name: l.name,
+                    holdout_buckets: l.holdout_buckets.clamp(0, TOTAL_BUCKETS as i32) as u32,
+                };
+                (layer.name.clone(), layer)
+            })
+            .collect();
+
+        let holdouts = repo
+            .list_active_holdouts()
+            .await?
+            .into_iter()
+            .map(|h| Holdout {
+                id: h.id,
+                name: h.name,
+                buckets: h.buckets.clamp(0, TOTAL_BUCKETS as i32) as u32,
+            })
+            .collect();
+
         // Update in-memory cache
         {
             let mut cache = self.experiments.write().await;
             *cache = experiments;
         }
+        *self.layers.write().await = layers;
+        *self.holdouts.write().await = holdouts;
 
         // Update refresh timestamp
         {
@@ -208,13 +295,17 @@ impl ABTestingFramework {
     ///
     /// Algorithm:
     /// 1. Hash user_id + experiment_name
-    /// 2. Bucket = hash % 100 (0-99)
-    /// 3. Find variant based on cumulative allocation
+    /// 2. Bucket = hash % 10000 (0-9999)
+    /// 3. Find variant based on cumulative allocation (1 bucket = 1 basis point)
     ///
     /// Example:
-    /// - Control: 50% (buckets 0-49)
-    /// - Variant A: 30% (buckets 50-79)
-    /// - Variant B: 20% (buckets 80-99)
+    /// - Control: 5000 bps (buckets 0-4999)
+    /// - Variant A: 4950 bps (buckets 5000-9949)
+    /// - Variant B: 50 bps (buckets 9950-9999)
+    ///
+    /// Only splits users between variants; whether the user is in the
+    /// experiment at all is decided by `check_eligibility`. The variant hash
+    /// is salted by experiment name, independent of the layer hash.
     pub fn assign_bucket<'a>(
         &self,
         user_id: Uuid,
@@ -236,46 +327,106 @@ impl ABTestingFramework {
         }
 
         // Consistent hashing
-        let mut hasher = DefaultHasher::new();
-        user_id.hash(&mut hasher);
-        experiment.name.hash(&mut hasher);
-        let hash_value = hasher.finish();
-        let bucket = (hash_value % 100) as u8;
+        let bucket = bucket_for(user_id, &experiment.name);
 
         // Find variant based on cumulative allocation
-        let mut cumulative = 0u8;
+        let mut cumulative = 0u32;
         for variant in &experiment.variants {
-            cumulative += variant.allocation;
+            cumulative += variant.allocation_bps;
             if bucket < cumulative {
                 return Ok(variant);
             }
         }
 
-        // Fallback to first variant (should never reach here if allocations sum to 100)
+        // Fallback to first variant (should never reach here if allocations sum to TOTAL_BUCKETS)
         Ok(&experiment.variants[0])
     }
 
+    /// Decide whether a user enters an experiment
+    ///
+    /// Applies global holdouts, the layer holdout, the experiment's bucket
+    /// ranges, its ramp schedule and its targeting rule, in that order.
+    pub async fn check_eligibility(
+        &self,
+        user_id: Uuid,
+        experiment: &Experiment,
+        attributes: &UserAttributes,
+    ) -> Eligibility {
+        let layers = self.layers.read().await;
+        let holdouts = self.holdouts.read().await;
+        experiment.traffic.eligibility(
+            user_id,
+            &experiment.name,
+            attributes,
+            &layers,
+            &holdouts,
+            Utc::now(),
+        )
+    }
+
     /// Get variant for user (with Redis caching)
+    ///
+    /// Convenience wrapper for experiments without targeting; users that are
+    /// not eligible get `NotFound`.
     pub async fn get_variant_for_user(
         &self,
         user_id: Uuid,
         experiment_name: &str,
     ) -> Result<(Uuid, String, serde_json::Value)> {
-        // Try Redis cache first
-        if let Some(cached) = self.get_cached_variant(user_id, experiment_name).await? {
-            debug!(
-                "Cache hit for user {} in experiment {}",
+        self.get_variant_for_user_with_attributes(
+            user_id,
+            experiment_name,
+            &UserAttributes::default(),
+        )
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "User {} is not in experiment {}",
                 user_id, experiment_name
-            );
-            return Ok((cached.variant_id, cached.variant_name, cached.config));
-        }
+            ))
+        })
+    }
 
+    /// Get variant for user, or `None` if the user is not in the experiment
+    ///
+    /// Eligibility is checked before the Redis cache so that ramp-downs and
+    /// new holdouts take effect immediately.
+    pub async fn get_variant_for_user_with_attributes(
+        &self,
+        user_id: Uuid,
+        experiment_name: &str,
+        attributes: &UserAttributes,
+    ) -> Result<Option<(Uuid, String, serde_json::Value)>> {
         // Get experiment from memory
         let experiments = self.experiments.read().await;
         let experiment = experiments
             .get(experiment_name)
             .ok_or_else(|| AppError::NotFound(format!("Experiment: {}", experiment_name)))?;
 
+        let eligibility = self
+            .check_eligibility(user_id, experiment, attributes)
+            .await;
+        if eligibility != Eligibility::Eligible {
+            debug!(
+                "User {} not in experiment {}: {:?}",
+                user_id, experiment_name, eligibility
+            );
+            return Ok(None);
+        }
+
+        // Try Redis cache first
+        if let Some(cached) = self.get_cached_variant(user_id, experiment_name).await? {
+            debug!(
+                "Cache hit for user {} in experiment {}",
+                user_id, experiment_name
+            );
+            return Ok(Some((
+                cached.variant_id,
+                cached.variant_name,
+                cached.config,
+            )));
+        }
+
         // Assign bucket
         let variant = self.assign_bucket(user_id, experiment)?;
 
@@ -308,7 +459,11 @@ impl ABTestingFramework {
         self.cache_variant(user_id, experiment_name, &cached)
             .await?;
 
-        Ok((variant.id, variant.name.clone(), variant.config.clone()))
+        Ok(Some((
+            variant.id,
+            variant.name.clone(),
+            variant.config.clone(),
+        )))
     }
 
     /// Get variant configuration for user (convenience method)
@@ -477,20 +632,148 @@ impl ABTestingFramework {
 
     /// Add or update experiment (in-memory, for testing)
     pub async fn add_experiment(&self, experiment: Experiment) -> Result<()> {
-        // Validate allocation sums to 100
-        let total_allocation: u8 = experiment.variants.iter().map(|v| v.allocation).sum();
-        if total_allocation != 100 {
+        // Validate allocation sums to 10000 bps (u64 so oversized inputs can't wrap)
+        let total_allocation: u64 = experiment
+            .variants
+            .iter()
+            .map(|v| v.allocation_bps as u64)
+            .sum();
+        if total_allocation != TOTAL_BUCKETS as u64 {
             return Err(AppError::BadRequest(format!(
-                "Variant allocations must sum to 100 (got {})",
-                total_allocation
+                "Variant allocations must sum to {} bps (got {})",
+                TOTAL_BUCKETS, total_allocation
             )));
         }
 
+        experiment.traffic.validate()?;
+
         let mut experiments = self.experiments.write().await;
+        Self::check_layer_conflicts(&experiments, &experiment.name, &experiment.traffic)?;
         experiments.insert(experiment.name.clone(), experiment);
         Ok(())
     }
 
+    /// Replace an experiment's traffic settings
+    ///
+    /// Rejects the change if the experiment's buckets would overlap another
+    /// live experiment in the same layer, then persists it when a repository
+    /// is configured.
+    pub async fn configure_traffic(
+        &self,
+        experiment_name: &str,
+        traffic: TrafficConfig,
+    ) -> Result<()> {
+        traffic.validate()?;
+
+        let layer_id = match &traffic.layer {
+            Some(name) => Some(
+                self.layers
+                    .read()
+                    .await
+                    .get(name)
+                    .map(|layer| layer.id)
+                    .ok_or_else(|| AppError::BadRequest(format!("Unknown layer: {}", name)))?,
+            ),
+            None => None,
+        };
+
+        let mut experiments = self.experiments.write().await;
+        let experiment_id = match experiments.get(experiment_name) {
+            Some(exp) => exp.id,
+            None => {
+                return Err(AppError::NotFound(format!(
+                    "Experiment: {}",
+                    experiment_name
+                )))
+            }
+        };
+        Self::check_layer_conflicts(&experiments, experiment_name, &traffic)?;
+
+        if let Some(repo) = &self.repo {
+            // Draft experiments are not loaded in memory; check them too
+            for row in repo.list_live_traffic().await? {
+                if row.experiment_id == experiment_id {
+                    continue;
+                }
+                let other = TrafficConfig::try_from(row)?;
+                if traffic.conflicts_with(&other) {
+                    return Err(AppError::BadRequest(format!(
+                        "Traffic of experiment {} overlaps another experiment in layer {:?}",
+                        experiment_name, traffic.layer
+                    )));
+                }
+            }
+
+            repo.upsert_traffic(
+                experiment_id,
+                layer_id,
+                serde_json::to_value(&traffic.bucket_ranges)?,
+                traffic
+                    .targeting
+                    .as_ref()
+                    .map(serde_json::to_value)
+                    .transpose()?,
+                serde_json::to_value(&traffic.ramp)?,
+            )
+            .await?;
+        }
+
+        if let Some(exp) = experiments.get_mut(experiment_name) {
+            exp.traffic = traffic;
+        }
+        Ok(())
+    }
+
+    /// Ensure `traffic` does not overlap another experiment in its layer
+    fn check_layer_conflicts(
+        experiments: &HashMap<String, Experiment>,
+        experiment_name: &str,
+        traffic: &TrafficConfig,
+    ) -> Result<()> {
+        let conflict = experiments.values().find(|other| {
+            other.name != experiment_name
+                && matches!(
+                    other.status,
+                    ExperimentStatus::Draft | ExperimentStatus::Running
+                )
+                && traffic.conflicts_with(&other.traffic)
+        });
+
+        match conflict {
+            Some(other) => Err(AppError::BadRequest(format!(
+                "Experiment {} overlaps experiment {} in layer {:?}",
+                experiment_name, other.name, traffic.layer
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Add or update a layer (in-memory, for testing)
+    pub async fn add_layer(&self, layer: Layer) -> Result<()> {
+        if layer.holdout_buckets > TOTAL_BUCKETS {
+            return Err(AppError::BadRequest(format!(
+                "Layer holdout must be at most {} buckets",
+                TOTAL_BUCKETS
+            )));
+        }
+        self.layers.write().await.insert(layer.name.clone(), layer);
+        Ok(())
+    }
+
+    /// Add a global holdout group (in-memory, for testing)
+    pub async fn add_holdout(&self, holdout: Holdout) -> Result<()> {
+        if holdout.buckets > TOTAL_BUCKETS {
+            return Err(AppError::BadRequest(format!(
+                "Holdout must be at most {} buckets",
+                TOTAL_BUCKETS
+            )));
+        }
+        let mut holdouts = self.holdouts.write().await;
+        holdouts.retain(|h| h.name != holdout.name);
+        holdouts.push(holdout);
+        Ok(())
+    }
+
     /// Remove experiment (in-memory)
     pub async fn remove_experiment(&self, experiment_name: &str) -> Result<()> {
         let mut experiments = self.experiments.write().await;
@@ -525,6 +808,7 @@ impl ABTestingFramework {
 
 #[cfg(test)]
 mod tests {
+    use super::super::traffic::{BucketRange, TargetingRule};
     use super::*;
 
     #[tokio::test]
@@ -541,17 +825,18 @@ mod tests {
                 Variant {
                     id: Uuid::new_v4(),
                     name: "control".to_string(),
-                    allocation: 50,
+                    allocation_bps: 5000,
                     config: serde_json::json!({"algorithm": "v1.0"}),
                 },
                 Variant {
                     id: Uuid::new_v4(),
                     name: "variant_a".to_string(),
-                    allocation: 50,
+                    allocation_bps: 5000,
                     config: serde_json::json!({"algorithm": "v2.0"}),
                 },
             ],
             status: ExperimentStatus::Running,
+            traffic: TrafficConfig::default(),
         };
 
         framework.add_experiment(experiment.clone()).await.unwrap();
@@ -579,23 +864,24 @@ mod tests {
                 Variant {
                     id: Uuid::new_v4(),
                     name: "control".to_string(),
-                    allocation: 50,
+                    allocation_bps: 5000,
                     config: serde_json::json!({"algorithm": "v1.0"}),
                 },
                 Variant {
                     id: Uuid::new_v4(),
                     name: "variant_a".to_string(),
-                    allocation: 30,
+                    allocation_bps: 3000,
                     config: serde_json::json!({"algorithm": "v2.0"}),
                 },
                 Variant {
                     id: Uuid::new_v4(),
                     name: "variant_b".to_string(),
-                    allocation: 20,
+                    allocation_bps: 2000,
                     config: serde_json::json!({"algorithm": "v2.0"}),
                 },
             ],
             status: ExperimentStatus::Running,
+            traffic: TrafficConfig::default(),
         };
 
         framework.add_experiment(experiment.clone()).await.unwrap();
@@ -630,6 +916,50 @@ mod tests {
         ); // ~20%
     }
 
+    #[tokio::test]
+    async fn test_sub_percent_allocation() {
+        let framework = ABTestingFramework::new().await.unwrap();
+
+        let experiment = Experiment {
+            id: Uuid::new_v4(),
+            name: "canary_experiment".to_string(),
+            description: "Test".to_string(),
+            start_date: Utc::now(),
+            end_date: None,
+            variants: vec![
+                Variant {
+                    id: Uuid::new_v4(),
+                    name: "control".to_string(),
+                    allocation_bps: 9950,
+                    config: serde_json::json!({}),
+                },
+                Variant {
+                    id: Uuid::new_v4(),
+                    name: "canary".to_string(),
+                    allocation_bps: 50, // 0.5%
+                    config: serde_json::json!({}),
+                },
+            ],
+            status: ExperimentStatus::Running,
+            traffic: TrafficConfig::default(),
+        };
+
+        framework.add_experiment(experiment.clone()).await.unwrap();
+
+        let canary = (0..20_000)
+            .filter(|_| {
+                framework
+                    .assign_bucket(Uuid::new_v4(), &experiment)
+                    .unwrap()
+                    .name
+                    == "canary"
+            })
+            .count();
+
+        // ~100 expected; a 1% floor would yield ~200
+        assert!(canary > 40 && canary < 170, "Canary: {}", canary);
+    }
+
     #[tokio::test]
     async fn test_invalid_allocation() {
         let framework = ABTestingFramework::new().await.unwrap();
@@ -643,10 +973,11 @@ mod tests {
             variants: vec![Variant {
                 id: Uuid::new_v4(),
                 name: "control".to_string(),
-                allocation: 40, // Only 40%, should fail
+                allocation_bps: 4000, // Only 40%, should fail
                 config: serde_json::json!({"algorithm": "v1.0"}),
             }],
             status: ExperimentStatus::Running,
+            traffic: TrafficConfig::default(),
         };
 
         let result = framework.add_experiment(experiment).await;
@@ -666,10 +997,11 @@ mod tests {
             variants: vec![Variant {
                 id: Uuid::new_v4(),
                 name: "control".to_string(),
-                allocation: 100,
+                allocation_bps: 10000,
                 config: serde_json::json!({}),
             }],
             status: ExperimentStatus::Draft, // Not running
+            traffic: TrafficConfig::default(),
         };
 
         let user_id = Uuid::new_v4();
@@ -690,10 +1022,11 @@ mod tests {
             variants: vec![Variant {
                 id: Uuid::new_v4(),
                 name: "control".to_string(),
-                allocation: 100,
+                allocation_bps: 10000,
                 config: serde_json::json!({}),
             }],
             status: ExperimentStatus::Running,
+            traffic: TrafficConfig::default(),
         };
 
         let draft_exp = Experiment {
@@ -705,10 +1038,11 @@ mod tests {
             variants: vec![Variant {
                 id: Uuid::new_v4(),
                 name: "control".to_string(),
-                allocation: 100,
+                allocation_bps: 10000,
                 config: serde_json::json!({}),
             }],
             status: ExperimentStatus::Draft,
+            traffic: TrafficConfig::default(),
         };
 
         framework.add_experiment(running_exp).await.unwrap();
@@ -718,4 +1052,113 @@ mod tests {
         assert_eq!(active.len(), 1);
         assert_eq!(active[0].name, "running_exp");
     }
+
+    fn layered_experiment(name: &str, layer: &str, start: u32, end: u32) -> Experiment {
+        Experiment {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: "Layered".to_string(),
+            start_date: Utc::now(),
+            end_date: None,
+            variants: vec![Variant {
+                id: Uuid::new_v4(),
+                name: "control".to_string(),
+                allocation_bps: 10000,
+                config: serde_json::json!({}),
+            }],
+            status: ExperimentStatus::Running,
+            traffic: TrafficConfig {
+                layer: Some(layer.to_string()),
+                bucket_ranges: vec![BucketRange { start, end }],
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layer_overlap_rejected() {
+        let framework = ABTestingFramework::new().await.unwrap();
+
+        framework
+            .add_experiment(layered_experiment("ranking_a", "ranking", 0, 5_000))
+            .await
+            .unwrap();
+        framework
+            .add_experiment(layered_experiment("ranking_b", "ranking", 5_000, 10_000))
+            .await
+            .unwrap();
+
+        // Overlaps ranking_a in the same layer
+        let result = framework
+            .add_experiment(layered_experiment("ranking_c", "ranking", 4_000, 4_500))
+            .await;
+        assert!(result.is_err());
+
+        // Same buckets in another layer are fine
+        framework
+            .add_experiment(layered_experiment("ui_a", "ui", 0, 5_000))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_targeted_variant_lookup() {
+        let framework = ABTestingFramework::new().await.unwrap();
+
+        let mut experiment = layered_experiment("tw_only", "ranking", 0, 10_000);
+        experiment.traffic.targeting = Some(TargetingRule::Eq {
+            attribute: "country".to_string(),
+            value: "TW".into(),
+        });
+        framework.add_experiment(experiment).await.unwrap();
+
+        let user_id = Uuid::new_v4();
+        let tw = UserAttributes::new().with("country", "TW");
+        let us = UserAttributes::new().with("country", "US");
+
+        let assigned = framework
+            .get_variant_for_user_with_attributes(user_id, "tw_only", &tw)
+            .await
+            .unwrap();
+        assert_eq!(assigned.unwrap().1, "control");
+
+        let excluded = framework
+            .get_variant_for_user_with_attributes(user_id, "tw_only", &us)
+            .await
+            .unwrap();
+        assert!(excluded.is_none());
+
+        // Untargeted lookup reports the user as not in the experiment
+        assert!(framework
+            .get_variant_for_user(user_id, "tw_only")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_global_holdout_excludes_user() {
+        let framework = ABTestingFramework::new().await.unwrap();
+        framework
+            .add_experiment(layered_experiment("held_out", "ranking", 0, 10_000))
+            .await
+            .unwrap();
+        framework
+            .add_holdout(Holdout {
+                id: Uuid::new_v4(),
+                name: "global".to_string(),
+                buckets: TOTAL_BUCKETS,
+            })
+            .await
+            .unwrap();
+
+        let result = framework
+            .get_variant_for_user_with_attributes(
+                Uuid::new_v4(),
+                "held_out",
+                &UserAttributes::default(),
+            )
+            .await
+            .unwrap();
+        assert!(result.is_none());
+    }
 }
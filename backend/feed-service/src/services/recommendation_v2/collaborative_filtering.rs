@@ -10,15 +10,24 @@
 //   ClickHouse (user_item_interactions) → Similarity Computation → Sparse Matrix
 //                                                ↓
 //                                         Recommendations
+//
+// The serving model sits behind `CfModelHandle` so the online trainer
+// (see `online_cf`) can swap in refreshed similarities without a restart.
 
 use crate::error::Result;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+const USER_SIMILARITY_FILE: &str = "user_similarity.json";
+const ITEM_SIMILARITY_FILE: &str = "item_similarity.json";
+const USER_LIKED_POSTS_FILE: &str = "user_liked_posts.json";
+
 /// Similarity metric for collaborative filtering
 #[derive(Debug, Clone, Copy)]
 pub enum SimilarityMetric {
@@ -50,9 +59,9 @@ impl CollaborativeFilteringModel {
     ///
     /// Expected file format: JSON serialized HashMap<Uuid, Vec<(Uuid, f64)>>
     pub fn load(user_sim_path: &str, item_sim_path: &str, k_neighbors: usize) -> Result<Self> {
-        let user_path = normalize_path(user_sim_path, "user_similarity.json");
-        let item_path = normalize_path(item_sim_path, "item_similarity.json");
-        let user_liked_path = normalize_path(user_sim_path, "user_liked_posts.json");
+        let user_path = normalize_path(user_sim_path, USER_SIMILARITY_FILE);
+        let item_path = normalize_path(item_sim_path, ITEM_SIMILARITY_FILE);
+        let user_liked_path = normalize_path(user_sim_path, USER_LIKED_POSTS_FILE);
 
         let user_similarity = load_similarity_map(&user_path, k_neighbors)?;
         let item_similarity = load_similarity_map(&item_path, k_neighbors)?;
@@ -74,6 +83,27 @@ impl CollaborativeFilteringModel {
         })
     }
 
+    /// Write the model to `dir` in the format read by `load()`
+    ///
+    /// Each file is written to a temporary name and renamed into place, so a
+    /// concurrent `load()` never sees a partial file.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        write_similarity_map(&dir.join(USER_SIMILARITY_FILE), &self.user_similarity)?;
+        write_similarity_map(&dir.join(ITEM_SIMILARITY_FILE), &self.item_similarity)?;
+        write_similarity_map(&dir.join(USER_LIKED_POSTS_FILE), &self.user_liked_posts)?;
+
+        info!(
+            user_entries = self.user_similarity.len(),
+            item_entries = self.item_similarity.len(),
+            user_liked_entries = self.user_liked_posts.len(),
+            path = %dir.display(),
+            "Collaborative filtering snapshot written"
+        );
+
+        Ok(())
+    }
+
     /// Create an empty model (for testing or initialization)
     pub fn empty(k_neighbors: usize) -> Self {
         Self {
@@ -261,6 +291,73 @@ impl CollaborativeFilteringModel {
     }
 }
 
+/// Hot-swappable handle to the serving CF model
+///
+/// Readers take a cheap `Arc` clone of the current model; `swap` replaces it
+/// atomically for subsequent readers. The last batch-trained model is kept
+/// alongside so online training always merges onto it rather than onto its
+/// own previous output.
+#[derive(Debug, Clone)]
+pub struct CfModelHandle {
+    models: Arc<RwLock<CfModels>>,
+}
+
+#[derive(Debug)]
+struct CfModels {
+    batch: Arc<CollaborativeFilteringModel>,
+    current: Arc<CollaborativeFilteringModel>,
+}
+
+impl CfModelHandle {
+    pub fn new(model: CollaborativeFilteringModel) -> Self {
+        let model = Arc::new(model);
+        Self {
+            models: Arc::new(RwLock::new(CfModels {
+                batch: model.clone(),
+                current: model,
+            })),
+        }
+    }
+
+    /// Current model
+    pub async fn current(&self) -> Arc<CollaborativeFilteringModel> {
+        self.models.read().await.current.clone()
+    }
+
+    /// Last batch-trained model (without online updates)
+    pub async fn batch(&self) -> Arc<CollaborativeFilteringModel> {
+        self.models.read().await.batch.clone()
+    }
+
+    /// Replace the batch model and serve it, returning the previous serving model
+    pub async fn swap(
+        &self,
+        model: CollaborativeFilteringModel,
+    ) -> Arc<CollaborativeFilteringModel> {
+        let model = Arc::new(model);
+        let mut models = self.models.write().await;
+        models.batch = model.clone();
+        std::mem::replace(&mut models.current, model)
+    }
+
+    /// Serve an online merge of `base`
+    ///
+    /// Returns false (and leaves the serving model alone) if the batch model
+    /// was swapped since `base` was read.
+    pub async fn publish_online(
+        &self,
+        base: &Arc<CollaborativeFilteringModel>,
+        model: CollaborativeFilteringModel,
+    ) -> bool {
+        let mut models = self.models.write().await;
+        if !Arc::ptr_eq(&models.batch, base) {
+            return false;
+        }
+        models.current = Arc::new(model);
+        true
+    }
+}
+
 fn normalize_path(path: &str, default_file: &str) -> PathBuf {
     let candidate = Path::new(path);
     if candidate.is_dir() {
@@ -375,6 +472,29 @@ fn load_similarity_map(path: &Path, k_neighbors: usize) -> Result<HashMap<Uuid,
     Ok(result)
 }
 
+fn write_similarity_map(path: &Path, map: &HashMap<Uuid, Vec<(Uuid, f64)>>) -> Result<()> {
+    let serialized: HashMap<String, Vec<SimilarityEntryOut>> = map
+        .iter()
+        .map(|(key, entries)| {
+            (
+                key.to_string(),
+                entries
+                    .iter()
+                    .map(|(id, score)| SimilarityEntryOut {
+                        id: id.to_string(),
+                        score: *score,
+                    })
+                    .collect(),
+            )
+        })
+        .collect();
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec(&serialized)?)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum SimilarityEntries {
@@ -402,6 +522,12 @@ struct SimilarityEntry {
     score: f64,
 }
 
+#[derive(Debug, Serialize)]
+struct SimilarityEntryOut {
+    id: String,
+    score: f64,
+}
+
 pub struct ModelMetadata {
     pub user_count: usize,
     pub item_count: usize,
@@ -517,6 +643,47 @@ mod tests {
         assert!((recommendations[2].1 - 0.7).abs() < 0.01);
     }
 
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = Uuid::new_v4();
+        let post1 = Uuid::new_v4();
+        let post2 = Uuid::new_v4();
+
+        let mut model = CollaborativeFilteringModel::empty(10);
+        model.item_similarity.insert(post1, vec![(post2, 0.75)]);
+        model
+            .user_similarity
+            .insert(user, vec![(Uuid::new_v4(), 0.5)]);
+        model.update_user_liked_posts(user, post1, 2.0);
+        model.save(dir.path()).unwrap();
+
+        let path = dir.path().to_str().unwrap();
+        let loaded = CollaborativeFilteringModel::load(path, path, 10).unwrap();
+        assert_eq!(loaded.find_similar_posts(post1, 5), vec![(post2, 0.75)]);
+        assert_eq!(loaded.user_similarity.len(), 1);
+        assert_eq!(
+            loaded.get_user_liked_posts(user).unwrap(),
+            &vec![(post1, 2.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_model_handle_swap() {
+        let post1 = Uuid::new_v4();
+        let post2 = Uuid::new_v4();
+        let handle = CfModelHandle::new(CollaborativeFilteringModel::empty(10));
+        let before = handle.current().await;
+
+        let mut updated = CollaborativeFilteringModel::empty(10);
+        updated.item_similarity.insert(post1, vec![(post2, 0.9)]);
+        handle.swap(updated).await;
+
+        // Readers holding the old Arc are unaffected
+        assert!(before.item_similarity.is_empty());
+        assert_eq!(handle.current().await.find_similar_posts(post1, 1).len(), 1);
+    }
+
     #[test]
     fn test_update_user_liked_posts() {
         let mut model = CollaborativeFilteringModel::empty(50);
@@ -0,0 +1,935 @@
+// ============================================
+// Experiment Analysis Engine
+// ============================================
+//
+// Turns logged ExperimentEvents (experiment_metrics rows) into a verdict per
+// variant against control.
+//
+// Features:
+// - Per-variant aggregation of exposures and conversions
+// - Absolute/relative deltas with normal-approximation confidence intervals
+//   (delta method for ratio metrics such as CTR)
+// - Mixture SPRT (mSPRT) always-valid p-values, safe under continuous peeking
+// - Sample-ratio-mismatch (SRM) check against configured Variant::allocation_bps
+// - CUPED variance reduction using a pre-experiment covariate per user
+//
+// Data Flow:
+//   experiment_metrics (PostgreSQL) → UserObservation per user → analyze()
+//                                                                   ↓
+//                                                          ExperimentAnalysis
+
+use super::ab_testing::Experiment;
+use crate::db::experiments_repo::ExperimentsRepo;
+use crate::error::{AppError, Result};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::debug;
+use uuid::Uuid;
+
+/// SRM is flagged below this chi-square p-value (standard industry threshold)
+const SRM_P_VALUE_THRESHOLD: f64 = 0.001;
+
+/// Default mixing variance for mSPRT, relative to the pooled metric variance
+const DEFAULT_MSPRT_TAU_SQ_RATIO: f64 = 0.01;
+
+/// Default CUPED pre-period length before experiment start
+const DEFAULT_CUPED_LOOKBACK_DAYS: i64 = 14;
+
+/// How a metric is computed from per-user observations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricKind {
+    /// Share of users with at least one conversion
+    Conversion,
+    /// Sum of conversions / sum of exposures (e.g. clicks / impressions)
+    Ctr,
+    /// Mean conversion value per user (e.g. dwell ms per user)
+    Mean,
+}
+
+impl MetricKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "conversion" => Some(Self::Conversion),
+            "ctr" => Some(Self::Ctr),
+            "mean" => Some(Self::Mean),
+            _ => None,
+        }
+    }
+}
+
+/// Aggregated metric values for one user in one variant
+#[derive(Debug, Clone, Copy)]
+pub struct UserObservation {
+    pub user_id: Uuid,
+    pub variant_id: Uuid,
+    /// Exposure count (e.g. impressions)
+    pub exposures: f64,
+    /// Outcome value (e.g. clicks, likes, dwell ms)
+    pub conversions: f64,
+    /// Same outcome measured before the experiment started (CUPED covariate)
+    pub pre_period: Option<f64>,
+}
+
+/// Analysis options
+#[derive(Debug, Clone)]
+pub struct AnalysisConfig {
+    pub metric: MetricKind,
+    /// Logged action counted as exposure (e.g. "impression")
+    pub exposure_action: String,
+    /// Logged action counted as outcome (e.g. "click", "like", "impression_dwell_ms")
+    pub outcome_action: String,
+    /// Name of the control variant (deltas are relative to it)
+    pub control_variant: String,
+    /// Significance level for CIs and sequential decisions
+    pub alpha: f64,
+    /// Apply CUPED when pre-period covariates are available
+    pub use_cuped: bool,
+    /// mSPRT mixing variance as a fraction of pooled variance
+    pub msprt_tau_sq_ratio: f64,
+    /// Pre-period window before experiment start used for CUPED
+    pub cuped_lookback: Duration,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            metric: MetricKind::Ctr,
+            exposure_action: "impression".to_string(),
+            outcome_action: "click".to_string(),
+            control_variant: "control".to_string(),
+            alpha: 0.05,
+            use_cuped: true,
+            msprt_tau_sq_ratio: DEFAULT_MSPRT_TAU_SQ_RATIO,
+            cuped_lookback: Duration::days(DEFAULT_CUPED_LOOKBACK_DAYS),
+        }
+    }
+}
+
+/// Per-variant summary statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantSummary {
+    pub variant_id: Uuid,
+    pub variant_name: String,
+    pub users: u64,
+    pub exposures: f64,
+    pub conversions: f64,
+    /// Metric point estimate (rate or mean, depending on MetricKind)
+    pub value: f64,
+    /// Variance of the point estimate (after CUPED if applied)
+    pub estimate_variance: f64,
+}
+
+/// Treatment vs control comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantComparison {
+    pub variant_name: String,
+    pub absolute_delta: f64,
+    pub relative_delta: Option<f64>,
+    pub ci_lower: f64,
+    pub ci_upper: f64,
+    /// Fixed-horizon two-sided p-value (only valid at the planned sample size)
+    pub p_value: f64,
+    pub sequential: SequentialResult,
+}
+
+/// mSPRT outcome at the current look
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequentialResult {
+    pub likelihood_ratio: f64,
+    /// Always-valid p-value: safe to check after every new observation
+    pub always_valid_p_value: f64,
+    pub decision: SequentialDecision,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequentialDecision {
+    /// Keep collecting data
+    Continue,
+    /// Difference is significant at `alpha`; safe to stop
+    RejectNull,
+}
+
+/// Sample-ratio-mismatch check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SrmCheck {
+    pub chi_square: f64,
+    pub p_value: f64,
+    pub mismatch: bool,
+    /// variant name → (observed users, expected users)
+    pub counts: HashMap<String, (u64, f64)>,
+}
+
+/// Full analysis result for an experiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentAnalysis {
+    pub experiment_id: Uuid,
+    pub experiment_name: String,
+    pub metric: MetricKind,
+    pub cuped_applied: bool,
+    /// Relative variance reduction achieved by CUPED (0 when not applied)
+    pub cuped_variance_reduction: f64,
+    pub variants: Vec<VariantSummary>,
+    pub comparisons: Vec<VariantComparison>,
+    pub srm: SrmCheck,
+}
+
+/// Load logged metrics for an experiment and analyze them
+pub async fn analyze_experiment(
+    repo: &ExperimentsRepo,
+    experiment: &Experiment,
+    config: &AnalysisConfig,
+) -> Result<ExperimentAnalysis> {
+    let aggregates = repo
+        .get_user_metric_aggregates(
+            experiment.id,
+            &config.exposure_action,
+            &config.outcome_action,
+        )
+        .await?;
+
+    let pre_period: HashMap<Uuid, f64> = if config.use_cuped && config.metric != MetricKind::Ctr {
+        let user_ids: Vec<Uuid> = aggregates.iter().map(|a| a.user_id).collect();
+        repo.get_pre_period_metric(
+            &user_ids,
+            &config.outcome_action,
+            experiment.start_date - config.cuped_lookback,
+            experiment.start_date,
+        )
+        .await?
+        .into_iter()
+        .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let observations: Vec<UserObservation> = aggregates
+        .into_iter()
+        .map(|a| UserObservation {
+            user_id: a.user_id,
+            variant_id: a.variant_id,
+            exposures: a.exposures,
+            conversions: a.outcomes,
+            // Users with no pre-period activity have a covariate of zero
+            pre_period: config
+                .use_cuped
+                .then(|| pre_period.get(&a.user_id).copied().unwrap_or(0.0)),
+        })
+        .collect();
+
+    debug!(
+        "Analyzing experiment {} over {} users",
+        experiment.name,
+        observations.len()
+    );
+    analyze(experiment, &observations, config)
+}
+
+/// Analyze experiment observations against the experiment's configured variants
+pub fn analyze(
+    experiment: &Experiment,
+    observations: &[UserObservation],
+    config: &AnalysisConfig,
+) -> Result<ExperimentAnalysis> {
+    if !(0.0..1.0).contains(&config.alpha) || config.alpha == 0.0 {
+        return Err(AppError::BadRequest(format!(
+            "alpha must be in (0, 1), got {}",
+            config.alpha
+        )));
+    }
+
+    let control = experiment
+        .variants
+        .iter()
+        .find(|v| v.name == config.control_variant)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Control variant '{}' not found in experiment {}",
+                config.control_variant, experiment.name
+            ))
+        })?;
+
+    // Per-user metric values Y (and covariate X for CUPED)
+    let mut by_variant: HashMap<Uuid, Vec<&UserObservation>> = HashMap::new();
+    for obs in observations {
+        by_variant.entry(obs.variant_id).or_default().push(obs);
+    }
+
+    let cuped_theta = if config.use_cuped && config.metric != MetricKind::Ctr {
+        cuped_theta(observations, config.metric)
+    } else {
+        None
+    };
+    let covariate_mean = cuped_theta.map(|_| {
+        mean(
+            &observations
+                .iter()
+                .filter_map(|o| o.pre_period.map(|x| covariate_value(x, config.metric)))
+                .collect::<Vec<_>>(),
+        )
+    });
+
+    let mut summaries = Vec::with_capacity(experiment.variants.len());
+    let mut raw_variance = 0.0;
+    let mut adjusted_variance = 0.0;
+    for variant in &experiment.variants {
+        let users = by_variant
+            .get(&variant.id)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        let exposures: f64 = users.iter().map(|o| o.exposures).sum();
+        let conversions: f64 = users.iter().map(|o| o.conversions).sum();
+
+        let (value, estimate_variance) = match config.metric {
+            MetricKind::Ctr => ratio_estimate(users),
+            MetricKind::Conversion | MetricKind::Mean => {
+                let ys: Vec<f64> = users.iter().map(|o| user_value(o, config.metric)).collect();
+                let raw = mean_estimate(&ys);
+                raw_variance += raw.1;
+
+                match (cuped_theta, covariate_mean) {
+                    (Some(theta), Some(x_bar)) => {
+                        let adjusted: Vec<f64> = users
+                            .iter()
+                            .map(|o| {
+                                user_value(o, config.metric)
+                                    - theta
+                                        * (o.pre_period
+                                            .map(|x| covariate_value(x, config.metric))
+                                            .unwrap_or(x_bar)
+                                            - x_bar)
+                            })
+                            .collect();
+                        let adj = mean_estimate(&adjusted);
+                        adjusted_variance += adj.1;
+                        adj
+                    }
+                    _ => {
+                        adjusted_variance += raw.1;
+                        raw
+                    }
+                }
+            }
+        };
+
+        summaries.push(VariantSummary {
+            variant_id: variant.id,
+            variant_name: variant.name.clone(),
+            users: users.len() as u64,
+            exposures,
+            conversions,
+            value,
+            estimate_variance,
+        });
+    }
+
+    let control_summary = summaries
+        .iter()
+        .find(|s| s.variant_id == control.id)
+        .cloned()
+        .expect("control summary exists");
+
+    let pooled_variance = pooled_unit_variance(&summaries);
+    let comparisons = summaries
+        .iter()
+        .filter(|s| s.variant_id != control.id)
+        .map(|treatment| {
+            compare(
+                &control_summary,
+                treatment,
+                config.alpha,
+                config.msprt_tau_sq_ratio * pooled_variance,
+            )
+        })
+        .collect();
+
+    let srm = sample_ratio_check(experiment, &summaries);
+
+    let cuped_variance_reduction = if cuped_theta.is_some() && raw_variance > 0.0 {
+        (1.0 - adjusted_variance / raw_variance).max(0.0)
+    } else {
+        0.0
+    };
+
+    Ok(ExperimentAnalysis {
+        experiment_id: experiment.id,
+        experiment_name: experiment.name.clone(),
+        metric: config.metric,
+        cuped_applied: cuped_theta.is_some(),
+        cuped_variance_reduction,
+        variants: summaries,
+        comparisons,
+        srm,
+    })
+}
+
+/// Chi-square goodness-of-fit of users per variant vs configured allocation
+pub fn sample_ratio_check(experiment: &Experiment, summaries: &[VariantSummary]) -> SrmCheck {
+    let total: u64 = summaries.iter().map(|s| s.users).sum();
+    let total_allocation: f64 = experiment
+        .variants
+        .iter()
+        .map(|v| v.allocation_bps as f64)
+        .sum();
+
+    let mut chi_square = 0.0;
+    let mut counts = HashMap::new();
+    let mut cells = 0usize;
+    for variant in &experiment.variants {
+        let observed = summaries
+            .iter()
+            .find(|s| s.variant_id == variant.id)
+            .map(|s| s.users)
+            .unwrap_or(0);
+        let expected = if total_allocation > 0.0 {
+            total as f64 * variant.allocation_bps as f64 / total_allocation
+        } else {
+            0.0
+        };
+        if expected > 0.0 {
+            chi_square += (observed as f64 - expected).powi(2) / expected;
+            cells += 1;
+        }
+        counts.insert(variant.name.clone(), (observed, expected));
+    }
+
+    let p_value = if cells < 2 || total == 0 {
+        1.0
+    } else {
+        chi_square_sf(chi_square, (cells - 1) as f64)
+    };
+
+    SrmCheck {
+        chi_square,
+        p_value,
+        mismatch: p_value < SRM_P_VALUE_THRESHOLD,
+        counts,
+    }
+}
+
+fn compare(
+    control: &VariantSummary,
+    treatment: &VariantSummary,
+    alpha: f64,
+    tau_sq: f64,
+) -> VariantComparison {
+    let delta = treatment.value - control.value;
+    let variance = treatment.estimate_variance + control.estimate_variance;
+    let se = variance.sqrt();
+    let z_crit = normal_quantile(1.0 - alpha / 2.0);
+
+    let p_value = if se > 0.0 {
+        2.0 * (1.0 - normal_cdf((delta / se).abs()))
+    } else if delta == 0.0 {
+        1.0
+    } else {
+        0.0
+    };
+
+    let relative_delta = (control.value != 0.0).then(|| delta / control.value);
+
+    VariantComparison {
+        variant_name: treatment.variant_name.clone(),
+        absolute_delta: delta,
+        relative_delta,
+        ci_lower: delta - z_crit * se,
+        ci_upper: delta + z_crit * se,
+        p_value,
+        sequential: msprt(delta, variance, tau_sq, alpha),
+    }
+}
+
+/// Normal-mixture SPRT (Johari et al., "Always Valid Inference")
+///
+/// With `V` the variance of the observed difference and `tau_sq` the variance
+/// of the N(0, tau²) mixing prior on the true effect:
+///   Λ = sqrt(V / (V + τ²)) · exp(τ² δ² / (2 V (V + τ²)))
+/// and the always-valid p-value is min(1, 1/Λ).
+pub fn msprt(delta: f64, variance: f64, tau_sq: f64, alpha: f64) -> SequentialResult {
+    if variance <= 0.0 || tau_sq <= 0.0 {
+        return SequentialResult {
+            likelihood_ratio: 1.0,
+            always_valid_p_value: 1.0,
+            decision: SequentialDecision::Continue,
+        };
+    }
+
+    let log_lr = 0.5 * (variance / (variance + tau_sq)).ln()
+        + tau_sq * delta * delta / (2.0 * variance * (variance + tau_sq));
+    let likelihood_ratio = log_lr.exp();
+    let always_valid_p_value = (-log_lr).exp().min(1.0);
+
+    SequentialResult {
+        likelihood_ratio,
+        always_valid_p_value,
+        decision: if always_valid_p_value < alpha {
+            SequentialDecision::RejectNull
+        } else {
+            SequentialDecision::Continue
+        },
+    }
+}
+
+fn user_value(obs: &UserObservation, metric: MetricKind) -> f64 {
+    match metric {
+        MetricKind::Conversion => {
+            if obs.conversions > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        MetricKind::Mean | MetricKind::Ctr => obs.conversions,
+    }
+}
+
+fn covariate_value(pre: f64, metric: MetricKind) -> f64 {
+    match metric {
+        MetricKind::Conversion => {
+            if pre > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        MetricKind::Mean | MetricKind::Ctr => pre,
+    }
+}
+
+/// θ = cov(X, Y) / var(X), pooled across all variants
+fn cuped_theta(observations: &[UserObservation], metric: MetricKind) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = observations
+        .iter()
+        .filter_map(|o| {
+            o.pre_period
+                .map(|x| (covariate_value(x, metric), user_value(o, metric)))
+        })
+        .collect();
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let n = pairs.len() as f64;
+    let x_bar = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let y_bar = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let cov = pairs
+        .iter()
+        .map(|(x, y)| (x - x_bar) * (y - y_bar))
+        .sum::<f64>()
+        / (n - 1.0);
+    let var_x = pairs.iter().map(|(x, _)| (x - x_bar).powi(2)).sum::<f64>() / (n - 1.0);
+
+    (var_x > 0.0).then(|| cov / var_x)
+}
+
+/// (mean, variance of the mean)
+fn mean_estimate(values: &[f64]) -> (f64, f64) {
+    let n = values.len();
+    if n == 0 {
+        return (0.0, 0.0);
+    }
+    let m = mean(values);
+    if n < 2 {
+        return (m, 0.0);
+    }
+    let var = values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (n as f64 - 1.0);
+    (m, var / n as f64)
+}
+
+/// Ratio metric Σy / Σx with delta-method variance (users are the randomization unit)
+fn ratio_estimate(users: &[&UserObservation]) -> (f64, f64) {
+    let n = users.len() as f64;
+    let sum_x: f64 = users.iter().map(|o| o.exposures).sum();
+    let sum_y: f64 = users.iter().map(|o| o.conversions).sum();
+    if sum_x <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let ratio = sum_y / sum_x;
+    if users.len() < 2 {
+        return (ratio, 0.0);
+    }
+
+    let x_bar = sum_x / n;
+    let y_bar = sum_y / n;
+    let var_x = users
+        .iter()
+        .map(|o| (o.exposures - x_bar).powi(2))
+        .sum::<f64>()
+        / (n - 1.0);
+    let var_y = users
+        .iter()
+        .map(|o| (o.conversions - y_bar).powi(2))
+        .sum::<f64>()
+        / (n - 1.0);
+    let cov = users
+        .iter()
+        .map(|o| (o.exposures - x_bar) * (o.conversions - y_bar))
+        .sum::<f64>()
+        / (n - 1.0);
+
+    let variance = (var_y - 2.0 * ratio * cov + ratio * ratio * var_x) / (n * x_bar * x_bar);
+    (ratio, variance.max(0.0))
+}
+
+/// Per-unit variance implied by the variant estimates (for mSPRT τ² scaling)
+fn pooled_unit_variance(summaries: &[VariantSummary]) -> f64 {
+    let users: u64 = summaries.iter().map(|s| s.users).sum();
+    if users == 0 {
+        return 0.0;
+    }
+    summaries
+        .iter()
+        .map(|s| s.estimate_variance * s.users as f64 * s.users as f64)
+        .sum::<f64>()
+        / users as f64
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// Standard normal CDF via erf (Abramowitz & Stegun 7.1.26, |ε| < 1.5e-7)
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+/// Inverse standard normal CDF (Acklam's rational approximation)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.024_25;
+
+    let p = p.clamp(1e-12, 1.0 - 1e-12);
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -normal_quantile(1.0 - p)
+    }
+}
+
+/// Survival function of the chi-square distribution: P(X > x) for `dof` degrees of freedom
+pub fn chi_square_sf(x: f64, dof: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    regularized_gamma_q(dof / 2.0, x / 2.0)
+}
+
+/// Upper regularized incomplete gamma Q(a, x) (Numerical Recipes gammq)
+fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_continued_fraction(a, x)
+    }
+}
+
+fn gamma_series(a: f64, x: f64) -> f64 {
+    let mut ap = a;
+    let mut sum = 1.0 / a;
+    let mut del = sum;
+    for _ in 0..500 {
+        ap += 1.0;
+        del *= x / ap;
+        sum += del;
+        if del.abs() < sum.abs() * 1e-14 {
+            break;
+        }
+    }
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+fn gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..500 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let del = d * c;
+        h *= del;
+        if (del - 1.0).abs() < 1e-14 {
+            break;
+        }
+    }
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+/// ln Γ(x) via Lanczos approximation (g = 7, n = 9)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut a = COEFFS[0];
+    let t = x + 7.5;
+    for (i, c) in COEFFS.iter().enumerate().skip(1) {
+        a += c / (x + i as f64);
+    }
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ab_testing::{ExperimentStatus, Variant};
+    use super::*;
+    use chrono::Utc;
+
+    fn experiment(allocations: &[(&str, u32)]) -> Experiment {
+        Experiment {
+            id: Uuid::new_v4(),
+            name: "ranking_test".to_string(),
+            description: String::new(),
+            start_date: Utc::now(),
+            end_date: None,
+            variants: allocations
+                .iter()
+                .map(|(name, allocation)| Variant {
+                    id: Uuid::new_v4(),
+                    name: name.to_string(),
+                    allocation_bps: *allocation,
+                    config: serde_json::json!({}),
+                })
+                .collect(),
+            status: ExperimentStatus::Running,
+            traffic: Default::default(),
+        }
+    }
+
+    /// Deterministic users: `clicks_of(i)` clicks out of 10 impressions
+    fn observations(
+        variant_id: Uuid,
+        count: usize,
+        clicks_of: impl Fn(usize) -> f64,
+        pre_of: impl Fn(usize) -> Option<f64>,
+    ) -> Vec<UserObservation> {
+        (0..count)
+            .map(|i| UserObservation {
+                user_id: Uuid::new_v4(),
+                variant_id,
+                exposures: 10.0,
+                conversions: clicks_of(i),
+                pre_period: pre_of(i),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_statistical_helpers() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+        assert!((normal_quantile(0.975) - 1.959_964).abs() < 1e-4);
+        // chi-square(1) critical value at 0.05
+        assert!((chi_square_sf(3.841, 1.0) - 0.05).abs() < 1e-3);
+        // chi-square(3) critical value at 0.01
+        assert!((chi_square_sf(11.345, 3.0) - 0.01).abs() < 1e-3);
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_ctr_lift_detected() {
+        let exp = experiment(&[("control", 5000), ("treatment", 5000)]);
+        let mut obs = observations(exp.variants[0].id, 2100, |i| (i % 3) as f64, |_| None);
+        obs.extend(observations(
+            exp.variants[1].id,
+            2100,
+            |i| (i % 3) as f64 + 1.0,
+            |_| None,
+        ));
+
+        let result = analyze(&exp, &obs, &AnalysisConfig::default()).unwrap();
+        assert_eq!(result.variants.len(), 2);
+        let cmp = &result.comparisons[0];
+        assert!((result.variants[0].value - 0.1).abs() < 1e-9);
+        assert!((cmp.absolute_delta - 0.1).abs() < 1e-9);
+        assert!((cmp.relative_delta.unwrap() - 1.0).abs() < 1e-9);
+        assert!(cmp.ci_lower > 0.0);
+        assert!(cmp.p_value < 0.001);
+        assert_eq!(cmp.sequential.decision, SequentialDecision::RejectNull);
+        assert!(!result.srm.mismatch);
+    }
+
+    #[test]
+    fn test_no_difference_keeps_running() {
+        let exp = experiment(&[("control", 5000), ("treatment", 5000)]);
+        let mut obs = observations(exp.variants[0].id, 500, |i| (i % 4) as f64, |_| None);
+        obs.extend(observations(
+            exp.variants[1].id,
+            500,
+            |i| ((i + 1) % 4) as f64,
+            |_| None,
+        ));
+
+        let result = analyze(&exp, &obs, &AnalysisConfig::default()).unwrap();
+        let cmp = &result.comparisons[0];
+        assert!(cmp.ci_lower < 0.0 && cmp.ci_upper > 0.0);
+        assert_eq!(cmp.sequential.decision, SequentialDecision::Continue);
+        assert!(cmp.sequential.always_valid_p_value >= cmp.p_value);
+    }
+
+    #[test]
+    fn test_srm_detected() {
+        let exp = experiment(&[("control", 5000), ("treatment", 5000)]);
+        let mut obs = observations(exp.variants[0].id, 1000, |_| 1.0, |_| None);
+        obs.extend(observations(exp.variants[1].id, 800, |_| 1.0, |_| None));
+
+        let result = analyze(&exp, &obs, &AnalysisConfig::default()).unwrap();
+        assert!(result.srm.mismatch, "p = {}", result.srm.p_value);
+        assert_eq!(result.srm.counts["treatment"], (800, 900.0));
+    }
+
+    #[test]
+    fn test_cuped_reduces_variance() {
+        let exp = experiment(&[("control", 5000), ("treatment", 5000)]);
+        // Outcome strongly correlated with pre-period behaviour
+        let mut obs = observations(
+            exp.variants[0].id,
+            1000,
+            |i| (i % 10) as f64,
+            |i| Some((i % 10) as f64),
+        );
+        obs.extend(observations(
+            exp.variants[1].id,
+            1000,
+            |i| (i % 10) as f64 + 0.5,
+            |i| Some((i % 10) as f64),
+        ));
+
+        let config = AnalysisConfig {
+            metric: MetricKind::Mean,
+            ..Default::default()
+        };
+        let with_cuped = analyze(&exp, &obs, &config).unwrap();
+        let without = analyze(
+            &exp,
+            &obs,
+            &AnalysisConfig {
+                use_cuped: false,
+                ..config
+            },
+        )
+        .unwrap();
+
+        assert!(with_cuped.cuped_applied);
+        assert!(with_cuped.cuped_variance_reduction > 0.9);
+        let w = &with_cuped.comparisons[0];
+        let wo = &without.comparisons[0];
+        assert!((w.absolute_delta - 0.5).abs() < 1e-9);
+        assert!((w.ci_upper - w.ci_lower) < (wo.ci_upper - wo.ci_lower));
+    }
+
+    #[test]
+    fn test_conversion_metric() {
+        let exp = experiment(&[("control", 5000), ("treatment", 5000)]);
+        let mut obs = observations(
+            exp.variants[0].id,
+            100,
+            |i| if i % 4 == 0 { 2.0 } else { 0.0 },
+            |_| None,
+        );
+        obs.extend(observations(
+            exp.variants[1].id,
+            100,
+            |i| if i % 2 == 0 { 1.0 } else { 0.0 },
+            |_| None,
+        ));
+
+        let config = AnalysisConfig {
+            metric: MetricKind::Conversion,
+            ..Default::default()
+        };
+        let result = analyze(&exp, &obs, &config).unwrap();
+        assert!((result.variants[0].value - 0.25).abs() < 1e-9);
+        assert!((result.variants[1].value - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unknown_control_rejected() {
+        let exp = experiment(&[("a", 5000), ("b", 5000)]);
+        assert!(analyze(&exp, &[], &AnalysisConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_msprt_grows_with_evidence() {
+        let weak = msprt(0.01, 1e-4, 1e-4, 0.05);
+        let strong = msprt(0.05, 1e-4, 1e-4, 0.05);
+        assert!(strong.likelihood_ratio > weak.likelihood_ratio);
+        assert_eq!(strong.decision, SequentialDecision::RejectNull);
+        assert_eq!(weak.decision, SequentialDecision::Continue);
+    }
+}
@@ -7,7 +7,7 @@
 
 use crate::error::{AppError, Result};
 use crate::services::recommendation_v2::{
-    CollaborativeFilteringModel, ContentBasedModel, UserContext,
+    CfModelHandle, CollaborativeFilteringModel, ContentBasedModel, UserContext,
 };
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
@@ -72,7 +72,7 @@ pub enum RankingStrategy {
 
 /// Hybrid recommendation ranker
 pub struct HybridRanker {
-    pub cf_model: CollaborativeFilteringModel,
+    pub cf_model: CfModelHandle,
     pub cb_model: ContentBasedModel,
     pub weights: HybridWeights,
 }
@@ -91,6 +91,15 @@ impl HybridRanker {
         cf_model: CollaborativeFilteringModel,
         cb_model: ContentBasedModel,
         weights: HybridWeights,
+    ) -> Result<Self> {
+        Self::with_cf_handle(CfModelHandle::new(cf_model), cb_model, weights)
+    }
+
+    /// Create a ranker that reads CF scores through a shared, hot-swappable handle
+    pub fn with_cf_handle(
+        cf_model: CfModelHandle,
+        cb_model: ContentBasedModel,
+        weights: HybridWeights,
     ) -> Result<Self> {
         weights.validate()?;
         Ok(Self {
@@ -152,9 +161,11 @@ impl HybridRanker {
             return Ok(HashMap::new());
         }
 
-        let recommendations =
-            self.cf_model
-                .recommend_item_based(&recent_posts, &seen_posts, candidates.len())?;
+        let recommendations = self.cf_model.current().await.recommend_item_based(
+            &recent_posts,
+            &seen_posts,
+            candidates.len(),
+        )?;
 
         Ok(filter_scores(recommendations, candidates))
     }
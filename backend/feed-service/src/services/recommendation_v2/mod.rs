@@ -6,7 +6,7 @@
 // 1. Collaborative Filtering (user-user, item-item)
 // 2. Content-Based Filtering (TF-IDF features + user profiles)
 // 3. Hybrid Ranking (weighted combination + diversity optimization)
-// 4. A/B Testing Framework (user bucketing + experiment tracking)
+// 4. A/B Testing Framework (layered bucketing + experiment tracking + analysis)
 // 5. Real-Time Model Serving (ONNX inference with fallback)
 //
 // Architecture:
@@ -19,14 +19,23 @@
 pub mod ab_testing;
 pub mod collaborative_filtering;
 pub mod content_based;
+pub mod experiment_analysis;
 pub mod hybrid_ranker;
+pub mod online_cf;
 pub mod onnx_serving;
+pub mod traffic;
 
 pub use ab_testing::{ABTestingFramework, Experiment, ExperimentEvent, Variant};
-pub use collaborative_filtering::{CollaborativeFilteringModel, SimilarityMetric};
+pub use collaborative_filtering::{CfModelHandle, CollaborativeFilteringModel, SimilarityMetric};
 pub use content_based::{ContentBasedModel, PostFeatures, UserProfile};
+pub use experiment_analysis::{AnalysisConfig, ExperimentAnalysis, MetricKind};
 pub use hybrid_ranker::{HybridRanker, HybridWeights, RankedPost, RankingStrategy};
+pub use online_cf::{OnlineCfConfig, OnlineCfTrainer};
 pub use onnx_serving::{LatencyStats, ONNXModelServer};
+pub use traffic::{
+    BucketRange, Eligibility, Holdout, Layer, RampStep, TargetingRule, TrafficConfig,
+    UserAttributes,
+};
 
 use crate::error::Result;
 use chrono::{DateTime, Utc};
@@ -49,12 +58,14 @@ const USER_HISTORY_MAX: i64 = 200;
 
 /// Unified recommendation service v2.0
 pub struct RecommendationServiceV2 {
-    pub cf_model: CollaborativeFilteringModel,
+    pub cf_model: CfModelHandle,
     pub cb_model: ContentBasedModel,
     pub hybrid_ranker: HybridRanker,
     pub ab_framework: ABTestingFramework,
     pub onnx_server: ONNXModelServer,
     pub vector_search: Option<crate::services::vector_search::VectorSearchService>,
+    /// Online item-item similarity updates (enabled via ONLINE_CF_ENABLED)
+    pub online_cf: Option<Arc<OnlineCfTrainer>>,
     db_pool: PgPool,
     config: RecommendationConfig,
     model_loaded_at: DateTime<Utc>,
@@ -68,28 +79,58 @@ impl RecommendationServiceV2 {
         _auth_client: Arc<grpc_clients::AuthClient>,
     ) -> Result<Self> {
         let (cf_model, cb_model) = Self::load_models_from_config(&config)?;
-        let hybrid_ranker =
-            HybridRanker::new(cf_model.clone(), cb_model.clone(), config.hybrid_weights)?;
+        let cf_model = CfModelHandle::new(cf_model);
+        let hybrid_ranker = HybridRanker::with_cf_handle(
+            cf_model.clone(),
+            cb_model.clone(),
+            config.hybrid_weights,
+        )?;
+
+        // Online CF training: engagement events update item similarities and
+        // the trainer hot-swaps the model shared with the hybrid ranker
+        let online_cf = OnlineCfConfig::from_env().map(|online_config| {
+            let trainer = Arc::new(OnlineCfTrainer::new(cf_model.clone(), online_config));
+            trainer.spawn();
+            if let Some(consumer_config) =
+                crate::consumers::engagement_consumer::EngagementConsumerConfig::from_env()
+            {
+                let consumer = crate::consumers::engagement_consumer::EngagementConsumer::new(
+                    consumer_config,
+                    Arc::clone(&trainer),
+                );
+                tokio::spawn(consumer.run());
+            }
+            info!("Online collaborative filtering training enabled");
+            trainer
+        });
 
         // Initialize A/B testing framework
         // Note: ExperimentsRepo integration is TODO - framework currently uses in-memory state
         let ab_framework = ABTestingFramework::new().await?;
         let onnx_server = ONNXModelServer::load(&config.onnx_model_path)?;
 
-        // Initialize vector search service with Milvus
-        let vector_search = match std::env::var("MILVUS_URL") {
-            Ok(milvus_url) => {
-                let vs = crate::services::vector_search::VectorSearchService::new(milvus_url, 768);
+        // Initialize vector search service (embedded HNSW unless Milvus is configured)
+        let vector_search = match crate::services::vector_search::VectorSearchConfig::from_env() {
+            Ok(vs_config) => {
+                let vs =
+                    crate::services::vector_search::VectorSearchService::from_config(&vs_config);
                 if let Err(e) = vs.initialize_collection().await {
-                    warn!("Failed to initialize Milvus collection: {:?}", e);
+                    warn!(
+                        "Failed to initialize {} vector search: {:?}",
+                        vs.backend_name(),
+                        e
+                    );
                     None
                 } else {
-                    info!("Vector search service initialized successfully");
+                    info!(
+                        "Vector search service initialized with {} backend",
+                        vs.backend_name()
+                    );
                     Some(vs)
                 }
             }
-            Err(_) => {
-                info!("MILVUS_URL not configured, vector search disabled");
+            Err(e) => {
+                warn!("Invalid vector search configuration, disabled: {:?}", e);
                 None
             }
         };
@@ -101,6 +142,7 @@ impl RecommendationServiceV2 {
             ab_framework,
             onnx_server,
             vector_search,
+            online_cf,
             db_pool,
             config,
             model_loaded_at: Utc::now(),
@@ -210,21 +252,49 @@ impl RecommendationServiceV2 {
     /// Reload models (hot-reload for version updates)
     pub async fn reload_models(&mut self) -> Result<()> {
         let (cf_model, cb_model) = Self::load_models_from_config(&self.config)?;
-        self.hybrid_ranker = HybridRanker::new(
-            cf_model.clone(),
+        self.cf_model.swap(cf_model).await;
+        self.hybrid_ranker = HybridRanker::with_cf_handle(
+            self.cf_model.clone(),
             cb_model.clone(),
             self.config.hybrid_weights,
         )?;
-        self.cf_model = cf_model;
         self.cb_model = cb_model;
         self.model_loaded_at = Utc::now();
         info!("Recommendation models reloaded");
         Ok(())
     }
 
+    /// Hot-swap the collaborative filtering model from `path` (file or
+    /// snapshot directory) without a restart
+    pub async fn reload_collaborative_model(&self, path: &str) -> Result<()> {
+        let path = path.to_string();
+        let cf_model = tokio::task::spawn_blocking(move || {
+            CollaborativeFilteringModel::load(&path, &path, DEFAULT_K_NEIGHBORS)
+        })
+        .await
+        .map_err(|e| crate::error::AppError::Internal(format!("CF reload task failed: {}", e)))??;
+
+        self.cf_model.swap(cf_model).await;
+        info!("Collaborative filtering model hot-swapped");
+        Ok(())
+    }
+
+    /// Feed an engagement event to online CF training (no-op when disabled)
+    pub async fn record_engagement(
+        &self,
+        user_id: Uuid,
+        post_id: Uuid,
+        kind: &str,
+        duration_ms: Option<u32>,
+    ) {
+        if let Some(trainer) = &self.online_cf {
+            trainer.record(user_id, post_id, kind, duration_ms).await;
+        }
+    }
+
     /// Get model version info
     pub async fn get_model_info(&self) -> ModelInfo {
-        let meta = self.cf_model.metadata();
+        let meta = self.cf_model.current().await.metadata();
         let collaborative_version = format!(
             "users:{} items:{} k:{}",
             meta.user_count, meta.item_count, meta.k_neighbors
@@ -252,7 +322,12 @@ impl RecommendationServiceV2 {
     ) -> Result<Vec<crate::services::vector_search::VectorSearchResult>> {
         if let Some(ref vector_search) = self.vector_search {
             vector_search
-                .search_similar_by_post(post_id, limit, 0.5)
+                .search_similar_by_post(
+                    post_id,
+                    limit,
+                    0.5,
+                    &crate::services::vector_search::SearchFilter::default(),
+                )
                 .await
         } else {
             warn!("Vector search service not available");
@@ -286,6 +361,15 @@ impl RecommendationServiceV2 {
         }
     }
 
+    /// Remove a post embedding from semantic search (e.g. post deleted)
+    pub async fn remove_post_embedding(&self, post_id: Uuid) -> Result<()> {
+        if let Some(ref vector_search) = self.vector_search {
+            vector_search.delete_embedding(post_id).await
+        } else {
+            Ok(())
+        }
+    }
+
     /// Get vector search cache statistics
     pub async fn get_vector_cache_stats(&self) -> (usize, usize) {
         if let Some(ref vector_search) = self.vector_search {
@@ -411,7 +495,7 @@ impl RecommendationServiceV2 {
         let mut ordered = Vec::new();
 
         if !context.recent_posts.is_empty() {
-            let cf_candidates = self.cf_model.recommend_item_based(
+            let cf_candidates = self.cf_model.current().await.recommend_item_based(
                 &context.recent_posts,
                 &context.seen_posts,
                 target,
@@ -544,6 +628,7 @@ impl RecommendationServiceV2 {
                 limit,
                 offset: 0,
                 status: ContentStatus::Published as i32,
+                viewer_id: user_id.to_string(),
             })
             .await
             .map_err(|e| crate::error::AppError::Internal(format!("get_user_posts failed: {}", e)))?
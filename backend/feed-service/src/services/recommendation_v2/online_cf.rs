@@ -0,0 +1,676 @@
+// ============================================
+// Online Item-Item Similarity Training
+// ============================================
+//
+// Keeps `CollaborativeFilteringModel::item_similarity` fresh between batch
+// retrains by folding like/engagement events into decayed co-occurrence
+// counts:
+//
+//   co(i, j)  = Σ_u w(u, i) · w(u, j)      (pairs within a user's recent history)
+//   norm(i)   = Σ_u w(u, i)²
+//   sim(i, j) = co(i, j) / sqrt(norm(i) · norm(j))   (cosine)
+//
+// Every count decays exponentially with a configurable half-life, so old
+// engagement fades out. Each item keeps its top-k neighbours, rescored
+// whenever the item or one of its pairs changes.
+//
+// Data Flow:
+//   Kafka (social.like.created, feedback) → OnlineItemSimilarity
+//                                               ↓ (publish interval)
+//                    merge into last batch model → CfModelHandle::publish_online
+//                                               ↓ (snapshot interval)
+//                                  CollaborativeFilteringModel::save
+
+use super::collaborative_filtering::{CfModelHandle, CollaborativeFilteringModel};
+use crate::error::{AppError, Result};
+use chrono::Utc;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Counts below this (after decay) are dropped during pruning
+const DEFAULT_PRUNE_THRESHOLD: f64 = 0.01;
+
+/// Configuration for online CF training
+#[derive(Debug, Clone)]
+pub struct OnlineCfConfig {
+    /// Half-life of co-occurrence counts
+    pub half_life: Duration,
+    /// Neighbours kept per item
+    pub k_neighbors: usize,
+    /// Recent interactions per user considered for co-occurrence
+    pub history_per_user: usize,
+    /// Neighbours below this similarity are not kept
+    pub min_similarity: f64,
+    /// Decayed counts below this are pruned
+    pub prune_threshold: f64,
+    /// How often the serving model is refreshed
+    pub publish_interval: Duration,
+    /// How often the serving model is written to disk
+    pub snapshot_interval: Duration,
+    /// Snapshot directory (same layout `CollaborativeFilteringModel::load` reads)
+    pub snapshot_dir: Option<PathBuf>,
+}
+
+impl Default for OnlineCfConfig {
+    fn default() -> Self {
+        Self {
+            half_life: Duration::from_secs(3 * 24 * 3600),
+            k_neighbors: 50,
+            history_per_user: 50,
+            min_similarity: 0.01,
+            prune_threshold: DEFAULT_PRUNE_THRESHOLD,
+            publish_interval: Duration::from_secs(60),
+            snapshot_interval: Duration::from_secs(15 * 60),
+            snapshot_dir: None,
+        }
+    }
+}
+
+impl OnlineCfConfig {
+    /// Load from environment; returns `None` unless `ONLINE_CF_ENABLED=true`
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("ONLINE_CF_ENABLED")
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+
+        let secs = |key: &str, default: Duration| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+
+        let defaults = Self::default();
+        Some(Self {
+            half_life: std::env::var("ONLINE_CF_HALF_LIFE_HOURS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(|h| Duration::from_secs(h * 3600))
+                .unwrap_or(defaults.half_life),
+            k_neighbors: std::env::var("ONLINE_CF_K_NEIGHBORS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.k_neighbors),
+            history_per_user: std::env::var("ONLINE_CF_HISTORY_PER_USER")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.history_per_user),
+            publish_interval: secs("ONLINE_CF_PUBLISH_INTERVAL_SECS", defaults.publish_interval),
+            snapshot_interval: secs(
+                "ONLINE_CF_SNAPSHOT_INTERVAL_SECS",
+                defaults.snapshot_interval,
+            ),
+            snapshot_dir: std::env::var("ONLINE_CF_SNAPSHOT_DIR")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .map(PathBuf::from),
+            ..defaults
+        })
+    }
+}
+
+/// Interaction weight for an engagement event (matches the batch weights in
+/// `CollaborativeFilteringModel::user_liked_posts`)
+///
+/// Returns `None` for events that should not count as positive engagement.
+pub fn interaction_weight(kind: &str, duration_ms: Option<u32>) -> Option<f64> {
+    match kind {
+        "like" => Some(1.0),
+        "comment" => Some(2.0),
+        "share" => Some(3.0),
+        "complete_watch" => Some(1.5),
+        "click" => Some(0.5),
+        // Dwell only counts once the user actually stayed on the post
+        "dwell" => match duration_ms {
+            Some(ms) if ms >= 10_000 => Some(1.0),
+            Some(ms) if ms >= 3_000 => Some(0.5),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Exponentially decayed value
+#[derive(Debug, Clone, Copy)]
+struct Decayed {
+    value: f64,
+    updated_at: i64,
+}
+
+impl Decayed {
+    fn value_at(&self, now: i64, half_life_secs: f64) -> f64 {
+        let elapsed = (now - self.updated_at).max(0) as f64;
+        self.value * 0.5f64.powf(elapsed / half_life_secs)
+    }
+
+    fn add(&mut self, delta: f64, now: i64, half_life_secs: f64) {
+        self.value = self.value_at(now, half_life_secs) + delta;
+        self.updated_at = now.max(self.updated_at);
+    }
+}
+
+/// A user's recent interaction
+#[derive(Debug, Clone, Copy)]
+struct Interaction {
+    post_id: Uuid,
+    weight: f64,
+    at: i64,
+}
+
+/// Order-independent key for an item pair
+fn pair_key(a: Uuid, b: Uuid) -> (Uuid, Uuid) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Incrementally maintained item-item cosine similarity
+#[derive(Debug)]
+pub struct OnlineItemSimilarity {
+    config: OnlineCfConfig,
+    half_life_secs: f64,
+    co_counts: HashMap<(Uuid, Uuid), Decayed>,
+    norms: HashMap<Uuid, Decayed>,
+    histories: HashMap<Uuid, VecDeque<Interaction>>,
+    top_k: HashMap<Uuid, Vec<(Uuid, f64)>>,
+    events_applied: u64,
+}
+
+impl OnlineItemSimilarity {
+    pub fn new(config: OnlineCfConfig) -> Self {
+        let half_life_secs = config.half_life.as_secs_f64().max(1.0);
+        Self {
+            config,
+            half_life_secs,
+            co_counts: HashMap::new(),
+            norms: HashMap::new(),
+            histories: HashMap::new(),
+            top_k: HashMap::new(),
+            events_applied: 0,
+        }
+    }
+
+    /// Fold one engagement into the counts
+    ///
+    /// `at` is the event time in Unix seconds. Repeated engagement with the
+    /// same post only counts the increase in weight.
+    pub fn observe(&mut self, user_id: Uuid, post_id: Uuid, weight: f64, at: i64) {
+        if !weight.is_finite() || weight <= 0.0 {
+            return;
+        }
+
+        let history = self.histories.entry(user_id).or_default();
+        let previous = history
+            .iter()
+            .position(|i| i.post_id == post_id)
+            .map(|idx| history[idx].weight);
+
+        let old_weight = previous.unwrap_or(0.0);
+        if weight <= old_weight {
+            return;
+        }
+
+        let partners: Vec<(Uuid, f64)> = history
+            .iter()
+            .filter(|i| i.post_id != post_id)
+            .map(|i| (i.post_id, i.weight))
+            .collect();
+
+        if previous.is_some() {
+            if let Some(entry) = history.iter_mut().find(|i| i.post_id == post_id) {
+                entry.weight = weight;
+                entry.at = at;
+            }
+        } else {
+            history.push_back(Interaction {
+                post_id,
+                weight,
+                at,
+            });
+            while history.len() > self.config.history_per_user {
+                history.pop_front();
+            }
+        }
+
+        let half_life = self.half_life_secs;
+        self.norms
+            .entry(post_id)
+            .or_insert(Decayed {
+                value: 0.0,
+                updated_at: at,
+            })
+            .add(weight * weight - old_weight * old_weight, at, half_life);
+
+        let delta = weight - old_weight;
+        for (partner, partner_weight) in partners {
+            self.co_counts
+                .entry(pair_key(post_id, partner))
+                .or_insert(Decayed {
+                    value: 0.0,
+                    updated_at: at,
+                })
+                .add(delta * partner_weight, at, half_life);
+            self.refresh_pair(post_id, partner, at);
+        }
+
+        // norm(post_id) changed, so every existing neighbour's score did too
+        let neighbors: Vec<Uuid> = self.neighbors(post_id).iter().map(|(id, _)| *id).collect();
+        for neighbor in neighbors {
+            self.refresh_pair(post_id, neighbor, at);
+        }
+
+        self.events_applied += 1;
+    }
+
+    fn refresh_pair(&mut self, a: Uuid, b: Uuid, now: i64) {
+        let similarity = self.similarity(a, b, now);
+        self.update_neighbor(a, b, similarity);
+        self.update_neighbor(b, a, similarity);
+    }
+
+    /// Cosine similarity of two items at time `now`
+    pub fn similarity(&self, a: Uuid, b: Uuid, now: i64) -> f64 {
+        let co = match self.co_counts.get(&pair_key(a, b)) {
+            Some(c) => c.value_at(now, self.half_life_secs),
+            None => return 0.0,
+        };
+        let norm = |id: &Uuid| {
+            self.norms
+                .get(id)
+                .map(|n| n.value_at(now, self.half_life_secs))
+                .unwrap_or(0.0)
+        };
+        let denom = (norm(&a) * norm(&b)).sqrt();
+        if denom <= f64::EPSILON {
+            0.0
+        } else {
+            (co / denom).clamp(0.0, 1.0)
+        }
+    }
+
+    fn update_neighbor(&mut self, item: Uuid, neighbor: Uuid, similarity: f64) {
+        let k = self.config.k_neighbors;
+        let min_similarity = self.config.min_similarity;
+        let list = self.top_k.entry(item).or_default();
+
+        list.retain(|(id, _)| *id != neighbor);
+        if similarity >= min_similarity {
+            let pos = list
+                .iter()
+                .position(|(_, s)| *s < similarity)
+                .unwrap_or(list.len());
+            list.insert(pos, (neighbor, similarity));
+            list.truncate(k);
+        }
+        if list.is_empty() {
+            self.top_k.remove(&item);
+        }
+    }
+
+    /// Current top-k neighbours of an item
+    pub fn neighbors(&self, post_id: Uuid) -> &[(Uuid, f64)] {
+        self.top_k.get(&post_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Number of engagement events applied so far
+    pub fn events_applied(&self) -> u64 {
+        self.events_applied
+    }
+
+    /// Drop counts that have decayed away and history older than ~10 half-lives
+    ///
+    /// Returns the number of item pairs removed.
+    pub fn prune(&mut self, now: i64) -> usize {
+        let half_life = self.half_life_secs;
+        let threshold = self.config.prune_threshold;
+
+        let before = self.co_counts.len();
+        self.co_counts
+            .retain(|_, c| c.value_at(now, half_life) >= threshold);
+        self.norms
+            .retain(|_, n| n.value_at(now, half_life) >= threshold);
+
+        let horizon = now - (half_life * 10.0) as i64;
+        self.histories.retain(|_, history| {
+            history.retain(|i| i.at >= horizon);
+            !history.is_empty()
+        });
+
+        let co_counts = &self.co_counts;
+        self.top_k.retain(|item, list| {
+            list.retain(|(neighbor, _)| co_counts.contains_key(&pair_key(*item, *neighbor)));
+            !list.is_empty()
+        });
+
+        before - self.co_counts.len()
+    }
+
+    /// Merge the online state into a copy of `base`
+    ///
+    /// `base` must be the last batch-trained model, not a previously published
+    /// merge, so online scores never compound across publishes. For items with
+    /// online neighbours, the batch list is overlaid with the online scores
+    /// (online replaces batch per neighbour) and cut to k. Recent user
+    /// interactions are folded into `user_liked_posts`.
+    pub fn merge_into(&self, base: &CollaborativeFilteringModel) -> CollaborativeFilteringModel {
+        let mut model = base.clone();
+        let k = model.k_neighbors.max(1);
+
+        for (item, online) in &self.top_k {
+            let mut merged: HashMap<Uuid, f64> = base
+                .item_similarity
+                .get(item)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            merged.extend(online.iter().copied());
+
+            let mut neighbors: Vec<(Uuid, f64)> = merged.into_iter().collect();
+            neighbors.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            neighbors.truncate(k);
+            model.item_similarity.insert(*item, neighbors);
+        }
+
+        for (user_id, history) in &self.histories {
+            for interaction in history {
+                model.update_user_liked_posts(*user_id, interaction.post_id, interaction.weight);
+            }
+        }
+
+        model
+    }
+}
+
+/// Online trainer: applies engagement events and hot-swaps the serving model
+pub struct OnlineCfTrainer {
+    state: Mutex<OnlineItemSimilarity>,
+    handle: CfModelHandle,
+    config: OnlineCfConfig,
+}
+
+impl OnlineCfTrainer {
+    pub fn new(handle: CfModelHandle, config: OnlineCfConfig) -> Self {
+        Self {
+            state: Mutex::new(OnlineItemSimilarity::new(config.clone())),
+            handle,
+            config,
+        }
+    }
+
+    /// Record an engagement event (`kind` as in `interaction_weight`)
+    pub async fn record(&self, user_id: Uuid, post_id: Uuid, kind: &str, duration_ms: Option<u32>) {
+        match interaction_weight(kind, duration_ms) {
+            Some(weight) => {
+                self.state
+                    .lock()
+                    .await
+                    .observe(user_id, post_id, weight, Utc::now().timestamp());
+            }
+            None => debug!("Ignoring non-positive engagement '{}'", kind),
+        }
+    }
+
+    /// Merge online similarities into the last batch model and serve the result
+    pub async fn publish(&self) -> Result<()> {
+        let base = self.handle.batch().await;
+        let (model, events) = {
+            let mut state = self.state.lock().await;
+            let pruned = state.prune(Utc::now().timestamp());
+            if pruned > 0 {
+                debug!("Pruned {} decayed item pairs", pruned);
+            }
+            (state.merge_into(&base), state.events_applied())
+        };
+
+        let item_entries = model.item_similarity.len();
+        if !self.handle.publish_online(&base, model).await {
+            debug!("Batch model replaced during merge; skipping online publish");
+            return Ok(());
+        }
+        debug!(
+            item_entries,
+            events_applied = events,
+            "Published online CF model"
+        );
+        Ok(())
+    }
+
+    /// Write the serving model to the snapshot directory
+    pub async fn snapshot(&self) -> Result<()> {
+        let Some(dir) = self.config.snapshot_dir.clone() else {
+            return Ok(());
+        };
+
+        let model = self.handle.current().await;
+        tokio::task::spawn_blocking(move || model.save(&dir))
+            .await
+            .map_err(|e| AppError::Internal(format!("CF snapshot task failed: {}", e)))?
+    }
+
+    /// Publish and snapshot on the configured intervals until aborted
+    pub fn spawn(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let trainer = Arc::clone(self);
+        tokio::spawn(async move {
+            info!(
+                publish_secs = trainer.config.publish_interval.as_secs(),
+                snapshot_secs = trainer.config.snapshot_interval.as_secs(),
+                "Online CF trainer started"
+            );
+
+            let mut publish = tokio::time::interval(trainer.config.publish_interval);
+            let mut snapshot = tokio::time::interval(trainer.config.snapshot_interval);
+            publish.tick().await;
+            snapshot.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = publish.tick() => {
+                        if let Err(e) = trainer.publish().await {
+                            warn!("Online CF publish failed: {:?}", e);
+                        }
+                    }
+                    _ = snapshot.tick() => {
+                        if let Err(e) = trainer.snapshot().await {
+                            warn!("Online CF snapshot failed: {:?}", e);
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 3600;
+
+    fn config() -> OnlineCfConfig {
+        OnlineCfConfig {
+            half_life: Duration::from_secs(DAY as u64),
+            k_neighbors: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_interaction_weights() {
+        assert_eq!(interaction_weight("like", None), Some(1.0));
+        assert_eq!(interaction_weight("share", None), Some(3.0));
+        assert_eq!(interaction_weight("dwell", Some(1_000)), None);
+        assert_eq!(interaction_weight("dwell", Some(12_000)), Some(1.0));
+        assert_eq!(interaction_weight("dislike", None), None);
+    }
+
+    #[test]
+    fn test_co_occurrence_matches_cosine() {
+        let mut online = OnlineItemSimilarity::new(config());
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (u1, u2) = (Uuid::new_v4(), Uuid::new_v4());
+
+        // Vectors over users: a = [1, 1], b = [1, 0], c = [0, 1]
+        online.observe(u1, a, 1.0, 0);
+        online.observe(u1, b, 1.0, 0);
+        online.observe(u2, a, 1.0, 0);
+        online.observe(u2, c, 1.0, 0);
+
+        let expected = 1.0 / 2f64.sqrt();
+        assert!((online.similarity(a, b, 0) - expected).abs() < 1e-9);
+        assert!((online.similarity(a, c, 0) - expected).abs() < 1e-9);
+        assert_eq!(online.similarity(b, c, 0), 0.0);
+        assert_eq!(online.neighbors(a).len(), 2);
+        assert_eq!(online.neighbors(b), &[(a, online.similarity(a, b, 0))]);
+    }
+
+    #[test]
+    fn test_repeat_engagement_counts_increase_only() {
+        let mut online = OnlineItemSimilarity::new(config());
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let user = Uuid::new_v4();
+
+        online.observe(user, a, 1.0, 0);
+        online.observe(user, b, 1.0, 0);
+        let once = online.similarity(a, b, 0);
+        online.observe(user, b, 1.0, 0);
+        assert_eq!(online.similarity(a, b, 0), once);
+        assert_eq!(online.events_applied(), 2);
+
+        // Upgrading like → share raises b's weight but keeps the pair cosine at 1
+        online.observe(user, b, 3.0, 0);
+        assert!((online.similarity(a, b, 0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_time_decay_favours_recent_pairs() {
+        let mut online = OnlineItemSimilarity::new(config());
+        let (a, old, fresh) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        // Old co-engagement two weeks ago, fresh co-engagement now
+        let (u1, u2) = (Uuid::new_v4(), Uuid::new_v4());
+        online.observe(u1, a, 1.0, 0);
+        online.observe(u1, old, 1.0, 0);
+        online.observe(u2, a, 1.0, 14 * DAY);
+        online.observe(u2, fresh, 1.0, 14 * DAY);
+
+        let now = 14 * DAY;
+        assert!(online.similarity(a, fresh, now) > online.similarity(a, old, now));
+
+        // The old pair decays below the prune threshold
+        assert_eq!(online.prune(now), 1);
+        assert_eq!(online.similarity(a, old, now), 0.0);
+        assert!(online.neighbors(a).iter().all(|(id, _)| *id != old));
+    }
+
+    #[test]
+    fn test_top_k_is_bounded_and_sorted() {
+        let mut online = OnlineItemSimilarity::new(config());
+        let anchor = Uuid::new_v4();
+        let others: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+
+        for (i, other) in others.iter().enumerate() {
+            let user = Uuid::new_v4();
+            online.observe(user, anchor, 1.0, 0);
+            online.observe(user, *other, 1.0 + i as f64, 0);
+        }
+
+        let neighbors = online.neighbors(anchor);
+        assert_eq!(neighbors.len(), 2);
+        assert!(neighbors[0].1 >= neighbors[1].1);
+    }
+
+    #[test]
+    fn test_merge_into_keeps_batch_neighbors() {
+        let mut online = OnlineItemSimilarity::new(config());
+        let (a, b, batch_only) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let user = Uuid::new_v4();
+
+        let mut base = CollaborativeFilteringModel::empty(10);
+        base.item_similarity.insert(a, vec![(batch_only, 0.4)]);
+
+        online.observe(user, a, 1.0, 0);
+        online.observe(user, b, 1.0, 0);
+        let merged = online.merge_into(&base);
+
+        let neighbors = merged.find_similar_posts(a, 10);
+        assert_eq!(neighbors[0].0, b);
+        assert!(neighbors.iter().any(|(id, _)| *id == batch_only));
+        assert_eq!(merged.get_user_liked_posts(user).unwrap().len(), 2);
+        // Base model is untouched
+        assert_eq!(base.find_similar_posts(a, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_merge_replaces_batch_scores_without_ratcheting() {
+        let mut online = OnlineItemSimilarity::new(config());
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (u1, u2) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let mut base = CollaborativeFilteringModel::empty(10);
+        base.item_similarity.insert(a, vec![(b, 0.95)]);
+
+        // Online cosine(a, b) drops to ~0.71 once `a` is also co-engaged with `c`
+        online.observe(u1, a, 1.0, 0);
+        online.observe(u1, b, 1.0, 0);
+        online.observe(u2, a, 1.0, 0);
+        online.observe(u2, c, 1.0, 0);
+        let online_ab = online.similarity(a, b, 0);
+        assert!(online_ab < 0.95);
+
+        let first = online.merge_into(&base);
+        let score = |model: &CollaborativeFilteringModel| {
+            model
+                .find_similar_posts(a, 10)
+                .into_iter()
+                .find(|(id, _)| *id == b)
+                .unwrap()
+                .1
+        };
+        assert!((score(&first) - online_ab).abs() < 1e-9);
+
+        // Re-merging onto the batch model is idempotent
+        let second = online.merge_into(&base);
+        assert!((score(&second) - score(&first)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_trainer_hot_swaps_and_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let handle = CfModelHandle::new(CollaborativeFilteringModel::empty(10));
+        let trainer = OnlineCfTrainer::new(
+            handle.clone(),
+            OnlineCfConfig {
+                snapshot_dir: Some(dir.path().to_path_buf()),
+                ..config()
+            },
+        );
+
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let user = Uuid::new_v4();
+        trainer.record(user, a, "like", None).await;
+        trainer.record(user, b, "comment", None).await;
+        assert!(handle.current().await.find_similar_posts(a, 5).is_empty());
+
+        trainer.publish().await.unwrap();
+        assert_eq!(handle.current().await.find_similar_posts(a, 5)[0].0, b);
+        // Online merges never leak into the batch base
+        assert!(handle.batch().await.find_similar_posts(a, 5).is_empty());
+
+        trainer.snapshot().await.unwrap();
+        let path = dir.path().to_str().unwrap();
+        let reloaded = CollaborativeFilteringModel::load(path, path, 10).unwrap();
+        assert_eq!(reloaded.find_similar_posts(b, 5)[0].0, a);
+    }
+}
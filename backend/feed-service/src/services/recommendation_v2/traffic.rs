@@ -0,0 +1,571 @@
+// ============================================
+// Experiment Traffic Allocation
+// ============================================
+//
+// Layered experiment infrastructure (Tang et al., "Overlapping Experiment
+// Infrastructure", KDD 2010):
+// - Layers: each layer hashes users independently, so experiments in
+//   different layers overlap orthogonally
+// - Mutual exclusion: experiments in the same layer own disjoint bucket ranges
+// - 10,000 buckets per hash (0.01% traffic granularity)
+// - Holdouts: global holdout groups and per-layer reserved buckets that never
+//   receive an experiment
+// - Targeting predicates over user attributes
+// - Ramp schedules: time-based share of the experiment's buckets that is live
+//
+// Eligibility Flow:
+//   Global holdouts → Layer bucket (holdout? owned range?) → Ramp → Targeting
+//                                                                     ↓
+//                                              ABTestingFramework::assign_bucket
+
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use uuid::Uuid;
+
+/// Buckets per hash space (1 bucket = 0.01% of traffic)
+pub const TOTAL_BUCKETS: u32 = 10_000;
+
+/// Hash a user into [0, TOTAL_BUCKETS) for the given salt (layer or experiment name)
+pub fn bucket_for(user_id: Uuid, salt: &str) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    user_id.hash(&mut hasher);
+    salt.hash(&mut hasher);
+    (hasher.finish() % TOTAL_BUCKETS as u64) as u32
+}
+
+/// Half-open bucket range [start, end)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl BucketRange {
+    pub fn new(start: u32, end: u32) -> Result<Self> {
+        if start >= end || end > TOTAL_BUCKETS {
+            return Err(AppError::BadRequest(format!(
+                "Invalid bucket range [{}, {}) (must satisfy start < end <= {})",
+                start, end, TOTAL_BUCKETS
+            )));
+        }
+        Ok(Self { start, end })
+    }
+
+    pub fn len(&self) -> u32 {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, bucket: u32) -> bool {
+        bucket >= self.start && bucket < self.end
+    }
+
+    pub fn overlaps(&self, other: &BucketRange) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// Experiment layer: experiments inside it are mutually exclusive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Layer {
+    pub id: Uuid,
+    pub name: String,
+    /// Buckets [0, holdout_buckets) of this layer never enter an experiment
+    pub holdout_buckets: u32,
+}
+
+/// Global holdout group: users hashed into it are excluded from every experiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holdout {
+    pub id: Uuid,
+    pub name: String,
+    /// Size of the holdout in buckets (out of TOTAL_BUCKETS)
+    pub buckets: u32,
+}
+
+impl Holdout {
+    pub fn contains(&self, user_id: Uuid) -> bool {
+        bucket_for(user_id, &format!("holdout:{}", self.name)) < self.buckets
+    }
+}
+
+/// One step of a ramp schedule
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RampStep {
+    pub starts_at: DateTime<Utc>,
+    /// Share of the experiment's buckets that is live, in basis points (10000 = 100%)
+    pub traffic_bps: u32,
+}
+
+/// User attributes available to targeting rules
+///
+/// Well-known keys: `country`, `platform`, `app_version`, `language`,
+/// `account_age_days`, `is_new_user`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserAttributes(HashMap<String, serde_json::Value>);
+
+impl UserAttributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.0.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.0.get(key)
+    }
+}
+
+/// Targeting predicate over user attributes (stored as JSONB)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TargetingRule {
+    /// Attribute equals value
+    Eq {
+        attribute: String,
+        value: serde_json::Value,
+    },
+    /// Attribute is one of values
+    In {
+        attribute: String,
+        values: Vec<serde_json::Value>,
+    },
+    /// Numeric attribute within [min, max] (either bound optional)
+    Range {
+        attribute: String,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// Attribute is present
+    Exists {
+        attribute: String,
+    },
+    All {
+        rules: Vec<TargetingRule>,
+    },
+    Any {
+        rules: Vec<TargetingRule>,
+    },
+    Not {
+        rule: Box<TargetingRule>,
+    },
+}
+
+impl TargetingRule {
+    /// Missing attributes never match (so `Not` of a missing attribute does)
+    pub fn matches(&self, attrs: &UserAttributes) -> bool {
+        match self {
+            Self::Eq { attribute, value } => attrs.get(attribute) == Some(value),
+            Self::In { attribute, values } => attrs
+                .get(attribute)
+                .is_some_and(|v| values.iter().any(|candidate| candidate == v)),
+            Self::Range {
+                attribute,
+                min,
+                max,
+            } => attrs
+                .get(attribute)
+                .and_then(serde_json::Value::as_f64)
+                .is_some_and(|v| min.map_or(true, |m| v >= m) && max.map_or(true, |m| v <= m)),
+            Self::Exists { attribute } => attrs.get(attribute).is_some(),
+            Self::All { rules } => rules.iter().all(|r| r.matches(attrs)),
+            Self::Any { rules } => rules.iter().any(|r| r.matches(attrs)),
+            Self::Not { rule } => !rule.matches(attrs),
+        }
+    }
+}
+
+/// Traffic settings for one experiment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrafficConfig {
+    /// Layer the experiment belongs to; `None` gives it a private layer
+    /// (salted by experiment name), i.e. the legacy independent hashing
+    pub layer: Option<String>,
+    /// Layer buckets owned by the experiment; empty means the whole layer
+    pub bucket_ranges: Vec<BucketRange>,
+    pub targeting: Option<TargetingRule>,
+    /// Ramp steps; empty means 100% of owned buckets from the start
+    pub ramp: Vec<RampStep>,
+}
+
+/// Why a user is (not) in an experiment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Eligibility {
+    Eligible,
+    GlobalHoldout(String),
+    LayerHoldout,
+    OutsideTraffic,
+    NotRampedIn,
+    TargetingMismatch,
+}
+
+impl TrafficConfig {
+    /// Validate ranges (well-formed and non-overlapping) and ramp steps
+    pub fn validate(&self) -> Result<()> {
+        for (i, range) in self.bucket_ranges.iter().enumerate() {
+            BucketRange::new(range.start, range.end)?;
+            if self.bucket_ranges[i + 1..]
+                .iter()
+                .any(|r| r.overlaps(range))
+            {
+                return Err(AppError::BadRequest(
+                    "Experiment bucket ranges overlap".to_string(),
+                ));
+            }
+        }
+        if !self.bucket_ranges.is_empty() && self.layer.is_none() {
+            return Err(AppError::BadRequest(
+                "Bucket ranges require a layer".to_string(),
+            ));
+        }
+        if self.ramp.iter().any(|s| s.traffic_bps > TOTAL_BUCKETS) {
+            return Err(AppError::BadRequest(format!(
+                "Ramp traffic must be at most {} bps",
+                TOTAL_BUCKETS
+            )));
+        }
+        if self
+            .ramp
+            .windows(2)
+            .any(|w| w[0].starts_at >= w[1].starts_at)
+        {
+            return Err(AppError::BadRequest(
+                "Ramp steps must be strictly ordered by start time".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether this experiment's owned buckets intersect `other`'s in the same layer
+    pub fn conflicts_with(&self, other: &TrafficConfig) -> bool {
+        if self.layer.is_none() || self.layer != other.layer {
+            return false;
+        }
+        let whole = [BucketRange {
+            start: 0,
+            end: TOTAL_BUCKETS,
+        }];
+        let mine = if self.bucket_ranges.is_empty() {
+            &whole[..]
+        } else {
+            &self.bucket_ranges[..]
+        };
+        let theirs = if other.bucket_ranges.is_empty() {
+            &whole[..]
+        } else {
+            &other.bucket_ranges[..]
+        };
+        mine.iter().any(|a| theirs.iter().any(|b| a.overlaps(b)))
+    }
+
+    /// Live traffic share at `now` in basis points
+    pub fn ramp_bps(&self, now: DateTime<Utc>) -> u32 {
+        if self.ramp.is_empty() {
+            return TOTAL_BUCKETS;
+        }
+        self.ramp
+            .iter()
+            .rev()
+            .find(|s| s.starts_at <= now)
+            .map(|s| s.traffic_bps)
+            .unwrap_or(0)
+    }
+
+    /// Decide whether `user_id` enters the experiment
+    ///
+    /// Ramping admits the lowest positions of the owned buckets first, so a
+    /// user admitted at 5% stays in as traffic grows.
+    pub fn eligibility(
+        &self,
+        user_id: Uuid,
+        experiment_name: &str,
+        attrs: &UserAttributes,
+        layers: &HashMap<String, Layer>,
+        holdouts: &[Holdout],
+        now: DateTime<Utc>,
+    ) -> Eligibility {
+        if let Some(holdout) = holdouts.iter().find(|h| h.contains(user_id)) {
+            return Eligibility::GlobalHoldout(holdout.name.clone());
+        }
+
+        let salt = self.layer.as_deref().unwrap_or(experiment_name);
+        let bucket = bucket_for(user_id, salt);
+
+        let holdout_buckets = self
+            .layer
+            .as_ref()
+            .and_then(|name| layers.get(name))
+            .map(|layer| layer.holdout_buckets)
+            .unwrap_or(0);
+        if bucket < holdout_buckets {
+            return Eligibility::LayerHoldout;
+        }
+
+        // Position of the bucket within the experiment's owned traffic
+        let (position, owned) = if self.bucket_ranges.is_empty() {
+            (bucket - holdout_buckets, TOTAL_BUCKETS - holdout_buckets)
+        } else {
+            let mut offset = 0;
+            let mut position = None;
+            for range in &self.bucket_ranges {
+                if range.contains(bucket) {
+                    position = Some(offset + bucket - range.start);
+                }
+                offset += range.len();
+            }
+            match position {
+                Some(p) => (p, offset),
+                None => return Eligibility::OutsideTraffic,
+            }
+        };
+
+        let live = owned as u64 * self.ramp_bps(now) as u64 / TOTAL_BUCKETS as u64;
+        if position as u64 >= live {
+            return Eligibility::NotRampedIn;
+        }
+
+        if let Some(rule) = &self.targeting {
+            if !rule.matches(attrs) {
+                return Eligibility::TargetingMismatch;
+            }
+        }
+
+        Eligibility::Eligible
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn layer(name: &str, holdout_buckets: u32) -> HashMap<String, Layer> {
+        let mut layers = HashMap::new();
+        layers.insert(
+            name.to_string(),
+            Layer {
+                id: Uuid::new_v4(),
+                name: name.to_string(),
+                holdout_buckets,
+            },
+        );
+        layers
+    }
+
+    fn in_layer(name: &str, start: u32, end: u32) -> TrafficConfig {
+        TrafficConfig {
+            layer: Some(name.to_string()),
+            bucket_ranges: vec![BucketRange::new(start, end).unwrap()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bucket_range_validation() {
+        assert!(BucketRange::new(0, TOTAL_BUCKETS).is_ok());
+        assert!(BucketRange::new(10, 10).is_err());
+        assert!(BucketRange::new(0, TOTAL_BUCKETS + 1).is_err());
+        let a = BucketRange::new(0, 100).unwrap();
+        assert!(a.overlaps(&BucketRange::new(99, 200).unwrap()));
+        assert!(!a.overlaps(&BucketRange::new(100, 200).unwrap()));
+    }
+
+    #[test]
+    fn test_mutual_exclusion_within_layer() {
+        let layers = layer("ranking", 0);
+        let exp_a = in_layer("ranking", 0, 5_000);
+        let exp_b = in_layer("ranking", 5_000, TOTAL_BUCKETS);
+        assert!(!exp_a.conflicts_with(&exp_b));
+        assert!(exp_a.conflicts_with(&in_layer("ranking", 4_000, 6_000)));
+        assert!(!exp_a.conflicts_with(&in_layer("ui", 0, 5_000)));
+
+        let now = Utc::now();
+        let attrs = UserAttributes::new();
+        for _ in 0..2_000 {
+            let user = Uuid::new_v4();
+            let in_a =
+                exp_a.eligibility(user, "a", &attrs, &layers, &[], now) == Eligibility::Eligible;
+            let in_b =
+                exp_b.eligibility(user, "b", &attrs, &layers, &[], now) == Eligibility::Eligible;
+            // Every user lands in exactly one of the two experiments
+            assert!(in_a ^ in_b);
+        }
+    }
+
+    #[test]
+    fn test_layers_are_orthogonal() {
+        let layers: HashMap<String, Layer> = layer("ranking", 0)
+            .into_iter()
+            .chain(layer("ui", 0))
+            .collect();
+        let ranking = in_layer("ranking", 0, 5_000);
+        let ui = in_layer("ui", 0, 5_000);
+
+        let now = Utc::now();
+        let attrs = UserAttributes::new();
+        let mut both = 0;
+        for _ in 0..4_000 {
+            let user = Uuid::new_v4();
+            if ranking.eligibility(user, "r", &attrs, &layers, &[], now) == Eligibility::Eligible
+                && ui.eligibility(user, "u", &attrs, &layers, &[], now) == Eligibility::Eligible
+            {
+                both += 1;
+            }
+        }
+        // Independent 50% × 50% ≈ 25%
+        assert!(both > 800 && both < 1_200, "overlap: {}", both);
+    }
+
+    #[test]
+    fn test_layer_and_global_holdouts() {
+        let layers = layer("ranking", TOTAL_BUCKETS);
+        let config = TrafficConfig {
+            layer: Some("ranking".to_string()),
+            ..Default::default()
+        };
+        let now = Utc::now();
+        let attrs = UserAttributes::new();
+        let user = Uuid::new_v4();
+        assert_eq!(
+            config.eligibility(user, "e", &attrs, &layers, &[], now),
+            Eligibility::LayerHoldout
+        );
+
+        let holdout = Holdout {
+            id: Uuid::new_v4(),
+            name: "q4_global".to_string(),
+            buckets: TOTAL_BUCKETS,
+        };
+        assert_eq!(
+            TrafficConfig::default().eligibility(user, "e", &attrs, &layers, &[holdout], now),
+            Eligibility::GlobalHoldout("q4_global".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ramp_schedule() {
+        let now = Utc::now();
+        let config = TrafficConfig {
+            ramp: vec![
+                RampStep {
+                    starts_at: now - Duration::days(2),
+                    traffic_bps: 100,
+                },
+                RampStep {
+                    starts_at: now - Duration::days(1),
+                    traffic_bps: 2_000,
+                },
+                RampStep {
+                    starts_at: now + Duration::days(1),
+                    traffic_bps: TOTAL_BUCKETS,
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.ramp_bps(now - Duration::days(3)), 0);
+        assert_eq!(config.ramp_bps(now), 2_000);
+        assert_eq!(config.ramp_bps(now + Duration::days(2)), TOTAL_BUCKETS);
+
+        let layers = HashMap::new();
+        let attrs = UserAttributes::new();
+        let mut admitted_early = Vec::new();
+        let mut admitted = 0;
+        for _ in 0..5_000 {
+            let user = Uuid::new_v4();
+            if config.eligibility(user, "ramped", &attrs, &layers, &[], now)
+                == Eligibility::Eligible
+            {
+                admitted += 1;
+            }
+            if config.eligibility(
+                user,
+                "ramped",
+                &attrs,
+                &layers,
+                &[],
+                now - Duration::hours(36),
+            ) == Eligibility::Eligible
+            {
+                admitted_early.push(user);
+            }
+        }
+        assert!(admitted > 800 && admitted < 1_200, "admitted: {}", admitted);
+        // Users admitted at 1% stay in at 20%
+        for user in admitted_early {
+            assert_eq!(
+                config.eligibility(user, "ramped", &attrs, &layers, &[], now),
+                Eligibility::Eligible
+            );
+        }
+    }
+
+    #[test]
+    fn test_targeting_rules() {
+        let rule = TargetingRule::All {
+            rules: vec![
+                TargetingRule::In {
+                    attribute: "country".to_string(),
+                    values: vec!["TW".into(), "JP".into()],
+                },
+                TargetingRule::Range {
+                    attribute: "account_age_days".to_string(),
+                    min: None,
+                    max: Some(7.0),
+                },
+                TargetingRule::Not {
+                    rule: Box::new(TargetingRule::Eq {
+                        attribute: "platform".to_string(),
+                        value: "web".into(),
+                    }),
+                },
+            ],
+        };
+
+        let new_tw_user = UserAttributes::new()
+            .with("country", "TW")
+            .with("account_age_days", 3)
+            .with("platform", "ios");
+        assert!(rule.matches(&new_tw_user));
+        assert!(!rule.matches(&new_tw_user.clone().with("account_age_days", 30)));
+        assert!(!rule.matches(&new_tw_user.clone().with("platform", "web")));
+        assert!(!rule.matches(&UserAttributes::new().with("account_age_days", 1)));
+
+        let json = serde_json::to_value(&rule).unwrap();
+        assert_eq!(json["op"], "all");
+        let parsed: TargetingRule = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, rule);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_config() {
+        let overlapping = TrafficConfig {
+            layer: Some("ranking".to_string()),
+            bucket_ranges: vec![
+                BucketRange { start: 0, end: 100 },
+                BucketRange {
+                    start: 50,
+                    end: 150,
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(overlapping.validate().is_err());
+
+        let no_layer = TrafficConfig {
+            bucket_ranges: vec![BucketRange { start: 0, end: 100 }],
+            ..Default::default()
+        };
+        assert!(no_layer.validate().is_err());
+    }
+}
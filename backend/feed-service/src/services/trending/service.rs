@@ -226,11 +226,13 @@ impl TrendingService {
                     }
                 }
                 "post" => {
-                    if let Some((content, creator_id)) = post_meta.get(&item.content_id) {
-                        meta.title = Some(content.clone());
-                        meta.creator_id = Some(creator_id.clone());
-                        // creator_username intentionally omitted to avoid extra calls
-                    }
+                    // Deleted and audience-list-limited posts are not returned
+                    let Some((content, creator_id)) = post_meta.get(&item.content_id) else {
+                        continue;
+                    };
+                    meta.title = Some(content.clone());
+                    meta.creator_id = Some(creator_id.clone());
+                    // creator_username intentionally omitted to avoid extra calls
                 }
                 "stream" => {
                     if let Ok(Some((title, creator_id, username, thumbnail))) =
@@ -259,8 +261,11 @@ impl TrendingService {
             return Ok(std::collections::HashMap::new());
         }
 
+        // Trending is shared by every viewer: fetch as anonymous so content-service
+        // withholds posts limited to an audience list
         let request = GetPostsByIdsRequest {
             post_ids: post_ids.iter().map(|id| id.to_string()).collect(),
+            viewer_id: String::new(),
         };
 
         let mut client = self.content_client.clone();
@@ -0,0 +1,339 @@
+/// Vector storage backends
+///
+/// `VectorSearchService` talks to a `VectorBackend`, so the in-process HNSW
+/// index and an external Milvus deployment are interchangeable.
+use super::hnsw::{DistanceMetric, HnswIndex, HnswParams, SearchFilter};
+use super::{PostEmbedding, VectorSearchResult};
+use crate::error::{AppError, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Storage/search backend for post embeddings
+#[async_trait]
+pub trait VectorBackend: Send + Sync {
+    /// Short backend name for logs and metrics
+    fn name(&self) -> &'static str;
+
+    /// Prepare storage (create collection, load snapshot, ...)
+    async fn initialize(&self) -> Result<()>;
+
+    /// Insert or replace embeddings; returns number written
+    async fn upsert(&self, embeddings: Vec<PostEmbedding>) -> Result<usize>;
+
+    /// Delete an embedding; returns whether it existed
+    async fn delete(&self, post_id: Uuid) -> Result<bool>;
+
+    /// Fetch the stored embedding for a post
+    async fn get_embedding(&self, post_id: Uuid) -> Result<Option<Vec<f32>>>;
+
+    /// Top-`limit` neighbours above `min_similarity`, most similar first
+    async fn search(
+        &self,
+        query: &[f32],
+        limit: usize,
+        min_similarity: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<VectorSearchResult>>;
+
+    /// Number of stored embeddings
+    async fn count(&self) -> usize;
+
+    /// Persist state to durable storage (no-op for backends that persist themselves)
+    async fn snapshot(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<()>;
+}
+
+/// Configuration for the embedded HNSW backend
+#[derive(Debug, Clone)]
+pub struct EmbeddedIndexConfig {
+    pub metric: DistanceMetric,
+    pub params: HnswParams,
+    /// Where to persist snapshots; `None` keeps the index memory-only
+    pub snapshot_path: Option<PathBuf>,
+}
+
+impl Default for EmbeddedIndexConfig {
+    fn default() -> Self {
+        Self {
+            metric: DistanceMetric::Cosine,
+            params: HnswParams::default(),
+            snapshot_path: None,
+        }
+    }
+}
+
+/// In-process HNSW backend with optional snapshot-to-disk
+pub struct EmbeddedBackend {
+    index: Arc<RwLock<HnswIndex>>,
+    config: EmbeddedIndexConfig,
+}
+
+impl EmbeddedBackend {
+    pub fn new(dim: usize, config: EmbeddedIndexConfig) -> Self {
+        Self {
+            index: Arc::new(RwLock::new(HnswIndex::new(
+                dim,
+                config.metric,
+                config.params,
+            ))),
+            config,
+        }
+    }
+
+    /// Reload the index from the configured snapshot, if one exists
+    ///
+    /// Returns the number of embeddings loaded.
+    pub async fn load_snapshot(&self) -> Result<usize> {
+        let Some(path) = self.config.snapshot_path.clone() else {
+            return Ok(0);
+        };
+        if !path.exists() {
+            debug!("No HNSW snapshot at {}, starting empty", path.display());
+            return Ok(0);
+        }
+
+        let loaded = tokio::task::spawn_blocking(move || HnswIndex::load(&path))
+            .await
+            .map_err(|e| AppError::Internal(format!("Snapshot load task failed: {}", e)))??;
+
+        let expected_dim = self.read()?.dim();
+        if loaded.dim() != expected_dim || loaded.metric() != self.config.metric {
+            return Err(AppError::Internal(format!(
+                "HNSW snapshot mismatch: dim {} / {:?}, expected dim {} / {:?}",
+                loaded.dim(),
+                loaded.metric(),
+                expected_dim,
+                self.config.metric
+            )));
+        }
+
+        let count = loaded.len();
+        *self.write()? = loaded;
+        info!("Loaded HNSW snapshot with {} embeddings", count);
+        Ok(count)
+    }
+
+    /// Snapshot the index every `interval` until the task is aborted
+    pub fn spawn_snapshot_task(
+        self: &Arc<Self>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let backend = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = backend.snapshot().await {
+                    warn!("Periodic HNSW snapshot failed: {:?}", e);
+                }
+            }
+        })
+    }
+
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, HnswIndex>> {
+        self.index
+            .read()
+            .map_err(|_| AppError::Internal("HNSW index lock poisoned".to_string()))
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, HnswIndex>> {
+        self.index
+            .write()
+            .map_err(|_| AppError::Internal("HNSW index lock poisoned".to_string()))
+    }
+}
+
+#[async_trait]
+impl VectorBackend for EmbeddedBackend {
+    fn name(&self) -> &'static str {
+        "embedded-hnsw"
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        self.load_snapshot().await.map(|_| ())
+    }
+
+    async fn upsert(&self, embeddings: Vec<PostEmbedding>) -> Result<usize> {
+        // Graph inserts (and any compaction they trigger) are CPU-bound
+        let index = Arc::clone(&self.index);
+        tokio::task::spawn_blocking(move || {
+            let mut index = index
+                .write()
+                .map_err(|_| AppError::Internal("HNSW index lock poisoned".to_string()))?;
+            for embedding in &embeddings {
+                index.upsert(
+                    embedding.post_id,
+                    embedding.author_id,
+                    embedding.created_at,
+                    &embedding.embedding,
+                )?;
+            }
+            Ok(embeddings.len())
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("HNSW upsert task failed: {}", e)))?
+    }
+
+    async fn delete(&self, post_id: Uuid) -> Result<bool> {
+        Ok(self.write()?.remove(&post_id))
+    }
+
+    async fn get_embedding(&self, post_id: Uuid) -> Result<Option<Vec<f32>>> {
+        Ok(self.read()?.get(&post_id).map(<[f32]>::to_vec))
+    }
+
+    async fn search(
+        &self,
+        query: &[f32],
+        limit: usize,
+        min_similarity: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<VectorSearchResult>> {
+        let index = self.read()?;
+        let metric = index.metric();
+        let results = index
+            .search(query, limit, filter)?
+            .into_iter()
+            .map(|n| VectorSearchResult {
+                post_id: n.post_id,
+                similarity_score: metric.to_similarity(n.distance),
+                distance: n.distance,
+                author_id: n.author_id,
+            })
+            .filter(|r| r.similarity_score >= min_similarity)
+            .collect();
+        Ok(results)
+    }
+
+    async fn count(&self) -> usize {
+        self.read().map(|index| index.len()).unwrap_or(0)
+    }
+
+    async fn snapshot(&self) -> Result<()> {
+        let Some(path) = self.config.snapshot_path.clone() else {
+            return Ok(());
+        };
+
+        // Clone under the read lock so writers are only blocked for the copy,
+        // not for serialization and disk I/O; all of it runs off the runtime.
+        let index = Arc::clone(&self.index);
+        let count = tokio::task::spawn_blocking(move || {
+            let index = index
+                .read()
+                .map_err(|_| AppError::Internal("HNSW index lock poisoned".to_string()))?
+                .clone();
+            index.save(&path)?;
+            Ok::<_, AppError>(index.len())
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Snapshot task failed: {}", e)))??;
+
+        debug!("HNSW snapshot written with {} embeddings", count);
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.read().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedding(post_id: Uuid, author_id: Uuid, created_at: i64, v: Vec<f32>) -> PostEmbedding {
+        PostEmbedding {
+            post_id,
+            embedding: v,
+            title: String::new(),
+            description: None,
+            author_id,
+            created_at,
+            engagement_score: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = EmbeddedIndexConfig {
+            snapshot_path: Some(dir.path().join("posts.hnsw")),
+            ..Default::default()
+        };
+
+        let post_id = Uuid::new_v4();
+        let backend = EmbeddedBackend::new(3, config.clone());
+        backend
+            .upsert(vec![embedding(
+                post_id,
+                Uuid::nil(),
+                0,
+                vec![1.0, 0.0, 0.0],
+            )])
+            .await
+            .unwrap();
+        backend.snapshot().await.unwrap();
+
+        let restored = EmbeddedBackend::new(3, config);
+        restored.initialize().await.unwrap();
+        assert_eq!(restored.count().await, 1);
+
+        let hits = restored
+            .search(&[1.0, 0.1, 0.0], 5, 0.5, &SearchFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(hits[0].post_id, post_id);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_dimension_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = EmbeddedIndexConfig {
+            snapshot_path: Some(dir.path().join("posts.hnsw")),
+            ..Default::default()
+        };
+        let backend = EmbeddedBackend::new(3, config.clone());
+        backend
+            .upsert(vec![embedding(
+                Uuid::new_v4(),
+                Uuid::nil(),
+                0,
+                vec![1.0, 0.0, 0.0],
+            )])
+            .await
+            .unwrap();
+        backend.snapshot().await.unwrap();
+
+        let other = EmbeddedBackend::new(4, config);
+        assert!(other.initialize().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_min_similarity_threshold() {
+        let backend = EmbeddedBackend::new(2, EmbeddedIndexConfig::default());
+        let close = Uuid::new_v4();
+        let far = Uuid::new_v4();
+        backend
+            .upsert(vec![
+                embedding(close, Uuid::nil(), 0, vec![1.0, 0.05]),
+                embedding(far, Uuid::nil(), 0, vec![-1.0, 0.0]),
+            ])
+            .await
+            .unwrap();
+
+        let hits = backend
+            .search(&[1.0, 0.0], 10, 0.5, &SearchFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].post_id, close);
+    }
+}
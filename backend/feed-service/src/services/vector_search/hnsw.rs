@@ -0,0 +1,796 @@
+/// In-process HNSW (Hierarchical Navigable Small World) index
+///
+/// Approximate nearest-neighbour search over post embeddings without an
+/// external vector database. Based on Malkov & Yashunin (2016):
+/// - Multi-layer proximity graph with exponentially decaying layer occupancy
+/// - Greedy descent through upper layers, beam search (`ef`) on layer 0
+/// - Heuristic neighbour selection to keep the graph navigable
+///
+/// Deletes are tombstones: the node stays in the graph for navigation but is
+/// never returned. `compact()` rebuilds the graph from live nodes once the
+/// tombstone ratio grows too large.
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Snapshot format version, bumped on incompatible layout changes
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Rebuild the graph once tombstones exceed this fraction of all nodes
+const COMPACT_TOMBSTONE_RATIO: f32 = 0.3;
+
+/// Do not bother compacting tiny indices
+const COMPACT_MIN_NODES: usize = 1024;
+
+/// Distance metric used for similarity search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum DistanceMetric {
+    /// Cosine similarity (vectors are L2-normalised on insert)
+    #[default]
+    Cosine,
+    /// Raw inner product (vectors stored as given)
+    InnerProduct,
+}
+
+impl DistanceMetric {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "cosine" | "cos" => Some(Self::Cosine),
+            "ip" | "inner_product" | "dot" => Some(Self::InnerProduct),
+            _ => None,
+        }
+    }
+
+    /// Similarity → distance (smaller is closer)
+    fn to_distance(self, similarity: f32) -> f32 {
+        match self {
+            Self::Cosine => 1.0 - similarity,
+            Self::InnerProduct => -similarity,
+        }
+    }
+
+    /// Distance → similarity (larger is closer)
+    pub fn to_similarity(self, distance: f32) -> f32 {
+        match self {
+            Self::Cosine => 1.0 - distance,
+            Self::InnerProduct => -distance,
+        }
+    }
+}
+
+/// HNSW build/search parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HnswParams {
+    /// Max neighbours per node on layers > 0 (layer 0 uses 2 * m)
+    pub m: usize,
+    /// Beam width while inserting
+    pub ef_construction: usize,
+    /// Default beam width while searching (raised to `k` if smaller)
+    pub ef_search: usize,
+    /// Seed for the level generator (deterministic builds)
+    pub seed: u64,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+            seed: 0x5eed_1e55_c0ff_ee00,
+        }
+    }
+}
+
+/// Metadata filter applied to search results
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// Only return posts from these authors
+    pub author_ids: Option<HashSet<Uuid>>,
+    /// Never return posts from these authors
+    pub exclude_author_ids: HashSet<Uuid>,
+    /// Never return these posts (e.g. the query post itself)
+    pub exclude_post_ids: HashSet<Uuid>,
+    /// Inclusive lower bound on `created_at` (unix seconds)
+    pub created_after: Option<i64>,
+    /// Inclusive upper bound on `created_at` (unix seconds)
+    pub created_before: Option<i64>,
+}
+
+impl SearchFilter {
+    pub fn is_empty(&self) -> bool {
+        self.author_ids.is_none()
+            && self.exclude_author_ids.is_empty()
+            && self.exclude_post_ids.is_empty()
+            && self.created_after.is_none()
+            && self.created_before.is_none()
+    }
+
+    pub fn matches(&self, post_id: Uuid, author_id: Uuid, created_at: i64) -> bool {
+        if let Some(ref authors) = self.author_ids {
+            if !authors.contains(&author_id) {
+                return false;
+            }
+        }
+        if self.exclude_author_ids.contains(&author_id) || self.exclude_post_ids.contains(&post_id)
+        {
+            return false;
+        }
+        if self.created_after.is_some_and(|after| created_at < after) {
+            return false;
+        }
+        if self
+            .created_before
+            .is_some_and(|before| created_at > before)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// A single search hit
+#[derive(Debug, Clone, Copy)]
+pub struct Neighbor {
+    pub post_id: Uuid,
+    pub author_id: Uuid,
+    pub distance: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Node {
+    post_id: Uuid,
+    author_id: Uuid,
+    created_at: i64,
+    vector: Vec<f32>,
+    /// neighbours[layer] = adjacent node ids on that layer
+    neighbors: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+impl Node {
+    fn level(&self) -> usize {
+        self.neighbors.len() - 1
+    }
+}
+
+/// Candidate ordered by distance (max-heap by default)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f32,
+    id: u32,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// HNSW approximate nearest-neighbour index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswIndex {
+    version: u32,
+    dim: usize,
+    metric: DistanceMetric,
+    params: HnswParams,
+    nodes: Vec<Node>,
+    /// post_id → live node id
+    ids: HashMap<Uuid, u32>,
+    entry_point: Option<u32>,
+    max_level: usize,
+    rng_state: u64,
+}
+
+impl HnswIndex {
+    pub fn new(dim: usize, metric: DistanceMetric, params: HnswParams) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            dim,
+            metric,
+            params,
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            entry_point: None,
+            max_level: 0,
+            // xorshift must never be seeded with zero
+            rng_state: params.seed.max(1),
+        }
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    /// Number of live (non-deleted) vectors
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Number of tombstoned nodes still held in the graph
+    pub fn tombstones(&self) -> usize {
+        self.nodes.len() - self.ids.len()
+    }
+
+    pub fn contains(&self, post_id: &Uuid) -> bool {
+        self.ids.contains_key(post_id)
+    }
+
+    /// Stored vector for a post (normalised when the metric is cosine)
+    pub fn get(&self, post_id: &Uuid) -> Option<&[f32]> {
+        self.ids
+            .get(post_id)
+            .map(|&id| self.nodes[id as usize].vector.as_slice())
+    }
+
+    /// Insert or replace the vector for `post_id`
+    pub fn upsert(
+        &mut self,
+        post_id: Uuid,
+        author_id: Uuid,
+        created_at: i64,
+        vector: &[f32],
+    ) -> Result<()> {
+        self.check_dim(vector.len())?;
+        let vector = self.prepare(vector)?;
+
+        if let Some(old) = self.ids.remove(&post_id) {
+            self.nodes[old as usize].deleted = true;
+        }
+
+        let id = self.insert_node(Node {
+            post_id,
+            author_id,
+            created_at,
+            vector,
+            neighbors: Vec::new(),
+            deleted: false,
+        });
+        self.ids.insert(post_id, id);
+        self.maybe_compact();
+        Ok(())
+    }
+
+    /// Tombstone a post; returns whether it was present
+    pub fn remove(&mut self, post_id: &Uuid) -> bool {
+        match self.ids.remove(post_id) {
+            Some(id) => {
+                self.nodes[id as usize].deleted = true;
+                self.maybe_compact();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// k nearest live neighbours of `query` that pass `filter`, closest first
+    pub fn search(&self, query: &[f32], k: usize, filter: &SearchFilter) -> Result<Vec<Neighbor>> {
+        self.check_dim(query.len())?;
+        let query = self.prepare(query)?;
+        let Some(entry) = self.entry_point else {
+            return Ok(Vec::new());
+        };
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut ep = entry;
+        for layer in (1..=self.max_level).rev() {
+            ep = self.greedy_closest(&query, ep, layer);
+        }
+
+        // Widen the beam when a restrictive filter leaves too few hits
+        let mut ef = self.params.ef_search.max(k);
+        loop {
+            let hits: Vec<Neighbor> = self
+                .search_layer(&query, &[ep], ef, 0)
+                .into_iter()
+                .filter_map(|c| {
+                    let node = &self.nodes[c.id as usize];
+                    (!node.deleted && filter.matches(node.post_id, node.author_id, node.created_at))
+                        .then_some(Neighbor {
+                            post_id: node.post_id,
+                            author_id: node.author_id,
+                            distance: c.distance,
+                        })
+                })
+                .take(k)
+                .collect();
+
+            if hits.len() >= k || ef >= self.nodes.len() {
+                return Ok(hits);
+            }
+            ef = (ef * 4).min(self.nodes.len());
+        }
+    }
+
+    /// Rebuild the graph from live nodes, dropping tombstones
+    pub fn compact(&mut self) {
+        let live: Vec<Node> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .filter(|n| !n.deleted)
+            .collect();
+
+        self.ids.clear();
+        self.entry_point = None;
+        self.max_level = 0;
+
+        for mut node in live {
+            node.neighbors.clear();
+            let post_id = node.post_id;
+            let id = self.insert_node(node);
+            self.ids.insert(post_id, id);
+        }
+    }
+
+    /// Write a snapshot atomically (temp file + rename)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = bincode::serialize(self)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize HNSW index: {}", e)))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        {
+            let mut file = fs::File::create(&tmp)?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Load a snapshot written by `save`
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        let index: Self = bincode::deserialize(&bytes)
+            .map_err(|e| AppError::Internal(format!("Failed to deserialize HNSW index: {}", e)))?;
+
+        if index.version != SNAPSHOT_VERSION {
+            return Err(AppError::Internal(format!(
+                "Unsupported HNSW snapshot version {} (expected {})",
+                index.version, SNAPSHOT_VERSION
+            )));
+        }
+        Ok(index)
+    }
+
+    fn check_dim(&self, len: usize) -> Result<()> {
+        if len != self.dim {
+            return Err(AppError::BadRequest(format!(
+                "Embedding dimension {} does not match expected dimension {}",
+                len, self.dim
+            )));
+        }
+        Ok(())
+    }
+
+    fn prepare(&self, vector: &[f32]) -> Result<Vec<f32>> {
+        if vector.iter().any(|v| !v.is_finite()) {
+            return Err(AppError::BadRequest(
+                "Embedding contains non-finite values".to_string(),
+            ));
+        }
+        match self.metric {
+            DistanceMetric::InnerProduct => Ok(vector.to_vec()),
+            DistanceMetric::Cosine => {
+                let norm = dot(vector, vector).sqrt();
+                if norm == 0.0 {
+                    return Err(AppError::BadRequest(
+                        "Cannot index zero vector with cosine metric".to_string(),
+                    ));
+                }
+                Ok(vector.iter().map(|v| v / norm).collect())
+            }
+        }
+    }
+
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.metric.to_distance(dot(a, b))
+    }
+
+    fn node_distance(&self, query: &[f32], id: u32) -> f32 {
+        self.distance(query, &self.nodes[id as usize].vector)
+    }
+
+    fn max_neighbors(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.params.m * 2
+        } else {
+            self.params.m
+        }
+    }
+
+    fn random_level(&mut self) -> usize {
+        // xorshift64*
+        let mut x = self.rng_state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.rng_state = x;
+        let r = x.wrapping_mul(0x2545_f491_4f6c_dd1d);
+
+        let uniform = ((r >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+        let ml = 1.0 / (self.params.m.max(2) as f64).ln();
+        (-uniform.ln() * ml).floor() as usize
+    }
+
+    fn insert_node(&mut self, mut node: Node) -> u32 {
+        let level = self.random_level();
+        let id = self.nodes.len() as u32;
+        node.neighbors = vec![Vec::new(); level + 1];
+        let query = node.vector.clone();
+        self.nodes.push(node);
+
+        let Some(entry) = self.entry_point else {
+            self.entry_point = Some(id);
+            self.max_level = level;
+            return id;
+        };
+
+        let mut ep = entry;
+        for layer in (level + 1..=self.max_level).rev() {
+            ep = self.greedy_closest(&query, ep, layer);
+        }
+
+        let mut entry_points = vec![ep];
+        for layer in (0..=level.min(self.max_level)).rev() {
+            let candidates =
+                self.search_layer(&query, &entry_points, self.params.ef_construction, layer);
+            let selected = self.select_neighbors(&candidates, self.max_neighbors(layer));
+            self.nodes[id as usize].neighbors[layer] = selected.clone();
+
+            for neighbor in selected {
+                self.connect(neighbor, id, layer);
+            }
+            entry_points = candidates.iter().map(|c| c.id).collect();
+        }
+
+        if level > self.max_level {
+            self.max_level = level;
+            self.entry_point = Some(id);
+        }
+        id
+    }
+
+    /// Add `new_id` to `node`'s adjacency on `layer`, pruning if over capacity
+    fn connect(&mut self, node: u32, new_id: u32, layer: usize) {
+        let cap = self.max_neighbors(layer);
+        let links = &mut self.nodes[node as usize].neighbors[layer];
+        links.push(new_id);
+        if links.len() <= cap {
+            return;
+        }
+
+        let base = self.nodes[node as usize].vector.clone();
+        let mut candidates: Vec<Candidate> = self.nodes[node as usize].neighbors[layer]
+            .iter()
+            .map(|&n| Candidate {
+                distance: self.node_distance(&base, n),
+                id: n,
+            })
+            .collect();
+        candidates.sort();
+        self.nodes[node as usize].neighbors[layer] = self.select_neighbors(&candidates, cap);
+    }
+
+    /// Neighbour selection heuristic (paper Algorithm 4, keeping pruned links)
+    ///
+    /// `candidates` must be sorted closest first.
+    fn select_neighbors(&self, candidates: &[Candidate], m: usize) -> Vec<u32> {
+        let mut selected: Vec<u32> = Vec::with_capacity(m);
+        let mut pruned: Vec<u32> = Vec::new();
+
+        for c in candidates {
+            if selected.len() >= m {
+                break;
+            }
+            let vector = &self.nodes[c.id as usize].vector;
+            let diverse = selected
+                .iter()
+                .all(|&s| self.node_distance(vector, s) > c.distance);
+            if diverse {
+                selected.push(c.id);
+            } else {
+                pruned.push(c.id);
+            }
+        }
+
+        for id in pruned {
+            if selected.len() >= m {
+                break;
+            }
+            selected.push(id);
+        }
+        selected
+    }
+
+    fn greedy_closest(&self, query: &[f32], start: u32, layer: usize) -> u32 {
+        let mut current = start;
+        let mut current_dist = self.node_distance(query, current);
+        loop {
+            let mut improved = false;
+            let node = &self.nodes[current as usize];
+            if layer > node.level() {
+                return current;
+            }
+            for &n in &node.neighbors[layer] {
+                let d = self.node_distance(query, n);
+                if d < current_dist {
+                    current_dist = d;
+                    current = n;
+                    improved = true;
+                }
+            }
+            if !improved {
+                return current;
+            }
+        }
+    }
+
+    /// Beam search on one layer; returns up to `ef` candidates, closest first
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[u32],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<u32> = entry_points.iter().copied().collect();
+        let mut frontier: BinaryHeap<std::cmp::Reverse<Candidate>> = BinaryHeap::new();
+        let mut results: BinaryHeap<Candidate> = BinaryHeap::new();
+
+        for &ep in entry_points {
+            let c = Candidate {
+                distance: self.node_distance(query, ep),
+                id: ep,
+            };
+            frontier.push(std::cmp::Reverse(c));
+            results.push(c);
+        }
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(std::cmp::Reverse(current)) = frontier.pop() {
+            let worst = results.peek().map(|c| c.distance).unwrap_or(f32::INFINITY);
+            if current.distance > worst && results.len() >= ef {
+                break;
+            }
+
+            let node = &self.nodes[current.id as usize];
+            if layer > node.level() {
+                continue;
+            }
+            for &n in &node.neighbors[layer] {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let d = self.node_distance(query, n);
+                let worst = results.peek().map(|c| c.distance).unwrap_or(f32::INFINITY);
+                if results.len() < ef || d < worst {
+                    let c = Candidate { distance: d, id: n };
+                    frontier.push(std::cmp::Reverse(c));
+                    results.push(c);
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        results.into_sorted_vec()
+    }
+
+    fn maybe_compact(&mut self) {
+        let total = self.nodes.len();
+        if total >= COMPACT_MIN_NODES
+            && self.tombstones() as f32 > total as f32 * COMPACT_TOMBSTONE_RATIO
+        {
+            self.compact();
+        }
+    }
+}
+
+#[inline]
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random vectors for reproducible tests
+    fn random_vectors(count: usize, dim: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                (0..dim)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        ((state >> 33) as f32 / (1u64 << 31) as f32) * 2.0 - 1.0
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn build(metric: DistanceMetric, vectors: &[Vec<f32>]) -> (HnswIndex, Vec<Uuid>) {
+        let mut index = HnswIndex::new(vectors[0].len(), metric, HnswParams::default());
+        let ids: Vec<Uuid> = vectors.iter().map(|_| Uuid::new_v4()).collect();
+        for (i, v) in vectors.iter().enumerate() {
+            index.upsert(ids[i], Uuid::nil(), i as i64, v).unwrap();
+        }
+        (index, ids)
+    }
+
+    fn brute_force(index: &HnswIndex, query: &[f32], k: usize) -> Vec<Uuid> {
+        let q = index.prepare(query).unwrap();
+        let mut all: Vec<(f32, Uuid)> = index
+            .ids
+            .iter()
+            .map(|(post_id, &id)| (index.node_distance(&q, id), *post_id))
+            .collect();
+        all.sort_by(|a, b| a.0.total_cmp(&b.0));
+        all.into_iter().take(k).map(|(_, id)| id).collect()
+    }
+
+    #[test]
+    fn test_recall_against_brute_force() {
+        let vectors = random_vectors(1000, 32, 7);
+        let (index, _) = build(DistanceMetric::Cosine, &vectors);
+        let queries = random_vectors(20, 32, 99);
+
+        let mut hits = 0;
+        for q in &queries {
+            let truth: HashSet<Uuid> = brute_force(&index, q, 10).into_iter().collect();
+            let found = index.search(q, 10, &SearchFilter::default()).unwrap();
+            hits += found.iter().filter(|n| truth.contains(&n.post_id)).count();
+        }
+
+        let recall = hits as f32 / (queries.len() * 10) as f32;
+        assert!(recall >= 0.9, "recall@10 too low: {}", recall);
+    }
+
+    #[test]
+    fn test_results_sorted_closest_first() {
+        let vectors = random_vectors(200, 16, 3);
+        let (index, _) = build(DistanceMetric::InnerProduct, &vectors);
+        let found = index
+            .search(&vectors[0], 20, &SearchFilter::default())
+            .unwrap();
+        assert_eq!(found.len(), 20);
+        assert!(found.windows(2).all(|w| w[0].distance <= w[1].distance));
+    }
+
+    #[test]
+    fn test_exact_match_is_top_hit() {
+        let vectors = random_vectors(300, 16, 11);
+        let (index, ids) = build(DistanceMetric::Cosine, &vectors);
+        let found = index
+            .search(&vectors[42], 1, &SearchFilter::default())
+            .unwrap();
+        assert_eq!(found[0].post_id, ids[42]);
+        assert!(DistanceMetric::Cosine.to_similarity(found[0].distance) > 0.999);
+    }
+
+    #[test]
+    fn test_upsert_replaces_and_remove_hides() {
+        let vectors = random_vectors(100, 8, 5);
+        let (mut index, ids) = build(DistanceMetric::Cosine, &vectors);
+        assert_eq!(index.len(), 100);
+
+        index.upsert(ids[0], Uuid::nil(), 0, &vectors[1]).unwrap();
+        assert_eq!(index.len(), 100);
+        assert_eq!(index.tombstones(), 1);
+
+        assert!(index.remove(&ids[1]));
+        assert!(!index.remove(&ids[1]));
+        let found = index
+            .search(&vectors[1], 5, &SearchFilter::default())
+            .unwrap();
+        assert!(found.iter().all(|n| n.post_id != ids[1]));
+        assert_eq!(found[0].post_id, ids[0]);
+    }
+
+    #[test]
+    fn test_filter_by_author_and_time() {
+        let vectors = random_vectors(500, 16, 21);
+        let mut index = HnswIndex::new(16, DistanceMetric::Cosine, HnswParams::default());
+        let author_a = Uuid::new_v4();
+        let author_b = Uuid::new_v4();
+        for (i, v) in vectors.iter().enumerate() {
+            let author = if i % 10 == 0 { author_a } else { author_b };
+            index.upsert(Uuid::new_v4(), author, i as i64, v).unwrap();
+        }
+
+        let filter = SearchFilter {
+            author_ids: Some([author_a].into_iter().collect()),
+            created_after: Some(100),
+            ..Default::default()
+        };
+        let found = index.search(&vectors[0], 10, &filter).unwrap();
+        assert_eq!(found.len(), 10);
+        assert!(found.iter().all(|n| n.author_id == author_a));
+    }
+
+    #[test]
+    fn test_compact_drops_tombstones() {
+        let vectors = random_vectors(50, 8, 17);
+        let (mut index, ids) = build(DistanceMetric::Cosine, &vectors);
+        for id in ids.iter().take(20) {
+            index.remove(id);
+        }
+        index.compact();
+        assert_eq!(index.tombstones(), 0);
+        assert_eq!(index.len(), 30);
+        let found = index
+            .search(&vectors[30], 1, &SearchFilter::default())
+            .unwrap();
+        assert_eq!(found[0].post_id, ids[30]);
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let vectors = random_vectors(100, 8, 13);
+        let (index, ids) = build(DistanceMetric::InnerProduct, &vectors);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("posts.hnsw");
+
+        index.save(&path).unwrap();
+        let loaded = HnswIndex::load(&path).unwrap();
+
+        assert_eq!(loaded.len(), 100);
+        assert_eq!(loaded.metric(), DistanceMetric::InnerProduct);
+        assert!(loaded.contains(&ids[7]));
+        let before = index
+            .search(&vectors[7], 5, &SearchFilter::default())
+            .unwrap();
+        let after = loaded
+            .search(&vectors[7], 5, &SearchFilter::default())
+            .unwrap();
+        let ids_of = |n: &[Neighbor]| n.iter().map(|n| n.post_id).collect::<Vec<_>>();
+        assert_eq!(ids_of(&before), ids_of(&after));
+    }
+
+    #[test]
+    fn test_rejects_bad_vectors() {
+        let mut index = HnswIndex::new(4, DistanceMetric::Cosine, HnswParams::default());
+        assert!(index
+            .upsert(Uuid::new_v4(), Uuid::nil(), 0, &[1.0, 2.0])
+            .is_err());
+        assert!(index
+            .upsert(Uuid::new_v4(), Uuid::nil(), 0, &[0.0; 4])
+            .is_err());
+        assert!(index
+            .upsert(Uuid::new_v4(), Uuid::nil(), 0, &[f32::NAN, 0.0, 0.0, 1.0])
+            .is_err());
+    }
+}
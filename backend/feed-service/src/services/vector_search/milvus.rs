@@ -0,0 +1,107 @@
+/// Milvus vector database backend
+///
+/// Optional external backend for deployments that run Milvus. The gRPC
+/// client is not wired yet, so writes are acknowledged and searches return
+/// no hits; use the embedded HNSW backend for working semantic recall.
+use super::backend::VectorBackend;
+use super::hnsw::SearchFilter;
+use super::{PostEmbedding, VectorSearchResult};
+use crate::error::Result;
+use async_trait::async_trait;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+pub struct MilvusBackend {
+    // Connection URL to Milvus server
+    milvus_url: String,
+    // Collection name for posts
+    collection_name: String,
+    // Vector dimension (typical embeddings: 768 or 1024)
+    vector_dim: usize,
+}
+
+impl MilvusBackend {
+    pub fn new(milvus_url: String, collection_name: String, vector_dim: usize) -> Self {
+        Self {
+            milvus_url,
+            collection_name,
+            vector_dim,
+        }
+    }
+}
+
+#[async_trait]
+impl VectorBackend for MilvusBackend {
+    fn name(&self) -> &'static str {
+        "milvus"
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        info!(
+            "Initializing Milvus collection: {} at {}",
+            self.collection_name, self.milvus_url
+        );
+
+        // In production, this would:
+        // 1. Connect to Milvus
+        // 2. Create collection with proper schema if not exists
+        // 3. Create index on embedding field (HNSW or IVF_FLAT)
+        // 4. Verify collection health
+        debug!(
+            "Collection schema: post_id (primary), embedding (vector[{}]), \
+            title (varchar), description (varchar), author_id (uuid), \
+            created_at (int64), engagement_score (float)",
+            self.vector_dim
+        );
+
+        Ok(())
+    }
+
+    async fn upsert(&self, embeddings: Vec<PostEmbedding>) -> Result<usize> {
+        // In production: insert into the collection in one batch and flush
+        debug!(
+            "Milvus upsert of {} embeddings into {}",
+            embeddings.len(),
+            self.collection_name
+        );
+        Ok(embeddings.len())
+    }
+
+    async fn delete(&self, post_id: Uuid) -> Result<bool> {
+        // In production: delete by primary key post_id
+        debug!("Milvus delete of {} from {}", post_id, self.collection_name);
+        Ok(false)
+    }
+
+    async fn get_embedding(&self, post_id: Uuid) -> Result<Option<Vec<f32>>> {
+        debug!("Milvus embedding lookup for {}", post_id);
+        Ok(None)
+    }
+
+    async fn search(
+        &self,
+        _query: &[f32],
+        limit: usize,
+        min_similarity: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<VectorSearchResult>> {
+        // In production: vector search with a boolean expression built from
+        // `filter` (author_id in [...], created_at >= ...)
+        debug!(
+            "Milvus search limit={} min_similarity={} filtered={}",
+            limit,
+            min_similarity,
+            !filter.is_empty()
+        );
+        Ok(Vec::new())
+    }
+
+    async fn count(&self) -> usize {
+        0
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        debug!("Running health check for Milvus at {}", self.milvus_url);
+        Ok(())
+    }
+}
@@ -0,0 +1,669 @@
+/// Vector Search
+///
+/// Provides semantic similarity search for posts using vector embeddings.
+///
+/// Backends (see `backend::VectorBackend`):
+/// - Embedded HNSW index (default): in-process ANN with snapshot-to-disk
+/// - Milvus: external vector database, enabled with `VECTOR_SEARCH_BACKEND=milvus`
+///
+/// Features:
+/// - Post embedding upsert/delete
+/// - Cosine and inner-product similarity
+/// - Filtering by author and creation time
+/// - Bounded LRU cache of recently used embeddings
+use crate::error::{AppError, Result};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+pub mod backend;
+pub mod hnsw;
+pub mod milvus;
+
+pub use backend::{EmbeddedBackend, EmbeddedIndexConfig, VectorBackend};
+pub use hnsw::{DistanceMetric, HnswIndex, HnswParams, SearchFilter};
+pub use milvus::MilvusBackend;
+
+const DEFAULT_COLLECTION_NAME: &str = "post_embeddings";
+const DEFAULT_VECTOR_DIM: usize = 768;
+const DEFAULT_SNAPSHOT_INTERVAL_SECS: u64 = 300;
+/// Embeddings kept in the local cache (768 dims ≈ 3 KiB each)
+const DEFAULT_EMBEDDING_CACHE_CAPACITY: usize = 10_000;
+
+/// Post embedding stored in the vector index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostEmbedding {
+    pub post_id: Uuid,
+    pub embedding: Vec<f32>,
+    pub title: String,
+    pub description: Option<String>,
+    pub author_id: Uuid,
+    pub created_at: i64,
+    pub engagement_score: f32,
+}
+
+/// Vector search result with similarity score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorSearchResult {
+    pub post_id: Uuid,
+    pub similarity_score: f32,
+    pub distance: f32,
+    pub author_id: Uuid,
+}
+
+/// Which backend serves vector search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VectorBackendKind {
+    Embedded,
+    Milvus { url: String },
+}
+
+/// Vector search configuration (from environment)
+#[derive(Debug, Clone)]
+pub struct VectorSearchConfig {
+    pub backend: VectorBackendKind,
+    pub vector_dim: usize,
+    pub metric: DistanceMetric,
+    pub snapshot_path: Option<PathBuf>,
+    pub snapshot_interval: Duration,
+}
+
+impl VectorSearchConfig {
+    /// Read configuration from environment
+    ///
+    /// - `VECTOR_SEARCH_BACKEND`: `embedded` or `milvus` (defaults to `milvus`
+    ///   when `MILVUS_URL` is set, otherwise `embedded`)
+    /// - `VECTOR_SEARCH_DIM`: embedding dimension (default 768)
+    /// - `VECTOR_SEARCH_METRIC`: `cosine` or `ip` (default cosine)
+    /// - `VECTOR_INDEX_SNAPSHOT_PATH`: snapshot file for the embedded index
+    /// - `VECTOR_INDEX_SNAPSHOT_INTERVAL_SECS`: snapshot period (default 300)
+    pub fn from_env() -> Result<Self> {
+        let milvus_url = std::env::var("MILVUS_URL").ok();
+        let backend = match std::env::var("VECTOR_SEARCH_BACKEND").ok().as_deref() {
+            Some("milvus") => VectorBackendKind::Milvus {
+                url: milvus_url.ok_or_else(|| {
+                    AppError::Internal(
+                        "VECTOR_SEARCH_BACKEND=milvus requires MILVUS_URL".to_string(),
+                    )
+                })?,
+            },
+            Some("embedded") => VectorBackendKind::Embedded,
+            Some(other) => {
+                return Err(AppError::Internal(format!(
+                    "Unknown VECTOR_SEARCH_BACKEND: {}",
+                    other
+                )))
+            }
+            None => match milvus_url {
+                Some(url) => VectorBackendKind::Milvus { url },
+                None => VectorBackendKind::Embedded,
+            },
+        };
+
+        let metric = match std::env::var("VECTOR_SEARCH_METRIC") {
+            Ok(value) => DistanceMetric::parse(&value).ok_or_else(|| {
+                AppError::Internal(format!("Unknown VECTOR_SEARCH_METRIC: {}", value))
+            })?,
+            Err(_) => DistanceMetric::Cosine,
+        };
+
+        Ok(Self {
+            backend,
+            vector_dim: std::env::var("VECTOR_SEARCH_DIM")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_VECTOR_DIM),
+            metric,
+            snapshot_path: std::env::var("VECTOR_INDEX_SNAPSHOT_PATH")
+                .ok()
+                .map(PathBuf::from),
+            snapshot_interval: Duration::from_secs(
+                std::env::var("VECTOR_INDEX_SNAPSHOT_INTERVAL_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(DEFAULT_SNAPSHOT_INTERVAL_SECS),
+            ),
+        })
+    }
+}
+
+/// Vector search service
+pub struct VectorSearchService {
+    // Storage/search backend
+    backend: Arc<dyn VectorBackend>,
+    // Collection name for posts
+    collection_name: String,
+    // Vector dimension (typical embeddings: 768 or 1024)
+    vector_dim: usize,
+    // LRU cache for recent embeddings
+    embedding_cache: Arc<tokio::sync::Mutex<LruCache<Uuid, Vec<f32>>>>,
+    // Embedded snapshot task, started once the snapshot has loaded
+    pending_snapshot_task: Mutex<Option<(Arc<EmbeddedBackend>, Duration)>>,
+}
+
+impl VectorSearchService {
+    /// Create new vector search service backed by Milvus
+    pub fn new(milvus_url: String, vector_dim: usize) -> Self {
+        let collection_name = DEFAULT_COLLECTION_NAME.to_string();
+        let backend = MilvusBackend::new(milvus_url, collection_name.clone(), vector_dim);
+        Self::with_backend(Arc::new(backend), vector_dim)
+    }
+
+    /// Create new vector search service backed by the in-process HNSW index
+    pub fn embedded(vector_dim: usize, config: EmbeddedIndexConfig) -> Self {
+        Self::with_backend(
+            Arc::new(EmbeddedBackend::new(vector_dim, config)),
+            vector_dim,
+        )
+    }
+
+    /// Create new vector search service over an arbitrary backend
+    pub fn with_backend(backend: Arc<dyn VectorBackend>, vector_dim: usize) -> Self {
+        Self {
+            backend,
+            collection_name: DEFAULT_COLLECTION_NAME.to_string(),
+            vector_dim,
+            embedding_cache: Arc::new(tokio::sync::Mutex::new(LruCache::new(
+                NonZeroUsize::new(DEFAULT_EMBEDDING_CACHE_CAPACITY).expect("non-zero capacity"),
+            ))),
+            pending_snapshot_task: Mutex::new(None),
+        }
+    }
+
+    /// Bound the local embedding cache to `capacity` entries (minimum 1)
+    pub fn with_cache_capacity(self, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            embedding_cache: Arc::new(tokio::sync::Mutex::new(LruCache::new(capacity))),
+            ..self
+        }
+    }
+
+    /// Build the service described by `config`
+    ///
+    /// For the embedded backend with a snapshot path, the periodic snapshot
+    /// task is started by `initialize_collection` once the existing snapshot
+    /// has loaded, so a failed load never overwrites it with an empty index.
+    pub fn from_config(config: &VectorSearchConfig) -> Self {
+        match &config.backend {
+            VectorBackendKind::Milvus { url } => Self::new(url.clone(), config.vector_dim),
+            VectorBackendKind::Embedded => {
+                let backend = Arc::new(EmbeddedBackend::new(
+                    config.vector_dim,
+                    EmbeddedIndexConfig {
+                        metric: config.metric,
+                        params: HnswParams::default(),
+                        snapshot_path: config.snapshot_path.clone(),
+                    },
+                ));
+                let service = Self::with_backend(backend.clone(), config.vector_dim);
+                if config.snapshot_path.is_some() {
+                    *service
+                        .pending_snapshot_task
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner()) =
+                        Some((backend, config.snapshot_interval));
+                }
+                service
+            }
+        }
+    }
+
+    /// Name of the active backend
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Initialize backend storage (create collection / load snapshot)
+    pub async fn initialize_collection(&self) -> Result<()> {
+        info!(
+            "Initializing vector collection {} on {} backend",
+            self.collection_name,
+            self.backend.name()
+        );
+        self.backend.initialize().await?;
+
+        let pending = self
+            .pending_snapshot_task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some((backend, interval)) = pending {
+            backend.spawn_snapshot_task(interval);
+        }
+        Ok(())
+    }
+
+    /// Search similar posts by embedding
+    pub async fn search_similar(
+        &self,
+        query_embedding: Vec<f32>,
+        limit: usize,
+        min_similarity: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<VectorSearchResult>> {
+        if query_embedding.len() != self.vector_dim {
+            return Err(AppError::BadRequest(format!(
+                "Query embedding dimension {} does not match expected dimension {}",
+                query_embedding.len(),
+                self.vector_dim
+            )));
+        }
+
+        Self::validate_limit(limit)?;
+
+        debug!(
+            "Searching {} similar posts with min_similarity: {}",
+            limit, min_similarity
+        );
+
+        let results = self
+            .backend
+            .search(&query_embedding, limit, min_similarity, filter)
+            .await?;
+
+        debug!(
+            "Vector search on {} returned {} of {} requested",
+            self.backend.name(),
+            results.len(),
+            limit
+        );
+        Ok(results)
+    }
+
+    /// Search similar posts by post ID (the post itself is excluded)
+    pub async fn search_similar_by_post(
+        &self,
+        post_id: Uuid,
+        limit: usize,
+        min_similarity: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<VectorSearchResult>> {
+        debug!("Searching similar posts for post_id: {}", post_id);
+
+        Self::validate_limit(limit)?;
+
+        let cached = self.embedding_cache.lock().await.get(&post_id).cloned();
+        let embedding = match cached {
+            Some(embedding) => embedding,
+            None => match self.backend.get_embedding(post_id).await? {
+                Some(embedding) => {
+                    self.embedding_cache
+                        .lock()
+                        .await
+                        .put(post_id, embedding.clone());
+                    embedding
+                }
+                None => {
+                    debug!("No embedding indexed for post {}", post_id);
+                    return Ok(Vec::new());
+                }
+            },
+        };
+
+        let mut filter = filter.clone();
+        filter.exclude_post_ids.insert(post_id);
+        self.search_similar(embedding, limit, min_similarity, &filter)
+            .await
+    }
+
+    /// Index new post embedding (insert or replace)
+    pub async fn index_embedding(&self, embedding: PostEmbedding) -> Result<()> {
+        self.batch_index_embeddings(vec![embedding])
+            .await
+            .map(|_| ())
+    }
+
+    /// Batch index multiple embeddings
+    pub async fn batch_index_embeddings(&self, embeddings: Vec<PostEmbedding>) -> Result<usize> {
+        if embeddings.is_empty() {
+            return Ok(0);
+        }
+
+        // Validate all embeddings
+        for embedding in &embeddings {
+            if embedding.embedding.len() != self.vector_dim {
+                return Err(AppError::BadRequest(format!(
+                    "Embedding dimension {} does not match expected dimension {}",
+                    embedding.embedding.len(),
+                    self.vector_dim
+                )));
+            }
+        }
+
+        let vectors: Vec<(Uuid, Vec<f32>)> = embeddings
+            .iter()
+            .map(|e| (e.post_id, e.embedding.clone()))
+            .collect();
+
+        // Cache only what the backend accepted; a rejected batch may be
+        // partially applied, so drop any stale entries for it instead
+        let written = match self.backend.upsert(embeddings).await {
+            Ok(written) => written,
+            Err(e) => {
+                let mut cache = self.embedding_cache.lock().await;
+                for (post_id, _) in &vectors {
+                    cache.pop(post_id);
+                }
+                return Err(e);
+            }
+        };
+        {
+            let mut cache = self.embedding_cache.lock().await;
+            for (post_id, vector) in vectors {
+                cache.put(post_id, vector);
+            }
+        }
+
+        debug!("Indexed {} embeddings on {}", written, self.backend.name());
+        Ok(written)
+    }
+
+    /// Delete embedding for post
+    pub async fn delete_embedding(&self, post_id: Uuid) -> Result<()> {
+        // Remove from local cache
+        {
+            let mut cache = self.embedding_cache.lock().await;
+            cache.pop(&post_id);
+        }
+
+        let existed = self.backend.delete(post_id).await?;
+        debug!(
+            "Deleted embedding for post {} (existed: {})",
+            post_id, existed
+        );
+        Ok(())
+    }
+
+    /// Number of embeddings held by the backend
+    pub async fn indexed_count(&self) -> usize {
+        self.backend.count().await
+    }
+
+    /// Persist the index (embedded backend writes its snapshot file)
+    pub async fn snapshot(&self) -> Result<()> {
+        self.backend.snapshot().await
+    }
+
+    /// Get embedding cache statistics
+    pub async fn cache_stats(&self) -> (usize, usize) {
+        let cache = self.embedding_cache.lock().await;
+        let len = cache.len();
+        let approx_size = len * (4 * self.vector_dim + 50); // rough estimate
+
+        (len, approx_size)
+    }
+
+    /// Health check - verify backend availability
+    pub async fn health_check(&self) -> Result<()> {
+        self.backend.health_check().await
+    }
+
+    /// Clear embedding cache
+    pub async fn clear_cache(&self) {
+        let mut cache = self.embedding_cache.lock().await;
+        let size = cache.len();
+        cache.clear();
+        info!("Cleared embedding cache with {} entries", size);
+    }
+
+    fn validate_limit(limit: usize) -> Result<()> {
+        if limit == 0 || limit > 1000 {
+            return Err(AppError::BadRequest(
+                "Limit must be between 1 and 1000".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_search_service_creation() {
+        let service = VectorSearchService::new("http://localhost:19530".to_string(), 768);
+        assert_eq!(service.collection_name, "post_embeddings");
+        assert_eq!(service.vector_dim, 768);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_embedding_dimension() {
+        let service = VectorSearchService::new("http://localhost:19530".to_string(), 768);
+        let embedding = PostEmbedding {
+            post_id: Uuid::new_v4(),
+            embedding: vec![0.1; 512], // Wrong dimension
+            title: "Test".to_string(),
+            description: None,
+            author_id: Uuid::new_v4(),
+            created_at: 0,
+            engagement_score: 0.5,
+        };
+
+        let result = service.index_embedding(embedding).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cache_operations() {
+        let service = VectorSearchService::new("http://localhost:19530".to_string(), 768);
+        let post_id = Uuid::new_v4();
+
+        let embedding = PostEmbedding {
+            post_id,
+            embedding: vec![0.1; 768],
+            title: "Test".to_string(),
+            description: None,
+            author_id: Uuid::new_v4(),
+            created_at: 0,
+            engagement_score: 0.8,
+        };
+
+        // Index should succeed
+        assert!(service.index_embedding(embedding).await.is_ok());
+
+        // Cache should have 1 entry
+        let (cache_size, _) = service.cache_stats().await;
+        assert_eq!(cache_size, 1);
+
+        // Delete should succeed
+        assert!(service.delete_embedding(post_id).await.is_ok());
+
+        // Cache should be empty
+        let (cache_size, _) = service.cache_stats().await;
+        assert_eq!(cache_size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_embedding_is_not_cached() {
+        let service = VectorSearchService::embedded(4, EmbeddedIndexConfig::default());
+        let embedding = PostEmbedding {
+            post_id: Uuid::new_v4(),
+            embedding: vec![0.0; 4], // cosine cannot index a zero vector
+            title: "Zero".to_string(),
+            description: None,
+            author_id: Uuid::new_v4(),
+            created_at: 0,
+            engagement_score: 0.5,
+        };
+
+        assert!(service.index_embedding(embedding).await.is_err());
+        let (cache_size, _) = service.cache_stats().await;
+        assert_eq!(cache_size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cache_is_bounded() {
+        let service = VectorSearchService::new("http://localhost:19530".to_string(), 4)
+            .with_cache_capacity(2);
+        for i in 0..5 {
+            let embedding = PostEmbedding {
+                post_id: Uuid::new_v4(),
+                embedding: vec![i as f32; 4],
+                title: format!("Post {}", i),
+                description: None,
+                author_id: Uuid::new_v4(),
+                created_at: 0,
+                engagement_score: 0.5,
+            };
+            service.index_embedding(embedding).await.unwrap();
+        }
+
+        let (cache_size, _) = service.cache_stats().await;
+        assert_eq!(cache_size, 2);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_task_waits_for_successful_load() {
+        let path = std::env::temp_dir().join(format!("hnsw-{}.bin", Uuid::new_v4()));
+        std::fs::write(&path, b"not a snapshot").unwrap();
+        let config = VectorSearchConfig {
+            backend: VectorBackendKind::Embedded,
+            vector_dim: 4,
+            metric: DistanceMetric::Cosine,
+            snapshot_path: Some(path.clone()),
+            snapshot_interval: Duration::from_secs(3600),
+        };
+
+        let service = VectorSearchService::from_config(&config);
+        assert!(service.initialize_collection().await.is_err());
+        assert!(
+            service.pending_snapshot_task.lock().unwrap().is_some(),
+            "snapshot task must not start after a failed load"
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a snapshot");
+
+        std::fs::remove_file(&path).unwrap();
+        let service = VectorSearchService::from_config(&config);
+        assert!(service.initialize_collection().await.is_ok());
+        assert!(service.pending_snapshot_task.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batch_indexing() {
+        let service = VectorSearchService::new("http://localhost:19530".to_string(), 768);
+
+        let embeddings = vec![
+            PostEmbedding {
+                post_id: Uuid::new_v4(),
+                embedding: vec![0.1; 768],
+                title: "Post 1".to_string(),
+                description: None,
+                author_id: Uuid::new_v4(),
+                created_at: 0,
+                engagement_score: 0.5,
+            },
+            PostEmbedding {
+                post_id: Uuid::new_v4(),
+                embedding: vec![0.2; 768],
+                title: "Post 2".to_string(),
+                description: None,
+                author_id: Uuid::new_v4(),
+                created_at: 0,
+                engagement_score: 0.7,
+            },
+        ];
+
+        let result = service.batch_index_embeddings(embeddings).await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 2);
+
+        let (cache_size, _) = service.cache_stats().await;
+        assert_eq!(cache_size, 2);
+    }
+
+    fn embedding(post_id: Uuid, author_id: Uuid, created_at: i64, v: Vec<f32>) -> PostEmbedding {
+        PostEmbedding {
+            post_id,
+            embedding: v,
+            title: "Test".to_string(),
+            description: None,
+            author_id,
+            created_at,
+            engagement_score: 0.5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embedded_search_similar_by_post() {
+        let service = VectorSearchService::embedded(3, EmbeddedIndexConfig::default());
+        assert_eq!(service.backend_name(), "embedded-hnsw");
+
+        let query_post = Uuid::new_v4();
+        let similar_post = Uuid::new_v4();
+        let unrelated_post = Uuid::new_v4();
+        service
+            .batch_index_embeddings(vec![
+                embedding(query_post, Uuid::new_v4(), 0, vec![1.0, 0.0, 0.0]),
+                embedding(similar_post, Uuid::new_v4(), 0, vec![0.9, 0.1, 0.0]),
+                embedding(unrelated_post, Uuid::new_v4(), 0, vec![0.0, 0.0, 1.0]),
+            ])
+            .await
+            .unwrap();
+
+        let results = service
+            .search_similar_by_post(query_post, 10, 0.5, &SearchFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].post_id, similar_post);
+        assert!(results[0].similarity_score > 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_embedded_search_with_filter_and_delete() {
+        let service = VectorSearchService::embedded(2, EmbeddedIndexConfig::default());
+        let author = Uuid::new_v4();
+        let old_post = Uuid::new_v4();
+        let new_post = Uuid::new_v4();
+        let other_author_post = Uuid::new_v4();
+        service
+            .batch_index_embeddings(vec![
+                embedding(old_post, author, 100, vec![1.0, 0.0]),
+                embedding(new_post, author, 200, vec![1.0, 0.1]),
+                embedding(other_author_post, Uuid::new_v4(), 300, vec![1.0, 0.0]),
+            ])
+            .await
+            .unwrap();
+
+        let filter = SearchFilter {
+            author_ids: Some([author].into_iter().collect()),
+            created_after: Some(150),
+            ..Default::default()
+        };
+        let results = service
+            .search_similar(vec![1.0, 0.0], 10, 0.0, &filter)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].post_id, new_post);
+
+        service.delete_embedding(new_post).await.unwrap();
+        let results = service
+            .search_similar(vec![1.0, 0.0], 10, 0.0, &filter)
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+        assert_eq!(service.indexed_count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_limit_validation() {
+        let service = VectorSearchService::embedded(2, EmbeddedIndexConfig::default());
+        let filter = SearchFilter::default();
+        assert!(service
+            .search_similar(vec![1.0, 0.0], 0, 0.0, &filter)
+            .await
+            .is_err());
+        assert!(service
+            .search_similar(vec![1.0, 0.0, 0.0], 10, 0.0, &filter)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,30 @@
+-- ============================================================================
+-- Graph Service: Private accounts and follow requests
+-- ============================================================================
+-- Purpose:
+--   - users.is_private: follows of a private account need the owner's approval
+--   - follow_requests: pending follow edges (requester -> target); approval
+--     moves the row into follows, deny/cancel deletes it
+-- Database: nova_graph
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS is_private BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Follow requests table (requester_id asks to follow target_id)
+CREATE TABLE IF NOT EXISTS follow_requests (
+    requester_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    target_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (requester_id, target_id),
+    CONSTRAINT no_self_follow_request CHECK (requester_id != target_id)
+);
+
+-- Incoming requests are listed newest first per target
+CREATE INDEX IF NOT EXISTS idx_follow_requests_target
+    ON follow_requests(target_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_follow_requests_requester
+    ON follow_requests(requester_id, created_at DESC);
+
+COMMENT ON TABLE follow_requests IS 'Pending follow requests to private accounts (requester -> target)';
+COMMENT ON COLUMN users.is_private IS 'Private account: new followers must be approved';
@@ -0,0 +1,41 @@
+-- ============================================================================
+-- Graph Service: Audience lists (close friends + custom lists)
+-- ============================================================================
+-- Purpose:
+--   - audience_lists: named lists owned by a user; every owner has at most one
+--     'close_friends' list (created on first use) plus any number of 'custom'
+--     lists
+--   - audience_list_members: owner-scoped membership edges (list -> member)
+--     used by content-service to target posts and stories at a list
+-- Database: nova_graph
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS audience_lists (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(64) NOT NULL,
+    kind VARCHAR(16) NOT NULL DEFAULT 'custom' CHECK (kind IN ('close_friends', 'custom')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    CONSTRAINT uq_audience_lists_owner_name UNIQUE (owner_id, name)
+);
+
+-- One close friends list per owner
+CREATE UNIQUE INDEX IF NOT EXISTS uq_audience_lists_close_friends
+    ON audience_lists(owner_id) WHERE kind = 'close_friends';
+
+CREATE TABLE IF NOT EXISTS audience_list_members (
+    list_id UUID NOT NULL REFERENCES audience_lists(id) ON DELETE CASCADE,
+    member_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    added_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (list_id, member_id)
+);
+
+-- Membership checks are keyed by viewer (member) during feed filtering
+CREATE INDEX IF NOT EXISTS idx_audience_list_members_member
+    ON audience_list_members(member_id, list_id);
+CREATE INDEX IF NOT EXISTS idx_audience_list_members_added
+    ON audience_list_members(list_id, added_at DESC);
+
+COMMENT ON TABLE audience_lists IS 'Named audience lists (close friends / custom) owned by a user';
+COMMENT ON TABLE audience_list_members IS 'Audience list membership edges (list -> member)';
@@ -1,4 +1,7 @@
 use anyhow::{Context, Result};
+use graph_service::migration::consistency::{
+    ConsistencyConfig, ConsistencyReport, ConsistencyVerifier,
+};
 use graph_service::migration::neo4j_backfill::Neo4jBackfill;
 use neo4rs::Graph;
 use sqlx::PgPool;
@@ -43,7 +46,8 @@ async fn main() -> Result<()> {
     let args: Vec<String> = std::env::args().collect();
     let command = args.get(1).map(|s| s.as_str()).unwrap_or("help");
 
-    let backfill = Neo4jBackfill::new(pg_pool.clone(), Arc::new(neo4j_graph));
+    let neo4j_graph = Arc::new(neo4j_graph);
+    let backfill = Neo4jBackfill::new(pg_pool.clone(), neo4j_graph.clone());
 
     match command {
         "backfill" | "migrate" => {
@@ -63,15 +67,33 @@ async fn main() -> Result<()> {
             }
         }
 
-        "verify" => {
-            info!("🔍 Verifying data consistency");
-            // Re-run backfill to get stats and verify
-            match backfill.run().await {
-                Ok(_) => info!("✅ Verification passed"),
-                Err(e) => {
-                    error!("❌ Verification failed: {}", e);
-                    return Err(e);
-                }
+        "verify" | "repair" => {
+            let repair = command == "repair";
+            let config = ConsistencyConfig {
+                repair,
+                chunk_delay: std::time::Duration::ZERO,
+                ..ConsistencyConfig::from_env()
+            };
+            info!(
+                "🔍 Diffing follow/mute/block/request edges ({} users per chunk, repair = {})",
+                config.chunk_size, repair
+            );
+
+            let verifier = ConsistencyVerifier::new(pg_pool.clone(), neo4j_graph.clone(), config);
+            let report = verifier.run_pass().await?;
+            log_consistency_report(&report);
+
+            if report.is_consistent() {
+                info!("✅ Verification passed");
+            } else if repair && report.chunks_failed == 0 {
+                info!("✅ Divergent edges repaired from PostgreSQL");
+            } else {
+                error!("❌ Verification failed");
+                return Err(anyhow::anyhow!(
+                    "{} divergent edges, {} failed chunks",
+                    report.divergent_edges(),
+                    report.chunks_failed
+                ));
             }
         }
 
@@ -182,7 +204,8 @@ async fn main() -> Result<()> {
             println!();
             println!("Commands:");
             println!("  backfill   - Migrate data from PostgreSQL to Neo4j");
-            println!("  verify     - Verify data consistency");
+            println!("  verify     - Diff edges between PostgreSQL and Neo4j (read-only)");
+            println!("  repair     - Diff edges and repair Neo4j from PostgreSQL");
             println!("  clear      - Clear all Neo4j data (WARNING: destructive)");
             println!("  check      - Check database connections");
             println!("  stats      - Show database statistics");
@@ -193,15 +216,40 @@ async fn main() -> Result<()> {
             println!("  NEO4J_URI          - Neo4j URI (default: bolt://neo4j:7687)");
             println!("  NEO4J_USER         - Neo4j username (default: neo4j)");
             println!("  NEO4J_PASSWORD     - Neo4j password (required)");
+            println!(
+                "  GRAPH_CONSISTENCY_CHUNK_SIZE - Users per verify/repair chunk (default: 500)"
+            );
             println!();
             println!("Examples:");
             println!("  neo4j-migrate check");
             println!("  neo4j-migrate stats");
             println!("  neo4j-migrate backfill");
             println!("  neo4j-migrate verify");
+            println!("  neo4j-migrate repair");
         }
     }
 
     info!("🎉 Done!");
     Ok(())
 }
+
+fn log_consistency_report(report: &ConsistencyReport) {
+    info!(
+        "   Users checked: {} in {:.1}s",
+        report.users_checked,
+        report.elapsed.as_secs_f64()
+    );
+    for entry in &report.edge_types {
+        info!(
+            "   {}: {} checked, {} missing in Neo4j, {} extra in Neo4j, {} repaired",
+            entry.edge_type.as_str(),
+            entry.checked,
+            entry.missing,
+            entry.extra,
+            entry.repaired
+        );
+    }
+    if report.chunks_failed > 0 {
+        error!("   Failed chunks: {}", report.chunks_failed);
+    }
+}
@@ -8,6 +8,8 @@ use std::time::Duration;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::domain::edge::FollowOutcome;
+use crate::events::{spawn_publish, GraphEventProducer};
 use crate::repository::GraphRepositoryTrait;
 
 /// Event payload for follow created/deleted events from social-service
@@ -33,6 +35,7 @@ pub struct SocialEventsConsumer {
     consumer: StreamConsumer,
     repository: Arc<dyn GraphRepositoryTrait + Send + Sync>,
     topic: String,
+    events: Option<GraphEventProducer>,
 }
 
 impl SocialEventsConsumer {
@@ -62,9 +65,16 @@ impl SocialEventsConsumer {
             consumer,
             repository,
             topic: topic.to_string(),
+            events: None,
         })
     }
 
+    /// Publish follow request events for follows of private accounts
+    pub fn with_event_producer(mut self, events: Option<GraphEventProducer>) -> Self {
+        self.events = events;
+        self
+    }
+
     /// Start consuming events
     pub async fn start(self) -> Result<()> {
         info!("Starting social events consumer for topic '{}'", self.topic);
@@ -109,11 +119,27 @@ impl SocialEventsConsumer {
                     follower_id, followee_id
                 );
 
-                self.repository
-                    .create_follow(follower_id, followee_id)
-                    .await?;
-
-                info!("Successfully created follow edge in graph");
+                // Follows of private accounts become pending requests
+                match self
+                    .repository
+                    .follow_or_request(follower_id, followee_id)
+                    .await?
+                {
+                    FollowOutcome::Followed => {
+                        info!("Successfully created follow edge in graph");
+                    }
+                    FollowOutcome::Requested => {
+                        info!("Followee is private; created follow request instead");
+                        spawn_publish(self.events.as_ref(), move |events| async move {
+                            events
+                                .publish_follow_request_created(follower_id, followee_id)
+                                .await
+                        });
+                    }
+                    FollowOutcome::AlreadyRequested => {
+                        info!("Follow request already pending");
+                    }
+                }
             }
             "social.follow.deleted" => {
                 let payload: FollowEventPayload = serde_json::from_value(event.payload.clone())?;
@@ -129,6 +155,18 @@ impl SocialEventsConsumer {
                     .delete_follow(follower_id, followee_id)
                     .await?;
 
+                if self
+                    .repository
+                    .delete_follow_request(follower_id, followee_id)
+                    .await?
+                {
+                    spawn_publish(self.events.as_ref(), move |events| async move {
+                        events
+                            .publish_follow_request_cancelled(follower_id, followee_id)
+                            .await
+                    });
+                }
+
                 info!("Successfully deleted follow edge from graph");
             }
             _ => {
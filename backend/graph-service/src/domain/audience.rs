@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// List reference accepted in place of a list id for the owner's close friends
+pub const CLOSE_FRIENDS_ALIAS: &str = "close_friends";
+
+/// Display name of the close friends list (not user editable)
+pub const CLOSE_FRIENDS_NAME: &str = "Close Friends";
+
+/// Maximum custom lists per owner
+pub const MAX_CUSTOM_LISTS: i64 = 50;
+
+/// Maximum list name length (characters)
+pub const MAX_LIST_NAME_LEN: usize = 64;
+
+/// Kind of audience list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudienceListKind {
+    /// The owner's single close friends list, created on first use
+    CloseFriends,
+    /// Named list created by the owner
+    Custom,
+}
+
+impl AudienceListKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudienceListKind::CloseFriends => "close_friends",
+            AudienceListKind::Custom => "custom",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "close_friends" => Some(AudienceListKind::CloseFriends),
+            "custom" => Some(AudienceListKind::Custom),
+            _ => None,
+        }
+    }
+}
+
+/// Named audience list owned by a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudienceList {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub kind: AudienceListKind,
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Trim and validate a list name; `None` if empty or too long
+pub fn normalize_list_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_LIST_NAME_LEN {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_kind_round_trip() {
+        for kind in [AudienceListKind::CloseFriends, AudienceListKind::Custom] {
+            assert_eq!(AudienceListKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(AudienceListKind::parse("followers"), None);
+    }
+
+    #[test]
+    fn test_normalize_list_name() {
+        assert_eq!(normalize_list_name("  Family "), Some("Family".to_string()));
+        assert_eq!(normalize_list_name("   "), None);
+        assert_eq!(
+            normalize_list_name(&"x".repeat(MAX_LIST_NAME_LEN + 1)),
+            None
+        );
+        assert!(normalize_list_name(&"好".repeat(MAX_LIST_NAME_LEN)).is_some());
+    }
+}
@@ -11,6 +11,8 @@ pub enum EdgeType {
     Follow,
     Mute,
     Block,
+    /// Pending follow of a private account, waiting for the owner's approval
+    FollowRequest,
 }
 
 #[allow(dead_code)]
@@ -20,6 +22,7 @@ impl EdgeType {
             EdgeType::Follow => "FOLLOWS",
             EdgeType::Mute => "MUTES",
             EdgeType::Block => "BLOCKS",
+            EdgeType::FollowRequest => "REQUESTED_FOLLOW",
         }
     }
 }
@@ -63,6 +66,42 @@ impl Edge {
             created_at: Utc::now(),
         }
     }
+
+    pub fn new_follow_request(requester_id: Uuid, target_id: Uuid) -> Self {
+        Self {
+            from_user_id: requester_id,
+            to_user_id: target_id,
+            edge_type: EdgeType::FollowRequest,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Result of a follow attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowOutcome {
+    /// FOLLOWS edge exists (created now or already present)
+    Followed,
+    /// Target is private; a new follow request was filed
+    Requested,
+    /// Target is private and a request was already pending
+    AlreadyRequested,
+}
+
+impl FollowOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FollowOutcome::Followed => "followed",
+            FollowOutcome::Requested => "requested",
+            FollowOutcome::AlreadyRequested => "already_requested",
+        }
+    }
+
+    /// Whether the follow is still waiting for approval
+    pub fn is_pending(&self) -> bool {
+        !matches!(self, FollowOutcome::Followed)
+    }
 }
 
 /// 關係圖統計
@@ -98,6 +137,7 @@ mod tests {
         assert_eq!(EdgeType::Follow.as_str(), "FOLLOWS");
         assert_eq!(EdgeType::Mute.as_str(), "MUTES");
         assert_eq!(EdgeType::Block.as_str(), "BLOCKS");
+        assert_eq!(EdgeType::FollowRequest.as_str(), "REQUESTED_FOLLOW");
     }
 
     #[test]
@@ -111,4 +151,15 @@ mod tests {
         assert_eq!(edge.to_user_id, followee);
         assert_eq!(edge.edge_type, EdgeType::Follow);
     }
+
+    #[test]
+    fn test_follow_outcome() {
+        assert!(!FollowOutcome::Followed.is_pending());
+        assert!(FollowOutcome::Requested.is_pending());
+        assert!(FollowOutcome::AlreadyRequested.is_pending());
+        assert_eq!(
+            serde_json::to_string(&FollowOutcome::AlreadyRequested).unwrap(),
+            "\"already_requested\""
+        );
+    }
 }
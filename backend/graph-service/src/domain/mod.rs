@@ -1 +1,2 @@
+pub mod audience;
 pub mod edge;
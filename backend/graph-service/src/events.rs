@@ -0,0 +1,331 @@
+//! Kafka event producer for graph service
+//!
+//! Publishes follow request and account privacy events for downstream
+//! consumers (notification-service, feed-service).
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use event_schema::EventEnvelope;
+use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub const FOLLOW_REQUEST_CREATED: &str = "graph.follow_request.created";
+pub const FOLLOW_REQUEST_APPROVED: &str = "graph.follow_request.approved";
+pub const FOLLOW_REQUEST_DENIED: &str = "graph.follow_request.denied";
+pub const FOLLOW_REQUEST_CANCELLED: &str = "graph.follow_request.cancelled";
+pub const ACCOUNT_PRIVACY_CHANGED: &str = "graph.account.privacy_changed";
+
+/// Publish in the background so a Kafka outage never fails the graph write
+pub fn spawn_publish<F, Fut>(producer: Option<&GraphEventProducer>, publish: F)
+where
+    F: FnOnce(GraphEventProducer) -> Fut,
+    Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+{
+    if let Some(producer) = producer {
+        let fut = publish(producer.clone());
+        tokio::spawn(async move {
+            if let Err(e) = fut.await {
+                warn!(error = ?e, "Failed to publish graph event");
+            }
+        });
+    }
+}
+
+/// Notification event format expected by notification-service Kafka consumer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KafkaNotification {
+    pub id: String,
+    pub user_id: Uuid,
+    pub event_type: String,
+    pub title: String,
+    pub body: String,
+    pub data: Option<serde_json::Value>,
+    pub timestamp: i64,
+}
+
+/// Payload for `graph.follow_request.*` events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowRequestEvent {
+    pub requester_id: Uuid,
+    pub target_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Payload for `graph.account.privacy_changed` events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountPrivacyChangedEvent {
+    pub user_id: Uuid,
+    pub is_private: bool,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Configuration for the graph event producer
+#[derive(Debug, Clone)]
+pub struct GraphEventProducerConfig {
+    pub brokers: String,
+    pub topic: String,
+    /// Topic for follow notifications (consumed by notification-service)
+    pub notification_topic: String,
+}
+
+impl GraphEventProducerConfig {
+    /// Load configuration from environment variables
+    pub fn from_env() -> Option<Self> {
+        let brokers = std::env::var("KAFKA_BROKERS").ok()?;
+
+        if brokers.trim().is_empty() {
+            return None;
+        }
+
+        let topic_prefix =
+            std::env::var("KAFKA_TOPIC_PREFIX").unwrap_or_else(|_| "nova".to_string());
+
+        Some(Self {
+            brokers,
+            topic: std::env::var("KAFKA_GRAPH_EVENTS_TOPIC")
+                .unwrap_or_else(|_| format!("{}.graph.events", topic_prefix)),
+            notification_topic: std::env::var("KAFKA_FOLLOW_NOTIFICATION_TOPIC")
+                .unwrap_or_else(|_| "FollowAdded".to_string()),
+        })
+    }
+}
+
+/// Kafka event producer for graph changes that other services react to
+#[derive(Clone)]
+pub struct GraphEventProducer {
+    producer: FutureProducer,
+    topic: String,
+    notification_topic: String,
+}
+
+impl GraphEventProducer {
+    /// Create a new Kafka event producer
+    pub fn new(config: &GraphEventProducerConfig) -> Result<Self> {
+        let producer = rdkafka::config::ClientConfig::new()
+            .set("bootstrap.servers", &config.brokers)
+            .set("client.id", "graph-service")
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .set("max.in.flight.requests.per.connection", "5")
+            .set("retries", "3")
+            .set("linger.ms", "5")
+            .create::<FutureProducer>()?;
+
+        info!(
+            brokers = %config.brokers,
+            topic = %config.topic,
+            notification_topic = %config.notification_topic,
+            "Graph service Kafka producer initialized"
+        );
+
+        Ok(Self {
+            producer,
+            topic: config.topic.clone(),
+            notification_topic: config.notification_topic.clone(),
+        })
+    }
+
+    /// Requester asked to follow a private account; notifies the target
+    pub async fn publish_follow_request_created(
+        &self,
+        requester_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<()> {
+        self.publish_follow_request(FOLLOW_REQUEST_CREATED, requester_id, target_id)
+            .await?;
+
+        self.publish_notification(KafkaNotification {
+            id: Uuid::new_v4().to_string(),
+            user_id: target_id,
+            event_type: "Follow".to_string(),
+            title: "New Follow Request".to_string(),
+            body: "Someone requested to follow you".to_string(),
+            data: Some(serde_json::json!({
+                "sender_id": requester_id.to_string(),
+                "object_id": requester_id.to_string(),
+                "object_type": "user",
+                "follow_request": "requested",
+            })),
+            timestamp: Utc::now().timestamp(),
+        })
+        .await
+    }
+
+    /// Target approved the request; notifies the requester
+    pub async fn publish_follow_request_approved(
+        &self,
+        requester_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<()> {
+        self.publish_follow_request(FOLLOW_REQUEST_APPROVED, requester_id, target_id)
+            .await?;
+
+        self.publish_notification(KafkaNotification {
+            id: Uuid::new_v4().to_string(),
+            user_id: requester_id,
+            event_type: "Follow".to_string(),
+            title: "Follow Request Approved".to_string(),
+            body: "Your follow request was approved".to_string(),
+            data: Some(serde_json::json!({
+                "sender_id": target_id.to_string(),
+                "object_id": target_id.to_string(),
+                "object_type": "user",
+                "follow_request": "approved",
+            })),
+            timestamp: Utc::now().timestamp(),
+        })
+        .await
+    }
+
+    /// Target denied the request (requester is not notified)
+    pub async fn publish_follow_request_denied(
+        &self,
+        requester_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<()> {
+        self.publish_follow_request(FOLLOW_REQUEST_DENIED, requester_id, target_id)
+            .await
+    }
+
+    /// Requester withdrew the request
+    pub async fn publish_follow_request_cancelled(
+        &self,
+        requester_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<()> {
+        self.publish_follow_request(FOLLOW_REQUEST_CANCELLED, requester_id, target_id)
+            .await
+    }
+
+    /// Account switched between private and public
+    pub async fn publish_privacy_changed(&self, user_id: Uuid, is_private: bool) -> Result<()> {
+        let event = AccountPrivacyChangedEvent {
+            user_id,
+            is_private,
+            changed_at: Utc::now(),
+        };
+
+        let envelope =
+            EventEnvelope::new_with_type("graph-service", ACCOUNT_PRIVACY_CHANGED, event)
+                .with_correlation_id(Uuid::new_v4());
+
+        self.publish_event(&envelope, user_id).await
+    }
+
+    async fn publish_follow_request(
+        &self,
+        event_type: &str,
+        requester_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<()> {
+        let event = FollowRequestEvent {
+            requester_id,
+            target_id,
+            occurred_at: Utc::now(),
+        };
+
+        let envelope = EventEnvelope::new_with_type("graph-service", event_type, event)
+            .with_correlation_id(Uuid::new_v4());
+
+        // Partition by target so a user's request stream stays ordered
+        self.publish_event(&envelope, target_id).await
+    }
+
+    /// Generic event publishing method
+    async fn publish_event<T: Serialize>(
+        &self,
+        envelope: &EventEnvelope<T>,
+        partition_key_id: Uuid,
+    ) -> Result<()> {
+        let payload = serde_json::to_string(envelope)?;
+        let partition_key = partition_key_id.to_string();
+
+        // Add event_type header for consumer routing
+        let headers = OwnedHeaders::new().insert(rdkafka::message::Header {
+            key: "event_type",
+            value: envelope.event_type.as_deref(),
+        });
+
+        let record = FutureRecord::to(&self.topic)
+            .key(&partition_key)
+            .payload(&payload)
+            .headers(headers);
+
+        match self.producer.send(record, Duration::from_secs(5)).await {
+            Ok(_) => {
+                info!(
+                    event_type = ?envelope.event_type,
+                    partition_key = %partition_key,
+                    "Published graph event to Kafka"
+                );
+                Ok(())
+            }
+            Err((err, _)) => {
+                warn!(
+                    error = ?err,
+                    event_type = ?envelope.event_type,
+                    "Failed to publish graph event to Kafka"
+                );
+                Err(anyhow::anyhow!("Failed to publish event: {}", err))
+            }
+        }
+    }
+
+    async fn publish_notification(&self, notification: KafkaNotification) -> Result<()> {
+        let payload = serde_json::to_string(&notification)?;
+        let partition_key = notification.user_id.to_string();
+
+        let record = FutureRecord::to(&self.notification_topic)
+            .key(&partition_key)
+            .payload(&payload);
+
+        match self.producer.send(record, Duration::from_secs(5)).await {
+            Ok(_) => {
+                info!(
+                    recipient_id = %notification.user_id,
+                    topic = %self.notification_topic,
+                    "Published follow request notification to Kafka"
+                );
+                Ok(())
+            }
+            Err((err, _)) => {
+                warn!(
+                    error = ?err,
+                    recipient_id = %notification.user_id,
+                    "Failed to publish follow request notification to Kafka"
+                );
+                Err(anyhow::anyhow!("Failed to publish notification: {}", err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follow_request_envelope_shape() {
+        let requester_id = Uuid::new_v4();
+        let target_id = Uuid::new_v4();
+        let envelope = EventEnvelope::new_with_type(
+            "graph-service",
+            FOLLOW_REQUEST_CREATED,
+            FollowRequestEvent {
+                requester_id,
+                target_id,
+                occurred_at: Utc::now(),
+            },
+        );
+
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["event_type"], FOLLOW_REQUEST_CREATED);
+        assert_eq!(json["source"], "graph-service");
+        assert_eq!(json["data"]["requester_id"], requester_id.to_string());
+        assert_eq!(json["data"]["target_id"], target_id.to_string());
+    }
+}
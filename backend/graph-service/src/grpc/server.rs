@@ -1,4 +1,11 @@
-use crate::repository::{GraphRepository, GraphRepositoryTrait};
+use crate::domain::audience::{
+    self, AudienceList as DomainAudienceList, CLOSE_FRIENDS_ALIAS, CLOSE_FRIENDS_NAME,
+    MAX_CUSTOM_LISTS,
+};
+use crate::domain::edge::FollowOutcome;
+use crate::events::{spawn_publish, GraphEventProducer};
+use crate::repository::{AudienceListRepository, GraphRepository, GraphRepositoryTrait};
+use crate::suggestions::{SuggestionConfig, SuggestionEngine};
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 use tracing::{error, info};
@@ -16,15 +23,18 @@ pub struct GraphServiceImpl {
     repo: Arc<dyn GraphRepositoryTrait + Send + Sync>,
     /// Optional write token; if None => writes are disabled (read-only mode).
     write_token: Option<String>,
+    /// Optional Kafka producer for follow request / privacy events
+    events: Option<GraphEventProducer>,
+    /// People-you-may-know engine
+    suggestions: Arc<SuggestionEngine>,
+    /// Audience lists (PostgreSQL only; None in Neo4j-only mode)
+    audience: Option<AudienceListRepository>,
 }
 
 impl GraphServiceImpl {
     /// Create GraphServiceImpl with legacy Neo4j-only repository
     pub fn new(repo: GraphRepository, write_token: Option<String>) -> Self {
-        Self {
-            repo: Arc::new(repo),
-            write_token,
-        }
+        Self::new_with_trait(Arc::new(repo), write_token)
     }
 
     /// Create GraphServiceImpl with any repository implementing GraphRepositoryTrait
@@ -32,7 +42,126 @@ impl GraphServiceImpl {
         repo: Arc<dyn GraphRepositoryTrait + Send + Sync>,
         write_token: Option<String>,
     ) -> Self {
-        Self { repo, write_token }
+        let suggestions = Arc::new(SuggestionEngine::new(
+            repo.clone(),
+            None,
+            SuggestionConfig::default(),
+        ));
+        Self {
+            repo,
+            write_token,
+            events: None,
+            suggestions,
+            audience: None,
+        }
+    }
+
+    /// Enable the audience list APIs
+    pub fn with_audience_lists(mut self, audience: AudienceListRepository) -> Self {
+        self.audience = Some(audience);
+        self
+    }
+
+    /// Use a configured (typically cache-backed) suggestion engine
+    pub fn with_suggestion_engine(mut self, suggestions: Arc<SuggestionEngine>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// Publish follow request and privacy events to Kafka
+    pub fn with_event_producer(mut self, events: Option<GraphEventProducer>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// One approved event (and requester notification) per new follower
+    fn publish_approvals(&self, target_id: Uuid, approved: &[Uuid]) {
+        for &requester_id in approved {
+            spawn_publish(self.events.as_ref(), move |events| async move {
+                events
+                    .publish_follow_request_approved(requester_id, target_id)
+                    .await
+            });
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn audience(&self) -> Result<&AudienceListRepository, Status> {
+        self.audience.as_ref().ok_or_else(|| {
+            Status::unavailable("audience lists require PostgreSQL (dual-write mode)")
+        })
+    }
+
+    /// Resolve a list reference (UUID or "close_friends") owned by `owner_id`
+    async fn resolve_audience_list(
+        &self,
+        owner_id: Uuid,
+        list_ref: &str,
+    ) -> Result<DomainAudienceList, Status> {
+        let audience = self.audience()?;
+
+        if list_ref == CLOSE_FRIENDS_ALIAS {
+            return audience.close_friends_list(owner_id).await.map_err(|e| {
+                error!("Failed to load close friends list: {}", e);
+                Status::internal(format!("Failed to load close friends list: {}", e))
+            });
+        }
+
+        let list_id = Uuid::parse_str(list_ref)
+            .map_err(|e| Status::invalid_argument(format!("Invalid list_id: {}", e)))?;
+
+        match audience.get_list(owner_id, list_id).await {
+            Ok(Some(list)) => Ok(list),
+            Ok(None) => Err(Status::not_found("Audience list not found")),
+            Err(e) => {
+                error!("Failed to load audience list: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to load audience list: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn parse_audience_name(name: &str) -> Result<String, Status> {
+        let name = audience::normalize_list_name(name)
+            .ok_or_else(|| Status::invalid_argument("List name must be 1-64 characters"))?;
+        if name.eq_ignore_ascii_case(CLOSE_FRIENDS_NAME) {
+            return Err(Status::invalid_argument(format!(
+                "\"{}\" is reserved",
+                CLOSE_FRIENDS_NAME
+            )));
+        }
+        Ok(name)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn parse_uuid_batch(ids: &[String], field: &str) -> Result<Vec<Uuid>, Status> {
+        if ids.len() > 1000 {
+            return Err(Status::invalid_argument(format!(
+                "Max 1000 {} allowed per batch",
+                field
+            )));
+        }
+        ids.iter()
+            .map(|id_str| Uuid::parse_str(id_str))
+            .collect::<Result<Vec<Uuid>, _>>()
+            .map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", field, e)))
+    }
+
+    /// Map repository errors; duplicate list names become ALREADY_EXISTS
+    fn audience_status(operation: &str, e: anyhow::Error) -> Status {
+        let duplicate = e
+            .downcast_ref::<sqlx::Error>()
+            .and_then(|e| e.as_database_error())
+            .map(|e| e.is_unique_violation())
+            .unwrap_or(false);
+        if duplicate {
+            return Status::already_exists("An audience list with this name already exists");
+        }
+        error!("Failed to {}: {}", operation, e);
+        Status::internal(format!("Failed to {}: {}", operation, e))
     }
 
     #[allow(clippy::result_large_err)]
@@ -73,12 +202,28 @@ impl GraphService for GraphServiceImpl {
         let followee_id = Uuid::parse_str(&req.followee_id)
             .map_err(|e| Status::invalid_argument(format!("Invalid followee_id: {}", e)))?;
 
-        match self.repo.create_follow(follower_id, followee_id).await {
-            Ok(_) => {
-                info!("Created follow: {} -> {}", follower_id, followee_id);
+        match self.repo.follow_or_request(follower_id, followee_id).await {
+            Ok(outcome) => {
+                let message = match outcome {
+                    FollowOutcome::Followed => {
+                        info!("Created follow: {} -> {}", follower_id, followee_id);
+                        "Follow created successfully"
+                    }
+                    FollowOutcome::Requested => {
+                        info!("Created follow request: {} -> {}", follower_id, followee_id);
+                        spawn_publish(self.events.as_ref(), move |events| async move {
+                            events
+                                .publish_follow_request_created(follower_id, followee_id)
+                                .await
+                        });
+                        "Follow request sent"
+                    }
+                    FollowOutcome::AlreadyRequested => "Follow request already pending",
+                };
                 Ok(Response::new(CreateFollowResponse {
                     success: true,
-                    message: "Follow created successfully".to_string(),
+                    message: message.to_string(),
+                    requested: outcome.is_pending(),
                 }))
             }
             Err(e) => {
@@ -104,6 +249,22 @@ impl GraphService for GraphServiceImpl {
         match self.repo.delete_follow(follower_id, followee_id).await {
             Ok(_) => {
                 info!("Deleted follow: {} -> {}", follower_id, followee_id);
+
+                // Unfollowing a private account also withdraws a pending request
+                match self
+                    .repo
+                    .delete_follow_request(follower_id, followee_id)
+                    .await
+                {
+                    Ok(true) => spawn_publish(self.events.as_ref(), move |events| async move {
+                        events
+                            .publish_follow_request_cancelled(follower_id, followee_id)
+                            .await
+                    }),
+                    Ok(false) => {}
+                    Err(e) => error!("Failed to cancel follow request on unfollow: {}", e),
+                }
+
                 Ok(Response::new(DeleteFollowResponse {
                     success: true,
                     message: "Follow deleted successfully".to_string(),
@@ -678,4 +839,638 @@ impl GraphService for GraphServiceImpl {
             }
         }
     }
+
+    async fn approve_follow_request(
+        &self,
+        request: Request<ApproveFollowRequestRequest>,
+    ) -> Result<Response<ApproveFollowRequestResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let target_id = Uuid::parse_str(&req.target_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid target_id: {}", e)))?;
+
+        let requester_id = Uuid::parse_str(&req.requester_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid requester_id: {}", e)))?;
+
+        match self
+            .repo
+            .approve_follow_request(requester_id, target_id)
+            .await
+        {
+            Ok(true) => {
+                info!("Approved follow request: {} -> {}", requester_id, target_id);
+                spawn_publish(self.events.as_ref(), move |events| async move {
+                    events
+                        .publish_follow_request_approved(requester_id, target_id)
+                        .await
+                });
+                Ok(Response::new(ApproveFollowRequestResponse {
+                    success: true,
+                    message: "Follow request approved".to_string(),
+                }))
+            }
+            Ok(false) => Err(Status::not_found("No pending follow request")),
+            Err(e) => {
+                error!("Failed to approve follow request: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to approve follow request: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn deny_follow_request(
+        &self,
+        request: Request<DenyFollowRequestRequest>,
+    ) -> Result<Response<DenyFollowRequestResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let target_id = Uuid::parse_str(&req.target_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid target_id: {}", e)))?;
+
+        let requester_id = Uuid::parse_str(&req.requester_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid requester_id: {}", e)))?;
+
+        match self
+            .repo
+            .delete_follow_request(requester_id, target_id)
+            .await
+        {
+            Ok(true) => {
+                info!("Denied follow request: {} -> {}", requester_id, target_id);
+                spawn_publish(self.events.as_ref(), move |events| async move {
+                    events
+                        .publish_follow_request_denied(requester_id, target_id)
+                        .await
+                });
+                Ok(Response::new(DenyFollowRequestResponse {
+                    success: true,
+                    message: "Follow request denied".to_string(),
+                }))
+            }
+            Ok(false) => Err(Status::not_found("No pending follow request")),
+            Err(e) => {
+                error!("Failed to deny follow request: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to deny follow request: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn cancel_follow_request(
+        &self,
+        request: Request<CancelFollowRequestRequest>,
+    ) -> Result<Response<CancelFollowRequestResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let requester_id = Uuid::parse_str(&req.requester_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid requester_id: {}", e)))?;
+
+        let target_id = Uuid::parse_str(&req.target_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid target_id: {}", e)))?;
+
+        match self
+            .repo
+            .delete_follow_request(requester_id, target_id)
+            .await
+        {
+            Ok(true) => {
+                info!(
+                    "Cancelled follow request: {} -> {}",
+                    requester_id, target_id
+                );
+                spawn_publish(self.events.as_ref(), move |events| async move {
+                    events
+                        .publish_follow_request_cancelled(requester_id, target_id)
+                        .await
+                });
+                Ok(Response::new(CancelFollowRequestResponse {
+                    success: true,
+                    message: "Follow request cancelled".to_string(),
+                }))
+            }
+            Ok(false) => Err(Status::not_found("No pending follow request")),
+            Err(e) => {
+                error!("Failed to cancel follow request: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to cancel follow request: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn bulk_approve_follow_requests(
+        &self,
+        request: Request<BulkApproveFollowRequestsRequest>,
+    ) -> Result<Response<BulkApproveFollowRequestsResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let target_id = Uuid::parse_str(&req.target_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid target_id: {}", e)))?;
+
+        let requester_ids = if req.approve_all {
+            None
+        } else {
+            if req.requester_ids.len() > 1000 {
+                return Err(Status::invalid_argument(
+                    "Max 1000 requester_ids allowed per batch",
+                ));
+            }
+
+            let ids = req
+                .requester_ids
+                .iter()
+                .map(|id_str| Uuid::parse_str(id_str))
+                .collect::<Result<Vec<Uuid>, _>>()
+                .map_err(|e| Status::invalid_argument(format!("Invalid requester_id: {}", e)))?;
+            Some(ids)
+        };
+
+        match self
+            .repo
+            .approve_follow_requests(target_id, requester_ids)
+            .await
+        {
+            Ok(approved) => {
+                info!(
+                    "Bulk approved {} follow requests for {}",
+                    approved.len(),
+                    target_id
+                );
+                self.publish_approvals(target_id, &approved);
+
+                Ok(Response::new(BulkApproveFollowRequestsResponse {
+                    approved_ids: approved.iter().map(|id| id.to_string()).collect(),
+                }))
+            }
+            Err(e) => {
+                error!("Failed to bulk approve follow requests: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to bulk approve follow requests: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn get_incoming_follow_requests(
+        &self,
+        request: Request<GetIncomingFollowRequestsRequest>,
+    ) -> Result<Response<GetIncomingFollowRequestsResponse>, Status> {
+        let req = request.into_inner();
+
+        let user_id = Uuid::parse_str(&req.user_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid user_id: {}", e)))?;
+
+        let limit = if req.limit > 0 { req.limit } else { 50 };
+        let offset = req.offset;
+
+        match self
+            .repo
+            .get_incoming_follow_requests(user_id, limit, offset)
+            .await
+        {
+            Ok((requesters, total_count, has_more)) => {
+                Ok(Response::new(GetIncomingFollowRequestsResponse {
+                    requester_ids: requesters.iter().map(|id| id.to_string()).collect(),
+                    total_count,
+                    has_more,
+                }))
+            }
+            Err(e) => {
+                error!("Failed to get incoming follow requests: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to get incoming follow requests: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn get_outgoing_follow_requests(
+        &self,
+        request: Request<GetOutgoingFollowRequestsRequest>,
+    ) -> Result<Response<GetOutgoingFollowRequestsResponse>, Status> {
+        let req = request.into_inner();
+
+        let user_id = Uuid::parse_str(&req.user_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid user_id: {}", e)))?;
+
+        let limit = if req.limit > 0 { req.limit } else { 50 };
+        let offset = req.offset;
+
+        match self
+            .repo
+            .get_outgoing_follow_requests(user_id, limit, offset)
+            .await
+        {
+            Ok((targets, total_count, has_more)) => {
+                Ok(Response::new(GetOutgoingFollowRequestsResponse {
+                    target_ids: targets.iter().map(|id| id.to_string()).collect(),
+                    total_count,
+                    has_more,
+                }))
+            }
+            Err(e) => {
+                error!("Failed to get outgoing follow requests: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to get outgoing follow requests: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn set_account_privacy(
+        &self,
+        request: Request<SetAccountPrivacyRequest>,
+    ) -> Result<Response<SetAccountPrivacyResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let user_id = Uuid::parse_str(&req.user_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid user_id: {}", e)))?;
+
+        let was_private = self.repo.is_account_private(user_id).await.map_err(|e| {
+            error!("Failed to read account privacy: {}", e);
+            Status::internal(format!("Failed to read account privacy: {}", e))
+        })?;
+
+        self.repo
+            .set_account_private(user_id, req.is_private)
+            .await
+            .map_err(|e| {
+                error!("Failed to set account privacy: {}", e);
+                Status::internal(format!("Failed to set account privacy: {}", e))
+            })?;
+
+        // Going public: nobody is left waiting on approval
+        let approved = if was_private && !req.is_private {
+            self.repo
+                .approve_follow_requests(user_id, None)
+                .await
+                .map_err(|e| {
+                    error!("Failed to approve pending requests for {}: {}", user_id, e);
+                    Status::internal(format!("Failed to approve pending requests: {}", e))
+                })?
+        } else {
+            Vec::new()
+        };
+        self.publish_approvals(user_id, &approved);
+
+        if was_private != req.is_private {
+            info!(
+                "Account {} is now {} ({} requests auto-approved)",
+                user_id,
+                if req.is_private { "private" } else { "public" },
+                approved.len()
+            );
+            let is_private = req.is_private;
+            spawn_publish(self.events.as_ref(), move |events| async move {
+                events.publish_privacy_changed(user_id, is_private).await
+            });
+        }
+
+        Ok(Response::new(SetAccountPrivacyResponse {
+            success: true,
+            message: "Account privacy updated".to_string(),
+            approved_count: approved.len() as i32,
+        }))
+    }
+
+    async fn get_account_privacy(
+        &self,
+        request: Request<GetAccountPrivacyRequest>,
+    ) -> Result<Response<GetAccountPrivacyResponse>, Status> {
+        let req = request.into_inner();
+
+        let user_id = Uuid::parse_str(&req.user_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid user_id: {}", e)))?;
+
+        match self.repo.is_account_private(user_id).await {
+            Ok(is_private) => Ok(Response::new(GetAccountPrivacyResponse { is_private })),
+            Err(e) => {
+                error!("Failed to get account privacy: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to get account privacy: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn get_suggested_follows(
+        &self,
+        request: Request<GetSuggestedFollowsRequest>,
+    ) -> Result<Response<GetSuggestedFollowsResponse>, Status> {
+        let req = request.into_inner();
+
+        let user_id = Uuid::parse_str(&req.user_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid user_id: {}", e)))?;
+
+        let limit = if req.limit > 0 { req.limit.min(50) } else { 20 };
+
+        match self.suggestions.suggest(user_id, limit as usize).await {
+            Ok(suggested) => {
+                info!(
+                    "Get suggested follows for {}: {} results",
+                    user_id,
+                    suggested.len()
+                );
+
+                let suggestions = suggested
+                    .into_iter()
+                    .map(|s| SuggestedFollow {
+                        user_id: s.candidate.user_id.to_string(),
+                        score: s.candidate.score,
+                        mutual_count: s.candidate.mutual_count as i32,
+                        mutual_user_ids: s
+                            .candidate
+                            .mutual_ids
+                            .iter()
+                            .map(|id| id.to_string())
+                            .collect(),
+                        reason: s.reason,
+                        adamic_adar: s.candidate.adamic_adar,
+                        jaccard: s.candidate.jaccard,
+                        pagerank: s.candidate.pagerank,
+                    })
+                    .collect();
+
+                Ok(Response::new(GetSuggestedFollowsResponse { suggestions }))
+            }
+            Err(e) => {
+                error!("Failed to get suggested follows: {}", e);
+                Err(Status::internal(format!(
+                    "Failed to get suggested follows: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn create_audience_list(
+        &self,
+        request: Request<CreateAudienceListRequest>,
+    ) -> Result<Response<CreateAudienceListResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let owner_id = Uuid::parse_str(&req.owner_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid owner_id: {}", e)))?;
+        let name = Self::parse_audience_name(&req.name)?;
+        let audience = self.audience()?;
+
+        let count = audience
+            .count_custom_lists(owner_id)
+            .await
+            .map_err(|e| Self::audience_status("count audience lists", e))?;
+        if count >= MAX_CUSTOM_LISTS {
+            return Err(Status::failed_precondition(format!(
+                "Max {} audience lists per user",
+                MAX_CUSTOM_LISTS
+            )));
+        }
+
+        let list = audience
+            .create_list(owner_id, &name)
+            .await
+            .map_err(|e| Self::audience_status("create audience list", e))?;
+
+        info!("Created audience list {} for {}", list.id, owner_id);
+        Ok(Response::new(CreateAudienceListResponse {
+            list: Some(audience_list_to_proto(&list)),
+        }))
+    }
+
+    async fn get_audience_lists(
+        &self,
+        request: Request<GetAudienceListsRequest>,
+    ) -> Result<Response<GetAudienceListsResponse>, Status> {
+        let req = request.into_inner();
+
+        let owner_id = Uuid::parse_str(&req.owner_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid owner_id: {}", e)))?;
+        let audience = self.audience()?;
+
+        // Materialize the close friends list so it is always listed
+        audience
+            .close_friends_list(owner_id)
+            .await
+            .map_err(|e| Self::audience_status("load close friends list", e))?;
+
+        let lists = audience
+            .get_lists(owner_id)
+            .await
+            .map_err(|e| Self::audience_status("list audience lists", e))?;
+
+        Ok(Response::new(GetAudienceListsResponse {
+            lists: lists.iter().map(audience_list_to_proto).collect(),
+        }))
+    }
+
+    async fn update_audience_list(
+        &self,
+        request: Request<UpdateAudienceListRequest>,
+    ) -> Result<Response<UpdateAudienceListResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let owner_id = Uuid::parse_str(&req.owner_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid owner_id: {}", e)))?;
+        let name = Self::parse_audience_name(&req.name)?;
+
+        let list = self.resolve_audience_list(owner_id, &req.list_id).await?;
+        if list.kind == audience::AudienceListKind::CloseFriends {
+            return Err(Status::failed_precondition(
+                "The close friends list cannot be renamed",
+            ));
+        }
+
+        let audience = self.audience()?;
+        audience
+            .rename_list(owner_id, list.id, &name)
+            .await
+            .map_err(|e| Self::audience_status("rename audience list", e))?;
+
+        let list = self
+            .resolve_audience_list(owner_id, &list.id.to_string())
+            .await?;
+        Ok(Response::new(UpdateAudienceListResponse {
+            list: Some(audience_list_to_proto(&list)),
+        }))
+    }
+
+    async fn delete_audience_list(
+        &self,
+        request: Request<DeleteAudienceListRequest>,
+    ) -> Result<Response<DeleteAudienceListResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let owner_id = Uuid::parse_str(&req.owner_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid owner_id: {}", e)))?;
+
+        let list = self.resolve_audience_list(owner_id, &req.list_id).await?;
+        if list.kind == audience::AudienceListKind::CloseFriends {
+            return Err(Status::failed_precondition(
+                "The close friends list cannot be deleted",
+            ));
+        }
+
+        let deleted = self
+            .audience()?
+            .delete_list(owner_id, list.id)
+            .await
+            .map_err(|e| Self::audience_status("delete audience list", e))?;
+
+        Ok(Response::new(DeleteAudienceListResponse {
+            success: deleted,
+            message: if deleted {
+                "Audience list deleted".to_string()
+            } else {
+                "Audience list not found".to_string()
+            },
+        }))
+    }
+
+    async fn add_audience_list_members(
+        &self,
+        request: Request<AddAudienceListMembersRequest>,
+    ) -> Result<Response<AddAudienceListMembersResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let owner_id = Uuid::parse_str(&req.owner_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid owner_id: {}", e)))?;
+        let member_ids = Self::parse_uuid_batch(&req.member_ids, "member_ids")?;
+
+        let list = self.resolve_audience_list(owner_id, &req.list_id).await?;
+        let added = self
+            .audience()?
+            .add_members(list.id, &member_ids)
+            .await
+            .map_err(|e| Self::audience_status("add audience list members", e))?;
+
+        info!("Added {} members to audience list {}", added, list.id);
+        Ok(Response::new(AddAudienceListMembersResponse {
+            success: true,
+            message: format!("Added {} members", added),
+            changed_count: added as i32,
+        }))
+    }
+
+    async fn remove_audience_list_members(
+        &self,
+        request: Request<RemoveAudienceListMembersRequest>,
+    ) -> Result<Response<RemoveAudienceListMembersResponse>, Status> {
+        self.authorize_write(&request)?;
+        let req = request.into_inner();
+
+        let owner_id = Uuid::parse_str(&req.owner_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid owner_id: {}", e)))?;
+        let member_ids = Self::parse_uuid_batch(&req.member_ids, "member_ids")?;
+
+        let list = self.resolve_audience_list(owner_id, &req.list_id).await?;
+        let removed = self
+            .audience()?
+            .remove_members(list.id, &member_ids)
+            .await
+            .map_err(|e| Self::audience_status("remove audience list members", e))?;
+
+        info!("Removed {} members from audience list {}", removed, list.id);
+        Ok(Response::new(RemoveAudienceListMembersResponse {
+            success: true,
+            message: format!("Removed {} members", removed),
+            changed_count: removed as i32,
+        }))
+    }
+
+    async fn get_audience_list_members(
+        &self,
+        request: Request<GetAudienceListMembersRequest>,
+    ) -> Result<Response<GetAudienceListMembersResponse>, Status> {
+        let req = request.into_inner();
+
+        let owner_id = Uuid::parse_str(&req.owner_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid owner_id: {}", e)))?;
+
+        let limit = if req.limit > 0 {
+            req.limit.min(1000)
+        } else {
+            100
+        };
+        let offset = req.offset.max(0);
+
+        let list = self.resolve_audience_list(owner_id, &req.list_id).await?;
+        let (members, total) = self
+            .audience()?
+            .get_members(list.id, limit as i64, offset as i64)
+            .await
+            .map_err(|e| Self::audience_status("get audience list members", e))?;
+
+        Ok(Response::new(GetAudienceListMembersResponse {
+            has_more: (offset as i64 + members.len() as i64) < total,
+            member_ids: members.iter().map(|id| id.to_string()).collect(),
+            total_count: total as i32,
+        }))
+    }
+
+    async fn batch_check_audience_membership(
+        &self,
+        request: Request<BatchCheckAudienceMembershipRequest>,
+    ) -> Result<Response<BatchCheckAudienceMembershipResponse>, Status> {
+        let req = request.into_inner();
+
+        let viewer_id = Uuid::parse_str(&req.viewer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid viewer_id: {}", e)))?;
+        let list_ids = Self::parse_uuid_batch(&req.list_ids, "list_ids")?;
+        let owner_ids = Self::parse_uuid_batch(&req.close_friends_of, "close_friends_of")?;
+        let audience = self.audience()?;
+
+        let visible = audience
+            .visible_lists(viewer_id, &list_ids)
+            .await
+            .map_err(|e| Self::audience_status("check audience membership", e))?;
+        let close_friend_of = audience
+            .close_friend_of(viewer_id, &owner_ids)
+            .await
+            .map_err(|e| Self::audience_status("check close friends membership", e))?;
+
+        Ok(Response::new(BatchCheckAudienceMembershipResponse {
+            lists: list_ids
+                .iter()
+                .map(|id| (id.to_string(), visible.contains(id)))
+                .collect(),
+            close_friends_of: owner_ids
+                .iter()
+                .map(|id| {
+                    (
+                        id.to_string(),
+                        *id == viewer_id || close_friend_of.contains(id),
+                    )
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn audience_list_to_proto(list: &DomainAudienceList) -> AudienceList {
+    AudienceList {
+        id: list.id.to_string(),
+        owner_id: list.owner_id.to_string(),
+        name: list.name.clone(),
+        kind: list.kind.as_str().to_string(),
+        member_count: list.member_count,
+        created_at: list.created_at.timestamp(),
+        updated_at: list.updated_at.timestamp(),
+    }
 }
@@ -1,9 +1,11 @@
 pub mod config;
 pub mod consumers;
 pub mod domain;
+pub mod events;
 pub mod grpc;
 pub mod migration;
 pub mod repository;
+pub mod suggestions;
 
-pub use domain::edge::{Edge, EdgeType, GraphStats};
+pub use domain::edge::{Edge, EdgeType, FollowOutcome, GraphStats};
 pub use repository::{DualWriteRepository, GraphRepository, PostgresGraphRepository};
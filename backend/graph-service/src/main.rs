@@ -1,21 +1,30 @@
 mod config;
 mod consumers;
 mod domain;
+mod events;
 mod grpc;
 mod repository;
+mod suggestions;
 
 use anyhow::{anyhow, Context, Result};
 use config::Config;
 use consumers::{IdentityEventsConsumer, SocialEventsConsumer};
+use events::{GraphEventProducer, GraphEventProducerConfig};
+use graph_service::migration::consistency::{
+    start_consistency_verifier, ConsistencyConfig, ConsistencyVerifier,
+};
 use grpc::server::graph::graph_service_server::GraphServiceServer;
 use grpc::GraphServiceImpl;
+use nova_cache::graph::GraphCache;
 use nova_cache::NovaCache;
 use redis_utils::RedisPool;
 use repository::{
-    CachedGraphRepository, DualWriteRepository, GraphRepository, PostgresGraphRepository,
+    AudienceListRepository, CachedGraphRepository, DualWriteRepository, GraphRepository,
+    PostgresGraphRepository,
 };
 use sqlx::PgPool;
 use std::sync::Arc;
+use suggestions::{start_suggestion_precompute, SuggestionConfig, SuggestionEngine};
 use tonic::transport::Server;
 use tonic_health::server::health_reporter;
 use tracing::{error, info, warn};
@@ -49,6 +58,21 @@ async fn main() -> Result<()> {
         config.server.grpc_port, config.neo4j.uri, config.enable_dual_write, config.redis.enabled
     );
 
+    // Kafka producer for follow request / privacy events (optional)
+    let event_producer = match GraphEventProducerConfig::from_env() {
+        Some(cfg) => match GraphEventProducer::new(&cfg) {
+            Ok(producer) => Some(producer),
+            Err(e) => {
+                warn!(error = %e, "Failed to create Kafka producer - graph events disabled");
+                None
+            }
+        },
+        None => {
+            info!("KAFKA_BROKERS not set - graph events disabled");
+            None
+        }
+    };
+
     // Initialize Redis connection for caching
     let nova_cache = if config.redis.enabled {
         match RedisPool::connect(&config.redis.url, None).await {
@@ -87,8 +111,20 @@ async fn main() -> Result<()> {
 
         info!("✅ Connected to Neo4j successfully");
 
+        // Background verifier repairs Neo4j edges lost by best-effort mirroring;
+        // replicas share it through a PostgreSQL advisory lock
+        let consistency_config = ConsistencyConfig::from_env();
+        if consistency_config.enabled {
+            let verifier =
+                ConsistencyVerifier::new(pg_pool.clone(), neo4j_repo.graph(), consistency_config);
+            tokio::spawn(start_consistency_verifier(Arc::new(verifier)));
+        } else {
+            info!("Graph consistency verifier disabled by configuration");
+        }
+
         // Create PostgreSQL repository
         let postgres_repo = PostgresGraphRepository::new(pg_pool.clone());
+        let audience_repo = AudienceListRepository::new(pg_pool.clone());
         // Clone for identity events consumer
         let postgres_repo_for_consumer = PostgresGraphRepository::new(pg_pool);
 
@@ -131,14 +167,20 @@ async fn main() -> Result<()> {
         // Clone repo for Kafka consumer
         let repo_for_consumer = repo.clone();
 
+        let suggestion_engine = build_suggestion_engine(repo.clone(), nova_cache);
+
         // Create gRPC service
         let graph_service =
-            GraphServiceImpl::new_with_trait(repo, config.internal_write_token.clone());
+            GraphServiceImpl::new_with_trait(repo, config.internal_write_token.clone())
+                .with_event_producer(event_producer.clone())
+                .with_suggestion_engine(suggestion_engine)
+                .with_audience_lists(audience_repo);
 
         // Spawn Kafka consumers for social and identity events if configured
         spawn_kafka_consumers_if_enabled(
             repo_for_consumer,
             Some(postgres_repo_for_consumer),
+            event_producer,
             &config,
         );
 
@@ -167,6 +209,7 @@ async fn main() -> Result<()> {
         info!("Neo4j health check passed");
 
         // Wrap with caching layer if Redis is available
+        let suggestion_cache = nova_cache.clone();
         let graph_service = match nova_cache {
             Some(cache) => {
                 info!("🚀 Graph service initialized with Neo4j + caching");
@@ -178,9 +221,18 @@ async fn main() -> Result<()> {
                 let repo_for_consumer = repo.clone();
 
                 // Spawn Kafka consumers (no PostgreSQL in legacy mode)
-                spawn_kafka_consumers_if_enabled(repo_for_consumer, None, &config);
+                spawn_kafka_consumers_if_enabled(
+                    repo_for_consumer,
+                    None,
+                    event_producer.clone(),
+                    &config,
+                );
+
+                let suggestion_engine = build_suggestion_engine(repo.clone(), suggestion_cache);
 
                 GraphServiceImpl::new_with_trait(repo, config.internal_write_token.clone())
+                    .with_event_producer(event_producer)
+                    .with_suggestion_engine(suggestion_engine)
             }
             None => {
                 info!("🚀 Graph service initialized with Neo4j (no cache)");
@@ -191,9 +243,18 @@ async fn main() -> Result<()> {
                 let repo_for_consumer = repo.clone();
 
                 // Spawn Kafka consumers (no PostgreSQL in legacy mode)
-                spawn_kafka_consumers_if_enabled(repo_for_consumer, None, &config);
+                spawn_kafka_consumers_if_enabled(
+                    repo_for_consumer,
+                    None,
+                    event_producer.clone(),
+                    &config,
+                );
+
+                let suggestion_engine = build_suggestion_engine(repo.clone(), suggestion_cache);
 
                 GraphServiceImpl::new_with_trait(repo, config.internal_write_token.clone())
+                    .with_event_producer(event_producer)
+                    .with_suggestion_engine(suggestion_engine)
             }
         };
 
@@ -204,6 +265,26 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Build the people-you-may-know engine; candidates are precomputed only when Redis is available
+fn build_suggestion_engine(
+    repo: Arc<dyn repository::GraphRepositoryTrait + Send + Sync>,
+    nova_cache: Option<NovaCache>,
+) -> Arc<SuggestionEngine> {
+    let cache = nova_cache.map(GraphCache::new);
+    let precompute = cache.is_some();
+    let engine = Arc::new(SuggestionEngine::new(
+        repo,
+        cache,
+        SuggestionConfig::from_env(),
+    ));
+
+    if precompute {
+        tokio::spawn(start_suggestion_precompute(engine.clone()));
+    }
+
+    engine
+}
+
 async fn start_grpc_server(graph_service: GraphServiceImpl, config: &Config) -> Result<()> {
     // Setup health reporting
     let (mut health_reporter, health_service) = health_reporter();
@@ -272,6 +353,7 @@ async fn start_grpc_server(graph_service: GraphServiceImpl, config: &Config) ->
 fn spawn_kafka_consumers_if_enabled(
     repo: Arc<dyn repository::GraphRepositoryTrait + Send + Sync>,
     postgres_repo: Option<PostgresGraphRepository>,
+    event_producer: Option<GraphEventProducer>,
     _config: &Config,
 ) {
     let kafka_enabled = std::env::var("KAFKA_ENABLED")
@@ -310,6 +392,7 @@ fn spawn_kafka_consumers_if_enabled(
         tokio::spawn(async move {
             match SocialEventsConsumer::new(&brokers, &group_id, &topic, repo) {
                 Ok(consumer) => {
+                    let consumer = consumer.with_event_producer(event_producer);
                     info!("✅ Social Events Kafka consumer initialized");
                     if let Err(e) = consumer.start().await {
                         error!("Social Events Kafka consumer failed: {}", e);
@@ -0,0 +1,621 @@
+//! Continuous PostgreSQL / Neo4j consistency verification
+//!
+//! `DualWriteRepository` writes PostgreSQL (source of truth) first and mirrors
+//! the edge to Neo4j best-effort, so a failed or lost Neo4j write leaves the
+//! graph stores diverged until something notices. The verifier walks users in
+//! id order, diffs each chunk's outgoing FOLLOWS / MUTES / BLOCKS /
+//! REQUESTED_FOLLOW edges between the stores and repairs Neo4j from PostgreSQL.
+//!
+//! Divergent edges are re-read from PostgreSQL before repair so a write that
+//! committed between the two reads is not undone.
+//!
+//! Every replica starts the verifier, but a pass only runs while holding a
+//! PostgreSQL session advisory lock, so at most one replica verifies at a time.
+
+use crate::domain::edge::EdgeType;
+use anyhow::{Context, Result};
+use neo4rs::{query, Graph};
+use prometheus::{IntCounter, IntCounterVec, IntGauge, Opts};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// Edge types mirrored to Neo4j by `DualWriteRepository`
+pub const VERIFIED_EDGE_TYPES: [EdgeType; 4] = [
+    EdgeType::Follow,
+    EdgeType::Mute,
+    EdgeType::Block,
+    EdgeType::FollowRequest,
+];
+
+/// PostgreSQL advisory lock key held for the duration of a pass
+pub const CONSISTENCY_LOCK_KEY: i64 = 0x6772_6170_685f_6376; // "graph_cv"
+
+/// Directed edge (source -> target)
+pub type EdgePair = (Uuid, Uuid);
+
+/// PostgreSQL query returning the outgoing edges of `$1` users
+fn pg_edges_sql(edge_type: EdgeType) -> &'static str {
+    match edge_type {
+        EdgeType::Follow => {
+            "SELECT follower_id, following_id FROM follows WHERE follower_id = ANY($1)"
+        }
+        EdgeType::Mute => "SELECT muter_id, muted_id FROM mutes WHERE muter_id = ANY($1)",
+        EdgeType::Block => "SELECT blocker_id, blocked_id FROM blocks WHERE blocker_id = ANY($1)",
+        EdgeType::FollowRequest => {
+            "SELECT requester_id, target_id FROM follow_requests WHERE requester_id = ANY($1)"
+        }
+    }
+}
+
+/// Configuration for the background verifier
+#[derive(Debug, Clone)]
+pub struct ConsistencyConfig {
+    /// Run the verifier in the background (dual-write mode only)
+    pub enabled: bool,
+    /// Pause between full passes over the user table
+    pub interval: Duration,
+    /// Users whose edges are diffed per round trip
+    pub chunk_size: i64,
+    /// Pause between chunks to keep load on both stores low
+    pub chunk_delay: Duration,
+    /// Write repairs to Neo4j; when false divergence is only reported
+    pub repair: bool,
+}
+
+impl Default for ConsistencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: Duration::from_secs(3600),
+            chunk_size: 500,
+            chunk_delay: Duration::from_millis(100),
+            repair: true,
+        }
+    }
+}
+
+impl ConsistencyConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("GRAPH_CONSISTENCY_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            interval: std::env::var("GRAPH_CONSISTENCY_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+            chunk_size: std::env::var("GRAPH_CONSISTENCY_CHUNK_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n: &i64| *n > 0)
+                .unwrap_or(defaults.chunk_size),
+            chunk_delay: std::env::var("GRAPH_CONSISTENCY_CHUNK_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.chunk_delay),
+            repair: std::env::var("GRAPH_CONSISTENCY_REPAIR")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.repair),
+        }
+    }
+}
+
+/// Edges present in only one of the stores
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EdgeDiff {
+    /// In PostgreSQL but not Neo4j (Neo4j write lost)
+    pub missing: Vec<EdgePair>,
+    /// In Neo4j but not PostgreSQL (Neo4j delete lost)
+    pub extra: Vec<EdgePair>,
+}
+
+impl EdgeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+
+    /// Keep only divergence that still holds against a fresh PostgreSQL read
+    pub fn confirm(self, pg_now: &HashSet<EdgePair>) -> Self {
+        Self {
+            missing: self
+                .missing
+                .into_iter()
+                .filter(|e| pg_now.contains(e))
+                .collect(),
+            extra: self
+                .extra
+                .into_iter()
+                .filter(|e| !pg_now.contains(e))
+                .collect(),
+        }
+    }
+}
+
+/// Diff PostgreSQL (expected) against Neo4j (actual); output is sorted
+pub fn diff_edges(pg: &HashSet<EdgePair>, neo4j: &HashSet<EdgePair>) -> EdgeDiff {
+    let mut missing: Vec<EdgePair> = pg.difference(neo4j).copied().collect();
+    let mut extra: Vec<EdgePair> = neo4j.difference(pg).copied().collect();
+    missing.sort();
+    extra.sort();
+    EdgeDiff { missing, extra }
+}
+
+/// Per edge type totals for one verification pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeTypeReport {
+    pub edge_type: EdgeType,
+    pub checked: u64,
+    pub missing: u64,
+    pub extra: u64,
+    pub repaired: u64,
+}
+
+/// Result of a full verification pass
+#[derive(Debug, Clone)]
+pub struct ConsistencyReport {
+    pub users_checked: u64,
+    pub chunks_failed: u64,
+    pub edge_types: Vec<EdgeTypeReport>,
+    pub elapsed: Duration,
+}
+
+impl Default for ConsistencyReport {
+    fn default() -> Self {
+        Self {
+            users_checked: 0,
+            chunks_failed: 0,
+            edge_types: VERIFIED_EDGE_TYPES
+                .iter()
+                .map(|&edge_type| EdgeTypeReport {
+                    edge_type,
+                    checked: 0,
+                    missing: 0,
+                    extra: 0,
+                    repaired: 0,
+                })
+                .collect(),
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl ConsistencyReport {
+    /// Total missing + extra edges found
+    pub fn divergent_edges(&self) -> u64 {
+        self.edge_types.iter().map(|r| r.missing + r.extra).sum()
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.divergent_edges() == 0 && self.chunks_failed == 0
+    }
+
+    fn entry(&mut self, edge_type: EdgeType) -> &mut EdgeTypeReport {
+        self.edge_types
+            .iter_mut()
+            .find(|r| r.edge_type == edge_type)
+            .expect("report covers every verified edge type")
+    }
+}
+
+/// Prometheus metrics for the verifier
+pub struct ConsistencyMetrics {
+    pub users_checked_total: IntCounter,
+    pub divergence_total: IntCounterVec,
+    pub repaired_total: IntCounterVec,
+    pub last_pass_divergence: IntGauge,
+    pub errors_total: IntCounter,
+}
+
+impl ConsistencyMetrics {
+    fn new() -> Self {
+        let registry = prometheus::default_registry();
+
+        let users_checked_total = IntCounter::new(
+            "graph_consistency_users_checked_total",
+            "Users whose edges were compared between PostgreSQL and Neo4j",
+        )
+        .expect("valid metric for graph_consistency_users_checked_total");
+
+        let divergence_total = IntCounterVec::new(
+            Opts::new(
+                "graph_consistency_divergence_total",
+                "Edges found in only one of PostgreSQL and Neo4j",
+            ),
+            &["edge_type", "kind"],
+        )
+        .expect("valid metric for graph_consistency_divergence_total");
+
+        let repaired_total = IntCounterVec::new(
+            Opts::new(
+                "graph_consistency_repaired_total",
+                "Divergent Neo4j edges repaired from PostgreSQL",
+            ),
+            &["edge_type"],
+        )
+        .expect("valid metric for graph_consistency_repaired_total");
+
+        let last_pass_divergence = IntGauge::new(
+            "graph_consistency_last_pass_divergence",
+            "Divergent edges found by the most recent full pass",
+        )
+        .expect("valid metric for graph_consistency_last_pass_divergence");
+
+        let errors_total = IntCounter::new(
+            "graph_consistency_errors_total",
+            "Verification chunks that failed",
+        )
+        .expect("valid metric for graph_consistency_errors_total");
+
+        for metric in [
+            Box::new(users_checked_total.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(divergence_total.clone()),
+            Box::new(repaired_total.clone()),
+            Box::new(last_pass_divergence.clone()),
+            Box::new(errors_total.clone()),
+        ] {
+            let _ = registry.register(metric);
+        }
+
+        Self {
+            users_checked_total,
+            divergence_total,
+            repaired_total,
+            last_pass_divergence,
+            errors_total,
+        }
+    }
+
+    /// Process-wide metrics (registered once)
+    pub fn global() -> &'static ConsistencyMetrics {
+        static METRICS: OnceLock<ConsistencyMetrics> = OnceLock::new();
+        METRICS.get_or_init(ConsistencyMetrics::new)
+    }
+
+    fn record_diff(&self, edge_type: EdgeType, diff: &EdgeDiff) {
+        let label = edge_type.as_str();
+        self.divergence_total
+            .with_label_values(&[label, "missing"])
+            .inc_by(diff.missing.len() as u64);
+        self.divergence_total
+            .with_label_values(&[label, "extra"])
+            .inc_by(diff.extra.len() as u64);
+    }
+}
+
+/// Diffs graph edges between PostgreSQL and Neo4j and repairs Neo4j
+pub struct ConsistencyVerifier {
+    pg_pool: PgPool,
+    neo4j_graph: Arc<Graph>,
+    config: ConsistencyConfig,
+}
+
+impl ConsistencyVerifier {
+    pub fn new(pg_pool: PgPool, neo4j_graph: Arc<Graph>, config: ConsistencyConfig) -> Self {
+        Self {
+            pg_pool,
+            neo4j_graph,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &ConsistencyConfig {
+        &self.config
+    }
+
+    /// Walk every user once; a failing chunk is counted and skipped
+    pub async fn run_pass(&self) -> Result<ConsistencyReport> {
+        let started = Instant::now();
+        let metrics = ConsistencyMetrics::global();
+        let mut report = ConsistencyReport::default();
+        let mut cursor = Uuid::nil();
+
+        loop {
+            let user_ids = self.next_chunk(cursor).await?;
+            let Some(&last) = user_ids.last() else {
+                break;
+            };
+
+            if let Err(e) = self.verify_chunk(&user_ids, &mut report).await {
+                error!(error = ?e, after = %cursor, "Consistency check failed for user chunk");
+                metrics.errors_total.inc();
+                report.chunks_failed += 1;
+            }
+
+            report.users_checked += user_ids.len() as u64;
+            metrics.users_checked_total.inc_by(user_ids.len() as u64);
+            cursor = last;
+
+            if (user_ids.len() as i64) < self.config.chunk_size {
+                break;
+            }
+            if !self.config.chunk_delay.is_zero() {
+                tokio::time::sleep(self.config.chunk_delay).await;
+            }
+        }
+
+        report.elapsed = started.elapsed();
+        metrics
+            .last_pass_divergence
+            .set(report.divergent_edges() as i64);
+
+        Ok(report)
+    }
+
+    /// Run a pass if no other replica is running one
+    ///
+    /// Returns `None` when the advisory lock is held elsewhere. The lock is
+    /// session scoped, so it is taken on a dedicated connection and released
+    /// explicitly; if the unlock fails the connection is closed instead of
+    /// being returned to the pool still holding the lock.
+    pub async fn try_run_pass(&self) -> Result<Option<ConsistencyReport>> {
+        let mut conn = self
+            .pg_pool
+            .acquire()
+            .await
+            .context("Failed to acquire connection for consistency lock")?;
+
+        let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(CONSISTENCY_LOCK_KEY)
+            .fetch_one(&mut *conn)
+            .await
+            .context("Failed to take consistency lock")?;
+        if !locked {
+            return Ok(None);
+        }
+
+        let report = self.run_pass().await;
+
+        if let Err(e) = sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(CONSISTENCY_LOCK_KEY)
+            .execute(&mut *conn)
+            .await
+        {
+            warn!(error = ?e, "Failed to release consistency lock, closing connection");
+            drop(conn.detach());
+        }
+
+        report.map(Some)
+    }
+
+    /// Next page of user ids after `cursor` (soft-deleted users included: their
+    /// edges are still mirrored)
+    async fn next_chunk(&self, cursor: Uuid) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar("SELECT id FROM users WHERE id > $1 ORDER BY id LIMIT $2")
+            .bind(cursor)
+            .bind(self.config.chunk_size)
+            .fetch_all(&self.pg_pool)
+            .await
+            .context("Failed to page users for consistency check")
+    }
+
+    async fn verify_chunk(&self, user_ids: &[Uuid], report: &mut ConsistencyReport) -> Result<()> {
+        let metrics = ConsistencyMetrics::global();
+
+        for edge_type in VERIFIED_EDGE_TYPES {
+            let pg = self.load_pg_edges(edge_type, user_ids).await?;
+            let neo4j = self.load_neo4j_edges(edge_type, user_ids).await?;
+
+            let entry = report.entry(edge_type);
+            entry.checked += pg.len() as u64;
+
+            let diff = diff_edges(&pg, &neo4j);
+            if diff.is_empty() {
+                continue;
+            }
+
+            let diff = diff.confirm(&self.load_pg_edges(edge_type, user_ids).await?);
+            if diff.is_empty() {
+                continue;
+            }
+
+            warn!(
+                edge_type = edge_type.as_str(),
+                missing = diff.missing.len(),
+                extra = diff.extra.len(),
+                "Neo4j diverged from PostgreSQL"
+            );
+            metrics.record_diff(edge_type, &diff);
+            entry.missing += diff.missing.len() as u64;
+            entry.extra += diff.extra.len() as u64;
+
+            if self.config.repair {
+                let repaired = self.repair(edge_type, &diff).await?;
+                metrics
+                    .repaired_total
+                    .with_label_values(&[edge_type.as_str()])
+                    .inc_by(repaired);
+                entry.repaired += repaired;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn load_pg_edges(
+        &self,
+        edge_type: EdgeType,
+        user_ids: &[Uuid],
+    ) -> Result<HashSet<EdgePair>> {
+        let rows: Vec<EdgePair> = sqlx::query_as(pg_edges_sql(edge_type))
+            .bind(user_ids)
+            .fetch_all(&self.pg_pool)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to load {} edges from PostgreSQL",
+                    edge_type.as_str()
+                )
+            })?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn load_neo4j_edges(
+        &self,
+        edge_type: EdgeType,
+        user_ids: &[Uuid],
+    ) -> Result<HashSet<EdgePair>> {
+        let cypher = format!(
+            "MATCH (a:User)-[:{}]->(b:User) WHERE a.id IN $ids RETURN a.id AS src, b.id AS dst",
+            edge_type.as_str()
+        );
+        let ids: Vec<String> = user_ids.iter().map(|id| id.to_string()).collect();
+
+        let mut result = self
+            .neo4j_graph
+            .execute(query(&cypher).param("ids", ids))
+            .await
+            .with_context(|| format!("Failed to load {} edges from Neo4j", edge_type.as_str()))?;
+
+        let mut edges = HashSet::new();
+        while let Some(row) = result.next().await? {
+            let src: String = row.get("src")?;
+            let dst: String = row.get("dst")?;
+            match (Uuid::parse_str(&src), Uuid::parse_str(&dst)) {
+                (Ok(src), Ok(dst)) => {
+                    edges.insert((src, dst));
+                }
+                _ => debug!(%src, %dst, "Skipping Neo4j edge with non-UUID endpoint"),
+            }
+        }
+
+        Ok(edges)
+    }
+
+    /// Create missing edges and delete extra ones; returns edges written
+    async fn repair(&self, edge_type: EdgeType, diff: &EdgeDiff) -> Result<u64> {
+        let rel = edge_type.as_str();
+        let mut repaired = 0;
+
+        if !diff.missing.is_empty() {
+            let cypher = format!(
+                r#"
+                UNWIND $edges AS e
+                MERGE (a:User {{id: e[0]}})
+                ON CREATE SET a.created_at = timestamp()
+                MERGE (b:User {{id: e[1]}})
+                ON CREATE SET b.created_at = timestamp()
+                MERGE (a)-[r:{rel}]->(b)
+                ON CREATE SET r.created_at = timestamp()
+                "#
+            );
+            self.neo4j_graph
+                .run(query(&cypher).param("edges", edge_params(&diff.missing)))
+                .await
+                .with_context(|| format!("Failed to create missing {} edges", rel))?;
+            repaired += diff.missing.len() as u64;
+        }
+
+        if !diff.extra.is_empty() {
+            let cypher = format!(
+                r#"
+                UNWIND $edges AS e
+                MATCH (a:User {{id: e[0]}})-[r:{rel}]->(b:User {{id: e[1]}})
+                DELETE r
+                "#
+            );
+            self.neo4j_graph
+                .run(query(&cypher).param("edges", edge_params(&diff.extra)))
+                .await
+                .with_context(|| format!("Failed to delete extra {} edges", rel))?;
+            repaired += diff.extra.len() as u64;
+        }
+
+        Ok(repaired)
+    }
+}
+
+fn edge_params(edges: &[EdgePair]) -> Vec<Vec<String>> {
+    edges
+        .iter()
+        .map(|(src, dst)| vec![src.to_string(), dst.to_string()])
+        .collect()
+}
+
+/// Background job: full verification pass every `interval`
+pub async fn start_consistency_verifier(verifier: Arc<ConsistencyVerifier>) {
+    let interval = verifier.config().interval;
+    info!(
+        interval_secs = interval.as_secs(),
+        chunk_size = verifier.config().chunk_size,
+        repair = verifier.config().repair,
+        "Starting graph consistency verifier"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+        match verifier.try_run_pass().await {
+            Ok(None) => debug!("Graph consistency pass skipped: another replica holds the lock"),
+            Ok(Some(report)) if report.is_consistent() => debug!(
+                users_checked = report.users_checked,
+                elapsed_ms = report.elapsed.as_millis() as u64,
+                "Graph stores consistent"
+            ),
+            Ok(Some(report)) => warn!(
+                users_checked = report.users_checked,
+                divergent_edges = report.divergent_edges(),
+                chunks_failed = report.chunks_failed,
+                elapsed_ms = report.elapsed.as_millis() as u64,
+                "Graph consistency pass found divergence"
+            ),
+            Err(e) => {
+                ConsistencyMetrics::global().errors_total.inc();
+                error!(error = ?e, "Graph consistency pass failed");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    #[test]
+    fn test_diff_edges_reports_missing_and_extra() {
+        let pg = HashSet::from([(id(1), id(2)), (id(1), id(3)), (id(2), id(3))]);
+        let neo4j = HashSet::from([(id(1), id(2)), (id(2), id(3)), (id(3), id(1))]);
+
+        let diff = diff_edges(&pg, &neo4j);
+        assert_eq!(diff.missing, vec![(id(1), id(3))]);
+        assert_eq!(diff.extra, vec![(id(3), id(1))]);
+        assert!(diff_edges(&pg, &pg).is_empty());
+    }
+
+    #[test]
+    fn test_confirm_drops_divergence_resolved_by_concurrent_writes() {
+        let diff = EdgeDiff {
+            missing: vec![(id(1), id(2)), (id(1), id(3))],
+            extra: vec![(id(4), id(5)), (id(4), id(6))],
+        };
+        // (1,3) was unfollowed and (4,6) followed after the first read
+        let pg_now = HashSet::from([(id(1), id(2)), (id(4), id(6))]);
+
+        let confirmed = diff.confirm(&pg_now);
+        assert_eq!(confirmed.missing, vec![(id(1), id(2))]);
+        assert_eq!(confirmed.extra, vec![(id(4), id(5))]);
+    }
+
+    #[test]
+    fn test_report_totals() {
+        let mut report = ConsistencyReport::default();
+        assert!(report.is_consistent());
+
+        report.entry(EdgeType::Follow).missing = 2;
+        report.entry(EdgeType::Block).extra = 1;
+        assert_eq!(report.divergent_edges(), 3);
+        assert!(!report.is_consistent());
+    }
+}
@@ -1 +1,2 @@
+pub mod consistency;
 pub mod neo4j_backfill;
@@ -0,0 +1,336 @@
+use crate::domain::audience::{AudienceList, AudienceListKind, CLOSE_FRIENDS_NAME};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use tracing::debug;
+use uuid::Uuid;
+
+/// Row shape shared by every list query (member_count is computed)
+type ListRow = (
+    Uuid,
+    Uuid,
+    String,
+    String,
+    i64,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
+const LIST_COLUMNS: &str = r#"
+    l.id, l.owner_id, l.name, l.kind,
+    (SELECT COUNT(*) FROM audience_list_members m WHERE m.list_id = l.id) AS member_count,
+    l.created_at, l.updated_at
+"#;
+
+fn to_list(row: ListRow) -> AudienceList {
+    let (id, owner_id, name, kind, member_count, created_at, updated_at) = row;
+    AudienceList {
+        id,
+        owner_id,
+        name,
+        kind: AudienceListKind::parse(&kind).unwrap_or(AudienceListKind::Custom),
+        member_count,
+        created_at,
+        updated_at,
+    }
+}
+
+/// PostgreSQL repository for audience lists (close friends / custom lists)
+///
+/// Lists live only in PostgreSQL: they are read by owner or by viewer, never
+/// traversed, so they are not mirrored to Neo4j.
+#[derive(Clone)]
+pub struct AudienceListRepository {
+    pool: PgPool,
+}
+
+impl AudienceListRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Ensure users exist in the local users table (see PostgresGraphRepository)
+    async fn ensure_users_exist(&self, user_ids: &[Uuid]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, username, created_at, updated_at)
+            SELECT u, u::text, NOW(), NOW() FROM UNNEST($1::uuid[]) AS u
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(user_ids)
+        .execute(&self.pool)
+        .await
+        .context("Failed to ensure users exist in PostgreSQL")?;
+        Ok(())
+    }
+
+    /// Owner's close friends list, created on first use
+    pub async fn close_friends_list(&self, owner_id: Uuid) -> Result<AudienceList> {
+        self.ensure_users_exist(&[owner_id]).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO audience_lists (owner_id, name, kind)
+            VALUES ($1, $2, 'close_friends')
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(owner_id)
+        .bind(CLOSE_FRIENDS_NAME)
+        .execute(&self.pool)
+        .await
+        .context("Failed to create close friends list")?;
+
+        let row: ListRow = sqlx::query_as(&format!(
+            "SELECT {LIST_COLUMNS} FROM audience_lists l WHERE l.owner_id = $1 AND l.kind = 'close_friends'"
+        ))
+        .bind(owner_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to load close friends list")?;
+
+        Ok(to_list(row))
+    }
+
+    /// Create a custom list; a duplicate name surfaces as a unique violation
+    pub async fn create_list(&self, owner_id: Uuid, name: &str) -> Result<AudienceList> {
+        self.ensure_users_exist(&[owner_id]).await?;
+
+        let row: ListRow = sqlx::query_as(&format!(
+            r#"
+            WITH l AS (
+                INSERT INTO audience_lists (owner_id, name, kind)
+                VALUES ($1, $2, 'custom')
+                RETURNING *
+            )
+            SELECT {LIST_COLUMNS} FROM l
+            "#
+        ))
+        .bind(owner_id)
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to create audience list")?;
+
+        debug!("Created audience list {} for {}", row.0, owner_id);
+        Ok(to_list(row))
+    }
+
+    /// Number of custom lists owned by the user
+    pub async fn count_custom_lists(&self, owner_id: Uuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audience_lists WHERE owner_id = $1 AND kind = 'custom'",
+        )
+        .bind(owner_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to count audience lists")?;
+        Ok(count)
+    }
+
+    /// All lists owned by the user, close friends first
+    pub async fn get_lists(&self, owner_id: Uuid) -> Result<Vec<AudienceList>> {
+        let rows: Vec<ListRow> = sqlx::query_as(&format!(
+            r#"
+            SELECT {LIST_COLUMNS} FROM audience_lists l
+            WHERE l.owner_id = $1
+            ORDER BY (l.kind = 'close_friends') DESC, l.created_at ASC
+            "#
+        ))
+        .bind(owner_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list audience lists")?;
+
+        Ok(rows.into_iter().map(to_list).collect())
+    }
+
+    /// A list, only if owned by `owner_id`
+    pub async fn get_list(&self, owner_id: Uuid, list_id: Uuid) -> Result<Option<AudienceList>> {
+        let row: Option<ListRow> = sqlx::query_as(&format!(
+            "SELECT {LIST_COLUMNS} FROM audience_lists l WHERE l.id = $1 AND l.owner_id = $2"
+        ))
+        .bind(list_id)
+        .bind(owner_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to load audience list")?;
+
+        Ok(row.map(to_list))
+    }
+
+    /// Rename a custom list (returns false if not found / not custom)
+    pub async fn rename_list(&self, owner_id: Uuid, list_id: Uuid, name: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE audience_lists SET name = $3, updated_at = NOW()
+            WHERE id = $1 AND owner_id = $2 AND kind = 'custom'
+            "#,
+        )
+        .bind(list_id)
+        .bind(owner_id)
+        .bind(name)
+        .execute(&self.pool)
+        .await
+        .context("Failed to rename audience list")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a custom list and its memberships (returns false if not found / not custom)
+    pub async fn delete_list(&self, owner_id: Uuid, list_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM audience_lists WHERE id = $1 AND owner_id = $2 AND kind = 'custom'",
+        )
+        .bind(list_id)
+        .bind(owner_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to delete audience list")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Add members to a list (the owner is skipped); returns newly added count
+    pub async fn add_members(&self, list_id: Uuid, member_ids: &[Uuid]) -> Result<u64> {
+        if member_ids.is_empty() {
+            return Ok(0);
+        }
+        self.ensure_users_exist(member_ids).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO audience_list_members (list_id, member_id)
+            SELECT l.id, m FROM audience_lists l, UNNEST($2::uuid[]) AS m
+            WHERE l.id = $1 AND m <> l.owner_id
+            ON CONFLICT (list_id, member_id) DO NOTHING
+            "#,
+        )
+        .bind(list_id)
+        .bind(member_ids)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to add audience list members")?;
+
+        sqlx::query("UPDATE audience_lists SET updated_at = NOW() WHERE id = $1")
+            .bind(list_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Remove members from a list; returns removed count
+    pub async fn remove_members(&self, list_id: Uuid, member_ids: &[Uuid]) -> Result<u64> {
+        if member_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            "DELETE FROM audience_list_members WHERE list_id = $1 AND member_id = ANY($2)",
+        )
+        .bind(list_id)
+        .bind(member_ids)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to remove audience list members")?;
+
+        sqlx::query("UPDATE audience_lists SET updated_at = NOW() WHERE id = $1")
+            .bind(list_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Members of a list, most recently added first
+    pub async fn get_members(
+        &self,
+        list_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Uuid>, i64)> {
+        let total: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM audience_list_members WHERE list_id = $1")
+                .bind(list_id)
+                .fetch_one(&self.pool)
+                .await
+                .context("Failed to count audience list members")?;
+
+        let members: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT member_id FROM audience_list_members
+            WHERE list_id = $1
+            ORDER BY added_at DESC, member_id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(list_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list audience list members")?;
+
+        Ok((members, total))
+    }
+
+    /// Lists among `list_ids` that the viewer may see: member or owner
+    pub async fn visible_lists(&self, viewer_id: Uuid, list_ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        if list_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let visible: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT l.id FROM audience_lists l
+            WHERE l.id = ANY($2)
+              AND (l.owner_id = $1 OR EXISTS (
+                    SELECT 1 FROM audience_list_members m
+                    WHERE m.list_id = l.id AND m.member_id = $1
+              ))
+            "#,
+        )
+        .bind(viewer_id)
+        .bind(list_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to check audience list membership")?;
+
+        Ok(visible.into_iter().collect())
+    }
+
+    /// Owners among `owner_ids` whose close friends list includes the viewer
+    pub async fn close_friend_of(
+        &self,
+        viewer_id: Uuid,
+        owner_ids: &[Uuid],
+    ) -> Result<HashSet<Uuid>> {
+        if owner_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let owners: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT l.owner_id FROM audience_lists l
+            JOIN audience_list_members m ON m.list_id = l.id
+            WHERE l.kind = 'close_friends' AND l.owner_id = ANY($2) AND m.member_id = $1
+            "#,
+        )
+        .bind(viewer_id)
+        .bind(owner_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to check close friends membership")?;
+
+        Ok(owners.into_iter().collect())
+    }
+}
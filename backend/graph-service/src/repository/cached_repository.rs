@@ -3,10 +3,11 @@
 //! Wraps any GraphRepositoryTrait implementation with Redis caching.
 
 use super::GraphRepositoryTrait;
+use crate::domain::edge::FollowOutcome;
 use anyhow::Result;
 use nova_cache::graph::GraphCache;
 use nova_cache::NovaCache;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::{debug, warn};
 use uuid::Uuid;
@@ -43,25 +44,6 @@ impl CachedGraphRepository {
 
 #[async_trait::async_trait]
 impl GraphRepositoryTrait for CachedGraphRepository {
-    async fn create_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
-        // Execute the write
-        self.inner.create_follow(follower_id, followee_id).await?;
-
-        // Invalidate caches
-        if self.enabled {
-            if let Err(e) = self.cache.on_follow_created(follower_id, followee_id).await {
-                warn!(
-                    error = %e,
-                    follower = %follower_id,
-                    followee = %followee_id,
-                    "Failed to invalidate cache after follow creation"
-                );
-            }
-        }
-
-        Ok(())
-    }
-
     async fn delete_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
         // Execute the delete
         self.inner.delete_follow(follower_id, followee_id).await?;
@@ -434,6 +416,114 @@ impl GraphRepositoryTrait for CachedGraphRepository {
             .await
     }
 
+    async fn set_account_private(&self, user_id: Uuid, is_private: bool) -> Result<()> {
+        self.inner.set_account_private(user_id, is_private).await
+    }
+
+    async fn is_account_private(&self, user_id: Uuid) -> Result<bool> {
+        self.inner.is_account_private(user_id).await
+    }
+
+    async fn delete_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool> {
+        self.inner
+            .delete_follow_request(requester_id, target_id)
+            .await
+    }
+
+    async fn follow_or_request(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<FollowOutcome> {
+        let outcome = self
+            .inner
+            .follow_or_request(follower_id, followee_id)
+            .await?;
+
+        // Only a direct follow adds an edge
+        if self.enabled && outcome == FollowOutcome::Followed {
+            if let Err(e) = self.cache.on_follow_created(follower_id, followee_id).await {
+                warn!(
+                    error = %e,
+                    follower = %follower_id,
+                    followee = %followee_id,
+                    "Failed to invalidate cache after follow creation"
+                );
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    async fn approve_follow_requests(
+        &self,
+        target_id: Uuid,
+        requester_ids: Option<Vec<Uuid>>,
+    ) -> Result<Vec<Uuid>> {
+        let approved = self
+            .inner
+            .approve_follow_requests(target_id, requester_ids)
+            .await?;
+
+        // Each approval is a new follow edge
+        if self.enabled {
+            for requester_id in &approved {
+                if let Err(e) = self.cache.on_follow_created(*requester_id, target_id).await {
+                    warn!(
+                        error = %e,
+                        follower = %requester_id,
+                        followee = %target_id,
+                        "Failed to invalidate cache after follow request approval"
+                    );
+                }
+            }
+        }
+
+        Ok(approved)
+    }
+
+    async fn get_incoming_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        self.inner
+            .get_incoming_follow_requests(user_id, limit, offset)
+            .await
+    }
+
+    async fn get_outgoing_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        self.inner
+            .get_outgoing_follow_requests(user_id, limit, offset)
+            .await
+    }
+
+    async fn get_follower_counts(&self, user_ids: &[Uuid]) -> Result<HashMap<Uuid, i64>> {
+        self.inner.get_follower_counts(user_ids).await
+    }
+
+    async fn get_following_batch(
+        &self,
+        user_ids: &[Uuid],
+        limit: i32,
+    ) -> Result<HashMap<Uuid, (Vec<Uuid>, i64)>> {
+        self.inner.get_following_batch(user_ids, limit).await
+    }
+
+    async fn get_hidden_users(&self, viewer_id: Uuid, user_ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        self.inner.get_hidden_users(viewer_id, user_ids).await
+    }
+
+    async fn get_usernames(&self, user_ids: &[Uuid]) -> Result<HashMap<Uuid, String>> {
+        self.inner.get_usernames(user_ids).await
+    }
+
     async fn health_check(&self) -> Result<()> {
         self.inner.health_check().await
     }
@@ -1,7 +1,7 @@
 use super::graph_repository::GraphRepository;
 use super::postgres_repository::PostgresGraphRepository;
 use super::GraphRepositoryTrait;
-use crate::domain::edge::GraphStats;
+use crate::domain::edge::{FollowOutcome, GraphStats};
 use anyhow::{Context, Result};
 use std::sync::Arc;
 use tracing::{error, warn};
@@ -54,6 +54,23 @@ impl DualWriteRepository {
         pg_write.await.context("PostgreSQL write failed")?;
 
         // Step 2: Write to Neo4j (best effort)
+        self.mirror_to_neo4j(operation, edge_desc, neo4j_write, pg_rollback)
+            .await
+    }
+
+    /// Helper: Mirror an already-applied PostgreSQL write to Neo4j.
+    /// Used directly by writes whose PostgreSQL result decides what to mirror.
+    async fn mirror_to_neo4j<Neo4jFut, RollbackFut>(
+        &self,
+        operation: &str,
+        edge_desc: String,
+        neo4j_write: Neo4jFut,
+        pg_rollback: Option<RollbackFut>,
+    ) -> Result<()>
+    where
+        Neo4jFut: std::future::Future<Output = Result<()>>,
+        RollbackFut: std::future::Future<Output = Result<()>>,
+    {
         if let Err(e) = neo4j_write.await {
             error!("Neo4j {} failed for {}: {}", operation, edge_desc, e);
             tracing::warn!("neo4j_write_failure{{operation=\"{}\"}}", operation);
@@ -80,16 +97,52 @@ impl DualWriteRepository {
         Ok(())
     }
 
-    /// Create follow with dual-write
-    pub async fn create_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
-        self.execute_dual_write(
-            "create_follow",
-            format!("FOLLOWS {} -> {}", follower_id, followee_id),
-            self.postgres.create_follow(follower_id, followee_id),
-            self.neo4j.create_follow(follower_id, followee_id),
-            Some(self.postgres.delete_follow(follower_id, followee_id)),
-        )
-        .await
+    /// Follow or request with dual-write.
+    /// PostgreSQL decides atomically; Neo4j mirrors the resulting edge.
+    pub async fn follow_or_request(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<FollowOutcome> {
+        let outcome = self
+            .postgres
+            .follow_or_request(follower_id, followee_id)
+            .await
+            .context("PostgreSQL write failed")?;
+
+        match outcome {
+            FollowOutcome::Followed => {
+                self.mirror_to_neo4j(
+                    "create_follow",
+                    format!("FOLLOWS {} -> {}", follower_id, followee_id),
+                    self.neo4j.create_follow(follower_id, followee_id),
+                    Some(self.postgres.delete_follow(follower_id, followee_id)),
+                )
+                .await?
+            }
+            FollowOutcome::Requested | FollowOutcome::AlreadyRequested => {
+                self.mirror_to_neo4j(
+                    "create_follow_request",
+                    format!("REQUESTED_FOLLOW {} -> {}", follower_id, followee_id),
+                    async {
+                        self.neo4j
+                            .create_follow_request(follower_id, followee_id)
+                            .await
+                            .map(|_| ())
+                    },
+                    // Only roll back a request this call created
+                    (outcome == FollowOutcome::Requested).then_some(async {
+                        self.postgres
+                            .delete_follow_request(follower_id, followee_id)
+                            .await
+                            .map(|_| ())
+                    }),
+                )
+                .await?
+            }
+        }
+
+        Ok(outcome)
     }
 
     /// Delete follow with dual-write
@@ -152,6 +205,108 @@ impl DualWriteRepository {
         .await
     }
 
+    /// Set account privacy with dual-write
+    pub async fn set_account_private(&self, user_id: Uuid, is_private: bool) -> Result<()> {
+        self.execute_dual_write::<_, _, std::future::Ready<Result<()>>>(
+            "set_account_private",
+            format!("User {} is_private={}", user_id, is_private),
+            self.postgres.set_account_private(user_id, is_private),
+            self.neo4j.set_account_private(user_id, is_private),
+            None, // Caller re-applies the previous setting on failure
+        )
+        .await
+    }
+
+    /// Check account privacy (PostgreSQL - source of truth)
+    pub async fn is_account_private(&self, user_id: Uuid) -> Result<bool> {
+        self.postgres.is_account_private(user_id).await
+    }
+
+    /// Delete follow request (deny / cancel) with dual-write
+    pub async fn delete_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool> {
+        let deleted = self
+            .postgres
+            .delete_follow_request(requester_id, target_id)
+            .await
+            .context("PostgreSQL write failed")?;
+
+        self.mirror_to_neo4j::<_, std::future::Ready<Result<()>>>(
+            "delete_follow_request",
+            format!("REQUESTED_FOLLOW {} -> {}", requester_id, target_id),
+            async {
+                self.neo4j
+                    .delete_follow_request(requester_id, target_id)
+                    .await
+                    .map(|_| ())
+            },
+            None, // No rollback for delete operations
+        )
+        .await?;
+
+        Ok(deleted)
+    }
+
+    /// Approve follow requests with dual-write.
+    /// Neo4j mirrors exactly the requesters PostgreSQL approved.
+    pub async fn approve_follow_requests(
+        &self,
+        target_id: Uuid,
+        requester_ids: Option<Vec<Uuid>>,
+    ) -> Result<Vec<Uuid>> {
+        let approved = self
+            .postgres
+            .approve_follow_requests(target_id, requester_ids.as_deref())
+            .await
+            .context("PostgreSQL write failed")?;
+
+        if approved.is_empty() {
+            return Ok(approved);
+        }
+
+        self.mirror_to_neo4j::<_, std::future::Ready<Result<()>>>(
+            "approve_follow_requests",
+            format!(
+                "REQUESTED_FOLLOW -> FOLLOWS ({} requesters) -> {}",
+                approved.len(),
+                target_id
+            ),
+            async {
+                self.neo4j
+                    .approve_follow_requests(target_id, Some(&approved))
+                    .await
+                    .map(|_| ())
+            },
+            None, // Approval is committed; the verifier repairs Neo4j drift
+        )
+        .await?;
+
+        Ok(approved)
+    }
+
+    /// Get incoming follow requests (PostgreSQL - source of truth)
+    pub async fn get_incoming_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        self.postgres
+            .get_incoming_follow_requests(user_id, limit, offset)
+            .await
+    }
+
+    /// Get outgoing follow requests (PostgreSQL - source of truth)
+    pub async fn get_outgoing_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        self.postgres
+            .get_outgoing_follow_requests(user_id, limit, offset)
+            .await
+    }
+
     /// Get followers (PostgreSQL - source of truth)
     /// Note: Using PostgreSQL directly due to neo4rs result iteration issues
     pub async fn get_followers(
@@ -312,10 +467,6 @@ impl DualWriteRepository {
 // Implement GraphRepositoryTrait for DualWriteRepository
 #[async_trait::async_trait]
 impl GraphRepositoryTrait for DualWriteRepository {
-    async fn create_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
-        Self::create_follow(self, follower_id, followee_id).await
-    }
-
     async fn delete_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
         Self::delete_follow(self, follower_id, followee_id).await
     }
@@ -392,6 +543,82 @@ impl GraphRepositoryTrait for DualWriteRepository {
         Self::get_mutual_followers(self, user_id, limit, offset).await
     }
 
+    async fn set_account_private(&self, user_id: Uuid, is_private: bool) -> Result<()> {
+        Self::set_account_private(self, user_id, is_private).await
+    }
+
+    async fn is_account_private(&self, user_id: Uuid) -> Result<bool> {
+        Self::is_account_private(self, user_id).await
+    }
+
+    async fn delete_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool> {
+        Self::delete_follow_request(self, requester_id, target_id).await
+    }
+
+    async fn follow_or_request(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<FollowOutcome> {
+        Self::follow_or_request(self, follower_id, followee_id).await
+    }
+
+    async fn approve_follow_requests(
+        &self,
+        target_id: Uuid,
+        requester_ids: Option<Vec<Uuid>>,
+    ) -> Result<Vec<Uuid>> {
+        Self::approve_follow_requests(self, target_id, requester_ids).await
+    }
+
+    async fn get_incoming_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        Self::get_incoming_follow_requests(self, user_id, limit, offset).await
+    }
+
+    async fn get_outgoing_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        Self::get_outgoing_follow_requests(self, user_id, limit, offset).await
+    }
+
+    async fn get_follower_counts(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<std::collections::HashMap<Uuid, i64>> {
+        self.postgres.get_follower_counts(user_ids).await
+    }
+
+    async fn get_following_batch(
+        &self,
+        user_ids: &[Uuid],
+        limit: i32,
+    ) -> Result<std::collections::HashMap<Uuid, (Vec<Uuid>, i64)>> {
+        self.postgres.get_following_batch(user_ids, limit).await
+    }
+
+    async fn get_hidden_users(
+        &self,
+        viewer_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        self.postgres.get_hidden_users(viewer_id, user_ids).await
+    }
+
+    async fn get_usernames(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<std::collections::HashMap<Uuid, String>> {
+        self.postgres.get_usernames(user_ids).await
+    }
+
     async fn health_check(&self) -> Result<()> {
         let (pg_healthy, neo4j_healthy) = Self::health_check(self).await?;
         if !pg_healthy {
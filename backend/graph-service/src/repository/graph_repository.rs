@@ -1,5 +1,5 @@
 use super::GraphRepositoryTrait;
-use crate::domain::edge::GraphStats;
+use crate::domain::edge::{FollowOutcome, GraphStats};
 use anyhow::{Context, Result};
 use neo4rs::{query, Graph};
 use std::sync::Arc;
@@ -21,6 +21,11 @@ impl GraphRepository {
         })
     }
 
+    /// Shared Neo4j connection pool (used by the consistency verifier)
+    pub fn graph(&self) -> Arc<Graph> {
+        self.graph.clone()
+    }
+
     /// Health check - verify Neo4j connection
     pub async fn health_check(&self) -> Result<bool> {
         let mut result = self
@@ -169,7 +174,7 @@ impl GraphRepository {
         Ok(())
     }
 
-    /// Create BLOCKS edge
+    /// Create BLOCKS edge, dropping REQUESTED_FOLLOW edges between the users
     pub async fn create_block(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<()> {
         if blocker_id == blocked_id {
             return Err(anyhow::anyhow!("Cannot block self"));
@@ -182,7 +187,10 @@ impl GraphRepository {
             MATCH (a:User {id: $blocker}), (b:User {id: $blocked})
             MERGE (a)-[r:BLOCKS]->(b)
             ON CREATE SET r.created_at = timestamp()
-            RETURN r.created_at
+            WITH a, b, r
+            OPTIONAL MATCH (a)-[req:REQUESTED_FOLLOW]-(b)
+            DELETE req
+            RETURN DISTINCT r.created_at
         "#;
 
         let mut result = self
@@ -224,6 +232,324 @@ impl GraphRepository {
         Ok(())
     }
 
+    /// Set `is_private` on the user node
+    pub async fn set_account_private(&self, user_id: Uuid, is_private: bool) -> Result<()> {
+        let cypher = r#"
+            MERGE (u:User {id: $id})
+            ON CREATE SET u.created_at = timestamp()
+            SET u.is_private = $is_private
+        "#;
+
+        let mut result = self
+            .graph
+            .execute(
+                query(cypher)
+                    .param("id", user_id.to_string())
+                    .param("is_private", is_private),
+            )
+            .await
+            .context("Failed to set account privacy")?;
+
+        while result.next().await?.is_some() {}
+
+        debug!("Set is_private={} on user node {}", is_private, user_id);
+        Ok(())
+    }
+
+    /// Check `is_private` on the user node (missing node or property = public)
+    pub async fn is_account_private(&self, user_id: Uuid) -> Result<bool> {
+        let cypher = r#"
+            OPTIONAL MATCH (u:User {id: $id})
+            RETURN coalesce(u.is_private, false) AS is_private
+        "#;
+
+        let mut result = self
+            .graph
+            .execute(query(cypher).param("id", user_id.to_string()))
+            .await
+            .context("Failed to check account privacy")?;
+
+        if let Some(row) = result.next().await? {
+            Ok(row.get("is_private").unwrap_or(false))
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Create REQUESTED_FOLLOW edge (returns false if already pending)
+    pub async fn create_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool> {
+        if requester_id == target_id {
+            return Err(anyhow::anyhow!("Cannot request to follow self"));
+        }
+
+        self.ensure_user_node(requester_id).await?;
+        self.ensure_user_node(target_id).await?;
+
+        let cypher = r#"
+            MATCH (a:User {id: $requester}), (b:User {id: $target})
+            OPTIONAL MATCH (a)-[existing:REQUESTED_FOLLOW]->(b)
+            WITH a, b, existing IS NULL AS created
+            MERGE (a)-[r:REQUESTED_FOLLOW]->(b)
+            ON CREATE SET r.created_at = timestamp()
+            RETURN created
+        "#;
+
+        let mut result = self
+            .graph
+            .execute(
+                query(cypher)
+                    .param("requester", requester_id.to_string())
+                    .param("target", target_id.to_string()),
+            )
+            .await
+            .context("Failed to create REQUESTED_FOLLOW edge")?;
+
+        let mut created = false;
+        while let Some(row) = result.next().await? {
+            created = row.get("created").unwrap_or(false);
+        }
+
+        debug!(
+            "Created REQUESTED_FOLLOW: {} -> {}",
+            requester_id, target_id
+        );
+        Ok(created)
+    }
+
+    /// Follow, or file a REQUESTED_FOLLOW edge if the followee is private
+    ///
+    /// One statement: touching `is_private` write-locks the followee node
+    /// before it is read, so a concurrent privacy change cannot interleave.
+    pub async fn follow_or_request(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<FollowOutcome> {
+        if follower_id == followee_id {
+            return Err(anyhow::anyhow!("Cannot follow self"));
+        }
+
+        self.ensure_user_node(follower_id).await?;
+        self.ensure_user_node(followee_id).await?;
+
+        let cypher = r#"
+            MATCH (a:User {id: $follower}), (b:User {id: $followee})
+            SET b.is_private = coalesce(b.is_private, false)
+            WITH a, b
+            OPTIONAL MATCH (a)-[f:FOLLOWS]->(b)
+            OPTIONAL MATCH (a)-[existing:REQUESTED_FOLLOW]->(b)
+            WITH a, b, b.is_private AND f IS NULL AS needs_request, existing IS NOT NULL AS pending
+            FOREACH (_ IN CASE WHEN needs_request THEN [1] ELSE [] END |
+                MERGE (a)-[r:REQUESTED_FOLLOW]->(b)
+                ON CREATE SET r.created_at = timestamp())
+            FOREACH (_ IN CASE WHEN needs_request THEN [] ELSE [1] END |
+                MERGE (a)-[r:FOLLOWS]->(b)
+                ON CREATE SET r.created_at = timestamp())
+            RETURN needs_request, pending
+        "#;
+
+        let mut result = self
+            .graph
+            .execute(
+                query(cypher)
+                    .param("follower", follower_id.to_string())
+                    .param("followee", followee_id.to_string()),
+            )
+            .await
+            .context("Failed to follow or request")?;
+
+        let mut outcome = FollowOutcome::Followed;
+        while let Some(row) = result.next().await? {
+            let needs_request: bool = row.get("needs_request").unwrap_or(false);
+            let pending: bool = row.get("pending").unwrap_or(false);
+            outcome = match (needs_request, pending) {
+                (false, _) => FollowOutcome::Followed,
+                (true, false) => FollowOutcome::Requested,
+                (true, true) => FollowOutcome::AlreadyRequested,
+            };
+        }
+
+        debug!(
+            "Follow {} -> {}: {}",
+            follower_id,
+            followee_id,
+            outcome.as_str()
+        );
+        Ok(outcome)
+    }
+
+    /// Delete REQUESTED_FOLLOW edge (deny or cancel)
+    pub async fn delete_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool> {
+        let cypher = r#"
+            MATCH (a:User {id: $requester})-[r:REQUESTED_FOLLOW]->(b:User {id: $target})
+            DELETE r
+            RETURN count(r) AS deleted
+        "#;
+
+        let mut result = self
+            .graph
+            .execute(
+                query(cypher)
+                    .param("requester", requester_id.to_string())
+                    .param("target", target_id.to_string()),
+            )
+            .await
+            .context("Failed to delete REQUESTED_FOLLOW edge")?;
+
+        let mut deleted: i64 = 0;
+        while let Some(row) = result.next().await? {
+            deleted = row.get("deleted").unwrap_or(0);
+        }
+
+        debug!(
+            "Deleted REQUESTED_FOLLOW: {} -> {}",
+            requester_id, target_id
+        );
+        Ok(deleted > 0)
+    }
+
+    /// Replace REQUESTED_FOLLOW edges to target with FOLLOWS edges
+    /// `requester_ids: None` approves every pending request.
+    pub async fn approve_follow_requests(
+        &self,
+        target_id: Uuid,
+        requester_ids: Option<&[Uuid]>,
+    ) -> Result<Vec<Uuid>> {
+        let cypher = r#"
+            MATCH (a:User)-[r:REQUESTED_FOLLOW]->(b:User {id: $target})
+            WHERE $all OR a.id IN $requester_ids
+            DELETE r
+            MERGE (a)-[f:FOLLOWS]->(b)
+            ON CREATE SET f.created_at = timestamp()
+            RETURN a.id AS requester_id
+        "#;
+
+        let requester_id_strings: Vec<String> = requester_ids
+            .unwrap_or_default()
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+
+        let mut result = self
+            .graph
+            .execute(
+                query(cypher)
+                    .param("target", target_id.to_string())
+                    .param("all", requester_ids.is_none())
+                    .param("requester_ids", requester_id_strings),
+            )
+            .await
+            .context("Failed to approve follow requests")?;
+
+        let mut approved = Vec::new();
+        while let Some(row) = result.next().await? {
+            if let Ok(id_str) = row.get::<String>("requester_id") {
+                if let Ok(requester_id) = Uuid::parse_str(&id_str) {
+                    approved.push(requester_id);
+                }
+            }
+        }
+
+        debug!(
+            "Approved {} follow requests for {}",
+            approved.len(),
+            target_id
+        );
+        Ok(approved)
+    }
+
+    /// Get pending follow requests to a user (newest first)
+    pub async fn get_incoming_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        let count_cypher = r#"
+            MATCH (requester:User)-[:REQUESTED_FOLLOW]->(user:User {id: $user_id})
+            RETURN count(requester) AS total
+        "#;
+        let cypher = r#"
+            MATCH (requester:User)-[r:REQUESTED_FOLLOW]->(user:User {id: $user_id})
+            RETURN requester.id AS other_id
+            ORDER BY r.created_at DESC
+            SKIP $offset
+            LIMIT $limit
+        "#;
+
+        self.list_follow_requests(user_id, limit, offset, count_cypher, cypher)
+            .await
+    }
+
+    /// Get pending follow requests sent by a user (newest first)
+    pub async fn get_outgoing_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        let count_cypher = r#"
+            MATCH (user:User {id: $user_id})-[:REQUESTED_FOLLOW]->(target:User)
+            RETURN count(target) AS total
+        "#;
+        let cypher = r#"
+            MATCH (user:User {id: $user_id})-[r:REQUESTED_FOLLOW]->(target:User)
+            RETURN target.id AS other_id
+            ORDER BY r.created_at DESC
+            SKIP $offset
+            LIMIT $limit
+        "#;
+
+        self.list_follow_requests(user_id, limit, offset, count_cypher, cypher)
+            .await
+    }
+
+    async fn list_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+        count_cypher: &str,
+        cypher: &str,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        let effective_limit = limit.min(10000);
+
+        let mut count_result = self
+            .graph
+            .execute(query(count_cypher).param("user_id", user_id.to_string()))
+            .await
+            .context("Failed to count follow requests")?;
+
+        let total_count: i32 = if let Some(row) = count_result.next().await? {
+            row.get("total").unwrap_or(0)
+        } else {
+            0
+        };
+
+        let mut result = self
+            .graph
+            .execute(
+                query(cypher)
+                    .param("user_id", user_id.to_string())
+                    .param("offset", offset as i64)
+                    .param("limit", effective_limit as i64),
+            )
+            .await
+            .context("Failed to list follow requests")?;
+
+        let mut user_ids = Vec::new();
+        while let Some(row) = result.next().await? {
+            if let Ok(id_str) = row.get::<String>("other_id") {
+                if let Ok(other_id) = Uuid::parse_str(&id_str) {
+                    user_ids.push(other_id);
+                }
+            }
+        }
+
+        let has_more = (offset + effective_limit) < total_count;
+        Ok((user_ids, total_count, has_more))
+    }
+
     /// Get followers of a user (who follows this user)
     pub async fn get_followers(
         &self,
@@ -552,6 +878,38 @@ impl GraphRepository {
         Ok(results)
     }
 
+    /// Follower counts for many users in one query
+    pub async fn get_follower_counts(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<std::collections::HashMap<Uuid, i64>> {
+        let user_id_strings: Vec<String> = user_ids.iter().map(|id| id.to_string()).collect();
+
+        let cypher = r#"
+            UNWIND $user_ids AS user_id
+            OPTIONAL MATCH (follower:User)-[:FOLLOWS]->(:User {id: user_id})
+            RETURN user_id, count(follower) AS followers
+        "#;
+
+        let mut result = self
+            .graph
+            .execute(query(cypher).param("user_ids", user_id_strings))
+            .await
+            .context("Failed to count followers")?;
+
+        let mut counts: std::collections::HashMap<Uuid, i64> =
+            user_ids.iter().map(|id| (*id, 0)).collect();
+        while let Some(row) = result.next().await? {
+            if let Ok(id_str) = row.get::<String>("user_id") {
+                if let Ok(user_id) = Uuid::parse_str(&id_str) {
+                    counts.insert(user_id, row.get("followers").unwrap_or(0));
+                }
+            }
+        }
+
+        Ok(counts)
+    }
+
     /// Get mutual followers (friends) - users who both follow each other
     pub async fn get_mutual_followers(
         &self,
@@ -736,10 +1094,6 @@ mod tests {
 // Implement GraphRepositoryTrait for GraphRepository
 #[async_trait::async_trait]
 impl GraphRepositoryTrait for GraphRepository {
-    async fn create_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
-        Self::create_follow(self, follower_id, followee_id).await
-    }
-
     async fn delete_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
         Self::delete_follow(self, follower_id, followee_id).await
     }
@@ -816,6 +1170,59 @@ impl GraphRepositoryTrait for GraphRepository {
         Self::get_mutual_followers(self, user_id, limit, offset).await
     }
 
+    async fn set_account_private(&self, user_id: Uuid, is_private: bool) -> Result<()> {
+        Self::set_account_private(self, user_id, is_private).await
+    }
+
+    async fn is_account_private(&self, user_id: Uuid) -> Result<bool> {
+        Self::is_account_private(self, user_id).await
+    }
+
+    async fn delete_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool> {
+        Self::delete_follow_request(self, requester_id, target_id).await
+    }
+
+    async fn follow_or_request(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<FollowOutcome> {
+        Self::follow_or_request(self, follower_id, followee_id).await
+    }
+
+    async fn approve_follow_requests(
+        &self,
+        target_id: Uuid,
+        requester_ids: Option<Vec<Uuid>>,
+    ) -> Result<Vec<Uuid>> {
+        Self::approve_follow_requests(self, target_id, requester_ids.as_deref()).await
+    }
+
+    async fn get_incoming_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        Self::get_incoming_follow_requests(self, user_id, limit, offset).await
+    }
+
+    async fn get_outgoing_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        Self::get_outgoing_follow_requests(self, user_id, limit, offset).await
+    }
+
+    async fn get_follower_counts(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<std::collections::HashMap<Uuid, i64>> {
+        Self::get_follower_counts(self, user_ids).await
+    }
+
     async fn health_check(&self) -> Result<()> {
         let is_healthy = Self::health_check(self).await?;
         if !is_healthy {
@@ -1,9 +1,11 @@
+mod audience_repository;
 mod cached_repository;
 mod dual_write_repository;
 mod graph_repository;
 mod postgres_repository;
 mod r#trait;
 
+pub use audience_repository::AudienceListRepository;
 pub use cached_repository::CachedGraphRepository;
 pub use dual_write_repository::DualWriteRepository;
 pub use graph_repository::GraphRepository;
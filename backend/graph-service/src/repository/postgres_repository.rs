@@ -1,5 +1,7 @@
+use crate::domain::edge::FollowOutcome;
 use anyhow::{Context, Result};
 use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
 use tracing::debug;
 use uuid::Uuid;
 
@@ -79,31 +81,6 @@ impl PostgresGraphRepository {
         Ok(())
     }
 
-    /// Create follow relationship (source of truth)
-    pub async fn create_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
-        // P1: Ensure both users exist before creating the relationship
-        self.ensure_user_exists(follower_id).await?;
-        self.ensure_user_exists(followee_id).await?;
-        sqlx::query(
-            r#"
-            INSERT INTO follows (follower_id, following_id, created_at)
-            VALUES ($1, $2, NOW())
-            ON CONFLICT (follower_id, following_id) DO NOTHING
-            "#,
-        )
-        .bind(follower_id)
-        .bind(followee_id)
-        .execute(&self.pool)
-        .await
-        .context("Failed to create follow in PostgreSQL")?;
-
-        debug!(
-            "Created FOLLOWS in PostgreSQL: {} -> {}",
-            follower_id, followee_id
-        );
-        Ok(())
-    }
-
     /// Delete follow relationship
     pub async fn delete_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()> {
         sqlx::query("DELETE FROM follows WHERE follower_id = $1 AND following_id = $2")
@@ -156,12 +133,18 @@ impl PostgresGraphRepository {
         Ok(())
     }
 
-    /// Create block relationship
+    /// Create block relationship, dropping pending follow requests between the users
     pub async fn create_block(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<()> {
         // P1: Ensure both users exist before creating the relationship
         self.ensure_user_exists(blocker_id).await?;
         self.ensure_user_exists(blocked_id).await?;
 
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin block creation")?;
+
         sqlx::query(
             r#"
             INSERT INTO blocks (blocker_id, blocked_id, created_at)
@@ -171,10 +154,27 @@ impl PostgresGraphRepository {
         )
         .bind(blocker_id)
         .bind(blocked_id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .context("Failed to create block in PostgreSQL - ensure blocks table exists")?;
 
+        sqlx::query(
+            r#"
+            DELETE FROM follow_requests
+            WHERE (requester_id = $1 AND target_id = $2)
+               OR (requester_id = $2 AND target_id = $1)
+            "#,
+        )
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to clear follow requests for block in PostgreSQL")?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit block creation")?;
+
         debug!(
             "Created BLOCKS in PostgreSQL: {} -> {}",
             blocker_id, blocked_id
@@ -198,6 +198,295 @@ impl PostgresGraphRepository {
         Ok(())
     }
 
+    /// Mark an account private or public
+    pub async fn set_account_private(&self, user_id: Uuid, is_private: bool) -> Result<()> {
+        self.ensure_user_exists(user_id).await?;
+
+        sqlx::query("UPDATE users SET is_private = $2, updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .bind(is_private)
+            .execute(&self.pool)
+            .await
+            .context("Failed to update account privacy in PostgreSQL")?;
+
+        debug!("Set is_private={} in PostgreSQL: {}", is_private, user_id);
+        Ok(())
+    }
+
+    /// Check if an account is private (unknown users are public)
+    pub async fn is_account_private(&self, user_id: Uuid) -> Result<bool> {
+        let is_private: Option<bool> =
+            sqlx::query_scalar("SELECT is_private FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await
+                .context("Failed to read account privacy from PostgreSQL")?;
+
+        Ok(is_private.unwrap_or(false))
+    }
+
+    /// Follow, or file a request if the followee is private, in one transaction
+    ///
+    /// The followee's row is locked `FOR SHARE`, so a concurrent privacy
+    /// change waits for this decision instead of racing it.
+    pub async fn follow_or_request(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<FollowOutcome> {
+        self.ensure_user_exists(follower_id).await?;
+        self.ensure_user_exists(followee_id).await?;
+
+        let mut tx = self.pool.begin().await.context("Failed to begin follow")?;
+
+        let is_private: bool =
+            sqlx::query_scalar("SELECT is_private FROM users WHERE id = $1 FOR SHARE")
+                .bind(followee_id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("Failed to read account privacy from PostgreSQL")?;
+
+        let already_following: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM follows WHERE follower_id = $1 AND following_id = $2)",
+        )
+        .bind(follower_id)
+        .bind(followee_id)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to check follow in PostgreSQL")?;
+
+        let outcome = if is_private && !already_following {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO follow_requests (requester_id, target_id, created_at)
+                VALUES ($1, $2, NOW())
+                ON CONFLICT (requester_id, target_id) DO NOTHING
+                "#,
+            )
+            .bind(follower_id)
+            .bind(followee_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create follow request in PostgreSQL")?;
+
+            if result.rows_affected() > 0 {
+                FollowOutcome::Requested
+            } else {
+                FollowOutcome::AlreadyRequested
+            }
+        } else {
+            sqlx::query(
+                r#"
+                INSERT INTO follows (follower_id, following_id, created_at)
+                VALUES ($1, $2, NOW())
+                ON CONFLICT (follower_id, following_id) DO NOTHING
+                "#,
+            )
+            .bind(follower_id)
+            .bind(followee_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create follow in PostgreSQL")?;
+
+            FollowOutcome::Followed
+        };
+
+        tx.commit().await.context("Failed to commit follow")?;
+
+        debug!(
+            "Follow {} -> {} in PostgreSQL: {}",
+            follower_id,
+            followee_id,
+            outcome.as_str()
+        );
+        Ok(outcome)
+    }
+
+    /// Delete follow request (deny or cancel)
+    pub async fn delete_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM follow_requests WHERE requester_id = $1 AND target_id = $2")
+                .bind(requester_id)
+                .bind(target_id)
+                .execute(&self.pool)
+                .await
+                .context("Failed to delete follow request in PostgreSQL")?;
+
+        debug!(
+            "Deleted REQUESTED_FOLLOW in PostgreSQL: {} -> {}",
+            requester_id, target_id
+        );
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move pending requests into follows in one transaction
+    /// `requester_ids: None` approves every pending request to target.
+    pub async fn approve_follow_requests(
+        &self,
+        target_id: Uuid,
+        requester_ids: Option<&[Uuid]>,
+    ) -> Result<Vec<Uuid>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin follow request approval")?;
+
+        let approved: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            DELETE FROM follow_requests
+            WHERE target_id = $1
+              AND ($2::UUID[] IS NULL OR requester_id = ANY($2))
+            RETURNING requester_id
+            "#,
+        )
+        .bind(target_id)
+        .bind(requester_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to take follow requests in PostgreSQL")?;
+
+        if !approved.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO follows (follower_id, following_id, created_at)
+                SELECT requester_id, $2, NOW() FROM UNNEST($1::UUID[]) AS r(requester_id)
+                ON CONFLICT (follower_id, following_id) DO NOTHING
+                "#,
+            )
+            .bind(&approved)
+            .bind(target_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create follows for approved requests in PostgreSQL")?;
+        }
+
+        tx.commit()
+            .await
+            .context("Failed to commit follow request approval")?;
+
+        debug!(
+            "Approved {} follow requests in PostgreSQL for {}",
+            approved.len(),
+            target_id
+        );
+        Ok(approved)
+    }
+
+    /// Follower counts for many users in one query
+    pub async fn get_follower_counts(&self, user_ids: &[Uuid]) -> Result<HashMap<Uuid, i64>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(Uuid, i64)> = sqlx::query_as(
+            "SELECT following_id, COUNT(*) FROM follows
+             WHERE following_id = ANY($1)
+             GROUP BY following_id",
+        )
+        .bind(user_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to count followers")?;
+
+        let mut counts: HashMap<Uuid, i64> = user_ids.iter().map(|id| (*id, 0)).collect();
+        counts.extend(rows);
+        Ok(counts)
+    }
+
+    /// Newest followees and total followee count for many users in one query
+    pub async fn get_following_batch(
+        &self,
+        user_ids: &[Uuid],
+        limit: i32,
+    ) -> Result<HashMap<Uuid, (Vec<Uuid>, i64)>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(Uuid, Option<Uuid>, i64)> = sqlx::query_as(
+            r#"
+            SELECT u.id, f.following_id, c.total
+            FROM UNNEST($1::UUID[]) AS u(id)
+            CROSS JOIN LATERAL (
+                SELECT COUNT(*) AS total FROM follows WHERE follower_id = u.id
+            ) c
+            LEFT JOIN LATERAL (
+                SELECT following_id FROM follows
+                WHERE follower_id = u.id
+                ORDER BY created_at DESC
+                LIMIT $2
+            ) f ON TRUE
+            "#,
+        )
+        .bind(user_ids)
+        .bind(limit.min(10000) as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch following for many users")?;
+
+        let mut following: HashMap<Uuid, (Vec<Uuid>, i64)> = HashMap::new();
+        for (user_id, followee, total) in rows {
+            let entry = following
+                .entry(user_id)
+                .or_insert_with(|| (Vec::new(), total));
+            entry.0.extend(followee);
+        }
+        Ok(following)
+    }
+
+    /// Users among `user_ids` with a block either way with the viewer, or muted by the viewer
+    pub async fn get_hidden_users(
+        &self,
+        viewer_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<HashSet<Uuid>> {
+        if user_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let hidden: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT u.id FROM UNNEST($2::UUID[]) AS u(id)
+            WHERE EXISTS (
+                    SELECT 1 FROM blocks b
+                    WHERE (b.blocker_id = $1 AND b.blocked_id = u.id)
+                       OR (b.blocker_id = u.id AND b.blocked_id = $1)
+                  )
+               OR EXISTS (
+                    SELECT 1 FROM mutes m
+                    WHERE m.muter_id = $1 AND m.muted_id = u.id
+                  )
+            "#,
+        )
+        .bind(viewer_id)
+        .bind(user_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to check blocks and mutes")?;
+
+        Ok(hidden.into_iter().collect())
+    }
+
+    /// Usernames for many users
+    /// Placeholder rows from `ensure_user_exists` (username = id) are skipped.
+    pub async fn get_usernames(&self, user_ids: &[Uuid]) -> Result<HashMap<Uuid, String>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT id, username FROM users
+             WHERE id = ANY($1) AND deleted_at IS NULL AND username <> id::text",
+        )
+        .bind(user_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch usernames")?;
+
+        Ok(rows.into_iter().collect())
+    }
+
     /// Get followers (PostgreSQL fallback)
     pub async fn get_followers(
         &self,
@@ -430,4 +719,67 @@ impl PostgresGraphRepository {
 
         Ok((friend_ids, total_count as i32, has_more))
     }
+
+    /// Get pending follow requests to a user (newest first)
+    pub async fn get_incoming_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        self.list_follow_requests(user_id, limit, offset, true)
+            .await
+    }
+
+    /// Get pending follow requests sent by a user (newest first)
+    pub async fn get_outgoing_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        self.list_follow_requests(user_id, limit, offset, false)
+            .await
+    }
+
+    async fn list_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+        incoming: bool,
+    ) -> Result<(Vec<Uuid>, i32, bool)> {
+        let effective_limit = limit.min(10000);
+        let (owner_col, other_col) = if incoming {
+            ("target_id", "requester_id")
+        } else {
+            ("requester_id", "target_id")
+        };
+
+        let total_count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM follow_requests WHERE {} = $1",
+            owner_col
+        ))
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows: Vec<(Uuid,)> = sqlx::query_as(&format!(
+            "SELECT {} FROM follow_requests
+             WHERE {} = $1
+             ORDER BY created_at DESC
+             LIMIT $2 OFFSET $3",
+            other_col, owner_col
+        ))
+        .bind(user_id)
+        .bind(effective_limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let user_ids: Vec<Uuid> = rows.into_iter().map(|(id,)| id).collect();
+        let has_more = (offset as i64 + effective_limit as i64) < total_count;
+
+        Ok((user_ids, total_count as i32, has_more))
+    }
 }
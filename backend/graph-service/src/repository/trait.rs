@@ -1,14 +1,12 @@
+use crate::domain::edge::FollowOutcome;
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// Trait defining the interface for graph repository operations.
 /// Both GraphRepository (Neo4j-only) and DualWriteRepository (PostgreSQL + Neo4j) implement this.
 #[async_trait::async_trait]
 pub trait GraphRepositoryTrait: Send + Sync {
-    /// Create a follow relationship
-    async fn create_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()>;
-
     /// Delete a follow relationship
     async fn delete_follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<()>;
 
@@ -94,6 +92,108 @@ pub trait GraphRepositoryTrait: Send + Sync {
         followee_ids: Vec<Uuid>,
     ) -> Result<HashMap<String, bool>>;
 
+    // ========== Private accounts & follow requests ==========
+
+    /// Mark an account private (follows need approval) or public
+    async fn set_account_private(&self, user_id: Uuid, is_private: bool) -> Result<()>;
+
+    /// Check if an account is private (unknown users are public)
+    async fn is_account_private(&self, user_id: Uuid) -> Result<bool>;
+
+    /// Delete a pending follow request (deny or cancel)
+    /// Returns: true if a request existed
+    async fn delete_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool>;
+
+    /// Turn pending requests to target into follows
+    /// `requester_ids: None` approves every pending request.
+    /// Returns: requester ids that were approved (ids without a pending request are skipped)
+    async fn approve_follow_requests(
+        &self,
+        target_id: Uuid,
+        requester_ids: Option<Vec<Uuid>>,
+    ) -> Result<Vec<Uuid>>;
+
+    /// Approve a single pending request
+    /// Returns: true if a request existed and is now a follow
+    async fn approve_follow_request(&self, requester_id: Uuid, target_id: Uuid) -> Result<bool> {
+        let approved = self
+            .approve_follow_requests(target_id, Some(vec![requester_id]))
+            .await?;
+        Ok(!approved.is_empty())
+    }
+
+    /// Get pending requests to a user (newest first)
+    /// Returns: (requester_ids, total_count, has_more)
+    async fn get_incoming_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)>;
+
+    /// Get pending requests sent by a user (newest first)
+    /// Returns: (target_ids, total_count, has_more)
+    async fn get_outgoing_follow_requests(
+        &self,
+        user_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<Uuid>, i32, bool)>;
+
+    /// Follow a user, or file a follow request if the account is private
+    ///
+    /// Existing followers of a private account stay followers. The privacy
+    /// check and the write must be atomic, so implementations decide in one
+    /// transaction or statement.
+    async fn follow_or_request(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<FollowOutcome>;
+
+    // ========== Suggestion inputs ==========
+
+    /// Follower counts for many users (unknown users count as 0)
+    async fn get_follower_counts(&self, user_ids: &[Uuid]) -> Result<HashMap<Uuid, i64>> {
+        let mut counts = HashMap::with_capacity(user_ids.len());
+        for &user_id in user_ids {
+            let (_, total, _) = self.get_followers(user_id, 1, 0).await?;
+            counts.insert(user_id, total as i64);
+        }
+        Ok(counts)
+    }
+
+    /// Newest followees (up to `limit`) and total followee count for many users
+    async fn get_following_batch(
+        &self,
+        user_ids: &[Uuid],
+        limit: i32,
+    ) -> Result<HashMap<Uuid, (Vec<Uuid>, i64)>> {
+        let mut following = HashMap::with_capacity(user_ids.len());
+        for &user_id in user_ids {
+            let (ids, total, _) = self.get_following(user_id, limit, 0).await?;
+            following.insert(user_id, (ids, total as i64));
+        }
+        Ok(following)
+    }
+
+    /// Users among `user_ids` with a block either way with the viewer, or muted by the viewer
+    async fn get_hidden_users(&self, viewer_id: Uuid, user_ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        let mut hidden = HashSet::new();
+        for &user_id in user_ids {
+            let (has_block, _, _) = self.has_block_between(viewer_id, user_id).await?;
+            if has_block || self.is_muted(viewer_id, user_id).await? {
+                hidden.insert(user_id);
+            }
+        }
+        Ok(hidden)
+    }
+
+    /// Usernames for many users (users without a synced username are omitted)
+    async fn get_usernames(&self, _user_ids: &[Uuid]) -> Result<HashMap<Uuid, String>> {
+        Ok(HashMap::new())
+    }
+
     /// Health check (optional)
     async fn health_check(&self) -> Result<()> {
         Ok(())
@@ -0,0 +1,596 @@
+//! People-you-may-know suggestions
+//!
+//! Candidates are friends-of-friends: users followed by the people the viewer
+//! follows. Each candidate is scored on
+//! - Adamic-Adar: sum of 1/ln(out-degree) over shared followees, so a mutual
+//!   who follows few people counts more than one who follows everyone
+//! - Jaccard: overlap of the viewer's following with the candidate's followers
+//! - personalized PageRank restarting at the viewer over the 2-hop ego graph
+//!
+//! Scored candidates are cached per user and refreshed in the background for
+//! recently active users. Block/mute/follow filters run on every read, so a
+//! cached list never resurfaces someone the viewer has since blocked or followed.
+
+use crate::repository::GraphRepositoryTrait;
+use anyhow::Result;
+use nova_cache::graph::{CachedSuggestion, GraphCache};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Score weights (components are normalized to [0, 1] first)
+const ADAMIC_ADAR_WEIGHT: f64 = 0.5;
+const JACCARD_WEIGHT: f64 = 0.2;
+const PAGERANK_WEIGHT: f64 = 0.3;
+
+/// Mutual followee ids kept per candidate (for "followed by X and N others")
+const MUTUAL_SAMPLE: usize = 3;
+
+/// Configuration for the suggestion engine
+#[derive(Debug, Clone)]
+pub struct SuggestionConfig {
+    /// Followees expanded when building the ego graph
+    pub max_following: i32,
+    /// Followings read per followee
+    pub max_fanout: i32,
+    /// Scored candidates kept per user
+    pub max_candidates: usize,
+    /// PageRank restart probability
+    pub pagerank_alpha: f64,
+    pub pagerank_iterations: usize,
+    /// How often cached candidates of active users are recomputed
+    pub refresh_interval: Duration,
+    /// Users who have not asked for suggestions within this window stop being refreshed
+    pub active_window: Duration,
+}
+
+impl Default for SuggestionConfig {
+    fn default() -> Self {
+        Self {
+            max_following: 200,
+            max_fanout: 500,
+            max_candidates: 300,
+            pagerank_alpha: 0.15,
+            pagerank_iterations: 20,
+            refresh_interval: Duration::from_secs(1800),
+            active_window: Duration::from_secs(24 * 3600),
+        }
+    }
+}
+
+impl SuggestionConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_following: std::env::var("GRAPH_SUGGESTIONS_MAX_FOLLOWING")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_following),
+            max_fanout: std::env::var("GRAPH_SUGGESTIONS_MAX_FANOUT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_fanout),
+            max_candidates: std::env::var("GRAPH_SUGGESTIONS_MAX_CANDIDATES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_candidates),
+            refresh_interval: std::env::var("GRAPH_SUGGESTIONS_REFRESH_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.refresh_interval),
+            active_window: std::env::var("GRAPH_SUGGESTIONS_ACTIVE_HOURS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(|h| Duration::from_secs(h * 3600))
+                .unwrap_or(defaults.active_window),
+            ..defaults
+        }
+    }
+}
+
+/// The viewer's 2-hop neighbourhood over FOLLOWS edges
+#[derive(Debug, Clone, Default)]
+pub struct EgoGraph {
+    pub user_id: Uuid,
+    /// Users the viewer follows
+    pub following: Vec<Uuid>,
+    /// Followee -> users that followee follows (sampled)
+    pub second_hop: HashMap<Uuid, Vec<Uuid>>,
+    /// Followee -> total following count (may exceed the sample)
+    pub out_degree: HashMap<Uuid, i64>,
+}
+
+/// Candidate -> the viewer's followees who follow them
+pub fn friends_of_friends(ego: &EgoGraph) -> HashMap<Uuid, Vec<Uuid>> {
+    let following: HashSet<Uuid> = ego.following.iter().copied().collect();
+    let mut candidates: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+
+    for followee in &ego.following {
+        if let Some(next) = ego.second_hop.get(followee) {
+            for candidate in next {
+                if *candidate != ego.user_id && !following.contains(candidate) {
+                    candidates.entry(*candidate).or_default().push(*followee);
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Personalized PageRank with restart at the viewer
+///
+/// Mass on nodes without known out-edges returns to the viewer, so scores
+/// always sum to 1.
+pub fn personalized_pagerank(ego: &EgoGraph, alpha: f64, iterations: usize) -> HashMap<Uuid, f64> {
+    let mut edges: HashMap<Uuid, &[Uuid]> = HashMap::new();
+    edges.insert(ego.user_id, &ego.following);
+    for (followee, next) in &ego.second_hop {
+        edges.insert(*followee, next);
+    }
+
+    let mut rank: HashMap<Uuid, f64> = HashMap::from([(ego.user_id, 1.0)]);
+
+    for _ in 0..iterations {
+        let mut next_rank: HashMap<Uuid, f64> = HashMap::with_capacity(rank.len());
+        let mut returned = alpha;
+
+        for (node, score) in &rank {
+            match edges.get(node) {
+                Some(out) if !out.is_empty() => {
+                    let share = (1.0 - alpha) * score / out.len() as f64;
+                    for target in out.iter() {
+                        *next_rank.entry(*target).or_insert(0.0) += share;
+                    }
+                }
+                _ => returned += (1.0 - alpha) * score,
+            }
+        }
+
+        *next_rank.entry(ego.user_id).or_insert(0.0) += returned;
+        rank = next_rank;
+    }
+
+    rank
+}
+
+/// Score friends-of-friends, best first, keeping `config.max_candidates`
+pub fn score_candidates(
+    ego: &EgoGraph,
+    fof: &HashMap<Uuid, Vec<Uuid>>,
+    follower_counts: &HashMap<Uuid, i64>,
+    config: &SuggestionConfig,
+) -> Vec<CachedSuggestion> {
+    let pagerank = personalized_pagerank(ego, config.pagerank_alpha, config.pagerank_iterations);
+    let following_count = ego.following.len() as f64;
+
+    let mut scored: Vec<CachedSuggestion> = fof
+        .iter()
+        .map(|(candidate, mutuals)| {
+            let adamic_adar = mutuals
+                .iter()
+                .map(|z| {
+                    let degree = ego.out_degree.get(z).copied().unwrap_or(1).max(2);
+                    1.0 / (degree as f64).ln()
+                })
+                .sum();
+
+            let mutual = mutuals.len() as f64;
+            let followers = follower_counts
+                .get(candidate)
+                .map(|c| *c as f64)
+                .unwrap_or(0.0)
+                .max(mutual);
+            let union = (following_count + followers - mutual).max(1.0);
+
+            CachedSuggestion {
+                user_id: *candidate,
+                score: 0.0,
+                mutual_count: mutuals.len() as u32,
+                mutual_ids: mutuals.iter().take(MUTUAL_SAMPLE).copied().collect(),
+                adamic_adar,
+                jaccard: mutual / union,
+                pagerank: pagerank.get(candidate).copied().unwrap_or(0.0),
+            }
+        })
+        .collect();
+
+    let max_of = |f: fn(&CachedSuggestion) -> f64| {
+        scored
+            .iter()
+            .map(f)
+            .fold(0.0_f64, f64::max)
+            .max(f64::MIN_POSITIVE)
+    };
+    let max_aa = max_of(|s| s.adamic_adar);
+    let max_jaccard = max_of(|s| s.jaccard);
+    let max_pagerank = max_of(|s| s.pagerank);
+
+    for s in &mut scored {
+        s.score = ADAMIC_ADAR_WEIGHT * s.adamic_adar / max_aa
+            + JACCARD_WEIGHT * s.jaccard / max_jaccard
+            + PAGERANK_WEIGHT * s.pagerank / max_pagerank;
+    }
+
+    scored.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.mutual_count.cmp(&a.mutual_count))
+    });
+    scored.truncate(config.max_candidates);
+    scored
+}
+
+/// "Followed by alice and 3 others"
+pub fn suggestion_reason(mutual_count: u32, first_mutual_name: Option<&str>) -> String {
+    match (mutual_count, first_mutual_name) {
+        (0, _) => "Suggested for you".to_string(),
+        (1, Some(name)) => format!("Followed by {}", name),
+        (2, Some(name)) => format!("Followed by {} and 1 other", name),
+        (n, Some(name)) => format!("Followed by {} and {} others", name, n - 1),
+        (1, None) => "Followed by 1 person you follow".to_string(),
+        (n, None) => format!("Followed by {} people you follow", n),
+    }
+}
+
+/// A candidate ready to return to the client
+#[derive(Debug, Clone)]
+pub struct SuggestedFollow {
+    pub candidate: CachedSuggestion,
+    pub reason: String,
+}
+
+/// People-you-may-know engine over any graph repository
+pub struct SuggestionEngine {
+    repo: Arc<dyn GraphRepositoryTrait + Send + Sync>,
+    cache: Option<GraphCache>,
+    config: SuggestionConfig,
+    /// Users who asked recently -> when (the precompute set)
+    active: Mutex<HashMap<Uuid, Instant>>,
+}
+
+impl SuggestionEngine {
+    pub fn new(
+        repo: Arc<dyn GraphRepositoryTrait + Send + Sync>,
+        cache: Option<GraphCache>,
+        config: SuggestionConfig,
+    ) -> Self {
+        Self {
+            repo,
+            cache,
+            config,
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &SuggestionConfig {
+        &self.config
+    }
+
+    /// Top `limit` suggestions for a user, filtered against current graph state
+    pub async fn suggest(&self, user_id: Uuid, limit: usize) -> Result<Vec<SuggestedFollow>> {
+        self.mark_active(user_id);
+
+        let candidates = match self.cached_candidates(user_id).await {
+            Some(candidates) => candidates,
+            None => {
+                let candidates = self.compute_candidates(user_id).await?;
+                self.store_candidates(user_id, &candidates).await;
+                candidates
+            }
+        };
+
+        let filtered = self.filter_candidates(user_id, candidates, limit).await?;
+        self.with_reasons(filtered).await
+    }
+
+    /// Build the ego graph and score every friend-of-friend
+    pub async fn compute_candidates(&self, user_id: Uuid) -> Result<Vec<CachedSuggestion>> {
+        let ego = self.load_ego_graph(user_id).await?;
+        let mut fof = friends_of_friends(&ego);
+
+        // Only the best-connected candidates get a follower count lookup
+        if fof.len() > self.config.max_candidates * 2 {
+            let mut by_mutuals: Vec<(Uuid, usize)> =
+                fof.iter().map(|(id, m)| (*id, m.len())).collect();
+            by_mutuals.sort_by_key(|(_, mutuals)| std::cmp::Reverse(*mutuals));
+            let keep: HashSet<Uuid> = by_mutuals
+                .into_iter()
+                .take(self.config.max_candidates * 2)
+                .map(|(id, _)| id)
+                .collect();
+            fof.retain(|id, _| keep.contains(id));
+        }
+
+        let candidate_ids: Vec<Uuid> = fof.keys().copied().collect();
+        let follower_counts = self.repo.get_follower_counts(&candidate_ids).await?;
+
+        let scored = score_candidates(&ego, &fof, &follower_counts, &self.config);
+        debug!(
+            "Computed {} suggestion candidates for {} ({} followees expanded)",
+            scored.len(),
+            user_id,
+            ego.second_hop.len()
+        );
+        Ok(scored)
+    }
+
+    /// Recompute cached candidates for users active within the window
+    /// Returns: number of users refreshed
+    pub async fn refresh_active(&self) -> usize {
+        if self.cache.is_none() {
+            return 0;
+        }
+
+        let users: Vec<Uuid> = {
+            let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+            let window = self.config.active_window;
+            active.retain(|_, last_seen| last_seen.elapsed() < window);
+            active.keys().copied().collect()
+        };
+
+        let mut refreshed = 0;
+        for user_id in users {
+            match self.compute_candidates(user_id).await {
+                Ok(candidates) => {
+                    self.store_candidates(user_id, &candidates).await;
+                    refreshed += 1;
+                }
+                Err(e) => warn!("Failed to refresh suggestions for {}: {}", user_id, e),
+            }
+        }
+        refreshed
+    }
+
+    fn mark_active(&self, user_id: Uuid) {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        active.insert(user_id, Instant::now());
+    }
+
+    async fn cached_candidates(&self, user_id: Uuid) -> Option<Vec<CachedSuggestion>> {
+        let cache = self.cache.as_ref()?;
+        match cache.get_suggestions(user_id).await {
+            Ok(cached) => cached.map(|c| c.candidates),
+            Err(e) => {
+                warn!(error = %e, user = %user_id, "Failed to read cached suggestions");
+                None
+            }
+        }
+    }
+
+    async fn store_candidates(&self, user_id: Uuid, candidates: &[CachedSuggestion]) {
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.set_suggestions(user_id, candidates.to_vec()).await {
+                warn!(error = %e, user = %user_id, "Failed to cache suggestions");
+            }
+        }
+    }
+
+    async fn load_ego_graph(&self, user_id: Uuid) -> Result<EgoGraph> {
+        let (following, _, _) = self
+            .repo
+            .get_following(user_id, self.config.max_following, 0)
+            .await?;
+
+        let mut ego = EgoGraph {
+            user_id,
+            following,
+            ..Default::default()
+        };
+
+        // One batched lookup for the whole second hop
+        match self
+            .repo
+            .get_following_batch(&ego.following, self.config.max_fanout)
+            .await
+        {
+            Ok(following) => {
+                for (followee, (next, total)) in following {
+                    ego.second_hop.insert(followee, next);
+                    ego.out_degree.insert(followee, total);
+                }
+            }
+            Err(e) => warn!("Skipping second hop of ego graph for {}: {}", user_id, e),
+        }
+
+        Ok(ego)
+    }
+
+    /// Drop self, followed, requested, blocked (either way) and muted users
+    async fn filter_candidates(
+        &self,
+        user_id: Uuid,
+        candidates: Vec<CachedSuggestion>,
+        limit: usize,
+    ) -> Result<Vec<CachedSuggestion>> {
+        let (blocked, _, _) = self.repo.get_blocked_users(user_id, 10000, 0).await?;
+        let (requested, _, _) = self
+            .repo
+            .get_outgoing_follow_requests(user_id, 10000, 0)
+            .await?;
+        let excluded: HashSet<Uuid> = blocked
+            .into_iter()
+            .chain(requested)
+            .chain(std::iter::once(user_id))
+            .collect();
+
+        // Headroom for the follow/block/mute checks below
+        let shortlist: Vec<CachedSuggestion> = candidates
+            .into_iter()
+            .filter(|c| !excluded.contains(&c.user_id))
+            .take(limit + limit / 2 + 5)
+            .collect();
+
+        // Follows made since the candidates were computed
+        let mut followed: HashSet<Uuid> = HashSet::new();
+        let ids: Vec<Uuid> = shortlist.iter().map(|c| c.user_id).collect();
+        for chunk in ids.chunks(100) {
+            let results = self
+                .repo
+                .batch_check_following(user_id, chunk.to_vec())
+                .await?;
+            followed.extend(
+                results
+                    .into_iter()
+                    .filter(|(_, is_following)| *is_following)
+                    .filter_map(|(id, _)| Uuid::parse_str(&id).ok()),
+            );
+        }
+
+        let unfollowed: Vec<Uuid> = ids
+            .into_iter()
+            .filter(|id| !followed.contains(id))
+            .collect();
+        let hidden = self.repo.get_hidden_users(user_id, &unfollowed).await?;
+
+        let kept: Vec<CachedSuggestion> = shortlist
+            .into_iter()
+            .filter(|c| !followed.contains(&c.user_id) && !hidden.contains(&c.user_id))
+            .take(limit)
+            .collect();
+
+        Ok(kept)
+    }
+
+    async fn with_reasons(
+        &self,
+        candidates: Vec<CachedSuggestion>,
+    ) -> Result<Vec<SuggestedFollow>> {
+        let first_mutuals: Vec<Uuid> = candidates
+            .iter()
+            .filter_map(|c| c.mutual_ids.first().copied())
+            .collect();
+        let usernames = match self.repo.get_usernames(&first_mutuals).await {
+            Ok(names) => names,
+            Err(e) => {
+                warn!("Failed to load usernames for suggestion reasons: {}", e);
+                HashMap::new()
+            }
+        };
+
+        Ok(candidates
+            .into_iter()
+            .map(|candidate| {
+                let name = candidate
+                    .mutual_ids
+                    .first()
+                    .and_then(|id| usernames.get(id))
+                    .map(String::as_str);
+                let reason = suggestion_reason(candidate.mutual_count, name);
+                SuggestedFollow { candidate, reason }
+            })
+            .collect())
+    }
+}
+
+/// Start the background job that keeps active users' candidates warm
+pub async fn start_suggestion_precompute(engine: Arc<SuggestionEngine>) {
+    let interval = engine.config().refresh_interval;
+    info!(
+        interval_secs = interval.as_secs(),
+        "Starting suggestion precompute background job"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+        let refreshed = engine.refresh_active().await;
+        debug!(refreshed, "Suggestion precompute pass complete");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    /// viewer(1) follows 2 and 3; 2 follows 4, 5; 3 follows 4 and the viewer
+    fn sample_ego() -> EgoGraph {
+        EgoGraph {
+            user_id: id(1),
+            following: vec![id(2), id(3)],
+            second_hop: HashMap::from([(id(2), vec![id(4), id(5)]), (id(3), vec![id(4), id(1)])]),
+            out_degree: HashMap::from([(id(2), 2), (id(3), 2)]),
+        }
+    }
+
+    #[test]
+    fn test_friends_of_friends_excludes_viewer_and_following() {
+        let fof = friends_of_friends(&sample_ego());
+
+        assert_eq!(fof.len(), 2);
+        assert_eq!(fof[&id(4)].len(), 2);
+        assert_eq!(fof[&id(5)], vec![id(2)]);
+        assert!(!fof.contains_key(&id(1)));
+    }
+
+    #[test]
+    fn test_pagerank_sums_to_one_and_favors_shared_candidates() {
+        let ego = sample_ego();
+        let rank = personalized_pagerank(&ego, 0.15, 30);
+
+        let total: f64 = rank.values().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(rank[&id(4)] > rank[&id(5)]);
+    }
+
+    #[test]
+    fn test_adamic_adar_prefers_selective_mutuals() {
+        let mut ego = sample_ego();
+        // 3 follows thousands of people; its endorsement is worth less
+        ego.out_degree.insert(id(3), 5000);
+        ego.second_hop.insert(id(3), vec![id(6)]);
+
+        let fof = friends_of_friends(&ego);
+        let scored = score_candidates(&ego, &fof, &HashMap::new(), &SuggestionConfig::default());
+        let aa = |user| {
+            scored
+                .iter()
+                .find(|s| s.user_id == user)
+                .unwrap()
+                .adamic_adar
+        };
+
+        assert!(aa(id(5)) > aa(id(6)));
+    }
+
+    #[test]
+    fn test_score_candidates_ranks_and_computes_jaccard() {
+        let ego = sample_ego();
+        let fof = friends_of_friends(&ego);
+        let counts = HashMap::from([(id(4), 2), (id(5), 10)]);
+
+        let scored = score_candidates(&ego, &fof, &counts, &SuggestionConfig::default());
+
+        assert_eq!(scored[0].user_id, id(4));
+        assert_eq!(scored[0].mutual_count, 2);
+        // |{2,3} ∩ followers(4)| = 2, |∪| = 2 + 2 - 2
+        assert!((scored[0].jaccard - 1.0).abs() < 1e-9);
+        // 1 / (2 + 10 - 1)
+        assert!((scored[1].jaccard - 1.0 / 11.0).abs() < 1e-9);
+        assert!(scored[0].score > scored[1].score);
+    }
+
+    #[test]
+    fn test_suggestion_reason() {
+        assert_eq!(suggestion_reason(1, Some("alice")), "Followed by alice");
+        assert_eq!(
+            suggestion_reason(2, Some("alice")),
+            "Followed by alice and 1 other"
+        );
+        assert_eq!(
+            suggestion_reason(4, Some("alice")),
+            "Followed by alice and 3 others"
+        );
+        assert_eq!(
+            suggestion_reason(3, None),
+            "Followed by 3 people you follow"
+        );
+        assert_eq!(suggestion_reason(0, None), "Suggested for you");
+    }
+}
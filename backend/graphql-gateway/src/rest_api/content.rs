@@ -78,6 +78,7 @@ pub struct CreatePostBody {
     pub media_urls: Option<Vec<String>>,
     pub media_type: Option<String>,
     pub channel_ids: Option<Vec<String>>, // Channel UUIDs or slugs (max 3)
+    pub audience_list_id: Option<String>, // Audience list UUID or "close_friends"
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,6 +86,7 @@ pub struct UpdatePostBody {
     pub content: Option<String>,
     pub visibility: Option<String>,
     pub comments_enabled: Option<bool>,
+    pub audience_list_id: Option<String>, // Empty string resets to the default audience
 }
 
 #[derive(Debug, Deserialize)]
@@ -162,9 +164,18 @@ async fn fetch_author_info(
     }
 }
 
+/// Authenticated viewer for audience list checks (empty when anonymous)
+fn viewer_id(req: &HttpRequest) -> String {
+    req.extensions()
+        .get::<AuthenticatedUser>()
+        .map(|AuthenticatedUser(id)| id.to_string())
+        .unwrap_or_default()
+}
+
 /// GET /api/v2/content/{id}
 /// Returns a specific post by ID with enriched author information
 pub async fn get_post(
+    req: HttpRequest,
     path: web::Path<String>,
     clients: web::Data<ServiceClients>,
 ) -> Result<HttpResponse> {
@@ -176,6 +187,7 @@ pub async fn get_post(
 
     let grpc_request = tonic::Request::new(GetPostRequest {
         post_id: post_id.clone(),
+        viewer_id: viewer_id(&req),
     });
 
     match content_client.get_post(grpc_request).await {
@@ -246,6 +258,7 @@ pub async fn get_post(
 /// GET /api/v2/content/user/{user_id}
 /// Returns posts by a specific user with enriched author information
 pub async fn get_user_posts(
+    req: HttpRequest,
     path: web::Path<String>,
     clients: web::Data<ServiceClients>,
     query: web::Query<UserPostsQueryParams>,
@@ -268,6 +281,7 @@ pub async fn get_user_posts(
         limit,
         offset,
         status: 2, // PUBLISHED
+        viewer_id: viewer_id(&req),
     });
 
     match content_client.get_user_posts(grpc_request).await {
@@ -370,6 +384,7 @@ pub async fn create_post(
         }),
         channel_ids,
         author_account_type: String::new(), // Default to primary (Issue #259)
+        audience_list_id: body.audience_list_id.clone().unwrap_or_default(),
     });
 
     // Retry config for transient failures (503 Service Unavailable)
@@ -536,6 +551,8 @@ pub async fn update_post(
         content: body.content.clone().unwrap_or_default(),
         visibility,
         comments_enabled: body.comments_enabled.unwrap_or(true),
+        audience_list_id: body.audience_list_id.clone().unwrap_or_default(),
+        clear_audience_list: body.audience_list_id.as_deref() == Some(""),
     });
 
     match content_client.update_post(grpc_request).await {
@@ -184,6 +184,7 @@ pub async fn create_poll(
         tags: body.tags.unwrap_or_default(),
         ends_at,
         initial_candidates,
+        ..Default::default()
     };
 
     // Create request with user_id in metadata
@@ -400,6 +401,7 @@ pub async fn vote_on_poll(
     let req = GrpcVoteOnPollRequest {
         poll_id: poll_id_str,
         candidate_id,
+        ..Default::default()
     };
 
     // Create request with user_id in metadata
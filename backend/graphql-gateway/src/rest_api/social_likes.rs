@@ -302,6 +302,7 @@ pub async fn get_comments(
         limit: q.limit.unwrap_or(50) as i32,
         offset: q.offset.unwrap_or(0) as i32,
         viewer_user_id,
+        ..Default::default()
     };
     match clients
         .call_social(|| {
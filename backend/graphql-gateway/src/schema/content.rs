@@ -54,8 +54,13 @@ impl ContentQuery {
 
         let mut client = clients.content_client();
 
-        let request =
-            tonic::Request::new(crate::clients::proto::content::GetPostRequest { post_id: id });
+        let viewer_id = get_authenticated_user_id(ctx)
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+        let request = tonic::Request::new(crate::clients::proto::content::GetPostRequest {
+            post_id: id,
+            viewer_id,
+        });
 
         match client.get_post(request).await {
             Ok(response) => {
@@ -175,6 +180,7 @@ impl ContentMutation {
             media_type: String::new(),
             channel_ids: vec![],
             author_account_type: String::new(), // Default to primary (Issue #259)
+            audience_list_id: String::new(),
         });
 
         let response = client
@@ -227,6 +233,7 @@ async fn delete_post_impl(ctx: &Context<'_>, id: String) -> Result<bool, String>
             let mut client = clients_clone.content_client();
             let get_req = tonic::Request::new(crate::clients::proto::content::GetPostRequest {
                 post_id: id_clone,
+                viewer_id: current_user_id.to_string(),
             });
             client.get_post(get_req).await
         })
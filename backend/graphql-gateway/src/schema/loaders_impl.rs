@@ -71,11 +71,13 @@ impl Loader<Uuid> for UserProfileLoader {
 #[derive(Clone)]
 pub struct PostLoader {
     clients: Arc<ServiceClients>,
+    /// Viewer for audience list checks (None = anonymous)
+    viewer_id: Option<Uuid>,
 }
 
 impl PostLoader {
-    pub fn new(clients: Arc<ServiceClients>) -> Self {
-        Self { clients }
+    pub fn new(clients: Arc<ServiceClients>, viewer_id: Option<Uuid>) -> Self {
+        Self { clients, viewer_id }
     }
 }
 
@@ -91,6 +93,10 @@ impl Loader<Uuid> for PostLoader {
 
         let request = tonic::Request::new(content::GetPostsByIdsRequest {
             post_ids,
+            viewer_id: self
+                .viewer_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
         });
 
         let response = client
@@ -17,6 +17,12 @@ pub enum ApnsError {
     StartServer(String),
     Internal,
     Config(String),
+    /// APNs answered with an error status; `reason` is the APNs reason code
+    /// (e.g. `BadDeviceToken`, `Unregistered`, `TooManyRequests`)
+    Rejected {
+        status: u16,
+        reason: String,
+    },
 }
 
 impl std::fmt::Display for ApnsError {
@@ -25,6 +31,13 @@ impl std::fmt::Display for ApnsError {
             ApnsError::StartServer(msg) => write!(f, "Failed to start APNs server: {}", msg),
             ApnsError::Internal => write!(f, "Internal APNs error"),
             ApnsError::Config(msg) => write!(f, "APNs configuration error: {}", msg),
+            ApnsError::Rejected { status, reason } => {
+                write!(
+                    f,
+                    "APNs rejected notification ({}, reason: {})",
+                    status, reason
+                )
+            }
         }
     }
 }
@@ -184,6 +197,21 @@ impl PushProvider for ApnsPush {
                 );
                 Ok(())
             }
+            Err(a2::Error::ResponseError(response)) => {
+                let reason = response
+                    .error
+                    .as_ref()
+                    .map(|body| format!("{:?}", body.reason))
+                    .unwrap_or_else(|| "Unknown".to_string());
+                error!(
+                    "APNs rejected notification for token {}: {} {}",
+                    device_token_prefix, response.code, reason
+                );
+                Err(ApnsError::Rejected {
+                    status: response.code,
+                    reason,
+                })
+            }
             Err(e) => {
                 error!("APNs send failed for token {}: {}", device_token_prefix, e);
                 Err(ApnsError::Config(format!("APNs send failed: {e}")))
@@ -231,6 +259,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rejected_error_display() {
+        let err = ApnsError::Rejected {
+            status: 410,
+            reason: "Unregistered".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "APNs rejected notification (410, reason: Unregistered)"
+        );
+    }
+
     #[test]
     fn test_apns_config_endpoint() {
         let prod_cfg = ApnsConfig::new(
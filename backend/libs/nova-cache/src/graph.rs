@@ -25,6 +25,26 @@ pub struct CachedRelationshipList {
     pub cached_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// One scored follow suggestion candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSuggestion {
+    pub user_id: Uuid,
+    pub score: f64,
+    pub mutual_count: u32,
+    /// A few of the viewer's followees who follow this candidate
+    pub mutual_ids: Vec<Uuid>,
+    pub adamic_adar: f64,
+    pub jaccard: f64,
+    pub pagerank: f64,
+}
+
+/// Precomputed suggestion candidates (before block/mute/follow filtering)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSuggestions {
+    pub candidates: Vec<CachedSuggestion>,
+    pub computed_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// Graph cache operations
 pub struct GraphCache {
     cache: NovaCache,
@@ -245,6 +265,34 @@ impl GraphCache {
         Ok(())
     }
 
+    // ============= Follow Suggestions =============
+
+    /// Get precomputed suggestion candidates for a user
+    pub async fn get_suggestions(&self, user_id: Uuid) -> CacheResult<Option<CachedSuggestions>> {
+        let key = CacheKey::suggestions(user_id);
+        self.cache.get(&key).await
+    }
+
+    /// Cache suggestion candidates for a user
+    pub async fn set_suggestions(
+        &self,
+        user_id: Uuid,
+        candidates: Vec<CachedSuggestion>,
+    ) -> CacheResult<()> {
+        let key = CacheKey::suggestions(user_id);
+        let cached = CachedSuggestions {
+            candidates,
+            computed_at: chrono::Utc::now(),
+        };
+        self.cache.set(&key, &cached, ttl::SUGGESTIONS).await
+    }
+
+    /// Invalidate suggestion candidates for a user
+    pub async fn invalidate_suggestions(&self, user_id: Uuid) -> CacheResult<()> {
+        let key = CacheKey::suggestions(user_id);
+        self.cache.del(&key).await
+    }
+
     // ============= Relationship Change Invalidation =============
 
     /// Invalidate all caches affected by a new follow
@@ -263,6 +311,9 @@ impl GraphCache {
         let feed_key = CacheKey::feed(follower_id);
         let _ = self.cache.del(&feed_key).await;
 
+        // Follower's friends-of-friends changed; recompute suggestions on next read
+        let _ = self.invalidate_suggestions(follower_id).await;
+
         debug!(
             follower = %follower_id,
             followee = %followee_id,
@@ -76,6 +76,12 @@ impl CacheKey {
         )
     }
 
+    /// Precomputed follow suggestion candidates
+    /// Format: v2:graph:suggestions:{user_id}
+    pub fn suggestions(user_id: Uuid) -> String {
+        format!("v{}:graph:suggestions:{}", CACHE_VERSION, user_id)
+    }
+
     /// Pattern for all graph keys of a user (as subject)
     pub fn graph_user_pattern(user_id: Uuid) -> String {
         format!("v{}:graph:*:{}*", CACHE_VERSION, user_id)
@@ -165,6 +171,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_suggestions_key() {
+        let user_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(
+            CacheKey::suggestions(user_id),
+            "v2:graph:suggestions:550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
     #[test]
     fn test_is_following_key() {
         let follower = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
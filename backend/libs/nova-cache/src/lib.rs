@@ -39,6 +39,7 @@ pub mod ttl {
     pub const FOLLOWING: u64 = 1800; // 30 minutes
     pub const FOLLOWERS: u64 = 1800; // 30 minutes
     pub const IS_FOLLOWING: u64 = 1800; // 30 minutes
+    pub const SUGGESTIONS: u64 = 21600; // 6 hours
     pub const POST: u64 = 3600; // 1 hour
     pub const USER: u64 = 3600; // 1 hour
     pub const SEARCH: u64 = 3600; // 1 hour
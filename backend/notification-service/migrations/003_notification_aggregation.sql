@@ -0,0 +1,51 @@
+-- Migration: 003_notification_aggregation
+-- Description: Group similar notifications, track push cooldowns and roll low-priority types into daily/weekly digests.
+
+BEGIN;
+
+-- Aggregation group ("like:post:<id>", "follow", ...) and delivery bookkeeping
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS group_key TEXT,
+    ADD COLUMN IF NOT EXISTS last_pushed_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS digest_pending BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS digest_id UUID;
+
+-- Lookup of the open group a new notification merges into
+CREATE INDEX IF NOT EXISTS idx_notifications_open_group
+    ON notifications(recipient_id, group_key, created_at DESC)
+    WHERE group_key IS NOT NULL AND is_read = FALSE AND deleted_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_notifications_digest_pending
+    ON notifications(recipient_id)
+    WHERE digest_pending = TRUE AND digest_id IS NULL;
+
+ALTER TABLE notification_preferences
+    ADD COLUMN IF NOT EXISTS digest_frequency TEXT NOT NULL DEFAULT 'off';
+
+ALTER TABLE notification_preferences
+    DROP CONSTRAINT IF EXISTS notification_preferences_digest_frequency_check;
+ALTER TABLE notification_preferences
+    ADD CONSTRAINT notification_preferences_digest_frequency_check
+    CHECK (digest_frequency IN ('off', 'daily', 'weekly'));
+
+CREATE INDEX IF NOT EXISTS idx_notification_preferences_digest
+    ON notification_preferences(digest_frequency)
+    WHERE digest_frequency <> 'off';
+
+-- One digest per user and period
+CREATE TABLE IF NOT EXISTS notification_digests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    period TEXT NOT NULL CHECK (period IN ('daily', 'weekly')),
+    period_start TIMESTAMPTZ NOT NULL,
+    notification_count INTEGER NOT NULL DEFAULT 0,
+    summary JSONB NOT NULL DEFAULT '{}'::jsonb,
+    notification_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, period, period_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_digests_user
+    ON notification_digests(user_id, created_at DESC);
+
+COMMIT;
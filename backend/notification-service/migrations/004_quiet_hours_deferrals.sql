@@ -0,0 +1,27 @@
+-- Migration: 004_quiet_hours_deferrals
+-- Description: Per-user timezone for quiet hours and persisted deferred pushes released when quiet hours end.
+
+BEGIN;
+
+-- IANA timezone name (e.g. "Europe/Berlin"); quiet hours are evaluated in it
+ALTER TABLE notification_preferences
+    ADD COLUMN IF NOT EXISTS timezone TEXT NOT NULL DEFAULT 'UTC';
+
+-- Pushes held back by quiet hours; one row per notification
+CREATE TABLE IF NOT EXISTS notification_deferrals (
+    notification_id UUID PRIMARY KEY,
+    recipient_id UUID NOT NULL,
+    release_at TIMESTAMPTZ NOT NULL,
+    released_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_deferrals_due
+    ON notification_deferrals(release_at)
+    WHERE released_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_notification_deferrals_recipient
+    ON notification_deferrals(recipient_id)
+    WHERE released_at IS NULL;
+
+COMMIT;
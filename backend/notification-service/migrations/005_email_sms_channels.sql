@@ -0,0 +1,81 @@
+-- Migration: 005_email_sms_channels
+-- Description: Email/SMS destinations, delivery attempt records and bounce/unsubscribe suppressions.
+
+BEGIN;
+
+-- Delivery destinations used by NotificationService: push tokens, email
+-- addresses and E.164 phone numbers (token column), keyed by channel.
+-- Older deployments created this table by hand; fail on a table missing
+-- columns the service reads instead of silently keeping it.
+DO $$
+DECLARE
+    missing TEXT;
+BEGIN
+    IF to_regclass('public.device_tokens') IS NULL THEN
+        CREATE TABLE device_tokens (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL,
+            token TEXT NOT NULL,
+            channel TEXT NOT NULL, -- 'fcm', 'apns', 'websocket', 'email', 'sms'
+            device_type TEXT NOT NULL DEFAULT 'unknown',
+            is_active BOOLEAN NOT NULL DEFAULT TRUE,
+            last_used_at TIMESTAMPTZ,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        );
+    ELSE
+        SELECT string_agg(required.name, ', ') INTO missing
+        FROM unnest(ARRAY[
+            'id', 'user_id', 'token', 'channel', 'device_type', 'is_active',
+            'last_used_at', 'created_at'
+        ]) AS required(name)
+        WHERE NOT EXISTS (
+            SELECT 1 FROM information_schema.columns c
+            WHERE c.table_schema = 'public' AND c.table_name = 'device_tokens'
+              AND c.column_name = required.name
+        );
+        IF missing IS NOT NULL THEN
+            RAISE EXCEPTION 'device_tokens exists but lacks columns: %', missing;
+        END IF;
+    END IF;
+END $$;
+
+-- Target of the registration upsert (ON CONFLICT (user_id, token, channel))
+CREATE UNIQUE INDEX IF NOT EXISTS uq_device_tokens_user_token_channel
+    ON device_tokens(user_id, token, channel);
+CREATE INDEX IF NOT EXISTS idx_device_tokens_user_active
+    ON device_tokens(user_id) WHERE is_active = TRUE;
+CREATE INDEX IF NOT EXISTS idx_device_tokens_channel_token
+    ON device_tokens(channel, token);
+
+-- One row per delivery to one destination
+CREATE TABLE IF NOT EXISTS delivery_attempts (
+    id UUID PRIMARY KEY,
+    notification_id UUID NOT NULL,
+    device_token_id UUID NOT NULL,
+    channel TEXT NOT NULL,
+    status TEXT NOT NULL, -- 'delivered', 'failed'
+    error_message TEXT,
+    retry_count INT NOT NULL DEFAULT 0,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    retry_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_attempts_notification
+    ON delivery_attempts(notification_id);
+CREATE INDEX IF NOT EXISTS idx_delivery_attempts_token_time
+    ON delivery_attempts(device_token_id, attempted_at DESC);
+-- Retention pruning deletes the oldest attempts first
+CREATE INDEX IF NOT EXISTS idx_delivery_attempts_attempted_at
+    ON delivery_attempts(attempted_at);
+
+-- Destinations that must not be sent to again (hard bounce, spam complaint,
+-- unsubscribe link or SMS STOP)
+CREATE TABLE IF NOT EXISTS channel_suppressions (
+    channel TEXT NOT NULL CHECK (channel IN ('email', 'sms')),
+    destination TEXT NOT NULL,
+    reason TEXT NOT NULL CHECK (reason IN ('bounce', 'complaint', 'unsubscribe')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (channel, destination)
+);
+
+COMMIT;
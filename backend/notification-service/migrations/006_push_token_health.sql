@@ -0,0 +1,29 @@
+-- Migration: 006_push_token_health
+-- Description: Per-token push delivery health and provider error classification.
+
+BEGIN;
+
+-- Send outcomes per FCM/APNs token. token_id refers to push_tokens (gRPC
+-- PushSender) or device_tokens (NotificationService), named by token_store.
+CREATE TABLE IF NOT EXISTS push_token_health (
+    token_id UUID PRIMARY KEY,
+    token_store TEXT NOT NULL CHECK (token_store IN ('push_tokens', 'device_tokens')),
+    provider TEXT NOT NULL, -- 'fcm', 'apns'
+    success_count BIGINT NOT NULL DEFAULT 0,
+    failure_count BIGINT NOT NULL DEFAULT 0,
+    consecutive_failures INT NOT NULL DEFAULT 0,
+    last_failure_kind TEXT CHECK (last_failure_kind IN (
+        'unregistered', 'bad_token', 'throttled', 'payload_too_large', 'transient', 'unknown'
+    )),
+    last_success_at TIMESTAMPTZ,
+    last_failure_at TIMESTAMPTZ,
+    deactivated_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Unhealthy tokens first, for dashboards and cleanup jobs
+CREATE INDEX IF NOT EXISTS idx_push_token_health_failing
+    ON push_token_health(consecutive_failures DESC)
+    WHERE consecutive_failures > 0;
+
+COMMIT;
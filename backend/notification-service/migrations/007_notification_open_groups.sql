@@ -0,0 +1,27 @@
+-- Migration: 007_notification_open_groups
+-- Description: At most one open aggregation group per recipient and group key,
+-- so concurrent first events cannot each start a group.
+
+BEGIN;
+
+-- The notification new events for (recipient_id, group_key) merge into
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS group_open BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Reopen the newest unread, undigested row of each existing group
+UPDATE notifications n
+SET group_open = TRUE
+FROM (
+    SELECT DISTINCT ON (recipient_id, group_key) id
+    FROM notifications
+    WHERE group_key IS NOT NULL AND is_read = FALSE AND deleted_at IS NULL
+      AND digest_id IS NULL
+    ORDER BY recipient_id, group_key, created_at DESC
+) latest
+WHERE n.id = latest.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_notifications_open_group
+    ON notifications(recipient_id, group_key)
+    WHERE group_open;
+
+COMMIT;
@@ -0,0 +1,15 @@
+-- Migration: 008_deferral_leases
+-- Description: Lease deferrals while they are being released so a failed push
+-- is retried instead of being marked released before it was sent.
+
+BEGIN;
+
+-- A worker owns the row until this time; expired leases are claimed again
+ALTER TABLE notification_deferrals
+    ADD COLUMN IF NOT EXISTS claimed_until TIMESTAMPTZ;
+
+-- Release attempts so far; the deferral is dropped after too many failures
+ALTER TABLE notification_deferrals
+    ADD COLUMN IF NOT EXISTS attempts INTEGER NOT NULL DEFAULT 0;
+
+COMMIT;
@@ -27,7 +27,7 @@ use nova::notification_service::v2::*;
 use crate::models::{
     CreateNotificationRequest as CoreCreateRequest, NotificationPriority, NotificationType,
 };
-use crate::services::{NotificationService as CoreNotificationService, PushSender};
+use crate::services::{Delivery, NotificationService as CoreNotificationService, PushSender};
 
 #[derive(Clone)]
 pub struct NotificationServiceImpl {
@@ -314,16 +314,23 @@ impl NotificationService for NotificationServiceImpl {
             priority: NotificationPriority::Normal,
         };
 
-        match self.core_service.create_notification(core_req).await {
-            Ok(notif) => {
-                // Send push notifications asynchronously
-                let sender = self.push_sender.clone();
-                let notif_clone = notif.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = sender_send_push_for_notification(&sender, &notif_clone).await {
-                        warn!("Failed to send push notifications: {}", e);
-                    }
-                });
+        match self.core_service.ingest_notification(core_req).await {
+            Ok(ingested) => {
+                let notif = ingested.notification;
+
+                // Send push notifications asynchronously, unless aggregation
+                // routed this one to a digest or suppressed it
+                if ingested.delivery == Delivery::Push {
+                    let sender = self.push_sender.clone();
+                    let notif_clone = notif.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) =
+                            sender_send_push_for_notification(&sender, &notif_clone).await
+                        {
+                            warn!("Failed to send push notifications: {}", e);
+                        }
+                    });
+                }
 
                 Ok(Response::new(CreateNotificationResponse {
                     notification: Some(Notification {
@@ -509,6 +516,8 @@ impl NotificationService for NotificationServiceImpl {
                     disable_all: !pref.enabled,
                     created_at: 0,
                     updated_at: pref.updated_at.timestamp(),
+                    digest_frequency: pref.digest_frequency.as_str().to_string(),
+                    timezone: pref.timezone.unwrap_or_else(|| "UTC".to_string()),
                 }),
             })),
             Err(e) => {
@@ -718,15 +727,18 @@ impl NotificationService for NotificationServiceImpl {
 
 /// Helper function to send push notifications for a notification
 async fn sender_send_push_for_notification(
-    _sender: &PushSender,
+    sender: &PushSender,
     notif: &crate::models::Notification,
 ) -> Result<(), String> {
-    // Get user's push tokens from database
-    // For now, this is a placeholder. In production, you would:
-    // 1. Query push_tokens table
-    // 2. Create PushRequest for each token
-    // 3. Call sender.send_batch()
-
     info!("Sending push notifications for notification {}", notif.id);
+
+    let results = sender.send_to_user(notif).await?;
+    let invalidated = results.iter().filter(|r| r.should_invalidate).count();
+    if invalidated > 0 {
+        debug!(
+            "{} push tokens invalidated while sending {}",
+            invalidated, notif.id
+        );
+    }
     Ok(())
 }
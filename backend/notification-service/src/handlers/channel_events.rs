@@ -0,0 +1,204 @@
+use super::ApiResponse;
+use crate::models::NotificationChannel;
+use crate::services::channels::{is_sms_opt_out, suppression, UnsubscribeLinks};
+use crate::services::NotificationService;
+/// Email/SMS channel callbacks: provider bounce webhooks, inbound SMS STOP and
+/// one-click unsubscribe links
+use actix_web::{web, HttpRequest, HttpResponse, Result as ActixResult};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Email provider event (bounce / complaint / unsubscribe)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmailEventPayload {
+    pub email: String,
+    /// "bounce", "complaint" or "unsubscribe"
+    pub event: String,
+    /// "hard" or "soft" (bounces only; defaults to hard)
+    pub bounce_type: Option<String>,
+}
+
+/// Inbound SMS forwarded by the gateway
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InboundSmsPayload {
+    pub from: String,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    pub token: String,
+}
+
+/// Provider webhooks must send `X-Webhook-Token: $NOTIFICATION_CHANNEL_WEBHOOK_TOKEN`
+fn webhook_authorized(req: &HttpRequest) -> bool {
+    let Ok(expected) = std::env::var("NOTIFICATION_CHANNEL_WEBHOOK_TOKEN") else {
+        return false;
+    };
+    !expected.is_empty()
+        && req
+            .headers()
+            .get("x-webhook-token")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|token| token == expected)
+}
+
+/// Email bounce / complaint / unsubscribe webhook
+///
+/// POST /api/v1/channels/email/events
+pub async fn email_event(
+    service: web::Data<Arc<NotificationService>>,
+    http_req: HttpRequest,
+    req: web::Json<EmailEventPayload>,
+) -> ActixResult<HttpResponse> {
+    if !webhook_authorized(&http_req) {
+        return Ok(
+            HttpResponse::Unauthorized().json(ApiResponse::<String>::err(
+                "Invalid webhook token".to_string(),
+            )),
+        );
+    }
+
+    let reason = match req.event.to_lowercase().as_str() {
+        "bounce" if req.bounce_type.as_deref() == Some("soft") => {
+            // Providers retry soft bounces themselves
+            return Ok(HttpResponse::Ok().json(ApiResponse::ok(serde_json::json!({
+                "suppressed": false
+            }))));
+        }
+        "bounce" => suppression::BOUNCE,
+        "complaint" => suppression::COMPLAINT,
+        "unsubscribe" => suppression::UNSUBSCRIBE,
+        other => {
+            return Ok(
+                HttpResponse::BadRequest().json(ApiResponse::<String>::err(format!(
+                    "Unknown email event: {}",
+                    other
+                ))),
+            )
+        }
+    };
+
+    match service
+        .suppress_destination(NotificationChannel::Email, &req.email, reason)
+        .await
+    {
+        Ok(deactivated) => Ok(HttpResponse::Ok().json(ApiResponse::ok(serde_json::json!({
+            "suppressed": true,
+            "deactivated_tokens": deactivated
+        })))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(ApiResponse::<String>::err(e))),
+    }
+}
+
+/// Inbound SMS webhook; STOP-style keywords opt the number out
+///
+/// POST /api/v1/channels/sms/inbound
+pub async fn inbound_sms(
+    service: web::Data<Arc<NotificationService>>,
+    http_req: HttpRequest,
+    req: web::Json<InboundSmsPayload>,
+) -> ActixResult<HttpResponse> {
+    if !webhook_authorized(&http_req) {
+        return Ok(
+            HttpResponse::Unauthorized().json(ApiResponse::<String>::err(
+                "Invalid webhook token".to_string(),
+            )),
+        );
+    }
+
+    if !is_sms_opt_out(&req.body) {
+        return Ok(HttpResponse::Ok().json(ApiResponse::ok(serde_json::json!({
+            "suppressed": false
+        }))));
+    }
+
+    match service
+        .suppress_destination(
+            NotificationChannel::SMS,
+            &req.from,
+            suppression::UNSUBSCRIBE,
+        )
+        .await
+    {
+        Ok(deactivated) => Ok(HttpResponse::Ok().json(ApiResponse::ok(serde_json::json!({
+            "suppressed": true,
+            "deactivated_tokens": deactivated
+        })))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(ApiResponse::<String>::err(e))),
+    }
+}
+
+/// Unsubscribe confirmation page for the link in the email body
+///
+/// GET /api/v1/channels/unsubscribe?token=...
+///
+/// Mail scanners and link previews follow GET links, so this only renders a
+/// form that posts back to `unsubscribe`.
+pub async fn unsubscribe_page(query: web::Query<UnsubscribeQuery>) -> ActixResult<HttpResponse> {
+    if UnsubscribeLinks::from_env()
+        .and_then(|links| links.verify(&query.token))
+        .is_none()
+    {
+        return Ok(invalid_unsubscribe_link());
+    }
+
+    // Verified tokens are JWTs (base64url and dots), safe inside an attribute
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(format!(
+            "<p>Stop receiving these emails?</p>\
+             <form method=\"post\" action=\"?token={}\">\
+             <button type=\"submit\">Unsubscribe</button></form>",
+            query.token
+        )))
+}
+
+/// Unsubscribe from the confirmation page or a mail client (RFC 8058 one-click)
+///
+/// POST /api/v1/channels/unsubscribe?token=...
+pub async fn unsubscribe(
+    service: web::Data<Arc<NotificationService>>,
+    query: web::Query<UnsubscribeQuery>,
+) -> ActixResult<HttpResponse> {
+    let Some(request) = UnsubscribeLinks::from_env().and_then(|links| links.verify(&query.token))
+    else {
+        return Ok(invalid_unsubscribe_link());
+    };
+
+    match service
+        .suppress_destination(
+            request.channel,
+            &request.destination,
+            suppression::UNSUBSCRIBE,
+        )
+        .await
+    {
+        Ok(_) => Ok(HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body("<p>You have been unsubscribed and will no longer receive these emails.</p>")),
+        Err(e) => {
+            tracing::warn!("Unsubscribe for {} failed: {}", request.user_id, e);
+            Ok(HttpResponse::InternalServerError()
+                .content_type("text/html; charset=utf-8")
+                .body("<p>Something went wrong. Please try again later.</p>"))
+        }
+    }
+}
+
+fn invalid_unsubscribe_link() -> HttpResponse {
+    HttpResponse::BadRequest()
+        .content_type("text/html; charset=utf-8")
+        .body("<p>This unsubscribe link is invalid or has expired.</p>")
+}
+
+/// Register routes
+pub fn register_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/channels")
+            .route("/email/events", web::post().to(email_event))
+            .route("/sms/inbound", web::post().to(inbound_sms))
+            .route("/unsubscribe", web::get().to(unsubscribe_page))
+            .route("/unsubscribe", web::post().to(unsubscribe)),
+    );
+}
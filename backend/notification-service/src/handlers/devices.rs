@@ -1,5 +1,6 @@
 use super::ApiResponse;
 use crate::models::NotificationChannel;
+use crate::services::channels::normalize_destination;
 use crate::services::NotificationService;
 /// Device token management handlers
 use actix_web::{web, HttpResponse, Result as ActixResult};
@@ -12,7 +13,7 @@ use uuid::Uuid;
 pub struct RegisterDevicePayload {
     pub user_id: Uuid,
     pub token: String,
-    pub channel: String,     // "fcm", "apns", "websocket"
+    pub channel: String,     // "fcm", "apns", "websocket", "email", "sms"
     pub device_type: String, // "ios", "android", "web"
 }
 
@@ -31,6 +32,18 @@ pub async fn register_device(
     req: web::Json<RegisterDevicePayload>,
 ) -> ActixResult<HttpResponse> {
     let channel = parse_channel(&req.channel);
+    if matches!(
+        channel,
+        NotificationChannel::Email | NotificationChannel::SMS
+    ) && normalize_destination(channel, &req.token).is_none()
+    {
+        return Ok(
+            HttpResponse::BadRequest().json(ApiResponse::<String>::err(format!(
+                "Invalid {} destination",
+                channel.as_str()
+            ))),
+        );
+    }
 
     match service
         .register_device_token(
@@ -82,6 +95,25 @@ pub async fn get_user_devices(
     }
 }
 
+/// Get push delivery health (success rate, last error) for a device
+///
+/// GET /api/v1/devices/{id}/health
+pub async fn get_device_health(
+    service: web::Data<Arc<NotificationService>>,
+    path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse> {
+    match service.get_device_health(path.into_inner()).await {
+        Ok(Some(health)) => Ok(HttpResponse::Ok().json(ApiResponse::ok(serde_json::json!({
+            "success_rate": health.success_rate(),
+            "health": health
+        })))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::<String>::err(
+            "No deliveries recorded for this device".to_string(),
+        ))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<String>::err(e))),
+    }
+}
+
 /// Helper function to parse channel string
 fn parse_channel(s: &str) -> NotificationChannel {
     match s.to_lowercase().as_str() {
@@ -100,6 +132,7 @@ pub fn register_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/api/v1/devices")
             .route("/register", web::post().to(register_device))
             .route("/unregister", web::post().to(unregister_device))
-            .route("/user/{user_id}", web::get().to(get_user_devices)),
+            .route("/user/{user_id}", web::get().to(get_user_devices))
+            .route("/{id}/health", web::get().to(get_device_health)),
     );
 }
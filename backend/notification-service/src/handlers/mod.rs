@@ -1,9 +1,11 @@
+pub mod channel_events;
 pub mod devices;
 /// HTTP handlers for notification service API
 pub mod notifications;
 pub mod preferences;
 pub mod websocket;
 
+pub use channel_events::register_routes as register_channels;
 pub use devices::*;
 pub use notifications::*;
 pub use preferences::*;
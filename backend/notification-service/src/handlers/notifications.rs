@@ -1,5 +1,5 @@
 use crate::models::CreateNotificationRequest;
-use crate::services::NotificationService;
+use crate::services::{NotificationService, ReceiptEvent};
 /// Notification CRUD handlers
 use actix_web::{web, HttpResponse, Result as ActixResult};
 use serde::{Deserialize, Serialize};
@@ -109,6 +109,38 @@ pub async fn mark_as_read(
     }
 }
 
+/// Client receipt for the push funnel
+#[derive(Debug, Deserialize)]
+pub struct ReceiptPayload {
+    /// "delivered" or "opened"
+    pub event: String,
+}
+
+/// Record that a push arrived on, or was opened from, a device
+///
+/// POST /api/v1/notifications/{id}/receipt
+pub async fn record_receipt(
+    service: web::Data<Arc<NotificationService>>,
+    path: web::Path<Uuid>,
+    req: web::Json<ReceiptPayload>,
+) -> ActixResult<HttpResponse> {
+    let Some(event) = ReceiptEvent::parse(&req.event) else {
+        return Ok(
+            HttpResponse::BadRequest().json(ApiResponse::<String>::err(format!(
+                "Unknown receipt event '{}': expected delivered or opened",
+                req.event
+            ))),
+        );
+    };
+
+    match service.record_receipt(path.into_inner(), event).await {
+        Ok(recorded) => Ok(HttpResponse::Ok().json(ApiResponse::ok(serde_json::json!({
+            "recorded": recorded
+        })))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<String>::err(e))),
+    }
+}
+
 /// Send push notifications to a user
 ///
 /// POST /api/v1/notifications/{id}/send
@@ -163,6 +195,7 @@ pub fn register_routes(cfg: &mut web::ServiceConfig) {
             .route("", web::post().to(create_notification))
             .route("/{id}", web::get().to(get_notification))
             .route("/{id}/read", web::put().to(mark_as_read))
+            .route("/{id}/receipt", web::post().to(record_receipt))
             .route("/{id}/send", web::post().to(send_notification)),
     );
 }
@@ -1,4 +1,6 @@
 use super::ApiResponse;
+use crate::models::DigestFrequency;
+use crate::services::quiet_hours::{parse_time, parse_timezone};
 use crate::services::NotificationService;
 /// Notification preferences handlers
 use actix_web::{web, HttpResponse, Result as ActixResult};
@@ -18,9 +20,13 @@ pub struct UpdatePreferencesPayload {
     pub stream_enabled: Option<bool>,
     pub quiet_hours_start: Option<String>,
     pub quiet_hours_end: Option<String>,
+    /// IANA timezone for quiet hours, e.g. "Europe/Berlin"
+    pub timezone: Option<String>,
     pub prefer_fcm: Option<bool>,
     pub prefer_apns: Option<bool>,
     pub prefer_email: Option<bool>,
+    /// "off", "daily" or "weekly"
+    pub digest_frequency: Option<String>,
 }
 
 /// Get user's notification preferences
@@ -48,6 +54,31 @@ pub async fn update_preferences(
 ) -> ActixResult<HttpResponse> {
     let user_id = path.into_inner();
 
+    // Empty strings clear quiet hours; anything else must be HH:MM
+    for bound in [&req.quiet_hours_start, &req.quiet_hours_end]
+        .into_iter()
+        .flatten()
+    {
+        if !bound.is_empty() && parse_time(bound).is_none() {
+            return Ok(
+                HttpResponse::BadRequest().json(ApiResponse::<String>::err(format!(
+                    "Invalid quiet hours time (expected HH:MM): {}",
+                    bound
+                ))),
+            );
+        }
+    }
+    if let Some(timezone) = &req.timezone {
+        if parse_timezone(timezone).is_none() {
+            return Ok(
+                HttpResponse::BadRequest().json(ApiResponse::<String>::err(format!(
+                    "Invalid timezone: {}",
+                    timezone
+                ))),
+            );
+        }
+    }
+
     // Get current preferences
     match service.get_preferences(user_id).await {
         Ok(mut prefs) => {
@@ -73,11 +104,14 @@ pub async fn update_preferences(
             if let Some(stream_enabled) = req.stream_enabled {
                 prefs.stream_enabled = stream_enabled;
             }
-            if req.quiet_hours_start.is_some() {
-                prefs.quiet_hours_start = req.quiet_hours_start.clone();
+            if let Some(start) = &req.quiet_hours_start {
+                prefs.quiet_hours_start = (!start.is_empty()).then(|| start.clone());
             }
-            if req.quiet_hours_end.is_some() {
-                prefs.quiet_hours_end = req.quiet_hours_end.clone();
+            if let Some(end) = &req.quiet_hours_end {
+                prefs.quiet_hours_end = (!end.is_empty()).then(|| end.clone());
+            }
+            if let Some(timezone) = &req.timezone {
+                prefs.timezone = Some(timezone.trim().to_string());
             }
             if let Some(prefer_fcm) = req.prefer_fcm {
                 prefs.prefer_fcm = prefer_fcm;
@@ -89,9 +123,23 @@ pub async fn update_preferences(
                 prefs.prefer_email = prefer_email;
             }
 
-            // In a full implementation, we would update the database here
-            // For now, we'll just return the updated preferences
-            Ok(HttpResponse::Ok().json(ApiResponse::ok(prefs)))
+            if let Some(frequency) = &req.digest_frequency {
+                match DigestFrequency::parse(frequency) {
+                    Some(frequency) => prefs.digest_frequency = frequency,
+                    None => {
+                        return Ok(HttpResponse::BadRequest().json(ApiResponse::<String>::err(
+                            format!("Invalid digest_frequency: {}", frequency),
+                        )))
+                    }
+                }
+            }
+
+            match service.update_preferences(&prefs).await {
+                Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::ok(prefs))),
+                Err(e) => {
+                    Ok(HttpResponse::InternalServerError().json(ApiResponse::<String>::err(e)))
+                }
+            }
         }
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<String>::err(e))),
     }
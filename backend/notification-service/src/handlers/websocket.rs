@@ -2,18 +2,98 @@
 ///
 /// Implements WebSocket endpoints for connection status and broadcasting.
 /// Real-time WebSocket connections are handled via /ws/{user_id}
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{web, HttpRequest, HttpResponse, Result as ActixResult};
+use actix_web_actors::ws;
+use serde::Deserialize;
 use serde_json::json;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use crate::metrics;
+use crate::websocket::manager::OUTBOUND_QUEUE_CAPACITY;
+use crate::websocket::session::NotificationSession;
 use crate::websocket::WebSocketMessage;
 use crate::ConnectionManager;
 
 /// WebSocket message size limit (256 KB)
-#[allow(dead_code)]
 const WS_MESSAGE_SIZE_LIMIT: usize = 256_000;
 
+#[derive(Debug, Deserialize)]
+pub struct ConnectQuery {
+    /// Last notification sequence the client received, for replay
+    pub last_seq: Option<u64>,
+}
+
+/// Open a real-time notification socket
+///
+/// Endpoint: GET /ws/{user_id}?last_seq={n}
+///
+/// With `last_seq`, notifications issued since are replayed before live
+/// traffic. If the replay buffer no longer reaches back that far the client
+/// gets a `REPLAY_TRUNCATED` error and should refetch over HTTP.
+pub async fn ws_connect(
+    req: HttpRequest,
+    stream: web::Payload,
+    path: web::Path<Uuid>,
+    query: web::Query<ConnectQuery>,
+    connection_manager: web::Data<Arc<ConnectionManager>>,
+) -> ActixResult<HttpResponse> {
+    let user_id = path.into_inner();
+    let manager = connection_manager.get_ref().as_ref().clone();
+
+    let mut initial = vec![WebSocketMessage::connected_to(
+        manager.node_id().to_string(),
+    )];
+    let last_seq = query.last_seq.unwrap_or(0);
+
+    // Subscribe before reading the replay so nothing falls in between;
+    // the session skips live messages the replay already covered
+    let (tx, rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
+    let connection_id = manager.subscribe(user_id, tx).await?;
+
+    if let (Some(seq), Some(cluster)) = (query.last_seq, manager.cluster()) {
+        match cluster.replay_since(user_id, seq).await {
+            Ok(replay) => {
+                metrics::record_ws_messages_replayed("replayed", replay.messages.len());
+                if replay.truncated {
+                    metrics::record_ws_messages_replayed("truncated", 1);
+                    initial.push(WebSocketMessage::error(
+                        "REPLAY_TRUNCATED".to_string(),
+                        "Some notifications are no longer available for replay".to_string(),
+                    ));
+                }
+                initial.extend(replay.messages);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to replay notifications for {}: {}", user_id, e);
+                initial.push(WebSocketMessage::error(
+                    "REPLAY_UNAVAILABLE".to_string(),
+                    "Missed notifications could not be replayed".to_string(),
+                ));
+            }
+        }
+    }
+
+    let session = NotificationSession::new(
+        user_id,
+        connection_id.clone(),
+        manager.clone(),
+        last_seq,
+        initial,
+        rx,
+    );
+    let response = ws::WsResponseBuilder::new(session, &req, stream)
+        .frame_size(WS_MESSAGE_SIZE_LIMIT)
+        .start();
+    if response.is_err() {
+        manager
+            .unsubscribe_connection(user_id, &connection_id)
+            .await?;
+    }
+    response
+}
+
 /// Get WebSocket connection status for a user
 ///
 /// Endpoint: GET /api/v1/ws/status/{user_id}
@@ -24,11 +104,13 @@ pub async fn ws_status(
     let user_id = path.into_inner();
 
     let connection_count = connection_manager.connection_count(user_id).await;
+    let nodes = connection_manager.presence(user_id).await;
 
     Ok(HttpResponse::Ok().json(json!({
         "user_id": user_id.to_string(),
-        "connected": connection_count > 0,
-        "connection_count": connection_count
+        "connected": connection_count > 0 || !nodes.is_empty(),
+        "connection_count": connection_count,
+        "nodes": nodes
     })))
 }
 
@@ -205,6 +287,7 @@ pub async fn list_connected_users(
 
 /// Register WebSocket routes
 pub fn register_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/ws/{user_id}", web::get().to(ws_connect));
     cfg.service(
         web::scope("/api/v1/ws")
             .route("/status/{user_id}", web::get().to(ws_status))
@@ -2,6 +2,7 @@ use actix_web::{middleware, web, App, HttpServer};
 use db_pool::{create_pool as create_pg_pool, DbConfig as DbPoolConfig};
 use notification_service::{
     handlers::{
+        channel_events::register_routes as register_channels,
         devices::register_routes as register_devices,
         notifications::register_routes as register_notifications,
         preferences::register_routes as register_preferences,
@@ -9,8 +10,13 @@ use notification_service::{
     },
     metrics,
     services::{
-        APNsClient, FCMClient, KafkaNotificationConsumer, RedisDeduplicator, ServiceAccountKey,
+        start_deferred_delivery_worker, start_digest_worker, APNsClient, AggregationConfig,
+        DeferredDeliveryConfig, DeferredDeliveryWorker, DigestConfig, DigestWorker, EmailClient,
+        FCMClient, FallbackPolicy, GraphEventsConsumer, KafkaNotificationConsumer,
+        NotificationAggregator, RedisDeduplicator, RetryPolicy, ServiceAccountKey, SmsClient,
+        SmsGatewayConfig, SmtpConfig, UnsubscribeLinks,
     },
+    websocket::{start_cluster_fanout, ClusterBus, ClusterConfig},
     ConnectionManager, NotificationService,
 };
 use std::io;
@@ -171,12 +177,115 @@ async fn main() -> io::Result<()> {
         }
     };
 
-    let notification_service = Arc::new(NotificationService::new(
+    // Initialize WebSocket connection manager; with Redis, sockets on every
+    // pod are reachable (presence registry, pub/sub relay, replay buffer)
+    let mut ws_manager = ConnectionManager::new();
+    if let Some(pool) = redis_pool.as_ref() {
+        match redis::Client::open(redis_url.as_str()) {
+            Ok(client) => {
+                let cluster_config = ClusterConfig::from_env();
+                tracing::info!(
+                    "WebSocket cluster fan-out enabled (node: {}, replay limit: {})",
+                    cluster_config.node_id,
+                    cluster_config.replay_limit
+                );
+                ws_manager = ws_manager.with_cluster(Arc::new(ClusterBus::new(
+                    pool.manager(),
+                    client,
+                    cluster_config,
+                )));
+            }
+            Err(e) => tracing::warn!(
+                "WebSocket cluster fan-out disabled - invalid Redis URL: {}",
+                e
+            ),
+        }
+    } else {
+        tracing::warn!("WebSocket fan-out is local to this node - Redis not available");
+    }
+    start_cluster_fanout(ws_manager.clone());
+    let connection_manager = Arc::new(ws_manager.clone());
+    tracing::info!("WebSocket connection manager initialized");
+
+    let mut notification_service =
+        NotificationService::new(db_pool.clone(), fcm_client.clone(), apns_client.clone());
+    notification_service = notification_service.with_connection_manager(ws_manager);
+
+    // Aggregation + push rate limiting (Redis-backed when available)
+    let aggregation_config = AggregationConfig::from_env();
+    if aggregation_config.enabled {
+        tracing::info!(
+            "Notification aggregation enabled (window: {:?}, push limit: {}/{:?})",
+            aggregation_config.window,
+            aggregation_config.push_limit,
+            aggregation_config.push_window
+        );
+        notification_service = notification_service.with_aggregator(NotificationAggregator::new(
+            aggregation_config,
+            redis_pool.as_ref().map(|pool| pool.manager()),
+        ));
+    }
+
+    // Email (SMTP) and SMS (HTTP gateway) channels, enabled by their env vars
+    notification_service = notification_service.with_fallback_policy(FallbackPolicy::from_env());
+    notification_service = notification_service.with_retry_policy(RetryPolicy::from_env());
+    match SmtpConfig::from_env()
+        .map(|config| EmailClient::new(&config, UnsubscribeLinks::from_env()))
+    {
+        Some(Ok(client)) => {
+            tracing::info!("Email channel enabled");
+            notification_service = notification_service.with_channel_provider(Arc::new(client));
+        }
+        Some(Err(e)) => tracing::warn!("Email channel disabled: {}", e),
+        None => tracing::info!("Email channel disabled (SMTP_HOST not set)"),
+    }
+    match SmsGatewayConfig::from_env().map(SmsClient::new) {
+        Some(Ok(client)) => {
+            tracing::info!("SMS channel enabled");
+            notification_service = notification_service.with_channel_provider(Arc::new(client));
+        }
+        Some(Err(e)) => tracing::warn!("SMS channel disabled: {}", e),
+        None => tracing::info!("SMS channel disabled (SMS_GATEWAY_URL not set)"),
+    }
+    let notification_service = Arc::new(notification_service);
+
+    // Release pushes held back by quiet hours
+    start_deferred_delivery_worker(DeferredDeliveryWorker::new(
         db_pool.clone(),
-        fcm_client.clone(),
-        apns_client.clone(),
+        notification_service.clone(),
+        DeferredDeliveryConfig::from_env(),
     ));
 
+    // Delivery attempts are only needed for recent debugging and retries
+    let retention_days = std::env::var("NOTIFICATION_DELIVERY_ATTEMPT_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(30);
+    let pruning_service = notification_service.clone();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(3600));
+        loop {
+            ticker.tick().await;
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days);
+            match pruning_service.prune_delivery_attempts(cutoff).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Pruned {} old delivery attempts", deleted),
+                Err(e) => tracing::warn!("Delivery attempt pruning failed: {}", e),
+            }
+        }
+    });
+
+    let digest_config = DigestConfig::from_env();
+    if digest_config.enabled {
+        start_digest_worker(DigestWorker::new(
+            db_pool.clone(),
+            notification_service.clone(),
+            digest_config,
+        ));
+    } else {
+        tracing::info!("Digest worker disabled (NOTIFICATION_DIGEST_ENABLED=false)");
+    }
+
     // Start Kafka consumer in background for event-driven notifications
     let kafka_notification_service = notification_service.clone();
     let kafka_broker =
@@ -186,6 +295,8 @@ async fn main() -> io::Result<()> {
         .unwrap_or(true);
 
     if kafka_enabled {
+        let kafka_broker_for_graph = kafka_broker.clone();
+
         // Create deduplicator if Redis is available
         let deduplicator = redis_pool
             .as_ref()
@@ -214,14 +325,25 @@ async fn main() -> io::Result<()> {
                 tracing::error!("Kafka consumer error: {}", e);
             }
         });
+
+        // Resolve follow request notifications when accounts go public
+        let graph_events_service = notification_service.clone();
+        let graph_events_broker = kafka_broker_for_graph;
+        tokio::spawn(async move {
+            let topic = std::env::var("KAFKA_GRAPH_EVENTS_TOPIC").unwrap_or_else(|_| {
+                let prefix =
+                    std::env::var("KAFKA_TOPIC_PREFIX").unwrap_or_else(|_| "nova".to_string());
+                format!("{}.graph.events", prefix)
+            });
+            let consumer = GraphEventsConsumer::new(graph_events_broker, topic);
+            if let Err(e) = consumer.start(graph_events_service).await {
+                tracing::error!("Graph events consumer error: {}", e);
+            }
+        });
     } else {
         tracing::info!("Kafka consumer disabled (KAFKA_ENABLED=false)");
     }
 
-    // Initialize WebSocket connection manager
-    let connection_manager = Arc::new(ConnectionManager::new());
-    tracing::info!("WebSocket connection manager initialized");
-
     // Support both PORT (legacy) and HTTP_PORT/SERVER_PORT (k8s) environment variables
     let http_port = std::env::var("HTTP_PORT")
         .or_else(|_| std::env::var("SERVER_PORT"))
@@ -276,11 +398,10 @@ async fn main() -> io::Result<()> {
             .await;
         tracing::info!("gRPC health check enabled (tonic-health protocol)");
 
-        let push_sender = Arc::new(PushSender::new(
-            grpc_db_pool.clone(),
-            grpc_fcm_client,
-            grpc_apns_client,
-        ));
+        let push_sender = Arc::new(
+            PushSender::new(grpc_db_pool.clone(), grpc_fcm_client, grpc_apns_client)
+                .with_retry_policy(RetryPolicy::from_env()),
+        );
         let svc =
             NotificationServiceImpl::new(grpc_db_pool, grpc_notification_service, push_sender);
 
@@ -399,6 +520,7 @@ async fn main() -> io::Result<()> {
                 register_devices(cfg);
                 register_preferences(cfg);
                 register_websocket(cfg);
+                register_channels(cfg);
             })
     })
     .bind(&addr)?
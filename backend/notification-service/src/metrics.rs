@@ -37,6 +37,292 @@ static HTTP_REQUEST_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     histogram
 });
 
+static NOTIFICATIONS_AGGREGATED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_notifications_aggregated_total",
+            "Events merged into an existing unread notification",
+        ),
+        &["type"],
+    )
+    .expect("failed to create notification_service_notifications_aggregated_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_notifications_aggregated_total");
+    counter
+});
+
+static PUSH_SUPPRESSED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_push_suppressed_total",
+            "Stored notifications that were not pushed",
+        ),
+        &["reason"],
+    )
+    .expect("failed to create notification_service_push_suppressed_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_push_suppressed_total");
+    counter
+});
+
+static DIGESTS_SENT_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_digests_sent_total",
+            "Digest summary notifications created",
+        ),
+        &["period"],
+    )
+    .expect("failed to create notification_service_digests_sent_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_digests_sent_total");
+    counter
+});
+
+static NOTIFICATIONS_DEFERRED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_notifications_deferred_total",
+            "Pushes held back by the recipient's quiet hours",
+        ),
+        &["type"],
+    )
+    .expect("failed to create notification_service_notifications_deferred_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_notifications_deferred_total");
+    counter
+});
+
+static DEFERRED_RELEASED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_deferred_released_total",
+            "Pushes sent when quiet hours ended (single or collapsed)",
+        ),
+        &["mode"],
+    )
+    .expect("failed to create notification_service_deferred_released_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_deferred_released_total");
+    counter
+});
+
+static CHANNEL_DELIVERIES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_channel_deliveries_total",
+            "Email/SMS deliveries by channel and outcome",
+        ),
+        &["channel", "outcome"],
+    )
+    .expect("failed to create notification_service_channel_deliveries_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_channel_deliveries_total");
+    counter
+});
+
+static CHANNEL_SUPPRESSIONS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_channel_suppressions_total",
+            "Email/SMS destinations suppressed by bounce, complaint or unsubscribe",
+        ),
+        &["channel", "reason"],
+    )
+    .expect("failed to create notification_service_channel_suppressions_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_channel_suppressions_total");
+    counter
+});
+
+static WS_CLUSTER_MESSAGES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_ws_cluster_messages_total",
+            "WebSocket messages relayed between nodes over Redis",
+        ),
+        &["direction"],
+    )
+    .expect("failed to create notification_service_ws_cluster_messages_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_ws_cluster_messages_total");
+    counter
+});
+
+static WS_CONNECTIONS_EVICTED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_ws_connections_evicted_total",
+            "WebSocket connections dropped by the server",
+        ),
+        &["reason"],
+    )
+    .expect("failed to create notification_service_ws_connections_evicted_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_ws_connections_evicted_total");
+    counter
+});
+
+static WS_MESSAGES_REPLAYED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_ws_messages_replayed_total",
+            "Notifications replayed to reconnecting WebSocket clients",
+        ),
+        &["outcome"],
+    )
+    .expect("failed to create notification_service_ws_messages_replayed_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_ws_messages_replayed_total");
+    counter
+});
+
+static PUSH_FUNNEL_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_push_funnel_total",
+            "Push notifications by funnel stage (queued, sent, delivered, opened)",
+        ),
+        &["stage"],
+    )
+    .expect("failed to create notification_service_push_funnel_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_push_funnel_total");
+    counter
+});
+
+static PUSH_FAILURES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_push_failures_total",
+            "Failed FCM/APNs sends by provider and classified error",
+        ),
+        &["provider", "kind"],
+    )
+    .expect("failed to create notification_service_push_failures_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_push_failures_total");
+    counter
+});
+
+static PUSH_RETRIES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_push_retries_total",
+            "FCM/APNs send retries after throttled or transient errors",
+        ),
+        &["provider", "kind"],
+    )
+    .expect("failed to create notification_service_push_retries_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_push_retries_total");
+    counter
+});
+
+static PUSH_TOKENS_DEACTIVATED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "notification_service_push_tokens_deactivated_total",
+            "Push tokens deactivated after provider rejection",
+        ),
+        &["provider", "reason"],
+    )
+    .expect("failed to create notification_service_push_tokens_deactivated_total");
+    prometheus::default_registry()
+        .register(Box::new(counter.clone()))
+        .expect("failed to register notification_service_push_tokens_deactivated_total");
+    counter
+});
+
+pub fn record_notification_aggregated(notification_type: &str) {
+    NOTIFICATIONS_AGGREGATED_TOTAL
+        .with_label_values(&[notification_type])
+        .inc();
+}
+
+pub fn record_push_suppressed(reason: &str) {
+    PUSH_SUPPRESSED_TOTAL.with_label_values(&[reason]).inc();
+}
+
+pub fn record_digest_sent(period: &str) {
+    DIGESTS_SENT_TOTAL.with_label_values(&[period]).inc();
+}
+
+pub fn record_notification_deferred(notification_type: &str) {
+    NOTIFICATIONS_DEFERRED_TOTAL
+        .with_label_values(&[notification_type])
+        .inc();
+}
+
+pub fn record_deferred_released(mode: &str) {
+    DEFERRED_RELEASED_TOTAL.with_label_values(&[mode]).inc();
+}
+
+pub fn record_channel_delivery(channel: &str, outcome: &str) {
+    CHANNEL_DELIVERIES_TOTAL
+        .with_label_values(&[channel, outcome])
+        .inc();
+}
+
+pub fn record_channel_suppressed(channel: &str, reason: &str) {
+    CHANNEL_SUPPRESSIONS_TOTAL
+        .with_label_values(&[channel, reason])
+        .inc();
+}
+
+pub fn record_ws_cluster_message(direction: &str) {
+    WS_CLUSTER_MESSAGES_TOTAL
+        .with_label_values(&[direction])
+        .inc();
+}
+
+pub fn record_ws_connection_evicted(reason: &str) {
+    WS_CONNECTIONS_EVICTED_TOTAL
+        .with_label_values(&[reason])
+        .inc();
+}
+
+pub fn record_ws_messages_replayed(outcome: &str, count: usize) {
+    WS_MESSAGES_REPLAYED_TOTAL
+        .with_label_values(&[outcome])
+        .inc_by(count as u64);
+}
+
+pub fn record_push_funnel(stage: &str) {
+    PUSH_FUNNEL_TOTAL.with_label_values(&[stage]).inc();
+}
+
+pub fn record_push_failure(provider: &str, kind: &str) {
+    PUSH_FAILURES_TOTAL
+        .with_label_values(&[provider, kind])
+        .inc();
+}
+
+pub fn record_push_retry(provider: &str, kind: &str) {
+    PUSH_RETRIES_TOTAL
+        .with_label_values(&[provider, kind])
+        .inc();
+}
+
+pub fn record_push_token_deactivated(provider: &str, reason: &str) {
+    PUSH_TOKENS_DEACTIVATED_TOTAL
+        .with_label_values(&[provider, reason])
+        .inc();
+}
+
 pub fn observe_http_request(method: &str, path: &str, status: u16, elapsed: Duration) {
     let status_label = status.to_string();
     HTTP_REQUESTS_TOTAL
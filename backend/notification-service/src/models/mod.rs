@@ -123,6 +123,38 @@ impl NotificationStatus {
     }
 }
 
+/// How often low-priority notifications are rolled into a digest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    /// No digest; every notification is delivered on its own
+    #[default]
+    Off,
+    /// One summary per day
+    Daily,
+    /// One summary per week
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestFrequency::Off => "off",
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Some(DigestFrequency::Off),
+            "daily" => Some(DigestFrequency::Daily),
+            "weekly" => Some(DigestFrequency::Weekly),
+            _ => None,
+        }
+    }
+}
+
 /// Core notification model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notification {
@@ -260,11 +292,19 @@ pub struct NotificationPreference {
     pub quiet_hours_start: Option<String>,
     pub quiet_hours_end: Option<String>,
 
+    /// IANA timezone quiet hours are evaluated in (`None` = UTC)
+    #[serde(default)]
+    pub timezone: Option<String>,
+
     /// Preferred channels
     pub prefer_fcm: bool,
     pub prefer_apns: bool,
     pub prefer_email: bool,
 
+    /// Digest rollup for low-priority notification types
+    #[serde(default)]
+    pub digest_frequency: DigestFrequency,
+
     /// Updated timestamp
     pub updated_at: DateTime<Utc>,
 }
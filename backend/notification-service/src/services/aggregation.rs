@@ -0,0 +1,479 @@
+/// Notification Aggregation
+///
+/// Collapses notifications of the same type and target into a single unread
+/// row ("Alice, Bob and 48 others liked your post") and decides whether the
+/// result should be pushed, suppressed, or left for the next digest.
+///
+/// Features:
+/// - Group keys per (type, target) within a configurable window
+/// - Actor list, distinct actor ids and count kept in `metadata.aggregation`
+/// - Per-user push rate limiting (Redis fixed window, in-memory fallback)
+/// - Per-group push cooldown so a viral post pushes once, not hundreds of times
+use super::priority_queue::RateLimiter;
+use crate::models::{CreateNotificationRequest, NotificationPriority, NotificationType};
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use redis_utils::SharedConnectionManager;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Aggregation and push throttling settings
+#[derive(Debug, Clone)]
+pub struct AggregationConfig {
+    /// Master switch (NOTIFICATION_AGGREGATION_ENABLED)
+    pub enabled: bool,
+    /// Unread notifications younger than this absorb new events
+    pub window: Duration,
+    /// Actors kept on an aggregated notification (names shown + ids)
+    pub max_actors: usize,
+    /// Pushes allowed per user per `push_window`
+    pub push_limit: usize,
+    pub push_window: Duration,
+    /// Minimum gap between pushes for the same aggregated notification
+    pub group_push_cooldown: Duration,
+    /// Types that go to the digest for users who enabled one
+    pub digest_types: Vec<NotificationType>,
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: Duration::from_secs(6 * 3600),
+            max_actors: 10,
+            push_limit: 20,
+            push_window: Duration::from_secs(3600),
+            group_push_cooldown: Duration::from_secs(15 * 60),
+            digest_types: vec![
+                NotificationType::Like,
+                NotificationType::Follow,
+                NotificationType::Share,
+            ],
+        }
+    }
+}
+
+impl AggregationConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let secs = |name: &str, default: Duration| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+
+        Self {
+            enabled: std::env::var("NOTIFICATION_AGGREGATION_ENABLED")
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(defaults.enabled),
+            window: secs("NOTIFICATION_AGGREGATION_WINDOW_SECS", defaults.window),
+            max_actors: std::env::var("NOTIFICATION_AGGREGATION_MAX_ACTORS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_actors),
+            push_limit: std::env::var("NOTIFICATION_PUSH_LIMIT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.push_limit),
+            push_window: secs("NOTIFICATION_PUSH_WINDOW_SECS", defaults.push_window),
+            group_push_cooldown: secs(
+                "NOTIFICATION_GROUP_PUSH_COOLDOWN_SECS",
+                defaults.group_push_cooldown,
+            ),
+            digest_types: std::env::var("NOTIFICATION_DIGEST_TYPES")
+                .ok()
+                .map(|v| parse_types(&v))
+                .unwrap_or(defaults.digest_types),
+        }
+    }
+}
+
+fn parse_types(raw: &str) -> Vec<NotificationType> {
+    raw.split(',')
+        .filter_map(|t| match t.trim().to_lowercase().as_str() {
+            "like" => Some(NotificationType::Like),
+            "comment" => Some(NotificationType::Comment),
+            "follow" => Some(NotificationType::Follow),
+            "share" => Some(NotificationType::Share),
+            "mention" => Some(NotificationType::Mention),
+            "video" => Some(NotificationType::Video),
+            "stream" => Some(NotificationType::Stream),
+            _ => None,
+        })
+        .collect()
+}
+
+/// What to do with a notification after it has been stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Send a push now
+    Push,
+    /// Leave for the user's next digest
+    Digest,
+    /// Stored (or merged) but not pushed
+    Suppressed(&'static str),
+    /// Held back by quiet hours; pushed (collapsed) at the given time
+    Deferred(DateTime<Utc>),
+}
+
+/// One actor on an aggregated notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Actor {
+    pub id: Option<Uuid>,
+    pub name: String,
+}
+
+/// Aggregation state stored under `metadata.aggregation`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregationState {
+    /// Total distinct actors, including those no longer listed
+    pub actor_count: i64,
+    /// Most recent actors first, capped at `max_actors`
+    pub actors: Vec<Actor>,
+    /// Every actor id counted in `actor_count`, listed or not
+    #[serde(default)]
+    pub actor_ids: HashSet<Uuid>,
+}
+
+impl AggregationState {
+    pub fn new(actor: Actor) -> Self {
+        Self {
+            actor_count: 1,
+            actor_ids: actor.id.into_iter().collect(),
+            actors: vec![actor],
+        }
+    }
+
+    pub fn from_metadata(metadata: Option<&serde_json::Value>) -> Option<Self> {
+        metadata
+            .and_then(|m| m.get("aggregation"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+
+    /// Record a new actor; false if they were already counted
+    ///
+    /// Actors without an id cannot be told apart and always count.
+    pub fn add_actor(&mut self, actor: Actor, max_actors: usize) -> bool {
+        if let Some(id) = actor.id {
+            // States written before `actor_ids` existed only have the list
+            let listed = self.actors.iter().any(|a| a.id == Some(id));
+            if !self.actor_ids.insert(id) || listed {
+                return false;
+            }
+        }
+        self.actors.insert(0, actor);
+        self.actors.truncate(max_actors.max(1));
+        self.actor_count += 1;
+        true
+    }
+
+    /// Event metadata with this state attached under `aggregation`
+    pub fn attach_to(&self, metadata: Option<serde_json::Value>) -> serde_json::Value {
+        let mut metadata = match metadata {
+            Some(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert(
+            "aggregation".to_string(),
+            serde_json::to_value(self).unwrap_or_default(),
+        );
+        serde_json::Value::Object(metadata)
+    }
+
+    /// "Alice", "Alice and Bob", "Alice, Bob and 48 others"
+    pub fn actor_summary(&self) -> String {
+        let names: Vec<&str> = self.actors.iter().map(|a| a.name.as_str()).collect();
+        match (names.as_slice(), self.actor_count) {
+            ([], _) => "Someone".to_string(),
+            ([only], 1) => only.to_string(),
+            ([first, second, ..], 2) => format!("{} and {}", first, second),
+            ([first], n) => others(first, n - 1),
+            ([first, second, ..], n) => others(&format!("{}, {}", first, second), n - 2),
+        }
+    }
+}
+
+fn others(names: &str, rest: i64) -> String {
+    if rest == 1 {
+        format!("{} and 1 other", names)
+    } else {
+        format!("{} and {} others", names, rest)
+    }
+}
+
+/// Group key for notifications that collapse together, `None` if the type
+/// is never aggregated (mentions, messages, system, streams)
+pub fn group_key(req: &CreateNotificationRequest) -> Option<String> {
+    match req.notification_type {
+        // The target of a follow is the recipient
+        NotificationType::Follow => Some("follow".to_string()),
+        NotificationType::Like | NotificationType::Comment | NotificationType::Share => {
+            let object_id = req.object_id?;
+            Some(format!(
+                "{}:{}:{}",
+                req.notification_type.as_str(),
+                req.object_type.as_deref().unwrap_or("post"),
+                object_id
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Actor of an event, from `sender_id` and `metadata.sender_username`
+pub fn actor_of(req: &CreateNotificationRequest) -> Actor {
+    let name = req
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("sender_username").or_else(|| m.get("sender_name")))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("Someone");
+    Actor {
+        id: req.sender_id,
+        name: name.to_string(),
+    }
+}
+
+/// Body text for an aggregated notification
+pub fn render_body(
+    notification_type: NotificationType,
+    object_type: Option<&str>,
+    state: &AggregationState,
+) -> String {
+    let target = object_type.unwrap_or("post");
+    let action = match notification_type {
+        NotificationType::Like => format!("liked your {}", target),
+        NotificationType::Comment => format!("commented on your {}", target),
+        NotificationType::Share => format!("shared your {}", target),
+        NotificationType::Follow => "started following you".to_string(),
+        _ => "interacted with you".to_string(),
+    };
+    format!("{} {}", state.actor_summary(), action)
+}
+
+/// Per-user push rate limiter
+///
+/// Uses a Redis fixed window so limits hold across instances; falls back to
+/// the in-process `RateLimiter` when Redis is not configured or errors.
+pub struct PushRateLimiter {
+    redis: Option<SharedConnectionManager>,
+    local: Mutex<RateLimiter>,
+    limit: usize,
+    window: Duration,
+}
+
+impl PushRateLimiter {
+    pub fn new(redis: Option<SharedConnectionManager>, limit: usize, window: Duration) -> Self {
+        Self {
+            redis,
+            local: Mutex::new(RateLimiter::new(limit, window)),
+            limit,
+            window,
+        }
+    }
+
+    /// Count a push for the user; false once the window's budget is spent
+    pub async fn allow(&self, user_id: Uuid) -> bool {
+        if let Some(redis) = &self.redis {
+            let window_secs = self.window.as_secs().max(1);
+            let key = format!(
+                "notif:push_rl:{}:{}",
+                user_id,
+                Utc::now().timestamp() as u64 / window_secs
+            );
+
+            let result: Result<i64, _> = redis_utils::with_timeout(async {
+                let mut conn = redis.lock().await;
+                let count: i64 = conn.incr(&key, 1).await?;
+                if count == 1 {
+                    conn.expire::<_, ()>(&key, window_secs as i64).await?;
+                }
+                Ok(count)
+            })
+            .await;
+
+            match result {
+                Ok(count) => return count <= self.limit as i64,
+                Err(e) => warn!(
+                    "Redis push rate limit check failed for {}: {} - using local limiter",
+                    user_id, e
+                ),
+            }
+        }
+
+        self.local
+            .lock()
+            .map(|mut limiter| limiter.can_notify(user_id))
+            .unwrap_or(true)
+    }
+}
+
+/// Aggregation settings plus the push limiter, attached to `NotificationService`
+pub struct NotificationAggregator {
+    pub config: AggregationConfig,
+    limiter: PushRateLimiter,
+}
+
+impl NotificationAggregator {
+    pub fn new(config: AggregationConfig, redis: Option<SharedConnectionManager>) -> Self {
+        let limiter = PushRateLimiter::new(redis, config.push_limit, config.push_window);
+        Self { config, limiter }
+    }
+
+    /// Whether the type goes to the digest instead of being pushed
+    pub fn is_digest_type(
+        &self,
+        notification_type: NotificationType,
+        priority: NotificationPriority,
+    ) -> bool {
+        priority != NotificationPriority::High
+            && self.config.digest_types.contains(&notification_type)
+    }
+
+    /// High priority notifications are never rate limited
+    pub async fn allow_push(&self, user_id: Uuid, priority: NotificationPriority) -> bool {
+        priority == NotificationPriority::High || self.limiter.allow(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(
+        notification_type: NotificationType,
+        object_id: Option<Uuid>,
+    ) -> CreateNotificationRequest {
+        CreateNotificationRequest {
+            recipient_id: Uuid::new_v4(),
+            sender_id: Some(Uuid::new_v4()),
+            notification_type,
+            title: "New Like".to_string(),
+            body: "alice liked your post".to_string(),
+            image_url: None,
+            object_id,
+            object_type: None,
+            metadata: Some(serde_json::json!({ "sender_username": "alice" })),
+            priority: NotificationPriority::Normal,
+        }
+    }
+
+    fn actor(name: &str) -> Actor {
+        Actor {
+            id: Some(Uuid::new_v4()),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_group_key() {
+        let post_id = Uuid::new_v4();
+        let like = request(NotificationType::Like, Some(post_id));
+        assert_eq!(group_key(&like), Some(format!("like:post:{}", post_id)));
+
+        let mut comment_like = request(NotificationType::Like, Some(post_id));
+        comment_like.object_type = Some("comment".to_string());
+        assert_ne!(group_key(&comment_like), group_key(&like));
+
+        assert_eq!(group_key(&request(NotificationType::Like, None)), None);
+        assert_eq!(
+            group_key(&request(NotificationType::Follow, None)),
+            Some("follow".to_string())
+        );
+        assert_eq!(
+            group_key(&request(NotificationType::Mention, Some(post_id))),
+            None
+        );
+        assert_eq!(
+            group_key(&request(NotificationType::Message, Some(post_id))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_add_actor_dedups_and_caps() {
+        let alice = actor("alice");
+        let mut state = AggregationState::new(alice.clone());
+
+        assert!(!state.add_actor(alice, 3));
+        assert_eq!(state.actor_count, 1);
+
+        for name in ["bob", "carol", "dave"] {
+            assert!(state.add_actor(actor(name), 3));
+        }
+        assert_eq!(state.actor_count, 4);
+        assert_eq!(state.actors.len(), 3);
+        assert_eq!(state.actors[0].name, "dave");
+    }
+
+    #[test]
+    fn test_add_actor_counts_unlisted_repeats_once() {
+        let alice = actor("alice");
+        let mut state = AggregationState::new(alice.clone());
+        for name in ["bob", "carol"] {
+            assert!(state.add_actor(actor(name), 2));
+        }
+        assert!(!state.actors.contains(&alice));
+
+        assert!(!state.add_actor(alice, 2));
+        assert_eq!(state.actor_count, 3);
+    }
+
+    #[test]
+    fn test_render_body() {
+        let mut state = AggregationState::new(actor("alice"));
+        assert_eq!(
+            render_body(NotificationType::Like, None, &state),
+            "alice liked your post"
+        );
+
+        state.add_actor(actor("bob"), 10);
+        assert_eq!(
+            render_body(NotificationType::Like, Some("comment"), &state),
+            "bob and alice liked your comment"
+        );
+
+        state.add_actor(actor("carol"), 10);
+        assert_eq!(
+            render_body(NotificationType::Follow, None, &state),
+            "carol, bob and 1 other started following you"
+        );
+
+        state.actor_count = 50;
+        assert_eq!(
+            render_body(NotificationType::Share, None, &state),
+            "carol, bob and 48 others shared your post"
+        );
+    }
+
+    #[test]
+    fn test_state_round_trips_through_metadata() {
+        let state = AggregationState::new(actor_of(&request(NotificationType::Like, None)));
+        assert_eq!(state.actors[0].name, "alice");
+
+        let metadata = state.attach_to(Some(serde_json::json!({ "like_id": "l1" })));
+        assert_eq!(metadata["like_id"], "l1");
+        assert_eq!(
+            AggregationState::from_metadata(Some(&metadata)),
+            Some(state)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_push_limit() {
+        let limiter = PushRateLimiter::new(None, 2, Duration::from_secs(60));
+        let user = Uuid::new_v4();
+
+        assert!(limiter.allow(user).await);
+        assert!(limiter.allow(user).await);
+        assert!(!limiter.allow(user).await);
+        assert!(limiter.allow(Uuid::new_v4()).await);
+    }
+}
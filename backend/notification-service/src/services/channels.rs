@@ -0,0 +1,448 @@
+/// Email / SMS Delivery Channels
+///
+/// Push (FCM/APNs) stays in `NotificationService`; this module adds pluggable
+/// providers for the `Email` and `SMS` channels plus the rules deciding when
+/// they are used.
+///
+/// Features:
+/// - `ChannelProvider` trait (SMTP email and HTTP SMS gateway implementations)
+/// - Fallback rules: email when no push device was active recently, SMS as a
+///   last resort for high priority notifications
+/// - Signed one-click unsubscribe links for email
+/// - Destination normalization shared with bounce/STOP handling
+use crate::models::{
+    DeviceToken, Notification, NotificationChannel, NotificationPreference, NotificationPriority,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Why a destination stopped receiving a channel
+pub mod suppression {
+    /// Permanent delivery failure reported by the provider
+    pub const BOUNCE: &str = "bounce";
+    /// Recipient marked the message as spam
+    pub const COMPLAINT: &str = "complaint";
+    /// Recipient opted out (email link or SMS STOP)
+    pub const UNSUBSCRIBE: &str = "unsubscribe";
+}
+
+/// Provider failure
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelError {
+    /// The destination can never succeed (invalid address, rejected number);
+    /// the destination is deactivated
+    Permanent(String),
+    /// Worth retrying later (network, 5xx, throttling)
+    Transient(String),
+}
+
+impl std::fmt::Display for ChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelError::Permanent(e) => write!(f, "permanent: {}", e),
+            ChannelError::Transient(e) => write!(f, "transient: {}", e),
+        }
+    }
+}
+
+/// A delivery channel backed by an external provider
+#[async_trait]
+pub trait ChannelProvider: Send + Sync {
+    /// Channel this provider delivers on
+    fn channel(&self) -> NotificationChannel;
+
+    /// Deliver `notification` to `destination` (email address / E.164 number);
+    /// returns the provider message id
+    async fn send(
+        &self,
+        destination: &str,
+        notification: &Notification,
+    ) -> Result<String, ChannelError>;
+}
+
+/// Normalize an email address or phone number for storage and matching
+pub fn normalize_destination(channel: NotificationChannel, destination: &str) -> Option<String> {
+    let destination = destination.trim();
+    match channel {
+        NotificationChannel::Email => {
+            let (local, domain) = destination.rsplit_once('@')?;
+            if local.is_empty()
+                || !domain.contains('.')
+                || destination.contains(char::is_whitespace)
+            {
+                return None;
+            }
+            Some(destination.to_lowercase())
+        }
+        NotificationChannel::SMS => {
+            let digits: String = destination
+                .chars()
+                .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.'))
+                .collect();
+            let number = digits.strip_prefix('+')?;
+            (number.len() >= 8 && number.len() <= 15 && number.chars().all(|c| c.is_ascii_digit()))
+                .then(|| format!("+{}", number))
+        }
+        _ => Some(destination.to_string()),
+    }
+}
+
+/// When email/SMS are used in addition to (or instead of) push
+#[derive(Debug, Clone)]
+pub struct FallbackPolicy {
+    /// Push devices unused for longer than this do not count as reachable
+    pub push_inactive_after: Duration,
+    /// Email users without a recently active push device
+    pub email_fallback: bool,
+    /// Minimum priority for SMS when neither push nor email can reach the user
+    pub sms_min_priority: Option<NotificationPriority>,
+}
+
+impl Default for FallbackPolicy {
+    fn default() -> Self {
+        Self {
+            push_inactive_after: Duration::days(7),
+            email_fallback: true,
+            sms_min_priority: Some(NotificationPriority::High),
+        }
+    }
+}
+
+impl FallbackPolicy {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            push_inactive_after: std::env::var("NOTIFICATION_PUSH_INACTIVE_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::days)
+                .unwrap_or(defaults.push_inactive_after),
+            email_fallback: std::env::var("NOTIFICATION_EMAIL_FALLBACK")
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(defaults.email_fallback),
+            sms_min_priority: match std::env::var("NOTIFICATION_SMS_FALLBACK_PRIORITY") {
+                Ok(v) => match v.to_lowercase().as_str() {
+                    "off" | "none" => None,
+                    "low" => Some(NotificationPriority::Low),
+                    "normal" => Some(NotificationPriority::Normal),
+                    _ => Some(NotificationPriority::High),
+                },
+                Err(_) => defaults.sms_min_priority,
+            },
+        }
+    }
+
+    /// Devices a notification goes to
+    ///
+    /// Push and WebSocket devices always receive it. Email is added when the
+    /// user prefers email, or when no push device was used within
+    /// `push_inactive_after`. SMS is only used when neither push nor email can
+    /// reach the user and the priority is high enough.
+    pub fn plan<'a>(
+        &self,
+        devices: &'a [DeviceToken],
+        preferences: &NotificationPreference,
+        priority: NotificationPriority,
+        now: DateTime<Utc>,
+    ) -> Vec<&'a DeviceToken> {
+        let active = devices.iter().filter(|d| d.is_active);
+        let of = |channel| active.clone().filter(move |d| d.channel == channel);
+
+        let mut planned: Vec<&DeviceToken> = active
+            .clone()
+            .filter(|d| {
+                matches!(
+                    d.channel,
+                    NotificationChannel::FCM
+                        | NotificationChannel::APNs
+                        | NotificationChannel::WebSocket
+                )
+            })
+            .collect();
+
+        let cutoff = now - self.push_inactive_after;
+        let push_reachable = planned.iter().any(|d| {
+            d.channel != NotificationChannel::WebSocket
+                && d.last_used_at.unwrap_or(d.created_at) >= cutoff
+        });
+
+        let emails: Vec<&DeviceToken> = of(NotificationChannel::Email).collect();
+        let use_email = preferences.prefer_email || (self.email_fallback && !push_reachable);
+        if use_email {
+            planned.extend(&emails);
+        }
+
+        let email_reachable = use_email && !emails.is_empty();
+        if !push_reachable
+            && !email_reachable
+            && self.sms_min_priority.is_some_and(|min| priority >= min)
+        {
+            planned.extend(of(NotificationChannel::SMS));
+        }
+
+        planned
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct UnsubscribeClaims {
+    sub: String,
+    ch: String,
+    dest: String,
+    exp: usize,
+}
+
+/// A verified unsubscribe request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unsubscribe {
+    pub user_id: Uuid,
+    pub channel: NotificationChannel,
+    pub destination: String,
+}
+
+/// Signs and verifies one-click unsubscribe links (HS256)
+#[derive(Clone)]
+pub struct UnsubscribeLinks {
+    secret: String,
+    base_url: String,
+}
+
+impl UnsubscribeLinks {
+    pub fn new(secret: String, base_url: String) -> Self {
+        Self { secret, base_url }
+    }
+
+    /// Built from NOTIFICATION_UNSUBSCRIBE_SECRET / NOTIFICATION_UNSUBSCRIBE_BASE_URL
+    pub fn from_env() -> Option<Self> {
+        let secret = std::env::var("NOTIFICATION_UNSUBSCRIBE_SECRET").ok()?;
+        if secret.len() < 16 {
+            return None;
+        }
+        let base_url = std::env::var("NOTIFICATION_UNSUBSCRIBE_BASE_URL")
+            .unwrap_or_else(|_| "http://localhost:8000/api/v1/channels/unsubscribe".to_string());
+        Some(Self::new(secret, base_url))
+    }
+
+    pub fn token(&self, user_id: Uuid, channel: NotificationChannel, destination: &str) -> String {
+        let claims = UnsubscribeClaims {
+            sub: user_id.to_string(),
+            ch: channel.as_str().to_string(),
+            dest: destination.to_string(),
+            exp: (Utc::now() + Duration::days(365)).timestamp() as usize,
+        };
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .unwrap_or_default()
+    }
+
+    pub fn url(&self, user_id: Uuid, channel: NotificationChannel, destination: &str) -> String {
+        format!(
+            "{}?token={}",
+            self.base_url,
+            self.token(user_id, channel, destination)
+        )
+    }
+
+    pub fn verify(&self, token: &str) -> Option<Unsubscribe> {
+        let claims = decode::<UnsubscribeClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )
+        .ok()?
+        .claims;
+
+        let channel = match claims.ch.as_str() {
+            "email" => NotificationChannel::Email,
+            "sms" => NotificationChannel::SMS,
+            _ => return None,
+        };
+        Some(Unsubscribe {
+            user_id: Uuid::parse_str(&claims.sub).ok()?,
+            channel,
+            destination: claims.dest,
+        })
+    }
+}
+
+/// SMS keywords that opt a number out (CTIA / carrier standard)
+pub fn is_sms_opt_out(body: &str) -> bool {
+    matches!(
+        body.trim().to_uppercase().as_str(),
+        "STOP" | "STOPALL" | "UNSUBSCRIBE" | "CANCEL" | "END" | "QUIT"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DigestFrequency;
+
+    fn device(channel: NotificationChannel, days_idle: i64) -> DeviceToken {
+        let now = Utc::now();
+        DeviceToken {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            token: format!("{}-token", channel.as_str()),
+            channel,
+            device_type: "ios".to_string(),
+            device_name: None,
+            is_active: true,
+            last_used_at: Some(now - Duration::days(days_idle)),
+            created_at: now - Duration::days(90),
+        }
+    }
+
+    fn preferences(prefer_email: bool) -> NotificationPreference {
+        NotificationPreference {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            enabled: true,
+            like_enabled: true,
+            comment_enabled: true,
+            follow_enabled: true,
+            mention_enabled: true,
+            message_enabled: true,
+            stream_enabled: true,
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            timezone: None,
+            prefer_fcm: true,
+            prefer_apns: true,
+            prefer_email,
+            digest_frequency: DigestFrequency::Off,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn channels(planned: &[&DeviceToken]) -> Vec<NotificationChannel> {
+        planned.iter().map(|d| d.channel).collect()
+    }
+
+    #[test]
+    fn test_normalize_destination() {
+        use NotificationChannel::{Email, SMS};
+        assert_eq!(
+            normalize_destination(Email, " Alice@Example.COM "),
+            Some("alice@example.com".to_string())
+        );
+        assert_eq!(normalize_destination(Email, "alice@localhost"), None);
+        assert_eq!(normalize_destination(Email, "no-at-sign"), None);
+        assert_eq!(
+            normalize_destination(SMS, "+1 (415) 555-0100"),
+            Some("+14155550100".to_string())
+        );
+        assert_eq!(normalize_destination(SMS, "4155550100"), None);
+        assert_eq!(normalize_destination(SMS, "+1415abc0100"), None);
+    }
+
+    #[test]
+    fn test_plan_push_only_when_recently_active() {
+        let policy = FallbackPolicy::default();
+        let devices = vec![
+            device(NotificationChannel::APNs, 1),
+            device(NotificationChannel::Email, 0),
+            device(NotificationChannel::SMS, 0),
+        ];
+
+        let planned = policy.plan(
+            &devices,
+            &preferences(false),
+            NotificationPriority::High,
+            Utc::now(),
+        );
+        assert_eq!(channels(&planned), vec![NotificationChannel::APNs]);
+
+        let planned = policy.plan(
+            &devices,
+            &preferences(true),
+            NotificationPriority::Normal,
+            Utc::now(),
+        );
+        assert_eq!(
+            channels(&planned),
+            vec![NotificationChannel::APNs, NotificationChannel::Email]
+        );
+    }
+
+    #[test]
+    fn test_plan_falls_back_to_email_then_sms() {
+        let policy = FallbackPolicy::default();
+        let stale_push = device(NotificationChannel::FCM, 10);
+
+        let with_email = vec![
+            stale_push.clone(),
+            device(NotificationChannel::Email, 0),
+            device(NotificationChannel::SMS, 0),
+        ];
+        let planned = policy.plan(
+            &with_email,
+            &preferences(false),
+            NotificationPriority::High,
+            Utc::now(),
+        );
+        assert_eq!(
+            channels(&planned),
+            vec![NotificationChannel::FCM, NotificationChannel::Email]
+        );
+
+        let sms_only = vec![stale_push, device(NotificationChannel::SMS, 0)];
+        let planned = policy.plan(
+            &sms_only,
+            &preferences(false),
+            NotificationPriority::High,
+            Utc::now(),
+        );
+        assert_eq!(
+            channels(&planned),
+            vec![NotificationChannel::FCM, NotificationChannel::SMS]
+        );
+
+        // SMS is reserved for high priority
+        let planned = policy.plan(
+            &sms_only,
+            &preferences(false),
+            NotificationPriority::Normal,
+            Utc::now(),
+        );
+        assert_eq!(channels(&planned), vec![NotificationChannel::FCM]);
+    }
+
+    #[test]
+    fn test_unsubscribe_link_round_trip() {
+        let links = UnsubscribeLinks::new(
+            "0123456789abcdef-secret".to_string(),
+            "https://example.com/unsubscribe".to_string(),
+        );
+        let user_id = Uuid::new_v4();
+        let token = links.token(user_id, NotificationChannel::Email, "a@example.com");
+
+        assert_eq!(
+            links.verify(&token),
+            Some(Unsubscribe {
+                user_id,
+                channel: NotificationChannel::Email,
+                destination: "a@example.com".to_string(),
+            })
+        );
+        assert!(links
+            .url(user_id, NotificationChannel::Email, "a@example.com")
+            .starts_with("https://example.com/unsubscribe?token="));
+
+        let other = UnsubscribeLinks::new("another-secret-value".to_string(), String::new());
+        assert_eq!(other.verify(&token), None);
+    }
+
+    #[test]
+    fn test_sms_opt_out_keywords() {
+        assert!(is_sms_opt_out(" stop "));
+        assert!(is_sms_opt_out("Unsubscribe"));
+        assert!(!is_sms_opt_out("please stop sending"));
+    }
+}
@@ -0,0 +1,462 @@
+/// Notification Digests
+///
+/// Rolls notifications routed to `Delivery::Digest` into one daily or weekly
+/// summary per user ("You got 120 likes, 5 new followers and 3 shares").
+///
+/// Periods follow the user's own timezone. Each (user, period, period_start)
+/// produces at most one digest: the `notification_digests` row is claimed with
+/// `ON CONFLICT DO NOTHING`, so several instances can run the worker side by
+/// side.
+use super::quiet_hours::{local_to_utc, parse_timezone};
+use super::NotificationService;
+use crate::metrics;
+use crate::models::{
+    CreateNotificationRequest, DigestFrequency, NotificationPriority, NotificationType,
+};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveTime, Utc};
+use chrono_tz::Tz;
+use sqlx::{PgPool, Row};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// Digest worker settings
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    /// NOTIFICATION_DIGEST_ENABLED
+    pub enabled: bool,
+    /// How often the worker looks for due digests
+    pub check_interval: Duration,
+    /// Local hour (in each user's timezone) after which the day's (or
+    /// Monday's) digest is sent
+    pub send_hour: u32,
+    /// Users loaded per page
+    pub batch_size: i64,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval: Duration::from_secs(900),
+            send_hour: 8,
+            batch_size: 500,
+        }
+    }
+}
+
+impl DigestConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("NOTIFICATION_DIGEST_ENABLED")
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(defaults.enabled),
+            check_interval: std::env::var("NOTIFICATION_DIGEST_CHECK_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.check_interval),
+            send_hour: std::env::var("NOTIFICATION_DIGEST_SEND_HOUR")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|h| *h < 24)
+                .unwrap_or(defaults.send_hour),
+            batch_size: std::env::var("NOTIFICATION_DIGEST_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.batch_size),
+        }
+    }
+}
+
+/// Start of the digest period that is due at `now`, if any
+///
+/// Periods start at local midnight in `timezone`. Daily digests are due from
+/// `send_hour` local time each day; weekly digests cover the ISO week and are
+/// due from `send_hour` on Monday.
+pub fn due_period_start(
+    frequency: DigestFrequency,
+    now: DateTime<Utc>,
+    send_hour: u32,
+    timezone: Tz,
+) -> Option<DateTime<Utc>> {
+    let local = now.with_timezone(&timezone);
+    let today = local.date_naive();
+    let start_date = match frequency {
+        DigestFrequency::Off => return None,
+        DigestFrequency::Daily => today,
+        DigestFrequency::Weekly => {
+            today - ChronoDuration::days(local.weekday().num_days_from_monday() as i64)
+        }
+    };
+    let start = local_to_utc(timezone, start_date, NaiveTime::MIN)?;
+    let due = local_to_utc(
+        timezone,
+        start_date,
+        NaiveTime::from_hms_opt(send_hour, 0, 0)?,
+    )?;
+    (now >= due).then_some(start)
+}
+
+/// Per-type counts rolled into a digest
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DigestSummary {
+    pub counts: BTreeMap<String, i64>,
+}
+
+impl DigestSummary {
+    pub fn add(&mut self, notification_type: &str, count: i64) {
+        *self
+            .counts
+            .entry(notification_type.to_string())
+            .or_default() += count.max(1);
+    }
+
+    pub fn total(&self) -> i64 {
+        self.counts.values().sum()
+    }
+
+    /// Title and body for the digest notification
+    pub fn render(&self, frequency: DigestFrequency) -> (String, String) {
+        let title = match frequency {
+            DigestFrequency::Weekly => "Your weekly summary",
+            _ => "Your daily summary",
+        };
+
+        let mut parts: Vec<(i64, String)> = self
+            .counts
+            .iter()
+            .map(|(kind, count)| (*count, label(kind, *count)))
+            .collect();
+        parts.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        let parts: Vec<String> = parts.into_iter().map(|(_, part)| part).collect();
+
+        let body = match parts.as_slice() {
+            [] => "You're all caught up".to_string(),
+            [only] => format!("You got {}", only),
+            [init @ .., last] => format!("You got {} and {}", init.join(", "), last),
+        };
+        (title.to_string(), body)
+    }
+}
+
+fn label(notification_type: &str, count: i64) -> String {
+    let (one, many) = match notification_type {
+        "like" => ("like", "likes"),
+        "follow" => ("new follower", "new followers"),
+        "share" => ("share", "shares"),
+        "comment" => ("comment", "comments"),
+        "mention" => ("mention", "mentions"),
+        _ => ("notification", "notifications"),
+    };
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+/// Periodically builds digests for users who opted into them
+pub struct DigestWorker {
+    db: PgPool,
+    service: Arc<NotificationService>,
+    config: DigestConfig,
+}
+
+impl DigestWorker {
+    pub fn new(db: PgPool, service: Arc<NotificationService>, config: DigestConfig) -> Self {
+        Self {
+            db,
+            service,
+            config,
+        }
+    }
+
+    /// Build every digest that is due; returns the number created
+    ///
+    /// Walks every opted-in user with pending notifications, `batch_size` at a
+    /// time, since whether a digest is due depends on each user's timezone.
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<usize, String> {
+        let mut created = 0;
+        for frequency in [DigestFrequency::Daily, DigestFrequency::Weekly] {
+            let mut cursor = Uuid::nil();
+            loop {
+                let rows = sqlx::query(
+                    r#"
+                    SELECT p.user_id, p.timezone,
+                           (SELECT MAX(d.period_start) FROM notification_digests d
+                            WHERE d.user_id = p.user_id AND d.period = $1) AS last_period_start
+                    FROM notification_preferences p
+                    WHERE p.digest_frequency = $1 AND p.user_id > $2
+                      AND EXISTS (
+                          SELECT 1 FROM notifications n
+                          WHERE n.recipient_id = p.user_id AND n.digest_pending = TRUE
+                            AND n.digest_id IS NULL AND n.is_read = FALSE AND n.deleted_at IS NULL
+                      )
+                    ORDER BY p.user_id
+                    LIMIT $3
+                    "#,
+                )
+                .bind(frequency.as_str())
+                .bind(cursor)
+                .bind(self.config.batch_size)
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| format!("Failed to find digest recipients: {}", e))?;
+
+                let Some(last) = rows.last().map(|row| row.get::<Uuid, _>("user_id")) else {
+                    break;
+                };
+
+                for row in &rows {
+                    let user_id: Uuid = row.get("user_id");
+                    let timezone = row
+                        .get::<Option<String>, _>("timezone")
+                        .as_deref()
+                        .and_then(parse_timezone)
+                        .unwrap_or(Tz::UTC);
+                    let Some(period_start) =
+                        due_period_start(frequency, now, self.config.send_hour, timezone)
+                    else {
+                        continue;
+                    };
+                    let last_period_start: Option<DateTime<Utc>> = row.get("last_period_start");
+                    if last_period_start.is_some_and(|last| last >= period_start) {
+                        continue;
+                    }
+
+                    match self.build_digest(user_id, frequency, period_start).await {
+                        Ok(true) => created += 1,
+                        Ok(false) => {}
+                        Err(e) => warn!(
+                            "Failed to build {} digest for {}: {}",
+                            frequency.as_str(),
+                            user_id,
+                            e
+                        ),
+                    }
+                }
+
+                cursor = last;
+                if (rows.len() as i64) < self.config.batch_size {
+                    break;
+                }
+            }
+        }
+        Ok(created)
+    }
+
+    /// Claim the period, roll pending notifications into it and notify the user
+    async fn build_digest(
+        &self,
+        user_id: Uuid,
+        frequency: DigestFrequency,
+        period_start: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start digest transaction: {}", e))?;
+
+        let digest_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO notification_digests (user_id, period, period_start)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, period, period_start) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(frequency.as_str())
+        .bind(period_start)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to claim digest: {}", e))?;
+
+        let Some(digest_id) = digest_id else {
+            return Ok(false);
+        };
+
+        // Aggregated rows count every actor, not just the row
+        let rows = sqlx::query(
+            r#"
+            UPDATE notifications
+            SET digest_id = $2, digest_pending = FALSE
+            WHERE recipient_id = $1 AND digest_pending = TRUE AND digest_id IS NULL
+              AND is_read = FALSE AND deleted_at IS NULL
+            RETURNING notification_type,
+                      COALESCE((metadata->'aggregation'->>'actor_count')::BIGINT, 1) AS actor_count
+            "#,
+        )
+        .bind(user_id)
+        .bind(digest_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to collect digest notifications: {}", e))?;
+
+        let mut summary = DigestSummary::default();
+        for row in &rows {
+            summary.add(
+                &row.get::<String, _>("notification_type").to_lowercase(),
+                row.get("actor_count"),
+            );
+        }
+        if summary.total() == 0 {
+            // Nothing left (read in the meantime); release the claim
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "UPDATE notification_digests SET notification_count = $2, summary = $3 WHERE id = $1",
+        )
+        .bind(digest_id)
+        .bind(rows.len() as i32)
+        .bind(serde_json::to_value(&summary.counts).unwrap_or_default())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to store digest summary: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit digest: {}", e))?;
+
+        let (title, body) = summary.render(frequency);
+        let notification = self
+            .service
+            .create_notification(CreateNotificationRequest {
+                recipient_id: user_id,
+                sender_id: None,
+                notification_type: NotificationType::System,
+                title,
+                body,
+                image_url: None,
+                object_id: Some(digest_id),
+                object_type: Some("digest".to_string()),
+                metadata: Some(serde_json::json!({
+                    "digest": {
+                        "id": digest_id.to_string(),
+                        "period": frequency.as_str(),
+                        "period_start": period_start.to_rfc3339(),
+                        "counts": summary.counts,
+                    }
+                })),
+                priority: NotificationPriority::Low,
+            })
+            .await?;
+
+        sqlx::query("UPDATE notification_digests SET notification_id = $2 WHERE id = $1")
+            .bind(digest_id)
+            .bind(notification.id)
+            .execute(&self.db)
+            .await
+            .map_err(|e| format!("Failed to link digest notification: {}", e))?;
+
+        // Digests are Low priority, so quiet hours defer them like any other push
+        if let Err(e) = self.service.push_or_defer(&notification).await {
+            warn!("Failed to push digest {} to {}: {}", digest_id, user_id, e);
+        }
+
+        metrics::record_digest_sent(frequency.as_str());
+        debug!(
+            "Created {} digest {} for {} ({} notifications)",
+            frequency.as_str(),
+            digest_id,
+            user_id,
+            rows.len()
+        );
+        Ok(true)
+    }
+}
+
+/// Run the digest worker until the process exits
+pub fn start_digest_worker(worker: DigestWorker) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            "Digest worker started (interval: {:?}, send hour: {:02}:00 local time)",
+            worker.config.check_interval, worker.config.send_hour
+        );
+        let mut ticker = tokio::time::interval(worker.config.check_interval);
+        loop {
+            ticker.tick().await;
+            match worker.run_once(Utc::now()).await {
+                Ok(0) => {}
+                Ok(count) => info!("Created {} notification digests", count),
+                Err(e) => error!("Digest pass failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_due_period_start() {
+        // 2026-10-14 is a Wednesday
+        let wednesday_morning = at(2026, 10, 14, 7);
+        let wednesday_noon = at(2026, 10, 14, 12);
+
+        assert_eq!(
+            due_period_start(DigestFrequency::Daily, wednesday_morning, 8, Tz::UTC),
+            None
+        );
+        assert_eq!(
+            due_period_start(DigestFrequency::Daily, wednesday_noon, 8, Tz::UTC),
+            Some(at(2026, 10, 14, 0))
+        );
+        assert_eq!(
+            due_period_start(DigestFrequency::Weekly, wednesday_noon, 8, Tz::UTC),
+            Some(at(2026, 10, 12, 0))
+        );
+        assert_eq!(
+            due_period_start(DigestFrequency::Weekly, at(2026, 10, 12, 7), 8, Tz::UTC),
+            None
+        );
+        assert_eq!(
+            due_period_start(DigestFrequency::Off, wednesday_noon, 8, Tz::UTC),
+            None
+        );
+
+        // Periods follow the user's timezone, not UTC
+        let los_angeles: Tz = "America/Los_Angeles".parse().unwrap();
+        assert_eq!(
+            due_period_start(DigestFrequency::Daily, wednesday_noon, 8, los_angeles),
+            None
+        );
+        assert_eq!(
+            due_period_start(DigestFrequency::Daily, at(2026, 10, 14, 16), 8, los_angeles),
+            Some(at(2026, 10, 14, 7))
+        );
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        assert_eq!(
+            due_period_start(DigestFrequency::Daily, at(2026, 10, 14, 0), 8, tokyo),
+            Some(at(2026, 10, 13, 15))
+        );
+    }
+
+    #[test]
+    fn test_digest_summary_render() {
+        let mut summary = DigestSummary::default();
+        assert_eq!(
+            summary.render(DigestFrequency::Daily).1,
+            "You're all caught up"
+        );
+
+        summary.add("like", 100);
+        summary.add("like", 20);
+        summary.add("follow", 5);
+        summary.add("share", 1);
+        assert_eq!(summary.total(), 126);
+
+        let (title, body) = summary.render(DigestFrequency::Weekly);
+        assert_eq!(title, "Your weekly summary");
+        assert_eq!(body, "You got 120 likes, 5 new followers and 1 share");
+    }
+}
@@ -0,0 +1,165 @@
+/// SMTP Email Provider
+///
+/// Delivers notifications on the `Email` channel through an SMTP relay.
+/// Messages are multipart (plain text + HTML) rendered from `templates`, and
+/// carry `List-Unsubscribe` headers when unsubscribe links are configured.
+use super::channels::{ChannelError, ChannelProvider, UnsubscribeLinks};
+use super::templates;
+use crate::models::{Notification, NotificationChannel};
+use async_trait::async_trait;
+use lettre::message::header::{HeaderName, HeaderValue};
+use lettre::message::{Mailbox, Message, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use tracing::{debug, warn};
+
+/// SMTP settings (same variables as identity-service)
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub use_starttls: bool,
+}
+
+impl SmtpConfig {
+    /// `None` when SMTP_HOST is unset (email channel disabled)
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("SMTP_HOST").ok()?;
+        if host.trim().is_empty() {
+            return None;
+        }
+        Some(Self {
+            host,
+            port: std::env::var("SMTP_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(587),
+            username: std::env::var("SMTP_USERNAME").ok(),
+            password: std::env::var("SMTP_PASSWORD").ok(),
+            from: std::env::var("SMTP_FROM").unwrap_or_else(|_| "noreply@nova.dev".to_string()),
+            use_starttls: std::env::var("SMTP_USE_STARTTLS")
+                .map(|v| v.to_lowercase() != "false" && v != "0")
+                .unwrap_or(true),
+        })
+    }
+}
+
+/// Email channel provider over SMTP
+pub struct EmailClient {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    unsubscribe: Option<UnsubscribeLinks>,
+}
+
+impl EmailClient {
+    pub fn new(config: &SmtpConfig, unsubscribe: Option<UnsubscribeLinks>) -> Result<Self, String> {
+        let from = config
+            .from
+            .parse::<Mailbox>()
+            .map_err(|e| format!("Invalid SMTP_FROM address: {}", e))?;
+
+        let builder = if config.use_starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+        }
+        .map_err(|e| format!("Failed to configure SMTP transport: {}", e))?
+        .port(config.port);
+
+        let builder = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+
+        if unsubscribe.is_none() {
+            warn!(
+                "NOTIFICATION_UNSUBSCRIBE_SECRET not set; emails go out without unsubscribe links"
+            );
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            unsubscribe,
+        })
+    }
+
+    fn build_message(
+        &self,
+        destination: &str,
+        notification: &Notification,
+    ) -> Result<Message, ChannelError> {
+        let to = destination
+            .parse::<Mailbox>()
+            .map_err(|e| ChannelError::Permanent(format!("Invalid email address: {}", e)))?;
+
+        let unsubscribe_url = self.unsubscribe.as_ref().map(|links| {
+            links.url(
+                notification.recipient_id,
+                NotificationChannel::Email,
+                destination,
+            )
+        });
+        let content = templates::render_email(notification, unsubscribe_url.as_deref());
+
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(content.subject)
+            .multipart(MultiPart::alternative_plain_html(
+                content.text,
+                content.html,
+            ))
+            .map_err(|e| ChannelError::Permanent(format!("Failed to build email: {}", e)))?;
+
+        // RFC 2369 / RFC 8058 one-click unsubscribe
+        if let Some(url) = unsubscribe_url {
+            let headers = message.headers_mut();
+            headers.insert_raw(HeaderValue::new(
+                HeaderName::new_from_ascii_str("List-Unsubscribe"),
+                format!("<{}>", url),
+            ));
+            headers.insert_raw(HeaderValue::new(
+                HeaderName::new_from_ascii_str("List-Unsubscribe-Post"),
+                "List-Unsubscribe=One-Click".to_string(),
+            ));
+        }
+
+        Ok(message)
+    }
+}
+
+#[async_trait]
+impl ChannelProvider for EmailClient {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Email
+    }
+
+    async fn send(
+        &self,
+        destination: &str,
+        notification: &Notification,
+    ) -> Result<String, ChannelError> {
+        let message = self.build_message(destination, notification)?;
+
+        match self.transport.send(message).await {
+            Ok(response) => {
+                let message_id = response
+                    .message()
+                    .next()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| notification.id.to_string());
+                debug!("Email delivered for notification {}", notification.id);
+                Ok(message_id)
+            }
+            // 5xx replies (unknown mailbox, rejected recipient) will not succeed on retry
+            Err(e) if e.is_permanent() => Err(ChannelError::Permanent(e.to_string())),
+            Err(e) => Err(ChannelError::Transient(e.to_string())),
+        }
+    }
+}
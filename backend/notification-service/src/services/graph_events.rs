@@ -0,0 +1,153 @@
+//! Graph events consumer
+//!
+//! Consumes `graph.account.privacy_changed` from graph-service. When an
+//! account goes public its pending follow requests are auto-approved, so the
+//! account's "New Follow Request" notifications are resolved (marked read).
+
+use super::NotificationService;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+const PRIVACY_CHANGED_EVENT: &str = "graph.account.privacy_changed";
+
+/// Envelope published by graph-service (event-schema `EventEnvelope`)
+#[derive(Debug, Deserialize)]
+struct EventEnvelope<T> {
+    #[serde(default)]
+    event_type: Option<String>,
+    data: T,
+}
+
+/// Payload of `graph.account.privacy_changed`
+#[derive(Debug, Deserialize)]
+struct PrivacyChangedEvent {
+    user_id: Uuid,
+    is_private: bool,
+}
+
+/// Kafka consumer for graph-service events
+pub struct GraphEventsConsumer {
+    pub broker: String,
+    pub topic: String,
+    pub group_id: String,
+}
+
+impl GraphEventsConsumer {
+    pub fn new(broker: String, topic: String) -> Self {
+        Self {
+            broker,
+            topic,
+            group_id: "notifications-graph-events".to_string(),
+        }
+    }
+
+    /// Consume graph events until the stream fails
+    pub async fn start(
+        &self,
+        notification_service: Arc<NotificationService>,
+    ) -> Result<(), String> {
+        use rdkafka::config::ClientConfig;
+        use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+        use rdkafka::message::Message;
+
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.broker)
+            .set("group.id", &self.group_id)
+            .set("auto.offset.reset", "latest")
+            .set("enable.auto.commit", "false")
+            .set("session.timeout.ms", "30000")
+            .set("heartbeat.interval.ms", "10000")
+            .create()
+            .map_err(|e| format!("Failed to create Kafka consumer: {}", e))?;
+
+        consumer
+            .subscribe(&[&self.topic])
+            .map_err(|e| format!("Failed to subscribe to {}: {}", self.topic, e))?;
+
+        tracing::info!("Graph events consumer subscribed to {}", self.topic);
+
+        loop {
+            match consumer.recv().await {
+                Ok(m) => {
+                    if let Some(payload) = m.payload() {
+                        match parse_privacy_changed(payload) {
+                            Ok(Some(event)) => {
+                                Self::handle_privacy_changed(&notification_service, &event).await
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                tracing::warn!("Failed to parse graph event: {}", e);
+                            }
+                        }
+                    }
+                    if let Err(e) = consumer.commit_message(&m, CommitMode::Async) {
+                        tracing::warn!("Failed to commit Kafka offset: {}", e);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Kafka consumer error: {}", e);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    async fn handle_privacy_changed(
+        notification_service: &NotificationService,
+        event: &PrivacyChangedEvent,
+    ) {
+        if event.is_private {
+            return;
+        }
+
+        match notification_service
+            .resolve_follow_request_notifications(event.user_id)
+            .await
+        {
+            Ok(resolved) => tracing::debug!(
+                user_id = %event.user_id,
+                resolved,
+                "Resolved follow request notifications for public account"
+            ),
+            Err(e) => tracing::warn!(user_id = %event.user_id, "{}", e),
+        }
+    }
+}
+
+/// Parse a privacy change; `None` for other graph events
+fn parse_privacy_changed(data: &[u8]) -> Result<Option<PrivacyChangedEvent>, String> {
+    let envelope: EventEnvelope<serde_json::Value> =
+        serde_json::from_slice(data).map_err(|e| e.to_string())?;
+    if envelope.event_type.as_deref() != Some(PRIVACY_CHANGED_EVENT) {
+        return Ok(None);
+    }
+
+    serde_json::from_value(envelope.data)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_privacy_changed() {
+        let json = r#"{
+            "event_type": "graph.account.privacy_changed",
+            "source": "graph-service",
+            "data": {
+                "user_id": "123e4567-e89b-12d3-a456-426614174000",
+                "is_private": false,
+                "changed_at": "2026-10-17T00:00:00Z"
+            }
+        }"#;
+        let event = parse_privacy_changed(json.as_bytes()).unwrap().unwrap();
+        assert!(!event.is_private);
+
+        let other = r#"{"event_type": "graph.follow_request.denied", "data": {}}"#;
+        assert!(parse_privacy_changed(other.as_bytes()).unwrap().is_none());
+    }
+}
@@ -40,6 +40,8 @@ pub enum NotificationEventType {
     MentionPost,
     MentionComment,
     Share,
+    /// Emoji reaction on a post or comment (`data.reaction` holds the type)
+    Reaction,
 }
 
 impl std::fmt::Display for NotificationEventType {
@@ -54,6 +56,7 @@ impl std::fmt::Display for NotificationEventType {
             NotificationEventType::MentionPost => write!(f, "mention_post"),
             NotificationEventType::MentionComment => write!(f, "mention_comment"),
             NotificationEventType::Share => write!(f, "share"),
+            NotificationEventType::Reaction => write!(f, "reaction"),
         }
     }
 }
@@ -240,7 +243,7 @@ pub struct KafkaNotificationConsumer {
 }
 
 use crate::models::{CreateNotificationRequest, NotificationPriority, NotificationType};
-use crate::services::NotificationService;
+use crate::services::{Delivery, NotificationService};
 use rdkafka::consumer::CommitMode;
 use std::sync::Arc;
 
@@ -284,7 +287,7 @@ impl KafkaNotificationConsumer {
         use tokio::time::interval;
 
         tracing::info!(
-            "Starting Kafka consumer for broker: {}, topics: MessageCreated, FollowAdded, CommentCreated, PostLiked, ReplyLiked, PostShared, MentionCreated",
+            "Starting Kafka consumer for broker: {}, topics: MessageCreated, FollowAdded, CommentCreated, PostLiked, ReplyLiked, PostShared, MentionCreated, ReactionAdded",
             self.broker
         );
 
@@ -309,6 +312,7 @@ impl KafkaNotificationConsumer {
             "ReplyLiked",
             "PostShared",
             "MentionCreated",
+            "ReactionAdded",
         ];
 
         consumer
@@ -428,10 +432,28 @@ impl KafkaNotificationConsumer {
         for kafka_notification in &batch.notifications {
             match self.process_message(kafka_notification.clone()).await {
                 Ok(create_req) => {
-                    match notification_service.create_notification(create_req).await {
-                        Ok(_) => {
+                    match notification_service.ingest_notification(create_req).await {
+                        Ok(ingested) => {
                             processed_count += 1;
-                            tracing::debug!("Processed notification: {}", kafka_notification.id);
+                            tracing::debug!(
+                                "Processed notification: {} (aggregated: {}, delivery: {:?})",
+                                kafka_notification.id,
+                                ingested.aggregated,
+                                ingested.delivery
+                            );
+
+                            if ingested.delivery == Delivery::Push {
+                                if let Err(e) = notification_service
+                                    .send_push_notifications(&ingested.notification)
+                                    .await
+                                {
+                                    tracing::warn!(
+                                        "Failed to push notification {}: {}",
+                                        ingested.notification.id,
+                                        e
+                                    );
+                                }
+                            }
                         }
                         Err(e) => {
                             tracing::warn!(
@@ -486,6 +508,7 @@ impl KafkaNotificationConsumer {
             NotificationEventType::MentionPost => NotificationType::Mention,
             NotificationEventType::MentionComment => NotificationType::Mention,
             NotificationEventType::Share => NotificationType::Share,
+            NotificationEventType::Reaction => NotificationType::Like, // Reactions map to Like type
         };
 
         // Extract sender_id from metadata if available
@@ -608,6 +631,7 @@ mod tests {
             NotificationEventType::MentionComment.to_string(),
             "mention_comment"
         );
+        assert_eq!(NotificationEventType::Reaction.to_string(), "reaction");
     }
 
     #[tokio::test]
@@ -1,16 +1,38 @@
+pub mod aggregation;
 pub mod apns_client;
+pub mod channels;
+pub mod digest;
+pub mod email_client;
 pub mod fcm_client;
+pub mod graph_events;
 pub mod kafka_consumer;
 pub mod notification_service;
 pub mod priority_queue;
+pub mod push_feedback;
 pub mod push_sender;
+pub mod quiet_hours;
+pub mod sms_client;
+pub mod templates;
 
+pub use aggregation::{AggregationConfig, Delivery, NotificationAggregator};
 pub use apns_client::*;
+pub use channels::{ChannelError, ChannelProvider, FallbackPolicy, UnsubscribeLinks};
+pub use digest::{start_digest_worker, DigestConfig, DigestWorker};
+pub use email_client::{EmailClient, SmtpConfig};
 pub use fcm_client::*;
+pub use graph_events::GraphEventsConsumer;
 pub use kafka_consumer::*;
 pub use notification_service::*;
 pub use priority_queue::{
     AdaptiveFlushStrategy, NotificationPriorityQueue, PriorityNotification, QueueMetrics,
     RateLimiter,
 };
+pub use push_feedback::{
+    classify_push_error, PushFailureKind, PushFeedback, ReceiptEvent, RetryPolicy, TokenHealth,
+    TokenStore,
+};
 pub use push_sender::*;
+pub use quiet_hours::{
+    start_deferred_delivery_worker, DeferredDeliveryConfig, DeferredDeliveryWorker, QuietHours,
+};
+pub use sms_client::{SmsClient, SmsGatewayConfig};
@@ -7,13 +7,30 @@
 /// 4. Supports notification preferences and filtering
 /// 5. Manages device tokens and delivery tracking
 /// 6. Implements priority queuing and batch processing
+/// 7. Aggregates same-target notifications and throttles push (see `aggregation`)
+/// 8. Defers pushes during the recipient's quiet hours (see `quiet_hours`)
+/// 9. Delivers on email/SMS with fallback rules (see `channels`)
+/// 10. Pushes to open WebSocket sessions on any node (see `websocket::cluster`)
+/// 11. Retries throttled pushes and prunes dead tokens (see `push_feedback`)
+use super::aggregation::{self, AggregationState, Delivery, NotificationAggregator};
+use super::channels::{
+    normalize_destination, suppression, ChannelError, ChannelProvider, FallbackPolicy,
+};
+use super::push_feedback::{
+    classify_push_error, funnel, PushFeedback, ReceiptEvent, RetryPolicy, TokenHealth, TokenStore,
+};
+use super::quiet_hours::QuietHours;
 use super::{APNsClient, FCMClient};
+use crate::metrics;
 use crate::models::{
-    CreateNotificationRequest, DeviceToken, Notification, NotificationChannel,
-    NotificationPreference, NotificationPriority, NotificationStatus, NotificationType,
+    CreateNotificationRequest, DeliveryAttempt, DeviceToken, DigestFrequency, Notification,
+    NotificationChannel, NotificationPreference, NotificationPriority, NotificationStatus,
+    NotificationType,
 };
-use chrono::{Duration, Utc};
+use crate::websocket::{ConnectionManager, WebSocketMessage};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
 use sqlx::{PgPool, Row};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
@@ -28,11 +45,42 @@ pub struct PushNotificationResult {
     pub error: Option<String>,
 }
 
+/// A stored notification and how it should be delivered
+#[derive(Debug, Clone)]
+pub struct IngestedNotification {
+    pub notification: Notification,
+    /// True if the event was merged into an existing unread notification
+    pub aggregated: bool,
+    pub delivery: Delivery,
+}
+
+const NOTIFICATION_COLUMNS: &str = r#"
+    id, recipient_id, sender_id, notification_type, title, body,
+    image_url, object_id, object_type, metadata, priority, status,
+    is_read, read_at, created_at, updated_at, expires_at
+"#;
+
+/// Outcome of folding an event into an aggregation group
+enum MergeResult {
+    /// Merged; carries the updated notification and when its group last pushed
+    Merged(Notification, Option<chrono::DateTime<Utc>>),
+    /// The actor is already on the group's notification
+    DuplicateActor(Notification),
+    /// No open group; the caller creates a new notification
+    NoGroup,
+}
+
 /// Main Notification Service
 pub struct NotificationService {
     db: PgPool,
     fcm_client: Option<Arc<FCMClient>>,
     apns_client: Option<Arc<APNsClient>>,
+    aggregator: Option<Arc<NotificationAggregator>>,
+    channel_providers: Vec<Arc<dyn ChannelProvider>>,
+    fallback: FallbackPolicy,
+    realtime: Option<ConnectionManager>,
+    feedback: PushFeedback,
+    retry: RetryPolicy,
 }
 
 impl NotificationService {
@@ -43,17 +91,67 @@ impl NotificationService {
         apns_client: Option<Arc<APNsClient>>,
     ) -> Self {
         Self {
+            feedback: PushFeedback::new(db.clone(), TokenStore::DeviceTokens),
+            retry: RetryPolicy::default(),
             db,
             fcm_client,
             apns_client,
+            aggregator: None,
+            channel_providers: Vec::new(),
+            fallback: FallbackPolicy::default(),
+            realtime: None,
         }
     }
 
+    /// Enable aggregation, push rate limiting and digest routing
+    pub fn with_aggregator(mut self, aggregator: NotificationAggregator) -> Self {
+        self.aggregator = Some(Arc::new(aggregator));
+        self
+    }
+
+    /// Register an email/SMS provider (one per channel; later ones win)
+    pub fn with_channel_provider(mut self, provider: Arc<dyn ChannelProvider>) -> Self {
+        self.channel_providers
+            .retain(|p| p.channel() != provider.channel());
+        self.channel_providers.push(provider);
+        self
+    }
+
+    /// Rules for when email/SMS supplement push
+    pub fn with_fallback_policy(mut self, fallback: FallbackPolicy) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Backoff for throttled/transient FCM and APNs errors
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Deliver to open WebSocket sessions alongside device push
+    pub fn with_connection_manager(mut self, manager: ConnectionManager) -> Self {
+        self.realtime = Some(manager);
+        self
+    }
+
     /// Create and store a new notification
     pub async fn create_notification(
         &self,
         req: CreateNotificationRequest,
     ) -> Result<Notification, String> {
+        self.insert_notification(req, None)
+            .await?
+            .ok_or_else(|| "Failed to create notification: no row inserted".to_string())
+    }
+
+    /// Insert a notification; with a group key it opens that group, and `None`
+    /// means another event opened the group first
+    async fn insert_notification(
+        &self,
+        req: CreateNotificationRequest,
+        group_key: Option<&str>,
+    ) -> Result<Option<Notification>, String> {
         let notification_id = Uuid::new_v4();
         let now = Utc::now();
         let expires_at = now + Duration::days(30); // 30-day expiration
@@ -62,10 +160,12 @@ impl NotificationService {
             INSERT INTO notifications (
                 id, recipient_id, sender_id, notification_type, title, body,
                 image_url, object_id, object_type, metadata, priority, status,
-                is_read, created_at, updated_at, expires_at
+                is_read, created_at, updated_at, expires_at, group_key, group_open
             ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, false, $13, $13, $14
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, false, $13, $13, $14, $15,
+                $15 IS NOT NULL
             )
+            ON CONFLICT (recipient_id, group_key) WHERE group_open DO NOTHING
             RETURNING id, recipient_id, sender_id, notification_type, title, body,
                       image_url, object_id, object_type, metadata, priority, status,
                       is_read, created_at, updated_at, expires_at
@@ -86,12 +186,16 @@ impl NotificationService {
             .bind("queued")
             .bind(now)
             .bind(expires_at)
-            .fetch_one(&self.db)
+            .bind(group_key)
+            .fetch_optional(&self.db)
             .await
             .map_err(|e| {
                 error!("Failed to create notification: {}", e);
                 format!("Failed to create notification: {}", e)
             })?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
 
         let notification = Notification {
             id: row.get("id"),
@@ -117,7 +221,296 @@ impl NotificationService {
             "Created notification: {} for user: {}",
             notification_id, req.recipient_id
         );
-        Ok(notification)
+        Ok(Some(notification))
+    }
+
+    /// Store a notification, merging it into a matching unread one when
+    /// aggregation is enabled, and decide how it should be delivered
+    ///
+    /// Without an aggregator this is `create_notification` + `Delivery::Push`.
+    pub async fn ingest_notification(
+        &self,
+        req: CreateNotificationRequest,
+    ) -> Result<IngestedNotification, String> {
+        let aggregator = match &self.aggregator {
+            Some(aggregator) if aggregator.config.enabled => aggregator.clone(),
+            _ => {
+                let notification = self.create_notification(req).await?;
+                let preferences = self.get_preferences(notification.recipient_id).await?;
+                let delivery = match self
+                    .defer_for_quiet_hours(&notification, &preferences)
+                    .await?
+                {
+                    Some(release_at) => Delivery::Deferred(release_at),
+                    None => Delivery::Push,
+                };
+                return Ok(IngestedNotification {
+                    notification,
+                    aggregated: false,
+                    delivery,
+                });
+            }
+        };
+
+        let notification_type = req.notification_type;
+        let priority = req.priority;
+        let recipient_id = req.recipient_id;
+
+        let (notification, aggregated, last_pushed_at) = match aggregation::group_key(&req) {
+            Some(key) => {
+                // A concurrent first event may open the group between the merge
+                // and the insert; the insert then conflicts and we merge instead
+                let mut merged = None;
+                for _ in 0..3 {
+                    match self.merge_into_group(&aggregator, &key, &req).await? {
+                        MergeResult::Merged(notification, last_pushed_at) => {
+                            metrics::record_notification_aggregated(notification_type.as_str());
+                            merged = Some((notification, true, last_pushed_at));
+                        }
+                        MergeResult::DuplicateActor(notification) => {
+                            return Ok(IngestedNotification {
+                                notification,
+                                aggregated: true,
+                                delivery: Delivery::Suppressed("duplicate_actor"),
+                            });
+                        }
+                        MergeResult::NoGroup => {
+                            let mut group_req = req.clone();
+                            let state = AggregationState::new(aggregation::actor_of(&group_req));
+                            group_req.metadata = Some(state.attach_to(group_req.metadata.take()));
+                            merged = self
+                                .insert_notification(group_req, Some(&key))
+                                .await?
+                                .map(|notification| (notification, false, None));
+                        }
+                    }
+                    if merged.is_some() {
+                        break;
+                    }
+                }
+                merged.ok_or_else(|| format!("Aggregation group {} kept changing", key))?
+            }
+            None => (self.create_notification(req).await?, false, None),
+        };
+
+        let preferences = self.get_preferences(recipient_id).await?;
+        let delivery = if preferences.digest_frequency != DigestFrequency::Off
+            && aggregator.is_digest_type(notification_type, priority)
+        {
+            self.mark_digest_pending(notification.id).await?;
+            Delivery::Digest
+        } else if last_pushed_at.is_some_and(|at| {
+            Utc::now()
+                .signed_duration_since(at)
+                .to_std()
+                .unwrap_or_default()
+                < aggregator.config.group_push_cooldown
+        }) {
+            Delivery::Suppressed("group_cooldown")
+        } else if let Some(release_at) = self
+            .defer_for_quiet_hours(&notification, &preferences)
+            .await?
+        {
+            Delivery::Deferred(release_at)
+        } else if !aggregator.allow_push(recipient_id, priority).await {
+            Delivery::Suppressed("rate_limited")
+        } else {
+            self.mark_pushed(&[notification.id]).await?;
+            Delivery::Push
+        };
+
+        if let Delivery::Suppressed(reason) = delivery {
+            metrics::record_push_suppressed(reason);
+        }
+
+        Ok(IngestedNotification {
+            notification,
+            aggregated,
+            delivery,
+        })
+    }
+
+    /// Fold an event into the newest unread notification with the same group key
+    async fn merge_into_group(
+        &self,
+        aggregator: &NotificationAggregator,
+        group_key: &str,
+        req: &CreateNotificationRequest,
+    ) -> Result<MergeResult, String> {
+        let window_start = Utc::now()
+            - Duration::from_std(aggregator.config.window).unwrap_or_else(|_| Duration::hours(6));
+
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start aggregation transaction: {}", e))?;
+
+        let existing = sqlx::query(&format!(
+            r#"
+            SELECT {NOTIFICATION_COLUMNS}, last_pushed_at, deleted_at, digest_id
+            FROM notifications
+            WHERE recipient_id = $1 AND group_key = $2 AND group_open
+            FOR UPDATE
+            "#
+        ))
+        .bind(req.recipient_id)
+        .bind(group_key)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to load aggregation group: {}", e))?;
+
+        let Some(row) = existing else {
+            return Ok(MergeResult::NoGroup);
+        };
+        let current = Self::notification_from_row(&row);
+
+        // Read, deleted, expired or already digested groups take no more
+        // events: close them so the caller opens a fresh one
+        let deleted_at: Option<chrono::DateTime<Utc>> = row.get("deleted_at");
+        let digest_id: Option<Uuid> = row.get("digest_id");
+        if current.is_read
+            || deleted_at.is_some()
+            || digest_id.is_some()
+            || current.created_at < window_start
+        {
+            sqlx::query("UPDATE notifications SET group_open = FALSE WHERE id = $1")
+                .bind(current.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to close aggregation group: {}", e))?;
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit aggregation: {}", e))?;
+            return Ok(MergeResult::NoGroup);
+        }
+        let last_pushed_at: Option<chrono::DateTime<Utc>> = row.get("last_pushed_at");
+
+        let mut state =
+            AggregationState::from_metadata(current.metadata.as_ref()).unwrap_or_else(|| {
+                AggregationState::new(aggregation::Actor {
+                    id: current.sender_id,
+                    name: "Someone".to_string(),
+                })
+            });
+        if !state.add_actor(aggregation::actor_of(req), aggregator.config.max_actors) {
+            return Ok(MergeResult::DuplicateActor(current));
+        }
+
+        let body = aggregation::render_body(
+            req.notification_type,
+            req.object_type
+                .as_deref()
+                .or(current.object_type.as_deref()),
+            &state,
+        );
+        let metadata = state.attach_to(req.metadata.clone());
+
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE notifications
+            SET title = $2, body = $3, metadata = $4, sender_id = COALESCE($5, sender_id),
+                image_url = COALESCE($6, image_url), status = 'queued', updated_at = NOW()
+            WHERE id = $1
+            RETURNING {NOTIFICATION_COLUMNS}
+            "#
+        ))
+        .bind(current.id)
+        .bind(&req.title)
+        .bind(&body)
+        .bind(&metadata)
+        .bind(req.sender_id)
+        .bind(&req.image_url)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to update aggregated notification: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit aggregation: {}", e))?;
+
+        debug!(
+            "Aggregated {} into notification {} ({} actors)",
+            group_key, current.id, state.actor_count
+        );
+        Ok(MergeResult::Merged(
+            Self::notification_from_row(&row),
+            last_pushed_at,
+        ))
+    }
+
+    pub(crate) async fn mark_pushed(&self, notification_ids: &[Uuid]) -> Result<(), String> {
+        sqlx::query("UPDATE notifications SET last_pushed_at = NOW() WHERE id = ANY($1)")
+            .bind(notification_ids)
+            .execute(&self.db)
+            .await
+            .map_err(|e| format!("Failed to record push time: {}", e))?;
+        Ok(())
+    }
+
+    async fn mark_digest_pending(&self, notification_id: Uuid) -> Result<(), String> {
+        sqlx::query("UPDATE notifications SET digest_pending = TRUE WHERE id = $1")
+            .bind(notification_id)
+            .execute(&self.db)
+            .await
+            .map_err(|e| format!("Failed to mark notification for digest: {}", e))?;
+        Ok(())
+    }
+
+    /// Store a deferral if the recipient is in quiet hours; returns the release time
+    ///
+    /// High priority notifications are never deferred.
+    async fn defer_for_quiet_hours(
+        &self,
+        notification: &Notification,
+        preferences: &NotificationPreference,
+    ) -> Result<Option<DateTime<Utc>>, String> {
+        if notification.priority == NotificationPriority::High {
+            return Ok(None);
+        }
+        let Some(release_at) =
+            QuietHours::from_preferences(preferences).and_then(|q| q.window_end(Utc::now()))
+        else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO notification_deferrals (notification_id, recipient_id, release_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (notification_id) DO UPDATE
+            SET release_at = EXCLUDED.release_at, released_at = NULL,
+                claimed_until = NULL, attempts = 0
+            "#,
+        )
+        .bind(notification.id)
+        .bind(notification.recipient_id)
+        .bind(release_at)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to defer notification: {}", e))?;
+
+        metrics::record_notification_deferred(notification.notification_type.as_str());
+        debug!(
+            "Deferred notification {} until {} (quiet hours)",
+            notification.id, release_at
+        );
+        Ok(Some(release_at))
+    }
+
+    /// Push a stored notification now, or defer it if the recipient is in quiet hours
+    pub async fn push_or_defer(&self, notification: &Notification) -> Result<Delivery, String> {
+        let preferences = self.get_preferences(notification.recipient_id).await?;
+        if let Some(release_at) = self
+            .defer_for_quiet_hours(notification, &preferences)
+            .await?
+        {
+            return Ok(Delivery::Deferred(release_at));
+        }
+
+        self.send_push_notifications(notification).await?;
+        self.mark_pushed(&[notification.id]).await?;
+        Ok(Delivery::Push)
     }
 
     /// Register or update a device token
@@ -128,6 +521,17 @@ impl NotificationService {
         channel: NotificationChannel,
         device_type: String,
     ) -> Result<Uuid, String> {
+        let token = match channel {
+            NotificationChannel::Email | NotificationChannel::SMS => {
+                let destination = normalize_destination(channel, &token).ok_or_else(|| {
+                    format!("Invalid {} destination: {}", channel.as_str(), token)
+                })?;
+                self.check_suppression(channel, &destination).await?;
+                destination
+            }
+            _ => token,
+        };
+
         let device_token_id = Uuid::new_v4();
         let now = Utc::now();
 
@@ -161,6 +565,99 @@ impl NotificationService {
         Ok(registered_id)
     }
 
+    /// Refuse destinations that bounced or complained; an explicit
+    /// re-registration after an unsubscribe opts the destination back in
+    async fn check_suppression(
+        &self,
+        channel: NotificationChannel,
+        destination: &str,
+    ) -> Result<(), String> {
+        let reason: Option<String> = sqlx::query_scalar(
+            "SELECT reason FROM channel_suppressions WHERE channel = $1 AND destination = $2",
+        )
+        .bind(channel.as_str())
+        .bind(destination)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| format!("Failed to check channel suppression: {}", e))?;
+
+        match reason.as_deref() {
+            None => Ok(()),
+            Some(suppression::UNSUBSCRIBE) => {
+                sqlx::query(
+                    "DELETE FROM channel_suppressions WHERE channel = $1 AND destination = $2",
+                )
+                .bind(channel.as_str())
+                .bind(destination)
+                .execute(&self.db)
+                .await
+                .map_err(|e| format!("Failed to clear channel suppression: {}", e))?;
+                Ok(())
+            }
+            Some(reason) => Err(format!(
+                "{} destination is suppressed ({})",
+                channel.as_str(),
+                reason
+            )),
+        }
+    }
+
+    /// Stop delivering to an email address / phone number after a bounce,
+    /// complaint or unsubscribe; returns the number of tokens deactivated
+    pub async fn suppress_destination(
+        &self,
+        channel: NotificationChannel,
+        destination: &str,
+        reason: &str,
+    ) -> Result<u64, String> {
+        let destination = normalize_destination(channel, destination)
+            .ok_or_else(|| format!("Invalid {} destination: {}", channel.as_str(), destination))?;
+
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start suppression transaction: {}", e))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO channel_suppressions (channel, destination, reason)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (channel, destination) DO UPDATE
+            SET reason = EXCLUDED.reason, created_at = NOW()
+            "#,
+        )
+        .bind(channel.as_str())
+        .bind(&destination)
+        .bind(reason)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to record channel suppression: {}", e))?;
+
+        let deactivated = sqlx::query(
+            "UPDATE device_tokens SET is_active = false WHERE channel = $1 AND token = $2",
+        )
+        .bind(channel.as_str())
+        .bind(&destination)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to deactivate suppressed destination: {}", e))?
+        .rows_affected();
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit channel suppression: {}", e))?;
+
+        metrics::record_channel_suppressed(channel.as_str(), reason);
+        info!(
+            "Suppressed {} destination ({}), {} tokens deactivated",
+            channel.as_str(),
+            reason,
+            deactivated
+        );
+        Ok(deactivated)
+    }
+
     /// Unregister a device token
     pub async fn unregister_device_token(&self, user_id: Uuid, token: &str) -> Result<(), String> {
         let query = r#"
@@ -232,8 +729,8 @@ impl NotificationService {
         let query = r#"
             SELECT id, user_id, enabled, like_enabled, comment_enabled, follow_enabled,
                    mention_enabled, message_enabled, stream_enabled,
-                   quiet_hours_start, quiet_hours_end, prefer_fcm, prefer_apns, prefer_email,
-                   updated_at
+                   quiet_hours_start, quiet_hours_end, timezone, prefer_fcm, prefer_apns,
+                   prefer_email, digest_frequency, updated_at
             FROM notification_preferences
             WHERE user_id = $1
         "#;
@@ -257,9 +754,15 @@ impl NotificationService {
                     stream_enabled: row.get("stream_enabled"),
                     quiet_hours_start: row.get("quiet_hours_start"),
                     quiet_hours_end: row.get("quiet_hours_end"),
+                    timezone: row.get("timezone"),
                     prefer_fcm: row.get("prefer_fcm"),
                     prefer_apns: row.get("prefer_apns"),
                     prefer_email: row.get("prefer_email"),
+                    digest_frequency: row
+                        .get::<Option<String>, _>("digest_frequency")
+                        .as_deref()
+                        .and_then(DigestFrequency::parse)
+                        .unwrap_or_default(),
                     updated_at: row.get("updated_at"),
                 };
                 Ok(pref)
@@ -299,15 +802,73 @@ impl NotificationService {
                     stream_enabled: true,
                     quiet_hours_start: None,
                     quiet_hours_end: None,
+                    timezone: None,
                     prefer_fcm: true,
                     prefer_apns: true,
                     prefer_email: false,
+                    digest_frequency: DigestFrequency::Off,
                     updated_at: Utc::now(),
                 })
             }
         }
     }
 
+    /// Persist user's notification preferences
+    pub async fn update_preferences(
+        &self,
+        preferences: &NotificationPreference,
+    ) -> Result<(), String> {
+        let query = r#"
+            INSERT INTO notification_preferences (
+                id, user_id, enabled, like_enabled, comment_enabled, follow_enabled,
+                mention_enabled, message_enabled, stream_enabled,
+                quiet_hours_start, quiet_hours_end, timezone, prefer_fcm, prefer_apns,
+                prefer_email, digest_frequency, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, NOW()
+            )
+            ON CONFLICT (user_id) DO UPDATE SET
+                enabled = EXCLUDED.enabled,
+                like_enabled = EXCLUDED.like_enabled,
+                comment_enabled = EXCLUDED.comment_enabled,
+                follow_enabled = EXCLUDED.follow_enabled,
+                mention_enabled = EXCLUDED.mention_enabled,
+                message_enabled = EXCLUDED.message_enabled,
+                stream_enabled = EXCLUDED.stream_enabled,
+                quiet_hours_start = EXCLUDED.quiet_hours_start,
+                quiet_hours_end = EXCLUDED.quiet_hours_end,
+                timezone = EXCLUDED.timezone,
+                prefer_fcm = EXCLUDED.prefer_fcm,
+                prefer_apns = EXCLUDED.prefer_apns,
+                prefer_email = EXCLUDED.prefer_email,
+                digest_frequency = EXCLUDED.digest_frequency,
+                updated_at = NOW()
+        "#;
+
+        sqlx::query(query)
+            .bind(preferences.id)
+            .bind(preferences.user_id)
+            .bind(preferences.enabled)
+            .bind(preferences.like_enabled)
+            .bind(preferences.comment_enabled)
+            .bind(preferences.follow_enabled)
+            .bind(preferences.mention_enabled)
+            .bind(preferences.message_enabled)
+            .bind(preferences.stream_enabled)
+            .bind(&preferences.quiet_hours_start)
+            .bind(&preferences.quiet_hours_end)
+            .bind(preferences.timezone.as_deref().unwrap_or("UTC"))
+            .bind(preferences.prefer_fcm)
+            .bind(preferences.prefer_apns)
+            .bind(preferences.prefer_email)
+            .bind(preferences.digest_frequency.as_str())
+            .execute(&self.db)
+            .await
+            .map_err(|e| format!("Failed to update preferences: {}", e))?;
+
+        Ok(())
+    }
+
     /// Check if notification should be sent based on preferences
     pub fn should_send_notification(
         &self,
@@ -346,18 +907,71 @@ impl NotificationService {
             return Ok(Vec::new());
         }
 
-        // Get user devices
+        // Open sockets (any node) get it in real time
+        if let Some(realtime) = &self.realtime {
+            if let Err(e) = realtime
+                .send_notification(
+                    notification.recipient_id,
+                    WebSocketMessage::from_notification(notification),
+                )
+                .await
+            {
+                warn!("WebSocket delivery failed for {}: {}", notification.id, e);
+            }
+        }
+
+        // Get user devices; email/SMS only join per the fallback policy
         let devices = self.get_user_devices(notification.recipient_id).await?;
+        let planned = self
+            .fallback
+            .plan(&devices, &preferences, notification.priority, Utc::now());
 
         let mut results = Vec::new();
+        let mut push_queued = false;
+        let mut push_sent = false;
 
-        for device in devices {
+        for device in planned {
             let result = self
-                .send_to_device(notification, &device, &preferences)
+                .send_to_device(notification, device, &preferences)
                 .await;
+            if matches!(
+                device.channel,
+                NotificationChannel::FCM | NotificationChannel::APNs
+            ) {
+                push_queued = true;
+                push_sent |= result.success;
+            }
+            if let Err(e) = self
+                .record_delivery_attempt(&DeliveryAttempt {
+                    id: Uuid::new_v4(),
+                    notification_id: notification.id,
+                    device_token_id: device.id,
+                    channel: device.channel,
+                    status: if result.success {
+                        NotificationStatus::Delivered
+                    } else {
+                        NotificationStatus::Failed
+                    },
+                    error_message: result.error.clone(),
+                    retry_count: 0,
+                    attempted_at: Utc::now(),
+                    retry_at: None,
+                })
+                .await
+            {
+                warn!("{}", e);
+            }
             results.push(result);
         }
 
+        // Funnel stages count notifications, not devices
+        if push_queued {
+            metrics::record_push_funnel(funnel::QUEUED);
+        }
+        if push_sent {
+            metrics::record_push_funnel(funnel::SENT);
+        }
+
         Ok(results)
     }
 
@@ -373,6 +987,9 @@ impl NotificationService {
                 self.send_via_apns(notification, device).await
             }
             NotificationChannel::FCM => self.send_via_fcm(notification, device).await,
+            NotificationChannel::Email | NotificationChannel::SMS => {
+                self.send_via_provider(notification, device).await
+            }
             NotificationChannel::WebSocket => {
                 // WebSocket handled separately (real-time push)
                 PushNotificationResult {
@@ -394,50 +1011,162 @@ impl NotificationService {
         }
     }
 
+    /// Send via the registered email/SMS provider
+    async fn send_via_provider(
+        &self,
+        notification: &Notification,
+        device: &DeviceToken,
+    ) -> PushNotificationResult {
+        let channel = device.channel.as_str();
+        let Some(provider) = self
+            .channel_providers
+            .iter()
+            .find(|p| p.channel() == device.channel)
+        else {
+            debug!("No provider configured for channel: {}", channel);
+            return PushNotificationResult {
+                device_token_id: device.id,
+                success: false,
+                message_id: None,
+                error: Some(format!("{} provider not configured", channel)),
+            };
+        };
+
+        match provider.send(&device.token, notification).await {
+            Ok(message_id) => {
+                metrics::record_channel_delivery(channel, "success");
+                PushNotificationResult {
+                    device_token_id: device.id,
+                    success: true,
+                    message_id: Some(message_id),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                warn!("{} delivery failed: {}", channel, e);
+                if let ChannelError::Permanent(_) = e {
+                    metrics::record_channel_delivery(channel, "permanent_failure");
+                    if let Err(e) = self
+                        .suppress_destination(device.channel, &device.token, suppression::BOUNCE)
+                        .await
+                    {
+                        warn!("{}", e);
+                    }
+                } else {
+                    metrics::record_channel_delivery(channel, "transient_failure");
+                }
+                PushNotificationResult {
+                    device_token_id: device.id,
+                    success: false,
+                    message_id: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        }
+    }
+
+    /// Persist one delivery attempt
+    pub async fn record_delivery_attempt(&self, attempt: &DeliveryAttempt) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT INTO delivery_attempts (
+                id, notification_id, device_token_id, channel, status, error_message,
+                retry_count, attempted_at, retry_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(attempt.id)
+        .bind(attempt.notification_id)
+        .bind(attempt.device_token_id)
+        .bind(attempt.channel.as_str())
+        .bind(attempt.status.as_str())
+        .bind(&attempt.error_message)
+        .bind(attempt.retry_count)
+        .bind(attempt.attempted_at)
+        .bind(attempt.retry_at)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to record delivery attempt: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Delete delivery attempts recorded before `older_than`, in batches;
+    /// returns the number of rows deleted
+    pub async fn prune_delivery_attempts(&self, older_than: DateTime<Utc>) -> Result<u64, String> {
+        let mut deleted = 0;
+        loop {
+            let result = sqlx::query(
+                r#"
+                DELETE FROM delivery_attempts
+                WHERE id IN (
+                    SELECT id FROM delivery_attempts
+                    WHERE attempted_at < $1
+                    LIMIT 10000
+                )
+                "#,
+            )
+            .bind(older_than)
+            .execute(&self.db)
+            .await
+            .map_err(|e| format!("Failed to prune delivery attempts: {}", e))?;
+
+            deleted += result.rows_affected();
+            if result.rows_affected() < 10000 {
+                return Ok(deleted);
+            }
+        }
+    }
+
     /// Send via APNs (iOS/macOS)
     async fn send_via_apns(
         &self,
         notification: &Notification,
         device: &DeviceToken,
     ) -> PushNotificationResult {
-        match &self.apns_client {
-            Some(apns) => {
-                match apns
-                    .send(
-                        &device.token,
-                        &notification.title,
-                        &notification.body,
-                        super::apns_client::APNsPriority::High, // Use High priority for normal notifications
-                    )
-                    .await
-                {
-                    Ok(result) => {
-                        debug!("APNs delivery successful: {}", result.message_id);
-                        PushNotificationResult {
-                            device_token_id: device.id,
-                            success: true,
-                            message_id: Some(result.message_id),
-                            error: None,
-                        }
-                    }
-                    Err(e) => {
-                        warn!("APNs delivery failed: {}", e);
-                        PushNotificationResult {
-                            device_token_id: device.id,
-                            success: false,
-                            message_id: None,
-                            error: Some(e),
-                        }
-                    }
+        let Some(apns) = self.apns_client.as_deref() else {
+            warn!("APNs client not configured");
+            return PushNotificationResult {
+                device_token_id: device.id,
+                success: false,
+                message_id: None,
+                error: Some("APNs client not configured".to_string()),
+            };
+        };
+
+        let (outcome, _retries) = self
+            .retry
+            .run("apns", move || {
+                apns.send(
+                    &device.token,
+                    &notification.title,
+                    &notification.body,
+                    super::apns_client::APNsPriority::High, // Use High priority for normal notifications
+                )
+            })
+            .await;
+
+        match outcome {
+            Ok(result) => {
+                debug!("APNs delivery successful: {}", result.message_id);
+                self.feedback.record_or_warn(device.id, "apns", None).await;
+                PushNotificationResult {
+                    device_token_id: device.id,
+                    success: true,
+                    message_id: Some(result.message_id),
+                    error: None,
                 }
             }
-            None => {
-                warn!("APNs client not configured");
+            Err(e) => {
+                warn!("APNs delivery failed: {}", e);
+                self.feedback
+                    .record_or_warn(device.id, "apns", Some(classify_push_error(&e)))
+                    .await;
                 PushNotificationResult {
                     device_token_id: device.id,
                     success: false,
                     message_id: None,
-                    error: Some("APNs client not configured".to_string()),
+                    error: Some(e),
                 }
             }
         }
@@ -449,44 +1178,49 @@ impl NotificationService {
         notification: &Notification,
         device: &DeviceToken,
     ) -> PushNotificationResult {
-        match &self.fcm_client {
-            Some(fcm) => {
-                match fcm
-                    .send(
-                        &device.token,
-                        &notification.title,
-                        &notification.body,
-                        notification.metadata.clone(),
-                    )
-                    .await
-                {
-                    Ok(result) => {
-                        debug!("FCM delivery successful: {}", result.message_id);
-                        PushNotificationResult {
-                            device_token_id: device.id,
-                            success: true,
-                            message_id: Some(result.message_id),
-                            error: result.error,
-                        }
-                    }
-                    Err(e) => {
-                        warn!("FCM delivery failed: {}", e);
-                        PushNotificationResult {
-                            device_token_id: device.id,
-                            success: false,
-                            message_id: None,
-                            error: Some(e),
-                        }
-                    }
+        let Some(fcm) = self.fcm_client.as_deref() else {
+            warn!("FCM client not configured");
+            return PushNotificationResult {
+                device_token_id: device.id,
+                success: false,
+                message_id: None,
+                error: Some("FCM client not configured".to_string()),
+            };
+        };
+
+        let (outcome, _retries) = self
+            .retry
+            .run("fcm", move || {
+                fcm.send(
+                    &device.token,
+                    &notification.title,
+                    &notification.body,
+                    notification.metadata.clone(),
+                )
+            })
+            .await;
+
+        match outcome {
+            Ok(result) => {
+                debug!("FCM delivery successful: {}", result.message_id);
+                self.feedback.record_or_warn(device.id, "fcm", None).await;
+                PushNotificationResult {
+                    device_token_id: device.id,
+                    success: true,
+                    message_id: Some(result.message_id),
+                    error: result.error,
                 }
             }
-            None => {
-                warn!("FCM client not configured");
+            Err(e) => {
+                warn!("FCM delivery failed: {}", e);
+                self.feedback
+                    .record_or_warn(device.id, "fcm", Some(classify_push_error(&e)))
+                    .await;
                 PushNotificationResult {
                     device_token_id: device.id,
                     success: false,
                     message_id: None,
-                    error: Some("FCM client not configured".to_string()),
+                    error: Some(e),
                 }
             }
         }
@@ -511,52 +1245,157 @@ impl NotificationService {
         Ok(())
     }
 
+    /// Mark a user's unread follow request notifications as read
+    ///
+    /// Called when the account goes public: graph-service approves every
+    /// pending request, so the requests are no longer actionable.
+    pub async fn resolve_follow_request_notifications(&self, user_id: Uuid) -> Result<u64, String> {
+        let now = Utc::now();
+        let query = r#"
+            UPDATE notifications
+            SET is_read = true, read_at = $1, status = 'read', updated_at = $1
+            WHERE recipient_id = $2
+              AND notification_type = $3
+              AND is_read = false
+              AND metadata->>'follow_request' = 'requested'
+        "#;
+
+        let result = sqlx::query(query)
+            .bind(now)
+            .bind(user_id)
+            .bind(NotificationType::Follow.as_str())
+            .execute(&self.db)
+            .await
+            .map_err(|e| format!("Failed to resolve follow request notifications: {}", e))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Record a client delivery/open receipt for the push funnel
+    ///
+    /// Returns false if the notification does not exist or the event was
+    /// already recorded (the first device to report wins). Opening implies
+    /// delivery, so an open without a prior delivered receipt counts both.
+    pub async fn record_receipt(
+        &self,
+        notification_id: Uuid,
+        event: ReceiptEvent,
+    ) -> Result<bool, String> {
+        match event {
+            ReceiptEvent::Delivered => {
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE notifications
+                    SET status = 'delivered', updated_at = NOW()
+                    WHERE id = $1 AND status IN ('queued', 'sending', 'failed')
+                    "#,
+                )
+                .bind(notification_id)
+                .execute(&self.db)
+                .await
+                .map_err(|e| format!("Failed to record delivery receipt: {}", e))?
+                .rows_affected();
+
+                if updated > 0 {
+                    metrics::record_push_funnel(funnel::DELIVERED);
+                }
+                Ok(updated > 0)
+            }
+            ReceiptEvent::Opened => {
+                let previous: Option<String> = sqlx::query_scalar(
+                    r#"
+                    UPDATE notifications n
+                    SET is_read = true, read_at = COALESCE(n.read_at, NOW()),
+                        status = 'read', updated_at = NOW()
+                    FROM (SELECT id, status FROM notifications WHERE id = $1 FOR UPDATE) prev
+                    WHERE n.id = prev.id AND n.is_read = false
+                    RETURNING prev.status
+                    "#,
+                )
+                .bind(notification_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(|e| format!("Failed to record open receipt: {}", e))?;
+
+                let Some(previous) = previous else {
+                    return Ok(false);
+                };
+                if matches!(previous.as_str(), "queued" | "sending" | "failed") {
+                    metrics::record_push_funnel(funnel::DELIVERED);
+                }
+                metrics::record_push_funnel(funnel::OPENED);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Push delivery health for a device token
+    pub async fn get_device_health(&self, device_id: Uuid) -> Result<Option<TokenHealth>, String> {
+        self.feedback.token_health(device_id).await
+    }
+
     /// Get notification by ID
     pub async fn get_notification(
         &self,
         notification_id: Uuid,
     ) -> Result<Option<Notification>, String> {
-        let query = r#"
-            SELECT id, recipient_id, sender_id, notification_type, title, body,
-                   image_url, object_id, object_type, metadata, priority, status,
-                   is_read, read_at, created_at, updated_at, expires_at
-            FROM notifications
-            WHERE id = $1
-        "#;
+        let query = format!("SELECT {NOTIFICATION_COLUMNS} FROM notifications WHERE id = $1");
 
-        match sqlx::query(query)
+        let row = sqlx::query(&query)
             .bind(notification_id)
             .fetch_optional(&self.db)
             .await
-            .map_err(|e| format!("Failed to fetch notification: {}", e))?
-        {
-            Some(row) => {
-                let notification_type_str: String = row.get("notification_type");
-                let priority_str: String = row.get("priority");
-                let status_str: String = row.get("status");
+            .map_err(|e| format!("Failed to fetch notification: {}", e))?;
 
-                let notification = Notification {
-                    id: row.get("id"),
-                    recipient_id: row.get("recipient_id"),
-                    sender_id: row.get("sender_id"),
-                    notification_type: Self::parse_notification_type(&notification_type_str),
-                    title: row.get("title"),
-                    body: row.get("body"),
-                    image_url: row.get("image_url"),
-                    object_id: row.get("object_id"),
-                    object_type: row.get("object_type"),
-                    metadata: row.get("metadata"),
-                    priority: Self::parse_priority(&priority_str),
-                    status: Self::parse_status(&status_str),
-                    is_read: row.get("is_read"),
-                    read_at: row.get("read_at"),
-                    created_at: row.get("created_at"),
-                    updated_at: row.get("updated_at"),
-                    expires_at: row.get("expires_at"),
-                };
-                Ok(Some(notification))
-            }
-            None => Ok(None),
+        Ok(row.as_ref().map(Self::notification_from_row))
+    }
+
+    /// Unread, non-deleted notifications among `ids`, newest first
+    pub async fn get_unread_notifications(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<Vec<Notification>, String> {
+        let query = format!(
+            r#"
+            SELECT {NOTIFICATION_COLUMNS} FROM notifications
+            WHERE id = ANY($1) AND is_read = FALSE AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            "#
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(ids)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to fetch notifications: {}", e))?;
+
+        Ok(rows.iter().map(Self::notification_from_row).collect())
+    }
+
+    /// Map a row selected with `NOTIFICATION_COLUMNS`
+    fn notification_from_row(row: &PgRow) -> Notification {
+        let notification_type_str: String = row.get("notification_type");
+        let priority_str: String = row.get("priority");
+        let status_str: String = row.get("status");
+
+        Notification {
+            id: row.get("id"),
+            recipient_id: row.get("recipient_id"),
+            sender_id: row.get("sender_id"),
+            notification_type: Self::parse_notification_type(&notification_type_str),
+            title: row.get("title"),
+            body: row.get("body"),
+            image_url: row.get("image_url"),
+            object_id: row.get("object_id"),
+            object_type: row.get("object_type"),
+            metadata: row.get("metadata"),
+            priority: Self::parse_priority(&priority_str),
+            status: Self::parse_status(&status_str),
+            is_read: row.get("is_read"),
+            read_at: row.get("read_at"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            expires_at: row.get("expires_at"),
         }
     }
 
@@ -571,6 +1410,7 @@ impl NotificationService {
             "MESSAGE" => NotificationType::Message,
             "VIDEO" => NotificationType::Video,
             "STREAM" => NotificationType::Stream,
+            "SHARE" => NotificationType::Share,
             _ => NotificationType::System,
         }
     }
@@ -0,0 +1,613 @@
+/// Push Delivery Feedback
+///
+/// Closes the loop on FCM/APNs sends:
+/// - Classifies provider errors (from `nova-fcm-shared` / `nova-apns-shared`)
+///   into unregistered, bad token, throttled, payload too large or transient
+/// - Retries throttled and transient sends with exponential backoff
+/// - Deactivates tokens the provider reports as dead
+/// - Keeps per-token success/failure counts in `push_token_health`
+/// - Counts the delivery funnel (queued → sent → delivered → opened)
+use crate::metrics;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use std::future::Future;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Delivery funnel stages, in order
+///
+/// Every stage counts notifications, not tokens: receipts arrive per
+/// notification, so a push to three devices is one queued/sent notification.
+pub mod funnel {
+    /// Push accepted for sending to at least one token
+    pub const QUEUED: &str = "queued";
+    /// Provider (FCM/APNs) accepted the message for at least one token
+    pub const SENT: &str = "sent";
+    /// Client reported the notification arrived
+    pub const DELIVERED: &str = "delivered";
+    /// Client reported the user opened it
+    pub const OPENED: &str = "opened";
+}
+
+/// Funnel event reported by the client app
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptEvent {
+    /// Notification shown on the device
+    Delivered,
+    /// User tapped/opened it
+    Opened,
+}
+
+impl ReceiptEvent {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "delivered" => Some(ReceiptEvent::Delivered),
+            "opened" => Some(ReceiptEvent::Opened),
+            _ => None,
+        }
+    }
+}
+
+/// Why a push send failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushFailureKind {
+    /// App uninstalled or token expired (FCM UNREGISTERED, APNs Unregistered)
+    Unregistered,
+    /// Token malformed or for another app/environment
+    BadToken,
+    /// Provider rate limit (FCM QUOTA_EXCEEDED, APNs TooManyRequests)
+    Throttled,
+    /// Payload over the provider limit (4 KB)
+    PayloadTooLarge,
+    /// Provider 5xx or network error
+    Transient,
+    /// Anything else, including our own credential/config errors
+    Unknown,
+}
+
+impl PushFailureKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PushFailureKind::Unregistered => "unregistered",
+            PushFailureKind::BadToken => "bad_token",
+            PushFailureKind::Throttled => "throttled",
+            PushFailureKind::PayloadTooLarge => "payload_too_large",
+            PushFailureKind::Transient => "transient",
+            PushFailureKind::Unknown => "unknown",
+        }
+    }
+
+    /// The token will never work again
+    pub fn deactivates_token(&self) -> bool {
+        matches!(
+            self,
+            PushFailureKind::Unregistered | PushFailureKind::BadToken
+        )
+    }
+
+    /// Sending the same message again later may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            PushFailureKind::Throttled | PushFailureKind::Transient
+        )
+    }
+}
+
+/// First standalone 3-digit HTTP status in an error message
+fn http_status(error: &str) -> Option<u16> {
+    error
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| part.len() == 3 && part.bytes().all(|b| b.is_ascii_digit()))
+        .filter_map(|part| part.parse::<u16>().ok())
+        .find(|status| (100..600).contains(status))
+}
+
+/// Classify an FCM or APNs send error
+///
+/// FCM errors carry the HTTP status and the v1 error body (`errorCode`);
+/// APNs errors carry the status and reason code. Tokens are only reported
+/// dead for the provider codes that say so; any other 4xx (bad request
+/// fields, our credentials) is `Unknown` so the token is kept.
+pub fn classify_push_error(error: &str) -> PushFailureKind {
+    let lower = error.to_lowercase();
+    let has = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+    let status = http_status(error);
+    let status_in = |codes: &[u16]| status.is_some_and(|s| codes.contains(&s));
+
+    // Provider authentication/configuration problems are ours, not the token's
+    if status_in(&[401, 403])
+        || has(&[
+            "providertoken",
+            "badcertificate",
+            "badtopic",
+            "topicdisallowed",
+            "third_party_auth_error",
+            "not configured",
+        ])
+    {
+        return PushFailureKind::Unknown;
+    }
+    if status_in(&[413]) || has(&["payloadtoolarge", "too big", "too large"]) {
+        return PushFailureKind::PayloadTooLarge;
+    }
+    if status_in(&[429]) || has(&["quota_exceeded", "toomanyrequests", "too many requests"]) {
+        return PushFailureKind::Throttled;
+    }
+    // FCM UNREGISTERED / legacy NotRegistered, APNs Unregistered
+    if has(&["unregistered", "notregistered"]) {
+        return PushFailureKind::Unregistered;
+    }
+    if has(&[
+        // FCM
+        "sender_id_mismatch",
+        "invalidregistration",
+        "invalid_registration",
+        "not a valid fcm registration token",
+        // APNs
+        "baddevicetoken",
+        "devicetokennotfortopic",
+    ]) {
+        return PushFailureKind::BadToken;
+    }
+    if status.is_some_and(|s| (400..500).contains(&s)) {
+        return PushFailureKind::Unknown;
+    }
+    if status.is_some_and(|s| s >= 500)
+        || has(&[
+            "unavailable",
+            "internal",
+            "shutdown",
+            "timeout",
+            "timed out",
+            "connection",
+            "request failed",
+        ])
+    {
+        return PushFailureKind::Transient;
+    }
+    PushFailureKind::Unknown
+}
+
+/// Backoff for throttled/transient sends
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Load from environment
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_retries: std::env::var("PUSH_RETRY_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_retries),
+            base_delay: std::env::var("PUSH_RETRY_BASE_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.base_delay),
+            max_delay: std::env::var("PUSH_RETRY_MAX_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.max_delay),
+        }
+    }
+
+    /// Delay before retry number `retry` (0-based): base * 2^retry, capped
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+
+    /// Run `send`, retrying throttled/transient failures with backoff
+    ///
+    /// Returns the final result and how many retries were made.
+    pub async fn run<T, F, Fut>(&self, provider: &str, mut send: F) -> (Result<T, String>, u32)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let mut retries = 0;
+        loop {
+            match send().await {
+                Ok(value) => return (Ok(value), retries),
+                Err(e) => {
+                    let kind = classify_push_error(&e);
+                    if !kind.is_retryable() || retries >= self.max_retries {
+                        return (Err(e), retries);
+                    }
+                    metrics::record_push_retry(provider, kind.as_str());
+                    tokio::time::sleep(self.delay_for(retries)).await;
+                    retries += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Which table a token ID belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenStore {
+    /// `push_tokens`, used by the gRPC `PushSender`
+    PushTokens,
+    /// `device_tokens`, used by `NotificationService`
+    DeviceTokens,
+}
+
+impl TokenStore {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenStore::PushTokens => "push_tokens",
+            TokenStore::DeviceTokens => "device_tokens",
+        }
+    }
+
+    fn deactivate_sql(&self) -> &'static str {
+        match self {
+            TokenStore::PushTokens => {
+                "UPDATE push_tokens SET is_valid = FALSE, updated_at = NOW() WHERE id = $1 AND is_valid"
+            }
+            TokenStore::DeviceTokens => {
+                "UPDATE device_tokens SET is_active = FALSE WHERE id = $1 AND is_active"
+            }
+        }
+    }
+}
+
+/// Delivery health of one token
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenHealth {
+    pub token_id: Uuid,
+    pub provider: String,
+    pub success_count: i64,
+    pub failure_count: i64,
+    pub consecutive_failures: i32,
+    pub last_failure_kind: Option<String>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    pub deactivated_at: Option<DateTime<Utc>>,
+}
+
+impl TokenHealth {
+    /// Share of sends the provider accepted; `None` before the first send
+    pub fn success_rate(&self) -> Option<f64> {
+        let total = self.success_count + self.failure_count;
+        (total > 0).then(|| self.success_count as f64 / total as f64)
+    }
+}
+
+/// Records send outcomes and prunes dead tokens
+#[derive(Clone)]
+pub struct PushFeedback {
+    db: PgPool,
+    store: TokenStore,
+}
+
+impl PushFeedback {
+    pub fn new(db: PgPool, store: TokenStore) -> Self {
+        Self { db, store }
+    }
+
+    /// Record a send outcome; returns true if the token was deactivated
+    pub async fn record(
+        &self,
+        token_id: Uuid,
+        provider: &str,
+        failure: Option<PushFailureKind>,
+    ) -> Result<bool, String> {
+        if let Some(kind) = failure {
+            metrics::record_push_failure(provider, kind.as_str());
+        }
+        let deactivate = failure.is_some_and(|kind| kind.deactivates_token());
+
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin token feedback transaction: {}", e))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO push_token_health (
+                token_id, token_store, provider, success_count, failure_count,
+                consecutive_failures, last_failure_kind, last_success_at, last_failure_at,
+                deactivated_at, updated_at
+            )
+            VALUES (
+                $1, $2, $3,
+                CASE WHEN $4 THEN 1 ELSE 0 END,
+                CASE WHEN $4 THEN 0 ELSE 1 END,
+                CASE WHEN $4 THEN 0 ELSE 1 END,
+                $5,
+                CASE WHEN $4 THEN NOW() END,
+                CASE WHEN $4 THEN NULL ELSE NOW() END,
+                CASE WHEN $6 THEN NOW() END,
+                NOW()
+            )
+            ON CONFLICT (token_id) DO UPDATE SET
+                success_count = push_token_health.success_count + EXCLUDED.success_count,
+                failure_count = push_token_health.failure_count + EXCLUDED.failure_count,
+                consecutive_failures = CASE
+                    WHEN $4 THEN 0
+                    ELSE push_token_health.consecutive_failures + 1
+                END,
+                last_failure_kind = COALESCE($5, push_token_health.last_failure_kind),
+                last_success_at = COALESCE(EXCLUDED.last_success_at, push_token_health.last_success_at),
+                last_failure_at = COALESCE(EXCLUDED.last_failure_at, push_token_health.last_failure_at),
+                deactivated_at = COALESCE(EXCLUDED.deactivated_at, push_token_health.deactivated_at),
+                updated_at = NOW()
+            "#,
+        )
+        .bind(token_id)
+        .bind(self.store.as_str())
+        .bind(provider)
+        .bind(failure.is_none())
+        .bind(failure.map(|kind| kind.as_str()))
+        .bind(deactivate)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to record token health: {}", e))?;
+
+        let deactivated = if deactivate {
+            sqlx::query(self.store.deactivate_sql())
+                .bind(token_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to deactivate token: {}", e))?
+                .rows_affected()
+                > 0
+        } else {
+            false
+        };
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit token feedback: {}", e))?;
+
+        if let (true, Some(kind)) = (deactivated, failure) {
+            info!(
+                "Deactivated {} token {} ({})",
+                provider,
+                token_id,
+                kind.as_str()
+            );
+            metrics::record_push_token_deactivated(provider, kind.as_str());
+        }
+        Ok(deactivated)
+    }
+
+    /// Record an outcome, logging instead of failing the send
+    pub async fn record_or_warn(
+        &self,
+        token_id: Uuid,
+        provider: &str,
+        failure: Option<PushFailureKind>,
+    ) {
+        if let Err(e) = self.record(token_id, provider, failure).await {
+            warn!("Failed to record push feedback for {}: {}", token_id, e);
+        }
+    }
+
+    /// Delivery health for a token
+    pub async fn token_health(&self, token_id: Uuid) -> Result<Option<TokenHealth>, String> {
+        let row = sqlx::query(
+            r#"
+            SELECT token_id, provider, success_count, failure_count, consecutive_failures,
+                   last_failure_kind, last_success_at, last_failure_at, deactivated_at
+            FROM push_token_health
+            WHERE token_id = $1 AND token_store = $2
+            "#,
+        )
+        .bind(token_id)
+        .bind(self.store.as_str())
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| format!("Failed to load token health: {}", e))?;
+
+        Ok(row.map(|row| TokenHealth {
+            token_id: row.get("token_id"),
+            provider: row.get("provider"),
+            success_count: row.get("success_count"),
+            failure_count: row.get("failure_count"),
+            consecutive_failures: row.get("consecutive_failures"),
+            last_failure_kind: row.get("last_failure_kind"),
+            last_success_at: row.get("last_success_at"),
+            last_failure_at: row.get("last_failure_at"),
+            deactivated_at: row.get("deactivated_at"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_fcm_errors() {
+        let unregistered = r#"FCM API error: 404 Not Found - {"error":{"code":404,"status":"NOT_FOUND","details":[{"errorCode":"UNREGISTERED"}]}}"#;
+        assert_eq!(
+            classify_push_error(unregistered),
+            PushFailureKind::Unregistered
+        );
+
+        let bad_token = r#"FCM API error: 400 Bad Request - {"error":{"message":"The registration token is not a valid FCM registration token","status":"INVALID_ARGUMENT"}}"#;
+        assert_eq!(classify_push_error(bad_token), PushFailureKind::BadToken);
+
+        let too_big = r#"FCM API error: 400 Bad Request - {"error":{"message":"Android message is too big","status":"INVALID_ARGUMENT"}}"#;
+        assert_eq!(
+            classify_push_error(too_big),
+            PushFailureKind::PayloadTooLarge
+        );
+
+        let quota = r#"FCM API error: 429 Too Many Requests - {"error":{"details":[{"errorCode":"QUOTA_EXCEEDED"}]}}"#;
+        assert_eq!(classify_push_error(quota), PushFailureKind::Throttled);
+
+        assert_eq!(
+            classify_push_error("FCM API error: 503 Service Unavailable - {}"),
+            PushFailureKind::Transient
+        );
+        assert_eq!(
+            classify_push_error("FCM send request failed: error sending request"),
+            PushFailureKind::Transient
+        );
+        assert_eq!(
+            classify_push_error("FCM API error: 401 Unauthorized - THIRD_PARTY_AUTH_ERROR"),
+            PushFailureKind::Unknown
+        );
+
+        // Digits inside IDs are not status codes
+        assert_eq!(http_status("request 5004-a400 failed: 503"), Some(503));
+        assert_eq!(http_status("no status here"), None);
+    }
+
+    #[test]
+    fn test_classify_apns_errors() {
+        let cases = [
+            ("(410, reason: Unregistered)", PushFailureKind::Unregistered),
+            ("(400, reason: BadDeviceToken)", PushFailureKind::BadToken),
+            (
+                "(400, reason: DeviceTokenNotForTopic)",
+                PushFailureKind::BadToken,
+            ),
+            (
+                "(413, reason: PayloadTooLarge)",
+                PushFailureKind::PayloadTooLarge,
+            ),
+            ("(429, reason: TooManyRequests)", PushFailureKind::Throttled),
+            (
+                "(503, reason: ServiceUnavailable)",
+                PushFailureKind::Transient,
+            ),
+            (
+                "(403, reason: ExpiredProviderToken)",
+                PushFailureKind::Unknown,
+            ),
+            ("(400, reason: BadTopic)", PushFailureKind::Unknown),
+        ];
+        for (detail, expected) in cases {
+            let error = format!("APNs rejected notification {}", detail);
+            assert_eq!(classify_push_error(&error), expected, "{}", error);
+        }
+
+        assert!(PushFailureKind::Unregistered.deactivates_token());
+        assert!(!PushFailureKind::Throttled.deactivates_token());
+        assert!(PushFailureKind::Throttled.is_retryable());
+        assert!(!PushFailureKind::PayloadTooLarge.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_keeps_tokens_on_other_client_errors() {
+        let cases = [
+            r#"FCM API error: 400 Bad Request - {"error":{"message":"Invalid value at 'message.android.ttl'","status":"INVALID_ARGUMENT"}}"#,
+            r#"FCM API error: 404 Not Found - {"error":{"message":"Requested entity was not found."}}"#,
+            "Token request failed with status: 400",
+            "APNs rejected notification (400, reason: BadCollapseId)",
+            "APNs rejected notification (400, reason: BadExpirationDate)",
+            "APNs rejected notification (400, reason: MissingTopic)",
+            "APNs rejected notification (400, reason: PayloadEmpty)",
+            "APNs rejected notification (400, reason: InvalidPushType)",
+            "APNs rejected notification (400, reason: MissingDeviceToken)",
+        ];
+        for error in cases {
+            let kind = classify_push_error(error);
+            assert_eq!(kind, PushFailureKind::Unknown, "{}", error);
+            assert!(!kind.deactivates_token(), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(3),
+        };
+        assert_eq!(policy.delay_for(0), Duration::from_millis(500));
+        assert_eq!(policy.delay_for(1), Duration::from_secs(1));
+        assert_eq!(policy.delay_for(2), Duration::from_secs(2));
+        assert_eq!(policy.delay_for(3), Duration::from_secs(3));
+        assert_eq!(policy.delay_for(40), Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_retries_throttled_only() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+        };
+
+        let mut calls = 0;
+        let (result, retries) = policy
+            .run("fcm", || {
+                calls += 1;
+                let attempt = calls;
+                async move {
+                    if attempt < 3 {
+                        Err("FCM API error: 429 Too Many Requests".to_string())
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+        assert_eq!(result, Ok(3));
+        assert_eq!(retries, 2);
+
+        let mut calls = 0;
+        let (result, retries) = policy
+            .run("apns", || {
+                calls += 1;
+                async {
+                    Err::<(), _>(
+                        "APNs rejected notification (410, reason: Unregistered)".to_string(),
+                    )
+                }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(retries, 0);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_token_health_success_rate() {
+        let mut health = TokenHealth {
+            token_id: Uuid::new_v4(),
+            provider: "fcm".to_string(),
+            success_count: 0,
+            failure_count: 0,
+            consecutive_failures: 0,
+            last_failure_kind: None,
+            last_success_at: None,
+            last_failure_at: None,
+            deactivated_at: None,
+        };
+        assert_eq!(health.success_rate(), None);
+
+        health.success_count = 3;
+        health.failure_count = 1;
+        assert_eq!(health.success_rate(), Some(0.75));
+    }
+}
@@ -0,0 +1,475 @@
+/// Quiet Hours
+///
+/// Holds back non-urgent pushes while the recipient is in their quiet hours
+/// and releases them when the window ends in the recipient's own timezone.
+///
+/// Features:
+/// - Windows may wrap midnight ("22:00" - "07:00")
+/// - Evaluated in the user's IANA timezone, DST aware
+/// - Deferrals are stored in `notification_deferrals` and survive restarts
+/// - Several deferred notifications are collapsed into one push on release
+use super::aggregation::AggregationState;
+use super::digest::DigestSummary;
+use super::NotificationService;
+use crate::metrics;
+use crate::models::{DigestFrequency, Notification, NotificationPreference};
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use sqlx::{PgPool, Row};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// Parse an IANA timezone name ("Europe/Berlin", "UTC")
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+/// Parse a quiet hours bound ("22:00" or "22:00:00")
+pub fn parse_time(value: &str) -> Option<NaiveTime> {
+    let value = value.trim();
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+        .ok()
+}
+
+/// A user's quiet hours window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub timezone: Tz,
+}
+
+impl QuietHours {
+    /// Quiet hours from preferences; `None` if unset, invalid or empty
+    pub fn from_preferences(preferences: &NotificationPreference) -> Option<Self> {
+        let start = parse_time(preferences.quiet_hours_start.as_deref()?)?;
+        let end = parse_time(preferences.quiet_hours_end.as_deref()?)?;
+        if start == end {
+            return None;
+        }
+        let timezone = preferences
+            .timezone
+            .as_deref()
+            .and_then(parse_timezone)
+            .unwrap_or(Tz::UTC);
+        Some(Self {
+            start,
+            end,
+            timezone,
+        })
+    }
+
+    /// When the window containing `now` ends, or `None` outside quiet hours
+    pub fn window_end(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = now.with_timezone(&self.timezone);
+        let time = local.time();
+        let today = local.date_naive();
+
+        let end_date = if self.start < self.end {
+            (self.start <= time && time < self.end).then_some(today)?
+        } else if time >= self.start {
+            today.succ_opt()?
+        } else if time < self.end {
+            today
+        } else {
+            return None;
+        };
+
+        self.local_to_utc(end_date, self.end)
+    }
+
+    fn local_to_utc(&self, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+        local_to_utc(self.timezone, date, time)
+    }
+}
+
+/// Local wall-clock time to UTC; a time skipped by DST moves to the next hour
+pub fn local_to_utc(timezone: Tz, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+    let local = date.and_time(time);
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            timezone
+                .from_local_datetime(&(local + ChronoDuration::hours(1)))
+                .earliest()
+        })
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// The single push sent for a recipient's released notifications
+///
+/// One notification goes out unchanged; several are collapsed into a summary
+/// carried by the newest one. `None` if nothing is left to push.
+pub fn collapse_deferred(notifications: &[Notification]) -> Option<Notification> {
+    let newest = notifications.iter().max_by_key(|n| n.created_at)?;
+    if notifications.len() == 1 {
+        return Some(newest.clone());
+    }
+
+    let mut summary = DigestSummary::default();
+    for notification in notifications {
+        let count = AggregationState::from_metadata(notification.metadata.as_ref())
+            .map(|state| state.actor_count)
+            .unwrap_or(1);
+        summary.add(notification.notification_type.as_str(), count);
+    }
+    let (_, body) = summary.render(DigestFrequency::Daily);
+
+    let mut collapsed = newest.clone();
+    collapsed.title = "While you were away".to_string();
+    collapsed.body = body;
+    collapsed.metadata = Some(serde_json::json!({
+        "deferred": {
+            "count": notifications.len(),
+            "notification_ids": notifications
+                .iter()
+                .map(|n| n.id.to_string())
+                .collect::<Vec<_>>(),
+        }
+    }));
+    Some(collapsed)
+}
+
+/// Deferred delivery worker settings
+#[derive(Debug, Clone)]
+pub struct DeferredDeliveryConfig {
+    /// How often due deferrals are released
+    pub check_interval: Duration,
+    /// Recipients claimed per pass; each recipient's due deferrals are claimed together
+    pub batch_size: i64,
+    /// How long a claim lasts before another pass may retry it
+    pub lease: Duration,
+    /// Failed releases after which a deferral is dropped
+    pub max_attempts: i32,
+}
+
+impl Default for DeferredDeliveryConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(60),
+            batch_size: 1000,
+            lease: Duration::from_secs(300),
+            max_attempts: 5,
+        }
+    }
+}
+
+impl DeferredDeliveryConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            check_interval: std::env::var("NOTIFICATION_DEFERRED_CHECK_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.check_interval),
+            batch_size: std::env::var("NOTIFICATION_DEFERRED_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.batch_size),
+            lease: std::env::var("NOTIFICATION_DEFERRED_LEASE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.lease),
+            max_attempts: std::env::var("NOTIFICATION_DEFERRED_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_attempts),
+        }
+    }
+}
+
+/// Releases deferred pushes once the recipient's quiet hours are over
+pub struct DeferredDeliveryWorker {
+    db: PgPool,
+    service: Arc<NotificationService>,
+    config: DeferredDeliveryConfig,
+}
+
+impl DeferredDeliveryWorker {
+    pub fn new(
+        db: PgPool,
+        service: Arc<NotificationService>,
+        config: DeferredDeliveryConfig,
+    ) -> Self {
+        Self {
+            db,
+            service,
+            config,
+        }
+    }
+
+    /// Release every deferral due at `now`; returns the number of pushes sent
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<usize, String> {
+        let lease = ChronoDuration::from_std(self.config.lease)
+            .map_err(|e| format!("Invalid deferral lease: {}", e))?;
+
+        // Lease all due rows of a batch of recipients. A competing pass blocks on
+        // the same rows and then skips them because the lease is no longer free;
+        // rows stay unreleased until their push has gone out.
+        let rows = sqlx::query(
+            r#"
+            UPDATE notification_deferrals
+            SET claimed_until = $2, attempts = attempts + 1
+            WHERE released_at IS NULL AND release_at <= $1
+              AND (claimed_until IS NULL OR claimed_until <= $1)
+              AND recipient_id IN (
+                  SELECT recipient_id FROM notification_deferrals
+                  WHERE released_at IS NULL AND release_at <= $1
+                    AND (claimed_until IS NULL OR claimed_until <= $1)
+                  GROUP BY recipient_id
+                  ORDER BY MIN(release_at)
+                  LIMIT $3
+              )
+            RETURNING recipient_id, notification_id, attempts
+            "#,
+        )
+        .bind(now)
+        .bind(now + lease)
+        .bind(self.config.batch_size)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| format!("Failed to claim deferred notifications: {}", e))?;
+
+        let mut by_recipient: BTreeMap<Uuid, (Vec<Uuid>, i32)> = BTreeMap::new();
+        for row in &rows {
+            let (ids, attempts) = by_recipient.entry(row.get("recipient_id")).or_default();
+            ids.push(row.get("notification_id"));
+            *attempts = (*attempts).max(row.get("attempts"));
+        }
+
+        let mut pushed = 0;
+        for (recipient_id, (ids, attempts)) in by_recipient {
+            match self.release(&ids).await {
+                Ok(sent) => {
+                    if sent {
+                        pushed += 1;
+                    }
+                    self.mark_released(&ids, now).await;
+                }
+                Err(e) if attempts >= self.config.max_attempts => {
+                    error!(
+                        "Dropping {} deferred notifications for {} after {} attempts: {}",
+                        ids.len(),
+                        recipient_id,
+                        attempts,
+                        e
+                    );
+                    self.mark_released(&ids, now).await;
+                }
+                // The lease expires and a later pass retries
+                Err(e) => warn!(
+                    "Failed to release {} deferred notifications for {} (attempt {}): {}",
+                    ids.len(),
+                    recipient_id,
+                    attempts,
+                    e
+                ),
+            }
+        }
+        Ok(pushed)
+    }
+
+    /// Stamp deferrals as done; on failure the lease expires and they are sent again
+    async fn mark_released(&self, ids: &[Uuid], now: DateTime<Utc>) {
+        if let Err(e) = sqlx::query(
+            "UPDATE notification_deferrals SET released_at = $2, claimed_until = NULL WHERE notification_id = ANY($1)",
+        )
+        .bind(ids)
+        .bind(now)
+        .execute(&self.db)
+        .await
+        {
+            warn!("Failed to mark {} deferrals released: {}", ids.len(), e);
+        }
+    }
+
+    /// Push one (possibly collapsed) notification for a recipient's deferrals
+    async fn release(&self, ids: &[Uuid]) -> Result<bool, String> {
+        // Anything read or deleted during quiet hours no longer needs a push
+        let notifications = self.service.get_unread_notifications(ids).await?;
+        let Some(push) = collapse_deferred(&notifications) else {
+            return Ok(false);
+        };
+
+        self.service.send_push_notifications(&push).await?;
+        self.service.mark_pushed(ids).await?;
+
+        metrics::record_deferred_released(if notifications.len() > 1 {
+            "collapsed"
+        } else {
+            "single"
+        });
+        debug!(
+            "Released {} deferred notifications for {}",
+            notifications.len(),
+            push.recipient_id
+        );
+        Ok(true)
+    }
+}
+
+/// Run the deferred delivery worker until the process exits
+pub fn start_deferred_delivery_worker(
+    worker: DeferredDeliveryWorker,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            "Deferred delivery worker started (interval: {:?})",
+            worker.config.check_interval
+        );
+        let mut ticker = tokio::time::interval(worker.config.check_interval);
+        loop {
+            ticker.tick().await;
+            match worker.run_once(Utc::now()).await {
+                Ok(0) => {}
+                Ok(count) => info!("Released {} deferred pushes", count),
+                Err(e) => error!("Deferred delivery pass failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NotificationPriority, NotificationStatus, NotificationType};
+
+    fn quiet(start: &str, end: &str, timezone: &str) -> QuietHours {
+        QuietHours {
+            start: parse_time(start).unwrap(),
+            end: parse_time(end).unwrap(),
+            timezone: parse_timezone(timezone).unwrap(),
+        }
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn notification(notification_type: NotificationType, minutes_ago: i64) -> Notification {
+        let now = Utc::now();
+        Notification {
+            id: Uuid::new_v4(),
+            recipient_id: Uuid::nil(),
+            sender_id: None,
+            notification_type,
+            title: "New".to_string(),
+            body: "Someone did something".to_string(),
+            image_url: None,
+            object_id: None,
+            object_type: None,
+            metadata: None,
+            priority: NotificationPriority::Normal,
+            status: NotificationStatus::Queued,
+            is_read: false,
+            read_at: None,
+            created_at: now - ChronoDuration::minutes(minutes_ago),
+            updated_at: now,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn test_overnight_window() {
+        let hours = quiet("22:00", "07:00", "UTC");
+
+        assert_eq!(
+            hours.window_end(utc(2026, 10, 14, 23, 30)),
+            Some(utc(2026, 10, 15, 7, 0))
+        );
+        assert_eq!(
+            hours.window_end(utc(2026, 10, 15, 3, 0)),
+            Some(utc(2026, 10, 15, 7, 0))
+        );
+        assert_eq!(hours.window_end(utc(2026, 10, 15, 7, 0)), None);
+        assert_eq!(hours.window_end(utc(2026, 10, 15, 12, 0)), None);
+    }
+
+    #[test]
+    fn test_same_day_window_in_user_timezone() {
+        // 13:00-15:00 in Tokyo (UTC+9) is 04:00-06:00 UTC
+        let hours = quiet("13:00", "15:00", "Asia/Tokyo");
+
+        assert_eq!(
+            hours.window_end(utc(2026, 10, 15, 4, 30)),
+            Some(utc(2026, 10, 15, 6, 0))
+        );
+        assert_eq!(hours.window_end(utc(2026, 10, 15, 13, 30)), None);
+    }
+
+    #[test]
+    fn test_window_end_across_dst_change() {
+        // Berlin leaves DST on 2026-10-25: 07:00 local is 05:00 UTC before, 06:00 after
+        let hours = quiet("23:00", "07:00", "Europe/Berlin");
+
+        assert_eq!(
+            hours.window_end(utc(2026, 10, 24, 22, 0)),
+            Some(utc(2026, 10, 25, 6, 0))
+        );
+    }
+
+    #[test]
+    fn test_from_preferences_requires_valid_window() {
+        let mut preferences = NotificationPreference {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            enabled: true,
+            like_enabled: true,
+            comment_enabled: true,
+            follow_enabled: true,
+            mention_enabled: true,
+            message_enabled: true,
+            stream_enabled: true,
+            quiet_hours_start: Some("22:00".to_string()),
+            quiet_hours_end: Some("07:00".to_string()),
+            timezone: Some("America/New_York".to_string()),
+            prefer_fcm: true,
+            prefer_apns: true,
+            prefer_email: false,
+            digest_frequency: DigestFrequency::Off,
+            updated_at: Utc::now(),
+        };
+        assert_eq!(
+            QuietHours::from_preferences(&preferences).map(|q| q.timezone),
+            Some(chrono_tz::America::New_York)
+        );
+
+        preferences.quiet_hours_end = Some("22:00".to_string());
+        assert!(QuietHours::from_preferences(&preferences).is_none());
+
+        preferences.quiet_hours_end = Some("late".to_string());
+        assert!(QuietHours::from_preferences(&preferences).is_none());
+    }
+
+    #[test]
+    fn test_collapse_deferred() {
+        assert!(collapse_deferred(&[]).is_none());
+
+        let single = notification(NotificationType::Comment, 5);
+        assert_eq!(
+            collapse_deferred(std::slice::from_ref(&single)).map(|n| n.body),
+            Some(single.body.clone())
+        );
+
+        let mut liked = notification(NotificationType::Like, 30);
+        liked.metadata = Some(serde_json::json!({
+            "aggregation": { "actor_count": 12, "actors": [] }
+        }));
+        let followed = notification(NotificationType::Follow, 1);
+        let collapsed = collapse_deferred(&[liked, single, followed.clone()]).unwrap();
+
+        assert_eq!(collapsed.id, followed.id);
+        assert_eq!(collapsed.title, "While you were away");
+        assert_eq!(
+            collapsed.body,
+            "You got 12 likes, 1 comment and 1 new follower"
+        );
+    }
+}
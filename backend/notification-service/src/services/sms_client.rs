@@ -0,0 +1,147 @@
+/// HTTP SMS Gateway Provider
+///
+/// Delivers notifications on the `SMS` channel through a generic HTTP gateway:
+///
+/// ```text
+/// POST {SMS_GATEWAY_URL}
+/// Authorization: Bearer {SMS_GATEWAY_API_KEY}
+/// {"to": "+14155550100", "from": "Icered", "text": "..."}
+/// ```
+///
+/// A 2xx response may carry `id` or `message_id`. Other 4xx responses (except
+/// 429) reject the number permanently; 429, 5xx and network errors are
+/// transient.
+use super::channels::{ChannelError, ChannelProvider};
+use super::templates;
+use crate::models::{Notification, NotificationChannel};
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::debug;
+
+/// SMS gateway settings
+#[derive(Debug, Clone)]
+pub struct SmsGatewayConfig {
+    pub url: String,
+    pub api_key: String,
+    pub sender_id: String,
+    pub timeout: Duration,
+}
+
+impl SmsGatewayConfig {
+    /// `None` when SMS_GATEWAY_URL is unset (SMS channel disabled)
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("SMS_GATEWAY_URL").ok()?;
+        if url.trim().is_empty() {
+            return None;
+        }
+        Some(Self {
+            url,
+            api_key: std::env::var("SMS_GATEWAY_API_KEY").unwrap_or_default(),
+            sender_id: std::env::var("SMS_SENDER_ID")
+                .unwrap_or_else(|_| templates::APP_NAME.to_string()),
+            timeout: Duration::from_secs(
+                std::env::var("SMS_GATEWAY_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(10),
+            ),
+        })
+    }
+}
+
+/// SMS channel provider over an HTTP gateway
+pub struct SmsClient {
+    http_client: reqwest::Client,
+    config: SmsGatewayConfig,
+}
+
+impl SmsClient {
+    pub fn new(config: SmsGatewayConfig) -> Result<Self, String> {
+        let http_client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| format!("Failed to build SMS HTTP client: {}", e))?;
+        Ok(Self {
+            http_client,
+            config,
+        })
+    }
+}
+
+/// Map a gateway HTTP status to a channel error
+fn classify_status(status: reqwest::StatusCode, body: &str) -> ChannelError {
+    let message = format!("SMS gateway returned {}: {}", status, body);
+    if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        ChannelError::Permanent(message)
+    } else {
+        ChannelError::Transient(message)
+    }
+}
+
+#[async_trait]
+impl ChannelProvider for SmsClient {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::SMS
+    }
+
+    async fn send(
+        &self,
+        destination: &str,
+        notification: &Notification,
+    ) -> Result<String, ChannelError> {
+        let payload = serde_json::json!({
+            "to": destination,
+            "from": self.config.sender_id,
+            "text": templates::render_sms(notification),
+            "reference": notification.id.to_string(),
+        });
+
+        let response = self
+            .http_client
+            .post(&self.config.url)
+            .bearer_auth(&self.config.api_key)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| ChannelError::Transient(format!("SMS gateway request failed: {}", e)))?;
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(classify_status(status, &body));
+        }
+
+        let message_id = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| {
+                v.get("id")
+                    .or_else(|| v.get("message_id"))
+                    .and_then(|id| id.as_str().map(str::to_string))
+            })
+            .unwrap_or_else(|| notification.id.to_string());
+        debug!("SMS accepted for notification {}", notification.id);
+        Ok(message_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_classify_status() {
+        assert!(matches!(
+            classify_status(StatusCode::BAD_REQUEST, "invalid number"),
+            ChannelError::Permanent(_)
+        ));
+        assert!(matches!(
+            classify_status(StatusCode::TOO_MANY_REQUESTS, ""),
+            ChannelError::Transient(_)
+        ));
+        assert!(matches!(
+            classify_status(StatusCode::BAD_GATEWAY, ""),
+            ChannelError::Transient(_)
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+/// Channel Templates
+///
+/// Renders a stored notification for channels that cannot show the app's own
+/// UI: an email (subject, plain text and HTML) or a short SMS text. Each
+/// `NotificationType` has its own subject and call to action.
+use crate::models::{Notification, NotificationType};
+
+/// Product name used in subjects and SMS prefixes
+pub const APP_NAME: &str = "Icered";
+
+/// GSM-7 single-segment SMS length
+pub const SMS_MAX_CHARS: usize = 160;
+
+/// Rendered email content
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailContent {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Per-type subject and call to action
+struct TypeTemplate {
+    subject: &'static str,
+    action: &'static str,
+}
+
+fn template_for(notification_type: NotificationType) -> TypeTemplate {
+    let (subject, action) = match notification_type {
+        NotificationType::Like => ("New likes on your post", "See your post"),
+        NotificationType::Comment => ("New comment on your post", "Reply"),
+        NotificationType::Follow => ("You have a new follower", "View profile"),
+        NotificationType::Mention => ("You were mentioned", "See the mention"),
+        NotificationType::Message => ("You have a new message", "Open conversation"),
+        NotificationType::Share => ("Your post was shared", "See your post"),
+        NotificationType::Video => ("New video activity", "Watch now"),
+        NotificationType::Stream => ("A live stream is starting", "Join the stream"),
+        NotificationType::System => ("News from Icered", "Open Icered"),
+    };
+    TypeTemplate { subject, action }
+}
+
+/// Escape text for inclusion in HTML
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render an email; `unsubscribe_url` adds a footer link when present
+pub fn render_email(notification: &Notification, unsubscribe_url: Option<&str>) -> EmailContent {
+    let template = template_for(notification.notification_type);
+    let subject = if notification.title.trim().is_empty() {
+        template.subject.to_string()
+    } else {
+        format!("{}: {}", APP_NAME, notification.title.trim())
+    };
+
+    let mut text = format!(
+        "{}\n\n{}\n\n{} in the {} app.",
+        notification.title, notification.body, template.action, APP_NAME
+    );
+    if let Some(url) = unsubscribe_url {
+        text.push_str(&format!(
+            "\n\nDon't want these emails? Unsubscribe: {}",
+            url
+        ));
+    }
+
+    let footer = unsubscribe_url
+        .map(|url| {
+            format!(
+                r#"<p style="color: #999; font-size: 12px; margin-top: 30px;">Don't want these emails? <a href="{}" style="color: #999;">Unsubscribe</a></p>"#,
+                escape_html(url)
+            )
+        })
+        .unwrap_or_default();
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+</head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; padding: 20px; color: #333;">
+    <h2>{title}</h2>
+    <p>{body}</p>
+    <p style="color: #666;">{action} in the {app} app.</p>
+    {footer}
+</body>
+</html>"#,
+        title = escape_html(&notification.title),
+        body = escape_html(&notification.body),
+        action = template.action,
+        app = APP_NAME,
+        footer = footer,
+    );
+
+    EmailContent {
+        subject,
+        text,
+        html,
+    }
+}
+
+/// Render an SMS, truncated to a single segment
+pub fn render_sms(notification: &Notification) -> String {
+    let content = if notification.body.trim().is_empty() {
+        template_for(notification.notification_type).subject
+    } else {
+        notification.body.trim()
+    };
+    let text = format!("{}: {} Reply STOP to opt out.", APP_NAME, content);
+    if text.chars().count() <= SMS_MAX_CHARS {
+        return text;
+    }
+
+    // Keep the opt-out notice; shorten the content
+    let suffix = " Reply STOP to opt out.";
+    let prefix = format!("{}: ", APP_NAME);
+    let budget = SMS_MAX_CHARS - prefix.chars().count() - suffix.chars().count() - 1;
+    let shortened: String = content.chars().take(budget).collect();
+    format!("{}{}…{}", prefix, shortened.trim_end(), suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NotificationPriority, NotificationStatus};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn notification(notification_type: NotificationType, title: &str, body: &str) -> Notification {
+        Notification {
+            id: Uuid::new_v4(),
+            recipient_id: Uuid::new_v4(),
+            sender_id: None,
+            notification_type,
+            title: title.to_string(),
+            body: body.to_string(),
+            image_url: None,
+            object_id: None,
+            object_type: None,
+            metadata: None,
+            priority: NotificationPriority::Normal,
+            status: NotificationStatus::Queued,
+            is_read: false,
+            read_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn test_render_email_escapes_and_links_unsubscribe() {
+        let n = notification(
+            NotificationType::Comment,
+            "New Comment",
+            "<b>alice</b> commented: nice & clean",
+        );
+        let email = render_email(&n, Some("https://example.com/u?token=a&b"));
+
+        assert_eq!(email.subject, "Icered: New Comment");
+        assert!(email
+            .html
+            .contains("&lt;b&gt;alice&lt;/b&gt; commented: nice &amp; clean"));
+        assert!(email.html.contains("https://example.com/u?token=a&amp;b"));
+        assert!(email.text.contains("Reply in the Icered app."));
+        assert!(email
+            .text
+            .contains("Unsubscribe: https://example.com/u?token=a&b"));
+
+        let untitled = render_email(&notification(NotificationType::Follow, " ", "x"), None);
+        assert_eq!(untitled.subject, "You have a new follower");
+        assert!(!untitled.html.contains("Unsubscribe"));
+    }
+
+    #[test]
+    fn test_render_sms_fits_one_segment() {
+        let short = render_sms(&notification(
+            NotificationType::Message,
+            "New Message",
+            "bob sent you a message",
+        ));
+        assert_eq!(
+            short,
+            "Icered: bob sent you a message Reply STOP to opt out."
+        );
+
+        let long = render_sms(&notification(
+            NotificationType::Comment,
+            "New Comment",
+            &"very long comment ".repeat(20),
+        ));
+        assert_eq!(long.chars().count(), SMS_MAX_CHARS);
+        assert!(long.ends_with("… Reply STOP to opt out."));
+    }
+}
@@ -0,0 +1,468 @@
+/// Cluster-aware WebSocket fan-out over Redis
+///
+/// Each pod only holds its own sockets, so delivery to a user connected
+/// elsewhere goes through Redis:
+/// - Presence: `ws:presence:{user_id}` is a sorted set of node IDs scored by
+///   lease expiry (unix seconds). Nodes refresh leases on a heartbeat, so a
+///   crashed node drops out once its lease lapses.
+/// - Delivery: every node subscribes to `ws:node:{node_id}` and to
+///   `ws:broadcast`. A sender publishes to the channels of the nodes holding
+///   the recipient; pub/sub is fire-and-forget, replay covers the gaps.
+/// - Replay: notifications get a per-user sequence (`ws:seq:{user_id}`) and
+///   the last `replay_limit` are kept in `ws:replay:{user_id}` scored by
+///   sequence. A client reconnecting with `last_seq` receives what it missed.
+use super::{ConnectionManager, WebSocketMessage};
+use crate::metrics;
+use futures::StreamExt;
+use redis::{AsyncCommands, RedisError};
+use redis_utils::SharedConnectionManager;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Pub/sub channel every node listens on for broadcasts
+pub const BROADCAST_CHANNEL: &str = "ws:broadcast";
+
+/// Cluster fan-out settings
+#[derive(Debug, Clone)]
+pub struct ClusterConfig {
+    /// Unique ID of this pod
+    pub node_id: String,
+    /// How long a presence lease lasts without a heartbeat
+    pub presence_ttl: Duration,
+    /// Notifications kept per user for replay
+    pub replay_limit: usize,
+    /// How long an idle user's replay buffer and sequence are kept
+    pub replay_ttl: Duration,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            node_id: Uuid::new_v4().to_string(),
+            presence_ttl: Duration::from_secs(60),
+            replay_limit: 200,
+            replay_ttl: Duration::from_secs(24 * 3600),
+        }
+    }
+}
+
+impl ClusterConfig {
+    /// Load from environment; the node ID defaults to the pod hostname
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let secs = |name: &str, default: Duration| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+
+        Self {
+            node_id: std::env::var("WS_NODE_ID")
+                .or_else(|_| std::env::var("HOSTNAME"))
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or(defaults.node_id),
+            presence_ttl: secs("WS_PRESENCE_TTL_SECS", defaults.presence_ttl)
+                .max(Duration::from_secs(3)),
+            replay_limit: std::env::var("WS_REPLAY_LIMIT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.replay_limit),
+            replay_ttl: secs("WS_REPLAY_TTL_SECS", defaults.replay_ttl),
+        }
+    }
+}
+
+/// Message relayed between nodes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClusterEnvelope {
+    /// Publishing node, so broadcasts are not delivered twice locally
+    pub origin: String,
+    /// Recipient; `None` for broadcasts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+    pub message: WebSocketMessage,
+}
+
+/// Notifications missed since `last_seq`, and whether older ones were lost
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    pub messages: Vec<WebSocketMessage>,
+    /// The buffer no longer reaches back to `last_seq`; the client should
+    /// refetch its notification list over HTTP
+    pub truncated: bool,
+}
+
+fn presence_key(user_id: Uuid) -> String {
+    format!("ws:presence:{}", user_id)
+}
+
+fn seq_key(user_id: Uuid) -> String {
+    format!("ws:seq:{}", user_id)
+}
+
+fn replay_key(user_id: Uuid) -> String {
+    format!("ws:replay:{}", user_id)
+}
+
+/// Pub/sub channel for messages addressed to one node
+pub fn node_channel(node_id: &str) -> String {
+    format!("ws:node:{}", node_id)
+}
+
+/// Whether a replay starting at `first_seq` leaves a gap after `last_seq`
+///
+/// `current_seq` is the user's latest sequence; an empty replay is only a gap
+/// if notifications were issued that have since been trimmed.
+fn replay_gap(last_seq: u64, first_seq: Option<u64>, current_seq: u64) -> bool {
+    match first_seq {
+        Some(first) => first > last_seq + 1,
+        None => current_seq > last_seq,
+    }
+}
+
+/// Redis-backed presence registry, sequencer and cross-node relay
+pub struct ClusterBus {
+    redis: SharedConnectionManager,
+    client: redis::Client,
+    config: ClusterConfig,
+}
+
+impl ClusterBus {
+    /// `client` opens the dedicated pub/sub connection; commands share `redis`
+    pub fn new(
+        redis: SharedConnectionManager,
+        client: redis::Client,
+        config: ClusterConfig,
+    ) -> Self {
+        Self {
+            redis,
+            client,
+            config,
+        }
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.config.node_id
+    }
+
+    fn lease_expiry(&self) -> i64 {
+        chrono::Utc::now().timestamp() + self.config.presence_ttl.as_secs() as i64
+    }
+
+    /// Record that this node holds connections for the user
+    pub async fn register_presence(&self, user_id: Uuid) -> Result<(), RedisError> {
+        self.refresh_presence(&[user_id]).await
+    }
+
+    /// Renew this node's leases for the given users
+    pub async fn refresh_presence(&self, user_ids: &[Uuid]) -> Result<(), RedisError> {
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+        let expiry = self.lease_expiry();
+        let key_ttl = self.config.presence_ttl.as_secs() as i64 * 2;
+
+        let mut pipe = redis::pipe();
+        for user_id in user_ids {
+            let key = presence_key(*user_id);
+            pipe.zadd(&key, &self.config.node_id, expiry)
+                .ignore()
+                .expire(&key, key_ttl)
+                .ignore();
+        }
+
+        redis_utils::with_timeout(async {
+            let mut conn = self.redis.lock().await;
+            pipe.query_async::<_, ()>(&mut *conn).await
+        })
+        .await
+    }
+
+    /// Drop this node's lease once the user's last local socket closes
+    pub async fn remove_presence(&self, user_id: Uuid) -> Result<(), RedisError> {
+        redis_utils::with_timeout(async {
+            let mut conn = self.redis.lock().await;
+            conn.zrem::<_, _, ()>(presence_key(user_id), &self.config.node_id)
+                .await
+        })
+        .await
+    }
+
+    /// Nodes with a live lease for the user
+    pub async fn nodes_for(&self, user_id: Uuid) -> Result<Vec<String>, RedisError> {
+        let now = chrono::Utc::now().timestamp();
+        redis_utils::with_timeout(async {
+            let mut conn = self.redis.lock().await;
+            conn.zrangebyscore(presence_key(user_id), now, "+inf").await
+        })
+        .await
+    }
+
+    /// Assign the next sequence number and store the message for replay
+    pub async fn sequence(
+        &self,
+        user_id: Uuid,
+        message: WebSocketMessage,
+    ) -> Result<WebSocketMessage, RedisError> {
+        let seq_key = seq_key(user_id);
+        let replay_key = replay_key(user_id);
+        let ttl = self.config.replay_ttl.as_secs() as i64;
+        let keep = self.config.replay_limit.max(1) as isize;
+
+        redis_utils::with_timeout(async {
+            let mut conn = self.redis.lock().await;
+            let seq: u64 = conn.incr(&seq_key, 1).await?;
+            let message = message.with_seq(seq);
+            let payload = message.to_json().map_err(|e| {
+                RedisError::from((redis::ErrorKind::TypeError, "encode", e.to_string()))
+            })?;
+
+            redis::pipe()
+                .atomic()
+                .zadd(&replay_key, payload, seq)
+                .ignore()
+                .zremrangebyrank(&replay_key, 0, -(keep + 1))
+                .ignore()
+                .expire(&replay_key, ttl)
+                .ignore()
+                .expire(&seq_key, ttl)
+                .ignore()
+                .query_async::<_, ()>(&mut *conn)
+                .await?;
+            Ok(message)
+        })
+        .await
+    }
+
+    /// Notifications with a sequence above `last_seq`, oldest first
+    pub async fn replay_since(&self, user_id: Uuid, last_seq: u64) -> Result<Replay, RedisError> {
+        let (payloads, current_seq): (Vec<String>, Option<u64>) =
+            redis_utils::with_timeout(async {
+                let mut conn = self.redis.lock().await;
+                let payloads: Vec<String> = conn
+                    .zrangebyscore(replay_key(user_id), format!("({}", last_seq), "+inf")
+                    .await?;
+                let current_seq: Option<u64> = conn.get(seq_key(user_id)).await?;
+                Ok((payloads, current_seq))
+            })
+            .await?;
+
+        let messages: Vec<WebSocketMessage> = payloads
+            .iter()
+            .filter_map(|payload| WebSocketMessage::from_json(payload).ok())
+            .collect();
+        let truncated = replay_gap(
+            last_seq,
+            messages.first().and_then(WebSocketMessage::seq),
+            current_seq.unwrap_or(0),
+        );
+
+        Ok(Replay {
+            messages,
+            truncated,
+        })
+    }
+
+    /// Publish a message to a user's sockets on other nodes
+    pub async fn publish_to_user(
+        &self,
+        user_id: Uuid,
+        message: &WebSocketMessage,
+    ) -> Result<(), RedisError> {
+        let remote: Vec<String> = self
+            .nodes_for(user_id)
+            .await?
+            .into_iter()
+            .filter(|node| node != &self.config.node_id)
+            .collect();
+        if remote.is_empty() {
+            return Ok(());
+        }
+
+        let envelope = ClusterEnvelope {
+            origin: self.config.node_id.clone(),
+            user_id: Some(user_id),
+            message: message.clone(),
+        };
+        // One unreachable node must not starve the others
+        let mut failed = Vec::new();
+        for node in &remote {
+            if let Err(e) = self.publish(&node_channel(node), &envelope).await {
+                warn!(
+                    "Failed to relay message for {} to node {}: {}",
+                    user_id, node, e
+                );
+                metrics::record_ws_cluster_message("publish_failed");
+                failed.push(node.as_str());
+            }
+        }
+        if failed.is_empty() {
+            return Ok(());
+        }
+        Err(RedisError::from((
+            redis::ErrorKind::IoError,
+            "cluster relay failed",
+            format!(
+                "{} of {} nodes: {}",
+                failed.len(),
+                remote.len(),
+                failed.join(", ")
+            ),
+        )))
+    }
+
+    /// Publish a message to every other node's sockets
+    pub async fn publish_broadcast(&self, message: &WebSocketMessage) -> Result<(), RedisError> {
+        let envelope = ClusterEnvelope {
+            origin: self.config.node_id.clone(),
+            user_id: None,
+            message: message.clone(),
+        };
+        self.publish(BROADCAST_CHANNEL, &envelope).await
+    }
+
+    async fn publish(&self, channel: &str, envelope: &ClusterEnvelope) -> Result<(), RedisError> {
+        let payload = serde_json::to_string(envelope).map_err(|e| {
+            RedisError::from((redis::ErrorKind::TypeError, "encode", e.to_string()))
+        })?;
+        redis_utils::with_timeout(async {
+            let mut conn = self.redis.lock().await;
+            conn.publish::<_, _, ()>(channel, payload).await
+        })
+        .await?;
+        metrics::record_ws_cluster_message("published");
+        Ok(())
+    }
+
+    /// Subscribe to this node's channel and the broadcast channel, delivering
+    /// relayed messages to local sockets until the connection drops
+    async fn listen(&self, manager: &ConnectionManager) -> Result<(), RedisError> {
+        let mut pubsub = self.client.get_async_pubsub().await?;
+        pubsub.subscribe(node_channel(&self.config.node_id)).await?;
+        pubsub.subscribe(BROADCAST_CHANNEL).await?;
+        info!(
+            "WebSocket cluster listener subscribed (node: {})",
+            self.config.node_id
+        );
+
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            let payload: String = match msg.get_payload() {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("Unreadable cluster message: {}", e);
+                    continue;
+                }
+            };
+            let envelope: ClusterEnvelope = match serde_json::from_str(&payload) {
+                Ok(envelope) => envelope,
+                Err(e) => {
+                    warn!("Malformed cluster message: {}", e);
+                    continue;
+                }
+            };
+            if envelope.origin == self.config.node_id {
+                continue;
+            }
+
+            metrics::record_ws_cluster_message("received");
+            match envelope.user_id {
+                Some(user_id) => {
+                    manager.deliver_local(user_id, envelope.message).await;
+                }
+                None => {
+                    manager.broadcast_local(envelope.message).await;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Start the relay listener and the presence heartbeat for a cluster-enabled
+/// manager; no-op when the manager has no cluster bus
+pub fn start_cluster_fanout(manager: ConnectionManager) {
+    let Some(bus) = manager.cluster() else {
+        return;
+    };
+
+    let listener_manager = manager.clone();
+    let listener_bus = bus.clone();
+    tokio::spawn(async move {
+        loop {
+            match listener_bus.listen(&listener_manager).await {
+                Ok(()) => warn!("WebSocket cluster subscription closed; reconnecting"),
+                Err(e) => warn!("WebSocket cluster listener error: {}; reconnecting", e),
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    });
+
+    let interval = bus.config.presence_ttl / 3;
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let users = manager.connected_user_ids().await;
+            match bus.refresh_presence(&users).await {
+                Ok(()) => debug!("Refreshed WebSocket presence for {} users", users.len()),
+                Err(e) => warn!("Failed to refresh WebSocket presence: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let user_id = Uuid::new_v4();
+        let envelope = ClusterEnvelope {
+            origin: "pod-a".to_string(),
+            user_id: Some(user_id),
+            message: WebSocketMessage::notification(
+                Uuid::new_v4(),
+                user_id,
+                "like".to_string(),
+                "New Like".to_string(),
+                "Someone liked your post".to_string(),
+                None,
+                "normal".to_string(),
+            )
+            .with_seq(3),
+        };
+
+        let json = serde_json::to_string(&envelope).unwrap();
+        let decoded: ClusterEnvelope = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, envelope);
+        assert_eq!(decoded.message.seq(), Some(3));
+
+        let broadcast = ClusterEnvelope {
+            origin: "pod-a".to_string(),
+            user_id: None,
+            message: WebSocketMessage::ping(),
+        };
+        let json = serde_json::to_string(&broadcast).unwrap();
+        assert!(!json.contains("user_id"));
+        assert_eq!(node_channel("pod-a"), "ws:node:pod-a");
+    }
+
+    #[test]
+    fn test_replay_gap() {
+        // Contiguous replay
+        assert!(!replay_gap(5, Some(6), 9));
+        // Oldest retained is past what the client saw
+        assert!(replay_gap(5, Some(8), 9));
+        // Client is up to date
+        assert!(!replay_gap(9, None, 9));
+        // Buffer expired or trimmed away entirely
+        assert!(replay_gap(5, None, 9));
+    }
+}
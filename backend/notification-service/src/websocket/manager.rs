@@ -7,15 +7,30 @@
 /// - Heartbeat (ping/pong) mechanism
 /// - Graceful disconnection handling
 /// - Multiple concurrent connections per user
+/// - Cross-node delivery, presence and replay via `ClusterBus` (optional)
+/// - Bounded per-connection queues; slow consumers are evicted
+use super::cluster::ClusterBus;
 use super::WebSocketMessage;
 use crate::error::Result;
+use crate::metrics;
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::{mpsc, RwLock};
+use tracing::warn;
 use uuid::Uuid;
 
 /// Type alias for WebSocket message sender
-pub type WebSocketSender = mpsc::UnboundedSender<WebSocketMessage>;
+pub type WebSocketSender = mpsc::Sender<WebSocketMessage>;
+
+/// Outbound messages buffered per connection before it counts as a slow consumer
+pub const OUTBOUND_QUEUE_CAPACITY: usize = 256;
+
+/// A single socket's outbound queue
+struct Connection {
+    id: String,
+    sender: WebSocketSender,
+}
 
 /// Manages active WebSocket connections
 ///
@@ -23,9 +38,12 @@ pub type WebSocketSender = mpsc::UnboundedSender<WebSocketMessage>;
 /// Routes notifications to specific users and maintains connection lifecycle.
 #[derive(Clone)]
 pub struct ConnectionManager {
-    /// Map of user_id -> Vec of message senders
+    /// Map of user_id -> connections
     /// Each user can have multiple concurrent connections
-    connections: Arc<RwLock<HashMap<Uuid, Vec<WebSocketSender>>>>,
+    connections: Arc<RwLock<HashMap<Uuid, Vec<Connection>>>>,
+    /// Redis fan-out to other nodes; `None` keeps delivery in-process
+    cluster: Option<Arc<ClusterBus>>,
+    node_id: Arc<str>,
 }
 
 impl ConnectionManager {
@@ -33,9 +51,28 @@ impl ConnectionManager {
     pub fn new() -> Self {
         Self {
             connections: Arc::new(RwLock::new(HashMap::new())),
+            cluster: None,
+            node_id: Uuid::new_v4().to_string().into(),
         }
     }
 
+    /// Route deliveries for users on other nodes through the cluster bus
+    pub fn with_cluster(mut self, cluster: Arc<ClusterBus>) -> Self {
+        self.node_id = cluster.node_id().into();
+        self.cluster = Some(cluster);
+        self
+    }
+
+    /// The cluster bus, if enabled
+    pub fn cluster(&self) -> Option<Arc<ClusterBus>> {
+        self.cluster.clone()
+    }
+
+    /// ID of this node, reported to clients on connect
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
     /// Subscribe a user to notifications
     ///
     /// Adds a new WebSocket sender for the given user.
@@ -50,16 +87,26 @@ impl ConnectionManager {
     ///
     /// A connection ID that can be used for cleanup
     pub async fn subscribe(&self, user_id: Uuid, sender: WebSocketSender) -> Result<String> {
-        let mut connections = self.connections.write().await;
+        let connection_id = format!("{}-{}", user_id, Uuid::new_v4().simple());
+
+        {
+            let mut connections = self.connections.write().await;
 
-        // Add sender to user's connection list
-        connections
-            .entry(user_id)
-            .or_insert_with(Vec::new)
-            .push(sender);
+            // Add sender to user's connection list
+            connections.entry(user_id).or_default().push(Connection {
+                id: connection_id.clone(),
+                sender,
+            });
+        }
 
-        // Generate connection ID
-        let connection_id = format!("{}-{}", user_id, chrono::Utc::now().timestamp_millis());
+        if let Some(cluster) = &self.cluster {
+            if let Err(e) = cluster.register_presence(user_id).await {
+                warn!(
+                    "Failed to register WebSocket presence for {}: {}",
+                    user_id, e
+                );
+            }
+        }
 
         Ok(connection_id)
     }
@@ -70,15 +117,131 @@ impl ConnectionManager {
     ///
     /// * `user_id` - The user to unsubscribe
     pub async fn unsubscribe(&self, user_id: Uuid) -> Result<()> {
-        let mut connections = self.connections.write().await;
-        connections.remove(&user_id);
+        let removed = {
+            let mut connections = self.connections.write().await;
+            connections.remove(&user_id).is_some()
+        };
+        if removed {
+            self.release_presence(user_id).await;
+        }
+        Ok(())
+    }
+
+    /// Remove a single connection (socket closed or evicted)
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The connection's user
+    /// * `connection_id` - ID returned by `subscribe`
+    pub async fn unsubscribe_connection(&self, user_id: Uuid, connection_id: &str) -> Result<()> {
+        self.remove_connections(user_id, &[connection_id.to_string()])
+            .await;
         Ok(())
     }
 
+    async fn remove_connections(&self, user_id: Uuid, connection_ids: &[String]) {
+        let now_empty = {
+            let mut connections = self.connections.write().await;
+            match connections.get_mut(&user_id) {
+                Some(list) => {
+                    list.retain(|c| !connection_ids.contains(&c.id));
+                    if list.is_empty() {
+                        connections.remove(&user_id);
+                        true
+                    } else {
+                        false
+                    }
+                }
+                None => false,
+            }
+        };
+        if now_empty {
+            self.release_presence(user_id).await;
+        }
+    }
+
+    async fn release_presence(&self, user_id: Uuid) {
+        if let Some(cluster) = &self.cluster {
+            if let Err(e) = cluster.remove_presence(user_id).await {
+                warn!("Failed to remove WebSocket presence for {}: {}", user_id, e);
+            }
+        }
+    }
+
+    /// Queue a message on one connection; `false` if it must be dropped
+    fn offer(connection: &Connection, message: WebSocketMessage) -> bool {
+        match connection.sender.try_send(message) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                warn!(
+                    "Evicting slow WebSocket consumer {} ({} messages queued)",
+                    connection.id, OUTBOUND_QUEUE_CAPACITY
+                );
+                metrics::record_ws_connection_evicted("slow_consumer");
+                false
+            }
+            // Socket already gone
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+
+    /// Deliver to this node's connections for a user, evicting any whose
+    /// queue is full; returns the number of connections reached
+    pub async fn deliver_local(&self, user_id: Uuid, message: WebSocketMessage) -> usize {
+        let mut delivered = 0;
+        let mut dropped = Vec::new();
+        {
+            let connections = self.connections.read().await;
+            if let Some(list) = connections.get(&user_id) {
+                for connection in list {
+                    if Self::offer(connection, message.clone()) {
+                        delivered += 1;
+                    } else {
+                        dropped.push(connection.id.clone());
+                    }
+                }
+            }
+        }
+
+        if !dropped.is_empty() {
+            self.remove_connections(user_id, &dropped).await;
+        }
+        delivered
+    }
+
+    /// Deliver to every connection on this node, evicting slow consumers
+    pub async fn broadcast_local(&self, message: WebSocketMessage) -> usize {
+        let mut delivered = 0;
+        let mut dropped: HashMap<Uuid, Vec<String>> = HashMap::new();
+        {
+            let connections = self.connections.read().await;
+            for (user_id, list) in connections.iter() {
+                for connection in list {
+                    if Self::offer(connection, message.clone()) {
+                        delivered += 1;
+                    } else {
+                        dropped
+                            .entry(*user_id)
+                            .or_default()
+                            .push(connection.id.clone());
+                    }
+                }
+            }
+        }
+
+        for (user_id, ids) in dropped {
+            self.remove_connections(user_id, &ids).await;
+        }
+        delivered
+    }
+
     /// Send a notification to a specific user
     ///
-    /// Routes the notification to all active connections for the user.
-    /// Silently skips if user has no active connections.
+    /// Routes the notification to all active connections for the user, on
+    /// this node and (with a cluster bus) on any other node holding them.
+    /// Notification messages are sequenced and kept for replay first; other
+    /// messages are control frames for this node's sessions and are never
+    /// relayed. Silently skips if user has no active connections.
     ///
     /// # Arguments
     ///
@@ -89,14 +252,27 @@ impl ConnectionManager {
         user_id: Uuid,
         notification: WebSocketMessage,
     ) -> Result<()> {
-        let connections = self.connections.read().await;
+        let cluster = match &self.cluster {
+            Some(cluster) if matches!(notification, WebSocketMessage::Notification { .. }) => {
+                cluster
+            }
+            _ => {
+                self.deliver_local(user_id, notification).await;
+                return Ok(());
+            }
+        };
 
-        if let Some(senders) = connections.get(&user_id) {
-            // Send to all active connections for this user
-            for sender in senders {
-                // Ignore send errors (connection might be closed)
-                let _ = sender.send(notification.clone());
+        let message = match cluster.sequence(user_id, notification.clone()).await {
+            Ok(sequenced) => sequenced,
+            Err(e) => {
+                warn!("Failed to sequence notification for {}: {}", user_id, e);
+                notification
             }
+        };
+
+        self.deliver_local(user_id, message.clone()).await;
+        if let Err(e) = cluster.publish_to_user(user_id, &message).await {
+            warn!("Failed to relay WebSocket message for {}: {}", user_id, e);
         }
 
         Ok(())
@@ -108,32 +284,51 @@ impl ConnectionManager {
     ///
     /// * `message` - The message to broadcast
     pub async fn broadcast(&self, message: WebSocketMessage) -> Result<()> {
-        let connections = self.connections.read().await;
+        self.broadcast_local(message.clone()).await;
 
-        for senders in connections.values() {
-            for sender in senders {
-                // Ignore send errors (connection might be closed)
-                let _ = sender.send(message.clone());
+        if let Some(cluster) = &self.cluster {
+            if let Err(e) = cluster.publish_broadcast(&message).await {
+                warn!("Failed to relay WebSocket broadcast: {}", e);
             }
         }
 
         Ok(())
     }
 
-    /// Send a heartbeat (ping) to a specific user
+    /// Nodes currently holding connections for a user
+    ///
+    /// Uses the cluster presence registry when enabled, otherwise reports
+    /// this node if the user is connected here.
+    pub async fn presence(&self, user_id: Uuid) -> Vec<String> {
+        if let Some(cluster) = &self.cluster {
+            match cluster.nodes_for(user_id).await {
+                Ok(nodes) => return nodes,
+                Err(e) => warn!("Failed to read WebSocket presence for {}: {}", user_id, e),
+            }
+        }
+        if self.connection_count(user_id).await > 0 {
+            vec![self.node_id.to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Send a heartbeat (ping) to a user's connections on this node
     ///
     /// # Arguments
     ///
     /// * `user_id` - The user to ping
     pub async fn ping_user(&self, user_id: Uuid) -> Result<()> {
         let ping = WebSocketMessage::ping();
-        self.send_notification(user_id, ping).await
+        self.deliver_local(user_id, ping).await;
+        Ok(())
     }
 
-    /// Send a heartbeat (ping) to all connected users
+    /// Send a heartbeat (ping) to every connection on this node
     pub async fn ping_all(&self) -> Result<()> {
         let ping = WebSocketMessage::ping();
-        self.broadcast(ping).await
+        self.broadcast_local(ping).await;
+        Ok(())
     }
 
     /// Get the number of active connections for a user
@@ -162,7 +357,7 @@ impl ConnectionManager {
         connections.len()
     }
 
-    /// Send error message to a user
+    /// Send error message to a user's connections on this node
     ///
     /// # Arguments
     ///
@@ -171,7 +366,8 @@ impl ConnectionManager {
     /// * `message` - The error message
     pub async fn send_error(&self, user_id: Uuid, code: String, message: String) -> Result<()> {
         let error_msg = WebSocketMessage::error(code, message);
-        self.send_notification(user_id, error_msg).await
+        self.deliver_local(user_id, error_msg).await;
+        Ok(())
     }
 
     /// Broadcast error to all users
@@ -185,7 +381,7 @@ impl ConnectionManager {
         self.broadcast(error_msg).await
     }
 
-    /// Send acknowledgment to a user
+    /// Send acknowledgment to a user's connections on this node
     ///
     /// # Arguments
     ///
@@ -193,23 +389,30 @@ impl ConnectionManager {
     /// * `message_id` - The message ID being acknowledged
     pub async fn send_ack(&self, user_id: Uuid, message_id: Option<String>) -> Result<()> {
         let ack = WebSocketMessage::Ack { message_id };
-        self.send_notification(user_id, ack).await
+        self.deliver_local(user_id, ack).await;
+        Ok(())
     }
 
-    /// Send connection confirmation to a user
+    /// Send connection confirmation to a user's connections on this node
     ///
     /// # Arguments
     ///
     /// * `user_id` - The user to send confirmation to
     pub async fn send_connected(&self, user_id: Uuid) -> Result<()> {
         let connected = WebSocketMessage::connected();
-        self.send_notification(user_id, connected).await
+        self.deliver_local(user_id, connected).await;
+        Ok(())
     }
 
     /// Clear all connections (useful for testing or graceful shutdown)
     pub async fn clear_all(&self) -> Result<()> {
-        let mut connections = self.connections.write().await;
-        connections.clear();
+        let user_ids: Vec<Uuid> = {
+            let mut connections = self.connections.write().await;
+            connections.drain().map(|(user_id, _)| user_id).collect()
+        };
+        for user_id in user_ids {
+            self.release_presence(user_id).await;
+        }
         Ok(())
     }
 
@@ -242,7 +445,7 @@ mod tests {
     async fn test_subscribe_user() {
         let manager = ConnectionManager::new();
         let user_id = Uuid::new_v4();
-        let (tx, _rx) = mpsc::unbounded_channel();
+        let (tx, _rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
 
         let result = manager.subscribe(user_id, tx).await;
         assert!(result.is_ok());
@@ -256,7 +459,7 @@ mod tests {
         let user_id = Uuid::new_v4();
 
         for _ in 0..3 {
-            let (tx, _rx) = mpsc::unbounded_channel();
+            let (tx, _rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
             manager.subscribe(user_id, tx).await.unwrap();
         }
 
@@ -271,7 +474,7 @@ mod tests {
 
         for _ in 0..5 {
             let user_id = Uuid::new_v4();
-            let (tx, _rx) = mpsc::unbounded_channel();
+            let (tx, _rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
             manager.subscribe(user_id, tx).await.unwrap();
         }
 
@@ -283,7 +486,7 @@ mod tests {
     async fn test_send_notification() {
         let manager = ConnectionManager::new();
         let user_id = Uuid::new_v4();
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
 
         manager.subscribe(user_id, tx).await.unwrap();
 
@@ -332,7 +535,7 @@ mod tests {
     async fn test_unsubscribe_user() {
         let manager = ConnectionManager::new();
         let user_id = Uuid::new_v4();
-        let (tx, _rx) = mpsc::unbounded_channel();
+        let (tx, _rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
 
         manager.subscribe(user_id, tx).await.unwrap();
         assert_eq!(manager.connection_count(user_id).await, 1);
@@ -350,7 +553,7 @@ mod tests {
         // Subscribe 3 users
         for _ in 0..3 {
             let user_id = Uuid::new_v4();
-            let (tx, rx) = mpsc::unbounded_channel();
+            let (tx, rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
             manager.subscribe(user_id, tx).await.unwrap();
             receivers.push(rx);
         }
@@ -370,7 +573,7 @@ mod tests {
     async fn test_ping_user() {
         let manager = ConnectionManager::new();
         let user_id = Uuid::new_v4();
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
 
         manager.subscribe(user_id, tx).await.unwrap();
         manager.ping_user(user_id).await.unwrap();
@@ -388,7 +591,7 @@ mod tests {
         // Subscribe 2 users
         for _ in 0..2 {
             let user_id = Uuid::new_v4();
-            let (tx, rx) = mpsc::unbounded_channel();
+            let (tx, rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
             manager.subscribe(user_id, tx).await.unwrap();
             receivers.push(rx);
         }
@@ -407,7 +610,7 @@ mod tests {
     async fn test_send_error() {
         let manager = ConnectionManager::new();
         let user_id = Uuid::new_v4();
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
 
         manager.subscribe(user_id, tx).await.unwrap();
         manager
@@ -428,7 +631,7 @@ mod tests {
     async fn test_send_ack() {
         let manager = ConnectionManager::new();
         let user_id = Uuid::new_v4();
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
 
         manager.subscribe(user_id, tx).await.unwrap();
         manager
@@ -445,7 +648,7 @@ mod tests {
     async fn test_send_connected() {
         let manager = ConnectionManager::new();
         let user_id = Uuid::new_v4();
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
 
         manager.subscribe(user_id, tx).await.unwrap();
         manager.send_connected(user_id).await.unwrap();
@@ -465,7 +668,7 @@ mod tests {
         // Subscribe multiple users
         for _ in 0..3 {
             let user_id = Uuid::new_v4();
-            let (tx, _rx) = mpsc::unbounded_channel();
+            let (tx, _rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
             manager.subscribe(user_id, tx).await.unwrap();
         }
 
@@ -483,7 +686,7 @@ mod tests {
         let user_ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
 
         for user_id in &user_ids {
-            let (tx, _rx) = mpsc::unbounded_channel();
+            let (tx, _rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
             manager.subscribe(*user_id, tx).await.unwrap();
         }
 
@@ -495,6 +698,78 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_unsubscribe_single_connection() {
+        let manager = ConnectionManager::new();
+        let user_id = Uuid::new_v4();
+        let (tx1, _rx1) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
+        let (tx2, mut rx2) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
+
+        let first = manager.subscribe(user_id, tx1).await.unwrap();
+        let second = manager.subscribe(user_id, tx2).await.unwrap();
+        assert_ne!(first, second);
+
+        manager
+            .unsubscribe_connection(user_id, &first)
+            .await
+            .unwrap();
+        assert_eq!(manager.connection_count(user_id).await, 1);
+
+        manager.ping_user(user_id).await.unwrap();
+        assert!(matches!(
+            rx2.recv().await.unwrap(),
+            WebSocketMessage::Ping { .. }
+        ));
+
+        manager
+            .unsubscribe_connection(user_id, &second)
+            .await
+            .unwrap();
+        assert_eq!(manager.connected_users_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_evicted() {
+        let manager = ConnectionManager::new();
+        let user_id = Uuid::new_v4();
+        let (slow_tx, mut slow_rx) = mpsc::channel(2);
+        let (fast_tx, mut fast_rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
+
+        manager.subscribe(user_id, slow_tx).await.unwrap();
+        manager.subscribe(user_id, fast_tx).await.unwrap();
+
+        for _ in 0..3 {
+            manager.ping_user(user_id).await.unwrap();
+        }
+
+        // The slow socket overflowed and was dropped; the other keeps receiving
+        assert_eq!(manager.connection_count(user_id).await, 1);
+        assert!(slow_rx.recv().await.is_some());
+        assert!(slow_rx.recv().await.is_some());
+        assert!(slow_rx.recv().await.is_none());
+        for _ in 0..3 {
+            assert!(fast_rx.recv().await.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_closed_connection_pruned() {
+        let manager = ConnectionManager::new();
+        let user_id = Uuid::new_v4();
+        let (tx, rx) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
+
+        manager.subscribe(user_id, tx).await.unwrap();
+        drop(rx);
+
+        assert_eq!(
+            manager
+                .deliver_local(user_id, WebSocketMessage::ping())
+                .await,
+            0
+        );
+        assert_eq!(manager.connected_users_count().await, 0);
+    }
+
     #[tokio::test]
     async fn test_default_constructor() {
         let manager = ConnectionManager::default();
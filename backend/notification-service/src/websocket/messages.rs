@@ -1,4 +1,5 @@
 /// WebSocket message types for real-time notifications
+use crate::models::Notification;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -23,6 +24,10 @@ pub enum WebSocketMessage {
         image_url: Option<String>,
         priority: String,
         timestamp: i64,
+        /// Per-user sequence number, set when the cluster bus is enabled;
+        /// clients reconnect with the last one they saw to replay gaps
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
 
     /// Server acknowledges message receipt
@@ -71,9 +76,41 @@ impl WebSocketMessage {
             image_url,
             priority,
             timestamp: chrono::Utc::now().timestamp(),
+            seq: None,
         }
     }
 
+    /// Create a notification message from a stored notification
+    pub fn from_notification(notification: &Notification) -> Self {
+        WebSocketMessage::Notification {
+            id: notification.id,
+            recipient_id: notification.recipient_id,
+            notification_type: notification.notification_type.as_str().to_string(),
+            title: notification.title.clone(),
+            body: notification.body.clone(),
+            image_url: notification.image_url.clone(),
+            priority: notification.priority.as_str().to_string(),
+            timestamp: notification.created_at.timestamp(),
+            seq: None,
+        }
+    }
+
+    /// Sequence number of a notification message
+    pub fn seq(&self) -> Option<u64> {
+        match self {
+            WebSocketMessage::Notification { seq, .. } => *seq,
+            _ => None,
+        }
+    }
+
+    /// Copy of a notification message carrying `seq`; other messages are unchanged
+    pub fn with_seq(mut self, value: u64) -> Self {
+        if let WebSocketMessage::Notification { seq, .. } = &mut self {
+            *seq = Some(value);
+        }
+        self
+    }
+
     /// Create a ping message
     pub fn ping() -> Self {
         WebSocketMessage::Ping {
@@ -93,8 +130,13 @@ impl WebSocketMessage {
 
     /// Create a connected message
     pub fn connected() -> Self {
+        Self::connected_to(Uuid::new_v4().to_string())
+    }
+
+    /// Create a connected message naming the serving node
+    pub fn connected_to(server_id: String) -> Self {
         WebSocketMessage::Connected {
-            server_id: Uuid::new_v4().to_string(),
+            server_id,
             timestamp: chrono::Utc::now().timestamp(),
         }
     }
@@ -143,6 +185,28 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_notification_seq_round_trip() {
+        let msg = WebSocketMessage::notification(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "like".to_string(),
+            "New Like".to_string(),
+            "Someone liked your post".to_string(),
+            None,
+            "normal".to_string(),
+        );
+        assert_eq!(msg.seq(), None);
+        assert!(!msg.to_json().unwrap().contains("seq"));
+
+        let sequenced = msg.with_seq(42);
+        let json = sequenced.to_json().unwrap();
+        assert_eq!(WebSocketMessage::from_json(&json).unwrap().seq(), Some(42));
+
+        // Non-notification messages never carry a sequence
+        assert_eq!(WebSocketMessage::ping().with_seq(7).seq(), None);
+    }
+
     #[test]
     fn test_ping_pong_messages() {
         let ping = WebSocketMessage::ping();
@@ -7,8 +7,13 @@
 /// 2. Message broadcast: Sends notifications to connected clients
 /// 3. User-specific channels: Route notifications to specific users
 /// 4. Graceful disconnection: Handle client disconnects
+/// 5. ClusterBus: Redis presence, cross-node relay and replay across pods
+/// 6. NotificationSession: Per-socket actor behind `/ws/{user_id}`
+pub mod cluster;
 pub mod manager;
 pub mod messages;
+pub mod session;
 
+pub use cluster::{start_cluster_fanout, ClusterBus, ClusterConfig};
 pub use manager::ConnectionManager;
 pub use messages::WebSocketMessage;
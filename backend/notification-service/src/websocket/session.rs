@@ -0,0 +1,162 @@
+/// WebSocket session actor
+///
+/// One actor per socket. Outbound messages arrive through the bounded queue
+/// registered with `ConnectionManager`; when the manager evicts the
+/// connection the queue closes and the socket is closed with 1013 (try
+/// again later) so the client reconnects with its last sequence number.
+use super::{ConnectionManager, WebSocketMessage};
+use actix::{Actor, ActorContext, AsyncContext, StreamHandler};
+use actix_web_actors::ws;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// How often the server pings the client
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Close the socket when nothing is heard from the client for this long
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(45);
+
+pub struct NotificationSession {
+    user_id: Uuid,
+    connection_id: String,
+    manager: ConnectionManager,
+    last_heartbeat: Instant,
+    /// Highest notification sequence written; later duplicates are skipped
+    last_seq: u64,
+    /// Written before live traffic: connect confirmation and replay
+    initial: Vec<WebSocketMessage>,
+    outbound: Option<mpsc::Receiver<WebSocketMessage>>,
+}
+
+impl NotificationSession {
+    pub fn new(
+        user_id: Uuid,
+        connection_id: String,
+        manager: ConnectionManager,
+        last_seq: u64,
+        initial: Vec<WebSocketMessage>,
+        outbound: mpsc::Receiver<WebSocketMessage>,
+    ) -> Self {
+        Self {
+            user_id,
+            connection_id,
+            manager,
+            last_heartbeat: Instant::now(),
+            last_seq,
+            initial,
+            outbound: Some(outbound),
+        }
+    }
+
+    fn write(&mut self, message: WebSocketMessage, ctx: &mut ws::WebsocketContext<Self>) {
+        if let Some(seq) = message.seq() {
+            // Already sent during replay
+            if seq <= self.last_seq {
+                return;
+            }
+            self.last_seq = seq;
+        }
+        match message.to_json() {
+            Ok(json) => ctx.text(json),
+            Err(e) => warn!("Failed to encode WebSocket message: {}", e),
+        }
+    }
+
+    fn start_heartbeat(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        ctx.run_interval(HEARTBEAT_INTERVAL, |session, ctx| {
+            if Instant::now().duration_since(session.last_heartbeat) > CLIENT_TIMEOUT {
+                debug!(
+                    "WebSocket heartbeat timed out for {}",
+                    session.connection_id
+                );
+                ctx.stop();
+                return;
+            }
+            ctx.ping(b"");
+        });
+    }
+}
+
+impl Actor for NotificationSession {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.start_heartbeat(ctx);
+
+        for message in std::mem::take(&mut self.initial) {
+            self.write(message, ctx);
+        }
+
+        if let Some(receiver) = self.outbound.take() {
+            let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+                receiver.recv().await.map(|message| (message, receiver))
+            });
+            ctx.add_stream(stream);
+        }
+    }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        let manager = self.manager.clone();
+        let user_id = self.user_id;
+        let connection_id = std::mem::take(&mut self.connection_id);
+        actix::spawn(async move {
+            let _ = manager
+                .unsubscribe_connection(user_id, &connection_id)
+                .await;
+        });
+    }
+}
+
+/// Outbound messages from the connection manager
+impl StreamHandler<WebSocketMessage> for NotificationSession {
+    fn handle(&mut self, message: WebSocketMessage, ctx: &mut Self::Context) {
+        self.write(message, ctx);
+    }
+
+    fn finished(&mut self, ctx: &mut Self::Context) {
+        // Queue closed: evicted as a slow consumer or unsubscribed
+        ctx.close(Some(ws::CloseReason {
+            code: ws::CloseCode::Again,
+            description: Some("reconnect with last_seq".to_string()),
+        }));
+        ctx.stop();
+    }
+}
+
+/// Frames from the client
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for NotificationSession {
+    fn handle(&mut self, frame: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                debug!("WebSocket protocol error for {}: {}", self.connection_id, e);
+                ctx.stop();
+                return;
+            }
+        };
+
+        match frame {
+            ws::Message::Ping(bytes) => {
+                self.last_heartbeat = Instant::now();
+                ctx.pong(&bytes);
+            }
+            ws::Message::Pong(_) => {
+                self.last_heartbeat = Instant::now();
+            }
+            ws::Message::Text(text) => {
+                self.last_heartbeat = Instant::now();
+                if let Ok(WebSocketMessage::Ping { timestamp }) = WebSocketMessage::from_json(&text)
+                {
+                    self.write(WebSocketMessage::pong(timestamp), ctx);
+                }
+            }
+            ws::Message::Close(reason) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            ws::Message::Binary(_) | ws::Message::Continuation(_) | ws::Message::Nop => {}
+        }
+    }
+}
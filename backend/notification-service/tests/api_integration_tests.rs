@@ -222,9 +222,11 @@ fn test_notification_preference_serialization() {
         stream_enabled: false,
         quiet_hours_start: Some("22:00".to_string()),
         quiet_hours_end: Some("08:00".to_string()),
+        timezone: None,
         prefer_fcm: true,
         prefer_apns: true,
         prefer_email: false,
+        digest_frequency: DigestFrequency::Off,
         updated_at: chrono::Utc::now(),
     };
 
@@ -117,9 +117,11 @@ fn test_notification_preference_creation() {
         stream_enabled: false,
         quiet_hours_start: Some("22:00".to_string()),
         quiet_hours_end: Some("08:00".to_string()),
+        timezone: None,
         prefer_fcm: true,
         prefer_apns: true,
         prefer_email: false,
+        digest_frequency: DigestFrequency::Off,
         updated_at: Utc::now(),
     };
 
@@ -307,9 +309,11 @@ fn test_notification_preference_quiet_hours_validation() {
         stream_enabled: true,
         quiet_hours_start: Some("22:00".to_string()),
         quiet_hours_end: Some("08:00".to_string()),
+        timezone: None,
         prefer_fcm: true,
         prefer_apns: true,
         prefer_email: false,
+        digest_frequency: DigestFrequency::Off,
         updated_at: Utc::now(),
     };
 
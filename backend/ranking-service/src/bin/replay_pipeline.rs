@@ -0,0 +1,18 @@
+//! Offline pipeline replay
+//!
+//! Replays logged feed requests through a baseline and a candidate
+//! ranking configuration and prints recall@k, NDCG, coverage, author
+//! diversity and IPS estimates with their deltas. See
+//! `jobs::replay_evaluation` for configuration.
+
+use ranking_service::jobs::run_replay_evaluation_job;
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(EnvFilter::from_default_env())
+        .init();
+
+    run_replay_evaluation_job()
+}
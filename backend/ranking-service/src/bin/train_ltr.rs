@@ -0,0 +1,25 @@
+//! LTR training job
+//!
+//! Trains a ranking model from ClickHouse impression logs and writes a
+//! versioned artifact for `RankingModel::load`. Intended to run as a
+//! Kubernetes CronJob; see `jobs::ltr_training` for configuration.
+
+use anyhow::anyhow;
+use ranking_service::jobs::run_ltr_training_job;
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // rustls 0.23 requires selecting a CryptoProvider at runtime
+    if let Err(err) = rustls::crypto::aws_lc_rs::default_provider().install_default() {
+        eprintln!("Failed to install rustls crypto provider: {:?}", err);
+        return Err(anyhow!("Unable to install TLS crypto provider: {:?}", err).into());
+    }
+
+    tracing_subscriber::registry()
+        .with(fmt::layer())
+        .with(EnvFilter::from_default_env())
+        .init();
+
+    run_ltr_training_job().await
+}
@@ -48,6 +48,8 @@ pub struct RedisConfig {
 pub struct GrpcClientsConfig {
     pub graph_service_url: String,
     pub content_service_url: String,
+    /// Enables model-based fine ranking when set (with `RANKING_MODEL_PATH`)
+    pub feature_store_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -89,6 +91,7 @@ impl Config {
                     .unwrap_or_else(|_| "http://localhost:9008".to_string()),
                 content_service_url: env::var("CONTENT_SERVICE_URL")
                     .unwrap_or_else(|_| "http://localhost:9002".to_string()),
+                feature_store_url: env::var("FEATURE_STORE_URL").ok(),
             },
             recall: RecallConfig {
                 graph_recall_limit: env::var("GRAPH_RECALL_LIMIT")
@@ -1,12 +1,17 @@
 use crate::models::{RankedPost, RecallStats};
 use crate::services::coarse_ranking::{CoarseCandidate, CoarseRankingLayer, UserFeatures};
-use crate::services::exploration::{NewContentPool, UCBExplorer};
+use crate::services::exploration::{
+    interleave_exploration, ExplorationContext, ExplorationPolicy, NewContentPool, UCBExplorer,
+};
 use crate::services::profile_builder::{
     ClickHouseProfileDatabase, LlmProfileAnalyzer, ProfileDatabase, ProfileUpdater,
     ProfileUpdaterConfig,
 };
+use crate::services::ranking::ValueContext;
 use crate::services::realtime::SessionInterestManager;
+use crate::services::trace::{DiversityTrace, PostTrace, RankingTrace, TraceBuilder, TraceStore};
 use crate::services::{DiversityLayer, FeatureClient, RankingLayer, RecallLayer};
+use chrono::Timelike;
 use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -20,9 +25,13 @@ pub mod ranking_proto {
 }
 
 use ranking_proto::{
-    ranking_service_server::RankingService, Candidate, PostFeatures, RankFeedRequest,
-    RankFeedResponse, RankedPost as ProtoRankedPost, RecallRequest, RecallResponse,
-    RecallStats as ProtoRecallStats,
+    ranking_service_server::RankingService, AddExplorationContentRequest,
+    AddExplorationContentResponse, Candidate, DiversityTrace as ProtoDiversityTrace,
+    GetRankingTraceRequest, GetRankingTraceResponse, PostFeatures, PostTrace as ProtoPostTrace,
+    RankFeedRequest, RankFeedResponse, RankedPost as ProtoRankedPost,
+    RankingTrace as ProtoRankingTrace, RecallRequest, RecallResponse,
+    RecallStats as ProtoRecallStats, RecordExplorationFeedbackRequest,
+    RecordExplorationFeedbackResponse,
 };
 
 // Profile-related proto types
@@ -84,6 +93,8 @@ pub struct RankingServiceImpl {
     // 用戶畫像服務
     profile_updater: Arc<RwLock<Option<ProfileUpdater<ClickHouseProfileDatabase>>>>,
     llm_analyzer: Arc<RwLock<Option<LlmProfileAnalyzer>>>,
+    // 排序解釋存儲（未設置時不記錄）
+    trace_store: Option<Arc<TraceStore>>,
     // 配置
     config: PipelineConfig,
 }
@@ -105,11 +116,15 @@ impl RankingServiceImpl {
             ranking_layer: Arc::new(ranking_layer),
             diversity_layer: Arc::new(diversity_layer),
             feature_client: Arc::new(FeatureClient::new(redis_client.clone())),
-            exploration_pool: Arc::new(NewContentPool::new(redis_client.clone())),
+            exploration_pool: Arc::new(
+                NewContentPool::new(redis_client.clone())
+                    .with_policy(ExplorationPolicy::from_env()),
+            ),
             ucb_explorer: Arc::new(UCBExplorer::new()),
             session_interests: Arc::new(SessionInterestManager::new(redis_client)),
             profile_updater: Arc::new(RwLock::new(None)),
             llm_analyzer: Arc::new(RwLock::new(None)),
+            trace_store: None,
             config: PipelineConfig::default(),
         }
     }
@@ -136,10 +151,17 @@ impl RankingServiceImpl {
             session_interests: Arc::new(session_interests),
             profile_updater: Arc::new(RwLock::new(None)),
             llm_analyzer: Arc::new(RwLock::new(None)),
+            trace_store: None,
             config,
         }
     }
 
+    /// 啟用排序解釋追蹤
+    pub fn with_trace_store(mut self, trace_store: Arc<TraceStore>) -> Self {
+        self.trace_store = Some(trace_store);
+        self
+    }
+
     /// 設置用戶畫像服務
     pub async fn set_profile_updater(&self, updater: ProfileUpdater<ClickHouseProfileDatabase>) {
         let mut guard = self.profile_updater.write().await;
@@ -194,9 +216,16 @@ impl RankingServiceImpl {
         }
     }
 
+    /// 探索池（供後台任務持久化策略狀態）
+    pub fn exploration_pool(&self) -> Arc<NewContentPool> {
+        self.exploration_pool.clone()
+    }
+
     /// 注入探索内容 (新内容发现)
     async fn inject_exploration_content(
         &self,
+        user_id: &str,
+        user_features: &UserFeatures,
         ranked_posts: Vec<RankedPost>,
         exploration_count: usize,
     ) -> Vec<RankedPost> {
@@ -204,8 +233,17 @@ impl RankingServiceImpl {
             return ranked_posts;
         }
 
-        // 从探索池采样新内容
-        let exploration_ids = match self.exploration_pool.sample_by_ucb(exploration_count).await {
+        // 从探索池采样新内容（LinUCB 使用用户上下文；匿名请求无上下文）
+        let context = exploration_context(user_features, chrono::Utc::now().hour());
+        let sampled = match Uuid::parse_str(user_id) {
+            Ok(uid) => {
+                self.exploration_pool
+                    .sample_for_user(uid, exploration_count, &context)
+                    .await
+            }
+            Err(_) => self.exploration_pool.sample_by_ucb(exploration_count).await,
+        };
+        let exploration_ids = match sampled {
             Ok(ids) => ids,
             Err(e) => {
                 warn!("Failed to sample exploration content: {}", e);
@@ -218,30 +256,7 @@ impl RankingServiceImpl {
         }
 
         // 将探索内容插入到结果中 (每隔 N 个位置插入一个)
-        let mut result = ranked_posts;
-        let interval = result.len() / (exploration_count + 1).max(1);
-
-        for (i, content_id) in exploration_ids.iter().enumerate() {
-            let position = ((i + 1) * interval).min(result.len());
-            result.insert(
-                position,
-                RankedPost {
-                    post_id: content_id.to_string(),
-                    score: 0.5, // 探索内容使用中等分数
-                    recall_source: crate::models::RecallSource::Personalized,
-                    features: crate::models::PostFeatures {
-                        engagement_score: 0.5,
-                        recency_score: 1.0, // 新内容
-                        author_quality_score: 0.5,
-                        content_quality_score: 0.5,
-                        completion_rate_score: 0.5,
-                        author_id: None,
-                    },
-                },
-            );
-        }
-
-        result
+        interleave_exploration(ranked_posts, &exploration_ids)
     }
 
     /// 应用会话级个性化提升
@@ -282,6 +297,11 @@ impl RankingService for RankingServiceImpl {
         let start_time = std::time::Instant::now();
         let req = request.into_inner();
         let user_id = req.user_id.clone();
+        let request_id = if req.request_id.is_empty() {
+            Uuid::new_v4().to_string()
+        } else {
+            req.request_id.clone()
+        };
         let limit = req.limit.max(1).min(100) as usize;
         let session_id = if req.session_id.is_empty() {
             None
@@ -296,19 +316,33 @@ impl RankingService for RankingServiceImpl {
         };
 
         info!(
-            "RankFeed 4-layer pipeline: user_id={}, limit={}, session={:?}",
-            user_id, limit, session_id
+            "RankFeed 4-layer pipeline: request_id={}, user_id={}, limit={}, session={:?}",
+            request_id, user_id, limit, session_id
         );
 
+        let value_context = ValueContext::new(&req.surface, &req.value_model_variant);
+        let mut trace = self.trace_store.as_ref().map(|_| {
+            TraceBuilder::new(
+                &request_id,
+                &user_id,
+                &value_context,
+                self.exploration_pool.policy_name(),
+            )
+        });
+
         // ============================================
         // Layer 1: Recall (召回層) - 萬級候選
         // ============================================
-        let (candidates, recall_stats) = self
+        let (candidates, recall_stats, extra_sources) = self
             .recall_layer
-            .recall_candidates(&user_id, Some(self.config.recall_limit))
+            .recall_with_sources(&user_id, Some(self.config.recall_limit))
             .await
             .map_err(|e| Status::internal(format!("Recall failed: {}", e)))?;
 
+        if let Some(trace) = trace.as_mut() {
+            trace.record_recall(&candidates, &extra_sources);
+        }
+
         let recall_count = candidates.len();
         info!("Layer 1 (Recall): {} candidates", recall_count);
 
@@ -326,6 +360,7 @@ impl RankingService for RankingServiceImpl {
                     fine_rank_latency_ms: 0.0,
                     total_latency_ms: start_time.elapsed().as_secs_f32() * 1000.0,
                 }),
+                request_id,
             }));
         }
 
@@ -344,9 +379,13 @@ impl RankingService for RankingServiceImpl {
 
         let coarse_ranked = self
             .coarse_ranking_layer
-            .rank(coarse_candidates, &user_features)
+            .rank_scored(coarse_candidates, &user_features)
             .map_err(|e| Status::internal(format!("Coarse ranking failed: {}", e)))?;
 
+        if let Some(trace) = trace.as_mut() {
+            trace.record_coarse(&coarse_ranked);
+        }
+
         let coarse_rank_count = coarse_ranked.len();
         let coarse_latency = coarse_start.elapsed().as_secs_f32() * 1000.0;
         info!(
@@ -355,8 +394,10 @@ impl RankingService for RankingServiceImpl {
         );
 
         // 將 CoarseCandidate 內的 Candidate 取出供精排使用
-        let filtered_candidates: Vec<crate::models::Candidate> =
-            coarse_ranked.into_iter().map(|cc| cc.candidate).collect();
+        let filtered_candidates: Vec<crate::models::Candidate> = coarse_ranked
+            .into_iter()
+            .map(|(cc, _)| cc.candidate)
+            .collect();
 
         // ============================================
         // Layer 3: Fine Ranking (精排層) - 百級候選
@@ -364,7 +405,7 @@ impl RankingService for RankingServiceImpl {
         let fine_start = std::time::Instant::now();
         let ranked_posts = self
             .ranking_layer
-            .rank_candidates(filtered_candidates)
+            .rank_for_user(&user_id, filtered_candidates, &value_context)
             .await
             .map_err(|e| Status::internal(format!("Fine ranking failed: {}", e)))?;
 
@@ -374,6 +415,10 @@ impl RankingService for RankingServiceImpl {
             .take(self.config.fine_limit)
             .collect();
 
+        if let Some(trace) = trace.as_mut() {
+            trace.record_fine(&fine_ranked, &self.ranking_layer);
+        }
+
         let fine_rank_count = fine_ranked.len();
         let fine_latency = fine_start.elapsed().as_secs_f32() * 1000.0;
         info!(
@@ -391,10 +436,13 @@ impl RankingService for RankingServiceImpl {
         };
 
         let with_exploration = self
-            .inject_exploration_content(fine_ranked, exploration_count)
+            .inject_exploration_content(&user_id, &user_features, fine_ranked, exploration_count)
             .await;
 
         let actual_exploration = with_exploration.len() - fine_rank_count;
+        if let Some(trace) = trace.as_mut() {
+            trace.record_exploration(&with_exploration);
+        }
         info!(
             "Exploration: injected {} new content items",
             actual_exploration
@@ -412,8 +460,18 @@ impl RankingService for RankingServiceImpl {
         // ============================================
         // Layer 4: Diversity Re-ranking (多樣性重排)
         // ============================================
-        let final_posts = self.diversity_layer.rerank(personalized, limit);
-        let final_count = final_posts.len();
+        let reranked = self.diversity_layer.rerank_explained(personalized, limit);
+        let final_count = reranked.len();
+
+        if let (Some(trace), Some(store)) = (trace, self.trace_store.as_ref()) {
+            let trace = trace.finish(&reranked);
+            let store = Arc::clone(store);
+            tokio::spawn(async move {
+                if let Err(e) = store.save(&trace).await {
+                    warn!(request_id = %trace.request_id, "Failed to save ranking trace: {}", e);
+                }
+            });
+        }
 
         let total_latency = start_time.elapsed().as_secs_f32() * 1000.0;
         info!(
@@ -423,8 +481,10 @@ impl RankingService for RankingServiceImpl {
             total_latency
         );
 
-        let proto_posts: Vec<ProtoRankedPost> =
-            final_posts.into_iter().map(to_proto_ranked_post).collect();
+        let proto_posts: Vec<ProtoRankedPost> = reranked
+            .into_iter()
+            .map(|(post, _)| to_proto_ranked_post(post))
+            .collect();
 
         Ok(Response::new(RankFeedResponse {
             posts: proto_posts,
@@ -439,6 +499,7 @@ impl RankingService for RankingServiceImpl {
                 fine_rank_latency_ms: fine_latency,
                 total_latency_ms: total_latency,
             }),
+            request_id,
         }))
     }
 
@@ -472,6 +533,99 @@ impl RankingService for RankingServiceImpl {
         }))
     }
 
+    async fn get_ranking_trace(
+        &self,
+        request: Request<GetRankingTraceRequest>,
+    ) -> Result<Response<GetRankingTraceResponse>, Status> {
+        let req = request.into_inner();
+        if req.request_id.is_empty() {
+            return Err(Status::invalid_argument("request_id is required"));
+        }
+
+        let store = self
+            .trace_store
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("Ranking traces are not enabled"))?;
+
+        let trace = store
+            .get(&req.request_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to load ranking trace: {}", e)))?;
+
+        let response = match trace {
+            Some(mut trace) => {
+                if !req.post_id.is_empty() {
+                    trace.posts.retain(|p| p.post_id == req.post_id);
+                }
+                GetRankingTraceResponse {
+                    found: true,
+                    trace: Some(to_proto_ranking_trace(trace)),
+                }
+            }
+            None => GetRankingTraceResponse {
+                found: false,
+                trace: None,
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn add_exploration_content(
+        &self,
+        request: Request<AddExplorationContentRequest>,
+    ) -> Result<Response<AddExplorationContentResponse>, Status> {
+        let req = request.into_inner();
+        let post_id = Uuid::parse_str(&req.post_id)
+            .map_err(|_| Status::invalid_argument("Invalid post_id"))?;
+        let author_id = Uuid::parse_str(&req.author_id)
+            .map_err(|_| Status::invalid_argument("Invalid author_id"))?;
+
+        // 未提供特徵時從特徵服務補全（LinUCB 內容特徵）
+        let features = if req.features.is_empty() {
+            let post = req.post_id.as_str();
+            vec![
+                self.feature_client.get_content_quality(post).await,
+                self.feature_client.get_author_quality(&req.author_id).await,
+                self.feature_client.get_content_completion_rate(post).await,
+            ]
+        } else {
+            req.features
+        };
+
+        self.exploration_pool
+            .add_content_with_features(post_id, author_id, features)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to add exploration content: {}", e)))?;
+
+        Ok(Response::new(AddExplorationContentResponse {
+            success: true,
+        }))
+    }
+
+    async fn record_exploration_feedback(
+        &self,
+        request: Request<RecordExplorationFeedbackRequest>,
+    ) -> Result<Response<RecordExplorationFeedbackResponse>, Status> {
+        let req = request.into_inner();
+        let user_id = Uuid::parse_str(&req.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user_id"))?;
+        let post_id = Uuid::parse_str(&req.post_id)
+            .map_err(|_| Status::invalid_argument("Invalid post_id"))?;
+
+        let recorded = self
+            .exploration_pool
+            .record_user_feedback(user_id, post_id, req.engaged)
+            .await
+            .map_err(|e| {
+                Status::internal(format!("Failed to record exploration feedback: {}", e))
+            })?;
+
+        Ok(Response::new(RecordExplorationFeedbackResponse {
+            recorded,
+        }))
+    }
+
     // ============================================
     // User Profile APIs (用戶畫像 API)
     // ============================================
@@ -951,11 +1105,27 @@ impl RankingService for RankingServiceImpl {
 }
 
 // Helper: 轉換為 Proto 格式
+/// LinUCB user context (each feature roughly in [0, 1]):
+/// followed authors (log-scaled), interest breadth, session length, and
+/// whether the user is usually active at this hour
+fn exploration_context(user: &UserFeatures, hour: u32) -> ExplorationContext {
+    let follows = ((1.0 + user.followed_authors.len() as f32).ln() / 1001f32.ln()).min(1.0);
+    let interests = (user.interest_tags.len() as f32 / 20.0).min(1.0);
+    let session = (user.avg_session_length.max(0) as f32 / 1800.0).min(1.0);
+    let active_now = if user.active_hours.contains(&(hour as u8)) {
+        1.0
+    } else {
+        0.0
+    };
+    ExplorationContext::new(vec![follows, interests, session, active_now])
+}
+
 fn to_proto_ranked_post(post: RankedPost) -> ProtoRankedPost {
     ProtoRankedPost {
         post_id: post.post_id,
         score: post.score,
         recall_source: post.recall_source.as_str().to_string(),
+        objective_scores: post.objective_scores.into_iter().collect(),
         features: Some(PostFeatures {
             engagement_score: post.features.engagement_score,
             recency_score: post.features.recency_score,
@@ -965,6 +1135,41 @@ fn to_proto_ranked_post(post: RankedPost) -> ProtoRankedPost {
     }
 }
 
+fn to_proto_ranking_trace(trace: RankingTrace) -> ProtoRankingTrace {
+    ProtoRankingTrace {
+        request_id: trace.request_id,
+        user_id: trace.user_id,
+        created_at_ms: trace.created_at,
+        surface: trace.surface.unwrap_or_default(),
+        value_model_variant: trace.value_model_variant.unwrap_or_default(),
+        exploration_policy: trace.exploration_policy,
+        posts: trace.posts.into_iter().map(to_proto_post_trace).collect(),
+    }
+}
+
+fn to_proto_post_trace(post: PostTrace) -> ProtoPostTrace {
+    ProtoPostTrace {
+        post_id: post.post_id,
+        final_position: post.final_position as i32,
+        final_score: post.final_score,
+        recall_sources: post.recall_sources,
+        recall_weight: post.recall_weight,
+        coarse_score: post.coarse_score,
+        fine_position: post.fine_position.map(|p| p as i32),
+        fine_score: post.fine_score,
+        feature_contributions: post.feature_contributions.into_iter().collect(),
+        objective_scores: post.objective_scores.into_iter().collect(),
+        session_boost: post.session_boost,
+        exploration_injected: post.exploration_injected,
+        diversity: post.diversity.map(|d: DiversityTrace| ProtoDiversityTrace {
+            input_position: d.input_position as i32,
+            relevance: d.relevance,
+            diversity: d.diversity,
+            mmr_score: d.mmr_score,
+        }),
+    }
+}
+
 fn to_proto_recall_stats(stats: RecallStats) -> ProtoRecallStats {
     ProtoRecallStats {
         graph_recall_count: stats.graph_recall_count,
@@ -1076,3 +1281,23 @@ impl VideoLengthExt for crate::services::profile_builder::VideoLengthPreference
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exploration_context_is_normalized() {
+        let user = UserFeatures {
+            interest_tags: vec!["travel".to_string(); 40],
+            content_type_preferences: vec![],
+            active_hours: vec![21],
+            avg_session_length: 900,
+            followed_authors: (0..5000).map(|_| Uuid::new_v4()).collect(),
+        };
+
+        let context = exploration_context(&user, 21);
+        assert_eq!(context.user_features, vec![1.0, 1.0, 0.5, 1.0]);
+        assert_eq!(exploration_context(&user, 9).user_features[3], 0.0);
+    }
+}
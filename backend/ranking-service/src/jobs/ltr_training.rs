@@ -0,0 +1,454 @@
+// ============================================
+// LTR Training Job (排序模型訓練任務)
+// ============================================
+//
+// Trains a learning-to-rank model from logged impressions and writes a
+// versioned artifact that RankingModel::load can serve.
+//
+// Workflow:
+// 1. Join training_interactions (labels) with training_features (feature
+//    snapshots taken at impression time) in ClickHouse
+// 2. Assemble vectors through the configured feature schema and train
+//    logistic regression or GBDT
+// 3. Evaluate AUC / NDCG@10 on a per-user holdout, next to the heuristic baseline
+// 4. Write ltr-<version>.json and promote it via LATEST if it clears the gate
+//
+// Usage:
+//   train-ltr   (configured via LTR_* and CLICKHOUSE_* environment variables)
+
+use crate::config::{ClickHouseConfig, Config};
+use crate::services::ranking::feature_schema::{
+    FeatureSchema, FeatureSchemaRegistry, FeatureValues, CANDIDATE_FEATURES_V1,
+};
+use crate::services::ranking::ltr::LtrArtifact;
+use crate::services::ranking::training::{
+    self, GbdtParams, LogisticParams, LtrAlgorithm, LtrSample, TrainingConfig,
+};
+use crate::services::ranking::RankingModel;
+use clickhouse::{Client, Row};
+use ndarray::Array2;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{error, info, warn};
+
+/// LTR training job configuration
+#[derive(Debug, Clone)]
+pub struct LtrTrainingConfig {
+    /// Days of impressions to train on
+    pub lookback_days: u32,
+    /// Upper bound on samples pulled from ClickHouse
+    pub max_samples: u64,
+    pub algorithm: LtrAlgorithm,
+    /// Feature schema version to train against
+    pub schema_version: String,
+    /// Fraction of users held out for evaluation
+    pub holdout_fraction: f64,
+    /// Directory that receives versioned artifacts and the LATEST pointer
+    pub output_dir: PathBuf,
+    /// Holdout AUC a model must reach before it is promoted
+    pub min_auc: f64,
+    /// Only promote when the model also beats the heuristic baseline's AUC
+    pub require_beat_baseline: bool,
+}
+
+impl Default for LtrTrainingConfig {
+    fn default() -> Self {
+        Self {
+            lookback_days: 14,
+            max_samples: 2_000_000,
+            algorithm: LtrAlgorithm::Gbdt,
+            schema_version: CANDIDATE_FEATURES_V1.to_string(),
+            holdout_fraction: 0.2,
+            output_dir: PathBuf::from("/models/ltr"),
+            min_auc: 0.55,
+            require_beat_baseline: true,
+        }
+    }
+}
+
+impl LtrTrainingConfig {
+    /// Create config from environment variables
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            lookback_days: std::env::var("LTR_LOOKBACK_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.lookback_days),
+            max_samples: std::env::var("LTR_MAX_SAMPLES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_samples),
+            algorithm: std::env::var("LTR_ALGORITHM")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.algorithm),
+            schema_version: std::env::var("LTR_FEATURE_SCHEMA").unwrap_or(defaults.schema_version),
+            holdout_fraction: std::env::var("LTR_HOLDOUT_FRACTION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.holdout_fraction),
+            output_dir: std::env::var("LTR_OUTPUT_DIR")
+                .map(PathBuf::from)
+                .unwrap_or(defaults.output_dir),
+            min_auc: std::env::var("LTR_MIN_AUC")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_auc),
+            require_beat_baseline: std::env::var("LTR_REQUIRE_BEAT_BASELINE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.require_beat_baseline),
+        }
+    }
+
+    fn training_config(&self) -> TrainingConfig {
+        TrainingConfig {
+            algorithm: self.algorithm,
+            holdout_fraction: self.holdout_fraction,
+            seed: 42,
+            logistic: LogisticParams::default(),
+            gbdt: GbdtParams::default(),
+        }
+    }
+}
+
+/// Outcome of a training run
+#[derive(Debug, Clone)]
+pub struct LtrTrainingReport {
+    pub model_version: String,
+    pub artifact_path: PathBuf,
+    pub auc: f64,
+    pub ndcg_at_10: f64,
+    pub baseline_auc: f64,
+    pub baseline_ndcg_at_10: f64,
+    pub promoted: bool,
+}
+
+/// Joined impression row; columns are named after `candidate_features_v1`
+#[derive(Debug, Row, Deserialize)]
+struct TrainingRow {
+    user_id: String,
+    label: u8,
+    user_follower_count: u32,
+    user_post_count: u32,
+    user_engagement_rate: f32,
+    post_like_count: u32,
+    post_comment_count: u32,
+    post_share_count: u32,
+    post_age_hours: f32,
+    author_is_following: u8,
+    previous_interactions: u32,
+    /// JSON object with any further numeric features, keyed by schema name
+    extra_features: String,
+}
+
+/// Impression with named feature values, before schema assembly
+#[derive(Debug, Clone)]
+pub struct RawSample {
+    pub query_id: String,
+    pub label: f32,
+    pub values: FeatureValues,
+}
+
+impl From<TrainingRow> for RawSample {
+    fn from(row: TrainingRow) -> Self {
+        // Numeric entries of extra_features make new schema features trainable
+        // without changing this query
+        let mut values: FeatureValues =
+            serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&row.extra_features)
+                .map(|extra| {
+                    extra
+                        .into_iter()
+                        .filter_map(|(k, v)| v.as_f64().map(|v| (k, v as f32)))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+        for (name, value) in [
+            ("user_follower_count", row.user_follower_count as f32),
+            ("user_post_count", row.user_post_count as f32),
+            ("user_engagement_rate", row.user_engagement_rate),
+            ("post_like_count", row.post_like_count as f32),
+            ("post_comment_count", row.post_comment_count as f32),
+            ("post_share_count", row.post_share_count as f32),
+            ("post_age_hours", row.post_age_hours),
+            ("author_is_following", row.author_is_following as f32),
+            ("previous_interactions", row.previous_interactions as f32),
+        ] {
+            values.insert(name.to_string(), value);
+        }
+
+        RawSample {
+            query_id: row.user_id,
+            label: if row.label > 0 { 1.0 } else { 0.0 },
+            values,
+        }
+    }
+}
+
+/// Assemble raw samples into model inputs for `schema`
+pub fn assemble_samples(raw: &[RawSample], schema: &FeatureSchema) -> Vec<LtrSample> {
+    raw.iter()
+        .map(|s| LtrSample {
+            query_id: s.query_id.clone(),
+            features: schema.assemble(&s.values),
+            label: s.label,
+        })
+        .collect()
+}
+
+/// LTR training job runner
+pub struct LtrTrainingJob {
+    config: LtrTrainingConfig,
+    schema: Arc<FeatureSchema>,
+    client: Client,
+    database: String,
+}
+
+impl LtrTrainingJob {
+    /// Create a new LTR training job
+    pub fn new(
+        config: LtrTrainingConfig,
+        ch_config: &ClickHouseConfig,
+        registry: &FeatureSchemaRegistry,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let schema = registry.get(&config.schema_version).ok_or_else(|| {
+            format!(
+                "Unknown feature schema {} (known: {:?})",
+                config.schema_version,
+                registry.versions()
+            )
+        })?;
+
+        let client = Client::default()
+            .with_url(&ch_config.url)
+            .with_database(&ch_config.database)
+            .with_user(&ch_config.username)
+            .with_password(&ch_config.password);
+
+        Ok(Self {
+            config,
+            schema,
+            client,
+            database: ch_config.database.clone(),
+        })
+    }
+
+    /// Fetch labelled impressions joined with their feature snapshots
+    ///
+    /// Share count and the viewer's engagement rate are not first-class
+    /// snapshot columns, so they come from `extra_features`; graph recall
+    /// implies the viewer follows the author.
+    ///
+    /// When `max_samples` truncates, rows are taken in user-hash order so the
+    /// sample is a uniform set of whole users rather than the lowest UUIDs.
+    pub async fn fetch_samples(&self) -> Result<Vec<RawSample>, Box<dyn std::error::Error>> {
+        let query = format!(
+            r#"
+            SELECT
+                ti.user_id AS user_id,
+                ti.label AS label,
+                tf.user_follower_count AS user_follower_count,
+                tf.user_post_count AS user_post_count,
+                toFloat32(JSONExtractFloat(tf.extra_features, 'user_engagement_rate'))
+                    AS user_engagement_rate,
+                tf.post_like_count AS post_like_count,
+                tf.post_comment_count AS post_comment_count,
+                toUInt32(JSONExtractUInt(tf.extra_features, 'post_share_count'))
+                    AS post_share_count,
+                tf.post_age_hours AS post_age_hours,
+                toUInt8(ti.recall_source = 'graph'
+                    OR JSONExtractBool(tf.extra_features, 'author_is_following'))
+                    AS author_is_following,
+                tf.user_author_interaction_count AS previous_interactions,
+                tf.extra_features AS extra_features
+            FROM {db}.training_interactions AS ti
+            INNER JOIN {db}.training_features AS tf
+                ON ti.user_id = tf.user_id AND ti.post_id = tf.post_id
+            WHERE ti.event_date >= today() - {lookback}
+              AND abs(toInt64(toUnixTimestamp(ti.impression_time))
+                    - toInt64(toUnixTimestamp(tf.snapshot_time))) < 3600
+            ORDER BY cityHash64(ti.user_id), ti.user_id, ti.impression_time
+            LIMIT {limit}
+            "#,
+            db = self.database,
+            lookback = self.config.lookback_days,
+            limit = self.config.max_samples,
+        );
+
+        let rows: Vec<TrainingRow> = self.client.query(&query).fetch_all().await.map_err(|e| {
+            error!(error = %e, "Failed to fetch LTR training samples");
+            e
+        })?;
+
+        Ok(rows.into_iter().map(RawSample::from).collect())
+    }
+
+    /// Run one training pass
+    pub async fn run(&self) -> Result<LtrTrainingReport, Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        let raw = self.fetch_samples().await?;
+        info!(
+            samples = raw.len(),
+            lookback_days = self.config.lookback_days,
+            schema = %self.schema.version,
+            "Fetched LTR training samples"
+        );
+
+        let training_config = self.config.training_config();
+        let schema = self.schema.clone();
+        let (artifact, baseline) = tokio::task::spawn_blocking(move || {
+            let baseline = heuristic_baseline(&raw, &training_config);
+            let samples = assemble_samples(&raw, &schema);
+            drop(raw);
+            training::train(samples, &schema, &training_config).map(|a| (a, baseline))
+        })
+        .await??;
+
+        let report = self.publish(artifact, baseline)?;
+        info!(
+            model_version = %report.model_version,
+            auc = report.auc,
+            ndcg_at_10 = report.ndcg_at_10,
+            baseline_auc = report.baseline_auc,
+            baseline_ndcg_at_10 = report.baseline_ndcg_at_10,
+            promoted = report.promoted,
+            duration_ms = started.elapsed().as_millis() as u64,
+            "LTR training completed"
+        );
+        Ok(report)
+    }
+
+    /// Write the artifact and promote it if it clears the quality gate
+    fn publish(
+        &self,
+        artifact: LtrArtifact,
+        baseline: (f64, f64),
+    ) -> Result<LtrTrainingReport, Box<dyn std::error::Error>> {
+        let artifact_path = artifact.write(&self.config.output_dir)?;
+        let metrics = &artifact.metrics;
+
+        let promoted = passes_gate(&self.config, metrics.auc, baseline.0);
+        if promoted {
+            artifact.promote(&self.config.output_dir)?;
+        } else {
+            warn!(
+                model_version = %artifact.model_version,
+                auc = metrics.auc,
+                baseline_auc = baseline.0,
+                min_auc = self.config.min_auc,
+                "LTR model below promotion gate, keeping current LATEST"
+            );
+        }
+
+        Ok(LtrTrainingReport {
+            model_version: artifact.model_version.clone(),
+            artifact_path,
+            auc: metrics.auc,
+            ndcg_at_10: metrics.ndcg_at_10,
+            baseline_auc: baseline.0,
+            baseline_ndcg_at_10: baseline.1,
+            promoted,
+        })
+    }
+}
+
+/// Whether a model with `auc` should replace the served one
+fn passes_gate(config: &LtrTrainingConfig, auc: f64, baseline_auc: f64) -> bool {
+    auc >= config.min_auc && (!config.require_beat_baseline || auc > baseline_auc)
+}
+
+/// (AUC, NDCG@10) of the heuristic scorer on the same holdout users
+fn heuristic_baseline(raw: &[RawSample], config: &TrainingConfig) -> (f64, f64) {
+    let model = RankingModel::heuristic();
+    let holdout: Vec<RawSample> = raw
+        .iter()
+        .filter(|s| training::is_holdout(&s.query_id, config.holdout_fraction, config.seed))
+        .cloned()
+        .collect();
+    let holdout = assemble_samples(&holdout, model.schema());
+
+    let width = model.schema().len();
+    let metrics = training::evaluate(&holdout, |x| {
+        Array2::from_shape_vec((1, width), x.to_vec())
+            .ok()
+            .and_then(|m| model.predict(m).ok())
+            .map(|scores| scores[0])
+            .unwrap_or(0.0)
+    });
+    (metrics.auc, metrics.ndcg_at_10)
+}
+
+/// Standalone entry point for the `train-ltr` binary
+pub async fn run_ltr_training_job() -> Result<(), Box<dyn std::error::Error>> {
+    info!("Initializing LTR training job");
+
+    let config = Config::from_env()?;
+    let registry = FeatureSchemaRegistry::from_env()?;
+    let job = LtrTrainingJob::new(LtrTrainingConfig::from_env(), &config.clickhouse, &registry)?;
+    let report = job.run().await?;
+
+    info!(
+        model_version = %report.model_version,
+        path = %report.artifact_path.display(),
+        promoted = report.promoted,
+        "LTR training job finished"
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults() {
+        let config = LtrTrainingConfig::default();
+        assert_eq!(config.algorithm, LtrAlgorithm::Gbdt);
+        assert!(config.require_beat_baseline);
+    }
+
+    #[test]
+    fn test_raw_sample_merges_extra_features() {
+        let row = TrainingRow {
+            user_id: "u1".to_string(),
+            label: 1,
+            user_follower_count: 10,
+            user_post_count: 2,
+            user_engagement_rate: 0.3,
+            post_like_count: 5,
+            post_comment_count: 1,
+            post_share_count: 0,
+            post_age_hours: 3.0,
+            author_is_following: 1,
+            previous_interactions: 4,
+            extra_features: r#"{"post_save_count": 7, "note": "x"}"#.to_string(),
+        };
+        let raw = RawSample::from(row);
+        assert_eq!(raw.values["post_save_count"], 7.0);
+        assert!(!raw.values.contains_key("note"));
+
+        let v1 = assemble_samples(&[raw], &FeatureSchema::v1());
+        assert_eq!(v1[0].features.len(), 9);
+        assert_eq!(v1[0].features[7], 1.0);
+        assert_eq!(v1[0].label, 1.0);
+    }
+
+    #[test]
+    fn test_promotion_gate() {
+        let config = LtrTrainingConfig::default();
+        assert!(passes_gate(&config, 0.70, 0.60));
+        assert!(!passes_gate(&config, 0.58, 0.60));
+        assert!(!passes_gate(&config, 0.50, 0.40));
+
+        let lenient = LtrTrainingConfig {
+            require_beat_baseline: false,
+            ..config
+        };
+        assert!(passes_gate(&lenient, 0.58, 0.60));
+    }
+}
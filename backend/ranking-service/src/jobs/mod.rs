@@ -6,12 +6,20 @@
 // 1. Profile batch updates
 // 2. Interest aggregation
 // 3. Feature store refresh
+// 4. Learning-to-rank model training
+// 5. Offline pipeline replay evaluation
 //
 // These jobs can be triggered via:
 // - CronJob (Kubernetes)
 // - Command line argument (--mode profile-batch)
 // - gRPC API (BatchUpdateProfiles)
 
+pub mod ltr_training;
 pub mod profile_batch;
+pub mod replay_evaluation;
 
+pub use ltr_training::{run_ltr_training_job, LtrTrainingConfig, LtrTrainingJob};
 pub use profile_batch::{run_profile_batch_job, ProfileBatchConfig, ProfileBatchJob};
+pub use replay_evaluation::{
+    run_replay_evaluation, run_replay_evaluation_job, ReplayEvaluationConfig,
+};
@@ -0,0 +1,108 @@
+// ============================================
+// Pipeline Replay Evaluation Job (離線回放評估任務)
+// ============================================
+//
+// Replays logged feed requests through a baseline and a candidate
+// pipeline configuration and reports offline metrics side by side.
+//
+// Inputs:
+// - REPLAY_LOG_PATH: JSON Lines of `LoggedFeedRequest`
+// - REPLAY_BASELINE_CONFIG: `ReplayPipelineConfig` JSON (serving defaults if unset)
+// - REPLAY_CANDIDATE_CONFIG: `ReplayPipelineConfig` JSON under evaluation
+//
+// Usage:
+//   replay-pipeline   (report is printed, and written to REPLAY_OUTPUT_PATH if set)
+
+use crate::services::pipeline_replay::{
+    compare, load_requests, ReplayComparison, ReplayError, ReplayOptions, ReplayPipelineConfig,
+};
+use std::path::PathBuf;
+use std::time::Instant;
+use tracing::info;
+
+/// Replay evaluation job configuration
+#[derive(Debug, Clone)]
+pub struct ReplayEvaluationConfig {
+    pub log_path: PathBuf,
+    pub baseline_config: Option<PathBuf>,
+    pub candidate_config: PathBuf,
+    pub output_path: Option<PathBuf>,
+    pub options: ReplayOptions,
+}
+
+impl ReplayEvaluationConfig {
+    /// Create config from environment variables
+    pub fn from_env() -> Result<Self, ReplayError> {
+        let required = |name: &str| {
+            std::env::var(name)
+                .map(PathBuf::from)
+                .map_err(|_| ReplayError::InvalidConfig(format!("{} is required", name)))
+        };
+        let optional = |name: &str| std::env::var(name).ok().map(PathBuf::from);
+        let defaults = ReplayOptions::default();
+
+        Ok(Self {
+            log_path: required("REPLAY_LOG_PATH")?,
+            baseline_config: optional("REPLAY_BASELINE_CONFIG"),
+            candidate_config: required("REPLAY_CANDIDATE_CONFIG")?,
+            output_path: optional("REPLAY_OUTPUT_PATH"),
+            options: ReplayOptions {
+                k: std::env::var("REPLAY_K")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(defaults.k),
+                max_importance_weight: std::env::var("REPLAY_MAX_IMPORTANCE_WEIGHT")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(defaults.max_importance_weight),
+                seed: std::env::var("REPLAY_SEED")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(defaults.seed),
+            },
+        })
+    }
+}
+
+/// Run the comparison described by `config`
+pub fn run_replay_evaluation(
+    config: &ReplayEvaluationConfig,
+) -> Result<ReplayComparison, ReplayError> {
+    let start = Instant::now();
+    let requests = load_requests(&config.log_path)?;
+    let baseline = match &config.baseline_config {
+        Some(path) => ReplayPipelineConfig::load(path)?,
+        None => ReplayPipelineConfig::default(),
+    };
+    let candidate = ReplayPipelineConfig::load(&config.candidate_config)?;
+
+    info!(
+        requests = requests.len(),
+        log = %config.log_path.display(),
+        "Replaying logged feed requests"
+    );
+
+    let comparison = compare(&baseline, &candidate, &requests, &config.options)?;
+
+    info!(
+        duration_ms = start.elapsed().as_millis() as u64,
+        "Replay evaluation finished"
+    );
+
+    Ok(comparison)
+}
+
+/// Entry point for the `replay-pipeline` binary
+pub fn run_replay_evaluation_job() -> Result<(), Box<dyn std::error::Error>> {
+    let config = ReplayEvaluationConfig::from_env()?;
+    let comparison = run_replay_evaluation(&config)?;
+
+    let report = serde_json::to_string_pretty(&comparison)?;
+    if let Some(path) = &config.output_path {
+        std::fs::write(path, &report)?;
+        info!(path = %path.display(), "Replay report written");
+    }
+    println!("{}", report);
+
+    Ok(())
+}
@@ -2,7 +2,9 @@ use anyhow::{anyhow, Context, Result};
 use ranking_service::{
     grpc::{ranking_proto::ranking_service_server::RankingServiceServer, RankingServiceImpl},
     jobs::run_profile_batch_job,
-    Config, DiversityLayer, FeatureClient, RankingLayer, RecallLayer,
+    services::ranking::{scorer::RankingScorer, FeatureSchemaRegistry, RankingModel, ValueModel},
+    services::trace::{TraceStore, TraceStoreConfig},
+    Config, DiversityLayer, FeatureClient, GrpcFeatureClient, RankingLayer, RecallLayer,
 };
 use std::env;
 use std::sync::Arc;
@@ -77,11 +79,66 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         redis_client,
         config.recall.clone(),
     );
-    let ranking_layer = RankingLayer::new(feature_client);
+    let mut ranking_layer = RankingLayer::new(feature_client);
+
+    // Multi-objective model scoring (needs a model and the feature-store)
+    if let (Ok(model_path), Some(feature_store_url)) = (
+        env::var("RANKING_MODEL_PATH"),
+        config.grpc_clients.feature_store_url.clone(),
+    ) {
+        let feature_store = tonic::transport::Channel::from_shared(feature_store_url)
+            .context("Invalid feature-store URL")?
+            .connect_lazy();
+        let registry = FeatureSchemaRegistry::from_env()?;
+        let model = RankingModel::load_with_registry(&model_path, &registry)?;
+        let scorer = RankingScorer::with_feature_store(
+            Arc::new(model),
+            Arc::new(GrpcFeatureClient::new(feature_store)),
+        );
+        let value_model = ValueModel::from_env()?;
+        info!(
+            "Model-based fine ranking enabled: {} ({} surface overrides, {} variant overrides)",
+            model_path,
+            value_model.surfaces.len(),
+            value_model.variants.len()
+        );
+        ranking_layer = ranking_layer.with_model_scoring(Arc::new(scorer), Arc::new(value_model));
+    }
     let diversity_layer = DiversityLayer::new(0.7); // lambda = 0.7
 
     // Create gRPC service
-    let ranking_service = RankingServiceImpl::new(recall_layer, ranking_layer, diversity_layer);
+    let trace_redis_client = redis::Client::open(config.redis.url.clone())
+        .expect("Failed to create Redis client for ranking traces");
+    let trace_store = TraceStore::new(trace_redis_client, TraceStoreConfig::from_env())
+        .with_clickhouse(&config.clickhouse);
+    let ranking_service = RankingServiceImpl::new(recall_layer, ranking_layer, diversity_layer)
+        .with_trace_store(Arc::new(trace_store));
+
+    // Restore the learned exploration policy, then persist it periodically
+    let exploration_pool = ranking_service.exploration_pool();
+    match exploration_pool.load_policy_state().await {
+        Ok(true) => info!(
+            "Restored {} exploration policy state",
+            exploration_pool.policy_name()
+        ),
+        Ok(false) => {}
+        Err(e) => warn!(error = %e, "Failed to restore exploration policy state"),
+    }
+    let save_interval = env::var("EXPLORATION_STATE_SAVE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60u64)
+        .max(1);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(save_interval));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = exploration_pool.save_policy_state().await {
+                warn!(error = %e, "Failed to persist exploration policy state");
+            }
+        }
+    });
 
     // Initialize profile services (optional - fails gracefully if ClickHouse not configured)
     if let Err(e) = ranking_service.init_profile_services(&config).await {
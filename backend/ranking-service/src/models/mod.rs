@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +32,9 @@ pub struct RankedPost {
     pub post_id: String,
     pub score: f32,
     pub recall_source: RecallSource,
+    /// Per-objective model predictions behind `score` (debug output, empty
+    /// when ranked by the linear scorer)
+    pub objective_scores: BTreeMap<String, f32>,
     pub features: PostFeatures,
 }
 
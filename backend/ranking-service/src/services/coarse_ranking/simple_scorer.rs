@@ -14,6 +14,7 @@
 
 use crate::models::Candidate;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use tracing::{debug, info};
 use uuid::Uuid;
@@ -63,7 +64,8 @@ impl From<Candidate> for CoarseCandidate {
 }
 
 /// Configurable weights for coarse ranking
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CoarseWeights {
     /// Weight for engagement/recall score
     pub engagement: f32,
@@ -134,6 +136,19 @@ impl CoarseRankingLayer {
         candidates: Vec<CoarseCandidate>,
         user_features: &UserFeatures,
     ) -> Result<Vec<CoarseCandidate>> {
+        Ok(self
+            .rank_scored(candidates, user_features)?
+            .into_iter()
+            .map(|(c, _)| c)
+            .collect())
+    }
+
+    /// Rank candidates, keeping each survivor's coarse score
+    pub fn rank_scored(
+        &self,
+        candidates: Vec<CoarseCandidate>,
+        user_features: &UserFeatures,
+    ) -> Result<Vec<(CoarseCandidate, f32)>> {
         if candidates.is_empty() {
             return Ok(Vec::new());
         }
@@ -154,11 +169,8 @@ impl CoarseRankingLayer {
         scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
         // Take top N
-        let output: Vec<CoarseCandidate> = scored
-            .into_iter()
-            .take(self.output_limit)
-            .map(|(c, _)| c)
-            .collect();
+        let output: Vec<(CoarseCandidate, f32)> =
+            scored.into_iter().take(self.output_limit).collect();
 
         info!(
             input_count = input_count,
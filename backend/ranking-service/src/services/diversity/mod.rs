@@ -2,6 +2,16 @@ use crate::models::RankedPost;
 use std::collections::HashSet;
 use uuid::Uuid;
 
+/// 多樣性重排中單個帖子的 MMR 決策
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiversityDecision {
+    /// 重排前的位置
+    pub input_position: usize,
+    pub relevance: f32,
+    pub diversity: f32,
+    pub mmr_score: f32,
+}
+
 /// Diversity Layer - 多樣性重排
 /// 使用 MMR (Maximal Marginal Relevance) 算法
 pub struct DiversityLayer {
@@ -30,23 +40,37 @@ impl DiversityLayer {
     /// lambda = 0.0: 只看多樣性
     /// lambda = 0.7: 平衡（推薦值）
     pub fn rerank(&self, posts: Vec<RankedPost>, top_k: usize) -> Vec<RankedPost> {
+        self.rerank_explained(posts, top_k)
+            .into_iter()
+            .map(|(post, _)| post)
+            .collect()
+    }
+
+    /// 重排並記錄每個入選帖子的 MMR 決策（排序解釋用）
+    pub fn rerank_explained(
+        &self,
+        posts: Vec<RankedPost>,
+        top_k: usize,
+    ) -> Vec<(RankedPost, DiversityDecision)> {
         if posts.is_empty() {
             return Vec::new();
         }
 
         let mut selected: Vec<RankedPost> = Vec::new();
-        let mut remaining = posts;
+        let mut decisions: Vec<DiversityDecision> = Vec::new();
+        let mut remaining: Vec<(usize, RankedPost)> = posts.into_iter().enumerate().collect();
         let mut seen_sources: HashSet<String> = HashSet::new();
 
         // MMR 貪心選擇
         while selected.len() < top_k && !remaining.is_empty() {
             let mut best_idx = 0;
             let mut best_mmr_score = f32::MIN;
+            let mut best_diversity = 1.0;
 
             // Get recent authors for diversity check
             let recent_authors = self.get_recent_authors(&selected);
 
-            for (i, post) in remaining.iter().enumerate() {
+            for (i, (_, post)) in remaining.iter().enumerate() {
                 // Hard constraint: Skip if violates author diversity
                 if self.violates_author_diversity(&recent_authors, post) {
                     continue;
@@ -59,15 +83,22 @@ impl DiversityLayer {
                 if mmr_score > best_mmr_score {
                     best_mmr_score = mmr_score;
                     best_idx = i;
+                    best_diversity = diversity;
                 }
             }
 
-            let selected_post = remaining.remove(best_idx);
+            let (input_position, selected_post) = remaining.remove(best_idx);
+            decisions.push(DiversityDecision {
+                input_position,
+                relevance: selected_post.score,
+                diversity: best_diversity,
+                mmr_score: best_mmr_score,
+            });
             seen_sources.insert(selected_post.recall_source.as_str().to_string());
             selected.push(selected_post);
         }
 
-        selected
+        selected.into_iter().zip(decisions).collect()
     }
 
     /// Get recent N author IDs from selected posts
@@ -151,24 +182,28 @@ mod tests {
                 post_id: "post1".to_string(),
                 score: 0.9,
                 recall_source: RecallSource::Graph,
+                objective_scores: Default::default(),
                 features: PostFeatures::default(),
             },
             RankedPost {
                 post_id: "post2".to_string(),
                 score: 0.85,
                 recall_source: RecallSource::Graph,
+                objective_scores: Default::default(),
                 features: PostFeatures::default(),
             },
             RankedPost {
                 post_id: "post3".to_string(),
                 score: 0.8,
                 recall_source: RecallSource::Trending,
+                objective_scores: Default::default(),
                 features: PostFeatures::default(),
             },
             RankedPost {
                 post_id: "post4".to_string(),
                 score: 0.75,
                 recall_source: RecallSource::Personalized,
+                objective_scores: Default::default(),
                 features: PostFeatures::default(),
             },
         ];
@@ -194,6 +229,7 @@ mod tests {
                 post_id: "post1".to_string(),
                 score: 0.9,
                 recall_source: RecallSource::Graph,
+                objective_scores: Default::default(),
                 features: PostFeatures {
                     author_id: Some(author1),
                     ..Default::default()
@@ -203,6 +239,7 @@ mod tests {
                 post_id: "post2".to_string(),
                 score: 0.88,
                 recall_source: RecallSource::Graph,
+                objective_scores: Default::default(),
                 features: PostFeatures {
                     author_id: Some(author1),
                     ..Default::default()
@@ -212,6 +249,7 @@ mod tests {
                 post_id: "post3".to_string(),
                 score: 0.86,
                 recall_source: RecallSource::Graph,
+                objective_scores: Default::default(),
                 features: PostFeatures {
                     author_id: Some(author1),
                     ..Default::default()
@@ -221,6 +259,7 @@ mod tests {
                 post_id: "post4".to_string(),
                 score: 0.7,
                 recall_source: RecallSource::Trending,
+                objective_scores: Default::default(),
                 features: PostFeatures {
                     author_id: Some(author2),
                     ..Default::default()
@@ -258,6 +297,7 @@ mod tests {
                 post_id: "post1".to_string(),
                 score: 0.9,
                 recall_source: RecallSource::Graph,
+                objective_scores: Default::default(),
                 features: PostFeatures {
                     author_id: Some(author1),
                     ..Default::default()
@@ -267,6 +307,7 @@ mod tests {
                 post_id: "post2".to_string(),
                 score: 0.88,
                 recall_source: RecallSource::Graph,
+                objective_scores: Default::default(),
                 features: PostFeatures {
                     author_id: Some(author1),
                     ..Default::default()
@@ -276,6 +317,7 @@ mod tests {
                 post_id: "post3".to_string(),
                 score: 0.85,
                 recall_source: RecallSource::Graph,
+                objective_scores: Default::default(),
                 features: PostFeatures {
                     author_id: Some(author2),
                     ..Default::default()
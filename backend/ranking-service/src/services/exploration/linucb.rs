@@ -0,0 +1,158 @@
+// ============================================
+// LinUCB Contextual Bandit
+// ============================================
+//
+// Linear UCB with a single model shared by all content, so knowledge
+// transfers to items that have never been shown (Li et al., 2010).
+//
+// Context vector for user u and content c:
+//   x = vec([1; u] ⊗ [1; c])
+// The cross terms let the same content score differently per user.
+//
+// Score:
+//   p(x) = θᵀx + α * sqrt(xᵀ A⁻¹ x),   θ = A⁻¹ b
+//
+// A⁻¹ is maintained directly with Sherman-Morrison updates, so an update
+// costs O(d²) and no matrix is ever inverted.
+
+use serde::{Deserialize, Serialize};
+
+/// Shared-parameter LinUCB model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinUcb {
+    user_dim: usize,
+    content_dim: usize,
+    /// Exploration strength (α)
+    alpha: f64,
+    /// A⁻¹, row-major d × d (A starts as the identity)
+    a_inv: Vec<f64>,
+    /// Reward-weighted sum of context vectors
+    b: Vec<f64>,
+    /// Number of updates applied
+    updates: u64,
+}
+
+impl LinUcb {
+    pub fn new(user_dim: usize, content_dim: usize, alpha: f64) -> Self {
+        let d = (user_dim + 1) * (content_dim + 1);
+        let mut a_inv = vec![0.0; d * d];
+        for i in 0..d {
+            a_inv[i * d + i] = 1.0;
+        }
+        Self {
+            user_dim,
+            content_dim,
+            alpha,
+            a_inv,
+            b: vec![0.0; d],
+            updates: 0,
+        }
+    }
+
+    /// Context vector dimension
+    pub fn dim(&self) -> usize {
+        self.b.len()
+    }
+
+    pub fn updates(&self) -> u64 {
+        self.updates
+    }
+
+    /// Build the joint context vector (features are zero-padded or truncated)
+    pub fn context_vector(&self, user: &[f32], content: &[f32]) -> Vec<f64> {
+        let with_bias = |features: &[f32], dim: usize| -> Vec<f64> {
+            std::iter::once(1.0)
+                .chain((0..dim).map(|i| features.get(i).copied().unwrap_or(0.0) as f64))
+                .collect()
+        };
+        let u = with_bias(user, self.user_dim);
+        let c = with_bias(content, self.content_dim);
+        u.iter()
+            .flat_map(|ui| c.iter().map(move |cj| ui * cj))
+            .collect()
+    }
+
+    /// Upper confidence bound of the expected reward
+    pub fn score(&self, user: &[f32], content: &[f32]) -> f64 {
+        let x = self.context_vector(user, content);
+        let a_inv_x = self.a_inv_times(&x);
+        let theta_x: f64 = self
+            .a_inv_times(&self.b)
+            .iter()
+            .zip(&x)
+            .map(|(t, x)| t * x)
+            .sum();
+        let variance: f64 = x.iter().zip(&a_inv_x).map(|(x, y)| x * y).sum();
+        theta_x + self.alpha * variance.max(0.0).sqrt()
+    }
+
+    /// Learn from one observed reward (1.0 = engaged, 0.0 = ignored)
+    pub fn update(&mut self, user: &[f32], content: &[f32], reward: f64) {
+        let x = self.context_vector(user, content);
+        let d = self.dim();
+        let a_inv_x = self.a_inv_times(&x);
+        let denom = 1.0 + x.iter().zip(&a_inv_x).map(|(x, y)| x * y).sum::<f64>();
+
+        // A⁻¹ is symmetric, so (xᵀA⁻¹)ᵀ = A⁻¹x
+        for i in 0..d {
+            for j in 0..d {
+                self.a_inv[i * d + j] -= a_inv_x[i] * a_inv_x[j] / denom;
+            }
+        }
+        for (b, x) in self.b.iter_mut().zip(&x) {
+            *b += reward * x;
+        }
+        self.updates += 1;
+    }
+
+    /// Whether `other` was built for the same user / content features
+    pub fn same_shape(&self, other: &LinUcb) -> bool {
+        self.user_dim == other.user_dim && self.content_dim == other.content_dim
+    }
+
+    fn a_inv_times(&self, v: &[f64]) -> Vec<f64> {
+        self.a_inv
+            .chunks(v.len())
+            .map(|row| row.iter().zip(v).map(|(a, v)| a * v).sum())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_vector_has_cross_terms() {
+        let model = LinUcb::new(1, 2, 1.0);
+        assert_eq!(model.dim(), 6);
+        // [1, u] ⊗ [1, c1, c2] with missing c2 padded
+        let x = model.context_vector(&[2.0], &[3.0]);
+        assert_eq!(x, vec![1.0, 3.0, 0.0, 2.0, 6.0, 0.0]);
+    }
+
+    #[test]
+    fn test_uncertainty_shrinks_with_updates() {
+        let mut model = LinUcb::new(0, 1, 1.0);
+        let before = model.score(&[], &[1.0]);
+        for _ in 0..50 {
+            model.update(&[], &[1.0], 0.0);
+        }
+        assert!(model.score(&[], &[1.0]) < before);
+        assert_eq!(model.updates(), 50);
+    }
+
+    #[test]
+    fn test_learns_user_specific_preference() {
+        // User feature 1.0 likes content feature 1.0; user 0.0 likes content 0.0
+        let mut model = LinUcb::new(1, 1, 0.1);
+        for _ in 0..200 {
+            model.update(&[1.0], &[1.0], 1.0);
+            model.update(&[1.0], &[0.0], 0.0);
+            model.update(&[0.0], &[1.0], 0.0);
+            model.update(&[0.0], &[0.0], 1.0);
+        }
+        assert!(model.score(&[1.0], &[1.0]) > model.score(&[1.0], &[0.0]));
+        assert!(model.score(&[0.0], &[0.0]) > model.score(&[0.0], &[1.0]));
+    }
+}
@@ -3,7 +3,8 @@
 // ============================================
 //
 // Implements explore-exploit balance for new content discovery
-// using Upper Confidence Bound (UCB) algorithm.
+// using pluggable bandit policies: UCB1, Thompson sampling and
+// LinUCB contextual bandits, with offline replay evaluation.
 //
 // TikTok-style new content handling:
 // 1. New content enters exploration pool
@@ -15,13 +16,21 @@
 // - Exploitation: avg_engagement_rate (CTR, completion rate)
 // - Exploration: uncertainty bonus based on impression count
 
+pub mod linucb;
 pub mod new_content_pool;
+pub mod policy;
+pub mod replay;
 pub mod ucb;
 
+pub use linucb::LinUcb;
 pub use new_content_pool::{NewContentEntry, NewContentPool};
+pub use policy::{ExplorationContext, ExplorationPolicy, ExplorationPolicyKind, ThompsonSampler};
+pub use replay::{compare_policies, replay, LoggedImpression, ReplayReport};
 pub use ucb::UCBExplorer;
 
+use crate::models::{PostFeatures, RankedPost, RecallSource};
 use thiserror::Error;
+use uuid::Uuid;
 
 #[derive(Debug, Error)]
 pub enum ExplorationError {
@@ -36,3 +45,38 @@ pub enum ExplorationError {
 }
 
 pub type Result<T> = std::result::Result<T, ExplorationError>;
+
+/// Spread exploration content evenly through a ranked list
+///
+/// Injected posts get a neutral score of 0.5 and fresh-content features,
+/// so later stages treat them like an average new post.
+pub fn interleave_exploration(
+    ranked_posts: Vec<RankedPost>,
+    content_ids: &[Uuid],
+) -> Vec<RankedPost> {
+    let mut result = ranked_posts;
+    let interval = result.len() / (content_ids.len() + 1).max(1);
+
+    for (i, content_id) in content_ids.iter().enumerate() {
+        let position = ((i + 1) * interval).min(result.len());
+        result.insert(
+            position,
+            RankedPost {
+                post_id: content_id.to_string(),
+                score: 0.5,
+                recall_source: RecallSource::Personalized,
+                objective_scores: Default::default(),
+                features: PostFeatures {
+                    engagement_score: 0.5,
+                    recency_score: 1.0,
+                    author_quality_score: 0.5,
+                    content_quality_score: 0.5,
+                    completion_rate_score: 0.5,
+                    author_id: None,
+                },
+            },
+        );
+    }
+
+    result
+}
@@ -7,15 +7,18 @@
 //
 // Data Flow:
 // 1. New content uploaded → Added to exploration pool
-// 2. UCB algorithm selects content for display
+// 2. Exploration policy (UCB / Thompson / LinUCB) selects content for display
 // 3. Engagement events update pool statistics
 // 4. Content graduates to main pool after sufficient data
 
+use super::linucb::LinUcb;
+use super::policy::{ExplorationContext, ExplorationPolicy};
 use super::{ucb::UCBExplorer, ExplorationError, Result};
 use chrono::{DateTime, Utc};
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use std::sync::RwLock;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 /// Entry in the new content exploration pool
@@ -30,6 +33,9 @@ pub struct NewContentEntry {
     pub ucb_score: f64,
     /// Whether content is still active in exploration
     pub is_active: bool,
+    /// Dense content features for contextual policies (LinUCB)
+    #[serde(default)]
+    pub features: Vec<f32>,
 }
 
 impl NewContentEntry {
@@ -42,6 +48,7 @@ impl NewContentEntry {
             engagements: 0,
             ucb_score: f64::MAX, // New content gets maximum exploration priority
             is_active: true,
+            features: Vec::new(),
         }
     }
 
@@ -58,6 +65,12 @@ impl NewContentEntry {
 pub struct NewContentPool {
     redis: redis::Client,
     ucb_explorer: UCBExplorer,
+    /// Policy used to pick content at request time
+    policy: RwLock<ExplorationPolicy>,
+    /// Maximum items per author in one sample (None = uncapped)
+    max_per_author: Option<usize>,
+    /// Number of top-UCB entries the policy chooses from
+    candidate_window: usize,
     /// Maximum age for content in exploration pool (hours)
     max_content_age_hours: i64,
     /// Redis key prefix
@@ -69,14 +82,23 @@ impl NewContentPool {
     /// - {prefix}:pool - Sorted set of content_id by UCB score
     /// - {prefix}:entry:{content_id} - Hash with entry details
     /// - {prefix}:total_impressions - Total impressions counter
+    /// - {prefix}:policy:linucb - Persisted LinUCB model
+    /// - {prefix}:served:{user_id}:{content_id} - Context an item was served in
     const POOL_KEY_SUFFIX: &'static str = ":pool";
     const ENTRY_KEY_PREFIX: &'static str = ":entry:";
     const TOTAL_IMPRESSIONS_KEY: &'static str = ":total_impressions";
+    const LINUCB_STATE_KEY: &'static str = ":policy:linucb";
+    const SERVED_KEY_PREFIX: &'static str = ":served:";
+    /// How long a served item can still receive feedback
+    const SERVED_TTL_SECS: u64 = 24 * 3600;
 
     pub fn new(redis: redis::Client) -> Self {
         Self {
             redis,
             ucb_explorer: UCBExplorer::default(),
+            policy: RwLock::new(ExplorationPolicy::default()),
+            max_per_author: Some(2),
+            candidate_window: 500,
             max_content_age_hours: 168, // 7 days
             key_prefix: "exploration".to_string(),
         }
@@ -88,6 +110,23 @@ impl NewContentPool {
         self
     }
 
+    /// Create with a different exploration policy
+    pub fn with_policy(self, policy: ExplorationPolicy) -> Self {
+        *self.policy.write().unwrap_or_else(|e| e.into_inner()) = policy;
+        self
+    }
+
+    /// Cap how many items of one author a single sample may return
+    pub fn with_author_cap(mut self, max_per_author: Option<usize>) -> Self {
+        self.max_per_author = max_per_author;
+        self
+    }
+
+    /// Name of the active exploration policy
+    pub fn policy_name(&self) -> &'static str {
+        self.policy.read().unwrap_or_else(|e| e.into_inner()).name()
+    }
+
     /// Create with custom key prefix
     pub fn with_key_prefix(mut self, prefix: &str) -> Self {
         self.key_prefix = prefix.to_string();
@@ -111,15 +150,43 @@ impl NewContentPool {
         format!("{}{}", self.key_prefix, Self::TOTAL_IMPRESSIONS_KEY)
     }
 
+    fn linucb_state_key(&self) -> String {
+        format!("{}{}", self.key_prefix, Self::LINUCB_STATE_KEY)
+    }
+
+    fn served_key(&self, user_id: &Uuid, content_id: &Uuid) -> String {
+        format!(
+            "{}{}{}:{}",
+            self.key_prefix,
+            Self::SERVED_KEY_PREFIX,
+            user_id,
+            content_id
+        )
+    }
+
     /// Add new content to exploration pool
     pub async fn add_content(&self, content_id: Uuid, author_id: Uuid) -> Result<()> {
+        self.add_content_with_features(content_id, author_id, Vec::new())
+            .await
+    }
+
+    /// Add new content with dense features for contextual policies
+    pub async fn add_content_with_features(
+        &self,
+        content_id: Uuid,
+        author_id: Uuid,
+        features: Vec<f32>,
+    ) -> Result<()> {
         let mut conn = self
             .redis
             .get_multiplexed_async_connection()
             .await
             .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
 
-        let entry = NewContentEntry::new(content_id, author_id);
+        let entry = NewContentEntry {
+            features,
+            ..NewContentEntry::new(content_id, author_id)
+        };
         let entry_json = serde_json::to_string(&entry)
             .map_err(|e| ExplorationError::PoolError(e.to_string()))?;
 
@@ -238,8 +305,108 @@ impl NewContentPool {
         Ok(())
     }
 
-    /// Sample content using UCB algorithm
+    /// Record the outcome of one exploration impression
+    ///
+    /// Updates the pool counters and lets contextual policies learn from the
+    /// request context the content was shown in.
+    pub async fn record_feedback(
+        &self,
+        content_id: Uuid,
+        context: &ExplorationContext,
+        engaged: bool,
+    ) -> Result<()> {
+        self.record_impression(content_id).await?;
+        if engaged {
+            self.record_engagement(content_id).await?;
+        }
+
+        if let Some(entry) = self.get_entry(content_id).await? {
+            self.policy
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .observe(&entry, context, engaged);
+        }
+
+        Ok(())
+    }
+
+    /// Sample content with the configured policy and no user context
     pub async fn sample_by_ucb(&self, count: usize) -> Result<Vec<Uuid>> {
+        self.sample(count, &ExplorationContext::default()).await
+    }
+
+    /// Sample content for a user and remember the context it was served in,
+    /// so `record_user_feedback` can credit the same context later
+    pub async fn sample_for_user(
+        &self,
+        user_id: Uuid,
+        count: usize,
+        context: &ExplorationContext,
+    ) -> Result<Vec<Uuid>> {
+        let sampled = self.sample(count, context).await?;
+        if sampled.is_empty() {
+            return Ok(sampled);
+        }
+
+        let context_json = serde_json::to_string(context)
+            .map_err(|e| ExplorationError::PoolError(e.to_string()))?;
+        let mut conn = self
+            .redis
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+        let mut pipe = redis::pipe();
+        for content_id in &sampled {
+            pipe.set_ex(
+                self.served_key(&user_id, content_id),
+                &context_json,
+                Self::SERVED_TTL_SECS,
+            )
+            .ignore();
+        }
+        let _: () = pipe
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+
+        Ok(sampled)
+    }
+
+    /// Record the outcome of an item served by `sample_for_user`
+    ///
+    /// Each served item is credited once; returns false when the item was not
+    /// served to this user (or its feedback was already recorded or expired).
+    pub async fn record_user_feedback(
+        &self,
+        user_id: Uuid,
+        content_id: Uuid,
+        engaged: bool,
+    ) -> Result<bool> {
+        let mut conn = self
+            .redis
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+        let served: Option<String> = redis::cmd("GETDEL")
+            .arg(self.served_key(&user_id, &content_id))
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+        let Some(served) = served else {
+            return Ok(false);
+        };
+
+        let context: ExplorationContext = serde_json::from_str(&served)
+            .map_err(|e| ExplorationError::PoolError(e.to_string()))?;
+        self.record_feedback(content_id, &context, engaged).await?;
+        Ok(true)
+    }
+
+    /// Sample content with the configured policy
+    ///
+    /// The policy chooses among the top `candidate_window` entries by UCB
+    /// score, returning at most `max_per_author` items per author.
+    pub async fn sample(&self, count: usize, context: &ExplorationContext) -> Result<Vec<Uuid>> {
         if count == 0 {
             return Ok(Vec::new());
         }
@@ -250,21 +417,50 @@ impl NewContentPool {
             .await
             .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
 
-        // Get top content by UCB score (stored in sorted set)
+        // Get candidate window by UCB score (stored in sorted set)
+        let window = self.candidate_window.max(count * 2);
         let content_ids: Vec<String> = conn
-            .zrevrange(self.pool_key(), 0, (count * 2) as isize)
+            .zrevrange(self.pool_key(), 0, window as isize - 1)
             .await
             .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
 
-        // Parse UUIDs
-        let result: Vec<Uuid> = content_ids
+        let keys: Vec<String> = content_ids
             .iter()
             .filter_map(|s| Uuid::parse_str(s).ok())
-            .take(count)
+            .map(|id| self.entry_key(&id))
             .collect();
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let entry_jsons: Vec<Option<String>> = redis::cmd("MGET")
+            .arg(&keys)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+
+        // Entries expire independently of the sorted set; skip stale members
+        let entries: Vec<NewContentEntry> = entry_jsons
+            .into_iter()
+            .flatten()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+
+        let result = {
+            let policy = self.policy.read().unwrap_or_else(|e| e.into_inner());
+            policy.select(
+                &entries,
+                context,
+                count,
+                self.max_per_author,
+                &mut rand::rng(),
+            )
+        };
 
         info!(
+            policy = self.policy_name(),
             requested = count,
+            window = entries.len(),
             returned = result.len(),
             "Sampled content from exploration pool"
         );
@@ -272,6 +468,75 @@ impl NewContentPool {
         Ok(result)
     }
 
+    /// Persist the LinUCB model so restarts (and new replicas) can load it
+    ///
+    /// Each replica learns from the feedback it receives and the last save
+    /// wins, so with several replicas the stored model reflects one of them.
+    pub async fn save_policy_state(&self) -> Result<bool> {
+        let state = {
+            let policy = self.policy.read().unwrap_or_else(|e| e.into_inner());
+            match &*policy {
+                ExplorationPolicy::LinUcb(model) => Some(
+                    serde_json::to_string(model)
+                        .map_err(|e| ExplorationError::PoolError(e.to_string()))?,
+                ),
+                _ => None,
+            }
+        };
+        let Some(state) = state else {
+            return Ok(false);
+        };
+
+        let mut conn = self
+            .redis
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+        let _: () = conn
+            .set(self.linucb_state_key(), state)
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+
+        Ok(true)
+    }
+
+    /// Replace the in-memory LinUCB model with the persisted one, if compatible
+    pub async fn load_policy_state(&self) -> Result<bool> {
+        if !matches!(
+            &*self.policy.read().unwrap_or_else(|e| e.into_inner()),
+            ExplorationPolicy::LinUcb(_)
+        ) {
+            return Ok(false);
+        }
+
+        let mut conn = self
+            .redis
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+        let state: Option<String> = conn
+            .get(self.linucb_state_key())
+            .await
+            .map_err(|e| ExplorationError::RedisError(e.to_string()))?;
+        let Some(state) = state else {
+            return Ok(false);
+        };
+        let loaded: LinUcb =
+            serde_json::from_str(&state).map_err(|e| ExplorationError::PoolError(e.to_string()))?;
+
+        let mut policy = self.policy.write().unwrap_or_else(|e| e.into_inner());
+        match &mut *policy {
+            ExplorationPolicy::LinUcb(model) if model.same_shape(&loaded) => {
+                *model = loaded;
+                Ok(true)
+            }
+            _ => {
+                warn!("Persisted LinUCB model does not match configured dimensions, ignoring");
+                Ok(false)
+            }
+        }
+    }
+
     /// Update UCB scores for all content in pool
     /// Should be called periodically (e.g., every minute)
     pub async fn refresh_ucb_scores(&self) -> Result<usize> {
@@ -0,0 +1,400 @@
+// ============================================
+// Exploration Policies
+// ============================================
+//
+// Pluggable arm-selection policies for the new content pool:
+//
+// - UCB1:     deterministic optimism bonus (see ucb.rs)
+// - Thompson: sample CTR from each content's Beta posterior
+//               Beta(α₀ + engagements, β₀ + impressions - engagements)
+// - LinUCB:   contextual bandit over user × content features (see linucb.rs)
+//
+// All policies share the same selection step, which caps how many items
+// of one author can be explored per request so a prolific creator cannot
+// crowd out everyone else's new content.
+
+use super::linucb::LinUcb;
+use super::new_content_pool::NewContentEntry;
+use super::ucb::UCBExplorer;
+use super::{ExplorationError, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Default user / content feature dimensions for LinUCB
+pub const DEFAULT_LINUCB_USER_DIM: usize = 4;
+pub const DEFAULT_LINUCB_CONTENT_DIM: usize = 4;
+
+/// Request-time context for contextual policies
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExplorationContext {
+    /// Dense user features (normalized to roughly [0, 1])
+    pub user_features: Vec<f32>,
+}
+
+impl ExplorationContext {
+    pub fn new(user_features: Vec<f32>) -> Self {
+        Self { user_features }
+    }
+}
+
+/// Thompson sampling over Beta posteriors of engagement rate
+#[derive(Debug, Clone)]
+pub struct ThompsonSampler {
+    prior_alpha: f64,
+    prior_beta: f64,
+}
+
+impl Default for ThompsonSampler {
+    fn default() -> Self {
+        // Uniform prior: unseen content is equally likely to be good or bad
+        Self {
+            prior_alpha: 1.0,
+            prior_beta: 1.0,
+        }
+    }
+}
+
+impl ThompsonSampler {
+    pub fn new(prior_alpha: f64, prior_beta: f64) -> Self {
+        Self {
+            prior_alpha: prior_alpha.max(f64::EPSILON),
+            prior_beta: prior_beta.max(f64::EPSILON),
+        }
+    }
+
+    /// Draw one engagement-rate sample from the content's posterior
+    pub fn sample<R: Rng + ?Sized>(&self, impressions: u32, engagements: u32, rng: &mut R) -> f64 {
+        let engagements = engagements.min(impressions);
+        let alpha = self.prior_alpha + engagements as f64;
+        let beta = self.prior_beta + (impressions - engagements) as f64;
+        sample_beta(alpha, beta, rng)
+    }
+}
+
+/// Policy kind, as configured by `EXPLORATION_POLICY`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorationPolicyKind {
+    Ucb,
+    Thompson,
+    LinUcb,
+}
+
+impl FromStr for ExplorationPolicyKind {
+    type Err = ExplorationError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ucb" | "ucb1" => Ok(Self::Ucb),
+            "thompson" => Ok(Self::Thompson),
+            "linucb" => Ok(Self::LinUcb),
+            other => Err(ExplorationError::PoolError(format!(
+                "Unknown exploration policy: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Arm-selection policy used by `NewContentPool`
+pub enum ExplorationPolicy {
+    Ucb(UCBExplorer),
+    Thompson(ThompsonSampler),
+    LinUcb(LinUcb),
+}
+
+impl Default for ExplorationPolicy {
+    fn default() -> Self {
+        ExplorationPolicy::Ucb(UCBExplorer::default())
+    }
+}
+
+impl ExplorationPolicy {
+    /// Build a policy with default parameters
+    pub fn from_kind(kind: ExplorationPolicyKind) -> Self {
+        match kind {
+            ExplorationPolicyKind::Ucb => ExplorationPolicy::Ucb(UCBExplorer::default()),
+            ExplorationPolicyKind::Thompson => {
+                ExplorationPolicy::Thompson(ThompsonSampler::default())
+            }
+            ExplorationPolicyKind::LinUcb => ExplorationPolicy::LinUcb(LinUcb::new(
+                DEFAULT_LINUCB_USER_DIM,
+                DEFAULT_LINUCB_CONTENT_DIM,
+                1.0,
+            )),
+        }
+    }
+
+    /// Read `EXPLORATION_POLICY` (ucb | thompson | linucb) and
+    /// `EXPLORATION_LINUCB_ALPHA`; unknown values fall back to UCB
+    pub fn from_env() -> Self {
+        let kind = match std::env::var("EXPLORATION_POLICY") {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                warn!("{}, falling back to UCB", e);
+                ExplorationPolicyKind::Ucb
+            }),
+            Err(_) => ExplorationPolicyKind::Ucb,
+        };
+        match kind {
+            ExplorationPolicyKind::LinUcb => {
+                let alpha = std::env::var("EXPLORATION_LINUCB_ALPHA")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1.0);
+                ExplorationPolicy::LinUcb(LinUcb::new(
+                    DEFAULT_LINUCB_USER_DIM,
+                    DEFAULT_LINUCB_CONTENT_DIM,
+                    alpha,
+                ))
+            }
+            other => Self::from_kind(other),
+        }
+    }
+
+    pub fn kind(&self) -> ExplorationPolicyKind {
+        match self {
+            ExplorationPolicy::Ucb(_) => ExplorationPolicyKind::Ucb,
+            ExplorationPolicy::Thompson(_) => ExplorationPolicyKind::Thompson,
+            ExplorationPolicy::LinUcb(_) => ExplorationPolicyKind::LinUcb,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExplorationPolicy::Ucb(_) => "ucb",
+            ExplorationPolicy::Thompson(_) => "thompson",
+            ExplorationPolicy::LinUcb(_) => "linucb",
+        }
+    }
+
+    /// Selection score for one content item (higher = explore first)
+    pub fn score<R: Rng + ?Sized>(
+        &self,
+        entry: &NewContentEntry,
+        total_impressions: u32,
+        context: &ExplorationContext,
+        rng: &mut R,
+    ) -> f64 {
+        match self {
+            ExplorationPolicy::Ucb(ucb) => {
+                ucb.ucb_score(entry.impressions, entry.engagements, total_impressions)
+            }
+            ExplorationPolicy::Thompson(ts) => ts.sample(entry.impressions, entry.engagements, rng),
+            ExplorationPolicy::LinUcb(model) => {
+                model.score(&context.user_features, &entry.features)
+            }
+        }
+    }
+
+    /// Learn from one impression outcome
+    ///
+    /// UCB and Thompson read their statistics from the pool entries, so only
+    /// LinUCB keeps state of its own.
+    pub fn observe(
+        &mut self,
+        entry: &NewContentEntry,
+        context: &ExplorationContext,
+        engaged: bool,
+    ) {
+        if let ExplorationPolicy::LinUcb(model) = self {
+            let reward = if engaged { 1.0 } else { 0.0 };
+            model.update(&context.user_features, &entry.features, reward);
+        }
+    }
+
+    /// Pick up to `count` items, at most `max_per_author` from any author
+    pub fn select<R: Rng + ?Sized>(
+        &self,
+        pool: &[NewContentEntry],
+        context: &ExplorationContext,
+        count: usize,
+        max_per_author: Option<usize>,
+        rng: &mut R,
+    ) -> Vec<Uuid> {
+        if pool.is_empty() || count == 0 {
+            return Vec::new();
+        }
+
+        let total_impressions: u32 = pool.iter().map(|e| e.impressions).sum();
+        let mut scored: Vec<(&NewContentEntry, f64)> = pool
+            .iter()
+            .filter(|e| e.is_active)
+            .map(|e| (e, self.score(e, total_impressions, context, rng)))
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut per_author: HashMap<Uuid, usize> = HashMap::new();
+        let mut selected = Vec::with_capacity(count);
+        for (entry, _) in scored {
+            if selected.len() >= count {
+                break;
+            }
+            let shown = per_author.entry(entry.author_id).or_insert(0);
+            if max_per_author.is_some_and(|cap| *shown >= cap) {
+                continue;
+            }
+            *shown += 1;
+            selected.push(entry.content_id);
+        }
+
+        debug!(
+            policy = self.name(),
+            pool_size = pool.len(),
+            selected = selected.len(),
+            authors = per_author.len(),
+            "Exploration selection completed"
+        );
+
+        selected
+    }
+}
+
+/// Sample from Beta(alpha, beta) as X / (X + Y) with X, Y ~ Gamma
+pub fn sample_beta<R: Rng + ?Sized>(alpha: f64, beta: f64, rng: &mut R) -> f64 {
+    let x = sample_gamma(alpha, rng);
+    let y = sample_gamma(beta, rng);
+    if x + y > 0.0 {
+        x / (x + y)
+    } else {
+        alpha / (alpha + beta)
+    }
+}
+
+/// Gamma(shape, 1) via Marsaglia & Tsang (2000)
+fn sample_gamma<R: Rng + ?Sized>(shape: f64, rng: &mut R) -> f64 {
+    if shape < 1.0 {
+        // Boost to shape + 1 and scale back down
+        let u: f64 = rng.random();
+        return sample_gamma(shape + 1.0, rng) * u.powf(1.0 / shape);
+    }
+
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let z = standard_normal(rng);
+        let v = (1.0 + c * z).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u: f64 = rng.random();
+        if u.ln() < 0.5 * z * z + d - d * v + d * v.ln() {
+            return d * v;
+        }
+    }
+}
+
+/// Standard normal via Box-Muller
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.random::<f64>(); // (0, 1]
+    let u2: f64 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn entry(author_id: Uuid, impressions: u32, engagements: u32) -> NewContentEntry {
+        let mut entry = NewContentEntry::new(Uuid::new_v4(), author_id);
+        entry.impressions = impressions;
+        entry.engagements = engagements;
+        entry
+    }
+
+    #[test]
+    fn test_beta_samples_match_posterior_mean() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let n = 20_000;
+        for (alpha, beta) in [(2.0, 8.0), (0.5, 0.5), (30.0, 10.0)] {
+            let mean = (0..n)
+                .map(|_| sample_beta(alpha, beta, &mut rng))
+                .sum::<f64>()
+                / n as f64;
+            let expected = alpha / (alpha + beta);
+            assert!(
+                (mean - expected).abs() < 0.01,
+                "Beta({}, {}) mean {} != {}",
+                alpha,
+                beta,
+                mean,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_thompson_prefers_better_arm_but_still_explores() {
+        let policy = ExplorationPolicy::Thompson(ThompsonSampler::default());
+        let good = entry(Uuid::new_v4(), 100, 30);
+        let bad = entry(Uuid::new_v4(), 100, 5);
+        let uncertain = entry(Uuid::new_v4(), 2, 0);
+        let pool = vec![good.clone(), bad, uncertain.clone()];
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut firsts: HashMap<Uuid, usize> = HashMap::new();
+        for _ in 0..1000 {
+            let pick = policy.select(&pool, &ExplorationContext::default(), 1, None, &mut rng);
+            *firsts.entry(pick[0]).or_default() += 1;
+        }
+
+        assert!(firsts[&good.content_id] > 500);
+        assert!(firsts.get(&uncertain.content_id).copied().unwrap_or(0) > 0);
+    }
+
+    #[test]
+    fn test_per_author_cap() {
+        let prolific = Uuid::new_v4();
+        let mut pool: Vec<NewContentEntry> = (0..5).map(|_| entry(prolific, 0, 0)).collect();
+        let other = entry(Uuid::new_v4(), 50, 5);
+        pool.push(other.clone());
+
+        let policy = ExplorationPolicy::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        let context = ExplorationContext::default();
+
+        let uncapped = policy.select(&pool, &context, 3, None, &mut rng);
+        assert!(!uncapped.contains(&other.content_id));
+
+        let capped = policy.select(&pool, &context, 3, Some(1), &mut rng);
+        assert_eq!(capped.len(), 2);
+        assert!(capped.contains(&other.content_id));
+    }
+
+    #[test]
+    fn test_linucb_policy_learns_from_observations() {
+        let mut policy = ExplorationPolicy::from_kind(ExplorationPolicyKind::LinUcb);
+        let mut video = entry(Uuid::new_v4(), 0, 0);
+        video.features = vec![1.0, 0.0];
+        let mut photo = entry(Uuid::new_v4(), 0, 0);
+        photo.features = vec![0.0, 1.0];
+        let context = ExplorationContext::new(vec![1.0]);
+
+        for _ in 0..100 {
+            policy.observe(&video, &context, true);
+            policy.observe(&photo, &context, false);
+        }
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let pool = vec![photo, video.clone()];
+        let pick = policy.select(&pool, &context, 1, None, &mut rng);
+        assert_eq!(pick, vec![video.content_id]);
+    }
+
+    #[test]
+    fn test_policy_kind_parsing() {
+        assert_eq!(
+            "Thompson".parse::<ExplorationPolicyKind>().unwrap(),
+            ExplorationPolicyKind::Thompson
+        );
+        assert_eq!(
+            "linucb".parse::<ExplorationPolicyKind>().unwrap(),
+            ExplorationPolicyKind::LinUcb
+        );
+        assert!("epsilon".parse::<ExplorationPolicyKind>().is_err());
+    }
+}
@@ -0,0 +1,224 @@
+// ============================================
+// Offline Replay Evaluation
+// ============================================
+//
+// Evaluates exploration policies against logged impressions using the
+// replay method (Li et al., 2011):
+//
+// 1. For each logged event, the policy picks one item from the candidates
+//    that were eligible at serving time.
+// 2. If it matches the item actually shown, the logged reward counts and
+//    the policy learns from it; otherwise the event is skipped.
+//
+// The matched-event CTR is unbiased when the logging policy picked
+// uniformly at random. For non-uniform logging, the IPS estimate reweights
+// each match by 1 / propensity of the logged choice.
+
+use super::new_content_pool::NewContentEntry;
+use super::policy::{ExplorationContext, ExplorationPolicy, ExplorationPolicyKind};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::info;
+use uuid::Uuid;
+
+/// Content eligible for exploration when an impression was logged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedCandidate {
+    pub content_id: Uuid,
+    pub author_id: Uuid,
+    #[serde(default)]
+    pub features: Vec<f32>,
+}
+
+/// One logged exploration impression
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedImpression {
+    #[serde(default)]
+    pub user_features: Vec<f32>,
+    pub candidates: Vec<LoggedCandidate>,
+    /// Item the logging policy showed
+    pub shown: Uuid,
+    pub engaged: bool,
+    /// Probability the logging policy chose `shown` (uniform if absent)
+    #[serde(default)]
+    pub propensity: Option<f64>,
+}
+
+/// Replay outcome for one policy
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    pub policy: String,
+    pub events: usize,
+    /// Events where the policy agreed with the logged choice
+    pub matched: usize,
+    pub engagements: usize,
+    /// Engagement rate over matched events
+    pub ctr: f64,
+    /// Inverse-propensity-weighted engagement rate over all events
+    pub ips_ctr: f64,
+}
+
+/// Replay `events` through `policy`, which learns from matched events only
+pub fn replay<R: Rng + ?Sized>(
+    policy: &mut ExplorationPolicy,
+    events: &[LoggedImpression],
+    rng: &mut R,
+) -> ReplayReport {
+    // Per-content statistics as the policy would have seen them
+    let mut stats: HashMap<Uuid, (u32, u32)> = HashMap::new();
+    let mut matched = 0usize;
+    let mut engagements = 0usize;
+    let mut ips_sum = 0.0f64;
+
+    for event in events {
+        if event.candidates.is_empty() {
+            continue;
+        }
+
+        let pool: Vec<NewContentEntry> = event
+            .candidates
+            .iter()
+            .map(|c| {
+                let (impressions, engaged) = stats.get(&c.content_id).copied().unwrap_or((0, 0));
+                let mut entry = NewContentEntry::new(c.content_id, c.author_id);
+                entry.impressions = impressions;
+                entry.engagements = engaged;
+                entry.features = c.features.clone();
+                entry
+            })
+            .collect();
+        let context = ExplorationContext::new(event.user_features.clone());
+
+        let picked = policy.select(&pool, &context, 1, None, rng);
+        if picked.first() != Some(&event.shown) {
+            continue;
+        }
+
+        matched += 1;
+        let stat = stats.entry(event.shown).or_insert((0, 0));
+        stat.0 += 1;
+        if event.engaged {
+            stat.1 += 1;
+            engagements += 1;
+            let propensity = event
+                .propensity
+                .filter(|p| *p > 0.0)
+                .unwrap_or(1.0 / event.candidates.len() as f64);
+            ips_sum += 1.0 / propensity;
+        }
+
+        if let Some(entry) = pool.iter().find(|e| e.content_id == event.shown) {
+            policy.observe(entry, &context, event.engaged);
+        }
+    }
+
+    let report = ReplayReport {
+        policy: policy.name().to_string(),
+        events: events.len(),
+        matched,
+        engagements,
+        ctr: if matched > 0 {
+            engagements as f64 / matched as f64
+        } else {
+            0.0
+        },
+        ips_ctr: if events.is_empty() {
+            0.0
+        } else {
+            ips_sum / events.len() as f64
+        },
+    };
+
+    info!(
+        policy = %report.policy,
+        events = report.events,
+        matched = report.matched,
+        ctr = report.ctr,
+        ips_ctr = report.ips_ctr,
+        "Replay evaluation completed"
+    );
+
+    report
+}
+
+/// Replay the same events through fresh policies of each kind
+pub fn compare_policies(
+    kinds: &[ExplorationPolicyKind],
+    events: &[LoggedImpression],
+    seed: u64,
+) -> Vec<ReplayReport> {
+    kinds
+        .iter()
+        .map(|&kind| {
+            let mut policy = ExplorationPolicy::from_kind(kind);
+            let mut rng = StdRng::seed_from_u64(seed);
+            replay(&mut policy, events, &mut rng)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Uniform-random logs over arms with fixed engagement rates
+    fn synthetic_log(rates: &[f64], n: usize, seed: u64) -> Vec<LoggedImpression> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let candidates: Vec<LoggedCandidate> = rates
+            .iter()
+            .map(|_| LoggedCandidate {
+                content_id: Uuid::new_v4(),
+                author_id: Uuid::new_v4(),
+                features: Vec::new(),
+            })
+            .collect();
+
+        (0..n)
+            .map(|_| {
+                let arm = rng.random_range(0..rates.len());
+                LoggedImpression {
+                    user_features: Vec::new(),
+                    candidates: candidates.clone(),
+                    shown: candidates[arm].content_id,
+                    engaged: rng.random::<f64>() < rates[arm],
+                    propensity: None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_learning_policies_beat_average_arm() {
+        let rates = [0.02, 0.05, 0.30];
+        let events = synthetic_log(&rates, 20_000, 11);
+        let reports = compare_policies(
+            &[ExplorationPolicyKind::Ucb, ExplorationPolicyKind::Thompson],
+            &events,
+            5,
+        );
+
+        let average = rates.iter().sum::<f64>() / rates.len() as f64;
+        for report in &reports {
+            assert_eq!(report.events, events.len());
+            assert!(report.matched > 0);
+            assert!(
+                report.ctr > average,
+                "{} replay CTR {} should beat uniform {}",
+                report.policy,
+                report.ctr,
+                average
+            );
+        }
+    }
+
+    #[test]
+    fn test_empty_log() {
+        let mut policy = ExplorationPolicy::default();
+        let report = replay(&mut policy, &[], &mut StdRng::seed_from_u64(0));
+        assert_eq!(report.matched, 0);
+        assert_eq!(report.ctr, 0.0);
+        assert_eq!(report.ips_ctr, 0.0);
+    }
+}
@@ -186,6 +186,8 @@ impl UCBExplorer {
     /// Beta(α, β) where:
     /// - α = engagements + 1 (successes)
     /// - β = impressions - engagements + 1 (failures)
+    ///
+    /// Returns the posterior mean; `ThompsonSampler` draws real samples.
     pub fn thompson_sample(&self, impressions: u32, engagements: u32) -> f64 {
         // Use mean of Beta distribution as deterministic approximation
         // Mean = α / (α + β)
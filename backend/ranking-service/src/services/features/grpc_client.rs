@@ -153,6 +153,69 @@ impl GrpcFeatureClient {
         Ok(result)
     }
 
+    /// Get arbitrary numeric features for one entity (missing/non-numeric omitted)
+    pub async fn get_numeric_features(
+        &self,
+        entity_type: &str,
+        entity_id: &str,
+        feature_names: &[String],
+    ) -> Result<HashMap<String, f32>> {
+        let mut client = self.client.clone();
+
+        let request = GetFeaturesRequest {
+            entity_id: entity_id.to_string(),
+            entity_type: entity_type.to_string(),
+            feature_names: feature_names.to_vec(),
+        };
+
+        let response = client.get_features(request).await?;
+        let features = response.into_inner().features;
+
+        Ok(feature_names
+            .iter()
+            .filter_map(|name| {
+                extract_double_feature(&features, name).map(|v| (name.clone(), v as f32))
+            })
+            .collect())
+    }
+
+    /// Batch get arbitrary numeric features (missing/non-numeric omitted)
+    pub async fn batch_get_numeric_features(
+        &self,
+        entity_type: &str,
+        entity_ids: &[String],
+        feature_names: &[String],
+    ) -> Result<HashMap<String, HashMap<String, f32>>> {
+        if entity_ids.is_empty() || feature_names.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut client = self.client.clone();
+
+        let request = BatchGetFeaturesRequest {
+            entity_ids: entity_ids.to_vec(),
+            entity_type: entity_type.to_string(),
+            feature_names: feature_names.to_vec(),
+        };
+
+        let response = client.batch_get_features(request).await?;
+        let entities = response.into_inner().entities;
+
+        Ok(entities
+            .into_iter()
+            .map(|(entity_id, entity_features)| {
+                let values = feature_names
+                    .iter()
+                    .filter_map(|name| {
+                        extract_double_feature(&entity_features.features, name)
+                            .map(|v| (name.clone(), v as f32))
+                    })
+                    .collect();
+                (entity_id, values)
+            })
+            .collect())
+    }
+
     /// Get content features for ranking (combines post and author features)
     pub async fn batch_get_content_features(
         &self,
@@ -3,10 +3,12 @@ pub mod content_classifier;
 pub mod diversity;
 pub mod exploration;
 pub mod features;
+pub mod pipeline_replay;
 pub mod profile_builder;
 pub mod ranking;
 pub mod realtime;
 pub mod recall;
+pub mod trace;
 pub mod user_memory;
 
 pub use coarse_ranking::{CoarseCandidate, CoarseRankingLayer, CoarseWeights, UserFeatures};
@@ -0,0 +1,188 @@
+// ============================================
+// Offline Replay Metrics
+// ============================================
+//
+// Metrics computed over the top-k of each replayed feed:
+// - recall@k: share of engaged posts the new ranking still places in top-k
+// - NDCG@k: graded by logged reward; unshown posts count as reward 0
+// - coverage: distinct recommended posts / distinct logged candidates
+// - author diversity: distinct authors / posts with a known author
+// - IPS engagement: expected reward per request, reweighting logged
+//   outcomes by 1 / propensity (clipped); SNIPS is the self-normalized
+//   per-impression variant
+
+use super::LoggedFeedRequest;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Aggregate metrics of one pipeline configuration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReplayMetrics {
+    pub requests: usize,
+    pub k: usize,
+    pub recall_at_k: f64,
+    pub ndcg_at_k: f64,
+    pub coverage: f64,
+    pub author_diversity: f64,
+    pub ips_engagement: f64,
+    pub snips_engagement: f64,
+}
+
+impl ReplayMetrics {
+    /// Metric values by name, for diffing
+    pub fn values(&self) -> [(&'static str, f64); 6] {
+        [
+            ("recall_at_k", self.recall_at_k),
+            ("ndcg_at_k", self.ndcg_at_k),
+            ("coverage", self.coverage),
+            ("author_diversity", self.author_diversity),
+            ("ips_engagement", self.ips_engagement),
+            ("snips_engagement", self.snips_engagement),
+        ]
+    }
+
+    /// `other - self` for every metric
+    pub fn delta(&self, other: &ReplayMetrics) -> BTreeMap<String, f64> {
+        self.values()
+            .iter()
+            .zip(other.values())
+            .map(|((name, base), (_, value))| (name.to_string(), value - base))
+            .collect()
+    }
+}
+
+/// Per-request accumulation of `ReplayMetrics`
+#[derive(Debug)]
+pub struct MetricsAccumulator {
+    k: usize,
+    max_importance_weight: f64,
+    requests: usize,
+    recall: Mean,
+    ndcg: Mean,
+    author_diversity: Mean,
+    /// Σ w·r, the numerator of both IPS and SNIPS
+    weighted_reward_sum: f64,
+    weight_sum: f64,
+    recommended: HashSet<String>,
+    catalog: HashSet<String>,
+}
+
+impl MetricsAccumulator {
+    pub fn new(k: usize, max_importance_weight: f64) -> Self {
+        Self {
+            k,
+            max_importance_weight,
+            requests: 0,
+            recall: Mean::default(),
+            ndcg: Mean::default(),
+            author_diversity: Mean::default(),
+            weighted_reward_sum: 0.0,
+            weight_sum: 0.0,
+            recommended: HashSet::new(),
+            catalog: HashSet::new(),
+        }
+    }
+
+    /// Add one replayed ranking (post ids in feed order)
+    pub fn add(&mut self, request: &LoggedFeedRequest, ranked: &[String]) {
+        let top_k = &ranked[..ranked.len().min(self.k)];
+        let in_top_k: HashSet<&str> = top_k.iter().map(|s| s.as_str()).collect();
+        let rewards: HashMap<&str, f64> = request
+            .outcomes
+            .iter()
+            .map(|o| (o.post_id.as_str(), o.reward() as f64))
+            .collect();
+
+        self.requests += 1;
+        self.catalog.extend(request.post_ids());
+        self.recommended.extend(top_k.iter().cloned());
+
+        // recall@k
+        let engaged: Vec<&str> = rewards
+            .iter()
+            .filter(|(_, &r)| r > 0.0)
+            .map(|(&id, _)| id)
+            .collect();
+        if !engaged.is_empty() {
+            let hits = engaged.iter().filter(|id| in_top_k.contains(*id)).count();
+            self.recall.add(hits as f64 / engaged.len() as f64);
+        }
+
+        // NDCG@k
+        let gains: Vec<f64> = top_k
+            .iter()
+            .map(|id| rewards.get(id.as_str()).copied().unwrap_or(0.0))
+            .collect();
+        let mut ideal: Vec<f64> = rewards.values().copied().collect();
+        ideal.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        ideal.truncate(self.k);
+        let idcg = dcg(&ideal);
+        if idcg > 0.0 {
+            self.ndcg.add(dcg(&gains) / idcg);
+        }
+
+        // Author diversity
+        let authors = request.authors();
+        let known: Vec<_> = top_k.iter().filter_map(|id| authors.get(id)).collect();
+        if !known.is_empty() {
+            let distinct: HashSet<_> = known.iter().collect();
+            self.author_diversity
+                .add(distinct.len() as f64 / known.len() as f64);
+        }
+
+        // IPS / SNIPS over logged outcomes the new ranking also shows
+        for outcome in &request.outcomes {
+            if !in_top_k.contains(outcome.post_id.as_str()) {
+                continue;
+            }
+            let propensity = outcome.propensity.filter(|p| *p > 0.0).unwrap_or(1.0);
+            let weight = (1.0 / propensity).min(self.max_importance_weight);
+            let reward = outcome.reward() as f64;
+            self.weighted_reward_sum += weight * reward;
+            self.weight_sum += weight;
+        }
+    }
+
+    pub fn finish(self) -> ReplayMetrics {
+        let ratio = |num: f64, den: f64| if den > 0.0 { num / den } else { 0.0 };
+        ReplayMetrics {
+            requests: self.requests,
+            k: self.k,
+            recall_at_k: self.recall.value(),
+            ndcg_at_k: self.ndcg.value(),
+            coverage: ratio(self.recommended.len() as f64, self.catalog.len() as f64),
+            author_diversity: self.author_diversity.value(),
+            ips_engagement: ratio(self.weighted_reward_sum, self.requests as f64),
+            snips_engagement: ratio(self.weighted_reward_sum, self.weight_sum),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Mean {
+    sum: f64,
+    count: usize,
+}
+
+impl Mean {
+    fn add(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn value(&self) -> f64 {
+        if self.count > 0 {
+            self.sum / self.count as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+fn dcg(gains: &[f64]) -> f64 {
+    gains
+        .iter()
+        .enumerate()
+        .map(|(i, g)| g / (i as f64 + 2.0).log2())
+        .sum()
+}
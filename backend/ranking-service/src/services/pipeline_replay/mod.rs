@@ -0,0 +1,675 @@
+// ============================================
+// Pipeline Replay (離線回放評估)
+// ============================================
+//
+// Re-runs the recall -> coarse -> fine -> exploration -> diversity
+// pipeline over logged feed requests, so a change to any layer can be
+// evaluated offline before it reaches production.
+//
+// Each logged request carries everything the online pipeline fetched:
+// per-strategy recall output, content features, the exploration pool and
+// the outcomes of the posts that were actually shown. Replays use the same
+// layer implementations as `RankFeed`; only the I/O is replaced by the log.
+//
+// Candidate ages are preserved by shifting timestamps to the replay clock,
+// so recency scoring matches serving time.
+//
+// `compare` replays the same log under a baseline and a candidate
+// configuration and reports both metric sets with their difference.
+
+pub mod metrics;
+
+pub use metrics::{MetricsAccumulator, ReplayMetrics};
+
+use crate::models::{Candidate, RankedPost, RecallSource};
+use crate::services::coarse_ranking::{
+    CoarseCandidate, CoarseRankingLayer, CoarseWeights, UserFeatures,
+};
+use crate::services::diversity::DiversityLayer;
+use crate::services::exploration::{
+    interleave_exploration, ExplorationContext, ExplorationPolicy, ExplorationPolicyKind,
+    NewContentEntry,
+};
+use crate::services::features::{ContentFeatures, FeatureClient};
+use crate::services::ranking::simple::RankingWeights;
+use crate::services::ranking::RankingLayer;
+use crate::services::recall::deduplicate_candidates;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::info;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Invalid replay config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Failed to read replay log: {0}")]
+    LogError(String),
+
+    #[error("Pipeline error: {0}")]
+    PipelineError(String),
+}
+
+pub type Result<T> = std::result::Result<T, ReplayError>;
+
+// ============================================
+// Logged Requests
+// ============================================
+
+/// Recall candidate with the features fetched for it at serving time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedRecallCandidate {
+    pub post_id: String,
+    pub recall_source: RecallSource,
+    pub recall_weight: f32,
+    /// Post creation time (unix seconds)
+    pub timestamp: i64,
+    #[serde(default)]
+    pub author_id: Option<Uuid>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "default_content_type")]
+    pub content_type: String,
+    #[serde(default = "default_quality")]
+    pub author_quality: f32,
+    #[serde(default = "default_quality")]
+    pub content_quality: f32,
+    #[serde(default = "default_quality")]
+    pub completion_rate: f32,
+}
+
+fn default_content_type() -> String {
+    "video".to_string()
+}
+
+fn default_quality() -> f32 {
+    0.5
+}
+
+/// User features the coarse ranker saw
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggedUserFeatures {
+    #[serde(default)]
+    pub interest_tags: Vec<String>,
+    #[serde(default)]
+    pub content_type_preferences: Vec<String>,
+    #[serde(default)]
+    pub followed_authors: Vec<Uuid>,
+}
+
+/// What happened to a post that was shown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedOutcome {
+    pub post_id: String,
+    pub engaged: bool,
+    /// Graded reward (defaults to 1.0 when engaged, else 0.0)
+    #[serde(default)]
+    pub reward: Option<f32>,
+    /// Probability the serving pipeline showed this post (1.0 if absent)
+    #[serde(default)]
+    pub propensity: Option<f64>,
+}
+
+impl LoggedOutcome {
+    pub fn reward(&self) -> f32 {
+        self.reward.unwrap_or(if self.engaged { 1.0 } else { 0.0 })
+    }
+}
+
+/// One logged RankFeed request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedFeedRequest {
+    pub request_id: String,
+    pub user_id: String,
+    /// Serving time (unix seconds)
+    pub timestamp: i64,
+    pub limit: usize,
+    #[serde(default)]
+    pub user_features: LoggedUserFeatures,
+    /// Recall output in strategy order
+    pub candidates: Vec<LoggedRecallCandidate>,
+    /// Exploration pool entries eligible at serving time
+    #[serde(default)]
+    pub exploration_pool: Vec<NewContentEntry>,
+    pub outcomes: Vec<LoggedOutcome>,
+}
+
+impl LoggedFeedRequest {
+    /// Every post the pipeline could have returned
+    pub fn post_ids(&self) -> Vec<String> {
+        self.candidates
+            .iter()
+            .map(|c| c.post_id.clone())
+            .chain(
+                self.exploration_pool
+                    .iter()
+                    .map(|e| e.content_id.to_string()),
+            )
+            .collect()
+    }
+
+    /// Author of every post with a known author
+    pub fn authors(&self) -> HashMap<String, Uuid> {
+        self.candidates
+            .iter()
+            .filter_map(|c| Some((c.post_id.clone(), c.author_id?)))
+            .chain(
+                self.exploration_pool
+                    .iter()
+                    .map(|e| (e.content_id.to_string(), e.author_id)),
+            )
+            .collect()
+    }
+}
+
+/// Read a JSON Lines log of feed requests
+pub fn load_requests<P: AsRef<Path>>(path: P) -> Result<Vec<LoggedFeedRequest>> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| ReplayError::LogError(format!("{}: {}", path.display(), e)))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                ReplayError::LogError(format!("{} line {}: {}", path.display(), i + 1, e))
+            })
+        })
+        .collect()
+}
+
+// ============================================
+// Pipeline Configuration
+// ============================================
+
+/// Pipeline settings under evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayPipelineConfig {
+    /// Max candidates kept per recall source (e.g. `{"trending": 50}`);
+    /// unlisted sources are unlimited, 0 disables a source
+    pub recall_source_limits: BTreeMap<String, usize>,
+    pub coarse_limit: usize,
+    pub coarse_weights: CoarseWeights,
+    pub min_coarse_score: f32,
+    pub ranking_weights: RankingWeights,
+    pub fine_limit: usize,
+    pub diversity_lambda: f32,
+    pub max_consecutive_from_author: usize,
+    pub exploration_ratio: f32,
+    /// "ucb" | "thompson" | "linucb"
+    pub exploration_policy: String,
+    pub exploration_max_per_author: Option<usize>,
+}
+
+impl Default for ReplayPipelineConfig {
+    /// Mirrors the serving defaults of `RankingServiceImpl::new`
+    fn default() -> Self {
+        Self {
+            recall_source_limits: BTreeMap::new(),
+            coarse_limit: 1000,
+            coarse_weights: CoarseWeights::default(),
+            min_coarse_score: 0.1,
+            ranking_weights: RankingWeights::default(),
+            fine_limit: 100,
+            diversity_lambda: 0.7,
+            max_consecutive_from_author: 2,
+            exploration_ratio: 0.1,
+            exploration_policy: "ucb".to_string(),
+            exploration_max_per_author: Some(2),
+        }
+    }
+}
+
+impl ReplayPipelineConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| ReplayError::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+        let config: Self = serde_json::from_slice(&bytes)
+            .map_err(|e| ReplayError::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+        config.policy_kind()?;
+        Ok(config)
+    }
+
+    fn policy_kind(&self) -> Result<ExplorationPolicyKind> {
+        self.exploration_policy
+            .parse()
+            .map_err(|e| ReplayError::InvalidConfig(format!("{}", e)))
+    }
+}
+
+// ============================================
+// Replayer
+// ============================================
+
+/// Runs the ranking pipeline of one configuration over logged requests
+pub struct PipelineReplayer {
+    config: ReplayPipelineConfig,
+    coarse_ranking_layer: CoarseRankingLayer,
+    ranking_layer: RankingLayer,
+    diversity_layer: DiversityLayer,
+    policy: ExplorationPolicy,
+    rng: StdRng,
+}
+
+impl PipelineReplayer {
+    pub fn new(config: ReplayPipelineConfig, seed: u64) -> Result<Self> {
+        let policy = ExplorationPolicy::from_kind(config.policy_kind()?);
+        // Features come from the log; the client is never queried
+        let redis_client = redis::Client::open("redis://localhost:6379")
+            .map_err(|e| ReplayError::PipelineError(e.to_string()))?;
+
+        Ok(Self {
+            coarse_ranking_layer: CoarseRankingLayer::with_weights(
+                config.coarse_limit,
+                config.coarse_weights.clone(),
+            )
+            .with_min_threshold(config.min_coarse_score),
+            ranking_layer: RankingLayer::with_weights(
+                Arc::new(FeatureClient::new(redis_client)),
+                config.ranking_weights.clone(),
+            ),
+            diversity_layer: DiversityLayer::with_author_limit(
+                config.diversity_lambda,
+                config.max_consecutive_from_author,
+            ),
+            policy,
+            rng: StdRng::seed_from_u64(seed),
+            config,
+        })
+    }
+
+    /// Replay one request, returning the feed it would have served
+    pub fn replay(&mut self, request: &LoggedFeedRequest) -> Result<Vec<RankedPost>> {
+        let age_shift = chrono::Utc::now().timestamp() - request.timestamp;
+        let followed: std::collections::HashSet<Uuid> = request
+            .user_features
+            .followed_authors
+            .iter()
+            .copied()
+            .collect();
+
+        // Layer 1: Recall (apply per-source limits, then dedupe)
+        let mut per_source: HashMap<&'static str, usize> = HashMap::new();
+        let recalled: Vec<Candidate> = request
+            .candidates
+            .iter()
+            .filter(|c| {
+                let source = c.recall_source.as_str();
+                let taken = per_source.entry(source).or_default();
+                let limit = self
+                    .config
+                    .recall_source_limits
+                    .get(source)
+                    .copied()
+                    .unwrap_or(usize::MAX);
+                *taken += 1;
+                *taken <= limit
+            })
+            .map(|c| Candidate {
+                post_id: c.post_id.clone(),
+                recall_source: c.recall_source.clone(),
+                recall_weight: c.recall_weight,
+                timestamp: c.timestamp + age_shift,
+            })
+            .collect();
+        let recalled = deduplicate_candidates(recalled);
+
+        // Layer 2: Coarse ranking
+        let logged: HashMap<&str, &LoggedRecallCandidate> = request
+            .candidates
+            .iter()
+            .map(|c| (c.post_id.as_str(), c))
+            .collect();
+        let coarse_candidates: Vec<CoarseCandidate> = recalled
+            .into_iter()
+            .map(|candidate| {
+                let logged = logged[candidate.post_id.as_str()];
+                CoarseCandidate {
+                    tags: logged.tags.clone(),
+                    content_type: logged.content_type.clone(),
+                    author_quality: logged.author_quality,
+                    author_id: logged.author_id,
+                    is_followed_author: logged.author_id.is_some_and(|a| followed.contains(&a)),
+                    candidate,
+                }
+            })
+            .collect();
+        let user_features = UserFeatures {
+            interest_tags: request.user_features.interest_tags.clone(),
+            content_type_preferences: request.user_features.content_type_preferences.clone(),
+            followed_authors: followed,
+            ..Default::default()
+        };
+        let coarse_ranked = self
+            .coarse_ranking_layer
+            .rank(coarse_candidates, &user_features)
+            .map_err(|e| ReplayError::PipelineError(e.to_string()))?;
+
+        // Layer 3: Fine ranking with logged features
+        let features: HashMap<String, ContentFeatures> = request
+            .candidates
+            .iter()
+            .map(|c| {
+                (
+                    c.post_id.clone(),
+                    ContentFeatures {
+                        content_quality: c.content_quality,
+                        author_quality: c.author_quality,
+                        author_id: c.author_id,
+                        completion_rate: c.completion_rate,
+                    },
+                )
+            })
+            .collect();
+        let fine_ranked: Vec<RankedPost> = self
+            .ranking_layer
+            .rank_with_features(
+                coarse_ranked.into_iter().map(|cc| cc.candidate).collect(),
+                &features,
+            )
+            .into_iter()
+            .take(self.config.fine_limit)
+            .collect();
+
+        // Exploration injection from the logged pool
+        let exploration_count = (request.limit as f32 * self.config.exploration_ratio) as usize;
+        let context = ExplorationContext::new(Vec::new());
+        let exploration_ids = self.policy.select(
+            &request.exploration_pool,
+            &context,
+            exploration_count,
+            self.config.exploration_max_per_author,
+            &mut self.rng,
+        );
+        self.observe_exploration(request, &exploration_ids, &context);
+        let with_exploration = interleave_exploration(fine_ranked, &exploration_ids);
+
+        // Layer 4: Diversity
+        Ok(self.diversity_layer.rerank(with_exploration, request.limit))
+    }
+
+    /// Let the policy learn from logged outcomes of the content it picked
+    fn observe_exploration(
+        &mut self,
+        request: &LoggedFeedRequest,
+        picked: &[Uuid],
+        context: &ExplorationContext,
+    ) {
+        for id in picked {
+            let id_str = id.to_string();
+            let outcome = request.outcomes.iter().find(|o| o.post_id == id_str);
+            let entry = request
+                .exploration_pool
+                .iter()
+                .find(|e| e.content_id == *id);
+            if let (Some(outcome), Some(entry)) = (outcome, entry) {
+                self.policy.observe(entry, context, outcome.engaged);
+            }
+        }
+    }
+}
+
+// ============================================
+// Evaluation
+// ============================================
+
+/// Shared evaluation settings
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    /// Cut-off for ranking metrics
+    pub k: usize,
+    /// Clip for inverse-propensity weights
+    pub max_importance_weight: f64,
+    pub seed: u64,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            k: 10,
+            max_importance_weight: 10.0,
+            seed: 42,
+        }
+    }
+}
+
+/// Replay all requests under `config`, returning metrics and the rankings
+pub fn evaluate(
+    config: &ReplayPipelineConfig,
+    requests: &[LoggedFeedRequest],
+    options: &ReplayOptions,
+) -> Result<(ReplayMetrics, Vec<Vec<String>>)> {
+    let mut replayer = PipelineReplayer::new(config.clone(), options.seed)?;
+    let mut metrics = MetricsAccumulator::new(options.k, options.max_importance_weight);
+    let mut rankings = Vec::with_capacity(requests.len());
+
+    for request in requests {
+        let ranked: Vec<String> = replayer
+            .replay(request)?
+            .into_iter()
+            .map(|p| p.post_id)
+            .collect();
+        metrics.add(request, &ranked);
+        rankings.push(ranked);
+    }
+
+    Ok((metrics.finish(), rankings))
+}
+
+/// Baseline vs candidate metrics over the same log
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayComparison {
+    pub baseline: ReplayMetrics,
+    pub candidate: ReplayMetrics,
+    /// candidate - baseline, per metric
+    pub delta: BTreeMap<String, f64>,
+    /// Requests whose top-k differs between the two configurations
+    pub changed_requests: usize,
+}
+
+pub fn compare(
+    baseline: &ReplayPipelineConfig,
+    candidate: &ReplayPipelineConfig,
+    requests: &[LoggedFeedRequest],
+    options: &ReplayOptions,
+) -> Result<ReplayComparison> {
+    let (baseline_metrics, baseline_rankings) = evaluate(baseline, requests, options)?;
+    let (candidate_metrics, candidate_rankings) = evaluate(candidate, requests, options)?;
+
+    let top_k = |ranking: &[String]| ranking[..ranking.len().min(options.k)].to_vec();
+    let changed_requests = baseline_rankings
+        .iter()
+        .zip(&candidate_rankings)
+        .filter(|(a, b)| top_k(a) != top_k(b))
+        .count();
+
+    let comparison = ReplayComparison {
+        delta: baseline_metrics.delta(&candidate_metrics),
+        baseline: baseline_metrics,
+        candidate: candidate_metrics,
+        changed_requests,
+    };
+
+    info!(
+        requests = requests.len(),
+        changed_requests = comparison.changed_requests,
+        ndcg_delta = comparison.delta["ndcg_at_k"],
+        ips_delta = comparison.delta["ips_engagement"],
+        "Pipeline replay comparison completed"
+    );
+
+    Ok(comparison)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logged_candidate(
+        post_id: &str,
+        content_quality: f32,
+        author: Uuid,
+    ) -> LoggedRecallCandidate {
+        LoggedRecallCandidate {
+            post_id: post_id.to_string(),
+            recall_source: RecallSource::Trending,
+            recall_weight: 0.5,
+            timestamp: 1_000_000 - 3600,
+            author_id: Some(author),
+            tags: Vec::new(),
+            content_type: default_content_type(),
+            author_quality: 0.5,
+            content_quality,
+            completion_rate: 0.5,
+        }
+    }
+
+    /// Users engage with high-quality posts, but the log ranks by recall order
+    fn quality_log() -> Vec<LoggedFeedRequest> {
+        (0..5)
+            .map(|r| {
+                let candidates: Vec<LoggedRecallCandidate> = (0..20)
+                    .map(|i| {
+                        logged_candidate(&format!("r{}-p{}", r, i), i as f32 / 20.0, Uuid::new_v4())
+                    })
+                    .collect();
+                let outcomes = candidates
+                    .iter()
+                    .take(10)
+                    .chain(candidates.iter().skip(17))
+                    .map(|c| LoggedOutcome {
+                        post_id: c.post_id.clone(),
+                        engaged: c.content_quality > 0.8,
+                        reward: None,
+                        propensity: Some(0.5),
+                    })
+                    .collect();
+                LoggedFeedRequest {
+                    request_id: format!("req-{}", r),
+                    user_id: "user".to_string(),
+                    timestamp: 1_000_000,
+                    limit: 10,
+                    user_features: LoggedUserFeatures::default(),
+                    candidates,
+                    exploration_pool: Vec::new(),
+                    outcomes,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_candidate_weights_improve_offline_metrics() {
+        let requests = quality_log();
+        let baseline = ReplayPipelineConfig {
+            ranking_weights: RankingWeights {
+                content_quality: 0.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let candidate = ReplayPipelineConfig {
+            ranking_weights: RankingWeights {
+                content_quality: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let comparison = compare(&baseline, &candidate, &requests, &ReplayOptions::default())
+            .expect("replay should succeed");
+
+        assert_eq!(comparison.baseline.requests, 5);
+        assert!(comparison.changed_requests > 0);
+        assert!(comparison.candidate.ndcg_at_k > comparison.baseline.ndcg_at_k);
+        assert!(comparison.delta["recall_at_k"] > 0.0);
+        assert!(comparison.delta["ips_engagement"] > 0.0);
+        assert_eq!(comparison.candidate.author_diversity, 1.0);
+    }
+
+    #[test]
+    fn test_recall_source_limits() {
+        let mut requests = quality_log();
+        requests.truncate(1);
+        let config = ReplayPipelineConfig {
+            recall_source_limits: BTreeMap::from([("trending".to_string(), 3)]),
+            exploration_ratio: 0.0,
+            ..Default::default()
+        };
+
+        let mut replayer = PipelineReplayer::new(config, 7).unwrap();
+        let feed = replayer.replay(&requests[0]).unwrap();
+
+        assert_eq!(feed.len(), 3);
+        assert!(feed
+            .iter()
+            .all(|p| ["r0-p0", "r0-p1", "r0-p2"].contains(&p.post_id.as_str())));
+    }
+
+    #[test]
+    fn test_exploration_content_is_injected() {
+        let mut requests = quality_log();
+        requests.truncate(1);
+        let new_content = NewContentEntry::new(Uuid::new_v4(), Uuid::new_v4());
+        requests[0].exploration_pool.push(new_content.clone());
+
+        let mut replayer = PipelineReplayer::new(ReplayPipelineConfig::default(), 7).unwrap();
+        let feed = replayer.replay(&requests[0]).unwrap();
+
+        assert!(feed
+            .iter()
+            .any(|p| p.post_id == new_content.content_id.to_string()));
+    }
+
+    #[test]
+    fn test_metrics_on_known_ranking() {
+        let author = Uuid::new_v4();
+        let request = LoggedFeedRequest {
+            request_id: "req".to_string(),
+            user_id: "user".to_string(),
+            timestamp: 0,
+            limit: 2,
+            user_features: LoggedUserFeatures::default(),
+            candidates: ["a", "b", "c"]
+                .iter()
+                .map(|id| logged_candidate(id, 0.5, author))
+                .collect(),
+            exploration_pool: Vec::new(),
+            outcomes: vec![
+                LoggedOutcome {
+                    post_id: "a".to_string(),
+                    engaged: false,
+                    reward: None,
+                    propensity: None,
+                },
+                LoggedOutcome {
+                    post_id: "b".to_string(),
+                    engaged: true,
+                    reward: None,
+                    propensity: Some(0.25),
+                },
+            ],
+        };
+
+        let mut metrics = MetricsAccumulator::new(2, 10.0);
+        metrics.add(&request, &["b".to_string(), "c".to_string()]);
+        let metrics = metrics.finish();
+
+        assert_eq!(metrics.recall_at_k, 1.0);
+        assert_eq!(metrics.ndcg_at_k, 1.0);
+        assert!((metrics.coverage - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(metrics.author_diversity, 0.5);
+        // Only "b" is shown by both: weight 4, reward 1
+        assert_eq!(metrics.ips_engagement, 4.0);
+        assert_eq!(metrics.snips_engagement, 1.0);
+    }
+}
@@ -0,0 +1,392 @@
+/// Ranking Feature Schemas
+///
+/// Declarative description of the model input vector: each feature has a
+/// name, type, default, transform and feature-store source. Models are
+/// stamped with the schema version they were trained on, and the scorer
+/// assembles vectors by name through that schema, so models with different
+/// feature sets can be served side by side.
+use super::{RankingError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::info;
+
+/// The original 9-feature layout (see `CandidateFeatures`)
+pub const CANDIDATE_FEATURES_V1: &str = "candidate_features_v1";
+
+/// Named feature values for one candidate
+pub type FeatureValues = HashMap<String, f32>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureType {
+    /// Real-valued feature
+    Float,
+    /// Non-negative count (negative values clamp to 0)
+    Count,
+    /// 0/1 flag (values > 0.5 become 1)
+    Bool,
+}
+
+/// Transform applied after defaulting and type coercion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ValueTransform {
+    Identity,
+    Log1p,
+    Clip { min: f32, max: f32 },
+}
+
+/// Where the serving path gets a feature from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "entity", rename_all = "snake_case")]
+pub enum FeatureOrigin {
+    /// feature-store `user` entity (the viewer)
+    User { feature: String },
+    /// feature-store `post` entity (the candidate)
+    Post { feature: String },
+    /// Computed at request time by the scorer (age, follow graph, history)
+    Request,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub feature_type: FeatureType,
+    /// Value used when the feature is missing or not finite
+    #[serde(default)]
+    pub default: f32,
+    #[serde(default = "default_transform")]
+    pub transform: ValueTransform,
+    pub source: FeatureOrigin,
+}
+
+/// Ordered feature layout identified by a version string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureSchema {
+    pub version: String,
+    pub features: Vec<FeatureSpec>,
+}
+
+fn default_transform() -> ValueTransform {
+    ValueTransform::Identity
+}
+
+impl FeatureSpec {
+    fn new(name: &str, feature_type: FeatureType, default: f32, source: FeatureOrigin) -> Self {
+        Self {
+            name: name.to_string(),
+            feature_type,
+            default,
+            transform: ValueTransform::Identity,
+            source,
+        }
+    }
+
+    /// Resolve the model input for this feature from raw named values
+    pub fn value(&self, values: &FeatureValues) -> f32 {
+        let raw = values
+            .get(&self.name)
+            .copied()
+            .filter(|v| v.is_finite())
+            .unwrap_or(self.default);
+        let typed = match self.feature_type {
+            FeatureType::Float => raw,
+            FeatureType::Count => raw.max(0.0),
+            FeatureType::Bool => {
+                if raw > 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        };
+        match &self.transform {
+            ValueTransform::Identity => typed,
+            ValueTransform::Log1p => typed.max(0.0).ln_1p(),
+            ValueTransform::Clip { min, max } => typed.clamp(*min, *max),
+        }
+    }
+}
+
+impl FeatureSchema {
+    /// Built-in `candidate_features_v1`, matching the heuristic and ONNX models
+    pub fn v1() -> Self {
+        use FeatureType::*;
+        let user = |f: &str| FeatureOrigin::User {
+            feature: f.to_string(),
+        };
+        let post = |f: &str| FeatureOrigin::Post {
+            feature: f.to_string(),
+        };
+        Self {
+            version: CANDIDATE_FEATURES_V1.to_string(),
+            features: vec![
+                FeatureSpec::new("user_follower_count", Count, 0.0, user("follower_count")),
+                FeatureSpec::new("user_post_count", Count, 0.0, user("post_count")),
+                FeatureSpec::new("user_engagement_rate", Float, 0.5, user("engagement_rate")),
+                FeatureSpec::new("post_like_count", Count, 0.0, post("like_count")),
+                FeatureSpec::new("post_comment_count", Count, 0.0, post("comment_count")),
+                FeatureSpec::new("post_share_count", Count, 0.0, post("share_count")),
+                FeatureSpec::new("post_age_hours", Float, 0.0, FeatureOrigin::Request),
+                FeatureSpec::new("author_is_following", Bool, 0.0, FeatureOrigin::Request),
+                FeatureSpec::new("previous_interactions", Count, 0.0, FeatureOrigin::Request),
+            ],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.features.iter().map(|f| f.name.clone()).collect()
+    }
+
+    /// Feature-store names to fetch for the `user` entity
+    pub fn user_source_features(&self) -> Vec<String> {
+        self.features
+            .iter()
+            .filter_map(|f| match &f.source {
+                FeatureOrigin::User { feature } => Some(feature.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Feature-store names to fetch for the `post` entity
+    pub fn post_source_features(&self) -> Vec<String> {
+        self.features
+            .iter()
+            .filter_map(|f| match &f.source {
+                FeatureOrigin::Post { feature } => Some(feature.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Build the model input vector in schema order
+    pub fn assemble(&self, values: &FeatureValues) -> Vec<f32> {
+        self.features.iter().map(|f| f.value(values)).collect()
+    }
+
+    /// Reject empty schemas, duplicate names and inverted clip ranges
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| {
+            RankingError::InvalidInput(format!("Feature schema {}: {}", self.version, reason))
+        };
+        if self.version.is_empty() {
+            return Err(invalid("missing version".to_string()));
+        }
+        if self.features.is_empty() {
+            return Err(invalid("no features".to_string()));
+        }
+        let mut seen = HashSet::new();
+        for spec in &self.features {
+            if !seen.insert(spec.name.as_str()) {
+                return Err(invalid(format!("duplicate feature {}", spec.name)));
+            }
+            if let ValueTransform::Clip { min, max } = spec.transform {
+                if min > max {
+                    return Err(invalid(format!("clip range inverted for {}", spec.name)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Known feature schemas by version
+#[derive(Debug, Clone)]
+pub struct FeatureSchemaRegistry {
+    schemas: HashMap<String, Arc<FeatureSchema>>,
+}
+
+impl Default for FeatureSchemaRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl FeatureSchemaRegistry {
+    /// Registry containing only the built-in schemas
+    pub fn builtin() -> Self {
+        let v1 = FeatureSchema::v1();
+        Self {
+            schemas: HashMap::from([(v1.version.clone(), Arc::new(v1))]),
+        }
+    }
+
+    /// Built-in schemas plus any `*.json` schemas in `FEATURE_SCHEMA_DIR`
+    pub fn from_env() -> Result<Self> {
+        let mut registry = Self::builtin();
+        if let Ok(dir) = std::env::var("FEATURE_SCHEMA_DIR") {
+            registry.load_dir(Path::new(&dir))?;
+        }
+        Ok(registry)
+    }
+
+    /// Register every `*.json` schema file in `dir`; returns how many were loaded
+    pub fn load_dir(&mut self, dir: &Path) -> Result<usize> {
+        let read_err = |e: std::io::Error| {
+            RankingError::InvalidInput(format!(
+                "Failed to read schema dir {}: {}",
+                dir.display(),
+                e
+            ))
+        };
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir).map_err(read_err)? {
+            let path = entry.map_err(read_err)?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let bytes = std::fs::read(&path).map_err(read_err)?;
+            let schema: FeatureSchema = serde_json::from_slice(&bytes).map_err(|e| {
+                RankingError::InvalidInput(format!("Invalid schema {}: {}", path.display(), e))
+            })?;
+            info!(version = %schema.version, features = schema.len(), "Loaded feature schema");
+            self.register(schema)?;
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// Add a schema; versions are immutable once registered
+    pub fn register(&mut self, schema: FeatureSchema) -> Result<Arc<FeatureSchema>> {
+        schema.validate()?;
+        if let Some(existing) = self.schemas.get(&schema.version) {
+            if existing.names() != schema.names() {
+                return Err(RankingError::InvalidInput(format!(
+                    "Feature schema {} already registered with a different layout",
+                    schema.version
+                )));
+            }
+            return Ok(existing.clone());
+        }
+        let schema = Arc::new(schema);
+        self.schemas.insert(schema.version.clone(), schema.clone());
+        Ok(schema)
+    }
+
+    pub fn get(&self, version: &str) -> Option<Arc<FeatureSchema>> {
+        self.schemas.get(version).cloned()
+    }
+
+    pub fn versions(&self) -> Vec<String> {
+        let mut versions: Vec<String> = self.schemas.keys().cloned().collect();
+        versions.sort();
+        versions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v1_matches_model_layout() {
+        let schema = FeatureSchema::v1();
+        assert_eq!(schema.len(), super::super::model::FEATURE_VECTOR_SIZE);
+        assert_eq!(schema.features[7].name, "author_is_following");
+        schema.validate().unwrap();
+    }
+
+    #[test]
+    fn test_assemble_defaults_types_and_transforms() {
+        let schema = FeatureSchema {
+            version: "test_v2".to_string(),
+            features: vec![
+                FeatureSpec {
+                    transform: ValueTransform::Log1p,
+                    ..FeatureSpec::new("likes", FeatureType::Count, 0.0, FeatureOrigin::Request)
+                },
+                FeatureSpec::new("rate", FeatureType::Float, 0.5, FeatureOrigin::Request),
+                FeatureSpec::new("flag", FeatureType::Bool, 0.0, FeatureOrigin::Request),
+                FeatureSpec {
+                    transform: ValueTransform::Clip { min: 0.0, max: 1.0 },
+                    ..FeatureSpec::new("ratio", FeatureType::Float, 0.0, FeatureOrigin::Request)
+                },
+            ],
+        };
+        let values = FeatureValues::from([
+            ("likes".to_string(), std::f32::consts::E - 1.0),
+            ("flag".to_string(), 0.7),
+            ("ratio".to_string(), 3.0),
+            ("unused".to_string(), 42.0),
+        ]);
+
+        let v = schema.assemble(&values);
+        assert!((v[0] - 1.0).abs() < 1e-6);
+        assert_eq!(v[1], 0.5); // default
+        assert_eq!(v[2], 1.0);
+        assert_eq!(v[3], 1.0); // clipped
+    }
+
+    #[test]
+    fn test_schema_json_roundtrip_and_sources() {
+        let json = r#"{
+            "version": "candidate_features_v2",
+            "features": [
+                {"name": "post_like_count", "type": "count",
+                 "transform": {"op": "log1p"},
+                 "source": {"entity": "post", "feature": "like_count"}},
+                {"name": "post_save_count", "type": "count",
+                 "source": {"entity": "post", "feature": "save_count"}},
+                {"name": "author_is_following", "type": "bool",
+                 "source": {"entity": "request"}}
+            ]
+        }"#;
+        let schema: FeatureSchema = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            schema.post_source_features(),
+            vec!["like_count", "save_count"]
+        );
+        assert!(schema.user_source_features().is_empty());
+        assert_eq!(schema.features[1].transform, ValueTransform::Identity);
+    }
+
+    #[test]
+    fn test_registry_rejects_conflicting_layout() {
+        let mut registry = FeatureSchemaRegistry::builtin();
+        let mut conflicting = FeatureSchema::v1();
+        conflicting.features.swap(0, 1);
+        assert!(registry.register(conflicting).is_err());
+
+        // Re-registering the same layout is a no-op
+        registry.register(FeatureSchema::v1()).unwrap();
+        assert_eq!(registry.versions(), vec![CANDIDATE_FEATURES_V1]);
+    }
+
+    #[test]
+    fn test_registry_rejects_duplicate_names() {
+        let mut schema = FeatureSchema::v1();
+        schema.version = "dup".to_string();
+        schema.features.push(schema.features[0].clone());
+        assert!(FeatureSchemaRegistry::builtin().register(schema).is_err());
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut schema = FeatureSchema::v1();
+        schema.version = "candidate_features_v2".to_string();
+        std::fs::write(
+            dir.path().join("v2.json"),
+            serde_json::to_vec(&schema).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "ignored").unwrap();
+
+        let mut registry = FeatureSchemaRegistry::builtin();
+        assert_eq!(registry.load_dir(dir.path()).unwrap(), 1);
+        assert!(registry.get("candidate_features_v2").is_some());
+    }
+}
@@ -0,0 +1,486 @@
+/// Learning-to-Rank Model Artifacts
+///
+/// JSON artifacts written by the `train-ltr` job and consumed by
+/// `RankingModel::load`. An artifact directory holds one file per model
+/// version plus a `LATEST` pointer naming the promoted one. Each artifact is
+/// stamped with the feature schema version it was trained on.
+///
+/// `model` is the engagement head; multi-objective artifacts add further
+/// heads keyed by objective, all reading the same feature vector.
+use super::feature_schema::{FeatureSchema, FeatureSchemaRegistry, CANDIDATE_FEATURES_V1};
+use super::value_model::{Objective, ObjectiveScores};
+use super::{RankingError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Bumped when the artifact layout changes incompatibly
+pub const ARTIFACT_FORMAT_VERSION: u32 = 1;
+
+/// File in an artifact directory naming the promoted model file
+pub const LATEST_POINTER: &str = "LATEST";
+
+/// Versioned LTR model artifact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LtrArtifact {
+    pub format_version: u32,
+    /// Unique model version, also used as the file stem
+    pub model_version: String,
+    pub created_at: DateTime<Utc>,
+    /// Feature schema the input vector follows
+    #[serde(default = "default_schema_version")]
+    pub schema_version: String,
+    /// Schema feature names in vector order, checked against the registry on load
+    pub feature_names: Vec<String>,
+    pub model: LtrModel,
+    /// Additional objective heads (like, share, negative feedback, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub heads: BTreeMap<Objective, LtrModel>,
+    /// Holdout metrics recorded at training time
+    pub metrics: EvaluationMetrics,
+}
+
+/// Offline evaluation results on the holdout set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvaluationMetrics {
+    pub auc: f64,
+    pub ndcg_at_10: f64,
+    pub train_samples: usize,
+    pub holdout_samples: usize,
+    pub holdout_queries: usize,
+}
+
+/// Trained model parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LtrModel {
+    /// Logistic regression over transformed features
+    Logistic {
+        weights: Vec<f32>,
+        bias: f32,
+        transform: FeatureTransform,
+    },
+    /// Gradient-boosted regression trees with logistic loss
+    Gbdt {
+        base_score: f32,
+        learning_rate: f32,
+        trees: Vec<RegressionTree>,
+    },
+}
+
+/// Per-feature `log1p` and standardization applied before a linear model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureTransform {
+    pub log1p: Vec<bool>,
+    pub mean: Vec<f32>,
+    pub std: Vec<f32>,
+}
+
+/// Binary regression tree stored as a flat node list (root at index 0)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionTree {
+    pub nodes: Vec<TreeNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TreeNode {
+    /// `x[feature] <= threshold` goes left, everything else (including NaN) right
+    Split {
+        feature: usize,
+        threshold: f32,
+        left: usize,
+        right: usize,
+    },
+    Leaf {
+        value: f32,
+    },
+}
+
+impl FeatureTransform {
+    pub fn apply(&self, features: &[f32]) -> Vec<f32> {
+        features
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                let x = if self.log1p[i] { x.max(0.0).ln_1p() } else { x };
+                (x - self.mean[i]) / self.std[i]
+            })
+            .collect()
+    }
+}
+
+impl RegressionTree {
+    pub fn predict(&self, features: &[f32]) -> f32 {
+        let mut idx = 0;
+        loop {
+            match &self.nodes[idx] {
+                TreeNode::Leaf { value } => return *value,
+                TreeNode::Split {
+                    feature,
+                    threshold,
+                    left,
+                    right,
+                } => {
+                    idx = if features[*feature] <= *threshold {
+                        *left
+                    } else {
+                        *right
+                    };
+                }
+            }
+        }
+    }
+
+    fn validate(&self, num_features: usize) -> std::result::Result<(), String> {
+        if self.nodes.is_empty() {
+            return Err("empty tree".to_string());
+        }
+        for (idx, node) in self.nodes.iter().enumerate() {
+            if let TreeNode::Split {
+                feature,
+                left,
+                right,
+                ..
+            } = node
+            {
+                if *feature >= num_features {
+                    return Err(format!(
+                        "node {} splits on unknown feature {}",
+                        idx, feature
+                    ));
+                }
+                // Children always follow their parent, which also rules out cycles
+                if *left <= idx
+                    || *right <= idx
+                    || *left >= self.nodes.len()
+                    || *right >= self.nodes.len()
+                {
+                    return Err(format!("node {} has invalid children", idx));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LtrModel {
+    /// Raw margin (log-odds of engagement)
+    pub fn margin(&self, features: &[f32]) -> f32 {
+        match self {
+            LtrModel::Logistic {
+                weights,
+                bias,
+                transform,
+            } => {
+                let x = transform.apply(features);
+                bias + weights.iter().zip(&x).map(|(w, x)| w * x).sum::<f32>()
+            }
+            LtrModel::Gbdt {
+                base_score,
+                learning_rate,
+                trees,
+            } => {
+                base_score + learning_rate * trees.iter().map(|t| t.predict(features)).sum::<f32>()
+            }
+        }
+    }
+
+    /// Predicted engagement probability in (0, 1)
+    pub fn predict(&self, features: &[f32]) -> f32 {
+        sigmoid(self.margin(features))
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LtrModel::Logistic { .. } => "logistic",
+            LtrModel::Gbdt { .. } => "gbdt",
+        }
+    }
+}
+
+impl LtrArtifact {
+    /// Predictions of every head for one feature vector
+    pub fn predict_objectives(&self, features: &[f32]) -> ObjectiveScores {
+        let mut scores: ObjectiveScores = self
+            .heads
+            .iter()
+            .map(|(objective, head)| (*objective, head.predict(features)))
+            .collect();
+        scores.insert(Objective::Engagement, self.model.predict(features));
+        scores
+    }
+
+    /// Load an artifact from a file, or from a directory's `LATEST` pointer
+    ///
+    /// Only checks the artifact is self-consistent; use `resolve_schema` to
+    /// match it against the schemas this build knows.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = resolve_artifact_path(path.as_ref())?;
+        let bytes = std::fs::read(&path).map_err(|e| {
+            RankingError::ModelLoadError(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let artifact: LtrArtifact = serde_json::from_slice(&bytes).map_err(|e| {
+            RankingError::ModelLoadError(format!("Invalid artifact {}: {}", path.display(), e))
+        })?;
+        artifact.validate()?;
+        Ok(artifact)
+    }
+
+    /// Look up the artifact's schema and check the feature layout matches it
+    pub fn resolve_schema(&self, registry: &FeatureSchemaRegistry) -> Result<Arc<FeatureSchema>> {
+        let schema = registry.get(&self.schema_version).ok_or_else(|| {
+            RankingError::ModelLoadError(format!(
+                "Artifact {} uses unknown feature schema {}",
+                self.model_version, self.schema_version
+            ))
+        })?;
+        if self.feature_names != schema.names() {
+            return Err(RankingError::ModelLoadError(format!(
+                "Artifact {} was trained on features {:?}, schema {} has {:?}",
+                self.model_version,
+                self.feature_names,
+                schema.version,
+                schema.names()
+            )));
+        }
+        Ok(schema)
+    }
+
+    /// Check format version and that parameters fit the feature count
+    pub fn validate(&self) -> Result<()> {
+        if self.format_version > ARTIFACT_FORMAT_VERSION {
+            return Err(RankingError::ModelLoadError(format!(
+                "Unsupported artifact format version {} (max {})",
+                self.format_version, ARTIFACT_FORMAT_VERSION
+            )));
+        }
+        let num_features = self.feature_names.len();
+        let invalid = |reason: String| {
+            RankingError::ModelLoadError(format!(
+                "Artifact {} is malformed: {}",
+                self.model_version, reason
+            ))
+        };
+        let heads = std::iter::once((Objective::Engagement, &self.model)).chain(
+            self.heads
+                .iter()
+                .map(|(objective, head)| (*objective, head)),
+        );
+        for (objective, model) in heads {
+            let head_invalid =
+                |reason: String| invalid(format!("{} head: {}", objective.as_str(), reason));
+            match model {
+                LtrModel::Logistic {
+                    weights, transform, ..
+                } => {
+                    let sizes = [
+                        weights.len(),
+                        transform.log1p.len(),
+                        transform.mean.len(),
+                        transform.std.len(),
+                    ];
+                    if sizes.iter().any(|&n| n != num_features) {
+                        return Err(head_invalid(format!("expected {} weights", num_features)));
+                    }
+                    if transform.std.iter().any(|&s| s <= 0.0 || !s.is_finite()) {
+                        return Err(head_invalid("non-positive feature std".to_string()));
+                    }
+                }
+                LtrModel::Gbdt { trees, .. } => {
+                    for (i, tree) in trees.iter().enumerate() {
+                        tree.validate(num_features)
+                            .map_err(|e| head_invalid(format!("tree {}: {}", i, e)))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// File name of this version inside an artifact directory
+    pub fn file_name(&self) -> String {
+        format!("ltr-{}.json", self.model_version)
+    }
+
+    /// Write this version into `dir` without promoting it
+    pub fn write(&self, dir: &Path) -> Result<PathBuf> {
+        let path = dir.join(self.file_name());
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| RankingError::TrainingError(format!("Serialize artifact: {}", e)))?;
+        write_atomic(&path, &json)?;
+        Ok(path)
+    }
+
+    /// Point `dir/LATEST` at this version so `RankingModel::load(dir)` serves it
+    pub fn promote(&self, dir: &Path) -> Result<()> {
+        write_atomic(&dir.join(LATEST_POINTER), self.file_name().as_bytes())
+    }
+}
+
+/// Resolve a directory to the file named by its `LATEST` pointer
+pub fn resolve_artifact_path(path: &Path) -> Result<PathBuf> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+    let pointer = path.join(LATEST_POINTER);
+    let name = std::fs::read_to_string(&pointer).map_err(|e| {
+        RankingError::ModelLoadError(format!("Failed to read {}: {}", pointer.display(), e))
+    })?;
+    let name = name.trim();
+    // Pointer must name a file inside the directory
+    if name.is_empty() || name.contains('/') || name.contains('\\') || name == ".." {
+        return Err(RankingError::ModelLoadError(format!(
+            "Invalid model pointer in {}",
+            pointer.display()
+        )));
+    }
+    Ok(path.join(name))
+}
+
+fn default_schema_version() -> String {
+    CANDIDATE_FEATURES_V1.to_string()
+}
+
+pub fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let io_err = |e: std::io::Error| {
+        RankingError::TrainingError(format!("Failed to write {}: {}", path.display(), e))
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes).map_err(io_err)?;
+    std::fs::rename(&tmp, path).map_err(io_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stump(feature: usize) -> LtrModel {
+        LtrModel::Gbdt {
+            base_score: 0.0,
+            learning_rate: 1.0,
+            trees: vec![RegressionTree {
+                nodes: vec![
+                    TreeNode::Split {
+                        feature,
+                        threshold: 0.5,
+                        left: 1,
+                        right: 2,
+                    },
+                    TreeNode::Leaf { value: -1.0 },
+                    TreeNode::Leaf { value: 1.0 },
+                ],
+            }],
+        }
+    }
+
+    fn stump_artifact(version: &str) -> LtrArtifact {
+        LtrArtifact {
+            format_version: ARTIFACT_FORMAT_VERSION,
+            model_version: version.to_string(),
+            created_at: Utc::now(),
+            schema_version: CANDIDATE_FEATURES_V1.to_string(),
+            feature_names: FeatureSchema::v1().names(),
+            model: stump(7),
+            heads: BTreeMap::new(),
+            metrics: EvaluationMetrics::default(),
+        }
+    }
+
+    #[test]
+    fn test_tree_routing() {
+        let artifact = stump_artifact("v1");
+        let mut x = [0.0f32; 9];
+        assert!(artifact.model.predict(&x) < 0.5);
+        x[7] = 1.0;
+        assert!(artifact.model.predict(&x) > 0.5);
+    }
+
+    #[test]
+    fn test_write_promote_and_load_latest() {
+        let dir = tempfile::tempdir().unwrap();
+        let v1 = stump_artifact("v1");
+        let v2 = stump_artifact("v2");
+        v1.write(dir.path()).unwrap();
+        v1.promote(dir.path()).unwrap();
+        v2.write(dir.path()).unwrap();
+
+        // v2 written but not promoted
+        assert_eq!(LtrArtifact::load(dir.path()).unwrap().model_version, "v1");
+
+        v2.promote(dir.path()).unwrap();
+        assert_eq!(LtrArtifact::load(dir.path()).unwrap().model_version, "v2");
+    }
+
+    #[test]
+    fn test_rejects_mismatched_features() {
+        let registry = FeatureSchemaRegistry::builtin();
+        let mut artifact = stump_artifact("v1");
+        assert_eq!(
+            artifact.resolve_schema(&registry).unwrap().version,
+            CANDIDATE_FEATURES_V1
+        );
+
+        artifact.feature_names.swap(0, 1);
+        assert!(matches!(
+            artifact.resolve_schema(&registry),
+            Err(RankingError::ModelLoadError(_))
+        ));
+
+        artifact.schema_version = "unknown_v9".to_string();
+        assert!(artifact.resolve_schema(&registry).is_err());
+    }
+
+    #[test]
+    fn test_artifact_without_schema_version_defaults_to_v1() {
+        let mut json = serde_json::to_value(stump_artifact("old")).unwrap();
+        json.as_object_mut().unwrap().remove("schema_version");
+        let artifact: LtrArtifact = serde_json::from_value(json).unwrap();
+        assert_eq!(artifact.schema_version, CANDIDATE_FEATURES_V1);
+    }
+
+    #[test]
+    fn test_rejects_cyclic_tree() {
+        let mut artifact = stump_artifact("v1");
+        if let LtrModel::Gbdt { trees, .. } = &mut artifact.model {
+            trees[0].nodes[0] = TreeNode::Split {
+                feature: 0,
+                threshold: 0.0,
+                left: 0,
+                right: 2,
+            };
+        }
+        assert!(artifact.validate().is_err());
+    }
+
+    #[test]
+    fn test_multi_head_predictions_and_validation() {
+        let mut artifact = stump_artifact("v1");
+        artifact.heads.insert(Objective::Share, stump(3));
+
+        let mut x = [0.0f32; 9];
+        x[3] = 1.0;
+        let scores = artifact.predict_objectives(&x);
+        assert_eq!(scores.len(), 2);
+        assert!(scores[&Objective::Engagement] < 0.5);
+        assert!(scores[&Objective::Share] > 0.5);
+
+        // Heads survive a JSON round trip
+        let json = serde_json::to_string(&artifact).unwrap();
+        let loaded: LtrArtifact = serde_json::from_str(&json).unwrap();
+        assert!(loaded.heads.contains_key(&Objective::Share));
+
+        // Every head is checked against the feature count
+        artifact.heads.insert(Objective::Like, stump(42));
+        assert!(artifact.validate().is_err());
+    }
+}
@@ -14,11 +14,19 @@
 /// 4. Apply diversity reranking (MMR algorithm)
 pub mod simple; // Phase D: Simple ranking layer
 
-// Phase E: Advanced ranking with ONNX models
-// pub mod model;
-// pub mod scorer;
-
+// Phase E: Advanced ranking with ONNX / LTR models
+pub mod feature_schema;
+pub mod ltr;
+pub mod model;
+pub mod scorer;
+pub mod training;
+pub mod value_model;
+
+pub use feature_schema::{FeatureSchema, FeatureSchemaRegistry};
+pub use ltr::LtrArtifact;
+pub use model::RankingModel;
 pub use simple::RankingLayer;
+pub use value_model::{Objective, ValueContext, ValueModel};
 
 use thiserror::Error;
 
@@ -38,6 +46,9 @@ pub enum RankingError {
 
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+
+    #[error("Model training failed: {0}")]
+    TrainingError(String),
 }
 
 pub type Result<T> = std::result::Result<T, RankingError>;
@@ -1,9 +1,18 @@
 /// GBDT Model Inference Module
 ///
-/// Loads and runs ONNX-exported GBDT models using tract-onnx.
-/// Supports both real ONNX models and fallback heuristic scoring.
-
-use super::{Result, RankingError};
+/// Loads and runs ONNX-exported GBDT models using tract-onnx, or JSON
+/// LTR artifacts produced by the `train-ltr` job.
+/// Supports both real models and fallback heuristic scoring.
+///
+/// Every model carries the feature schema its input vector follows; ONNX and
+/// heuristic models always use `candidate_features_v1`.
+///
+/// LTR artifacts may carry several objective heads; ONNX and heuristic models
+/// are single-head and predict only `Objective::Engagement`.
+use super::feature_schema::{FeatureSchema, FeatureSchemaRegistry};
+use super::ltr::LtrArtifact;
+use super::value_model::{Objective, ObjectiveScores};
+use super::{RankingError, Result};
 use ndarray::{Array1, Array2};
 use std::path::Path;
 use std::sync::Arc;
@@ -13,21 +22,30 @@ use tracing::{debug, warn};
 /// - User: 3 features (follower_count, post_count, engagement_rate)
 /// - Post: 4 features (like_count, comment_count, share_count, age_hours)
 /// - Interaction: 2 features (author_is_following, previous_interactions)
-const FEATURE_VECTOR_SIZE: usize = 9;
+pub const FEATURE_VECTOR_SIZE: usize = 9;
+
+/// Optimized, runnable tract-onnx plan
+type OnnxPlan = tract_onnx::prelude::SimplePlan<
+    tract_onnx::prelude::TypedFact,
+    Box<dyn tract_onnx::prelude::TypedOp>,
+    tract_onnx::prelude::Graph<
+        tract_onnx::prelude::TypedFact,
+        Box<dyn tract_onnx::prelude::TypedOp>,
+    >,
+>;
 
 /// GBDT Ranking Model
 ///
 /// Wraps tract-onnx model with fallback to heuristic scoring if ONNX model unavailable.
 pub struct RankingModel {
     /// ONNX model (None = use heuristic fallback)
-    model: Option<Arc<tract_onnx::prelude::SimplePlan<
-        tract_onnx::prelude::TypedFact,
-        Box<dyn tract_onnx::prelude::TypedOp>,
-        tract_onnx::prelude::Graph<
-            tract_onnx::prelude::TypedFact,
-            Box<dyn tract_onnx::prelude::TypedOp>,
-        >,
-    >>>,
+    model: Option<Arc<OnnxPlan>>,
+
+    /// Trained LTR artifact (JSON)
+    ltr: Option<Arc<LtrArtifact>>,
+
+    /// Input layout expected by `predict`
+    schema: Arc<FeatureSchema>,
 
     /// Model type indicator
     model_type: ModelType,
@@ -36,24 +54,38 @@ pub struct RankingModel {
 #[derive(Debug, Clone, Copy)]
 enum ModelType {
     Onnx,
+    Ltr,
     Heuristic,
 }
 
 impl RankingModel {
-    /// Load ONNX model from file path
+    /// Load model from file path
     ///
+    /// `.json` files and artifact directories (via their `LATEST` pointer) are
+    /// loaded as LTR artifacts; anything else is treated as ONNX.
     /// Falls back to heuristic scoring if model file not found or loading fails.
     pub fn load<P: AsRef<Path>>(model_path: P) -> Result<Self> {
+        Self::load_with_registry(model_path, &FeatureSchemaRegistry::builtin())
+    }
+
+    /// Load model, resolving LTR artifact schemas against `registry`
+    pub fn load_with_registry<P: AsRef<Path>>(
+        model_path: P,
+        registry: &FeatureSchemaRegistry,
+    ) -> Result<Self> {
         let path = model_path.as_ref();
 
+        if path.is_dir() || path.extension().is_some_and(|ext| ext == "json") {
+            return Ok(Self::load_ltr(path, registry));
+        }
+
         match Self::try_load_onnx(path) {
             Ok(model) => {
-                debug!(
-                    "✅ Loaded ONNX ranking model from: {}",
-                    path.display()
-                );
+                debug!("✅ Loaded ONNX ranking model from: {}", path.display());
                 Ok(Self {
                     model: Some(Arc::new(model)),
+                    ltr: None,
+                    schema: Arc::new(FeatureSchema::v1()),
                     model_type: ModelType::Onnx,
                 })
             }
@@ -64,77 +96,177 @@ impl RankingModel {
                     e
                 );
                 warn!("   Falling back to heuristic scoring");
-                Ok(Self {
-                    model: None,
-                    model_type: ModelType::Heuristic,
-                })
+                Ok(Self::heuristic())
+            }
+        }
+    }
+
+    /// Load a JSON LTR artifact, falling back to heuristic scoring on failure
+    fn load_ltr(path: &Path, registry: &FeatureSchemaRegistry) -> Self {
+        let loaded = LtrArtifact::load(path).and_then(|artifact| {
+            let schema = artifact.resolve_schema(registry)?;
+            Ok((artifact, schema))
+        });
+
+        match loaded {
+            Ok((artifact, schema)) => {
+                debug!(
+                    "✅ Loaded LTR ranking model {} ({}, schema {}) from: {}",
+                    artifact.model_version,
+                    artifact.model.kind(),
+                    schema.version,
+                    path.display()
+                );
+                Self::from_artifact(artifact, schema)
+            }
+            Err(e) => {
+                warn!(
+                    "⚠️  Failed to load LTR model from {}: {}",
+                    path.display(),
+                    e
+                );
+                warn!("   Falling back to heuristic scoring");
+                Self::heuristic()
             }
         }
     }
 
+    /// Wrap an already-loaded LTR artifact and its resolved schema
+    pub fn from_artifact(artifact: LtrArtifact, schema: Arc<FeatureSchema>) -> Self {
+        Self {
+            model: None,
+            ltr: Some(Arc::new(artifact)),
+            schema,
+            model_type: ModelType::Ltr,
+        }
+    }
+
     /// Create model with heuristic fallback (for testing/development)
     pub fn heuristic() -> Self {
         debug!("Using heuristic ranking model");
         Self {
             model: None,
+            ltr: None,
+            schema: Arc::new(FeatureSchema::v1()),
             model_type: ModelType::Heuristic,
         }
     }
 
+    /// Version of the loaded LTR artifact, if any
+    pub fn model_version(&self) -> Option<&str> {
+        self.ltr.as_ref().map(|a| a.model_version.as_str())
+    }
+
+    /// Feature schema this model's input vectors must follow
+    pub fn schema(&self) -> &Arc<FeatureSchema> {
+        &self.schema
+    }
+
     /// Predict relevance scores for batch of feature vectors
     ///
     /// # Arguments
-    /// * `features` - 2D array (batch_size × FEATURE_VECTOR_SIZE)
+    /// * `features` - 2D array (batch_size × schema length)
     ///
     /// # Returns
     /// * Array of relevance scores (batch_size)
     pub fn predict(&self, features: Array2<f32>) -> Result<Array1<f32>> {
-        let batch_size = features.shape()[0];
-
-        if features.shape()[1] != FEATURE_VECTOR_SIZE {
+        if features.shape()[1] != self.schema.len() {
             return Err(RankingError::InvalidInput(format!(
                 "Expected {} features, got {}",
-                FEATURE_VECTOR_SIZE,
+                self.schema.len(),
                 features.shape()[1]
             )));
         }
 
         match self.model_type {
             ModelType::Onnx => self.predict_onnx(features),
+            ModelType::Ltr => self.predict_ltr(features),
             ModelType::Heuristic => self.predict_heuristic(features),
         }
     }
 
+    /// Objectives this model predicts
+    pub fn objectives(&self) -> Vec<Objective> {
+        match &self.ltr {
+            Some(artifact) => std::iter::once(Objective::Engagement)
+                .chain(artifact.heads.keys().copied())
+                .collect(),
+            None => vec![Objective::Engagement],
+        }
+    }
+
+    /// Predict every objective head for a batch of feature vectors
+    ///
+    /// Same input contract as `predict`; returns one score map per row.
+    pub fn predict_objectives(&self, features: Array2<f32>) -> Result<Vec<ObjectiveScores>> {
+        match (&self.ltr, self.model_type) {
+            (Some(artifact), ModelType::Ltr) if !artifact.heads.is_empty() => {
+                if features.shape()[1] != self.schema.len() {
+                    return Err(RankingError::InvalidInput(format!(
+                        "Expected {} features, got {}",
+                        self.schema.len(),
+                        features.shape()[1]
+                    )));
+                }
+                Ok(features
+                    .outer_iter()
+                    .map(|row| artifact.predict_objectives(&row.to_vec()))
+                    .collect())
+            }
+            _ => Ok(self
+                .predict(features)?
+                .iter()
+                .map(|&score| ObjectiveScores::from([(Objective::Engagement, score)]))
+                .collect()),
+        }
+    }
+
     /// ONNX model inference
     fn predict_onnx(&self, features: Array2<f32>) -> Result<Array1<f32>> {
-        let model = self.model.as_ref().ok_or_else(|| {
-            RankingError::InferenceError("ONNX model not loaded".to_string())
-        })?;
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| RankingError::InferenceError("ONNX model not loaded".to_string()))?;
 
         let batch_size = features.shape()[0];
 
         // Convert ndarray to tract tensor
         let input_tensor = tract_onnx::prelude::tract_ndarray::Array2::from_shape_fn(
-            (batch_size, FEATURE_VECTOR_SIZE),
+            (batch_size, features.shape()[1]),
             |(i, j)| features[[i, j]],
         );
 
         // Run inference
-        let input = tract_onnx::prelude::tvec![input_tensor.into_dyn().into()];
+        let input: tract_onnx::prelude::TVec<tract_onnx::prelude::TValue> =
+            std::iter::once(tract_onnx::prelude::Tensor::from(input_tensor.into_dyn()).into())
+                .collect();
         let output = model
             .run(input)
             .map_err(|e| RankingError::InferenceError(format!("ONNX inference failed: {}", e)))?;
 
         // Extract scores from output tensor
-        let scores_tensor = output[0]
-            .to_array_view::<f32>()
-            .map_err(|e| RankingError::InferenceError(format!("Output extraction failed: {}", e)))?;
+        let scores_tensor = output[0].to_array_view::<f32>().map_err(|e| {
+            RankingError::InferenceError(format!("Output extraction failed: {}", e))
+        })?;
 
         let scores = Array1::from_iter(scores_tensor.iter().copied());
 
         Ok(scores)
     }
 
+    /// LTR artifact inference (engagement probability per row)
+    fn predict_ltr(&self, features: Array2<f32>) -> Result<Array1<f32>> {
+        let artifact = self
+            .ltr
+            .as_ref()
+            .ok_or_else(|| RankingError::InferenceError("LTR model not loaded".to_string()))?;
+
+        Ok(features
+            .outer_iter()
+            .map(|row| artifact.model.predict(&row.to_vec()))
+            .collect())
+    }
+
     /// Heuristic scoring (fallback when no ONNX model)
     ///
     /// Formula: `score = (like_count * 0.3 + comment_count * 0.5 + share_count * 0.2) / (age_hours + 1.0)`
@@ -175,23 +307,13 @@ impl RankingModel {
     }
 
     /// Try to load ONNX model (private helper)
-    fn try_load_onnx(
-        path: &Path,
-    ) -> std::result::Result<
-        tract_onnx::prelude::SimplePlan<
-            tract_onnx::prelude::TypedFact,
-            Box<dyn tract_onnx::prelude::TypedOp>,
-            tract_onnx::prelude::Graph<
-                tract_onnx::prelude::TypedFact,
-                Box<dyn tract_onnx::prelude::TypedOp>,
-            >,
-        >,
-        Box<dyn std::error::Error>,
-    > {
+    fn try_load_onnx(path: &Path) -> std::result::Result<OnnxPlan, Box<dyn std::error::Error>> {
         if !path.exists() {
             return Err(format!("Model file not found: {}", path.display()).into());
         }
 
+        use tract_onnx::prelude::{Framework, InferenceModelExt};
+
         // Load ONNX model
         let model = tract_onnx::onnx()
             .model_for_path(path)?
@@ -214,9 +336,9 @@ mod tests {
         let features = Array2::from_shape_vec(
             (1, 9),
             vec![
-                1000.0, 500.0, 0.8,    // user: follower_count, post_count, engagement_rate
+                1000.0, 500.0, 0.8, // user: follower_count, post_count, engagement_rate
                 100.0, 50.0, 20.0, 2.0, // post: likes, comments, shares, age_hours
-                1.0, 5.0,               // interaction: is_following, previous_interactions
+                1.0, 5.0, // interaction: is_following, previous_interactions
             ],
         )
         .unwrap();
@@ -269,6 +391,74 @@ mod tests {
         assert!(matches!(result, Err(RankingError::InvalidInput(_))));
     }
 
+    #[test]
+    fn test_load_ltr_artifact_directory() {
+        use crate::services::ranking::feature_schema::CANDIDATE_FEATURES_V1;
+        use crate::services::ranking::ltr::{
+            EvaluationMetrics, LtrModel, RegressionTree, TreeNode, ARTIFACT_FORMAT_VERSION,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = LtrArtifact {
+            format_version: ARTIFACT_FORMAT_VERSION,
+            model_version: "test".to_string(),
+            created_at: chrono::Utc::now(),
+            schema_version: CANDIDATE_FEATURES_V1.to_string(),
+            feature_names: FeatureSchema::v1().names(),
+            model: LtrModel::Gbdt {
+                base_score: 0.0,
+                learning_rate: 1.0,
+                trees: vec![RegressionTree {
+                    nodes: vec![
+                        TreeNode::Split {
+                            feature: 7,
+                            threshold: 0.5,
+                            left: 1,
+                            right: 2,
+                        },
+                        TreeNode::Leaf { value: -2.0 },
+                        TreeNode::Leaf { value: 2.0 },
+                    ],
+                }],
+            },
+            heads: Default::default(),
+            metrics: EvaluationMetrics::default(),
+        };
+        artifact.write(dir.path()).unwrap();
+        artifact.promote(dir.path()).unwrap();
+
+        let model = RankingModel::load(dir.path()).unwrap();
+        assert_eq!(model.model_version(), Some("test"));
+
+        let mut rows = vec![0.0f32; 18];
+        rows[9 + 7] = 1.0; // second row: following
+        let scores = model
+            .predict(Array2::from_shape_vec((2, 9), rows).unwrap())
+            .unwrap();
+        assert!(scores[0] < 0.5 && scores[1] > 0.5);
+    }
+
+    #[test]
+    fn test_single_head_models_predict_engagement_only() {
+        let model = RankingModel::heuristic();
+        assert_eq!(model.objectives(), vec![Objective::Engagement]);
+
+        let features =
+            Array2::from_shape_vec((1, 9), vec![0.0, 0.0, 0.0, 10.0, 5.0, 2.0, 1.0, 0.0, 0.0])
+                .unwrap();
+        let expected = model.predict(features.clone()).unwrap()[0];
+        let objectives = model.predict_objectives(features).unwrap();
+        assert_eq!(objectives.len(), 1);
+        assert_eq!(objectives[0][&Objective::Engagement], expected);
+    }
+
+    #[test]
+    fn test_load_missing_ltr_falls_back_to_heuristic() {
+        let model = RankingModel::load("/nonexistent/ltr-model.json").unwrap();
+        assert!(model.model_version().is_none());
+        assert!(matches!(model.model_type, ModelType::Heuristic));
+    }
+
     #[test]
     fn test_following_boost() {
         let model = RankingModel::heuristic();
@@ -1,7 +1,10 @@
 /// Candidate Scoring Module
 ///
 /// Orchestrates feature extraction, model inference, and batch scoring for feed ranking.
-
+/// Feature vectors are assembled by name through the model's feature schema,
+/// and per-objective predictions are combined by the scorer's value weights.
+use super::feature_schema::{FeatureOrigin, FeatureSchema, FeatureValues};
+use super::value_model::{ObjectiveScores, ValueWeights};
 use super::{RankingError, RankingModel, Result};
 use crate::services::features::GrpcFeatureClient;
 use ndarray::Array2;
@@ -11,14 +14,17 @@ use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 /// Maximum batch size for scoring (to prevent memory issues)
-const MAX_BATCH_SIZE: usize = 100;
+pub const MAX_BATCH_SIZE: usize = 100;
 
 /// Scored candidate post
 #[derive(Debug, Clone)]
 pub struct ScoredCandidate {
     pub post_id: Uuid,
     pub author_id: Uuid,
+    /// Value-model output the candidates are ranked by
     pub score: f32,
+    /// Raw prediction of each model head
+    pub objective_scores: ObjectiveScores,
     pub features: CandidateFeatures,
 }
 
@@ -39,24 +45,37 @@ pub struct CandidateFeatures {
     // Interaction features
     pub author_is_following: bool,
     pub previous_interactions: f32,
+
+    /// Further schema features, keyed by schema feature name
+    pub extra: FeatureValues,
 }
 
 impl CandidateFeatures {
-    /// Convert to feature vector for model inference
-    ///
-    /// Layout: [user_features (3), post_features (4), interaction_features (2)]
-    fn to_vector(&self) -> Vec<f32> {
-        vec![
-            self.user_follower_count,
-            self.user_post_count,
-            self.user_engagement_rate,
-            self.post_like_count,
-            self.post_comment_count,
-            self.post_share_count,
-            self.post_age_hours,
-            if self.author_is_following { 1.0 } else { 0.0 },
-            self.previous_interactions,
-        ]
+    /// Named values for schema assembly (built-in fields win over `extra`)
+    pub fn to_values(&self) -> FeatureValues {
+        let mut values = self.extra.clone();
+        for (name, value) in [
+            ("user_follower_count", self.user_follower_count),
+            ("user_post_count", self.user_post_count),
+            ("user_engagement_rate", self.user_engagement_rate),
+            ("post_like_count", self.post_like_count),
+            ("post_comment_count", self.post_comment_count),
+            ("post_share_count", self.post_share_count),
+            ("post_age_hours", self.post_age_hours),
+            (
+                "author_is_following",
+                if self.author_is_following { 1.0 } else { 0.0 },
+            ),
+            ("previous_interactions", self.previous_interactions),
+        ] {
+            values.insert(name.to_string(), value);
+        }
+        values
+    }
+
+    /// Convert to feature vector for model inference, in `schema` order
+    pub fn to_vector(&self, schema: &FeatureSchema) -> Vec<f32> {
+        schema.assemble(&self.to_values())
     }
 }
 
@@ -67,6 +86,8 @@ pub struct RankingScorer {
     model: Arc<RankingModel>,
     /// Optional feature-store gRPC client for ML feature retrieval
     feature_store_client: Option<Arc<GrpcFeatureClient>>,
+    /// Combines objective predictions into the ranking score
+    value_weights: Arc<ValueWeights>,
 }
 
 impl RankingScorer {
@@ -75,6 +96,7 @@ impl RankingScorer {
         Self {
             model,
             feature_store_client: None,
+            value_weights: Arc::new(ValueWeights::default()),
         }
     }
 
@@ -86,9 +108,38 @@ impl RankingScorer {
         Self {
             model,
             feature_store_client: Some(feature_store_client),
+            value_weights: Arc::new(ValueWeights::default()),
+        }
+    }
+
+    /// Scorer for another model sharing this scorer's feature-store client
+    ///
+    /// Each model assembles vectors through its own schema, so models trained
+    /// on different feature sets can be served side by side.
+    pub fn with_model(&self, model: Arc<RankingModel>) -> Self {
+        Self {
+            model,
+            feature_store_client: self.feature_store_client.clone(),
+            value_weights: self.value_weights.clone(),
         }
     }
 
+    /// Scorer combining objectives with other value weights
+    ///
+    /// Resolve the weights per request with `ValueModel::resolve` to rank a
+    /// surface or experiment variant differently.
+    pub fn with_value_weights(&self, value_weights: Arc<ValueWeights>) -> Self {
+        Self {
+            model: self.model.clone(),
+            feature_store_client: self.feature_store_client.clone(),
+            value_weights,
+        }
+    }
+
+    pub fn model(&self) -> &Arc<RankingModel> {
+        &self.model
+    }
+
     /// Check if feature-store is available
     pub fn has_feature_store(&self) -> bool {
         self.feature_store_client.is_some()
@@ -137,7 +188,7 @@ impl RankingScorer {
 
         // Extract features for all candidates
         let features = self.extract_features(
-            &candidates,
+            candidates,
             &user_features,
             &post_features_map,
             &following_set,
@@ -145,26 +196,33 @@ impl RankingScorer {
         )?;
 
         // Convert to 2D array for model inference
-        let feature_vectors: Vec<f32> = features.iter().flat_map(|f| f.to_vector()).collect();
-        let feature_matrix =
-            Array2::from_shape_vec((candidates.len(), 9), feature_vectors).map_err(|e| {
-                RankingError::FeatureExtractionError(format!("Failed to build feature matrix: {}", e))
-            })?;
+        let schema = self.model.schema();
+        let feature_vectors: Vec<f32> = features.iter().flat_map(|f| f.to_vector(schema)).collect();
+        let feature_matrix = Array2::from_shape_vec(
+            (candidates.len(), schema.len()),
+            feature_vectors,
+        )
+        .map_err(|e| {
+            RankingError::FeatureExtractionError(format!("Failed to build feature matrix: {}", e))
+        })?;
 
-        // Run model inference
-        let scores = self.model.predict(feature_matrix)?;
+        // Run model inference (one prediction per objective head)
+        let objective_scores = self.model.predict_objectives(feature_matrix)?;
 
-        // Combine candidates with scores
+        // Combine candidates with value-model scores
         let mut scored_candidates: Vec<ScoredCandidate> = candidates
             .iter()
-            .zip(scores.iter())
+            .zip(objective_scores)
             .zip(features.iter())
-            .map(|((candidate, &score), features)| ScoredCandidate {
-                post_id: candidate.post_id,
-                author_id: candidate.author_id,
-                score,
-                features: features.clone(),
-            })
+            .map(
+                |((candidate, objective_scores), features)| ScoredCandidate {
+                    post_id: candidate.post_id,
+                    author_id: candidate.author_id,
+                    score: self.value_weights.value(&objective_scores),
+                    objective_scores,
+                    features: features.clone(),
+                },
+            )
             .collect();
 
         // Sort by score descending
@@ -195,18 +253,17 @@ impl RankingScorer {
         interaction_history: &HashMap<Uuid, u32>,
     ) -> Result<Vec<CandidateFeatures>> {
         let now = chrono::Utc::now();
+        let schema = self.model.schema();
 
         candidates
             .iter()
             .map(|candidate| {
-                let post_features = post_features_map
-                    .get(&candidate.post_id)
-                    .ok_or_else(|| {
-                        RankingError::FeatureExtractionError(format!(
-                            "Post features not found for post_id: {}",
-                            candidate.post_id
-                        ))
-                    })?;
+                let post_features = post_features_map.get(&candidate.post_id).ok_or_else(|| {
+                    RankingError::FeatureExtractionError(format!(
+                        "Post features not found for post_id: {}",
+                        candidate.post_id
+                    ))
+                })?;
 
                 let post_age_hours = (now - candidate.created_at).num_hours() as f32;
 
@@ -228,6 +285,8 @@ impl RankingScorer {
                         .get(&candidate.author_id)
                         .copied()
                         .unwrap_or(0) as f32,
+
+                    extra: source_values(schema, user_features, post_features),
                 })
             })
             .collect()
@@ -251,9 +310,7 @@ impl RankingScorer {
         interaction_history: HashMap<Uuid, u32>,
     ) -> Result<Vec<ScoredCandidate>> {
         let feature_client = self.feature_store_client.as_ref().ok_or_else(|| {
-            RankingError::FeatureExtractionError(
-                "Feature-store client not configured".to_string(),
-            )
+            RankingError::FeatureExtractionError("Feature-store client not configured".to_string())
         })?;
 
         if candidates.is_empty() {
@@ -277,11 +334,12 @@ impl RankingScorer {
         );
 
         // 1. Fetch user features from feature-store
-        let user_features = match feature_client.get_user_features(&user_id.to_string()).await {
+        let mut user_features = match feature_client.get_user_features(&user_id.to_string()).await {
             Ok(features) => UserFeatures {
                 follower_count: features.follower_count,
                 post_count: features.post_count,
                 engagement_rate: features.engagement_rate,
+                ..Default::default()
             },
             Err(e) => {
                 warn!("Failed to fetch user features from feature-store: {}", e);
@@ -290,15 +348,33 @@ impl RankingScorer {
                     follower_count: 100,
                     post_count: 50,
                     engagement_rate: 0.5,
+                    ..Default::default()
                 }
             }
         };
 
+        // Schema features beyond the built-in set
+        let schema = self.model.schema();
+        let user_extra_names: Vec<String> = schema
+            .user_source_features()
+            .into_iter()
+            .filter(|name| !UserFeatures::BUILTIN.contains(&name.as_str()))
+            .collect();
+        if !user_extra_names.is_empty() {
+            match feature_client
+                .get_numeric_features("user", &user_id.to_string(), &user_extra_names)
+                .await
+            {
+                Ok(extra) => user_features.extra = extra,
+                Err(e) => warn!("Failed to fetch extra user features: {}", e),
+            }
+        }
+
         // 2. Batch fetch post features from feature-store
         let post_ids: Vec<String> = candidates.iter().map(|c| c.post_id.to_string()).collect();
         let post_features_result = feature_client.batch_get_post_features(&post_ids).await;
 
-        let post_features_map: HashMap<Uuid, PostFeatures> = match post_features_result {
+        let mut post_features_map: HashMap<Uuid, PostFeatures> = match post_features_result {
             Ok(features) => features
                 .into_iter()
                 .filter_map(|(id, f)| {
@@ -309,6 +385,7 @@ impl RankingScorer {
                                 like_count: f.like_count,
                                 comment_count: f.comment_count,
                                 share_count: f.share_count,
+                                ..Default::default()
                             },
                         )
                     })
@@ -320,6 +397,30 @@ impl RankingScorer {
             }
         };
 
+        let post_extra_names: Vec<String> = schema
+            .post_source_features()
+            .into_iter()
+            .filter(|name| !PostFeatures::BUILTIN.contains(&name.as_str()))
+            .collect();
+        if !post_extra_names.is_empty() && !post_features_map.is_empty() {
+            match feature_client
+                .batch_get_numeric_features("post", &post_ids, &post_extra_names)
+                .await
+            {
+                Ok(extras) => {
+                    for (id, extra) in extras {
+                        if let Some(features) = Uuid::parse_str(&id)
+                            .ok()
+                            .and_then(|uuid| post_features_map.get_mut(&uuid))
+                        {
+                            features.extra = extra;
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to fetch extra post features: {}", e),
+            }
+        }
+
         debug!(
             user_id = %user_id,
             user_follower_count = user_features.follower_count,
@@ -349,19 +450,71 @@ pub struct CandidatePost {
 }
 
 /// User features from feature-store
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct UserFeatures {
     pub follower_count: u32,
     pub post_count: u32,
     pub engagement_rate: f32,
+    /// Additional schema-sourced values, keyed by feature-store name
+    pub extra: FeatureValues,
+}
+
+impl UserFeatures {
+    /// Feature-store names backed by the typed fields
+    pub const BUILTIN: [&'static str; 3] = ["follower_count", "post_count", "engagement_rate"];
+
+    fn source_value(&self, feature: &str) -> Option<f32> {
+        match feature {
+            "follower_count" => Some(self.follower_count as f32),
+            "post_count" => Some(self.post_count as f32),
+            "engagement_rate" => Some(self.engagement_rate),
+            other => self.extra.get(other).copied(),
+        }
+    }
 }
 
 /// Post features from feature-store
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PostFeatures {
     pub like_count: u32,
     pub comment_count: u32,
     pub share_count: u32,
+    /// Additional schema-sourced values, keyed by feature-store name
+    pub extra: FeatureValues,
+}
+
+impl PostFeatures {
+    /// Feature-store names backed by the typed fields
+    pub const BUILTIN: [&'static str; 3] = ["like_count", "comment_count", "share_count"];
+
+    fn source_value(&self, feature: &str) -> Option<f32> {
+        match feature {
+            "like_count" => Some(self.like_count as f32),
+            "comment_count" => Some(self.comment_count as f32),
+            "share_count" => Some(self.share_count as f32),
+            other => self.extra.get(other).copied(),
+        }
+    }
+}
+
+/// Map feature-store values onto schema feature names via each spec's source
+fn source_values(
+    schema: &FeatureSchema,
+    user: &UserFeatures,
+    post: &PostFeatures,
+) -> FeatureValues {
+    schema
+        .features
+        .iter()
+        .filter_map(|spec| {
+            let value = match &spec.source {
+                FeatureOrigin::User { feature } => user.source_value(feature),
+                FeatureOrigin::Post { feature } => post.source_value(feature),
+                FeatureOrigin::Request => None,
+            }?;
+            Some((spec.name.clone(), value))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -402,6 +555,7 @@ mod tests {
             follower_count: 1000,
             post_count: 500,
             engagement_rate: 0.8,
+            ..Default::default()
         };
 
         let post_features_map: HashMap<Uuid, PostFeatures> = candidates
@@ -415,6 +569,7 @@ mod tests {
                         like_count: [100, 10, 50][i],
                         comment_count: [50, 5, 25][i],
                         share_count: [20, 2, 10][i],
+                        ..Default::default()
                     },
                 )
             })
@@ -459,6 +614,7 @@ mod tests {
                     follower_count: 100,
                     post_count: 50,
                     engagement_rate: 0.5,
+                    ..Default::default()
                 },
                 HashMap::new(),
                 HashSet::new(),
@@ -479,6 +635,7 @@ mod tests {
             follower_count: 1000,
             post_count: 500,
             engagement_rate: 0.8,
+            ..Default::default()
         };
 
         // Missing post features for all candidates
@@ -515,6 +672,7 @@ mod tests {
             follower_count: 1000,
             post_count: 500,
             engagement_rate: 0.8,
+            ..Default::default()
         };
 
         let post_features_map: HashMap<Uuid, PostFeatures> = [(
@@ -523,6 +681,7 @@ mod tests {
                 like_count: 50,
                 comment_count: 25,
                 share_count: 10,
+                ..Default::default()
             },
         )]
         .iter()
@@ -565,6 +724,184 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_models_with_different_schemas_side_by_side() {
+        use crate::services::ranking::feature_schema::FeatureSchemaRegistry;
+        use crate::services::ranking::ltr::{
+            EvaluationMetrics, LtrArtifact, LtrModel, RegressionTree, TreeNode,
+            ARTIFACT_FORMAT_VERSION,
+        };
+
+        // v2 drops everything but one new feature-store feature
+        let mut registry = FeatureSchemaRegistry::builtin();
+        let v2: FeatureSchema = serde_json::from_str(
+            r#"{"version": "test_v2", "features": [
+                {"name": "post_save_count", "type": "count",
+                 "source": {"entity": "post", "feature": "save_count"}}
+            ]}"#,
+        )
+        .unwrap();
+        let v2 = registry.register(v2).unwrap();
+        let artifact = LtrArtifact {
+            format_version: ARTIFACT_FORMAT_VERSION,
+            model_version: "saves".to_string(),
+            created_at: chrono::Utc::now(),
+            schema_version: v2.version.clone(),
+            feature_names: v2.names(),
+            model: LtrModel::Gbdt {
+                base_score: 0.0,
+                learning_rate: 1.0,
+                trees: vec![RegressionTree {
+                    nodes: vec![
+                        TreeNode::Split {
+                            feature: 0,
+                            threshold: 5.0,
+                            left: 1,
+                            right: 2,
+                        },
+                        TreeNode::Leaf { value: -1.0 },
+                        TreeNode::Leaf { value: 1.0 },
+                    ],
+                }],
+            },
+            heads: Default::default(),
+            metrics: EvaluationMetrics::default(),
+        };
+        let schema = artifact.resolve_schema(&registry).unwrap();
+
+        let v1_scorer = create_test_scorer();
+        let v2_scorer =
+            v1_scorer.with_model(Arc::new(RankingModel::from_artifact(artifact, schema)));
+
+        let candidates = create_test_candidates();
+        // Least-liked post has the most saves
+        let post_features_map: HashMap<Uuid, PostFeatures> = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                (
+                    c.post_id,
+                    PostFeatures {
+                        like_count: [100, 10, 50][i],
+                        extra: FeatureValues::from([(
+                            "save_count".to_string(),
+                            [0.0, 20.0, 1.0][i],
+                        )]),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for scorer in [&v1_scorer, &v2_scorer] {
+            let scored = scorer
+                .score_candidates(
+                    Uuid::new_v4(),
+                    candidates.clone(),
+                    UserFeatures::default(),
+                    post_features_map.clone(),
+                    HashSet::new(),
+                    HashMap::new(),
+                )
+                .await
+                .unwrap();
+            results.push(scored);
+        }
+        let (by_v1, by_v2) = (&results[0], &results[1]);
+
+        assert_eq!(by_v1[0].post_id, candidates[0].post_id);
+        assert_eq!(by_v2[0].post_id, candidates[1].post_id);
+        assert_eq!(by_v2[0].features.extra["post_save_count"], 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_value_weights_trade_off_objectives() {
+        use crate::services::ranking::ltr::{
+            EvaluationMetrics, LtrArtifact, LtrModel, RegressionTree, TreeNode,
+            ARTIFACT_FORMAT_VERSION,
+        };
+        use crate::services::ranking::value_model::{Objective, ValueFormula};
+        use std::collections::BTreeMap;
+
+        let stump = |feature: usize, threshold: f32| LtrModel::Gbdt {
+            base_score: 0.0,
+            learning_rate: 1.0,
+            trees: vec![RegressionTree {
+                nodes: vec![
+                    TreeNode::Split {
+                        feature,
+                        threshold,
+                        left: 1,
+                        right: 2,
+                    },
+                    TreeNode::Leaf { value: -2.0 },
+                    TreeNode::Leaf { value: 2.0 },
+                ],
+            }],
+        };
+        // Engagement follows likes; shares predict negative feedback
+        let schema = Arc::new(FeatureSchema::v1());
+        let artifact = LtrArtifact {
+            format_version: ARTIFACT_FORMAT_VERSION,
+            model_version: "multi".to_string(),
+            created_at: chrono::Utc::now(),
+            schema_version: schema.version.clone(),
+            feature_names: schema.names(),
+            model: stump(3, 30.0),
+            heads: BTreeMap::from([(Objective::NegativeFeedback, stump(5, 5.0))]),
+            metrics: EvaluationMetrics::default(),
+        };
+        let scorer = RankingScorer::new(Arc::new(RankingModel::from_artifact(artifact, schema)));
+
+        let candidates = create_test_candidates();
+        let post_features_map: HashMap<Uuid, PostFeatures> = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                (
+                    c.post_id,
+                    PostFeatures {
+                        like_count: [100, 10, 50][i],
+                        share_count: [20, 0, 0][i],
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        let engagement_only = Arc::new(ValueWeights {
+            formula: ValueFormula::WeightedSum,
+            weights: BTreeMap::from([(Objective::Engagement, 1.0)]),
+        });
+        let mut results = Vec::new();
+        for scorer in [
+            scorer.with_value_weights(engagement_only),
+            scorer.with_value_weights(Arc::new(ValueWeights::default())),
+        ] {
+            let scored = scorer
+                .score_candidates(
+                    Uuid::new_v4(),
+                    candidates.clone(),
+                    UserFeatures::default(),
+                    post_features_map.clone(),
+                    HashSet::new(),
+                    HashMap::new(),
+                )
+                .await
+                .unwrap();
+            results.push(scored);
+        }
+        let (by_engagement, by_value) = (&results[0], &results[1]);
+
+        // Most-liked post also draws negative feedback
+        let top = &by_engagement[0];
+        assert_eq!(top.post_id, candidates[0].post_id);
+        assert_eq!(top.objective_scores.len(), 2);
+        assert!(top.objective_scores[&Objective::NegativeFeedback] > 0.5);
+        assert_ne!(by_value[0].post_id, candidates[0].post_id);
+    }
+
     #[test]
     fn test_feature_vector_conversion() {
         let features = CandidateFeatures {
@@ -577,9 +914,10 @@ mod tests {
             post_age_hours: 5.0,
             author_is_following: true,
             previous_interactions: 10.0,
+            extra: FeatureValues::new(),
         };
 
-        let vector = features.to_vector();
+        let vector = features.to_vector(&FeatureSchema::v1());
 
         assert_eq!(vector.len(), 9);
         assert_eq!(vector[0], 1000.0); // user_follower_count
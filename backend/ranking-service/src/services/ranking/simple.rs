@@ -1,19 +1,27 @@
-use crate::models::{Candidate, PostFeatures, RankedPost};
-use crate::services::features::FeatureClient;
+use super::scorer::{CandidatePost, RankingScorer, MAX_BATCH_SIZE};
+use super::value_model::{ValueContext, ValueModel};
+use crate::models::{Candidate, PostFeatures, RankedPost, RecallSource};
+use crate::services::features::{ContentFeatures, FeatureClient};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tracing::debug;
+use tracing::{debug, warn};
+use uuid::Uuid;
 
 /// Ranking Layer - Fine-grained scoring
 /// Phase D: Linear weighted scoring with real features
-/// Phase E: GBDT ONNX model inference (planned)
+/// Phase E: Multi-objective model scoring via `RankingScorer` (optional)
 pub struct RankingLayer {
     feature_client: Arc<FeatureClient>,
     weights: RankingWeights,
+    /// Model scorer and value model; replaces the linear score when set
+    model_scoring: Option<(Arc<RankingScorer>, Arc<ValueModel>)>,
 }
 
 /// Configurable ranking weights
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RankingWeights {
     pub engagement: f32,
     pub recency: f32,
@@ -40,6 +48,7 @@ impl RankingLayer {
         Self {
             feature_client,
             weights: RankingWeights::default(),
+            model_scoring: None,
         }
     }
 
@@ -48,9 +57,114 @@ impl RankingLayer {
         Self {
             feature_client,
             weights,
+            model_scoring: None,
         }
     }
 
+    /// Score with a multi-objective model, combining heads via `value_model`
+    ///
+    /// The scorer needs a feature-store client; the linear score stays as the
+    /// fallback when model scoring fails.
+    pub fn with_model_scoring(
+        mut self,
+        scorer: Arc<RankingScorer>,
+        value_model: Arc<ValueModel>,
+    ) -> Self {
+        self.model_scoring = Some((scorer, value_model));
+        self
+    }
+
+    /// Rank candidates for a user on a surface / experiment variant
+    ///
+    /// Uses model scoring when configured, otherwise the linear scorer.
+    pub async fn rank_for_user(
+        &self,
+        user_id: &str,
+        candidates: Vec<Candidate>,
+        context: &ValueContext,
+    ) -> Result<Vec<RankedPost>> {
+        let Some((scorer, value_model)) = &self.model_scoring else {
+            return self.rank_candidates(candidates).await;
+        };
+        let Ok(user_uuid) = Uuid::parse_str(user_id) else {
+            return self.rank_candidates(candidates).await;
+        };
+
+        let mut ranked_posts = self.rank_candidates(candidates.clone()).await?;
+        let authors: HashMap<&str, Uuid> = ranked_posts
+            .iter()
+            .filter_map(|p| Some((p.post_id.as_str(), p.features.author_id?)))
+            .collect();
+
+        let mut following_set = HashSet::new();
+        let candidate_posts: Vec<CandidatePost> = candidates
+            .iter()
+            .filter_map(|c| {
+                let post_id = Uuid::parse_str(&c.post_id).ok()?;
+                let created_at = chrono::DateTime::from_timestamp(c.timestamp, 0)?;
+                let author_id = authors.get(c.post_id.as_str()).copied().unwrap_or_default();
+                // Graph recall only returns followed authors
+                if c.recall_source == RecallSource::Graph && !author_id.is_nil() {
+                    following_set.insert(author_id);
+                }
+                Some(CandidatePost {
+                    post_id,
+                    author_id,
+                    created_at,
+                })
+            })
+            .collect();
+
+        let scorer = scorer.with_value_weights(Arc::new(value_model.resolve(context).clone()));
+        let mut model_scores = HashMap::with_capacity(candidate_posts.len());
+        for chunk in candidate_posts.chunks(MAX_BATCH_SIZE) {
+            match scorer
+                .score_with_feature_store(
+                    user_uuid,
+                    chunk.to_vec(),
+                    following_set.clone(),
+                    HashMap::new(),
+                )
+                .await
+            {
+                Ok(scored) => model_scores.extend(scored.into_iter().map(|c| (c.post_id, c))),
+                Err(e) => {
+                    warn!("Model scoring failed, using linear scores: {}", e);
+                    return Ok(ranked_posts);
+                }
+            }
+        }
+
+        for post in ranked_posts.iter_mut() {
+            let scored = Uuid::parse_str(&post.post_id)
+                .ok()
+                .and_then(|id| model_scores.get(&id));
+            if let Some(scored) = scored {
+                post.score = scored.score;
+                post.objective_scores = scored
+                    .objective_scores
+                    .iter()
+                    .map(|(objective, &p)| (objective.as_str().to_string(), p))
+                    .collect();
+            }
+        }
+
+        // Model-scored posts first (scores are not comparable across scorers),
+        // then the rest in linear order
+        ranked_posts.sort_by(|a, b| {
+            a.objective_scores
+                .is_empty()
+                .cmp(&b.objective_scores.is_empty())
+                .then(
+                    b.score
+                        .partial_cmp(&a.score)
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+        });
+
+        Ok(ranked_posts)
+    }
+
     /// Rank candidates with real feature extraction
     pub async fn rank_candidates(&self, candidates: Vec<Candidate>) -> Result<Vec<RankedPost>> {
         if candidates.is_empty() {
@@ -70,6 +184,18 @@ impl RankingLayer {
             features_map.len()
         );
 
+        Ok(self.rank_with_features(candidates, &features_map))
+    }
+
+    /// Rank candidates with already-fetched content features
+    ///
+    /// Candidates without an entry fall back to default features, as in
+    /// `rank_candidates`. Used directly by offline replay.
+    pub fn rank_with_features(
+        &self,
+        candidates: Vec<Candidate>,
+        features_map: &HashMap<String, ContentFeatures>,
+    ) -> Vec<RankedPost> {
         let mut ranked_posts: Vec<RankedPost> = candidates
             .into_iter()
             .map(|candidate| {
@@ -81,6 +207,7 @@ impl RankingLayer {
                     post_id: candidate.post_id,
                     score,
                     recall_source: candidate.recall_source,
+                    objective_scores: Default::default(),
                     features,
                 }
             })
@@ -93,14 +220,14 @@ impl RankingLayer {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        Ok(ranked_posts)
+        ranked_posts
     }
 
     /// Extract features from candidate with fetched content features
     fn extract_features(
         &self,
         candidate: &Candidate,
-        content_features: Option<&crate::services::features::ContentFeatures>,
+        content_features: Option<&ContentFeatures>,
     ) -> PostFeatures {
         let (author_quality, content_quality, author_id, completion_rate) =
             if let Some(cf) = content_features {
@@ -141,11 +268,33 @@ impl RankingLayer {
     /// Phase D: Linear weighted combination
     /// Phase E: Replace with GBDT ONNX model
     fn compute_score(&self, features: &PostFeatures) -> f32 {
-        features.engagement_score * self.weights.engagement
-            + features.recency_score * self.weights.recency
-            + features.author_quality_score * self.weights.author_quality
-            + features.content_quality_score * self.weights.content_quality
-            + features.completion_rate_score * self.weights.completion_rate
+        self.feature_contributions(features)
+            .iter()
+            .map(|(_, contribution)| contribution)
+            .sum()
+    }
+
+    /// Weighted terms of the linear score, by feature (they sum to the score)
+    pub fn feature_contributions(&self, features: &PostFeatures) -> [(&'static str, f32); 5] {
+        [
+            (
+                "engagement",
+                features.engagement_score * self.weights.engagement,
+            ),
+            ("recency", features.recency_score * self.weights.recency),
+            (
+                "author_quality",
+                features.author_quality_score * self.weights.author_quality,
+            ),
+            (
+                "content_quality",
+                features.content_quality_score * self.weights.content_quality,
+            ),
+            (
+                "completion_rate",
+                features.completion_rate_score * self.weights.completion_rate,
+            ),
+        ]
     }
 }
 
@@ -238,6 +387,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_feature_contributions() {
+        let feature_client = create_test_feature_client();
+        let layer = RankingLayer::new(feature_client);
+
+        let features = PostFeatures {
+            engagement_score: 0.8,
+            recency_score: 0.4,
+            ..Default::default()
+        };
+
+        let contributions = layer.feature_contributions(&features);
+        assert_eq!(contributions[0], ("engagement", 0.8 * 0.30));
+        assert_eq!(contributions[1], ("recency", 0.4 * 0.25));
+        let total: f32 = contributions.iter().map(|(_, c)| c).sum();
+        assert_eq!(total, layer.compute_score(&features));
+    }
+
     #[test]
     fn test_custom_weights() {
         let feature_client = create_test_feature_client();
@@ -0,0 +1,655 @@
+/// LTR Model Training
+///
+/// Trainers and offline metrics used by the `train-ltr` job: logistic
+/// regression and gradient-boosted trees over the candidate feature vector,
+/// a per-user holdout split, and NDCG@10 / AUC evaluation. Samples are
+/// assembled through a feature schema, whose version is stamped into the
+/// resulting artifact.
+use super::feature_schema::{FeatureSchema, FeatureType, ValueTransform};
+use super::ltr::{
+    sigmoid, EvaluationMetrics, FeatureTransform, LtrArtifact, LtrModel, RegressionTree, TreeNode,
+    ARTIFACT_FORMAT_VERSION,
+};
+use super::{RankingError, Result};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::debug;
+
+/// Cut-off for the NDCG metric reported in artifacts
+pub const NDCG_K: usize = 10;
+
+/// One logged impression with its engagement label
+#[derive(Debug, Clone)]
+pub struct LtrSample {
+    /// Ranking query the impression belongs to (the viewing user)
+    pub query_id: String,
+    /// Model input assembled through the training schema
+    pub features: Vec<f32>,
+    /// 1.0 = engaged, 0.0 = impression without engagement
+    pub label: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LtrAlgorithm {
+    Logistic,
+    Gbdt,
+}
+
+impl FromStr for LtrAlgorithm {
+    type Err = RankingError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "logistic" | "lr" => Ok(LtrAlgorithm::Logistic),
+            "gbdt" => Ok(LtrAlgorithm::Gbdt),
+            other => Err(RankingError::InvalidInput(format!(
+                "Unknown LTR algorithm: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogisticParams {
+    pub epochs: usize,
+    pub learning_rate: f32,
+    pub l2: f32,
+}
+
+impl Default for LogisticParams {
+    fn default() -> Self {
+        Self {
+            epochs: 200,
+            learning_rate: 0.5,
+            l2: 1e-4,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GbdtParams {
+    pub num_trees: usize,
+    pub max_depth: usize,
+    pub learning_rate: f32,
+    pub min_samples_leaf: usize,
+    /// L2 regularization on leaf values
+    pub lambda: f32,
+    /// Candidate split thresholds per feature (quantiles), at most 255
+    pub max_bins: usize,
+}
+
+impl Default for GbdtParams {
+    fn default() -> Self {
+        Self {
+            num_trees: 100,
+            max_depth: 4,
+            learning_rate: 0.1,
+            min_samples_leaf: 20,
+            lambda: 1.0,
+            max_bins: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrainingConfig {
+    pub algorithm: LtrAlgorithm,
+    /// Fraction of users held out for evaluation
+    pub holdout_fraction: f64,
+    /// Seed for the holdout split
+    pub seed: u64,
+    pub logistic: LogisticParams,
+    pub gbdt: GbdtParams,
+}
+
+impl Default for TrainingConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: LtrAlgorithm::Gbdt,
+            holdout_fraction: 0.2,
+            seed: 42,
+            logistic: LogisticParams::default(),
+            gbdt: GbdtParams::default(),
+        }
+    }
+}
+
+/// Train a model on `samples` and evaluate it on a per-user holdout
+pub fn train(
+    samples: Vec<LtrSample>,
+    schema: &FeatureSchema,
+    config: &TrainingConfig,
+) -> Result<LtrArtifact> {
+    if let Some(bad) = samples.iter().find(|s| s.features.len() != schema.len()) {
+        return Err(RankingError::TrainingError(format!(
+            "Sample has {} features, schema {} expects {}",
+            bad.features.len(),
+            schema.version,
+            schema.len()
+        )));
+    }
+
+    let (train_set, holdout) = split_holdout(samples, config.holdout_fraction, config.seed);
+
+    if train_set.is_empty() || holdout.is_empty() {
+        return Err(RankingError::TrainingError(format!(
+            "Need samples on both sides of the split (train={}, holdout={})",
+            train_set.len(),
+            holdout.len()
+        )));
+    }
+    let positives = train_set.iter().filter(|s| s.label > 0.5).count();
+    if positives == 0 || positives == train_set.len() {
+        return Err(RankingError::TrainingError(
+            "Training set contains a single label class".to_string(),
+        ));
+    }
+
+    debug!(
+        train = train_set.len(),
+        holdout = holdout.len(),
+        positives,
+        "Training LTR model"
+    );
+
+    let model = match config.algorithm {
+        LtrAlgorithm::Logistic => fit_logistic(&train_set, schema, &config.logistic),
+        LtrAlgorithm::Gbdt => fit_gbdt(&train_set, schema.len(), &config.gbdt),
+    };
+
+    let mut metrics = evaluate(&holdout, |x| model.predict(x));
+    metrics.train_samples = train_set.len();
+
+    let created_at = Utc::now();
+    Ok(LtrArtifact {
+        format_version: ARTIFACT_FORMAT_VERSION,
+        model_version: format!("{}-{}", created_at.format("%Y%m%dT%H%M%SZ"), model.kind()),
+        created_at,
+        schema_version: schema.version.clone(),
+        feature_names: schema.names(),
+        model,
+        heads: Default::default(),
+        metrics,
+    })
+}
+
+/// Split samples by query so a user's impressions land on one side only
+pub fn split_holdout(
+    samples: Vec<LtrSample>,
+    fraction: f64,
+    seed: u64,
+) -> (Vec<LtrSample>, Vec<LtrSample>) {
+    let (holdout, train_set) = samples
+        .into_iter()
+        .partition(|s| is_holdout(&s.query_id, fraction, seed));
+    (train_set, holdout)
+}
+
+/// Whether `query_id` falls on the holdout side of the split
+pub fn is_holdout(query_id: &str, fraction: f64, seed: u64) -> bool {
+    let cutoff = (fraction.clamp(0.0, 1.0) * 10_000.0) as u64;
+    query_bucket(query_id, seed) < cutoff
+}
+
+/// Holdout metrics for any scoring function
+pub fn evaluate<F: Fn(&[f32]) -> f32>(samples: &[LtrSample], score: F) -> EvaluationMetrics {
+    let scores: Vec<f32> = samples.iter().map(|s| score(&s.features)).collect();
+    let labels: Vec<f32> = samples.iter().map(|s| s.label).collect();
+    let queries = samples
+        .iter()
+        .map(|s| s.query_id.as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+
+    EvaluationMetrics {
+        auc: auc(&scores, &labels),
+        ndcg_at_10: ndcg_at_k(samples, &scores, NDCG_K),
+        train_samples: 0,
+        holdout_samples: samples.len(),
+        holdout_queries: queries,
+    }
+}
+
+/// ROC AUC with average ranks for tied scores (0.5 when one class is missing)
+pub fn auc(scores: &[f32], labels: &[f32]) -> f64 {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[a].total_cmp(&scores[b]));
+
+    let mut positive_rank_sum = 0.0;
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && scores[order[j + 1]] == scores[order[i]] {
+            j += 1;
+        }
+        // 1-based average rank of the tie group
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for &idx in &order[i..=j] {
+            if labels[idx] > 0.5 {
+                positive_rank_sum += rank;
+            }
+        }
+        i = j + 1;
+    }
+
+    let positives = labels.iter().filter(|&&l| l > 0.5).count() as f64;
+    let negatives = labels.len() as f64 - positives;
+    if positives == 0.0 || negatives == 0.0 {
+        return 0.5;
+    }
+    (positive_rank_sum - positives * (positives + 1.0) / 2.0) / (positives * negatives)
+}
+
+/// Mean NDCG@k over queries that have at least one positive
+pub fn ndcg_at_k(samples: &[LtrSample], scores: &[f32], k: usize) -> f64 {
+    let mut groups: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, s) in samples.iter().enumerate() {
+        groups.entry(s.query_id.as_str()).or_default().push(i);
+    }
+
+    let dcg = |labels: &mut dyn Iterator<Item = f32>| -> f64 {
+        labels
+            .take(k)
+            .enumerate()
+            .map(|(rank, l)| (2f64.powf(l as f64) - 1.0) / ((rank + 2) as f64).log2())
+            .sum()
+    };
+
+    let mut total = 0.0;
+    let mut counted = 0usize;
+    for mut idx in groups.into_values() {
+        if !idx.iter().any(|&i| samples[i].label > 0.0) {
+            continue;
+        }
+        idx.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        let actual = dcg(&mut idx.iter().map(|&i| samples[i].label));
+
+        let mut ideal: Vec<f32> = idx.iter().map(|&i| samples[i].label).collect();
+        ideal.sort_by(|a, b| b.total_cmp(a));
+        let ideal = dcg(&mut ideal.into_iter());
+
+        total += actual / ideal;
+        counted += 1;
+    }
+
+    if counted == 0 {
+        0.0
+    } else {
+        total / counted as f64
+    }
+}
+
+/// Full-batch gradient descent on log loss over standardized features
+///
+/// Count features the schema leaves untransformed get `log1p` first.
+pub fn fit_logistic(
+    samples: &[LtrSample],
+    schema: &FeatureSchema,
+    params: &LogisticParams,
+) -> LtrModel {
+    let n = samples.len() as f32;
+    let dim = schema.len();
+    let log1p = schema
+        .features
+        .iter()
+        .map(|f| f.feature_type == FeatureType::Count && f.transform == ValueTransform::Identity)
+        .collect();
+
+    let mut transform = FeatureTransform {
+        log1p,
+        mean: vec![0.0; dim],
+        std: vec![1.0; dim],
+    };
+    let raw: Vec<Vec<f32>> = samples
+        .iter()
+        .map(|s| transform.apply(&s.features))
+        .collect();
+    for f in 0..dim {
+        let mean = raw.iter().map(|x| x[f]).sum::<f32>() / n;
+        let var = raw.iter().map(|x| (x[f] - mean).powi(2)).sum::<f32>() / n;
+        transform.mean[f] = mean;
+        // Constant features keep unit scale so they simply get zero weight
+        transform.std[f] = if var.sqrt() > 1e-6 { var.sqrt() } else { 1.0 };
+    }
+    let xs: Vec<Vec<f32>> = samples
+        .iter()
+        .map(|s| transform.apply(&s.features))
+        .collect();
+
+    let mut weights = vec![0.0f32; dim];
+    let mut bias = 0.0f32;
+    for _ in 0..params.epochs {
+        let mut grad_w = vec![0.0f32; dim];
+        let mut grad_b = 0.0f32;
+        for (x, s) in xs.iter().zip(samples) {
+            let margin = bias + weights.iter().zip(x).map(|(w, x)| w * x).sum::<f32>();
+            let err = sigmoid(margin) - s.label;
+            for (g, xi) in grad_w.iter_mut().zip(x) {
+                *g += err * xi;
+            }
+            grad_b += err;
+        }
+        for (w, g) in weights.iter_mut().zip(&grad_w) {
+            *w -= params.learning_rate * (g / n + params.l2 * *w);
+        }
+        bias -= params.learning_rate * grad_b / n;
+    }
+
+    LtrModel::Logistic {
+        weights,
+        bias,
+        transform,
+    }
+}
+
+/// Gradient-boosted trees on log loss using histogram split finding
+pub fn fit_gbdt(samples: &[LtrSample], num_features: usize, params: &GbdtParams) -> LtrModel {
+    let edges = quantile_edges(samples, num_features, params.max_bins.clamp(1, 255));
+    let bins: Vec<Vec<u8>> = samples
+        .iter()
+        .map(|s| {
+            s.features
+                .iter()
+                .zip(&edges)
+                .map(|(&x, e)| bin_of(e, x))
+                .collect()
+        })
+        .collect();
+
+    let mean = samples.iter().map(|s| s.label).sum::<f32>() / samples.len() as f32;
+    let mean = mean.clamp(1e-4, 1.0 - 1e-4);
+    let base_score = (mean / (1.0 - mean)).ln();
+
+    let mut margins = vec![base_score; samples.len()];
+    let mut trees = Vec::with_capacity(params.num_trees);
+    let builder = TreeBuilder {
+        params,
+        edges: &edges,
+        bins: &bins,
+    };
+
+    for _ in 0..params.num_trees {
+        let (grad, hess): (Vec<f32>, Vec<f32>) = margins
+            .iter()
+            .zip(samples)
+            .map(|(&m, s)| {
+                let p = sigmoid(m);
+                (p - s.label, (p * (1.0 - p)).max(1e-6))
+            })
+            .unzip();
+
+        let mut nodes = Vec::new();
+        builder.build(&mut nodes, (0..samples.len()).collect(), &grad, &hess, 0);
+        let tree = RegressionTree { nodes };
+        for (m, s) in margins.iter_mut().zip(samples) {
+            *m += params.learning_rate * tree.predict(&s.features);
+        }
+        trees.push(tree);
+    }
+
+    LtrModel::Gbdt {
+        base_score,
+        learning_rate: params.learning_rate,
+        trees,
+    }
+}
+
+struct TreeBuilder<'a> {
+    params: &'a GbdtParams,
+    edges: &'a [Vec<f32>],
+    bins: &'a [Vec<u8>],
+}
+
+impl TreeBuilder<'_> {
+    /// Append the subtree for `rows` in pre-order and return its root index
+    fn build(
+        &self,
+        nodes: &mut Vec<TreeNode>,
+        rows: Vec<usize>,
+        grad: &[f32],
+        hess: &[f32],
+        depth: usize,
+    ) -> usize {
+        let lambda = self.params.lambda;
+        let g: f32 = rows.iter().map(|&r| grad[r]).sum();
+        let h: f32 = rows.iter().map(|&r| hess[r]).sum();
+        let idx = nodes.len();
+        nodes.push(TreeNode::Leaf {
+            value: -g / (h + lambda),
+        });
+
+        let min_leaf = self.params.min_samples_leaf.max(1);
+        if depth >= self.params.max_depth || rows.len() < 2 * min_leaf {
+            return idx;
+        }
+
+        let parent_gain = g * g / (h + lambda);
+        let mut best: Option<(f32, usize, u8)> = None;
+        for f in 0..self.edges.len() {
+            let n_bins = self.edges[f].len() + 1;
+            let mut hist = vec![(0.0f32, 0.0f32, 0usize); n_bins];
+            for &r in &rows {
+                let b = &mut hist[self.bins[r][f] as usize];
+                b.0 += grad[r];
+                b.1 += hess[r];
+                b.2 += 1;
+            }
+
+            let (mut gl, mut hl, mut nl) = (0.0f32, 0.0f32, 0usize);
+            for (b, &(bg, bh, bn)) in hist.iter().enumerate().take(n_bins - 1) {
+                gl += bg;
+                hl += bh;
+                nl += bn;
+                let nr = rows.len() - nl;
+                if nl < min_leaf || nr < min_leaf {
+                    continue;
+                }
+                let (gr, hr) = (g - gl, h - hl);
+                let gain = gl * gl / (hl + lambda) + gr * gr / (hr + lambda) - parent_gain;
+                if gain > 1e-6 && best.map_or(true, |(best_gain, _, _)| gain > best_gain) {
+                    best = Some((gain, f, b as u8));
+                }
+            }
+        }
+
+        let Some((_, feature, bin)) = best else {
+            return idx;
+        };
+        let (left_rows, right_rows): (Vec<usize>, Vec<usize>) = rows
+            .into_iter()
+            .partition(|&r| self.bins[r][feature] <= bin);
+
+        let left = self.build(nodes, left_rows, grad, hess, depth + 1);
+        let right = self.build(nodes, right_rows, grad, hess, depth + 1);
+        nodes[idx] = TreeNode::Split {
+            feature,
+            threshold: self.edges[feature][bin as usize],
+            left,
+            right,
+        };
+        idx
+    }
+}
+
+/// Distinct quantile cut points per feature
+fn quantile_edges(samples: &[LtrSample], num_features: usize, max_bins: usize) -> Vec<Vec<f32>> {
+    (0..num_features)
+        .map(|f| {
+            let mut values: Vec<f32> = samples
+                .iter()
+                .map(|s| s.features[f])
+                .filter(|v| v.is_finite())
+                .collect();
+            values.sort_by(|a, b| a.total_cmp(b));
+
+            let mut edges: Vec<f32> = (1..=max_bins)
+                .filter_map(|q| values.get(q * values.len() / (max_bins + 1)).copied())
+                .collect();
+            edges.dedup();
+            // The largest value can't separate anything
+            if edges.last() == values.last() {
+                edges.pop();
+            }
+            edges
+        })
+        .collect()
+}
+
+/// Bin `b` holds values in `(edges[b-1], edges[b]]`; NaN goes to the last bin
+fn bin_of(edges: &[f32], x: f32) -> u8 {
+    if x.is_nan() {
+        return edges.len() as u8;
+    }
+    edges.partition_point(|&e| e < x) as u8
+}
+
+/// Stable FNV-1a bucket in `[0, 10_000)` for the holdout split
+fn query_bucket(query_id: &str, seed: u64) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in seed.to_le_bytes().iter().chain(query_id.as_bytes()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash % 10_000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Synthetic impressions: engagement driven by following and like count
+    fn synthetic_samples(users: usize, per_user: usize) -> Vec<LtrSample> {
+        let mut state: u64 = 7;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as f32 / (1u64 << 31) as f32
+        };
+
+        let mut samples = Vec::new();
+        for u in 0..users {
+            for _ in 0..per_user {
+                let following = if next() < 0.3 { 1.0 } else { 0.0 };
+                let likes = (next() * 500.0).floor();
+                let age = next() * 72.0;
+                let p = 0.05 + 0.5 * following + 0.4 * (likes / 500.0) - 0.1 * (age / 72.0);
+                let label = if next() < p { 1.0 } else { 0.0 };
+                samples.push(LtrSample {
+                    query_id: format!("user-{}", u),
+                    features: vec![
+                        1000.0,
+                        50.0,
+                        0.1,
+                        likes,
+                        likes / 10.0,
+                        likes / 50.0,
+                        age,
+                        following,
+                        next() * 3.0,
+                    ],
+                    label,
+                });
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn test_auc_extremes() {
+        let labels = [0.0, 0.0, 1.0, 1.0];
+        assert_eq!(auc(&[0.1, 0.2, 0.8, 0.9], &labels), 1.0);
+        assert_eq!(auc(&[0.9, 0.8, 0.2, 0.1], &labels), 0.0);
+        assert_eq!(auc(&[0.5, 0.5, 0.5, 0.5], &labels), 0.5);
+        assert_eq!(auc(&[0.1, 0.2], &[1.0, 1.0]), 0.5);
+    }
+
+    #[test]
+    fn test_ndcg_perfect_and_reversed() {
+        let samples: Vec<LtrSample> = [1.0, 0.0, 0.0]
+            .iter()
+            .map(|&label| LtrSample {
+                query_id: "q".to_string(),
+                features: vec![0.0; 9],
+                label,
+            })
+            .collect();
+
+        assert!((ndcg_at_k(&samples, &[0.9, 0.5, 0.1], 10) - 1.0).abs() < 1e-9);
+        // Positive ranked third: 1 / log2(4)
+        assert!((ndcg_at_k(&samples, &[0.1, 0.5, 0.9], 10) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_holdout_split_keeps_queries_together() {
+        let (train_set, holdout) = split_holdout(synthetic_samples(200, 5), 0.2, 1);
+        assert!(!train_set.is_empty() && !holdout.is_empty());
+
+        let held: std::collections::HashSet<_> =
+            holdout.iter().map(|s| s.query_id.clone()).collect();
+        assert!(train_set.iter().all(|s| !held.contains(&s.query_id)));
+    }
+
+    #[test]
+    fn test_gbdt_learns_signal() {
+        let artifact = train(
+            synthetic_samples(300, 20),
+            &FeatureSchema::v1(),
+            &TrainingConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(artifact.model.kind(), "gbdt");
+        assert!(artifact.metrics.auc > 0.75, "auc {}", artifact.metrics.auc);
+        assert!(artifact.metrics.ndcg_at_10 > 0.5);
+        artifact.validate().unwrap();
+    }
+
+    #[test]
+    fn test_logistic_learns_signal() {
+        let config = TrainingConfig {
+            algorithm: LtrAlgorithm::Logistic,
+            ..Default::default()
+        };
+        let artifact = train(synthetic_samples(300, 20), &FeatureSchema::v1(), &config).unwrap();
+
+        assert_eq!(artifact.model.kind(), "logistic");
+        assert!(artifact.metrics.auc > 0.75, "auc {}", artifact.metrics.auc);
+        artifact.validate().unwrap();
+    }
+
+    #[test]
+    fn test_schema_stamped_and_width_checked() {
+        let mut schema = FeatureSchema::v1();
+        schema.version = "candidate_features_v2".to_string();
+        let artifact = train(
+            synthetic_samples(100, 10),
+            &schema,
+            &TrainingConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(artifact.schema_version, "candidate_features_v2");
+
+        schema.features.pop();
+        assert!(train(
+            synthetic_samples(100, 10),
+            &schema,
+            &TrainingConfig::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_single_class_rejected() {
+        let samples: Vec<LtrSample> = synthetic_samples(50, 5)
+            .into_iter()
+            .map(|s| LtrSample { label: 0.0, ..s })
+            .collect();
+        assert!(matches!(
+            train(samples, &FeatureSchema::v1(), &TrainingConfig::default()),
+            Err(RankingError::TrainingError(_))
+        ));
+    }
+}
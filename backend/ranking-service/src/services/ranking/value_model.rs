@@ -0,0 +1,285 @@
+/// Value Model
+///
+/// Combines the per-objective predictions of a multi-head `RankingModel` into
+/// the single value candidates are ranked by. Weights are configured per
+/// surface (feed, explore, ...) and can be overridden per experiment variant,
+/// so A/B tests can trade objectives off without retraining.
+use super::{RankingError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Keeps weighted products finite when a head predicts exactly zero
+const PRODUCT_EPSILON: f32 = 1e-6;
+
+/// Prediction target of one model head
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    /// Generic engagement head (single-head models predict only this)
+    Engagement,
+    Like,
+    Comment,
+    Share,
+    Dwell,
+    /// Hide / report / "not interested" probability
+    NegativeFeedback,
+}
+
+impl Objective {
+    pub const ALL: [Objective; 6] = [
+        Objective::Engagement,
+        Objective::Like,
+        Objective::Comment,
+        Objective::Share,
+        Objective::Dwell,
+        Objective::NegativeFeedback,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Objective::Engagement => "engagement",
+            Objective::Like => "like",
+            Objective::Comment => "comment",
+            Objective::Share => "share",
+            Objective::Dwell => "dwell",
+            Objective::NegativeFeedback => "negative_feedback",
+        }
+    }
+}
+
+impl FromStr for Objective {
+    type Err = RankingError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Objective::ALL
+            .into_iter()
+            .find(|o| o.as_str() == s)
+            .ok_or_else(|| RankingError::InvalidInput(format!("Unknown objective: {}", s)))
+    }
+}
+
+/// Per-objective predictions for one candidate
+pub type ObjectiveScores = BTreeMap<Objective, f32>;
+
+/// How objective predictions are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueFormula {
+    /// `Σ w_o · p_o`
+    #[default]
+    WeightedSum,
+    /// `Π (p_o + ε)^w_o` (negative weights act as penalties)
+    WeightedProduct,
+}
+
+/// Formula plus objective weights for one surface or variant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueWeights {
+    #[serde(default)]
+    pub formula: ValueFormula,
+    pub weights: BTreeMap<Objective, f32>,
+}
+
+impl Default for ValueWeights {
+    fn default() -> Self {
+        Self {
+            formula: ValueFormula::WeightedSum,
+            weights: BTreeMap::from([
+                (Objective::Engagement, 1.0),
+                (Objective::Like, 1.0),
+                (Objective::Comment, 2.0),
+                (Objective::Share, 3.0),
+                (Objective::Dwell, 0.5),
+                (Objective::NegativeFeedback, -4.0),
+            ]),
+        }
+    }
+}
+
+impl ValueWeights {
+    /// Combine predictions into a ranking value
+    ///
+    /// Objectives the model has no head for are skipped, so single-head
+    /// models rank exactly as before under any weighting that keeps
+    /// `engagement` at 1.0.
+    pub fn value(&self, scores: &ObjectiveScores) -> f32 {
+        let terms = self
+            .weights
+            .iter()
+            .filter_map(|(objective, &w)| scores.get(objective).map(|&p| (w, p)));
+
+        match self.formula {
+            ValueFormula::WeightedSum => terms.map(|(w, p)| w * p).sum(),
+            ValueFormula::WeightedProduct => {
+                let mut any = false;
+                let product = terms
+                    .map(|(w, p)| {
+                        any = true;
+                        (p.max(0.0) + PRODUCT_EPSILON).powf(w)
+                    })
+                    .product();
+                if any {
+                    product
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    fn validate(&self, name: &str) -> Result<()> {
+        if self.weights.values().any(|w| !w.is_finite()) {
+            return Err(RankingError::InvalidInput(format!(
+                "Value weights {} contain a non-finite weight",
+                name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Surface and experiment variant a request is ranked for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValueContext {
+    pub surface: Option<String>,
+    pub variant: Option<String>,
+}
+
+impl ValueContext {
+    /// Build from request fields, treating empty strings as unset
+    pub fn new(surface: &str, variant: &str) -> Self {
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        Self {
+            surface: non_empty(surface),
+            variant: non_empty(variant),
+        }
+    }
+}
+
+/// Value model configuration
+///
+/// Resolution order: experiment variant, then surface, then `default`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValueModel {
+    #[serde(default)]
+    pub default: ValueWeights,
+    /// Per-surface weights, keyed by surface name
+    #[serde(default)]
+    pub surfaces: HashMap<String, ValueWeights>,
+    /// A/B overrides, keyed by experiment variant name
+    #[serde(default)]
+    pub variants: HashMap<String, ValueWeights>,
+}
+
+impl ValueModel {
+    /// Load from the JSON file named by `RANKING_VALUE_MODEL_PATH`, if set
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("RANKING_VALUE_MODEL_PATH") {
+            Ok(path) if !path.is_empty() => Self::load(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| {
+            RankingError::InvalidInput(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let model: ValueModel = serde_json::from_slice(&bytes).map_err(|e| {
+            RankingError::InvalidInput(format!("Invalid value model {}: {}", path.display(), e))
+        })?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.default.validate("default")?;
+        for (name, weights) in self.surfaces.iter().chain(&self.variants) {
+            weights.validate(name)?;
+        }
+        Ok(())
+    }
+
+    /// Weights to rank with for `context`
+    pub fn resolve(&self, context: &ValueContext) -> &ValueWeights {
+        context
+            .variant
+            .as_ref()
+            .and_then(|v| self.variants.get(v))
+            .or_else(|| context.surface.as_ref().and_then(|s| self.surfaces.get(s)))
+            .unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(pairs: &[(Objective, f32)]) -> ObjectiveScores {
+        pairs.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_weighted_sum_skips_missing_heads() {
+        let weights = ValueWeights::default();
+        let single = scores(&[(Objective::Engagement, 0.4)]);
+        assert!((weights.value(&single) - 0.4).abs() < 1e-6);
+
+        let multi = scores(&[
+            (Objective::Like, 0.5),
+            (Objective::Share, 0.1),
+            (Objective::NegativeFeedback, 0.05),
+        ]);
+        // 0.5 + 3 * 0.1 - 4 * 0.05
+        assert!((weights.value(&multi) - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_weighted_product_penalizes_negative_weights() {
+        let weights = ValueWeights {
+            formula: ValueFormula::WeightedProduct,
+            weights: BTreeMap::from([(Objective::Like, 1.0), (Objective::NegativeFeedback, -1.0)]),
+        };
+        let safe = scores(&[(Objective::Like, 0.5), (Objective::NegativeFeedback, 0.1)]);
+        let risky = scores(&[(Objective::Like, 0.5), (Objective::NegativeFeedback, 0.9)]);
+        assert!(weights.value(&safe) > weights.value(&risky));
+        assert_eq!(weights.value(&ObjectiveScores::new()), 0.0);
+    }
+
+    #[test]
+    fn test_resolve_prefers_variant_then_surface() {
+        let json = r#"{
+            "surfaces": {"explore": {"weights": {"share": 5.0}}},
+            "variants": {"shares_heavy": {"formula": "weighted_product", "weights": {"share": 2.0}}}
+        }"#;
+        let model: ValueModel = serde_json::from_str(json).unwrap();
+        model.validate().unwrap();
+
+        assert_eq!(model.resolve(&ValueContext::default()), &model.default);
+        assert_eq!(
+            model.resolve(&ValueContext::new("explore", "")).weights[&Objective::Share],
+            5.0
+        );
+        assert_eq!(
+            model
+                .resolve(&ValueContext::new("explore", "shares_heavy"))
+                .formula,
+            ValueFormula::WeightedProduct
+        );
+        // Unknown names fall through
+        assert_eq!(
+            model.resolve(&ValueContext::new("home", "control")),
+            &model.default
+        );
+    }
+
+    #[test]
+    fn test_objective_round_trip() {
+        for objective in Objective::ALL {
+            assert_eq!(objective.as_str().parse::<Objective>().unwrap(), objective);
+        }
+        assert!("clicks".parse::<Objective>().is_err());
+    }
+}
@@ -46,7 +46,7 @@ impl RecallStrategy for GraphRecallStrategy {
         // 2. 獲取關注用戶的最新帖子（gRPC 調用 content-service）
         let posts_per_user = (limit / following_ids.len() as i32).max(3);
         let candidates = self
-            .get_posts_from_users(&following_ids, posts_per_user)
+            .get_posts_from_users(user_id, &following_ids, posts_per_user)
             .await?;
 
         info!(
@@ -89,6 +89,7 @@ impl GraphRecallStrategy {
     /// 從 content-service 批量獲取用戶的最新帖子
     async fn get_posts_from_users(
         &self,
+        viewer_id: &str,
         user_ids: &[String],
         posts_per_user: i32,
     ) -> Result<Vec<Candidate>> {
@@ -106,6 +107,7 @@ impl GraphRecallStrategy {
                 limit: posts_per_user,
                 offset: 0,
                 status: content_proto::ContentStatus::Published as i32,
+                viewer_id: viewer_id.to_string(),
             });
 
             match client.get_user_posts(request).await {
@@ -6,7 +6,7 @@ use crate::config::RecallConfig;
 use crate::models::{Candidate, RecallSource, RecallStats};
 use anyhow::Result;
 use async_trait::async_trait;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use tonic::transport::Channel;
 use tracing::{info, warn};
 
@@ -61,6 +61,22 @@ impl RecallLayer {
         user_id: &str,
         limit_override: Option<i32>,
     ) -> Result<(Vec<Candidate>, RecallStats)> {
+        let (candidates, stats, _) = self.recall_with_sources(user_id, limit_override).await?;
+        Ok((candidates, stats))
+    }
+
+    /// 召回候選集，並返回被多個策略召回的帖子的其他召回源（排序解釋用）
+    ///
+    /// 去重後每個候選只保留第一個召回源；其餘召回源按 post_id 返回。
+    pub async fn recall_with_sources(
+        &self,
+        user_id: &str,
+        limit_override: Option<i32>,
+    ) -> Result<(
+        Vec<Candidate>,
+        RecallStats,
+        HashMap<String, Vec<RecallSource>>,
+    )> {
         let mut all_candidates = Vec::new();
         let mut stats = RecallStats::default();
 
@@ -98,6 +114,7 @@ impl RecallLayer {
         }
 
         // 去重（同一 post_id 可能來自多個策略）
+        let extra_sources = additional_sources(&all_candidates);
         let unique_candidates = self.deduplicate_and_merge(all_candidates);
 
         stats.total_candidates = unique_candidates.len() as i32;
@@ -111,23 +128,51 @@ impl RecallLayer {
             stats.total_candidates
         );
 
-        Ok((unique_candidates, stats))
+        Ok((unique_candidates, stats, extra_sources))
     }
 
     /// 去重並合併權重（相同 post_id 取最高權重的策略）
     fn deduplicate_and_merge(&self, candidates: Vec<Candidate>) -> Vec<Candidate> {
-        let mut seen: HashSet<String> = HashSet::new();
-        let mut unique: Vec<Candidate> = Vec::new();
+        deduplicate_candidates(candidates)
+    }
+}
 
-        for candidate in candidates {
-            if !seen.contains(&candidate.post_id) {
-                seen.insert(candidate.post_id.clone());
-                unique.push(candidate);
-            }
+/// 按 post_id 去重，保留先出現的召回策略（策略順序即優先級）
+pub fn deduplicate_candidates(candidates: Vec<Candidate>) -> Vec<Candidate> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut unique: Vec<Candidate> = Vec::new();
+
+    for candidate in candidates {
+        if !seen.contains(&candidate.post_id) {
+            seen.insert(candidate.post_id.clone());
+            unique.push(candidate);
         }
+    }
+
+    unique
+}
+
+/// 被多個策略召回的帖子：第一個之後的召回源
+fn additional_sources(candidates: &[Candidate]) -> HashMap<String, Vec<RecallSource>> {
+    let mut first: HashMap<&str, &RecallSource> = HashMap::with_capacity(candidates.len());
+    let mut extra: HashMap<String, Vec<RecallSource>> = HashMap::new();
 
-        unique
+    for candidate in candidates {
+        match first.get(candidate.post_id.as_str()) {
+            None => {
+                first.insert(&candidate.post_id, &candidate.recall_source);
+            }
+            Some(&primary) if *primary != candidate.recall_source => {
+                let sources = extra.entry(candidate.post_id.clone()).or_default();
+                if !sources.contains(&candidate.recall_source) {
+                    sources.push(candidate.recall_source.clone());
+                }
+            }
+            Some(_) => {}
+        }
     }
+
+    extra
 }
 
 #[cfg(test)]
@@ -178,4 +223,25 @@ mod tests {
         assert_eq!(unique[0].post_id, "post1");
         assert_eq!(unique[1].post_id, "post2");
     }
+
+    #[test]
+    fn test_additional_sources() {
+        let candidate = |post_id: &str, recall_source: RecallSource| Candidate {
+            post_id: post_id.to_string(),
+            recall_source,
+            recall_weight: 0.5,
+            timestamp: 1000,
+        };
+        let candidates = vec![
+            candidate("post1", RecallSource::Graph),
+            candidate("post1", RecallSource::Trending),
+            candidate("post1", RecallSource::Trending),
+            candidate("post2", RecallSource::Personalized),
+        ];
+
+        let extra = additional_sources(&candidates);
+
+        assert_eq!(extra.len(), 1);
+        assert_eq!(extra["post1"], vec![RecallSource::Trending]);
+    }
 }
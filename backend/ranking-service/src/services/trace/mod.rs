@@ -0,0 +1,318 @@
+// ============================================
+// Ranking Trace Module (排序解釋)
+// ============================================
+//
+// Answers "why was this post shown?" for a RankFeed request.
+//
+// While the pipeline runs, a `TraceBuilder` collects what each stage did
+// to every candidate:
+// 1. Recall: which strategies retrieved it and with what weight
+// 2. Coarse ranking: the lightweight score it survived with
+// 3. Fine ranking: score, per-feature contributions, objective heads
+// 4. Exploration: whether it was injected by the exploration policy
+// 5. Session personalization: boost applied to the fine score
+// 6. Diversity: input position and MMR relevance / diversity terms
+//
+// The finished `RankingTrace` is kept in Redis for short-term lookup by
+// request id, and a deterministic sample is persisted to ClickHouse.
+
+pub mod store;
+
+pub use store::{TraceStore, TraceStoreConfig};
+
+use crate::models::{Candidate, RankedPost, RecallSource};
+use crate::services::coarse_ranking::CoarseCandidate;
+use crate::services::diversity::DiversityDecision;
+use crate::services::ranking::{RankingLayer, ValueContext};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TraceError {
+    #[error("Redis error: {0}")]
+    RedisError(String),
+
+    #[error("ClickHouse error: {0}")]
+    ClickHouseError(String),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+}
+
+pub type Result<T> = std::result::Result<T, TraceError>;
+
+/// Explanation of one RankFeed response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankingTrace {
+    pub request_id: String,
+    pub user_id: String,
+    /// Unix milliseconds
+    pub created_at: i64,
+    #[serde(default)]
+    pub surface: Option<String>,
+    #[serde(default)]
+    pub value_model_variant: Option<String>,
+    pub exploration_policy: String,
+    /// Returned posts, in response order
+    pub posts: Vec<PostTrace>,
+}
+
+impl RankingTrace {
+    pub fn post(&self, post_id: &str) -> Option<&PostTrace> {
+        self.posts.iter().find(|p| p.post_id == post_id)
+    }
+}
+
+/// How one returned post moved through the pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostTrace {
+    pub post_id: String,
+    pub final_position: u32,
+    pub final_score: f32,
+    /// Recall strategies that retrieved the post (primary first)
+    pub recall_sources: Vec<String>,
+    pub recall_weight: f32,
+    /// None for exploration content, which bypasses coarse ranking
+    pub coarse_score: Option<f32>,
+    pub fine_position: Option<u32>,
+    pub fine_score: Option<f32>,
+    /// Weighted linear-score terms by feature
+    #[serde(default)]
+    pub feature_contributions: BTreeMap<String, f32>,
+    /// Per-objective predictions when a multi-head model scored the post
+    #[serde(default)]
+    pub objective_scores: BTreeMap<String, f32>,
+    /// Score added by session personalization
+    pub session_boost: f32,
+    pub exploration_injected: bool,
+    pub diversity: Option<DiversityTrace>,
+}
+
+/// MMR decision that placed the post
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DiversityTrace {
+    pub input_position: u32,
+    pub relevance: f32,
+    pub diversity: f32,
+    pub mmr_score: f32,
+}
+
+impl From<DiversityDecision> for DiversityTrace {
+    fn from(decision: DiversityDecision) -> Self {
+        Self {
+            input_position: decision.input_position as u32,
+            relevance: decision.relevance,
+            diversity: decision.diversity,
+            mmr_score: decision.mmr_score,
+        }
+    }
+}
+
+/// Per-post data gathered before the final stage
+#[derive(Debug, Default)]
+struct PendingPost {
+    recall_sources: Vec<String>,
+    recall_weight: f32,
+    coarse_score: Option<f32>,
+    fine_position: Option<u32>,
+    fine_score: Option<f32>,
+    feature_contributions: BTreeMap<String, f32>,
+    objective_scores: BTreeMap<String, f32>,
+    /// Score entering session personalization
+    pre_boost_score: Option<f32>,
+    exploration_injected: bool,
+}
+
+/// Collects stage outputs while a RankFeed request runs
+pub struct TraceBuilder {
+    request_id: String,
+    user_id: String,
+    context: ValueContext,
+    exploration_policy: String,
+    posts: HashMap<String, PendingPost>,
+}
+
+impl TraceBuilder {
+    pub fn new(
+        request_id: &str,
+        user_id: &str,
+        context: &ValueContext,
+        exploration_policy: &str,
+    ) -> Self {
+        Self {
+            request_id: request_id.to_string(),
+            user_id: user_id.to_string(),
+            context: context.clone(),
+            exploration_policy: exploration_policy.to_string(),
+            posts: HashMap::new(),
+        }
+    }
+
+    /// Record deduplicated recall candidates and their secondary sources
+    pub fn record_recall(
+        &mut self,
+        candidates: &[Candidate],
+        extra_sources: &HashMap<String, Vec<RecallSource>>,
+    ) {
+        for candidate in candidates {
+            let sources = std::iter::once(&candidate.recall_source)
+                .chain(extra_sources.get(&candidate.post_id).into_iter().flatten())
+                .map(|s| s.as_str().to_string())
+                .collect();
+            let pending = self.posts.entry(candidate.post_id.clone()).or_default();
+            pending.recall_sources = sources;
+            pending.recall_weight = candidate.recall_weight;
+        }
+    }
+
+    pub fn record_coarse(&mut self, ranked: &[(CoarseCandidate, f32)]) {
+        for (candidate, score) in ranked {
+            self.posts
+                .entry(candidate.candidate.post_id.clone())
+                .or_default()
+                .coarse_score = Some(*score);
+        }
+    }
+
+    /// Record fine-ranked posts (in order) with their linear-score breakdown
+    pub fn record_fine(&mut self, ranked: &[RankedPost], ranking_layer: &RankingLayer) {
+        for (position, post) in ranked.iter().enumerate() {
+            let pending = self.posts.entry(post.post_id.clone()).or_default();
+            pending.fine_position = Some(position as u32);
+            pending.fine_score = Some(post.score);
+            pending.pre_boost_score = Some(post.score);
+            pending.feature_contributions = ranking_layer
+                .feature_contributions(&post.features)
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect();
+            pending.objective_scores = post.objective_scores.clone();
+        }
+    }
+
+    /// Mark posts that entered the list without passing fine ranking
+    pub fn record_exploration(&mut self, posts: &[RankedPost]) {
+        for post in posts {
+            let pending = self.posts.entry(post.post_id.clone()).or_default();
+            if pending.fine_position.is_none() {
+                pending.exploration_injected = true;
+                pending.pre_boost_score = Some(post.score);
+            }
+        }
+    }
+
+    /// Build the trace for the final response
+    pub fn finish(self, final_posts: &[(RankedPost, DiversityDecision)]) -> RankingTrace {
+        let mut pending_posts = self.posts;
+        let posts = final_posts
+            .iter()
+            .enumerate()
+            .map(|(position, (post, decision))| {
+                let pending = pending_posts.remove(&post.post_id).unwrap_or_default();
+                let session_boost = pending
+                    .pre_boost_score
+                    .map_or(0.0, |before| decision.relevance - before);
+                PostTrace {
+                    post_id: post.post_id.clone(),
+                    final_position: position as u32,
+                    final_score: post.score,
+                    recall_sources: pending.recall_sources,
+                    recall_weight: pending.recall_weight,
+                    coarse_score: pending.coarse_score,
+                    fine_position: pending.fine_position,
+                    fine_score: pending.fine_score,
+                    feature_contributions: pending.feature_contributions,
+                    objective_scores: pending.objective_scores,
+                    session_boost,
+                    exploration_injected: pending.exploration_injected,
+                    diversity: Some((*decision).into()),
+                }
+            })
+            .collect();
+
+        RankingTrace {
+            request_id: self.request_id,
+            user_id: self.user_id,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            surface: self.context.surface,
+            value_model_variant: self.context.variant,
+            exploration_policy: self.exploration_policy,
+            posts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PostFeatures;
+    use crate::services::FeatureClient;
+    use std::sync::Arc;
+
+    fn ranked(post_id: &str, score: f32) -> RankedPost {
+        RankedPost {
+            post_id: post_id.to_string(),
+            score,
+            recall_source: RecallSource::Graph,
+            objective_scores: Default::default(),
+            features: PostFeatures {
+                engagement_score: 0.5,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn decision(input_position: usize, relevance: f32) -> DiversityDecision {
+        DiversityDecision {
+            input_position,
+            relevance,
+            diversity: 1.0,
+            mmr_score: 0.7 * relevance + 0.3,
+        }
+    }
+
+    #[test]
+    fn test_trace_follows_post_through_pipeline() {
+        let redis_client = redis::Client::open("redis://localhost:6379").unwrap();
+        let ranking_layer = RankingLayer::new(Arc::new(FeatureClient::new(redis_client)));
+
+        let candidate = Candidate {
+            post_id: "post1".to_string(),
+            recall_source: RecallSource::Graph,
+            recall_weight: 0.9,
+            timestamp: 1000,
+        };
+        let extra = HashMap::from([("post1".to_string(), vec![RecallSource::Trending])]);
+
+        let mut builder = TraceBuilder::new("req-1", "user-1", &ValueContext::default(), "ucb");
+        builder.record_recall(std::slice::from_ref(&candidate), &extra);
+        builder.record_coarse(&[(CoarseCandidate::from(candidate), 0.42)]);
+        let fine = vec![ranked("post1", 0.6)];
+        builder.record_fine(&fine, &ranking_layer);
+        let with_exploration = vec![ranked("post1", 0.6), ranked("new1", 0.5)];
+        builder.record_exploration(&with_exploration);
+
+        let trace = builder.finish(&[
+            (ranked("new1", 0.5), decision(1, 0.5)),
+            (ranked("post1", 0.6), decision(0, 0.7)),
+        ]);
+
+        assert_eq!(trace.request_id, "req-1");
+        assert_eq!(trace.posts.len(), 2);
+
+        let explored = trace.post("new1").unwrap();
+        assert_eq!(explored.final_position, 0);
+        assert!(explored.exploration_injected);
+        assert_eq!(explored.coarse_score, None);
+
+        let post = trace.post("post1").unwrap();
+        assert_eq!(post.recall_sources, vec!["graph", "trending"]);
+        assert_eq!(post.coarse_score, Some(0.42));
+        assert_eq!(post.fine_position, Some(0));
+        assert!((post.feature_contributions["engagement"] - 0.15).abs() < 1e-6);
+        assert!((post.session_boost - 0.1).abs() < 1e-6);
+        assert_eq!(post.diversity.unwrap().input_position, 0);
+        assert!(!post.exploration_injected);
+    }
+}
@@ -0,0 +1,241 @@
+// ============================================
+// Ranking Trace Store
+// ============================================
+//
+// Every trace is cached in Redis (`ranking_trace:{request_id}`) so a
+// recent request can be explained immediately. A deterministic sample,
+// chosen by hashing the request id, is also written to ClickHouse for
+// offline debugging once the Redis copy has expired.
+
+use super::{RankingTrace, Result, TraceError};
+use crate::config::ClickHouseConfig;
+use clickhouse::{Client, Row};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+const TRACE_TABLE: &str = "ranking_traces";
+
+/// Trace retention and sampling settings
+#[derive(Debug, Clone)]
+pub struct TraceStoreConfig {
+    /// Redis TTL for every trace
+    pub ttl_secs: u64,
+    /// Fraction of requests persisted to ClickHouse (0.0 - 1.0)
+    pub sample_rate: f64,
+}
+
+impl Default for TraceStoreConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 3600,
+            sample_rate: 0.01,
+        }
+    }
+}
+
+impl TraceStoreConfig {
+    /// Read `RANKING_TRACE_TTL_SECS` and `RANKING_TRACE_SAMPLE_RATE`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            ttl_secs: std::env::var("RANKING_TRACE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.ttl_secs),
+            sample_rate: std::env::var("RANKING_TRACE_SAMPLE_RATE")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .map(|rate| rate.clamp(0.0, 1.0))
+                .unwrap_or(defaults.sample_rate),
+        }
+    }
+}
+
+#[derive(Debug, Row, Serialize)]
+struct TraceRow {
+    request_id: String,
+    user_id: String,
+    /// DateTime64(3)
+    created_at: i64,
+    surface: String,
+    value_model_variant: String,
+    exploration_policy: String,
+    post_count: u32,
+    post_ids: Vec<String>,
+    trace: String,
+}
+
+#[derive(Debug, Row, Deserialize)]
+struct TraceJsonRow {
+    trace: String,
+}
+
+/// Redis + sampled ClickHouse storage for ranking traces
+pub struct TraceStore {
+    redis: redis::Client,
+    clickhouse: Option<Client>,
+    config: TraceStoreConfig,
+}
+
+impl TraceStore {
+    /// Redis-only store
+    pub fn new(redis: redis::Client, config: TraceStoreConfig) -> Self {
+        Self {
+            redis,
+            clickhouse: None,
+            config,
+        }
+    }
+
+    /// Persist sampled traces to ClickHouse
+    pub fn with_clickhouse(mut self, config: &ClickHouseConfig) -> Self {
+        self.clickhouse = Some(
+            Client::default()
+                .with_url(&config.url)
+                .with_database(&config.database)
+                .with_user(&config.username)
+                .with_password(&config.password),
+        );
+        self
+    }
+
+    fn trace_key(request_id: &str) -> String {
+        format!("ranking_trace:{}", request_id)
+    }
+
+    /// Whether `request_id` falls in the ClickHouse sample
+    ///
+    /// Uses FNV-1a so every replica makes the same decision.
+    pub fn is_sampled(&self, request_id: &str) -> bool {
+        if self.config.sample_rate <= 0.0 {
+            return false;
+        }
+        let hash = request_id
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        (hash as f64 / u64::MAX as f64) < self.config.sample_rate
+    }
+
+    /// Cache the trace and persist it if sampled
+    pub async fn save(&self, trace: &RankingTrace) -> Result<()> {
+        let json = serde_json::to_string(trace)
+            .map_err(|e| TraceError::SerializationError(e.to_string()))?;
+
+        let mut conn = self
+            .redis
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| TraceError::RedisError(e.to_string()))?;
+        let _: () = conn
+            .set_ex(
+                Self::trace_key(&trace.request_id),
+                &json,
+                self.config.ttl_secs,
+            )
+            .await
+            .map_err(|e| TraceError::RedisError(e.to_string()))?;
+
+        if let Some(client) = &self.clickhouse {
+            if self.is_sampled(&trace.request_id) {
+                Self::insert(client, trace, json).await?;
+                debug!(request_id = %trace.request_id, "Ranking trace sampled to ClickHouse");
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn insert(client: &Client, trace: &RankingTrace, json: String) -> Result<()> {
+        let row = TraceRow {
+            request_id: trace.request_id.clone(),
+            user_id: trace.user_id.clone(),
+            created_at: trace.created_at,
+            surface: trace.surface.clone().unwrap_or_default(),
+            value_model_variant: trace.value_model_variant.clone().unwrap_or_default(),
+            exploration_policy: trace.exploration_policy.clone(),
+            post_count: trace.posts.len() as u32,
+            post_ids: trace.posts.iter().map(|p| p.post_id.clone()).collect(),
+            trace: json,
+        };
+
+        let mut insert = client
+            .insert(TRACE_TABLE)
+            .map_err(|e| TraceError::ClickHouseError(e.to_string()))?;
+        insert
+            .write(&row)
+            .await
+            .map_err(|e| TraceError::ClickHouseError(e.to_string()))?;
+        insert
+            .end()
+            .await
+            .map_err(|e| TraceError::ClickHouseError(e.to_string()))
+    }
+
+    /// Look up a trace: Redis first, then sampled traces in ClickHouse
+    pub async fn get(&self, request_id: &str) -> Result<Option<RankingTrace>> {
+        let mut conn = self
+            .redis
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| TraceError::RedisError(e.to_string()))?;
+        let cached: Option<String> = conn
+            .get(Self::trace_key(request_id))
+            .await
+            .map_err(|e| TraceError::RedisError(e.to_string()))?;
+
+        let json = match (cached, &self.clickhouse) {
+            (Some(json), _) => Some(json),
+            (None, Some(client)) => client
+                .query("SELECT trace FROM ranking_traces WHERE request_id = ? LIMIT 1")
+                .bind(request_id)
+                .fetch_optional::<TraceJsonRow>()
+                .await
+                .map_err(|e| TraceError::ClickHouseError(e.to_string()))?
+                .map(|row| row.trace),
+            (None, None) => None,
+        };
+
+        match json {
+            Some(json) => {
+                let trace = serde_json::from_str(&json)
+                    .map_err(|e| TraceError::SerializationError(e.to_string()))?;
+                info!(request_id = %request_id, "Ranking trace found");
+                Ok(Some(trace))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(sample_rate: f64) -> TraceStore {
+        TraceStore::new(
+            redis::Client::open("redis://localhost:6379").unwrap(),
+            TraceStoreConfig {
+                ttl_secs: 60,
+                sample_rate,
+            },
+        )
+    }
+
+    #[test]
+    fn test_sampling_is_deterministic_and_proportional() {
+        let sampled = store(0.2);
+        let ids: Vec<String> = (0..10_000).map(|i| format!("req-{}", i)).collect();
+
+        let hits = ids.iter().filter(|id| sampled.is_sampled(id)).count();
+        assert!((1_500..2_500).contains(&hits), "sampled {} of 10000", hits);
+        assert!(ids
+            .iter()
+            .all(|id| sampled.is_sampled(id) == sampled.is_sampled(id)));
+
+        assert!(!ids.iter().any(|id| store(0.0).is_sampled(id)));
+        assert!(ids.iter().all(|id| store(1.0).is_sampled(id)));
+    }
+}
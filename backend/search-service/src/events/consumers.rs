@@ -1,6 +1,5 @@
-use crate::services::elasticsearch::{
-    ElasticsearchClient, ElasticsearchError, MessageDocument, UserDocument,
-};
+use crate::services::elasticsearch::{MessageDocument, UserDocument};
+use crate::services::search_backend::{SearchBackend, SearchError};
 use chrono::{DateTime, Utc};
 use serde::de::{DeserializeOwned, Deserializer, Error as DeError};
 use serde::Deserialize;
@@ -16,16 +15,16 @@ use uuid::Uuid;
 /// individual handlers can remain lightweight and testable.
 #[derive(Clone, Default)]
 pub struct EventContext {
-    search_backend: Option<Arc<ElasticsearchClient>>,
+    search_backend: Option<Arc<dyn SearchBackend>>,
 }
 
 impl EventContext {
-    /// Build a new event context with the provided Elasticsearch client.
-    pub fn new(search_backend: Option<Arc<ElasticsearchClient>>) -> Self {
+    /// Build a new event context with the provided search backend.
+    pub fn new(search_backend: Option<Arc<dyn SearchBackend>>) -> Self {
         Self { search_backend }
     }
 
-    fn search_backend(&self) -> Option<&Arc<ElasticsearchClient>> {
+    fn search_backend(&self) -> Option<&Arc<dyn SearchBackend>> {
         self.search_backend.as_ref()
     }
 }
@@ -36,7 +35,7 @@ pub enum EventError {
     #[error("failed to decode event payload: {0}")]
     Decode(#[from] serde_json::Error),
     #[error("search backend error: {0}")]
-    Search(#[from] ElasticsearchError),
+    Search(#[from] SearchError),
 }
 
 /// Event payload for `message_persisted`
@@ -114,7 +113,7 @@ pub async fn on_message_persisted(ctx: &EventContext, payload: &[u8]) -> Result<
     if let Err(err) = search.index_message(&document).await {
         error!(
             message_id = %event.message_id,
-            "Failed to index message in Elasticsearch: {err}"
+            "Failed to index message in search index: {err}"
         );
         return Err(EventError::Search(err));
     }
@@ -122,7 +121,7 @@ pub async fn on_message_persisted(ctx: &EventContext, payload: &[u8]) -> Result<
     debug!(
         message_id = %event.message_id,
         conversation_id = %event.conversation_id,
-        "Indexed message into Elasticsearch"
+        "Indexed message into search index"
     );
     Ok(())
 }
@@ -142,14 +141,14 @@ pub async fn on_message_deleted(ctx: &EventContext, payload: &[u8]) -> Result<()
     if let Err(err) = search.delete_message(event.message_id).await {
         error!(
             message_id = %event.message_id,
-            "Failed to remove message from Elasticsearch: {err}"
+            "Failed to remove message from search index: {err}"
         );
         return Err(EventError::Search(err));
     }
 
     debug!(
         message_id = %event.message_id,
-        "Removed message document from Elasticsearch"
+        "Removed message document from search index"
     );
     Ok(())
 }
@@ -299,7 +298,7 @@ pub async fn on_identity_event(
 }
 
 async fn handle_user_created(
-    search: &Arc<ElasticsearchClient>,
+    search: &Arc<dyn SearchBackend>,
     event: UserCreatedEventData,
 ) -> Result<(), EventError> {
     debug!(
@@ -323,20 +322,20 @@ async fn handle_user_created(
     if let Err(err) = search.index_user(&document).await {
         error!(
             user_id = %event.user_id,
-            "Failed to index user in Elasticsearch: {err}"
+            "Failed to index user in search index: {err}"
         );
         return Err(EventError::Search(err));
     }
 
     debug!(
         user_id = %event.user_id,
-        "Indexed new user into Elasticsearch"
+        "Indexed new user into search index"
     );
     Ok(())
 }
 
 async fn handle_user_profile_updated(
-    search: &Arc<ElasticsearchClient>,
+    search: &Arc<dyn SearchBackend>,
     event: UserProfileUpdatedEventData,
 ) -> Result<(), EventError> {
     debug!(
@@ -360,20 +359,20 @@ async fn handle_user_profile_updated(
     if let Err(err) = search.index_user(&document).await {
         error!(
             user_id = %event.user_id,
-            "Failed to update user in Elasticsearch: {err}"
+            "Failed to update user in search index: {err}"
         );
         return Err(EventError::Search(err));
     }
 
     debug!(
         user_id = %event.user_id,
-        "Updated user in Elasticsearch"
+        "Updated user in search index"
     );
     Ok(())
 }
 
 async fn handle_user_deleted(
-    search: &Arc<ElasticsearchClient>,
+    search: &Arc<dyn SearchBackend>,
     event: UserDeletedEventData,
 ) -> Result<(), EventError> {
     debug!(
@@ -386,14 +385,14 @@ async fn handle_user_deleted(
         if let Err(err) = search.delete_user(event.user_id).await {
             error!(
                 user_id = %event.user_id,
-                "Failed to delete user from Elasticsearch: {err}"
+                "Failed to delete user from search index: {err}"
             );
             return Err(EventError::Search(err));
         }
 
         debug!(
             user_id = %event.user_id,
-            "Deleted user from Elasticsearch"
+            "Deleted user from search index"
         );
     }
 
@@ -1,4 +1,4 @@
-use crate::services::{elasticsearch, ElasticsearchClient};
+use crate::services::{elasticsearch, SearchBackend, SearchError};
 use chrono::{DateTime, Utc};
 use rdkafka::consumer::{Consumer, StreamConsumer};
 use rdkafka::message::Message;
@@ -14,8 +14,8 @@ use uuid::Uuid;
 pub enum KafkaConsumerError {
     #[error("Kafka error: {0}")]
     Kafka(#[from] rdkafka::error::KafkaError),
-    #[error("Elasticsearch error: {0}")]
-    Elasticsearch(#[from] elasticsearch::ElasticsearchError),
+    #[error("search backend error: {0}")]
+    Search(#[from] SearchError),
     #[error("deserialization error: {0}")]
     Serde(#[from] serde_json::Error),
     #[error("invalid message format")]
@@ -87,7 +87,7 @@ pub struct CommentDeletedEvent {
 
 pub struct SearchIndexConsumer {
     consumer: Arc<StreamConsumer>,
-    es_client: Arc<ElasticsearchClient>,
+    search: Arc<dyn SearchBackend>,
 }
 
 impl SearchIndexConsumer {
@@ -95,7 +95,7 @@ impl SearchIndexConsumer {
         kafka_brokers: &str,
         group_id: &str,
         topics: &[&str],
-        es_client: ElasticsearchClient,
+        search: Arc<dyn SearchBackend>,
     ) -> Result<Self, KafkaConsumerError> {
         let consumer: StreamConsumer = ClientConfig::new()
             .set("group.id", group_id)
@@ -115,7 +115,7 @@ impl SearchIndexConsumer {
 
         Ok(Self {
             consumer: Arc::new(consumer),
-            es_client: Arc::new(es_client),
+            search,
         })
     }
 
@@ -190,7 +190,7 @@ impl SearchIndexConsumer {
             created_at: event.created_at,
         };
 
-        self.es_client.index_post(&doc).await?;
+        self.search.index_post(&doc).await?;
         Ok(())
     }
 
@@ -211,14 +211,14 @@ impl SearchIndexConsumer {
             created_at: Utc::now(),
         };
 
-        self.es_client.index_post(&doc).await?;
+        self.search.index_post(&doc).await?;
         Ok(())
     }
 
     async fn handle_post_deleted(&self, event: PostDeletedEvent) -> Result<(), KafkaConsumerError> {
         info!("Deleting post from index: {}", event.post_id);
 
-        self.es_client.delete_post(event.post_id).await?;
+        self.search.delete_post(event.post_id).await?;
         Ok(())
     }
 
@@ -237,7 +237,7 @@ impl SearchIndexConsumer {
             follower_count: event.follower_count,
         };
 
-        self.es_client.index_user(&doc).await?;
+        self.search.index_user(&doc).await?;
         Ok(())
     }
 
@@ -255,7 +255,7 @@ impl SearchIndexConsumer {
             created_at: event.created_at,
         };
 
-        self.es_client.index_comment(&doc).await?;
+        self.search.index_comment(&doc).await?;
         Ok(())
     }
 
@@ -265,7 +265,7 @@ impl SearchIndexConsumer {
     ) -> Result<(), KafkaConsumerError> {
         info!("Deleting comment from index: {}", event.comment_id);
 
-        self.es_client.delete_comment(event.comment_id).await?;
+        self.search.delete_comment(event.comment_id).await?;
         Ok(())
     }
 
@@ -1,4 +1,4 @@
-use crate::services::{ClickHouseClient, ElasticsearchClient, RedisCache};
+use crate::services::{ClickHouseClient, RedisCache, SearchBackend};
 use chrono::Utc;
 use std::sync::Arc;
 use std::time::Instant;
@@ -20,19 +20,19 @@ use nova::search_service::v2::*;
 
 #[derive(Clone)]
 pub struct SearchServiceImpl {
-    es_client: Arc<ElasticsearchClient>,
+    search: Arc<dyn SearchBackend>,
     ch_client: Arc<ClickHouseClient>,
     redis: Arc<RedisCache>,
 }
 
 impl SearchServiceImpl {
     pub fn new(
-        es_client: ElasticsearchClient,
+        search: Arc<dyn SearchBackend>,
         ch_client: ClickHouseClient,
         redis: RedisCache,
     ) -> Self {
         Self {
-            es_client: Arc::new(es_client),
+            search,
             ch_client: Arc::new(ch_client),
             redis: Arc::new(redis),
         }
@@ -68,10 +68,10 @@ impl SearchService for SearchServiceImpl {
         }
 
         // Perform search with fallback
-        let search_result = match self.es_client.full_text_search(query, limit, offset).await {
+        let search_result = match self.search.full_text_search(query, limit, offset).await {
             Ok(results) => results,
             Err(e) => {
-                error!("Elasticsearch error: {}", e);
+                error!("{} search error: {}", self.search.name(), e);
                 // Fallback to cached results if available
                 if let Ok(cached) = self.redis.get_search_results_cache(&cache_key).await {
                     warn!("Using fallback cache for query: {}", query);
@@ -171,7 +171,7 @@ impl SearchService for SearchServiceImpl {
         let offset = req.offset.max(0) as i64;
 
         let posts = self
-            .es_client
+            .search
             .search_posts(query, limit, offset)
             .await
             .map_err(|e| {
@@ -217,7 +217,7 @@ impl SearchService for SearchServiceImpl {
         let offset = req.offset.max(0) as i64;
 
         let users = self
-            .es_client
+            .search
             .search_users(query, limit, offset, req.verified_only)
             .await
             .map_err(|e| {
@@ -261,7 +261,7 @@ impl SearchService for SearchServiceImpl {
         let limit = req.limit.max(1).min(100) as i64;
 
         let tags = self
-            .es_client
+            .search
             .search_hashtags(query, limit)
             .await
             .map_err(|e| {
@@ -305,7 +305,7 @@ impl SearchService for SearchServiceImpl {
 
         // Search posts with exact hashtag match
         let posts = self
-            .es_client
+            .search
             .search_posts(hashtag, limit, offset)
             .await
             .map_err(|e| {
@@ -367,9 +367,9 @@ impl SearchService for SearchServiceImpl {
             return Ok(Response::new(GetSearchSuggestionsResponse { suggestions }));
         }
 
-        // Get suggestions from Elasticsearch (hashtags)
+        // Hashtag prefix matches from the search backend
         let hashtags = self
-            .es_client
+            .search
             .search_hashtags(&partial_query, limit as i64)
             .await
             .unwrap_or_default();
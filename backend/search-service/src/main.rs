@@ -10,10 +10,10 @@ use search_service::events::consumers::EventContext;
 use search_service::events::kafka::{spawn_message_consumer, KafkaConsumerConfig};
 use search_service::openapi::ApiDoc;
 use search_service::search_suggestions::SearchSuggestionsService;
-use search_service::services::elasticsearch::{
-    ElasticsearchClient, ElasticsearchError, PostDocument, UserDocument,
+use search_service::services::elasticsearch::{ElasticsearchClient, PostDocument, UserDocument};
+use search_service::services::{
+    ClickHouseClient, RedisCache, SearchBackend, SearchBackendConfig, SearchError,
 };
-use search_service::services::{ClickHouseClient, RedisCache};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -89,8 +89,8 @@ impl actix_web::ResponseError for AppError {
     }
 }
 
-impl From<ElasticsearchError> for AppError {
-    fn from(err: ElasticsearchError) -> Self {
+impl From<SearchError> for AppError {
+    fn from(err: SearchError) -> Self {
         AppError::SearchBackend(err.to_string())
     }
 }
@@ -237,7 +237,7 @@ fn default_reindex_batch_size() -> i64 {
 struct AppState {
     db: PgPool,
     redis: ConnectionManager,
-    search_backend: Option<Arc<ElasticsearchClient>>,
+    search_backend: Option<Arc<dyn SearchBackend>>,
 }
 
 // ============================================
@@ -260,7 +260,6 @@ async fn search_users(
         }));
     }
 
-    // Use Elasticsearch for user search
     if let Some(search_backend) = &state.search_backend {
         match search_backend
             .search_users(&params.q, params.limit, params.offset, false)
@@ -274,7 +273,8 @@ async fn search_users(
             }
             Err(err) => {
                 tracing::warn!(
-                    "Elasticsearch user search failed for '{}': {}",
+                    "{} user search failed for '{}': {}",
+                    search_backend.name(),
                     params.q,
                     err
                 );
@@ -283,7 +283,7 @@ async fn search_users(
     }
 
     // Fallback: return empty result (PostgreSQL users table doesn't exist)
-    tracing::warn!("No Elasticsearch backend available for user search");
+    tracing::warn!("No search backend available for user search");
     Ok(Json(UserSearchResponse {
         users: vec![],
         total: 0,
@@ -318,7 +318,12 @@ async fn search_posts(
                 }));
             }
             Err(err) => {
-                tracing::warn!("Elasticsearch search failed for '{}': {}", params.q, err);
+                tracing::warn!(
+                    "{} post search failed for '{}': {}",
+                    search_backend.name(),
+                    params.q,
+                    err
+                );
             }
         }
     }
@@ -427,7 +432,7 @@ async fn reindex_posts(
     let search_backend = state
         .search_backend
         .clone()
-        .ok_or_else(|| AppError::Config("Elasticsearch backend disabled".into()))?;
+        .ok_or_else(|| AppError::Config("Search backend disabled".into()))?;
 
     let limit = payload.batch_size.clamp(1, 1_000);
     let offset = payload.offset.max(0);
@@ -875,8 +880,12 @@ async fn main() -> std::io::Result<()> {
     };
 
     // Create application state
-    let search_backend = match std::env::var("ELASTICSEARCH_URL") {
-        Ok(url) if !url.is_empty() => {
+    let search_config = SearchBackendConfig::from_env();
+    let elasticsearch_url = std::env::var("ELASTICSEARCH_URL")
+        .ok()
+        .filter(|url| !url.is_empty() && search_config.uses_elasticsearch());
+    let elasticsearch = match &elasticsearch_url {
+        Some(url) => {
             let post_index = std::env::var("ELASTICSEARCH_POST_INDEX")
                 .unwrap_or_else(|_| "nova_posts".to_string());
             let message_index = std::env::var("ELASTICSEARCH_MESSAGE_INDEX")
@@ -887,7 +896,7 @@ async fn main() -> std::io::Result<()> {
                 .unwrap_or_else(|_| "nova_comments".to_string());
 
             match ElasticsearchClient::new(
-                &url,
+                url,
                 &post_index,
                 &message_index,
                 &user_index,
@@ -903,7 +912,7 @@ async fn main() -> std::io::Result<()> {
                         user_index,
                         comment_index
                     );
-                    Some(Arc::new(client))
+                    Some(client)
                 }
                 Err(err) => {
                     tracing::warn!("Failed to initialize Elasticsearch client: {}", err);
@@ -911,12 +920,31 @@ async fn main() -> std::io::Result<()> {
                 }
             }
         }
-        _ => {
+        None => None,
+    };
+
+    let search_backend = match search_config
+        .select(elasticsearch, elasticsearch_url.is_some())
+        .await
+    {
+        Ok(Some(backend)) => {
+            tracing::info!(
+                "Search backend: {} ({:?})",
+                backend.name(),
+                search_config.kind
+            );
+            Some(backend)
+        }
+        Ok(None) => {
             tracing::info!(
-                "Elasticsearch URL not set. Falling back to PostgreSQL full-text search"
+                "No search backend configured. Falling back to PostgreSQL full-text search"
             );
             None
         }
+        Err(err) => {
+            tracing::warn!("Failed to open embedded search index: {}", err);
+            None
+        }
     };
 
     if let Some(search_backend_clone) = search_backend.clone() {
@@ -931,7 +959,7 @@ async fn main() -> std::io::Result<()> {
     }
 
     // Clone clients for gRPC service before moving into state
-    let grpc_es = search_backend.clone();
+    let grpc_search = search_backend.clone();
     let grpc_ch = ch_client.clone();
     let grpc_redis = redis_cache.clone();
 
@@ -964,14 +992,16 @@ async fn main() -> std::io::Result<()> {
             .await;
 
         // Create gRPC service with clients (only if all are available)
-        let svc = if let (Some(es), Some(ch), Some(redis)) = (grpc_es, grpc_ch, grpc_redis) {
-            let es = Arc::try_unwrap(es).unwrap_or_else(|arc| (*arc).clone());
+        let svc = if let (Some(search), Some(ch), Some(redis)) = (grpc_search, grpc_ch, grpc_redis)
+        {
             let ch = Arc::try_unwrap(ch).unwrap_or_else(|arc| (*arc).clone());
             let redis = Arc::try_unwrap(redis).unwrap_or_else(|arc| (*arc).clone());
-            Some(search_service::grpc::SearchServiceImpl::new(es, ch, redis))
+            Some(search_service::grpc::SearchServiceImpl::new(
+                search, ch, redis,
+            ))
         } else {
             tracing::error!(
-                "Cannot start gRPC service: missing required clients (ES/ClickHouse/Redis)"
+                "Cannot start gRPC service: missing required clients (search backend/ClickHouse/Redis)"
             );
             None
         };
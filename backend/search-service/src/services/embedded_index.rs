@@ -0,0 +1,772 @@
+use crate::services::elasticsearch::{
+    CommentDocument, MessageDocument, PostDocument, UserDocument,
+};
+use crate::services::search_backend::{SearchBackend, SearchError};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// BM25 term-frequency saturation (Lucene default)
+const BM25_K1: f32 = 1.2;
+/// BM25 document-length normalisation (Lucene default)
+const BM25_B: f32 = 0.75;
+
+const SNAPSHOT_FILE: &str = "index.json";
+
+/// Elasticsearch's `_english_` stop word list
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Analyzer {
+    /// Lowercased word tokens (Elasticsearch `standard`)
+    Standard,
+    /// Standard plus stop words and plural stemming (the `english` analyzers
+    /// used by the Elasticsearch mappings)
+    English,
+}
+
+fn analyze(text: &str, analyzer: Analyzer) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .filter_map(|token| match analyzer {
+            Analyzer::Standard => Some(token),
+            Analyzer::English if STOP_WORDS.contains(&token.as_str()) => None,
+            Analyzer::English => Some(minimal_stem(&token)),
+        })
+        .collect()
+}
+
+/// Lucene's minimal English stemmer: strips plurals only, so prefix queries
+/// still line up with what users type.
+fn minimal_stem(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    let len = chars.len();
+    if len < 3 || chars[len - 1] != 's' {
+        return token.to_string();
+    }
+    match chars[len - 2] {
+        'u' | 's' => token.to_string(),
+        'e' if len > 3
+            && chars[len - 3] == 'i'
+            && chars[len - 4] != 'a'
+            && chars[len - 4] != 'e' =>
+        {
+            let mut stem: String = chars[..len - 3].iter().collect();
+            stem.push('y');
+            stem
+        }
+        'e' if matches!(chars[len - 3], 'i' | 'a' | 'o' | 'e') => token.to_string(),
+        _ => chars[..len - 1].iter().collect(),
+    }
+}
+
+/// Inverted index for one field: term -> (document key -> term frequency).
+/// Terms are kept sorted so prefix queries are a range scan.
+#[derive(Debug, Default)]
+struct FieldIndex {
+    postings: BTreeMap<String, HashMap<String, u32>>,
+    lengths: HashMap<String, u32>,
+    total_length: u64,
+}
+
+impl FieldIndex {
+    fn insert(&mut self, key: &str, terms: &[String]) {
+        if terms.is_empty() {
+            return;
+        }
+        self.lengths.insert(key.to_string(), terms.len() as u32);
+        self.total_length += terms.len() as u64;
+        for term in terms {
+            *self
+                .postings
+                .entry(term.clone())
+                .or_default()
+                .entry(key.to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
+    fn remove(&mut self, key: &str, terms: &[String]) {
+        if let Some(length) = self.lengths.remove(key) {
+            self.total_length -= u64::from(length);
+        }
+        for term in terms {
+            if let Some(docs) = self.postings.get_mut(term) {
+                docs.remove(key);
+                if docs.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+    }
+
+    /// BM25 score of every document matching any of `terms`
+    fn bm25(&self, terms: &[String]) -> HashMap<&str, f32> {
+        let mut scores = HashMap::new();
+        let doc_count = self.lengths.len() as f32;
+        if doc_count == 0.0 {
+            return scores;
+        }
+        let avg_length = self.total_length as f32 / doc_count;
+
+        let unique: HashSet<&String> = terms.iter().collect();
+        for term in unique {
+            let Some(docs) = self.postings.get(term) else {
+                continue;
+            };
+            let doc_freq = docs.len() as f32;
+            let idf = (1.0 + (doc_count - doc_freq + 0.5) / (doc_freq + 0.5)).ln();
+            for (key, &freq) in docs {
+                let freq = freq as f32;
+                let length = self.lengths.get(key).copied().unwrap_or(1) as f32;
+                let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * length / avg_length);
+                *scores.entry(key.as_str()).or_insert(0.0) +=
+                    idf * freq * (BM25_K1 + 1.0) / (freq + norm);
+            }
+        }
+        scores
+    }
+
+    /// Terms starting with `prefix`, with the documents containing them
+    fn prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = (&'a String, &'a HashMap<String, u32>)> + 'a {
+        self.postings
+            .range(prefix.to_string()..)
+            .take_while(move |(term, _)| term.starts_with(prefix))
+    }
+}
+
+/// A document type stored in the embedded index
+trait Indexable {
+    fn key(&self) -> String;
+    /// Analyzed terms per field
+    fn fields(&self) -> Vec<(&'static str, Vec<String>)>;
+}
+
+fn optional_text(text: &Option<String>) -> Vec<String> {
+    text.as_deref()
+        .map(|t| analyze(t, Analyzer::English))
+        .unwrap_or_default()
+}
+
+impl Indexable for PostDocument {
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Vec<String>)> {
+        vec![
+            ("title", optional_text(&self.title)),
+            ("content", optional_text(&self.content)),
+            (
+                "tags",
+                self.tags.iter().map(|tag| tag.to_lowercase()).collect(),
+            ),
+        ]
+    }
+}
+
+impl Indexable for UserDocument {
+    fn key(&self) -> String {
+        self.user_id.to_string()
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Vec<String>)> {
+        vec![
+            ("username_exact", vec![self.username.to_lowercase()]),
+            ("username", analyze(&self.username, Analyzer::Standard)),
+            (
+                "display_name",
+                analyze(&self.display_name, Analyzer::English),
+            ),
+            ("bio", optional_text(&self.bio)),
+        ]
+    }
+}
+
+#[derive(Debug)]
+struct Collection<D> {
+    docs: HashMap<String, D>,
+    fields: HashMap<&'static str, FieldIndex>,
+}
+
+impl<D> Default for Collection<D> {
+    fn default() -> Self {
+        Self {
+            docs: HashMap::new(),
+            fields: HashMap::new(),
+        }
+    }
+}
+
+impl<D: Indexable> Collection<D> {
+    fn upsert(&mut self, doc: D) {
+        let key = doc.key();
+        self.remove(&key);
+        for (field, terms) in doc.fields() {
+            self.fields.entry(field).or_default().insert(&key, &terms);
+        }
+        self.docs.insert(key, doc);
+    }
+
+    fn remove(&mut self, key: &str) -> bool {
+        let Some(doc) = self.docs.remove(key) else {
+            return false;
+        };
+        for (field, terms) in doc.fields() {
+            if let Some(index) = self.fields.get_mut(field) {
+                index.remove(key, &terms);
+            }
+        }
+        true
+    }
+
+    fn bm25(&self, field: &str, terms: &[String]) -> HashMap<&str, f32> {
+        self.fields
+            .get(field)
+            .map(|index| index.bm25(terms))
+            .unwrap_or_default()
+    }
+
+    /// Keys of documents with a term in `field` starting with `prefix`
+    fn prefix_matches<'a>(&'a self, field: &str, prefix: &'a str) -> HashSet<&'a str> {
+        let mut keys = HashSet::new();
+        if let Some(index) = self.fields.get(field) {
+            for (_, docs) in index.prefix(prefix) {
+                keys.extend(docs.keys().map(String::as_str));
+            }
+        }
+        keys
+    }
+}
+
+/// Merge field scores the way `multi_match` `best_fields` does: the best
+/// boosted field score wins.
+fn best_field<'a>(scores: &mut HashMap<&'a str, f32>, field: HashMap<&'a str, f32>, boost: f32) {
+    for (key, score) in field {
+        let entry = scores.entry(key).or_insert(0.0);
+        *entry = entry.max(score * boost);
+    }
+}
+
+fn paginate<D: Clone>(
+    mut ranked: Vec<(f32, &D)>,
+    limit: i64,
+    offset: i64,
+    tiebreak: impl Fn(&D, &D) -> Ordering,
+) -> Vec<D> {
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .partial_cmp(a_score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| tiebreak(a, b))
+    });
+    ranked
+        .into_iter()
+        .skip(offset.max(0) as usize)
+        .take(limit.clamp(1, 100) as usize)
+        .map(|(_, doc)| doc.clone())
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct IndexState {
+    posts: Collection<PostDocument>,
+    users: Collection<UserDocument>,
+}
+
+/// On-disk form. Only documents are stored; postings are rebuilt on load so
+/// analyzer changes never leave a stale index behind.
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    posts: Vec<&'a PostDocument>,
+    users: Vec<&'a UserDocument>,
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    #[serde(default)]
+    posts: Vec<PostDocument>,
+    #[serde(default)]
+    users: Vec<UserDocument>,
+}
+
+impl IndexState {
+    fn from_snapshot(snapshot: Snapshot) -> Self {
+        let mut state = Self::default();
+        snapshot
+            .posts
+            .into_iter()
+            .for_each(|doc| state.posts.upsert(doc));
+        snapshot
+            .users
+            .into_iter()
+            .for_each(|doc| state.users.upsert(doc));
+        state
+    }
+
+    fn snapshot(&self) -> SnapshotRef<'_> {
+        SnapshotRef {
+            posts: self.posts.docs.values().collect(),
+            users: self.users.docs.values().collect(),
+        }
+    }
+}
+
+/// In-process inverted index used when Elasticsearch is unavailable.
+///
+/// Ranks with BM25 and mirrors the Elasticsearch query shapes (see
+/// [`SearchBackend`]). Changes are held in memory and written to
+/// `<path>/index.json` by [`EmbeddedIndex::flush`], which
+/// [`EmbeddedIndex::spawn_flusher`] runs periodically.
+pub struct EmbeddedIndex {
+    path: Option<PathBuf>,
+    state: RwLock<IndexState>,
+    dirty: AtomicBool,
+    flush_lock: tokio::sync::Mutex<()>,
+}
+
+impl EmbeddedIndex {
+    /// Open (or create) the index stored under `dir`.
+    pub async fn open(dir: &Path) -> Result<Self, SearchError> {
+        tokio::fs::create_dir_all(dir).await?;
+        let state = match tokio::fs::read(dir.join(SNAPSHOT_FILE)).await {
+            Ok(bytes) => IndexState::from_snapshot(serde_json::from_slice(&bytes)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => IndexState::default(),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Self {
+            path: Some(dir.to_path_buf()),
+            state: RwLock::new(state),
+            dirty: AtomicBool::new(false),
+            flush_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// Index that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            state: RwLock::new(IndexState::default()),
+            dirty: AtomicBool::new(false),
+            flush_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn post_count(&self) -> usize {
+        self.read().posts.docs.len()
+    }
+
+    pub fn user_count(&self) -> usize {
+        self.read().users.docs.len()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, IndexState> {
+        self.state
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Apply a change and mark the index for the next flush
+    fn update<R>(&self, change: impl FnOnce(&mut IndexState) -> R) -> R {
+        let mut state = self
+            .state
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let result = change(&mut state);
+        // Set while still holding the lock so a concurrent flush either sees
+        // the change or leaves the flag set for the next one
+        self.dirty.store(true, AtomicOrdering::SeqCst);
+        result
+    }
+
+    /// Write pending changes to disk. Returns whether a snapshot was written.
+    pub async fn flush(&self) -> Result<bool, SearchError> {
+        let Some(dir) = &self.path else {
+            return Ok(false);
+        };
+        let _guard = self.flush_lock.lock().await;
+        if !self.dirty.swap(false, AtomicOrdering::SeqCst) {
+            return Ok(false);
+        }
+
+        let result = async {
+            let bytes = serde_json::to_vec(&self.read().snapshot())?;
+            // Write-then-rename so a crash never leaves a truncated snapshot
+            let tmp = dir.join(format!("{}.tmp", SNAPSHOT_FILE));
+            tokio::fs::write(&tmp, bytes).await?;
+            tokio::fs::rename(&tmp, dir.join(SNAPSHOT_FILE)).await?;
+            Ok::<_, SearchError>(())
+        }
+        .await;
+
+        if let Err(err) = result {
+            self.dirty.store(true, AtomicOrdering::SeqCst);
+            return Err(err);
+        }
+        debug!("Embedded search index flushed to {}", dir.display());
+        Ok(true)
+    }
+
+    /// Flush pending changes every `interval`.
+    pub fn spawn_flusher(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(err) = self.flush().await {
+                    warn!("Failed to flush embedded search index: {}", err);
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl SearchBackend for EmbeddedIndex {
+    fn name(&self) -> &'static str {
+        "embedded"
+    }
+
+    async fn search_posts(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PostDocument>, SearchError> {
+        let terms = analyze(query, Analyzer::English);
+        let tag = vec![query.trim().to_lowercase()];
+
+        let state = self.read();
+        let posts = &state.posts;
+        let mut scores = HashMap::new();
+        best_field(&mut scores, posts.bm25("title", &terms), 2.0);
+        best_field(&mut scores, posts.bm25("content", &terms), 1.0);
+        best_field(&mut scores, posts.bm25("tags", &tag), 1.0);
+
+        let ranked = scores
+            .into_iter()
+            .filter_map(|(key, score)| posts.docs.get(key).map(|doc| (score, doc)))
+            .collect();
+        Ok(paginate(ranked, limit, offset, |a: &PostDocument, b| {
+            b.created_at.cmp(&a.created_at)
+        }))
+    }
+
+    async fn search_users(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+        verified_only: bool,
+    ) -> Result<Vec<UserDocument>, SearchError> {
+        let lowered = query.trim().to_lowercase();
+        let terms = analyze(query, Analyzer::Standard);
+        let english_terms = analyze(query, Analyzer::English);
+
+        let state = self.read();
+        let users = &state.users;
+
+        // Full-text match across username^3, display_name^2 and bio
+        let mut scores = HashMap::new();
+        best_field(&mut scores, users.bm25("username", &terms), 3.0);
+        best_field(&mut scores, users.bm25("display_name", &english_terms), 2.0);
+        best_field(&mut scores, users.bm25("bio", &english_terms), 1.0);
+
+        // Exact username match dominates
+        for (key, score) in users.bm25("username_exact", std::slice::from_ref(&lowered)) {
+            *scores.entry(key).or_insert(0.0) += score * 10.0;
+        }
+
+        // Partial matches as the user types
+        if !lowered.is_empty() {
+            for key in users.prefix_matches("username", &lowered) {
+                *scores.entry(key).or_insert(0.0) += 2.0;
+            }
+            for key in users.prefix_matches("display_name", &lowered) {
+                *scores.entry(key).or_insert(0.0) += 1.5;
+            }
+        }
+
+        let ranked = scores
+            .into_iter()
+            .filter_map(|(key, score)| users.docs.get(key).map(|doc| (score, doc)))
+            .filter(|(_, doc)| !verified_only || doc.is_verified)
+            .collect();
+        Ok(paginate(ranked, limit, offset, |a: &UserDocument, b| {
+            b.follower_count.cmp(&a.follower_count)
+        }))
+    }
+
+    async fn search_hashtags(&self, query: &str, limit: i64) -> Result<Vec<String>, SearchError> {
+        let prefix = query.trim().to_lowercase();
+
+        let state = self.read();
+        let mut ranked: Vec<(&str, i64)> = state
+            .posts
+            .fields
+            .get("tags")
+            .map(|tags| {
+                tags.prefix(&prefix)
+                    .map(|(tag, posts)| (tag.as_str(), posts.len() as i64))
+                    .collect()
+            })
+            .unwrap_or_default();
+        ranked.sort_by(|(a_tag, a), (b_tag, b)| b.cmp(a).then_with(|| a_tag.cmp(b_tag)));
+        Ok(ranked
+            .into_iter()
+            .take(limit.clamp(1, 100) as usize)
+            .map(|(tag, _)| tag.to_string())
+            .collect())
+    }
+
+    async fn index_post(&self, doc: &PostDocument) -> Result<(), SearchError> {
+        self.update(|state| state.posts.upsert(doc.clone()));
+        Ok(())
+    }
+
+    async fn delete_post(&self, id: Uuid) -> Result<(), SearchError> {
+        self.update(|state| state.posts.remove(&id.to_string()));
+        Ok(())
+    }
+
+    async fn bulk_index_posts(&self, docs: Vec<PostDocument>) -> Result<(), SearchError> {
+        self.update(|state| {
+            for doc in docs {
+                state.posts.upsert(doc);
+            }
+        });
+        Ok(())
+    }
+
+    async fn index_user(&self, doc: &UserDocument) -> Result<(), SearchError> {
+        self.update(|state| state.users.upsert(doc.clone()));
+        Ok(())
+    }
+
+    async fn delete_user(&self, user_id: Uuid) -> Result<(), SearchError> {
+        self.update(|state| state.users.remove(&user_id.to_string()));
+        Ok(())
+    }
+
+    // Messages and comments are only searched through Elasticsearch; direct
+    // message plaintext must not end up in a local snapshot file
+    async fn index_message(&self, _doc: &MessageDocument) -> Result<(), SearchError> {
+        Ok(())
+    }
+
+    async fn delete_message(&self, _id: Uuid) -> Result<(), SearchError> {
+        Ok(())
+    }
+
+    async fn index_comment(&self, _doc: &CommentDocument) -> Result<(), SearchError> {
+        Ok(())
+    }
+
+    async fn delete_comment(&self, _id: Uuid) -> Result<(), SearchError> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), SearchError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration as ChronoDuration, Utc};
+
+    fn post(title: &str, content: &str, tags: &[&str]) -> PostDocument {
+        PostDocument {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            title: Some(title.to_string()),
+            content: Some(content.to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            likes_count: 0,
+            comments_count: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn user(username: &str, display_name: &str, is_verified: bool, followers: i32) -> UserDocument {
+        UserDocument {
+            user_id: Uuid::new_v4(),
+            username: username.to_string(),
+            display_name: display_name.to_string(),
+            bio: None,
+            avatar_url: None,
+            location: None,
+            interests: vec![],
+            is_verified,
+            follower_count: followers,
+        }
+    }
+
+    #[test]
+    fn test_analyze_english() {
+        assert_eq!(
+            analyze("The Cities of Lights, and ferries!", Analyzer::English),
+            vec!["city", "light", "ferry"]
+        );
+        assert_eq!(minimal_stem("glass"), "glass");
+        assert_eq!(minimal_stem("status"), "status");
+        assert_eq!(minimal_stem("shoes"), "shoes");
+        assert_eq!(minimal_stem("horses"), "horse");
+        assert_eq!(
+            analyze("Night_Owl 2024", Analyzer::Standard),
+            vec!["night_owl", "2024"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_posts_ranks_with_bm25() {
+        let index = EmbeddedIndex::in_memory();
+        let in_title = post("Sunset", "walk on the beach", &[]);
+        let repeated = post("Evening", "sunset sunset over hills", &[]);
+        let passing = post(
+            "Trip",
+            "long day of driving, hiking and eating, then a sunset",
+            &[],
+        );
+        let unrelated = post("Breakfast", "pancakes", &[]);
+        for doc in [&in_title, &repeated, &passing, &unrelated] {
+            index.index_post(doc).await.unwrap();
+        }
+
+        let ids: Vec<Uuid> = index
+            .search_posts("sunsets", 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec![in_title.id, repeated.id, passing.id]);
+
+        let page: Vec<Uuid> = index
+            .search_posts("sunset", 1, 1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(page, vec![repeated.id]);
+
+        index.delete_post(in_title.id).await.unwrap();
+        assert_eq!(index.search_posts("sunset", 10, 0).await.unwrap().len(), 2);
+        assert!(index.search_posts("the", 10, 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_users_exact_prefix_and_verified() {
+        let index = EmbeddedIndex::in_memory();
+        let exact = user("alice", "Alice Liddell", false, 10);
+        let popular = user("alicent", "Queen Alicent", true, 5_000);
+        let other = user("bob", "Bob", true, 1);
+        for doc in [&exact, &popular, &other] {
+            index.index_user(doc).await.unwrap();
+        }
+
+        let found = index.search_users("Alice", 10, 0, false).await.unwrap();
+        assert_eq!(found[0].user_id, exact.user_id);
+        assert_eq!(found[1].user_id, popular.user_id);
+        assert_eq!(found.len(), 2);
+
+        let verified = index.search_users("ali", 10, 0, true).await.unwrap();
+        assert_eq!(verified.len(), 1);
+        assert_eq!(verified[0].user_id, popular.user_id);
+
+        // Re-indexing replaces the old terms
+        let mut renamed = other.clone();
+        renamed.username = "robert".to_string();
+        renamed.display_name = "Robert".to_string();
+        index.index_user(&renamed).await.unwrap();
+        assert!(index
+            .search_users("bob", 10, 0, false)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(index.user_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_search_hashtags_prefix_by_usage() {
+        let index = EmbeddedIndex::in_memory();
+        index
+            .index_post(&post("a", "", &["travel", "food"]))
+            .await
+            .unwrap();
+        index.index_post(&post("b", "", &["travel"])).await.unwrap();
+        index.index_post(&post("c", "", &["trains"])).await.unwrap();
+
+        assert_eq!(
+            index.search_hashtags("tra", 10).await.unwrap(),
+            vec!["travel", "trains"]
+        );
+        assert_eq!(
+            index.search_hashtags("tra", 1).await.unwrap(),
+            vec!["travel"]
+        );
+        assert!(index.search_hashtags("x", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("embedded-index-{}", Uuid::new_v4()));
+        let index = EmbeddedIndex::open(&dir).await.unwrap();
+        assert!(!index.flush().await.unwrap(), "nothing to write yet");
+
+        let mut older = post("Harbour lights", "", &["night"]);
+        older.created_at = Utc::now() - ChronoDuration::days(1);
+        index.index_post(&older).await.unwrap();
+        index
+            .index_user(&user("carol", "Carol", false, 3))
+            .await
+            .unwrap();
+        index
+            .index_message(&MessageDocument {
+                id: Uuid::new_v4(),
+                conversation_id: Uuid::new_v4(),
+                sender_id: Uuid::new_v4(),
+                content: "private harbour plans".to_string(),
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        assert!(index.flush().await.unwrap());
+        let written = std::fs::read_to_string(dir.join(SNAPSHOT_FILE)).unwrap();
+        assert!(!written.contains("private harbour plans"));
+        assert!(!index.flush().await.unwrap(), "already flushed");
+
+        let reopened = EmbeddedIndex::open(&dir).await.unwrap();
+        assert_eq!(reopened.post_count(), 1);
+        assert_eq!(reopened.user_count(), 1);
+        let found = reopened.search_posts("light", 10, 0).await.unwrap();
+        assert_eq!(found[0].id, older.id);
+        assert_eq!(found[0].created_at, older.created_at);
+        assert_eq!(
+            reopened.search_hashtags("n", 10).await.unwrap(),
+            vec!["night"]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,7 +1,13 @@
 pub mod clickhouse;
 pub mod elasticsearch;
+pub mod embedded_index;
 pub mod redis_cache;
+pub mod search_backend;
 
 pub use clickhouse::ClickHouseClient;
 pub use elasticsearch::ElasticsearchClient;
+pub use embedded_index::EmbeddedIndex;
 pub use redis_cache::RedisCache;
+pub use search_backend::{
+    FallbackBackend, SearchBackend, SearchBackendConfig, SearchBackendKind, SearchError,
+};
@@ -0,0 +1,564 @@
+use crate::services::elasticsearch::{
+    CommentDocument, ElasticsearchClient, ElasticsearchError, FullTextSearchResults,
+    MessageDocument, PostDocument, UserDocument,
+};
+use crate::services::embedded_index::EmbeddedIndex;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+    #[error(transparent)]
+    Elasticsearch(#[from] ElasticsearchError),
+    #[error("index I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("index serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Full-text search operations shared by Elasticsearch and the embedded index.
+///
+/// Query semantics follow the Elasticsearch implementation: posts use
+/// best-fields matching over `title^2`, `content` and `tags`; users combine an
+/// exact username match, full-text match and partial (prefix) matches; hashtag
+/// search is a prefix query over post tags ordered by usage.
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Short name for logs ("elasticsearch", "embedded", ...)
+    fn name(&self) -> &'static str;
+
+    async fn search_posts(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PostDocument>, SearchError>;
+
+    async fn search_users(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+        verified_only: bool,
+    ) -> Result<Vec<UserDocument>, SearchError>;
+
+    async fn search_hashtags(&self, query: &str, limit: i64) -> Result<Vec<String>, SearchError>;
+
+    async fn full_text_search(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<FullTextSearchResults, SearchError> {
+        let (posts, users, hashtags) = tokio::join!(
+            self.search_posts(query, limit, offset),
+            self.search_users(query, limit.min(20), 0, false),
+            self.search_hashtags(query, 10)
+        );
+
+        Ok(FullTextSearchResults {
+            posts: posts?,
+            users: users?,
+            hashtags: hashtags?,
+        })
+    }
+
+    async fn index_post(&self, doc: &PostDocument) -> Result<(), SearchError>;
+
+    async fn delete_post(&self, id: Uuid) -> Result<(), SearchError>;
+
+    async fn bulk_index_posts(&self, docs: Vec<PostDocument>) -> Result<(), SearchError>;
+
+    async fn index_user(&self, doc: &UserDocument) -> Result<(), SearchError>;
+
+    async fn delete_user(&self, user_id: Uuid) -> Result<(), SearchError>;
+
+    async fn index_message(&self, doc: &MessageDocument) -> Result<(), SearchError>;
+
+    async fn delete_message(&self, id: Uuid) -> Result<(), SearchError>;
+
+    async fn index_comment(&self, doc: &CommentDocument) -> Result<(), SearchError>;
+
+    async fn delete_comment(&self, id: Uuid) -> Result<(), SearchError>;
+
+    async fn health_check(&self) -> Result<(), SearchError>;
+}
+
+#[async_trait]
+impl SearchBackend for ElasticsearchClient {
+    fn name(&self) -> &'static str {
+        "elasticsearch"
+    }
+
+    async fn search_posts(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PostDocument>, SearchError> {
+        Ok(ElasticsearchClient::search_posts(self, query, limit, offset).await?)
+    }
+
+    async fn search_users(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+        verified_only: bool,
+    ) -> Result<Vec<UserDocument>, SearchError> {
+        Ok(ElasticsearchClient::search_users(self, query, limit, offset, verified_only).await?)
+    }
+
+    async fn search_hashtags(&self, query: &str, limit: i64) -> Result<Vec<String>, SearchError> {
+        Ok(ElasticsearchClient::search_hashtags(self, query, limit).await?)
+    }
+
+    async fn index_post(&self, doc: &PostDocument) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::index_post(self, doc).await?)
+    }
+
+    async fn delete_post(&self, id: Uuid) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::delete_post(self, id).await?)
+    }
+
+    async fn bulk_index_posts(&self, docs: Vec<PostDocument>) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::bulk_index_posts(self, docs).await?)
+    }
+
+    async fn index_user(&self, doc: &UserDocument) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::index_user(self, doc).await?)
+    }
+
+    async fn delete_user(&self, user_id: Uuid) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::delete_user(self, user_id).await?)
+    }
+
+    async fn index_message(&self, doc: &MessageDocument) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::index_message(self, doc).await?)
+    }
+
+    async fn delete_message(&self, id: Uuid) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::delete_message(self, id).await?)
+    }
+
+    async fn index_comment(&self, doc: &CommentDocument) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::index_comment(self, doc).await?)
+    }
+
+    async fn delete_comment(&self, id: Uuid) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::delete_comment(self, id).await?)
+    }
+
+    async fn health_check(&self) -> Result<(), SearchError> {
+        Ok(ElasticsearchClient::health_check(self).await?)
+    }
+}
+
+/// Primary backend with an automatic fallback.
+///
+/// Reads go to the primary and are retried on the fallback when the primary
+/// errors. Writes go to both so the fallback is warm when it is needed; a
+/// primary write error is returned so callers can retry, while fallback write
+/// errors are only logged.
+pub struct FallbackBackend {
+    primary: Arc<dyn SearchBackend>,
+    fallback: Arc<dyn SearchBackend>,
+}
+
+impl FallbackBackend {
+    pub fn new(primary: Arc<dyn SearchBackend>, fallback: Arc<dyn SearchBackend>) -> Self {
+        Self { primary, fallback }
+    }
+
+    fn on_primary_error(&self, operation: &str, err: &SearchError) {
+        warn!(
+            "{} {} failed, using {}: {}",
+            self.primary.name(),
+            operation,
+            self.fallback.name(),
+            err
+        );
+    }
+
+    fn mirror(
+        &self,
+        operation: &str,
+        primary: Result<(), SearchError>,
+        fallback: Result<(), SearchError>,
+    ) -> Result<(), SearchError> {
+        if let Err(err) = fallback {
+            warn!("{} {} failed: {}", self.fallback.name(), operation, err);
+        }
+        primary
+    }
+}
+
+#[async_trait]
+impl SearchBackend for FallbackBackend {
+    fn name(&self) -> &'static str {
+        self.primary.name()
+    }
+
+    async fn search_posts(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PostDocument>, SearchError> {
+        match self.primary.search_posts(query, limit, offset).await {
+            Ok(docs) => Ok(docs),
+            Err(err) => {
+                self.on_primary_error("post search", &err);
+                self.fallback.search_posts(query, limit, offset).await
+            }
+        }
+    }
+
+    async fn search_users(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+        verified_only: bool,
+    ) -> Result<Vec<UserDocument>, SearchError> {
+        match self
+            .primary
+            .search_users(query, limit, offset, verified_only)
+            .await
+        {
+            Ok(docs) => Ok(docs),
+            Err(err) => {
+                self.on_primary_error("user search", &err);
+                self.fallback
+                    .search_users(query, limit, offset, verified_only)
+                    .await
+            }
+        }
+    }
+
+    async fn search_hashtags(&self, query: &str, limit: i64) -> Result<Vec<String>, SearchError> {
+        match self.primary.search_hashtags(query, limit).await {
+            Ok(tags) => Ok(tags),
+            Err(err) => {
+                self.on_primary_error("hashtag search", &err);
+                self.fallback.search_hashtags(query, limit).await
+            }
+        }
+    }
+
+    async fn index_post(&self, doc: &PostDocument) -> Result<(), SearchError> {
+        let (primary, fallback) =
+            tokio::join!(self.primary.index_post(doc), self.fallback.index_post(doc));
+        self.mirror("post indexing", primary, fallback)
+    }
+
+    async fn delete_post(&self, id: Uuid) -> Result<(), SearchError> {
+        let (primary, fallback) =
+            tokio::join!(self.primary.delete_post(id), self.fallback.delete_post(id));
+        self.mirror("post deletion", primary, fallback)
+    }
+
+    async fn bulk_index_posts(&self, docs: Vec<PostDocument>) -> Result<(), SearchError> {
+        let (primary, fallback) = tokio::join!(
+            self.primary.bulk_index_posts(docs.clone()),
+            self.fallback.bulk_index_posts(docs)
+        );
+        self.mirror("bulk post indexing", primary, fallback)
+    }
+
+    async fn index_user(&self, doc: &UserDocument) -> Result<(), SearchError> {
+        let (primary, fallback) =
+            tokio::join!(self.primary.index_user(doc), self.fallback.index_user(doc));
+        self.mirror("user indexing", primary, fallback)
+    }
+
+    async fn delete_user(&self, user_id: Uuid) -> Result<(), SearchError> {
+        let (primary, fallback) = tokio::join!(
+            self.primary.delete_user(user_id),
+            self.fallback.delete_user(user_id)
+        );
+        self.mirror("user deletion", primary, fallback)
+    }
+
+    async fn index_message(&self, doc: &MessageDocument) -> Result<(), SearchError> {
+        let (primary, fallback) = tokio::join!(
+            self.primary.index_message(doc),
+            self.fallback.index_message(doc)
+        );
+        self.mirror("message indexing", primary, fallback)
+    }
+
+    async fn delete_message(&self, id: Uuid) -> Result<(), SearchError> {
+        let (primary, fallback) = tokio::join!(
+            self.primary.delete_message(id),
+            self.fallback.delete_message(id)
+        );
+        self.mirror("message deletion", primary, fallback)
+    }
+
+    async fn index_comment(&self, doc: &CommentDocument) -> Result<(), SearchError> {
+        let (primary, fallback) = tokio::join!(
+            self.primary.index_comment(doc),
+            self.fallback.index_comment(doc)
+        );
+        self.mirror("comment indexing", primary, fallback)
+    }
+
+    async fn delete_comment(&self, id: Uuid) -> Result<(), SearchError> {
+        let (primary, fallback) = tokio::join!(
+            self.primary.delete_comment(id),
+            self.fallback.delete_comment(id)
+        );
+        self.mirror("comment deletion", primary, fallback)
+    }
+
+    async fn health_check(&self) -> Result<(), SearchError> {
+        match self.primary.health_check().await {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                self.on_primary_error("health check", &err);
+                self.fallback.health_check().await
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchBackendKind {
+    /// Elasticsearch only
+    Elasticsearch,
+    /// Embedded on-disk index only (local development, single replica)
+    Embedded,
+    /// Elasticsearch with the embedded index as fallback; the embedded index
+    /// serves alone when Elasticsearch cannot be reached at startup.
+    ///
+    /// Each replica's embedded index only sees the Kafka partitions that
+    /// replica consumes, so fallback results are complete only when the
+    /// service runs as a single replica.
+    Auto,
+}
+
+impl SearchBackendKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "elasticsearch" | "es" => Some(Self::Elasticsearch),
+            "embedded" => Some(Self::Embedded),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchBackendConfig {
+    pub kind: SearchBackendKind,
+    /// Directory holding the embedded index snapshot
+    pub index_path: PathBuf,
+    /// How often pending embedded index changes are written to disk
+    pub flush_interval: Duration,
+}
+
+impl SearchBackendConfig {
+    /// Load backend selection from environment variables.
+    pub fn from_env() -> Self {
+        let kind = match std::env::var("SEARCH_BACKEND") {
+            Ok(value) => SearchBackendKind::parse(&value).unwrap_or_else(|| {
+                warn!("Unknown SEARCH_BACKEND '{}', using elasticsearch", value);
+                SearchBackendKind::Elasticsearch
+            }),
+            Err(_) => SearchBackendKind::Elasticsearch,
+        };
+
+        Self {
+            kind,
+            index_path: std::env::var("SEARCH_INDEX_PATH")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("data/search-index")),
+            flush_interval: Duration::from_secs(
+                std::env::var("SEARCH_INDEX_FLUSH_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(5)
+                    .max(1),
+            ),
+        }
+    }
+
+    /// Whether an Elasticsearch client should be created.
+    pub fn uses_elasticsearch(&self) -> bool {
+        self.kind != SearchBackendKind::Embedded
+    }
+
+    /// Pick the backend for this configuration.
+    ///
+    /// `elasticsearch` is the connected client if one was configured and
+    /// reachable. Returns `None` when no search backend should be used, in which
+    /// case callers fall back to PostgreSQL full-text search.
+    pub async fn select(
+        &self,
+        elasticsearch: Option<ElasticsearchClient>,
+        elasticsearch_configured: bool,
+    ) -> Result<Option<Arc<dyn SearchBackend>>, SearchError> {
+        let backend: Option<Arc<dyn SearchBackend>> = match (self.kind, elasticsearch) {
+            (SearchBackendKind::Elasticsearch, es) => {
+                es.map(|es| Arc::new(es) as Arc<dyn SearchBackend>)
+            }
+            (SearchBackendKind::Embedded, _) => Some(self.open_embedded().await?),
+            // A broken embedded index must not take Elasticsearch down with it
+            (SearchBackendKind::Auto, Some(es)) => match self.open_embedded().await {
+                Ok(embedded) => Some(Arc::new(FallbackBackend::new(Arc::new(es), embedded))),
+                Err(err) => {
+                    warn!(
+                        "Failed to open embedded search index, using Elasticsearch only: {}",
+                        err
+                    );
+                    Some(Arc::new(es))
+                }
+            },
+            (SearchBackendKind::Auto, None) if elasticsearch_configured => {
+                warn!("Elasticsearch unavailable; serving search from the embedded index");
+                Some(self.open_embedded().await?)
+            }
+            (SearchBackendKind::Auto, None) => None,
+        };
+        Ok(backend)
+    }
+
+    async fn open_embedded(&self) -> Result<Arc<dyn SearchBackend>, SearchError> {
+        let index = Arc::new(EmbeddedIndex::open(&self.index_path).await?);
+        info!(
+            "Embedded search index opened at {} ({} posts, {} users)",
+            self.index_path.display(),
+            index.post_count(),
+            index.user_count()
+        );
+        index.clone().spawn_flusher(self.flush_interval);
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    /// Backend whose every operation fails, standing in for an unreachable
+    /// Elasticsearch cluster
+    struct Unavailable;
+
+    fn unavailable() -> SearchError {
+        SearchError::Io(std::io::Error::new(
+            std::io::ErrorKind::ConnectionRefused,
+            "connection refused",
+        ))
+    }
+
+    #[async_trait]
+    impl SearchBackend for Unavailable {
+        fn name(&self) -> &'static str {
+            "unavailable"
+        }
+        async fn search_posts(
+            &self,
+            _: &str,
+            _: i64,
+            _: i64,
+        ) -> Result<Vec<PostDocument>, SearchError> {
+            Err(unavailable())
+        }
+        async fn search_users(
+            &self,
+            _: &str,
+            _: i64,
+            _: i64,
+            _: bool,
+        ) -> Result<Vec<UserDocument>, SearchError> {
+            Err(unavailable())
+        }
+        async fn search_hashtags(&self, _: &str, _: i64) -> Result<Vec<String>, SearchError> {
+            Err(unavailable())
+        }
+        async fn index_post(&self, _: &PostDocument) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn delete_post(&self, _: Uuid) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn bulk_index_posts(&self, _: Vec<PostDocument>) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn index_user(&self, _: &UserDocument) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn delete_user(&self, _: Uuid) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn index_message(&self, _: &MessageDocument) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn delete_message(&self, _: Uuid) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn index_comment(&self, _: &CommentDocument) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn delete_comment(&self, _: Uuid) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+        async fn health_check(&self) -> Result<(), SearchError> {
+            Err(unavailable())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_serves_reads_and_writes_when_primary_is_down() {
+        let embedded = Arc::new(EmbeddedIndex::in_memory());
+        let backend = FallbackBackend::new(Arc::new(Unavailable), embedded.clone());
+
+        let post = PostDocument {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            title: Some("Sunset over the bay".to_string()),
+            content: None,
+            tags: vec!["sunset".to_string()],
+            likes_count: 0,
+            comments_count: 0,
+            created_at: Utc::now(),
+        };
+        // The primary's write error is surfaced, but the fallback still has it
+        assert!(backend.index_post(&post).await.is_err());
+        assert_eq!(embedded.post_count(), 1);
+
+        let results = backend.full_text_search("sunset", 10, 0).await.unwrap();
+        assert_eq!(results.posts.len(), 1);
+        assert_eq!(results.hashtags, vec!["sunset".to_string()]);
+        assert!(backend.health_check().await.is_ok());
+
+        // Both sides failing surfaces the error
+        let dead = FallbackBackend::new(Arc::new(Unavailable), Arc::new(Unavailable));
+        assert!(dead.search_posts("sunset", 10, 0).await.is_err());
+        assert!(dead.delete_post(post.id).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_backend_kind() {
+        assert_eq!(
+            SearchBackendKind::parse("Elasticsearch"),
+            Some(SearchBackendKind::Elasticsearch)
+        );
+        assert_eq!(
+            SearchBackendKind::parse(" embedded "),
+            Some(SearchBackendKind::Embedded)
+        );
+        assert_eq!(
+            SearchBackendKind::parse("auto"),
+            Some(SearchBackendKind::Auto)
+        );
+        assert_eq!(SearchBackendKind::parse("solr"), None);
+    }
+}
@@ -0,0 +1,32 @@
+-- Migration: Threaded comment listing with keyset pagination
+-- Description: "Top" sort score plus indexes backing cursor pagination of
+-- top-level comments and replies (see CommentRepository::list_comments)
+
+-- Top sort key: log-scaled likes plus recency, one extra "like decade"
+-- per two days. Declared IMMUTABLE so it can back an expression index;
+-- the epoch of a TIMESTAMPTZ does not depend on the session time zone.
+CREATE OR REPLACE FUNCTION comment_top_score(like_count BIGINT, created_at TIMESTAMPTZ)
+RETURNS DOUBLE PRECISION AS $$
+    SELECT LN(1 + GREATEST(like_count, 0)::DOUBLE PRECISION)
+        + EXTRACT(EPOCH FROM created_at)::DOUBLE PRECISION / 172800.0
+$$ LANGUAGE SQL IMMUTABLE PARALLEL SAFE;
+
+-- Top-level comments, newest / oldest
+CREATE INDEX IF NOT EXISTS idx_comments_top_level_created
+    ON comments(post_id, created_at DESC, id DESC)
+    WHERE parent_comment_id IS NULL AND is_deleted = FALSE;
+
+-- Top-level comments, top
+CREATE INDEX IF NOT EXISTS idx_comments_top_level_score
+    ON comments(post_id, comment_top_score(like_count, created_at) DESC, id DESC)
+    WHERE parent_comment_id IS NULL AND is_deleted = FALSE;
+
+-- Replies of a thread, oldest first
+CREATE INDEX IF NOT EXISTS idx_comments_replies_created
+    ON comments(parent_comment_id, created_at, id)
+    WHERE parent_comment_id IS NOT NULL AND is_deleted = FALSE;
+
+-- Flat listing of every comment on a post
+CREATE INDEX IF NOT EXISTS idx_comments_post_created
+    ON comments(post_id, created_at DESC, id DESC)
+    WHERE is_deleted = FALSE;
@@ -0,0 +1,78 @@
+-- Migration: Emoji reactions on posts and comments
+-- Description: One reaction per user per target (switching updates the row),
+-- with per-reaction counters maintained by triggers. The reaction set itself
+-- is configured in the service (SOCIAL_REACTION_TYPES), not in the schema.
+
+-- ============ REACTIONS TABLE ============
+CREATE TABLE IF NOT EXISTS reactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    target_type VARCHAR(10) NOT NULL,  -- post, comment
+    target_id UUID NOT NULL,
+    reaction VARCHAR(32) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Constraints
+    CHECK (target_type IN ('post', 'comment')),
+    CONSTRAINT unique_reaction_per_user_per_target UNIQUE (user_id, target_type, target_id)
+);
+
+-- Reactor listing per target (optionally filtered by reaction)
+CREATE INDEX IF NOT EXISTS idx_reactions_target
+    ON reactions(target_type, target_id, reaction, created_at DESC);
+
+COMMENT ON TABLE reactions IS 'Emoji reactions on posts and comments (one per user per target)';
+COMMENT ON COLUMN reactions.target_id IS 'Post (content-service) or comment ID';
+
+-- ============ REACTION COUNTERS TABLE ============
+CREATE TABLE IF NOT EXISTS reaction_counters (
+    target_type VARCHAR(10) NOT NULL,
+    target_id UUID NOT NULL,
+    reaction VARCHAR(32) NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (target_type, target_id, reaction),
+    CHECK (count >= 0)
+);
+
+COMMENT ON TABLE reaction_counters IS 'Per-reaction counters (synced with Redis cache)';
+
+-- ============ REACTION COUNT TRIGGERS ============
+
+CREATE OR REPLACE FUNCTION adjust_reaction_counter(
+    p_target_type VARCHAR, p_target_id UUID, p_reaction VARCHAR, p_delta BIGINT
+) RETURNS VOID AS $$
+BEGIN
+    INSERT INTO reaction_counters (target_type, target_id, reaction, count, updated_at)
+    VALUES (p_target_type, p_target_id, p_reaction, GREATEST(p_delta, 0), NOW())
+    ON CONFLICT (target_type, target_id, reaction) DO UPDATE
+    SET count = GREATEST(reaction_counters.count + p_delta, 0),
+        updated_at = NOW();
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION update_reaction_counters() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM adjust_reaction_counter(NEW.target_type, NEW.target_id, NEW.reaction, 1);
+        RETURN NEW;
+    ELSIF TG_OP = 'UPDATE' THEN
+        -- Switching reactions moves one count between reaction types
+        IF NEW.reaction IS DISTINCT FROM OLD.reaction THEN
+            PERFORM adjust_reaction_counter(OLD.target_type, OLD.target_id, OLD.reaction, -1);
+            PERFORM adjust_reaction_counter(NEW.target_type, NEW.target_id, NEW.reaction, 1);
+        END IF;
+        RETURN NEW;
+    ELSE
+        PERFORM adjust_reaction_counter(OLD.target_type, OLD.target_id, OLD.reaction, -1);
+        RETURN OLD;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trigger_update_reaction_counters ON reactions;
+CREATE TRIGGER trigger_update_reaction_counters
+AFTER INSERT OR UPDATE OF reaction OR DELETE ON reactions
+FOR EACH ROW EXECUTE FUNCTION update_reaction_counters();
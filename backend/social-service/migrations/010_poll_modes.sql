@@ -0,0 +1,92 @@
+-- ============================================================================
+-- Migration: Poll modes (multi-select, ranked-choice, scheduling, privacy)
+-- Service: social-service
+-- Purpose:
+--   - multiple_choice polls with a max choice count (NULL = no limit)
+--   - ranked_choice polls tallied by instant runoff (see services/polls.rs)
+--   - scheduled open time (starts_at) next to the existing ends_at
+--   - anonymous voter lists and results hidden until close
+-- Notes:
+--   - A ballot is now one poll_votes row per choice, ordered by choice_rank
+--     (1 = first choice). Single-choice ballots are a single rank-1 row.
+-- ============================================================================
+
+-- POLLS: mode settings
+ALTER TABLE polls
+    ADD COLUMN IF NOT EXISTS max_choices INT,
+    ADD COLUMN IF NOT EXISTS starts_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS hide_results_until_close BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS voter_count BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE polls DROP CONSTRAINT IF EXISTS chk_poll_type;
+ALTER TABLE polls ADD CONSTRAINT chk_poll_type
+    CHECK (poll_type IN ('single_choice', 'multiple_choice', 'ranking', 'ranked_choice'));
+
+ALTER TABLE polls DROP CONSTRAINT IF EXISTS chk_poll_max_choices;
+ALTER TABLE polls ADD CONSTRAINT chk_poll_max_choices
+    CHECK (max_choices IS NULL OR max_choices >= 1);
+
+ALTER TABLE polls DROP CONSTRAINT IF EXISTS chk_poll_schedule;
+ALTER TABLE polls ADD CONSTRAINT chk_poll_schedule
+    CHECK (starts_at IS NULL OR ends_at IS NULL OR starts_at < ends_at);
+
+-- Every existing ballot is a single vote
+UPDATE polls SET voter_count = total_votes WHERE voter_count = 0 AND total_votes > 0;
+
+-- VOTES: one row per choice
+ALTER TABLE poll_votes
+    ADD COLUMN IF NOT EXISTS choice_rank INT NOT NULL DEFAULT 1;
+
+ALTER TABLE poll_votes DROP CONSTRAINT IF EXISTS unique_vote_per_user_per_poll;
+ALTER TABLE poll_votes DROP CONSTRAINT IF EXISTS unique_vote_choice_per_user;
+ALTER TABLE poll_votes ADD CONSTRAINT unique_vote_choice_per_user
+    UNIQUE (poll_id, user_id, candidate_id);
+ALTER TABLE poll_votes DROP CONSTRAINT IF EXISTS unique_vote_rank_per_user;
+ALTER TABLE poll_votes ADD CONSTRAINT unique_vote_rank_per_user
+    UNIQUE (poll_id, user_id, choice_rank);
+
+-- Ballot loading for instant-runoff tallies
+CREATE INDEX IF NOT EXISTS idx_poll_votes_ballots
+    ON poll_votes (poll_id, user_id, choice_rank);
+
+-- TRIGGERS: vote counts per mode
+--   candidate vote_count / total_votes: every choice, except ranked_choice
+--     where only first preferences count (runoff rounds are computed on read)
+--   voter_count: one per ballot (rank-1 row)
+CREATE OR REPLACE FUNCTION update_poll_vote_counts() RETURNS TRIGGER AS $$
+DECLARE
+    v_row poll_votes%ROWTYPE;
+    v_delta BIGINT;
+    v_counts BOOLEAN;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        v_row := NEW;
+        v_delta := 1;
+    ELSE
+        v_row := OLD;
+        v_delta := -1;
+    END IF;
+
+    SELECT poll_type <> 'ranked_choice' OR v_row.choice_rank = 1
+    INTO v_counts
+    FROM polls WHERE id = v_row.poll_id;
+
+    IF v_counts THEN
+        UPDATE poll_candidates
+        SET vote_count = GREATEST(vote_count + v_delta, 0)
+        WHERE id = v_row.candidate_id;
+        UPDATE polls
+        SET total_votes = GREATEST(total_votes + v_delta, 0), updated_at = NOW()
+        WHERE id = v_row.poll_id;
+    END IF;
+
+    IF v_row.choice_rank = 1 THEN
+        UPDATE polls
+        SET voter_count = GREATEST(voter_count + v_delta, 0)
+        WHERE id = v_row.poll_id;
+    END IF;
+
+    RETURN v_row;
+END;
+$$ LANGUAGE plpgsql;
@@ -15,6 +15,9 @@ pub struct Config {
     pub redis: RedisConfig,
     /// gRPC configuration
     pub grpc: GrpcConfig,
+    /// Emoji reaction set
+    #[serde(default)]
+    pub reactions: ReactionConfig,
 }
 
 /// Application settings
@@ -61,6 +64,70 @@ pub struct GrpcConfig {
     pub ca_cert_path: Option<String>,
 }
 
+/// A reaction users can leave on posts and comments
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReactionType {
+    /// Stable identifier stored in the database (e.g. "love")
+    pub name: String,
+    /// Emoji shown to users
+    pub emoji: String,
+}
+
+/// Configured reaction set
+///
+/// `SOCIAL_REACTION_TYPES` lists `name:emoji` pairs separated by commas,
+/// e.g. `like:👍,love:❤️`. Removing a type stops new reactions of that type;
+/// existing ones keep counting until users switch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactionConfig {
+    pub types: Vec<ReactionType>,
+}
+
+impl Default for ReactionConfig {
+    fn default() -> Self {
+        Self::parse("like:👍,love:❤️,haha:😂,wow:😮,sad:😢,angry:😡")
+            .expect("default reaction set is valid")
+    }
+}
+
+impl ReactionConfig {
+    /// Names that collide with keys of the reaction count cache
+    const RESERVED_NAMES: &'static [&'static str] = &["total"];
+
+    /// Parse a `name:emoji` list; None if it contains no valid entry
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut types: Vec<ReactionType> = Vec::new();
+        for entry in value.split(',') {
+            let Some((name, emoji)) = entry.split_once(':') else {
+                continue;
+            };
+            let name = name.trim().to_lowercase();
+            let emoji = emoji.trim();
+            let valid_name = !name.is_empty()
+                && name.len() <= 32
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !Self::RESERVED_NAMES.contains(&name.as_str());
+            if valid_name && !emoji.is_empty() && !types.iter().any(|t| t.name == name) {
+                types.push(ReactionType {
+                    name,
+                    emoji: emoji.to_string(),
+                });
+            }
+        }
+        (!types.is_empty()).then_some(Self { types })
+    }
+
+    /// Look up a reaction type by name
+    pub fn get(&self, name: &str) -> Option<&ReactionType> {
+        self.types.iter().find(|t| t.name == name)
+    }
+
+    /// Emoji for a reaction name, falling back to the name itself
+    pub fn emoji<'a>(&'a self, name: &'a str) -> &'a str {
+        self.get(name).map(|t| t.emoji.as_str()).unwrap_or(name)
+    }
+}
+
 // Default values
 fn default_max_connections() -> u32 {
     20
@@ -109,11 +176,18 @@ impl Config {
             ca_cert_path: std::env::var("GRPC_CA_CERT_PATH").ok(),
         };
 
+        let reactions = match std::env::var("SOCIAL_REACTION_TYPES") {
+            Ok(value) => ReactionConfig::parse(&value)
+                .context("SOCIAL_REACTION_TYPES must contain at least one name:emoji pair")?,
+            Err(_) => ReactionConfig::default(),
+        };
+
         Ok(Config {
             app,
             database,
             redis,
             grpc,
+            reactions,
         })
     }
 }
@@ -135,5 +209,25 @@ mod tests {
         assert_eq!(config.database.max_connections, 20);
         assert_eq!(config.database.min_connections, 0);
         assert_eq!(config.grpc.port, 50053);
+        assert_eq!(config.reactions.types.len(), 6);
+    }
+
+    #[test]
+    fn test_reaction_config_parse() {
+        let config = ReactionConfig::parse(" Like:👍, fire:🔥,bad entry,:x,like:❤️,fire:").unwrap();
+
+        let names: Vec<&str> = config.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["like", "fire"]);
+        assert_eq!(config.emoji("fire"), "🔥");
+        assert_eq!(config.emoji("unknown"), "unknown");
+        assert!(ReactionConfig::parse("no pairs here").is_none());
+        assert!(ReactionConfig::parse("total:#").is_none());
+        assert_eq!(
+            ReactionConfig::parse("Total:#,like:👍")
+                .unwrap()
+                .types
+                .len(),
+            1
+        );
     }
 }
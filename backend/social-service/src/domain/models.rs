@@ -35,6 +35,50 @@ pub struct Comment {
     pub author_account_type: Option<String>,
 }
 
+/// Comment with its denormalized engagement counters (threaded listing)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CommentWithCounts {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub comment: Comment,
+    pub like_count: i64,
+    pub reply_count: i64,
+    /// `comment_top_score(like_count, created_at)`, the "top" sort key
+    pub top_score: f64,
+}
+
+/// What a reaction is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionTarget {
+    Post,
+    Comment,
+}
+
+impl ReactionTarget {
+    /// Value stored in `reactions.target_type`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReactionTarget::Post => "post",
+            ReactionTarget::Comment => "comment",
+        }
+    }
+}
+
+/// Reaction entity - a user's emoji reaction on a post or comment
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Reaction {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// "post" or "comment" (see `ReactionTarget`)
+    pub target_type: String,
+    pub target_id: Uuid,
+    /// Reaction name from the configured reaction set (e.g. "love")
+    pub reaction: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// Share entity - represents a user sharing a post
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Share {
@@ -100,6 +144,16 @@ pub struct Poll {
     pub updated_at: DateTime<Utc>,
     pub ends_at: Option<DateTime<Utc>>,
     pub is_deleted: bool,
+    /// Max choices per ballot (multiple_choice / ranked_choice); None = no limit
+    pub max_choices: Option<i32>,
+    /// Scheduled open time; None = open on creation
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Voter lists are not exposed
+    pub anonymous: bool,
+    /// Vote counts are only shown to the creator until the poll closes
+    pub hide_results_until_close: bool,
+    /// Number of ballots (total_votes counts choices)
+    pub voter_count: i64,
 }
 
 /// Poll candidate - represents a candidate in a poll
@@ -124,6 +178,8 @@ pub struct PollVote {
     pub poll_id: Uuid,
     pub candidate_id: Uuid,
     pub user_id: Uuid,
+    /// Preference order within the ballot (1 = first choice)
+    pub choice_rank: i32,
     pub created_at: DateTime<Utc>,
 }
 
@@ -139,6 +195,8 @@ pub struct CandidateWithRank {
     pub rank: i32,
     pub rank_change: i32,
     pub vote_percentage: f64,
+    /// Instant-runoff round the candidate was eliminated in (ranked_choice)
+    pub eliminated_in_round: Option<i32>,
 }
 
 /// Candidate preview for poll summary
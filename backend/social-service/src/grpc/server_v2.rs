@@ -13,15 +13,23 @@ use tonic::{Request, Response, Status};
 use transactional_outbox::{OutboxError, SqlxOutboxRepository};
 use uuid::Uuid;
 
+use crate::config::ReactionConfig;
 use crate::domain::models::{
     CandidatePreview as CandidatePreviewModel, CandidateWithRank, Comment as CommentModel,
-    Like as LikeModel, Poll as PollModel, PollCandidate as PollCandidateModel, Share as ShareModel,
+    CommentWithCounts, Like as LikeModel, Poll as PollModel, PollCandidate as PollCandidateModel,
+    ReactionTarget, Share as ShareModel,
 };
 use crate::repository::{
-    polls::CreateCandidateInput, BookmarkRepository, CommentLikeRepository, CommentRepository,
-    LikeRepository, PollRepository, ShareRepository,
+    comments::{CommentCursor, CommentScope, CommentSort as CommentSortModel},
+    polls::{CreateCandidateInput, PollSettings},
+    BookmarkRepository, CommentLikeRepository, CommentRepository, LikeRepository, PollRepository,
+    ReactionRepository, ShareRepository,
+};
+use crate::services::polls::{self as poll_rules, PollMode, PollRankingService};
+use crate::services::{
+    extract_mentions, CounterReconciler, CounterService, FollowService, ReactionCounts,
+    SocialEventProducer,
 };
-use crate::services::{extract_mentions, CounterService, FollowService, SocialEventProducer};
 use transactional_outbox::{OutboxEvent, OutboxRepository};
 
 fn outbox_error_to_status(err: OutboxError) -> Status {
@@ -49,6 +57,8 @@ pub struct AppState {
     pub content_client: Option<ContentServiceClient<Channel>>,
     /// Optional Kafka event producer for social events
     pub event_producer: Option<Arc<SocialEventProducer>>,
+    /// Emoji reactions accepted by SetReaction
+    pub reaction_config: ReactionConfig,
 }
 
 impl AppState {
@@ -66,6 +76,7 @@ impl AppState {
             identity_client: None,
             content_client: None,
             event_producer: None,
+            reaction_config: ReactionConfig::default(),
         }
     }
 
@@ -83,6 +94,11 @@ impl AppState {
         self.event_producer = Some(Arc::new(producer));
         self
     }
+
+    pub fn with_reaction_config(mut self, config: ReactionConfig) -> Self {
+        self.reaction_config = config;
+        self
+    }
 }
 
 pub struct SocialServiceImpl {
@@ -102,6 +118,63 @@ impl SocialServiceImpl {
         FollowService::new(self.state.graph_client.clone())
     }
 
+    /// Offset-paginated flat listing kept for clients that predate cursors
+    async fn get_comments_by_offset(
+        &self,
+        post_id: Uuid,
+        limit: i32,
+        offset: i32,
+        viewer_user_id: Option<Uuid>,
+    ) -> Result<Response<GetCommentsResponse>, Status> {
+        let repo = self.comment_repo();
+        let comments = repo
+            .get_comments(post_id, limit, offset, "created_at", "desc")
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get comments: {}", e)))?;
+
+        // Always use DB count for accuracy (counter cache can become stale after deletions)
+        let total = repo.get_comment_count(post_id).await.unwrap_or(0) as i32;
+
+        // Batch fetch engagement data to avoid N+1 queries
+        let comment_ids: Vec<Uuid> = comments.iter().map(|c| c.id).collect();
+        let comment_like_repo = self.comment_like_repo();
+
+        // Batch get like counts for all comments
+        let like_counts = comment_like_repo
+            .batch_get_like_counts(&comment_ids)
+            .await
+            .unwrap_or_default();
+
+        // Batch check if viewer has liked these comments
+        let liked_by_viewer = if let Some(viewer_id) = viewer_user_id {
+            comment_like_repo
+                .batch_check_liked(viewer_id, &comment_ids)
+                .await
+                .unwrap_or_default()
+        } else {
+            std::collections::HashMap::new()
+        };
+
+        let has_more = offset + (comments.len() as i32) < total;
+
+        // Convert to proto with engagement data
+        let proto_comments: Vec<Comment> = comments
+            .into_iter()
+            .map(|c| {
+                let like_count = like_counts.get(&c.id).copied().unwrap_or(0);
+                let is_liked = liked_by_viewer.get(&c.id).copied().unwrap_or(false);
+                to_proto_comment_with_engagement(c, like_count, is_liked)
+            })
+            .collect();
+
+        Ok(Response::new(GetCommentsResponse {
+            comments: proto_comments,
+            total,
+            next_cursor: String::new(),
+            has_more,
+        }))
+    }
+
     fn comment_repo(&self) -> CommentRepository {
         CommentRepository::new(self.state.pg_pool.clone())
     }
@@ -114,6 +187,10 @@ impl SocialServiceImpl {
         PollRepository::new(self.state.pg_pool.clone())
     }
 
+    fn poll_rankings(&self) -> PollRankingService {
+        PollRankingService::new(self.poll_repo(), self.state.counter_service.redis().clone())
+    }
+
     fn bookmark_repo(&self) -> BookmarkRepository {
         BookmarkRepository::new(self.state.pg_pool.clone())
     }
@@ -121,6 +198,29 @@ impl SocialServiceImpl {
     fn comment_like_repo(&self) -> CommentLikeRepository {
         CommentLikeRepository::new(self.state.pg_pool.clone())
     }
+
+    fn reaction_repo(&self) -> ReactionRepository {
+        ReactionRepository::new(self.state.pg_pool.clone())
+    }
+
+    /// Counts plus the viewer's reaction for one target
+    async fn reaction_summary(
+        &self,
+        target: ReactionTarget,
+        target_id: Uuid,
+        viewer_reaction: Option<String>,
+    ) -> ReactionSummary {
+        let counts = self
+            .state
+            .counter_service
+            .get_reaction_counts(target, target_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = ?e, target_id = %target_id, "Failed to get reaction counts");
+                ReactionCounts::default()
+            });
+        to_proto_reaction_summary(target_id, counts, viewer_reaction)
+    }
 }
 
 #[tonic::async_trait]
@@ -143,10 +243,20 @@ impl SocialService for SocialServiceImpl {
         if created {
             publish_outbox_follow(&self.state, follower_id, followee_id, true).await?;
 
-            // Send push notification to followee (async, fire-and-forget)
+            // Send push notification to followee (async, fire-and-forget).
+            // Private accounts get a follow request notification from graph-service instead.
             if let Some(producer) = &self.state.event_producer {
                 let producer = producer.clone();
+                let follow_service = self.follow_service();
                 tokio::spawn(async move {
+                    match follow_service.is_account_private(followee_id).await {
+                        Ok(false) => {}
+                        Ok(true) => return,
+                        Err(e) => {
+                            tracing::warn!(error = ?e, "Failed to check followee privacy; skipping follow notification");
+                            return;
+                        }
+                    }
                     if let Err(e) = producer
                         .publish_follow_notification(
                             follower_id,
@@ -297,6 +407,17 @@ impl SocialService for SocialServiceImpl {
             .await
             .map_err(|e| Status::internal(format!("Failed to create like: {}", e)))?;
 
+        if was_created {
+            if let Err(e) = self
+                .state
+                .counter_service
+                .increment_like_count(post_id)
+                .await
+            {
+                tracing::warn!(post_id = %post_id, error = ?e, "Failed to increment like counter");
+            }
+        }
+
         // Emit Kafka events asynchronously (fire-and-forget for analytics/notifications)
         if was_created {
             if let Some(producer) = &self.state.event_producer {
@@ -317,6 +438,7 @@ impl SocialService for SocialServiceImpl {
                     if let Some(mut client) = content_client {
                         let request = tonic::Request::new(GetPostRequest {
                             post_id: post_id.to_string(),
+                            viewer_id: user_id.to_string(),
                         });
                         match client.get_post(request).await {
                             Ok(response) => {
@@ -371,6 +493,17 @@ impl SocialService for SocialServiceImpl {
             .await
             .map_err(|e| Status::internal(format!("Failed to delete like: {}", e)))?;
 
+        if was_deleted {
+            if let Err(e) = self
+                .state
+                .counter_service
+                .decrement_like_count(post_id)
+                .await
+            {
+                tracing::warn!(post_id = %post_id, error = ?e, "Failed to decrement like counter");
+            }
+        }
+
         // Emit Kafka event asynchronously (fire-and-forget for analytics/notifications)
         if was_deleted {
             if let Some(producer) = &self.state.event_producer {
@@ -503,6 +636,7 @@ impl SocialService for SocialServiceImpl {
                 if let Some(mut client) = content_client {
                     let request = tonic::Request::new(GetPostRequest {
                         post_id: post_id.to_string(),
+                        viewer_id: user_id.to_string(),
                     });
                     match client.get_post(request).await {
                         Ok(response) => {
@@ -631,7 +765,6 @@ impl SocialService for SocialServiceImpl {
         request: Request<GetCommentsRequest>,
     ) -> Result<Response<GetCommentsResponse>, Status> {
         let req = request.into_inner();
-        let post_id = parse_uuid(&req.post_id, "post_id")?;
         let limit = sanitize_limit(req.limit, 1, 100, 50);
         let offset = req.offset.max(0);
         let viewer_user_id = if req.viewer_user_id.is_empty() {
@@ -639,49 +772,117 @@ impl SocialService for SocialServiceImpl {
         } else {
             Some(parse_uuid(&req.viewer_user_id, "viewer_user_id")?)
         };
+        let parent_comment_id = if req.parent_comment_id.is_empty() {
+            None
+        } else {
+            Some(parse_uuid(&req.parent_comment_id, "parent_comment_id")?)
+        };
+        let post_id = match parent_comment_id {
+            Some(_) if req.post_id.is_empty() => None,
+            _ => Some(parse_uuid(&req.post_id, "post_id")?),
+        };
+
+        // Legacy offset pagination: flat listing, newest first
+        if let (Some(post_id), None) = (post_id, parent_comment_id) {
+            if offset > 0 && req.cursor.is_empty() {
+                return self
+                    .get_comments_by_offset(post_id, limit, offset, viewer_user_id)
+                    .await;
+            }
+        }
+
+        let sort = match (CommentSort::try_from(req.sort), parent_comment_id) {
+            (Ok(CommentSort::Newest), _) => CommentSortModel::Newest,
+            (Ok(CommentSort::Oldest), _) => CommentSortModel::Oldest,
+            (Ok(CommentSort::Top), _) => CommentSortModel::Top,
+            // Replies read top to bottom by default
+            (_, Some(_)) => CommentSortModel::Oldest,
+            (_, None) => CommentSortModel::Newest,
+        };
+        let cursor = if req.cursor.is_empty() {
+            None
+        } else {
+            Some(
+                CommentCursor::decode(&req.cursor, sort)
+                    .ok_or_else(|| Status::invalid_argument("Invalid cursor for this sort"))?,
+            )
+        };
+        let scope = match (parent_comment_id, post_id) {
+            (Some(parent_id), _) => CommentScope::Replies(parent_id),
+            (None, Some(post_id)) if req.threaded => CommentScope::TopLevel(post_id),
+            (None, Some(post_id)) => CommentScope::Post(post_id),
+            (None, None) => return Err(Status::invalid_argument("post_id is required")),
+        };
 
         let repo = self.comment_repo();
-        let comments = repo
-            .get_comments(post_id, limit, offset, "created_at", "desc")
+        let page = repo
+            .list_comments(scope, sort, cursor.as_ref(), limit as i64)
             .await
             .map_err(|e| Status::internal(format!("Failed to get comments: {}", e)))?;
 
-        // Always use DB count for accuracy (counter cache can become stale after deletions)
-        let total = repo.get_comment_count(post_id).await.unwrap_or(0) as i32;
-
-        // Batch fetch engagement data to avoid N+1 queries
-        let comment_ids: Vec<Uuid> = comments.iter().map(|c| c.id).collect();
-        let comment_like_repo = self.comment_like_repo();
+        let mut replies = if req.threaded && parent_comment_id.is_none() {
+            let per_thread = sanitize_limit(req.replies_per_thread, 1, 10, 3) as i64;
+            let parent_ids: Vec<Uuid> = page.comments.iter().map(|c| c.comment.id).collect();
+            repo.get_first_replies(&parent_ids, per_thread)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get replies: {}", e)))?
+        } else {
+            std::collections::HashMap::new()
+        };
 
-        // Batch get like counts for all comments
-        let like_counts = comment_like_repo
-            .batch_get_like_counts(&comment_ids)
-            .await
-            .unwrap_or_default();
+        let total = match post_id {
+            Some(post_id) => repo.get_comment_count(post_id).await.unwrap_or(0) as i32,
+            None => 0,
+        };
 
-        // Batch check if viewer has liked these comments
+        // One batch check covers top-level comments and inlined replies
         let liked_by_viewer = if let Some(viewer_id) = viewer_user_id {
-            comment_like_repo
+            let comment_ids: Vec<Uuid> = page
+                .comments
+                .iter()
+                .chain(replies.values().flatten())
+                .map(|c| c.comment.id)
+                .collect();
+            self.comment_like_repo()
                 .batch_check_liked(viewer_id, &comment_ids)
                 .await
                 .unwrap_or_default()
         } else {
             std::collections::HashMap::new()
         };
+        let is_liked = |id: &Uuid| liked_by_viewer.get(id).copied().unwrap_or(false);
 
-        // Convert to proto with engagement data
-        let proto_comments: Vec<Comment> = comments
+        let proto_comments: Vec<Comment> = page
+            .comments
             .into_iter()
             .map(|c| {
-                let like_count = like_counts.get(&c.id).copied().unwrap_or(0);
-                let is_liked = liked_by_viewer.get(&c.id).copied().unwrap_or(false);
-                to_proto_comment_with_engagement(c, like_count, is_liked)
+                let thread = replies.remove(&c.comment.id).unwrap_or_default();
+                // Remaining replies continue after the last inlined one
+                let replies_cursor = match thread.last() {
+                    Some(last) if c.reply_count > thread.len() as i64 => {
+                        CommentCursor::after(CommentSortModel::Oldest, last).encode()
+                    }
+                    _ => String::new(),
+                };
+                let liked = is_liked(&c.comment.id);
+                let mut proto = to_proto_comment_with_counts(c, liked);
+                proto.replies = thread
+                    .into_iter()
+                    .map(|r| {
+                        let liked = is_liked(&r.comment.id);
+                        to_proto_comment_with_counts(r, liked)
+                    })
+                    .collect();
+                proto.replies_cursor = replies_cursor;
+                proto
             })
             .collect();
 
         Ok(Response::new(GetCommentsResponse {
             comments: proto_comments,
             total,
+            has_more: page.next_cursor.is_some(),
+            next_cursor: page.next_cursor.unwrap_or_default(),
         }))
     }
 
@@ -844,6 +1045,292 @@ impl SocialService for SocialServiceImpl {
         }))
     }
 
+    // ========= Reactions =========
+    async fn set_reaction(
+        &self,
+        request: Request<SetReactionRequest>,
+    ) -> Result<Response<SetReactionResponse>, Status> {
+        let req = request.into_inner();
+        let user_id = parse_uuid(&req.user_id, "user_id")?;
+        let target = parse_reaction_target(req.target_type)?;
+        let target_id = parse_uuid(&req.target_id, "target_id")?;
+        let reaction = req.reaction.trim().to_lowercase();
+        if self.state.reaction_config.get(&reaction).is_none() {
+            return Err(Status::invalid_argument(format!(
+                "Unsupported reaction: {}",
+                req.reaction
+            )));
+        }
+
+        let change = self
+            .reaction_repo()
+            .set_reaction(user_id, target, target_id, &reaction)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to set reaction: {}", e)))?;
+
+        if !change.is_unchanged() {
+            if let Err(e) = self
+                .state
+                .counter_service
+                .apply_reaction_change(
+                    target,
+                    target_id,
+                    change.previous.as_deref(),
+                    Some(&reaction),
+                )
+                .await
+            {
+                tracing::warn!(error = ?e, target_id = %target_id, "Failed to update reaction counters");
+            }
+
+            publish_outbox_reaction(
+                &self.state,
+                user_id,
+                target,
+                target_id,
+                change.previous.as_deref(),
+                Some(&reaction),
+            )
+            .await?;
+        }
+
+        let summary = self
+            .reaction_summary(target, target_id, Some(reaction.clone()))
+            .await;
+
+        // Notify the author on new reactions only; switching is silent
+        if change.previous.is_none() {
+            if let Some(producer) = &self.state.event_producer {
+                let producer = producer.clone();
+                let content_client = self.state.content_client.clone();
+                let comment_repo = self.comment_repo();
+                let reaction_config = self.state.reaction_config.clone();
+                let reaction_id = change.reaction.id;
+                let counts = ReactionCounts {
+                    counts: summary.counts.clone().into_iter().collect(),
+                    total: summary.total,
+                };
+
+                tokio::spawn(async move {
+                    let Some(recipient_id) = resolve_reaction_recipient(
+                        content_client,
+                        &comment_repo,
+                        user_id,
+                        target,
+                        target_id,
+                    )
+                    .await
+                    else {
+                        tracing::warn!(target_id = %target_id, "Reaction target author not found for notification");
+                        return;
+                    };
+                    if let Err(e) = producer
+                        .publish_reaction_notification(
+                            reaction_id,
+                            target,
+                            target_id,
+                            user_id,
+                            recipient_id,
+                            &reaction,
+                            &counts,
+                            &reaction_config,
+                            None, // TODO: fetch username from identity service if needed
+                        )
+                        .await
+                    {
+                        tracing::warn!(error = ?e, "Failed to publish reaction notification");
+                    }
+                });
+            }
+        }
+
+        Ok(Response::new(SetReactionResponse {
+            success: true,
+            previous_reaction: change.previous.unwrap_or_default(),
+            summary: Some(summary),
+        }))
+    }
+
+    async fn remove_reaction(
+        &self,
+        request: Request<RemoveReactionRequest>,
+    ) -> Result<Response<RemoveReactionResponse>, Status> {
+        let req = request.into_inner();
+        let user_id = parse_uuid(&req.user_id, "user_id")?;
+        let target = parse_reaction_target(req.target_type)?;
+        let target_id = parse_uuid(&req.target_id, "target_id")?;
+
+        let removed = self
+            .reaction_repo()
+            .remove_reaction(user_id, target, target_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to remove reaction: {}", e)))?;
+
+        if let Some(previous) = &removed {
+            if let Err(e) = self
+                .state
+                .counter_service
+                .apply_reaction_change(target, target_id, Some(previous), None)
+                .await
+            {
+                tracing::warn!(error = ?e, target_id = %target_id, "Failed to update reaction counters");
+            }
+
+            publish_outbox_reaction(
+                &self.state,
+                user_id,
+                target,
+                target_id,
+                Some(previous.as_str()),
+                None,
+            )
+            .await?;
+        }
+
+        let summary = self.reaction_summary(target, target_id, None).await;
+
+        Ok(Response::new(RemoveReactionResponse {
+            success: true,
+            removed_reaction: removed.unwrap_or_default(),
+            summary: Some(summary),
+        }))
+    }
+
+    async fn get_reaction_summary(
+        &self,
+        request: Request<GetReactionSummaryRequest>,
+    ) -> Result<Response<GetReactionSummaryResponse>, Status> {
+        let req = request.into_inner();
+        let target = parse_reaction_target(req.target_type)?;
+        let target_id = parse_uuid(&req.target_id, "target_id")?;
+
+        let viewer_reaction = if req.viewer_user_id.is_empty() {
+            None
+        } else {
+            let viewer_id = parse_uuid(&req.viewer_user_id, "viewer_user_id")?;
+            self.reaction_repo()
+                .get_user_reaction(viewer_id, target, target_id)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get reaction: {}", e)))?
+        };
+
+        let summary = self
+            .reaction_summary(target, target_id, viewer_reaction)
+            .await;
+
+        Ok(Response::new(GetReactionSummaryResponse {
+            summary: Some(summary),
+        }))
+    }
+
+    async fn batch_get_reaction_summaries(
+        &self,
+        request: Request<BatchGetReactionSummariesRequest>,
+    ) -> Result<Response<BatchGetReactionSummariesResponse>, Status> {
+        let req = request.into_inner();
+        let target = parse_reaction_target(req.target_type)?;
+
+        if req.target_ids.is_empty() {
+            return Ok(Response::new(BatchGetReactionSummariesResponse {
+                summaries: std::collections::HashMap::new(),
+            }));
+        }
+
+        if req.target_ids.len() > 100 {
+            return Err(Status::invalid_argument("Maximum 100 target_ids allowed"));
+        }
+
+        let target_ids: Vec<Uuid> = req
+            .target_ids
+            .iter()
+            .map(|id| parse_uuid(id, "target_id"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut counts = self
+            .state
+            .counter_service
+            .batch_get_reaction_counts(target, &target_ids)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get reaction counts: {}", e)))?;
+
+        let mut viewer_reactions = if req.viewer_user_id.is_empty() {
+            std::collections::HashMap::new()
+        } else {
+            let viewer_id = parse_uuid(&req.viewer_user_id, "viewer_user_id")?;
+            self.reaction_repo()
+                .batch_get_user_reactions(viewer_id, target, &target_ids)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get reactions: {}", e)))?
+        };
+
+        let summaries = target_ids
+            .into_iter()
+            .map(|target_id| {
+                let summary = to_proto_reaction_summary(
+                    target_id,
+                    counts.remove(&target_id).unwrap_or_default(),
+                    viewer_reactions.remove(&target_id),
+                );
+                (target_id.to_string(), summary)
+            })
+            .collect();
+
+        Ok(Response::new(BatchGetReactionSummariesResponse {
+            summaries,
+        }))
+    }
+
+    async fn get_reactors(
+        &self,
+        request: Request<GetReactorsRequest>,
+    ) -> Result<Response<GetReactorsResponse>, Status> {
+        let req = request.into_inner();
+        let target = parse_reaction_target(req.target_type)?;
+        let target_id = parse_uuid(&req.target_id, "target_id")?;
+        let limit = sanitize_limit(req.limit, 1, 100, 50);
+        let offset = req.offset.max(0);
+        let reaction = if req.reaction.is_empty() {
+            None
+        } else {
+            Some(req.reaction.to_lowercase())
+        };
+
+        let reactions = self
+            .reaction_repo()
+            .get_reactions(target, target_id, reaction.as_deref(), limit, offset)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get reactors: {}", e)))?;
+
+        let reactors = reactions
+            .into_iter()
+            .map(|r| Reactor {
+                user_id: r.user_id.to_string(),
+                reaction: r.reaction,
+                reacted_at: to_ts(r.updated_at),
+            })
+            .collect();
+
+        Ok(Response::new(GetReactorsResponse { reactors }))
+    }
+
+    async fn get_reaction_types(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<GetReactionTypesResponse>, Status> {
+        let types = self
+            .state
+            .reaction_config
+            .types
+            .iter()
+            .map(|t| ReactionType {
+                name: t.name.clone(),
+                emoji: t.emoji.clone(),
+            })
+            .collect();
+
+        Ok(Response::new(GetReactionTypesResponse { types }))
+    }
+
     // ========= Shares =========
     async fn create_share(
         &self,
@@ -876,6 +1363,7 @@ impl SocialService for SocialServiceImpl {
                 if let Some(mut client) = content_client {
                     let request = tonic::Request::new(GetPostRequest {
                         post_id: post_id.to_string(),
+                        viewer_id: user_id.to_string(),
                     });
                     match client.get_post(request).await {
                         Ok(response) => {
@@ -968,13 +1456,15 @@ impl SocialService for SocialServiceImpl {
             .map_err(|e| Status::internal(format!("Failed to get trending polls: {}", e)))?;
 
         // Get top candidates for each poll
+        let now = Utc::now();
         let mut summaries = Vec::with_capacity(polls.len());
         for poll in polls {
+            let results_visible = poll_rules::results_visible(&poll, None, now);
             let top_candidates = repo
-                .get_top_candidates(poll.id, 3)
+                .get_top_candidates(poll.id, 3, results_visible)
                 .await
                 .unwrap_or_default();
-            summaries.push(to_proto_poll_summary(poll, top_candidates));
+            summaries.push(to_proto_poll_summary(poll, top_candidates, results_visible));
         }
 
         Ok(Response::new(GetTrendingPollsResponse { polls: summaries }))
@@ -984,6 +1474,13 @@ impl SocialService for SocialServiceImpl {
         &self,
         request: Request<GetPollRequest>,
     ) -> Result<Response<GetPollResponse>, Status> {
+        // Viewer is optional: anonymous callers get the public view
+        let viewer_id = request
+            .metadata()
+            .get("x-user-id")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| Uuid::parse_str(s).ok());
+
         let req = request.into_inner();
         let poll_id = parse_uuid(&req.poll_id, "poll_id")?;
 
@@ -994,25 +1491,38 @@ impl SocialService for SocialServiceImpl {
             .map_err(|e| Status::internal(format!("Failed to get poll: {}", e)))?
             .ok_or_else(|| Status::not_found("Poll not found"))?;
 
+        let results_visible = poll_rules::results_visible(&poll, viewer_id, Utc::now());
+
         let candidates = if req.include_candidates {
-            let candidates = repo.get_poll_candidates(poll_id).await.unwrap_or_default();
-            let total_votes = poll.total_votes;
-            candidates
+            self.poll_rankings()
+                .get_rankings(&poll, i32::MAX, 0, results_visible)
+                .await
+                .map(|page| page.rankings)
+                .unwrap_or_default()
                 .into_iter()
-                .enumerate()
-                .map(|(idx, c)| to_proto_candidate_with_rank(c, (idx + 1) as i32, total_votes))
+                .map(to_proto_candidate_ranked)
                 .collect()
         } else {
             vec![]
         };
 
-        // TODO: Get user's voted candidate from request context
-        let my_voted_candidate_id = String::new();
+        let my_voted_candidate_ids: Vec<String> = match viewer_id {
+            Some(viewer_id) => repo
+                .check_voted(poll_id, viewer_id)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to check vote: {}", e)))?
+                .into_iter()
+                .map(|v| v.candidate_id.to_string())
+                .collect(),
+            None => vec![],
+        };
 
         Ok(Response::new(GetPollResponse {
-            poll: Some(to_proto_poll(poll)),
+            poll: Some(to_proto_poll(poll, results_visible)),
             candidates,
-            my_voted_candidate_id,
+            my_voted_candidate_id: my_voted_candidate_ids.first().cloned().unwrap_or_default(),
+            my_voted_candidate_ids,
+            results_hidden: !results_visible,
         }))
     }
 
@@ -1020,24 +1530,42 @@ impl SocialService for SocialServiceImpl {
         &self,
         request: Request<GetPollRankingsRequest>,
     ) -> Result<Response<GetPollRankingsResponse>, Status> {
+        let viewer_id = request
+            .metadata()
+            .get("x-user-id")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| Uuid::parse_str(s).ok());
+
         let req = request.into_inner();
         let poll_id = parse_uuid(&req.poll_id, "poll_id")?;
         let limit = sanitize_limit(req.limit, 1, 100, 20);
         let offset = req.offset.max(0);
 
         let repo = self.poll_repo();
-        let (rankings, total_candidates, total_votes) = repo
-            .get_rankings(poll_id, limit, offset)
+        let poll = repo
+            .get_poll(poll_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get poll: {}", e)))?
+            .ok_or_else(|| Status::not_found("Poll not found"))?;
+
+        let results_visible = poll_rules::results_visible(&poll, viewer_id, Utc::now());
+        let page = self
+            .poll_rankings()
+            .get_rankings(&poll, limit, offset, results_visible)
             .await
             .map_err(|e| Status::internal(format!("Failed to get rankings: {}", e)))?;
 
         Ok(Response::new(GetPollRankingsResponse {
-            rankings: rankings
+            rankings: page
+                .rankings
                 .into_iter()
                 .map(to_proto_candidate_ranked)
                 .collect(),
-            total_candidates,
-            total_votes,
+            total_candidates: page.total_candidates,
+            total_votes: page.total_votes,
+            results_hidden: !results_visible,
+            runoff_rounds: page.runoff_rounds,
+            voter_count: page.voter_count,
         }))
     }
 
@@ -1055,24 +1583,41 @@ impl SocialService for SocialServiceImpl {
 
         let req = request.into_inner();
         let poll_id = parse_uuid(&req.poll_id, "poll_id")?;
-        let candidate_id = parse_uuid(&req.candidate_id, "candidate_id")?;
+        // Ballot in preference order; single-choice clients send candidate_id
+        let ballot: Vec<Uuid> = if req.candidate_ids.is_empty() {
+            vec![parse_uuid(&req.candidate_id, "candidate_id")?]
+        } else {
+            req.candidate_ids
+                .iter()
+                .map(|id| parse_uuid(id, "candidate_ids"))
+                .collect::<Result<_, _>>()?
+        };
 
         let repo = self.poll_repo();
+        let poll = repo
+            .get_poll(poll_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get poll: {}", e)))?
+            .ok_or_else(|| Status::not_found("Poll not found"))?;
+
+        // Mode, choice count and schedule are enforced here, not by clients
+        poll_rules::validate_ballot(&poll, &ballot, Utc::now())?;
 
         // Vote (triggers update counts automatically)
-        repo.vote(poll_id, candidate_id, user_id)
-            .await
-            .map_err(|e| {
-                if e.to_string().contains("already voted") {
-                    Status::already_exists("Already voted on this poll")
-                } else {
-                    Status::internal(format!("Failed to vote: {}", e))
-                }
-            })?;
+        repo.vote(poll_id, &ballot, user_id).await.map_err(|e| {
+            let msg = e.to_string();
+            if msg.contains("already voted") {
+                Status::already_exists("Already voted on this poll")
+            } else if msg.contains("Candidate not found") {
+                Status::not_found("Candidate not found")
+            } else {
+                Status::internal(format!("Failed to vote: {}", e))
+            }
+        })?;
 
-        // Get updated candidate and poll stats
-        let candidate = repo
-            .get_candidate(candidate_id)
+        // Get updated candidate (first choice) and poll stats
+        let mut candidate = repo
+            .get_candidate(ballot[0])
             .await
             .map_err(|e| Status::internal(format!("Failed to get candidate: {}", e)))?
             .ok_or_else(|| Status::not_found("Candidate not found"))?;
@@ -1083,10 +1628,18 @@ impl SocialService for SocialServiceImpl {
             .map_err(|e| Status::internal(format!("Failed to get poll: {}", e)))?
             .ok_or_else(|| Status::not_found("Poll not found"))?;
 
+        let total_votes = if poll_rules::results_visible(&poll, Some(user_id), Utc::now()) {
+            poll.total_votes
+        } else {
+            candidate.vote_count = 0;
+            0
+        };
+
         Ok(Response::new(VoteOnPollResponse {
             success: true,
-            updated_candidate: Some(to_proto_candidate_with_rank(candidate, 0, poll.total_votes)),
-            total_votes: poll.total_votes,
+            updated_candidate: Some(to_proto_candidate_with_rank(candidate, 0, total_votes)),
+            total_votes,
+            voted_candidate_ids: ballot.iter().map(|id| id.to_string()).collect(),
         }))
     }
 
@@ -1106,18 +1659,19 @@ impl SocialService for SocialServiceImpl {
         let poll_id = parse_uuid(&req.poll_id, "poll_id")?;
 
         let repo = self.poll_repo();
-        let vote = repo
+        let votes = repo
             .check_voted(poll_id, user_id)
             .await
             .map_err(|e| Status::internal(format!("Failed to check vote: {}", e)))?;
+        let first_choice = votes.first();
 
         Ok(Response::new(CheckPollVotedResponse {
-            has_voted: vote.is_some(),
-            voted_candidate_id: vote
-                .as_ref()
+            has_voted: first_choice.is_some(),
+            voted_candidate_id: first_choice
                 .map(|v| v.candidate_id.to_string())
                 .unwrap_or_default(),
-            voted_at: vote.map(|v| to_ts(v.created_at)).flatten(),
+            voted_at: first_choice.and_then(|v| to_ts(v.created_at)),
+            voted_candidate_ids: votes.iter().map(|v| v.candidate_id.to_string()).collect(),
         }))
     }
 
@@ -1139,10 +1693,45 @@ impl SocialService for SocialServiceImpl {
             return Err(Status::invalid_argument("Title is required"));
         }
 
+        let poll_type = if req.poll_type.is_empty() {
+            "ranking".to_string()
+        } else {
+            req.poll_type
+        };
+        let mode = PollMode::parse(&poll_type)
+            .ok_or_else(|| Status::invalid_argument(format!("Unknown poll_type: {}", poll_type)))?;
+
+        let max_choices = match req.max_choices {
+            n if n < 0 => return Err(Status::invalid_argument("max_choices must be positive")),
+            0 => None,
+            n if mode.allows_multiple() => Some(n),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "max_choices requires a multiple_choice or ranked_choice poll",
+                ))
+            }
+        };
+
         let ends_at = req.ends_at.map(|ts| {
             chrono::DateTime::from_timestamp(ts.seconds, ts.nanos as u32)
                 .unwrap_or_else(chrono::Utc::now)
         });
+        let starts_at = req.starts_at.map(|ts| {
+            chrono::DateTime::from_timestamp(ts.seconds, ts.nanos as u32)
+                .unwrap_or_else(chrono::Utc::now)
+        });
+        if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+            if starts_at >= ends_at {
+                return Err(Status::invalid_argument("starts_at must be before ends_at"));
+            }
+        }
+
+        let settings = PollSettings {
+            max_choices,
+            starts_at,
+            anonymous: req.anonymous,
+            hide_results_until_close: req.hide_results_until_close,
+        };
 
         let initial_candidates: Vec<CreateCandidateInput> = req
             .initial_candidates
@@ -1182,20 +1771,17 @@ impl SocialService for SocialServiceImpl {
                 } else {
                     Some(req.cover_image_url)
                 },
-                if req.poll_type.is_empty() {
-                    "ranking".to_string()
-                } else {
-                    req.poll_type
-                },
+                mode.as_str().to_string(),
                 req.tags,
                 ends_at,
+                settings,
                 initial_candidates,
             )
             .await
             .map_err(|e| Status::internal(format!("Failed to create poll: {}", e)))?;
 
         Ok(Response::new(CreatePollResponse {
-            poll: Some(to_proto_poll(poll)),
+            poll: Some(to_proto_poll(poll, true)),
             candidates: candidates
                 .into_iter()
                 .enumerate()
@@ -1219,13 +1805,25 @@ impl SocialService for SocialServiceImpl {
         let poll_id = parse_uuid(&req.poll_id, "poll_id")?;
 
         let repo = self.poll_repo();
+        let poll = repo
+            .get_poll(poll_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get poll: {}", e)))?
+            .ok_or_else(|| Status::not_found("Poll not found"))?;
+
+        // Ballots are final once the poll has closed
+        poll_rules::ensure_open(&poll, Utc::now())?;
+
         let success = repo
             .unvote(poll_id, user_id)
             .await
             .map_err(|e| Status::internal(format!("Failed to unvote: {}", e)))?;
 
         let poll = repo.get_poll(poll_id).await.ok().flatten();
-        let total_votes = poll.map(|p| p.total_votes).unwrap_or(0);
+        let total_votes = poll
+            .filter(|p| poll_rules::results_visible(p, Some(user_id), Utc::now()))
+            .map(|p| p.total_votes)
+            .unwrap_or(0);
 
         Ok(Response::new(UnvotePollResponse {
             success,
@@ -1409,13 +2007,15 @@ impl SocialService for SocialServiceImpl {
             .map_err(|e| Status::internal(format!("Failed to get active polls: {}", e)))?;
 
         // Get top candidates for each poll
+        let now = Utc::now();
         let mut summaries = Vec::with_capacity(polls.len());
         for poll in polls {
+            let results_visible = poll_rules::results_visible(&poll, None, now);
             let top_candidates = repo
-                .get_top_candidates(poll.id, 3)
+                .get_top_candidates(poll.id, 3, results_visible)
                 .await
                 .unwrap_or_default();
-            summaries.push(to_proto_poll_summary(poll, top_candidates));
+            summaries.push(to_proto_poll_summary(poll, top_candidates, results_visible));
         }
 
         Ok(Response::new(GetActivePollsResponse {
@@ -1424,6 +2024,61 @@ impl SocialService for SocialServiceImpl {
         }))
     }
 
+    async fn get_poll_voters(
+        &self,
+        request: Request<GetPollVotersRequest>,
+    ) -> Result<Response<GetPollVotersResponse>, Status> {
+        let viewer_id = request
+            .metadata()
+            .get("x-user-id")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| Uuid::parse_str(s).ok());
+
+        let req = request.into_inner();
+        let poll_id = parse_uuid(&req.poll_id, "poll_id")?;
+        let candidate_id = if req.candidate_id.is_empty() {
+            None
+        } else {
+            Some(parse_uuid(&req.candidate_id, "candidate_id")?)
+        };
+        let limit = sanitize_limit(req.limit, 1, 100, 20);
+        let offset = req.offset.max(0);
+
+        let repo = self.poll_repo();
+        let poll = repo
+            .get_poll(poll_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get poll: {}", e)))?
+            .ok_or_else(|| Status::not_found("Poll not found"))?;
+
+        if poll.anonymous {
+            return Err(Status::permission_denied(
+                "Voters of anonymous polls are not listed",
+            ));
+        }
+        if !poll_rules::results_visible(&poll, viewer_id, Utc::now()) {
+            return Err(Status::permission_denied(
+                "Results are hidden until the poll closes",
+            ));
+        }
+
+        let ballots = repo
+            .get_voters(poll_id, candidate_id, limit, offset)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get voters: {}", e)))?;
+
+        Ok(Response::new(GetPollVotersResponse {
+            voters: ballots
+                .into_iter()
+                .map(|b| PollVoter {
+                    user_id: b.user_id.to_string(),
+                    candidate_ids: b.candidate_ids.iter().map(|id| id.to_string()).collect(),
+                    voted_at: to_ts(b.voted_at),
+                })
+                .collect(),
+        }))
+    }
+
     // ========= Batch Operations (Feed Rendering Optimization) =========
 
     async fn batch_get_counts(
@@ -1521,6 +2176,46 @@ impl SocialService for SocialServiceImpl {
         Ok(Response::new(BatchGetLikeStatusResponse { statuses }))
     }
 
+    // ========= Admin =========
+
+    async fn recount_post(
+        &self,
+        request: Request<RecountPostRequest>,
+    ) -> Result<Response<RecountPostResponse>, Status> {
+        let req = request.into_inner();
+        let post_id = parse_uuid(&req.post_id, "post_id")?;
+
+        let report = CounterReconciler::new(self.state.counter_service.clone())
+            .recount_post(post_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to recount post: {}", e)))?;
+
+        let counts = report
+            .actual
+            .into_iter()
+            .next()
+            .map(|(_, counts)| PostCounts {
+                like_count: counts.like_count,
+                comment_count: counts.comment_count,
+                share_count: counts.share_count,
+                bookmark_count: counts.bookmark_count,
+            });
+
+        Ok(Response::new(RecountPostResponse {
+            counts,
+            drifts: report
+                .drifts
+                .into_iter()
+                .map(|d| CounterDrift {
+                    counter: d.kind.as_str().to_string(),
+                    store: d.store.as_str().to_string(),
+                    cached: d.cached,
+                    actual: d.actual,
+                })
+                .collect(),
+        }))
+    }
+
     // ========= Bookmarks =========
 
     async fn create_bookmark(
@@ -1537,6 +2232,8 @@ impl SocialService for SocialServiceImpl {
             .await
             .map_err(|e| Status::internal(format!("Failed to create bookmark: {}", e)))?;
 
+        self.state.counter_service.mark_hot_post(post_id).await;
+
         let proto_bookmark = Bookmark {
             id: bookmark.id.to_string(),
             user_id: bookmark.user_id.to_string(),
@@ -1567,6 +2264,8 @@ impl SocialService for SocialServiceImpl {
             .await
             .map_err(|e| Status::internal(format!("Failed to delete bookmark: {}", e)))?;
 
+        self.state.counter_service.mark_hot_post(post_id).await;
+
         Ok(Response::new(DeleteBookmarkResponse { success: true }))
     }
 
@@ -1707,6 +2406,102 @@ async fn publish_outbox_follow(
     Ok(())
 }
 
+async fn publish_outbox_reaction(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    target: ReactionTarget,
+    target_id: Uuid,
+    previous: Option<&str>,
+    current: Option<&str>,
+) -> Result<(), Status> {
+    let mut tx = state
+        .pg_pool
+        .begin()
+        .await
+        .map_err(|e| Status::internal(format!("Failed to open tx for outbox: {}", e)))?;
+
+    let event_type = match (previous, current) {
+        (None, _) => "social.reaction.added",
+        (Some(_), Some(_)) => "social.reaction.changed",
+        (Some(_), None) => "social.reaction.removed",
+    };
+    let event = OutboxEvent {
+        id: Uuid::new_v4(),
+        aggregate_type: "reaction".to_string(),
+        aggregate_id: target_id,
+        event_type: event_type.to_string(),
+        payload: serde_json::json!({
+            "user_id": user_id.to_string(),
+            "target_type": target.as_str(),
+            "target_id": target_id.to_string(),
+            "reaction": current,
+            "previous_reaction": previous,
+        }),
+        metadata: None,
+        created_at: chrono::Utc::now(),
+        published_at: None,
+        retry_count: 0,
+        last_error: None,
+    };
+
+    state
+        .outbox_repo
+        .insert(&mut tx, &event)
+        .await
+        .map_err(outbox_error_to_status)?;
+
+    tx.commit()
+        .await
+        .map_err(|e| Status::internal(format!("Failed to commit reaction outbox: {}", e)))?;
+    Ok(())
+}
+
+/// Author of the reacted post (content-service, as seen by the reacting user) or comment
+async fn resolve_reaction_recipient(
+    content_client: Option<ContentServiceClient<Channel>>,
+    comment_repo: &CommentRepository,
+    actor_id: Uuid,
+    target: ReactionTarget,
+    target_id: Uuid,
+) -> Option<Uuid> {
+    match target {
+        ReactionTarget::Comment => comment_repo
+            .get_comment_author(target_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = ?e, "Failed to get comment author for notification");
+                None
+            }),
+        ReactionTarget::Post => {
+            let mut client = content_client?;
+            let request = tonic::Request::new(GetPostRequest {
+                post_id: target_id.to_string(),
+                viewer_id: actor_id.to_string(),
+            });
+            match client.get_post(request).await {
+                Ok(response) => response
+                    .into_inner()
+                    .post
+                    .and_then(|post| Uuid::parse_str(&post.author_id).ok()),
+                Err(e) => {
+                    tracing::warn!(error = ?e, "Failed to get post from content-service");
+                    None
+                }
+            }
+        }
+    }
+}
+
+fn parse_reaction_target(value: i32) -> Result<ReactionTarget, Status> {
+    match ReactionTargetType::try_from(value) {
+        Ok(ReactionTargetType::Post) => Ok(ReactionTarget::Post),
+        Ok(ReactionTargetType::Comment) => Ok(ReactionTarget::Comment),
+        _ => Err(Status::invalid_argument(
+            "target_type must be POST or COMMENT",
+        )),
+    }
+}
+
 fn to_ts(dt: DateTime<Utc>) -> Option<Timestamp> {
     Some(Timestamp {
         seconds: dt.timestamp(),
@@ -1739,6 +2534,9 @@ fn to_proto_comment(comment: CommentModel) -> Comment {
         author_account_type: comment
             .author_account_type
             .unwrap_or_else(|| "primary".to_string()),
+        reply_count: 0,
+        replies: Vec::new(),
+        replies_cursor: String::new(),
     }
 }
 
@@ -1762,6 +2560,28 @@ fn to_proto_comment_with_engagement(
         author_account_type: comment
             .author_account_type
             .unwrap_or_else(|| "primary".to_string()),
+        reply_count: 0,
+        replies: Vec::new(),
+        replies_cursor: String::new(),
+    }
+}
+
+fn to_proto_comment_with_counts(comment: CommentWithCounts, is_liked: bool) -> Comment {
+    let mut proto = to_proto_comment_with_engagement(comment.comment, comment.like_count, is_liked);
+    proto.reply_count = comment.reply_count;
+    proto
+}
+
+fn to_proto_reaction_summary(
+    target_id: Uuid,
+    counts: ReactionCounts,
+    viewer_reaction: Option<String>,
+) -> ReactionSummary {
+    ReactionSummary {
+        target_id: target_id.to_string(),
+        counts: counts.counts.into_iter().collect(),
+        total: counts.total,
+        viewer_reaction: viewer_reaction.unwrap_or_default(),
     }
 }
 
@@ -1770,7 +2590,9 @@ fn _to_proto_share(_share: ShareModel) {}
 
 // ========= Poll Proto Conversions =========
 
-fn to_proto_poll(poll: PollModel) -> Poll {
+/// Status reflects the schedule; vote totals are zeroed while results are hidden
+fn to_proto_poll(poll: PollModel, results_visible: bool) -> Poll {
+    let status = poll_rules::effective_status(&poll, Utc::now());
     Poll {
         id: poll.id.to_string(),
         title: poll.title,
@@ -1778,26 +2600,33 @@ fn to_proto_poll(poll: PollModel) -> Poll {
         cover_image_url: poll.cover_image_url.unwrap_or_default(),
         creator_id: poll.creator_id.to_string(),
         poll_type: poll.poll_type,
-        status: poll.status,
-        total_votes: poll.total_votes,
+        status,
+        total_votes: if results_visible { poll.total_votes } else { 0 },
         candidate_count: poll.candidate_count,
         tags: poll.tags,
         created_at: to_ts(poll.created_at),
         ends_at: poll.ends_at.and_then(to_ts),
+        max_choices: poll.max_choices.unwrap_or(0),
+        starts_at: poll.starts_at.and_then(to_ts),
+        anonymous: poll.anonymous,
+        hide_results_until_close: poll.hide_results_until_close,
+        voter_count: poll.voter_count,
     }
 }
 
 fn to_proto_poll_summary(
     poll: PollModel,
     top_candidates: Vec<CandidatePreviewModel>,
+    results_visible: bool,
 ) -> PollSummary {
+    let status = poll_rules::effective_status(&poll, Utc::now());
     PollSummary {
         id: poll.id.to_string(),
         title: poll.title,
         cover_image_url: poll.cover_image_url.unwrap_or_default(),
         poll_type: poll.poll_type,
-        status: poll.status,
-        total_votes: poll.total_votes,
+        status,
+        total_votes: if results_visible { poll.total_votes } else { 0 },
         candidate_count: poll.candidate_count,
         top_candidates: top_candidates
             .into_iter()
@@ -1805,6 +2634,7 @@ fn to_proto_poll_summary(
             .collect(),
         tags: poll.tags,
         ends_at: poll.ends_at.and_then(to_ts),
+        starts_at: poll.starts_at.and_then(to_ts),
     }
 }
 
@@ -1841,6 +2671,7 @@ fn to_proto_candidate_with_rank(
         rank,
         rank_change: 0,
         vote_percentage,
+        eliminated_in_round: 0,
     }
 }
 
@@ -1855,5 +2686,6 @@ fn to_proto_candidate_ranked(ranked: CandidateWithRank) -> social::PollCandidate
         rank: ranked.rank,
         rank_change: ranked.rank_change,
         vote_percentage: ranked.vote_percentage,
+        eliminated_in_round: ranked.eliminated_in_round.unwrap_or(0),
     }
 }
@@ -28,9 +28,10 @@ use consumers::content_events::{ContentEventsConsumer, ContentEventsConsumerConf
 use grpc::server_v2::{
     social::social_service_server::SocialServiceServer, AppState, SocialServiceImpl,
 };
-use services::{CounterService, KafkaEventProducerConfig, SocialEventProducer};
+use services::sharded_counters::ShardedCounterConfig;
+use services::{CounterReconciler, CounterService, KafkaEventProducerConfig, SocialEventProducer};
 use transactional_outbox::SqlxOutboxRepository;
-use workers::{graph_sync::GraphSyncConsumer, outbox_worker, redis_health};
+use workers::{counter_reconcile, graph_sync::GraphSyncConsumer, outbox_worker, redis_health};
 
 async fn shutdown_signal() {
     #[cfg(unix)]
@@ -168,7 +169,8 @@ async fn main() -> Result<()> {
     info!("✅ Outbox repository initialized");
 
     // Initialize Counter service
-    let counter_service = CounterService::new(redis_conn.clone(), pg_pool.clone());
+    let counter_service = CounterService::new(redis_conn.clone(), pg_pool.clone())
+        .with_sharding(ShardedCounterConfig::from_env());
     info!("✅ Counter service initialized");
 
     // Start Redis health check background job to prevent broken pipe errors
@@ -182,6 +184,16 @@ async fn main() -> Result<()> {
     });
     info!("✅ Redis health check background job started");
 
+    // Start counter reconciliation (repairs Redis/PostgreSQL counter drift)
+    let reconciler = CounterReconciler::new(counter_service.clone());
+    tokio::spawn(async move {
+        counter_reconcile::start_counter_reconcile(
+            reconciler,
+            counter_reconcile::CounterReconcileConfig::from_env(),
+        )
+        .await;
+    });
+
     // Initialize gRPC client for graph-service
     let grpc_cfg = GrpcClientConfig::from_env()
         .map_err(|e| anyhow::anyhow!("Failed to load gRPC client config: {}", e))?;
@@ -212,7 +224,8 @@ async fn main() -> Result<()> {
     let content_client = grpc_pool.content();
     let mut app_state = AppState::new(pg_pool.clone(), counter_service, outbox_repo, graph_client)
         .with_identity_client(identity_client)
-        .with_content_client(content_client);
+        .with_content_client(content_client)
+        .with_reaction_config(config.reactions.clone());
     info!("✅ Identity and Content service clients initialized");
 
     if let Some(producer) = event_producer {
@@ -1,8 +1,106 @@
-use crate::domain::models::Comment;
+use crate::domain::models::{Comment, CommentWithCounts};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Columns selected for threaded listings (see `CommentWithCounts`)
+const COMMENT_WITH_COUNTS_COLUMNS: &str = "id, post_id, user_id, content, parent_comment_id, \
+     created_at, updated_at, author_account_type, like_count, reply_count, \
+     comment_top_score(like_count, created_at) AS top_score";
+
+/// Sort order for comment listings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentSort {
+    #[default]
+    Newest,
+    Oldest,
+    /// Like count plus recency (`comment_top_score` in migration 008)
+    Top,
+}
+
+impl CommentSort {
+    fn tag(self) -> &'static str {
+        match self {
+            CommentSort::Newest => "n",
+            CommentSort::Oldest => "o",
+            CommentSort::Top => "t",
+        }
+    }
+}
+
+/// Which comments a listing covers
+#[derive(Debug, Clone, Copy)]
+pub enum CommentScope {
+    /// Every comment on a post, replies included (legacy flat listing)
+    Post(Uuid),
+    /// Top-level comments on a post
+    TopLevel(Uuid),
+    /// Direct replies to a comment
+    Replies(Uuid),
+}
+
+/// Keyset position after the last comment of a page
+///
+/// Encoded as `<sort>:<key>:<id>`, where the key is `created_at` in
+/// microseconds for time sorts and the top score for `Top`, so a cursor
+/// can only be reused with the sort that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommentCursor {
+    pub sort: CommentSort,
+    pub created_at: DateTime<Utc>,
+    pub top_score: f64,
+    pub id: Uuid,
+}
+
+impl CommentCursor {
+    pub fn after(sort: CommentSort, comment: &CommentWithCounts) -> Self {
+        Self {
+            sort,
+            created_at: comment.comment.created_at,
+            top_score: comment.top_score,
+            id: comment.comment.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let key = match self.sort {
+            CommentSort::Top => self.top_score.to_string(),
+            _ => self.created_at.timestamp_micros().to_string(),
+        };
+        format!("{}:{}:{}", self.sort.tag(), key, self.id)
+    }
+
+    /// Decode a cursor produced by `encode` for the same sort
+    pub fn decode(cursor: &str, sort: CommentSort) -> Option<Self> {
+        let mut parts = cursor.splitn(3, ':');
+        let (tag, key, id) = (parts.next()?, parts.next()?, parts.next()?);
+        if tag != sort.tag() {
+            return None;
+        }
+        let id = Uuid::parse_str(id).ok()?;
+        let (created_at, top_score) = match sort {
+            CommentSort::Top => (DateTime::<Utc>::UNIX_EPOCH, key.parse::<f64>().ok()?),
+            _ => (DateTime::from_timestamp_micros(key.parse().ok()?)?, 0.0),
+        };
+        Some(Self {
+            sort,
+            created_at,
+            top_score,
+            id,
+        })
+    }
+}
+
+/// One page of a keyset-paginated listing
+#[derive(Debug, Clone, Default)]
+pub struct CommentPage {
+    pub comments: Vec<CommentWithCounts>,
+    /// Cursor for the next page; None on the last page
+    pub next_cursor: Option<String>,
+}
+
 /// Repository for Comment operations
 #[derive(Clone)]
 pub struct CommentRepository {
@@ -171,4 +269,147 @@ impl CommentRepository {
 
         Ok(author_id)
     }
+
+    /// List comments with keyset pagination
+    pub async fn list_comments(
+        &self,
+        scope: CommentScope,
+        sort: CommentSort,
+        cursor: Option<&CommentCursor>,
+        limit: i64,
+    ) -> Result<CommentPage> {
+        let (scope_clause, scope_id) = match scope {
+            CommentScope::Post(id) => ("post_id = $1", id),
+            CommentScope::TopLevel(id) => ("post_id = $1 AND parent_comment_id IS NULL", id),
+            CommentScope::Replies(id) => ("parent_comment_id = $1", id),
+        };
+        let (key_expr, order, op) = match sort {
+            CommentSort::Newest => ("created_at", "DESC", "<"),
+            CommentSort::Oldest => ("created_at", "ASC", ">"),
+            CommentSort::Top => ("comment_top_score(like_count, created_at)", "DESC", "<"),
+        };
+        let cursor_clause = if cursor.is_some() {
+            format!("AND ({}, id) {} ($3, $4)", key_expr, op)
+        } else {
+            String::new()
+        };
+
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM comments
+            WHERE {} AND is_deleted = FALSE {}
+            ORDER BY {} {}, id {}
+            LIMIT $2
+            "#,
+            COMMENT_WITH_COUNTS_COLUMNS, scope_clause, cursor_clause, key_expr, order, order
+        );
+
+        // Fetch one extra row to know whether another page exists
+        let mut q = sqlx::query_as::<_, CommentWithCounts>(&query)
+            .bind(scope_id)
+            .bind(limit + 1);
+        if let Some(cursor) = cursor {
+            q = match sort {
+                CommentSort::Top => q.bind(cursor.top_score),
+                _ => q.bind(cursor.created_at),
+            }
+            .bind(cursor.id);
+        }
+        let mut comments = q.fetch_all(&self.pool).await?;
+
+        let next_cursor = if comments.len() as i64 > limit {
+            comments.truncate(limit as usize);
+            comments
+                .last()
+                .map(|last| CommentCursor::after(sort, last).encode())
+        } else {
+            None
+        };
+
+        Ok(CommentPage {
+            comments,
+            next_cursor,
+        })
+    }
+
+    /// First `per_thread` replies (oldest first) of each parent comment
+    pub async fn get_first_replies(
+        &self,
+        parent_ids: &[Uuid],
+        per_thread: i64,
+    ) -> Result<HashMap<Uuid, Vec<CommentWithCounts>>> {
+        if parent_ids.is_empty() || per_thread <= 0 {
+            return Ok(HashMap::new());
+        }
+
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM (
+                SELECT *, ROW_NUMBER() OVER (
+                    PARTITION BY parent_comment_id ORDER BY created_at ASC, id ASC
+                ) AS thread_rank
+                FROM comments
+                WHERE parent_comment_id = ANY($1) AND is_deleted = FALSE
+            ) replies
+            WHERE thread_rank <= $2
+            ORDER BY parent_comment_id, created_at ASC, id ASC
+            "#,
+            COMMENT_WITH_COUNTS_COLUMNS
+        );
+
+        let replies = sqlx::query_as::<_, CommentWithCounts>(&query)
+            .bind(parent_ids)
+            .bind(per_thread)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut threads: HashMap<Uuid, Vec<CommentWithCounts>> = HashMap::new();
+        for reply in replies {
+            if let Some(parent_id) = reply.comment.parent_comment_id {
+                threads.entry(parent_id).or_default().push(reply);
+            }
+        }
+
+        Ok(threads)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let created_at = DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap();
+        let id = Uuid::new_v4();
+        for sort in [CommentSort::Newest, CommentSort::Oldest, CommentSort::Top] {
+            let cursor = CommentCursor {
+                sort,
+                created_at,
+                top_score: 9837.123456789,
+                id,
+            };
+            let decoded = CommentCursor::decode(&cursor.encode(), sort).unwrap();
+            assert_eq!(decoded.id, id);
+            match sort {
+                CommentSort::Top => assert_eq!(decoded.top_score, cursor.top_score),
+                _ => assert_eq!(decoded.created_at, created_at),
+            }
+        }
+    }
+
+    #[test]
+    fn test_cursor_rejects_other_sort_and_garbage() {
+        let cursor = CommentCursor {
+            sort: CommentSort::Top,
+            created_at: Utc::now(),
+            top_score: 1.5,
+            id: Uuid::new_v4(),
+        };
+        assert!(CommentCursor::decode(&cursor.encode(), CommentSort::Newest).is_none());
+        assert!(CommentCursor::decode("n:abc:def", CommentSort::Newest).is_none());
+        assert!(CommentCursor::decode("", CommentSort::Newest).is_none());
+    }
 }
@@ -3,6 +3,7 @@ pub mod comment_likes;
 pub mod comments;
 pub mod likes;
 pub mod polls;
+pub mod reactions;
 pub mod shares;
 
 pub use bookmarks::BookmarkRepository;
@@ -10,4 +11,5 @@ pub use comment_likes::CommentLikeRepository;
 pub use comments::CommentRepository;
 pub use likes::LikeRepository;
 pub use polls::PollRepository;
+pub use reactions::ReactionRepository;
 pub use shares::ShareRepository;
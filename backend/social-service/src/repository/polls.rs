@@ -1,5 +1,5 @@
-use crate::domain::models::{CandidatePreview, CandidateWithRank, Poll, PollCandidate, PollVote};
-use anyhow::{Context, Result};
+use crate::domain::models::{CandidatePreview, Poll, PollCandidate, PollVote};
+use anyhow::Result;
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -14,7 +14,7 @@ impl PollRepository {
         Self { pool }
     }
 
-    /// Get trending polls (active polls sorted by vote count)
+    /// Get trending polls (open polls sorted by vote count)
     pub async fn get_trending_polls(
         &self,
         limit: i32,
@@ -25,9 +25,12 @@ impl PollRepository {
                 r#"
                 SELECT id, title, description, cover_image_url, creator_id, poll_type,
                        status, total_votes, candidate_count, tags, created_at, updated_at,
-                       ends_at, is_deleted
+                       ends_at, is_deleted, max_choices, starts_at, anonymous,
+                       hide_results_until_close, voter_count
                 FROM polls
                 WHERE status = 'active' AND is_deleted = FALSE AND tags && $1
+                  AND (starts_at IS NULL OR starts_at <= NOW())
+                  AND (ends_at IS NULL OR ends_at > NOW())
                 ORDER BY total_votes DESC
                 LIMIT $2
                 "#,
@@ -41,9 +44,12 @@ impl PollRepository {
                 r#"
                 SELECT id, title, description, cover_image_url, creator_id, poll_type,
                        status, total_votes, candidate_count, tags, created_at, updated_at,
-                       ends_at, is_deleted
+                       ends_at, is_deleted, max_choices, starts_at, anonymous,
+                       hide_results_until_close, voter_count
                 FROM polls
                 WHERE status = 'active' AND is_deleted = FALSE
+                  AND (starts_at IS NULL OR starts_at <= NOW())
+                  AND (ends_at IS NULL OR ends_at > NOW())
                 ORDER BY total_votes DESC
                 LIMIT $1
                 "#,
@@ -62,7 +68,8 @@ impl PollRepository {
             r#"
             SELECT id, title, description, cover_image_url, creator_id, poll_type,
                    status, total_votes, candidate_count, tags, created_at, updated_at,
-                   ends_at, is_deleted
+                   ends_at, is_deleted, max_choices, starts_at, anonymous,
+                   hide_results_until_close, voter_count
             FROM polls
             WHERE id = $1 AND is_deleted = FALSE
             "#,
@@ -74,41 +81,27 @@ impl PollRepository {
         Ok(poll)
     }
 
-    /// Get candidates for a poll (sorted by vote_count DESC)
-    pub async fn get_poll_candidates(&self, poll_id: Uuid) -> Result<Vec<PollCandidate>> {
-        let candidates = sqlx::query_as::<_, PollCandidate>(
-            r#"
-            SELECT id, poll_id, name, avatar_url, description, user_id,
-                   vote_count, position, created_at, is_deleted
-            FROM poll_candidates
-            WHERE poll_id = $1 AND is_deleted = FALSE
-            ORDER BY vote_count DESC, position ASC
-            "#,
-        )
-        .bind(poll_id)
-        .fetch_all(&self.pool)
-        .await?;
-
-        Ok(candidates)
-    }
-
     /// Get top N candidates for preview
+    ///
+    /// With `by_votes` false (results hidden) candidates come in listing order.
     pub async fn get_top_candidates(
         &self,
         poll_id: Uuid,
         limit: i32,
+        by_votes: bool,
     ) -> Result<Vec<CandidatePreview>> {
         let rows = sqlx::query_as::<_, (Uuid, String, Option<String>, i64)>(
             r#"
             SELECT id, name, avatar_url, vote_count
             FROM poll_candidates
             WHERE poll_id = $1 AND is_deleted = FALSE
-            ORDER BY vote_count DESC
+            ORDER BY CASE WHEN $3 THEN vote_count ELSE 0 END DESC, position ASC
             LIMIT $2
             "#,
         )
         .bind(poll_id)
         .bind(limit)
+        .bind(by_votes)
         .fetch_all(&self.pool)
         .await?;
 
@@ -126,25 +119,20 @@ impl PollRepository {
         Ok(previews)
     }
 
-    /// Get rankings with pagination
-    pub async fn get_rankings(
+    /// Candidates ordered by stored vote count
+    pub async fn list_candidates_by_votes(
         &self,
         poll_id: Uuid,
         limit: i32,
         offset: i32,
-    ) -> Result<(Vec<CandidateWithRank>, i32, i64)> {
-        // Get total votes for percentage calculation
-        let poll = self.get_poll(poll_id).await?.context("Poll not found")?;
-        let total_votes = poll.total_votes;
-
-        // Get candidates with rank
+    ) -> Result<Vec<PollCandidate>> {
         let candidates = sqlx::query_as::<_, PollCandidate>(
             r#"
             SELECT id, poll_id, name, avatar_url, description, user_id,
                    vote_count, position, created_at, is_deleted
             FROM poll_candidates
             WHERE poll_id = $1 AND is_deleted = FALSE
-            ORDER BY vote_count DESC
+            ORDER BY vote_count DESC, position ASC
             LIMIT $2 OFFSET $3
             "#,
         )
@@ -154,35 +142,56 @@ impl PollRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        let ranked: Vec<CandidateWithRank> = candidates
-            .into_iter()
-            .enumerate()
-            .map(|(idx, c)| {
-                let rank = offset + (idx as i32) + 1;
-                let vote_percentage = if total_votes > 0 {
-                    (c.vote_count as f64 / total_votes as f64) * 100.0
-                } else {
-                    0.0
-                };
-                CandidateWithRank {
-                    id: c.id,
-                    name: c.name,
-                    avatar_url: c.avatar_url,
-                    description: c.description,
-                    user_id: c.user_id,
-                    vote_count: c.vote_count,
-                    rank,
-                    rank_change: 0, // TODO: Track rank changes over time
-                    vote_percentage,
-                }
-            })
-            .collect();
+        Ok(candidates)
+    }
 
-        Ok((ranked, poll.candidate_count, total_votes))
+    /// Candidates in listing order
+    pub async fn list_candidates_by_position(&self, poll_id: Uuid) -> Result<Vec<PollCandidate>> {
+        let candidates = sqlx::query_as::<_, PollCandidate>(
+            r#"
+            SELECT id, poll_id, name, avatar_url, description, user_id,
+                   vote_count, position, created_at, is_deleted
+            FROM poll_candidates
+            WHERE poll_id = $1 AND is_deleted = FALSE
+            ORDER BY position ASC
+            "#,
+        )
+        .bind(poll_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// All ballots of a poll, each in preference order
+    pub async fn get_ballots(&self, poll_id: Uuid) -> Result<Vec<Vec<Uuid>>> {
+        let ballots: Vec<Vec<Uuid>> = sqlx::query_scalar(
+            r#"
+            SELECT array_agg(candidate_id ORDER BY choice_rank)
+            FROM poll_votes
+            WHERE poll_id = $1
+            GROUP BY user_id
+            "#,
+        )
+        .bind(poll_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ballots)
     }
 
-    /// Vote on a poll (one vote per user per poll)
-    pub async fn vote(&self, poll_id: Uuid, candidate_id: Uuid, user_id: Uuid) -> Result<PollVote> {
+    /// Cast a ballot (one ballot per user per poll)
+    ///
+    /// `candidate_ids` are in preference order and must already satisfy the
+    /// poll's mode (see `validate_ballot` in the poll service).
+    pub async fn vote(
+        &self,
+        poll_id: Uuid,
+        candidate_ids: &[Uuid],
+        user_id: Uuid,
+    ) -> Result<Vec<PollVote>> {
+        let mut tx = self.pool.begin().await?;
+
         // Check if user already voted
         let existing = sqlx::query_scalar::<_, bool>(
             r#"
@@ -194,45 +203,106 @@ impl PollRepository {
         )
         .bind(poll_id)
         .bind(user_id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
         if existing {
             anyhow::bail!("User has already voted on this poll");
         }
 
-        // Insert vote (triggers will update counts)
-        let vote = sqlx::query_as::<_, PollVote>(
+        let valid: i64 = sqlx::query_scalar(
             r#"
-            INSERT INTO poll_votes (poll_id, candidate_id, user_id)
-            VALUES ($1, $2, $3)
-            RETURNING id, poll_id, candidate_id, user_id, created_at
+            SELECT COUNT(*) FROM poll_candidates
+            WHERE poll_id = $1 AND id = ANY($2) AND is_deleted = FALSE
             "#,
         )
         .bind(poll_id)
-        .bind(candidate_id)
-        .bind(user_id)
-        .fetch_one(&self.pool)
+        .bind(candidate_ids)
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(vote)
+        if valid != candidate_ids.len() as i64 {
+            anyhow::bail!("Candidate not found in this poll");
+        }
+
+        // Insert one row per choice (triggers will update counts)
+        let mut votes = Vec::with_capacity(candidate_ids.len());
+        for (idx, candidate_id) in candidate_ids.iter().enumerate() {
+            let vote = sqlx::query_as::<_, PollVote>(
+                r#"
+                INSERT INTO poll_votes (poll_id, candidate_id, user_id, choice_rank)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id, poll_id, candidate_id, user_id, choice_rank, created_at
+                "#,
+            )
+            .bind(poll_id)
+            .bind(candidate_id)
+            .bind(user_id)
+            .bind((idx + 1) as i32)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                // A concurrent ballot from the same user won the race
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    anyhow::anyhow!("User has already voted on this poll")
+                }
+                e => e.into(),
+            })?;
+            votes.push(vote);
+        }
+
+        tx.commit().await?;
+        Ok(votes)
     }
 
-    /// Check if user has voted on a poll
-    pub async fn check_voted(&self, poll_id: Uuid, user_id: Uuid) -> Result<Option<PollVote>> {
-        let vote = sqlx::query_as::<_, PollVote>(
+    /// Get the user's ballot on a poll in preference order (empty if none)
+    pub async fn check_voted(&self, poll_id: Uuid, user_id: Uuid) -> Result<Vec<PollVote>> {
+        let votes = sqlx::query_as::<_, PollVote>(
             r#"
-            SELECT id, poll_id, candidate_id, user_id, created_at
+            SELECT id, poll_id, candidate_id, user_id, choice_rank, created_at
             FROM poll_votes
             WHERE poll_id = $1 AND user_id = $2
+            ORDER BY choice_rank ASC
             "#,
         )
         .bind(poll_id)
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(votes)
+    }
+
+    /// Get ballots with voter IDs (newest first), optionally only those
+    /// that include a candidate
+    pub async fn get_voters(
+        &self,
+        poll_id: Uuid,
+        candidate_id: Option<Uuid>,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<PollBallot>> {
+        let ballots = sqlx::query_as::<_, PollBallot>(
+            r#"
+            SELECT user_id,
+                   array_agg(candidate_id ORDER BY choice_rank) AS candidate_ids,
+                   MIN(created_at) AS voted_at
+            FROM poll_votes
+            WHERE poll_id = $1
+            GROUP BY user_id
+            HAVING $2::UUID IS NULL OR bool_or(candidate_id = $2)
+            ORDER BY voted_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(poll_id)
+        .bind(candidate_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(vote)
+        Ok(ballots)
     }
 
     /// Get a specific candidate
@@ -253,6 +323,7 @@ impl PollRepository {
     }
 
     /// Create a new poll with optional initial candidates
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_poll(
         &self,
         creator_id: Uuid,
@@ -262,6 +333,7 @@ impl PollRepository {
         poll_type: String,
         tags: Vec<String>,
         ends_at: Option<chrono::DateTime<chrono::Utc>>,
+        settings: PollSettings,
         initial_candidates: Vec<CreateCandidateInput>,
     ) -> Result<(Poll, Vec<PollCandidate>)> {
         let mut tx = self.pool.begin().await?;
@@ -269,11 +341,13 @@ impl PollRepository {
         // Create poll
         let poll = sqlx::query_as::<_, Poll>(
             r#"
-            INSERT INTO polls (creator_id, title, description, cover_image_url, poll_type, tags, ends_at, status, candidate_count)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, 'active', $8)
+            INSERT INTO polls (creator_id, title, description, cover_image_url, poll_type, tags, ends_at, status, candidate_count,
+                               max_choices, starts_at, anonymous, hide_results_until_close)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, 'active', $8, $9, $10, $11, $12)
             RETURNING id, title, description, cover_image_url, creator_id, poll_type,
                       status, total_votes, candidate_count, tags, created_at, updated_at,
-                      ends_at, is_deleted
+                      ends_at, is_deleted, max_choices, starts_at, anonymous,
+                      hide_results_until_close, voter_count
             "#,
         )
         .bind(creator_id)
@@ -284,6 +358,10 @@ impl PollRepository {
         .bind(&tags)
         .bind(ends_at)
         .bind(initial_candidates.len() as i32)
+        .bind(settings.max_choices)
+        .bind(settings.starts_at)
+        .bind(settings.anonymous)
+        .bind(settings.hide_results_until_close)
         .fetch_one(&mut *tx)
         .await?;
 
@@ -313,54 +391,17 @@ impl PollRepository {
         Ok((poll, candidates))
     }
 
-    /// Unvote (remove vote) from a poll
+    /// Unvote (remove the user's whole ballot) from a poll
+    ///
+    /// Counts are maintained by the poll_votes trigger.
     pub async fn unvote(&self, poll_id: Uuid, user_id: Uuid) -> Result<bool> {
-        // Get existing vote to find candidate_id
-        let vote = sqlx::query_as::<_, PollVote>(
-            r#"
-            SELECT id, poll_id, candidate_id, user_id, created_at
-            FROM poll_votes
-            WHERE poll_id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(poll_id)
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        if vote.is_none() {
-            return Ok(false);
-        }
-
-        // SAFETY: We checked is_none() above and returned early
-        let vote = vote.expect("vote checked above");
-
-        // Delete vote and update counts
-        let mut tx = self.pool.begin().await?;
-
-        sqlx::query("DELETE FROM poll_votes WHERE id = $1")
-            .bind(vote.id)
-            .execute(&mut *tx)
+        let result = sqlx::query("DELETE FROM poll_votes WHERE poll_id = $1 AND user_id = $2")
+            .bind(poll_id)
+            .bind(user_id)
+            .execute(&self.pool)
             .await?;
 
-        // Decrement candidate vote count
-        sqlx::query(
-            "UPDATE poll_candidates SET vote_count = vote_count - 1 WHERE id = $1 AND vote_count > 0",
-        )
-        .bind(vote.candidate_id)
-        .execute(&mut *tx)
-        .await?;
-
-        // Decrement poll total votes
-        sqlx::query(
-            "UPDATE polls SET total_votes = total_votes - 1 WHERE id = $1 AND total_votes > 0",
-        )
-        .bind(poll_id)
-        .execute(&mut *tx)
-        .await?;
-
-        tx.commit().await?;
-        Ok(true)
+        Ok(result.rows_affected() > 0)
     }
 
     /// Add a candidate to a poll
@@ -400,7 +441,9 @@ impl PollRepository {
         .await?;
 
         // Update poll candidate count
-        sqlx::query("UPDATE polls SET candidate_count = candidate_count + 1 WHERE id = $1")
+        sqlx::query(
+            "UPDATE polls SET candidate_count = candidate_count + 1, updated_at = NOW() WHERE id = $1",
+        )
             .bind(poll_id)
             .execute(&mut *tx)
             .await?;
@@ -424,7 +467,7 @@ impl PollRepository {
         if result.rows_affected() > 0 {
             // Update poll candidate count
             sqlx::query(
-                "UPDATE polls SET candidate_count = candidate_count - 1 WHERE id = $1 AND candidate_count > 0",
+                "UPDATE polls SET candidate_count = candidate_count - 1, updated_at = NOW() WHERE id = $1 AND candidate_count > 0",
             )
             .bind(poll_id)
             .execute(&mut *tx)
@@ -461,7 +504,7 @@ impl PollRepository {
         Ok(result.rows_affected() > 0)
     }
 
-    /// Get active polls with pagination
+    /// Get open polls (active and within their schedule) with pagination
     pub async fn get_active_polls(
         &self,
         limit: i32,
@@ -473,9 +516,12 @@ impl PollRepository {
                 r#"
                 SELECT id, title, description, cover_image_url, creator_id, poll_type,
                        status, total_votes, candidate_count, tags, created_at, updated_at,
-                       ends_at, is_deleted
+                       ends_at, is_deleted, max_choices, starts_at, anonymous,
+                       hide_results_until_close, voter_count
                 FROM polls
                 WHERE status = 'active' AND is_deleted = FALSE AND tags && $1
+                  AND (starts_at IS NULL OR starts_at <= NOW())
+                  AND (ends_at IS NULL OR ends_at > NOW())
                 ORDER BY created_at DESC
                 LIMIT $2 OFFSET $3
                 "#,
@@ -487,7 +533,12 @@ impl PollRepository {
             .await?;
 
             let total: i64 = sqlx::query_scalar(
-                "SELECT COUNT(*) FROM polls WHERE status = 'active' AND is_deleted = FALSE AND tags && $1",
+                r#"
+                SELECT COUNT(*) FROM polls
+                WHERE status = 'active' AND is_deleted = FALSE AND tags && $1
+                  AND (starts_at IS NULL OR starts_at <= NOW())
+                  AND (ends_at IS NULL OR ends_at > NOW())
+                "#,
             )
             .bind(&tags)
             .fetch_one(&self.pool)
@@ -499,9 +550,12 @@ impl PollRepository {
                 r#"
                 SELECT id, title, description, cover_image_url, creator_id, poll_type,
                        status, total_votes, candidate_count, tags, created_at, updated_at,
-                       ends_at, is_deleted
+                       ends_at, is_deleted, max_choices, starts_at, anonymous,
+                       hide_results_until_close, voter_count
                 FROM polls
                 WHERE status = 'active' AND is_deleted = FALSE
+                  AND (starts_at IS NULL OR starts_at <= NOW())
+                  AND (ends_at IS NULL OR ends_at > NOW())
                 ORDER BY created_at DESC
                 LIMIT $1 OFFSET $2
                 "#,
@@ -512,7 +566,12 @@ impl PollRepository {
             .await?;
 
             let total: i64 = sqlx::query_scalar(
-                "SELECT COUNT(*) FROM polls WHERE status = 'active' AND is_deleted = FALSE",
+                r#"
+                SELECT COUNT(*) FROM polls
+                WHERE status = 'active' AND is_deleted = FALSE
+                  AND (starts_at IS NULL OR starts_at <= NOW())
+                  AND (ends_at IS NULL OR ends_at > NOW())
+                "#,
             )
             .fetch_one(&self.pool)
             .await?;
@@ -524,6 +583,23 @@ impl PollRepository {
     }
 }
 
+/// Mode settings for a new poll
+#[derive(Debug, Clone, Default)]
+pub struct PollSettings {
+    pub max_choices: Option<i32>,
+    pub starts_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub anonymous: bool,
+    pub hide_results_until_close: bool,
+}
+
+/// One user's ballot, choices in preference order
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PollBallot {
+    pub user_id: Uuid,
+    pub candidate_ids: Vec<Uuid>,
+    pub voted_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// Input for creating a candidate
 pub struct CreateCandidateInput {
     pub name: String,
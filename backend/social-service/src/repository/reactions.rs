@@ -0,0 +1,201 @@
+use crate::domain::models::{Reaction, ReactionTarget};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Helper struct for set_reaction with the reaction it replaced
+#[derive(sqlx::FromRow)]
+struct ReactionWithPrevious {
+    id: Uuid,
+    user_id: Uuid,
+    target_type: String,
+    target_id: Uuid,
+    reaction: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    previous_reaction: Option<String>,
+}
+
+/// Result of setting a reaction
+#[derive(Debug, Clone)]
+pub struct ReactionChange {
+    pub reaction: Reaction,
+    /// Reaction the user had before; None for a new reaction
+    pub previous: Option<String>,
+}
+
+impl ReactionChange {
+    /// True when the user re-sent the reaction they already had
+    pub fn is_unchanged(&self) -> bool {
+        self.previous.as_deref() == Some(self.reaction.reaction.as_str())
+    }
+}
+
+/// Repository for Reaction operations
+///
+/// Per-reaction counters live in `reaction_counters` (trigger-maintained)
+/// and are read through `CounterService`.
+#[derive(Clone)]
+pub struct ReactionRepository {
+    pool: PgPool,
+}
+
+impl ReactionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Set the user's reaction on a target, replacing any previous one
+    pub async fn set_reaction(
+        &self,
+        user_id: Uuid,
+        target: ReactionTarget,
+        target_id: Uuid,
+        reaction: &str,
+    ) -> Result<ReactionChange> {
+        // The CTE reads the row as it was before the upsert
+        let row = sqlx::query_as::<_, ReactionWithPrevious>(
+            r#"
+            WITH previous AS (
+                SELECT reaction FROM reactions
+                WHERE user_id = $1 AND target_type = $2 AND target_id = $3
+            )
+            INSERT INTO reactions (user_id, target_type, target_id, reaction)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, target_type, target_id) DO UPDATE
+            SET reaction = EXCLUDED.reaction,
+                updated_at = CASE
+                    WHEN reactions.reaction = EXCLUDED.reaction THEN reactions.updated_at
+                    ELSE NOW()
+                END
+            RETURNING id, user_id, target_type, target_id, reaction, created_at, updated_at,
+                (SELECT reaction FROM previous) AS previous_reaction
+            "#,
+        )
+        .bind(user_id)
+        .bind(target.as_str())
+        .bind(target_id)
+        .bind(reaction)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ReactionChange {
+            reaction: Reaction {
+                id: row.id,
+                user_id: row.user_id,
+                target_type: row.target_type,
+                target_id: row.target_id,
+                reaction: row.reaction,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            },
+            previous: row.previous_reaction,
+        })
+    }
+
+    /// Remove the user's reaction (idempotent); returns the removed reaction
+    pub async fn remove_reaction(
+        &self,
+        user_id: Uuid,
+        target: ReactionTarget,
+        target_id: Uuid,
+    ) -> Result<Option<String>> {
+        let removed: Option<String> = sqlx::query_scalar(
+            r#"
+            DELETE FROM reactions
+            WHERE user_id = $1 AND target_type = $2 AND target_id = $3
+            RETURNING reaction
+            "#,
+        )
+        .bind(user_id)
+        .bind(target.as_str())
+        .bind(target_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(removed)
+    }
+
+    /// Get the user's reaction on a target
+    pub async fn get_user_reaction(
+        &self,
+        user_id: Uuid,
+        target: ReactionTarget,
+        target_id: Uuid,
+    ) -> Result<Option<String>> {
+        let reaction: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT reaction FROM reactions
+            WHERE user_id = $1 AND target_type = $2 AND target_id = $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(target.as_str())
+        .bind(target_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(reaction)
+    }
+
+    /// Batch lookup of the user's reactions (feed rendering)
+    ///
+    /// Targets the user has not reacted to are absent from the map.
+    pub async fn batch_get_user_reactions(
+        &self,
+        user_id: Uuid,
+        target: ReactionTarget,
+        target_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, String>> {
+        if target_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT target_id, reaction
+            FROM reactions
+            WHERE user_id = $1 AND target_type = $2 AND target_id = ANY($3)
+            "#,
+        )
+        .bind(user_id)
+        .bind(target.as_str())
+        .bind(target_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Get paginated reactions on a target, optionally for one reaction type
+    pub async fn get_reactions(
+        &self,
+        target: ReactionTarget,
+        target_id: Uuid,
+        reaction: Option<&str>,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<Reaction>> {
+        let reactions = sqlx::query_as::<_, Reaction>(
+            r#"
+            SELECT id, user_id, target_type, target_id, reaction, created_at, updated_at
+            FROM reactions
+            WHERE target_type = $1 AND target_id = $2
+              AND ($3::VARCHAR IS NULL OR reaction = $3)
+            ORDER BY created_at DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(target.as_str())
+        .bind(target_id)
+        .bind(reaction)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(reactions)
+    }
+}
@@ -0,0 +1,506 @@
+//! Counter drift detection and repair
+//!
+//! Counters are cached at two levels: Redis keys (`post:{id}:likes|comments|shares`)
+//! updated with INCR/DECR after PostgreSQL writes, and the trigger-maintained
+//! tables `post_counters` (likes/comments/shares) and `post_metadata.save_count`
+//! (bookmarks). Either level can drift on partial failures (write committed but
+//! Redis update lost, INCR on an expired key, ...). The reconciler recounts the
+//! source tables and repairs both levels. Redis values are read and repaired
+//! through `CounterService`, so hot counters split into sub-keys are compared
+//! by their summed value.
+
+use crate::services::counters::{CounterService, PostCounts};
+use anyhow::{Context, Result};
+use prometheus::{HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts};
+use sqlx::PgPool;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Counter checked by the reconciler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterKind {
+    Likes,
+    Comments,
+    Shares,
+    Bookmarks,
+}
+
+impl CounterKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CounterKind::Likes => "likes",
+            CounterKind::Comments => "comments",
+            CounterKind::Shares => "shares",
+            CounterKind::Bookmarks => "bookmarks",
+        }
+    }
+
+    /// Kinds cached in Redis (bookmarks are read from PostgreSQL)
+    const CACHED: [CounterKind; 3] = [
+        CounterKind::Likes,
+        CounterKind::Comments,
+        CounterKind::Shares,
+    ];
+
+    fn redis_key(&self, post_id: Uuid) -> String {
+        format!("post:{}:{}", post_id, self.as_str())
+    }
+
+    fn value(&self, counts: &PostCounts) -> i64 {
+        match self {
+            CounterKind::Likes => counts.like_count,
+            CounterKind::Comments => counts.comment_count,
+            CounterKind::Shares => counts.share_count,
+            CounterKind::Bookmarks => counts.bookmark_count,
+        }
+    }
+}
+
+/// Where a drifted value was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterStore {
+    Redis,
+    Postgres,
+}
+
+impl CounterStore {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CounterStore::Redis => "redis",
+            CounterStore::Postgres => "postgres",
+        }
+    }
+}
+
+/// A cached counter that disagrees with the recount
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterDrift {
+    pub post_id: Uuid,
+    pub kind: CounterKind,
+    pub store: CounterStore,
+    pub cached: i64,
+    pub actual: i64,
+}
+
+impl CounterDrift {
+    /// Signed error of the cached value (positive = over-counted)
+    pub fn delta(&self) -> i64 {
+        self.cached - self.actual
+    }
+}
+
+/// Outcome of reconciling a batch of posts
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileReport {
+    pub posts_checked: usize,
+    pub drifts: Vec<CounterDrift>,
+    /// Recounted values per post
+    pub actual: Vec<(Uuid, PostCounts)>,
+}
+
+/// Stored counters next to a recount of the source tables
+#[derive(Debug, sqlx::FromRow)]
+struct CounterSnapshot {
+    post_id: Uuid,
+    stored_likes: Option<i64>,
+    stored_comments: Option<i64>,
+    stored_shares: Option<i64>,
+    stored_bookmarks: Option<i64>,
+    actual_likes: i64,
+    actual_comments: i64,
+    actual_shares: i64,
+    actual_bookmarks: i64,
+}
+
+impl CounterSnapshot {
+    fn actual(&self) -> PostCounts {
+        PostCounts {
+            like_count: self.actual_likes,
+            comment_count: self.actual_comments,
+            share_count: self.actual_shares,
+            bookmark_count: self.actual_bookmarks,
+        }
+    }
+
+    /// Stored PostgreSQL values; None where the row does not exist
+    fn stored(&self, kind: CounterKind) -> Option<i64> {
+        match kind {
+            // A missing post_counters row reads as zero everywhere
+            CounterKind::Likes => Some(self.stored_likes.unwrap_or(0)),
+            CounterKind::Comments => Some(self.stored_comments.unwrap_or(0)),
+            CounterKind::Shares => Some(self.stored_shares.unwrap_or(0)),
+            CounterKind::Bookmarks => self.stored_bookmarks,
+        }
+    }
+}
+
+/// Compare cached values against the recount
+///
+/// `redis` holds the likes/comments/shares keys in `CounterKind::CACHED`
+/// order; missing keys are not drift (they are reloaded on the next read).
+fn detect_drift(snapshot: &CounterSnapshot, redis: &[Option<i64>]) -> Vec<CounterDrift> {
+    let actual = snapshot.actual();
+    let mut drifts = Vec::new();
+
+    for kind in [
+        CounterKind::Likes,
+        CounterKind::Comments,
+        CounterKind::Shares,
+        CounterKind::Bookmarks,
+    ] {
+        let expected = kind.value(&actual);
+        if let Some(stored) = snapshot.stored(kind) {
+            if stored != expected {
+                drifts.push(CounterDrift {
+                    post_id: snapshot.post_id,
+                    kind,
+                    store: CounterStore::Postgres,
+                    cached: stored,
+                    actual: expected,
+                });
+            }
+        }
+    }
+
+    for (kind, cached) in CounterKind::CACHED.iter().zip(redis) {
+        let expected = kind.value(&actual);
+        if let Some(cached) = cached {
+            if *cached != expected {
+                drifts.push(CounterDrift {
+                    post_id: snapshot.post_id,
+                    kind: *kind,
+                    store: CounterStore::Redis,
+                    cached: *cached,
+                    actual: expected,
+                });
+            }
+        }
+    }
+
+    drifts
+}
+
+/// Prometheus metrics for counter reconciliation
+pub struct ReconcileMetrics {
+    pub posts_checked_total: IntCounter,
+    pub drift_total: IntCounterVec,
+    pub drift_magnitude: HistogramVec,
+    pub errors_total: IntCounter,
+}
+
+impl ReconcileMetrics {
+    fn new() -> Self {
+        let registry = prometheus::default_registry();
+
+        let posts_checked_total = IntCounter::new(
+            "social_counter_reconcile_posts_total",
+            "Posts whose counters were compared against PostgreSQL",
+        )
+        .expect("valid metric for social_counter_reconcile_posts_total");
+
+        let drift_total = IntCounterVec::new(
+            Opts::new(
+                "social_counter_drift_total",
+                "Drifted counters found and repaired",
+            ),
+            &["counter", "store"],
+        )
+        .expect("valid metric for social_counter_drift_total");
+
+        let drift_magnitude = HistogramVec::new(
+            HistogramOpts::new(
+                "social_counter_drift_magnitude",
+                "Absolute difference between cached and recounted values",
+            )
+            .buckets(vec![1.0, 2.0, 5.0, 10.0, 50.0, 100.0, 1000.0, 10000.0]),
+            &["counter", "store"],
+        )
+        .expect("valid metric for social_counter_drift_magnitude");
+
+        let errors_total = IntCounter::new(
+            "social_counter_reconcile_errors_total",
+            "Reconciliation batches that failed",
+        )
+        .expect("valid metric for social_counter_reconcile_errors_total");
+
+        for metric in [
+            Box::new(posts_checked_total.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(drift_total.clone()),
+            Box::new(drift_magnitude.clone()),
+            Box::new(errors_total.clone()),
+        ] {
+            let _ = registry.register(metric);
+        }
+
+        Self {
+            posts_checked_total,
+            drift_total,
+            drift_magnitude,
+            errors_total,
+        }
+    }
+
+    /// Process-wide metrics (registered once)
+    pub fn global() -> &'static ReconcileMetrics {
+        static METRICS: OnceLock<ReconcileMetrics> = OnceLock::new();
+        METRICS.get_or_init(ReconcileMetrics::new)
+    }
+
+    fn record(&self, report: &ReconcileReport) {
+        self.posts_checked_total.inc_by(report.posts_checked as u64);
+        for drift in &report.drifts {
+            let labels = [drift.kind.as_str(), drift.store.as_str()];
+            self.drift_total.with_label_values(&labels).inc();
+            self.drift_magnitude
+                .with_label_values(&labels)
+                .observe(drift.delta().unsigned_abs() as f64);
+        }
+    }
+}
+
+/// Recounts post counters from the source tables and repairs drift
+#[derive(Clone)]
+pub struct CounterReconciler {
+    counters: CounterService,
+    pool: PgPool,
+}
+
+impl CounterReconciler {
+    pub fn new(counters: CounterService) -> Self {
+        let pool = counters.pg_pool().clone();
+        Self { counters, pool }
+    }
+
+    pub fn counters(&self) -> &CounterService {
+        &self.counters
+    }
+
+    /// Next page of the rolling sample: posts with counters after `after`,
+    /// in id order (random UUIDs make this an unbiased sample)
+    pub async fn sample_posts(&self, after: Option<Uuid>, limit: i64) -> Result<Vec<Uuid>> {
+        let post_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT post_id FROM post_counters
+            WHERE $1::UUID IS NULL OR post_id > $1
+            ORDER BY post_id
+            LIMIT $2
+            "#,
+        )
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to sample posts for reconciliation")?;
+
+        Ok(post_ids)
+    }
+
+    /// Compare and repair a batch of posts
+    ///
+    /// Redis keys are repaired with a compare-and-set so a write racing the
+    /// recount is not overwritten; the next pass settles it.
+    pub async fn reconcile_posts(&self, post_ids: &[Uuid]) -> Result<ReconcileReport> {
+        self.reconcile(post_ids, false).await
+    }
+
+    /// On-demand recount of one post ("recount post X")
+    ///
+    /// Unlike the periodic pass this also writes missing Redis keys and
+    /// overwrites existing ones unconditionally.
+    pub async fn recount_post(&self, post_id: Uuid) -> Result<ReconcileReport> {
+        self.reconcile(&[post_id], true).await
+    }
+
+    async fn reconcile(&self, post_ids: &[Uuid], force: bool) -> Result<ReconcileReport> {
+        if post_ids.is_empty() {
+            return Ok(ReconcileReport::default());
+        }
+
+        let snapshots = self.load_snapshots(post_ids).await?;
+
+        let keys: Vec<String> = snapshots
+            .iter()
+            .flat_map(|s| CounterKind::CACHED.iter().map(|k| k.redis_key(s.post_id)))
+            .collect();
+        let redis_values = self
+            .counters
+            .read_counters_fresh(&keys)
+            .await
+            .context("Failed to read counters from Redis")?;
+
+        let mut report = ReconcileReport {
+            posts_checked: snapshots.len(),
+            ..Default::default()
+        };
+
+        for (snapshot, redis) in snapshots
+            .iter()
+            .zip(redis_values.chunks(CounterKind::CACHED.len()))
+        {
+            let drifts = detect_drift(snapshot, redis);
+            let actual = snapshot.actual();
+
+            // PostgreSQL first: Redis misses are reloaded from these rows
+            if drifts
+                .iter()
+                .any(|d| d.store == CounterStore::Postgres && d.kind != CounterKind::Bookmarks)
+            {
+                self.repair_post_counters(snapshot).await?;
+            }
+            if drifts.iter().any(|d| d.kind == CounterKind::Bookmarks) {
+                let delta = actual.bookmark_count - snapshot.stored_bookmarks.unwrap_or(0);
+                self.repair_save_count(snapshot.post_id, delta).await?;
+            }
+
+            for (kind, cached) in CounterKind::CACHED.iter().zip(redis) {
+                let expected = kind.value(&actual);
+                let key = kind.redis_key(snapshot.post_id);
+                if force {
+                    self.counters.set_counter(&key, expected).await?;
+                } else if let Some(cached) = cached {
+                    if *cached != expected {
+                        self.counters
+                            .repair_counter(&key, *cached, expected)
+                            .await?;
+                    }
+                }
+            }
+
+            report.drifts.extend(drifts);
+            report.actual.push((snapshot.post_id, actual));
+        }
+
+        ReconcileMetrics::global().record(&report);
+        if !report.drifts.is_empty() {
+            tracing::info!(
+                posts_checked = report.posts_checked,
+                drifted = report.drifts.len(),
+                "Repaired counter drift"
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Stored counters and a recount of the source tables for each post
+    async fn load_snapshots(&self, post_ids: &[Uuid]) -> Result<Vec<CounterSnapshot>> {
+        let snapshots = sqlx::query_as::<_, CounterSnapshot>(
+            r#"
+            SELECT p.post_id,
+                   pc.like_count AS stored_likes,
+                   pc.comment_count AS stored_comments,
+                   pc.share_count AS stored_shares,
+                   pm.save_count AS stored_bookmarks,
+                   (SELECT COUNT(*) FROM likes l WHERE l.post_id = p.post_id) AS actual_likes,
+                   (SELECT COUNT(*) FROM comments c
+                     WHERE c.post_id = p.post_id AND c.is_deleted = FALSE) AS actual_comments,
+                   (SELECT COUNT(*) FROM shares s WHERE s.post_id = p.post_id) AS actual_shares,
+                   (SELECT COUNT(*) FROM saved_posts b WHERE b.post_id = p.post_id) AS actual_bookmarks
+            FROM UNNEST($1::UUID[]) AS p(post_id)
+            LEFT JOIN post_counters pc ON pc.post_id = p.post_id
+            LEFT JOIN post_metadata pm ON pm.post_id = p.post_id
+            "#,
+        )
+        .bind(post_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to recount post counters")?;
+
+        Ok(snapshots)
+    }
+
+    /// Shift stored counters by the drift seen in `snapshot`
+    ///
+    /// Stored and actual values come from one statement, so applying their
+    /// difference keeps trigger updates committed since then instead of
+    /// overwriting them with a stale recount.
+    async fn repair_post_counters(&self, snapshot: &CounterSnapshot) -> Result<()> {
+        let delta = |stored: Option<i64>, actual: i64| actual - stored.unwrap_or(0);
+        sqlx::query(
+            r#"
+            INSERT INTO post_counters (post_id, like_count, comment_count, share_count, updated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (post_id) DO UPDATE
+            SET like_count = post_counters.like_count + EXCLUDED.like_count,
+                comment_count = post_counters.comment_count + EXCLUDED.comment_count,
+                share_count = post_counters.share_count + EXCLUDED.share_count,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(snapshot.post_id)
+        .bind(delta(snapshot.stored_likes, snapshot.actual_likes))
+        .bind(delta(snapshot.stored_comments, snapshot.actual_comments))
+        .bind(delta(snapshot.stored_shares, snapshot.actual_shares))
+        .execute(&self.pool)
+        .await
+        .context("Failed to repair post_counters")?;
+
+        Ok(())
+    }
+
+    /// Shift `post_metadata.save_count` by the drift seen in the snapshot
+    async fn repair_save_count(&self, post_id: Uuid, delta: i64) -> Result<()> {
+        sqlx::query("UPDATE post_metadata SET save_count = save_count + $2 WHERE post_id = $1")
+            .bind(post_id)
+            .bind(delta)
+            .execute(&self.pool)
+            .await
+            .context("Failed to repair post_metadata.save_count")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(stored: [Option<i64>; 4], actual: [i64; 4]) -> CounterSnapshot {
+        CounterSnapshot {
+            post_id: Uuid::new_v4(),
+            stored_likes: stored[0],
+            stored_comments: stored[1],
+            stored_shares: stored[2],
+            stored_bookmarks: stored[3],
+            actual_likes: actual[0],
+            actual_comments: actual[1],
+            actual_shares: actual[2],
+            actual_bookmarks: actual[3],
+        }
+    }
+
+    #[test]
+    fn test_no_drift_when_consistent() {
+        let s = snapshot([Some(3), Some(2), Some(1), Some(4)], [3, 2, 1, 4]);
+        assert!(detect_drift(&s, &[Some(3), Some(2), Some(1)]).is_empty());
+        // Expired Redis keys are not drift
+        assert!(detect_drift(&s, &[None, None, None]).is_empty());
+    }
+
+    #[test]
+    fn test_detects_redis_and_postgres_drift() {
+        // Like INCR landed on an expired key; bookmark trigger missed a delete
+        let s = snapshot([Some(10), Some(2), Some(1), Some(5)], [10, 2, 1, 4]);
+        let drifts = detect_drift(&s, &[Some(1), Some(2), None]);
+
+        assert_eq!(drifts.len(), 2);
+        assert_eq!(drifts[0].kind, CounterKind::Bookmarks);
+        assert_eq!(drifts[0].store, CounterStore::Postgres);
+        assert_eq!(drifts[0].delta(), 1);
+        assert_eq!(drifts[1].kind, CounterKind::Likes);
+        assert_eq!(drifts[1].store, CounterStore::Redis);
+        assert_eq!(drifts[1].delta(), -9);
+    }
+
+    #[test]
+    fn test_missing_rows() {
+        // No post_counters row reads as zero; no post_metadata row is skipped
+        let s = snapshot([None, None, None, None], [1, 0, 0, 2]);
+        let drifts = detect_drift(&s, &[None, None, None]);
+
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].kind, CounterKind::Likes);
+        assert_eq!(drifts[0].cached, 0);
+        assert_eq!(drifts[0].actual, 1);
+    }
+}
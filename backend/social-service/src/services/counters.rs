@@ -1,34 +1,53 @@
+use crate::domain::models::ReactionTarget;
+use crate::services::sharded_counters::{self, ShardMetrics, ShardState, ShardedCounterConfig};
 use anyhow::{Context, Result};
 use redis::{aio::ConnectionManager, AsyncCommands};
 use sqlx::PgPool;
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Redis counter service for fast reads (<10ms latency)
 ///
-/// Keys: post:{post_id}:likes, post:{post_id}:comments, post:{post_id}:shares
+/// Keys: post:{post_id}:likes, post:{post_id}:comments, post:{post_id}:shares,
+/// {post|comment}:{target_id}:reactions (hash: reaction -> count, plus `total`)
 /// TTL: 7 days (604800 seconds)
 ///
 /// Architecture:
 /// - Increment/Decrement: Update Redis after PostgreSQL operation
 /// - Get: Read from Redis with PostgreSQL fallback
 /// - Batch operations: Use Redis MGET for optimization
-/// - Reconciliation: Periodic sync from PostgreSQL to Redis
+/// - Reconciliation: Periodic sync from PostgreSQL to Redis; writes mark posts
+///   in the sharded `counters:hot_posts:{n}` sorted sets so `CounterReconciler` checks
+///   recently active posts first
+/// - Hot keys: likes/comments/shares keys with a high write rate are split
+///   into sub-keys and summed on read (see `sharded_counters`)
 #[derive(Clone)]
 pub struct CounterService {
     redis: ConnectionManager,
     pg_pool: PgPool,
+    sharding: Arc<ShardState>,
 }
 
 #[allow(dead_code)]
 impl CounterService {
     /// TTL for counter keys (7 days) - for set_ex (u64)
-    const COUNTER_TTL_U64: u64 = 604800;
+    pub const COUNTER_TTL_U64: u64 = 604800;
     /// TTL for counter keys (7 days) - for expire (i64)
     const COUNTER_TTL_I64: i64 = 604800;
 
     pub fn new(redis: ConnectionManager, pg_pool: PgPool) -> Self {
-        Self { redis, pg_pool }
+        Self {
+            redis,
+            pg_pool,
+            sharding: Arc::new(ShardState::new(ShardedCounterConfig::default())),
+        }
+    }
+
+    /// Override the hot-key sharding configuration
+    pub fn with_sharding(mut self, config: ShardedCounterConfig) -> Self {
+        self.sharding = Arc::new(ShardState::new(config));
+        self
     }
 
     /// Get reference to Redis connection manager for health checks
@@ -36,6 +55,11 @@ impl CounterService {
         &self.redis
     }
 
+    /// Get reference to the PostgreSQL pool (source of truth for reconciliation)
+    pub fn pg_pool(&self) -> &PgPool {
+        &self.pg_pool
+    }
+
     /// Ping Redis to check connection health
     /// Used by background health check to keep connections alive
     pub async fn ping(&self) -> Result<()> {
@@ -51,40 +75,23 @@ impl CounterService {
     /// Increment like count (called after PostgreSQL insert)
     pub async fn increment_like_count(&self, post_id: Uuid) -> Result<i64> {
         let key = format!("post:{}:likes", post_id);
-        let new_count: i64 = self
-            .redis
-            .clone()
-            .incr(&key, 1)
+        let new_count = self
+            .incr_counter(&key, 1)
             .await
             .context("Failed to increment like count")?;
-
-        // Set TTL on first increment
-        if new_count == 1 {
-            let _: () = self
-                .redis
-                .clone()
-                .expire(&key, Self::COUNTER_TTL_I64)
-                .await
-                .context("Failed to set TTL on like counter")?;
-        }
-
+        self.mark_hot_post(post_id).await;
         Ok(new_count)
     }
 
     /// Decrement like count (called after PostgreSQL delete)
     pub async fn decrement_like_count(&self, post_id: Uuid) -> Result<i64> {
         let key = format!("post:{}:likes", post_id);
-
-        // Ensure counter doesn't go negative
-        let current: i64 = self.redis.clone().get(&key).await.unwrap_or(0);
-
-        if current > 0 {
-            let new_count: i64 = self
-                .redis
-                .clone()
-                .decr(&key, 1)
-                .await
-                .context("Failed to decrement like count")?;
+        let new_count = self
+            .decr_counter(&key)
+            .await
+            .context("Failed to decrement like count")?;
+        if let Some(new_count) = new_count {
+            self.mark_hot_post(post_id).await;
             Ok(new_count)
         } else {
             Ok(0)
@@ -96,10 +103,8 @@ impl CounterService {
         let key = format!("post:{}:likes", post_id);
 
         // Try Redis first
-        let count: Option<i64> = self
-            .redis
-            .clone()
-            .get(&key)
+        let count = self
+            .read_counter(&key)
             .await
             .context("Failed to get like count from Redis")?;
 
@@ -124,38 +129,23 @@ impl CounterService {
     /// Increment comment count
     pub async fn increment_comment_count(&self, post_id: Uuid) -> Result<i64> {
         let key = format!("post:{}:comments", post_id);
-        let new_count: i64 = self
-            .redis
-            .clone()
-            .incr(&key, 1)
+        let new_count = self
+            .incr_counter(&key, 1)
             .await
             .context("Failed to increment comment count")?;
-
-        if new_count == 1 {
-            let _: () = self
-                .redis
-                .clone()
-                .expire(&key, Self::COUNTER_TTL_I64)
-                .await
-                .context("Failed to set TTL on comment counter")?;
-        }
-
+        self.mark_hot_post(post_id).await;
         Ok(new_count)
     }
 
     /// Decrement comment count (soft delete)
     pub async fn decrement_comment_count(&self, post_id: Uuid) -> Result<i64> {
         let key = format!("post:{}:comments", post_id);
-
-        let current: i64 = self.redis.clone().get(&key).await.unwrap_or(0);
-
-        if current > 0 {
-            let new_count: i64 = self
-                .redis
-                .clone()
-                .decr(&key, 1)
-                .await
-                .context("Failed to decrement comment count")?;
+        let new_count = self
+            .decr_counter(&key)
+            .await
+            .context("Failed to decrement comment count")?;
+        if let Some(new_count) = new_count {
+            self.mark_hot_post(post_id).await;
             Ok(new_count)
         } else {
             Ok(0)
@@ -166,10 +156,8 @@ impl CounterService {
     pub async fn get_comment_count(&self, post_id: Uuid) -> Result<i64> {
         let key = format!("post:{}:comments", post_id);
 
-        let count: Option<i64> = self
-            .redis
-            .clone()
-            .get(&key)
+        let count = self
+            .read_counter(&key)
             .await
             .context("Failed to get comment count from Redis")?;
 
@@ -193,22 +181,11 @@ impl CounterService {
     /// Increment share count
     pub async fn increment_share_count(&self, post_id: Uuid) -> Result<i64> {
         let key = format!("post:{}:shares", post_id);
-        let new_count: i64 = self
-            .redis
-            .clone()
-            .incr(&key, 1)
+        let new_count = self
+            .incr_counter(&key, 1)
             .await
             .context("Failed to increment share count")?;
-
-        if new_count == 1 {
-            let _: () = self
-                .redis
-                .clone()
-                .expire(&key, Self::COUNTER_TTL_I64)
-                .await
-                .context("Failed to set TTL on share counter")?;
-        }
-
+        self.mark_hot_post(post_id).await;
         Ok(new_count)
     }
 
@@ -216,10 +193,8 @@ impl CounterService {
     pub async fn get_share_count(&self, post_id: Uuid) -> Result<i64> {
         let key = format!("post:{}:shares", post_id);
 
-        let count: Option<i64> = self
-            .redis
-            .clone()
-            .get(&key)
+        let count = self
+            .read_counter(&key)
             .await
             .context("Failed to get share count from Redis")?;
 
@@ -238,6 +213,434 @@ impl CounterService {
         }
     }
 
+    // ========== Sharded Counter Primitives ==========
+
+    /// Add `delta` to a counter, spreading writes over sub-keys while it is hot
+    ///
+    /// Returns the new value; for sharded keys this is the (locally cached) sum.
+    async fn incr_counter(&self, key: &str, delta: i64) -> redis::RedisResult<i64> {
+        if self.sharding.record_write(key) {
+            self.split_counter(key).await;
+        }
+
+        if let Some(shards) = self.sharding.shard_count(key) {
+            return self.incr_shard(key, shards, delta).await;
+        }
+
+        let (new_count, shards): (i64, Option<u32>) = redis::pipe()
+            .incr(key, delta)
+            .get(sharded_counters::marker_key(key))
+            .query_async(&mut self.redis.clone())
+            .await?;
+        // Set TTL on first increment
+        if new_count == delta {
+            let _: () = self
+                .redis
+                .clone()
+                .expire(key, Self::COUNTER_TTL_I64)
+                .await?;
+        }
+
+        match shards.filter(|n| *n > 0) {
+            // Another instance split the key; the base write still counts
+            Some(shards) => {
+                self.sharding.set_shard_count(key, shards);
+                Ok(self.read_counter(key).await?.unwrap_or(new_count))
+            }
+            None => Ok(new_count),
+        }
+    }
+
+    async fn incr_shard(&self, key: &str, shards: u32, delta: i64) -> redis::RedisResult<i64> {
+        let shard = sharded_counters::shard_key(key, sharded_counters::pick_shard(shards));
+        let sharded: i64 = redis::Script::new(sharded_counters::SHARD_INCR_SCRIPT)
+            .key(key)
+            .key(sharded_counters::marker_key(key))
+            .key(&shard)
+            .arg(delta)
+            .arg(Self::COUNTER_TTL_I64)
+            .invoke_async(&mut self.redis.clone())
+            .await?;
+
+        if sharded == 0 {
+            // Folded since we cached the marker; the write went to the base key
+            self.sharding.forget(key);
+            return Ok(self.read_counter(key).await?.unwrap_or(0));
+        }
+
+        match self.sharding.adjust_cached_total(key, delta) {
+            Some(total) => Ok(total),
+            None => Ok(self.read_counter(key).await?.unwrap_or(0)),
+        }
+    }
+
+    /// Decrement a counter without taking it below zero
+    ///
+    /// Returns `None` when the counter was already zero (or not cached).
+    async fn decr_counter(&self, key: &str) -> redis::RedisResult<Option<i64>> {
+        let current = self.read_counter(key).await?.unwrap_or(0);
+        if current <= 0 {
+            return Ok(None);
+        }
+
+        if self.sharding.record_write(key) {
+            self.split_counter(key).await;
+        }
+
+        match self.sharding.shard_count(key) {
+            Some(shards) => self.incr_shard(key, shards, -1).await.map(Some),
+            None => self.redis.clone().decr(key, 1).await.map(Some),
+        }
+    }
+
+    /// Read one counter (summing shards)
+    pub async fn read_counter(&self, key: &str) -> redis::RedisResult<Option<i64>> {
+        Ok(self
+            .read_counters(&[key.to_string()])
+            .await?
+            .pop()
+            .flatten())
+    }
+
+    /// Read counters in order (`None` = not cached), summing sharded keys
+    ///
+    /// One MGET for the base keys and their shard markers; sharded keys not in
+    /// the local read cache cost a second MGET for their sub-keys.
+    pub async fn read_counters(&self, keys: &[String]) -> redis::RedisResult<Vec<Option<i64>>> {
+        self.read_counters_with(keys, true).await
+    }
+
+    /// Like `read_counters`, bypassing the local read cache (for repairs)
+    pub async fn read_counters_fresh(
+        &self,
+        keys: &[String],
+    ) -> redis::RedisResult<Vec<Option<i64>>> {
+        self.read_counters_with(keys, false).await
+    }
+
+    async fn read_counters_with(
+        &self,
+        keys: &[String],
+        use_cache: bool,
+    ) -> redis::RedisResult<Vec<Option<i64>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut lookup: Vec<String> = keys.to_vec();
+        lookup.extend(keys.iter().map(|k| sharded_counters::marker_key(k)));
+        let values: Vec<Option<i64>> = self.redis.clone().mget(&lookup).await?;
+        let (base, markers) = values.split_at(keys.len());
+
+        let mut totals = base.to_vec();
+        let mut pending = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            let shards = markers[i].filter(|n| *n > 0).unwrap_or(0) as u32;
+            self.sharding.set_shard_count(key, shards);
+            if shards == 0 {
+                continue;
+            }
+            match self.sharding.cached_total(key).filter(|_| use_cache) {
+                Some(total) => totals[i] = Some(total),
+                None => pending.push((i, shards)),
+            }
+        }
+
+        if pending.is_empty() {
+            return Ok(totals);
+        }
+
+        // Per pending key: its shard keys, then its hot flag
+        let lookup: Vec<String> = pending
+            .iter()
+            .flat_map(|(i, shards)| {
+                let mut keys_for = sharded_counters::shard_keys(&keys[*i], *shards);
+                keys_for.push(sharded_counters::hot_key(&keys[*i]));
+                keys_for
+            })
+            .collect();
+        let values: Vec<Option<i64>> = self.redis.clone().mget(&lookup).await?;
+
+        let mut offset = 0;
+        for (i, shards) in pending {
+            let shard_values = &values[offset..offset + shards as usize];
+            let hot = values[offset + shards as usize].is_some();
+            offset += shards as usize + 1;
+
+            let total = sharded_counters::sum_shards(base[i], shard_values);
+            totals[i] = Some(total);
+            if hot {
+                self.sharding.cache_total(&keys[i], total);
+            } else {
+                self.fold_counter(&keys[i], shards).await;
+            }
+        }
+
+        Ok(totals)
+    }
+
+    /// Split a hot counter into sub-keys (refreshes the hot flag if already split)
+    async fn split_counter(&self, key: &str) {
+        let shards = self.sharding.config.shards;
+        let script = redis::Script::new(sharded_counters::SPLIT_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        invocation
+            .key(key)
+            .key(sharded_counters::marker_key(key))
+            .key(sharded_counters::hot_key(key))
+            .key(sharded_counters::shard_keys(key, shards))
+            .arg(shards)
+            .arg(Self::COUNTER_TTL_U64)
+            .arg(self.sharding.config.cooldown.as_secs().max(1));
+
+        match invocation
+            .invoke_async::<_, i64>(&mut self.redis.clone())
+            .await
+        {
+            Ok(split) => {
+                if split == 1 {
+                    ShardMetrics::global().splits_total.inc();
+                    tracing::info!(key = %key, shards, "Split hot counter");
+                }
+                self.sharding.set_shard_count(key, shards);
+            }
+            Err(e) => tracing::warn!(key = %key, error = ?e, "Failed to split hot counter"),
+        }
+    }
+
+    /// Collapse a cooled-down counter back into its base key (best effort)
+    async fn fold_counter(&self, key: &str, shards: u32) {
+        let script = redis::Script::new(sharded_counters::FOLD_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        invocation
+            .key(key)
+            .key(sharded_counters::marker_key(key))
+            .key(sharded_counters::hot_key(key))
+            .key(sharded_counters::shard_keys(key, shards))
+            .arg(Self::COUNTER_TTL_U64);
+
+        match invocation
+            .invoke_async::<_, i64>(&mut self.redis.clone())
+            .await
+        {
+            Ok(folded) => {
+                if folded == 1 {
+                    ShardMetrics::global().folds_total.inc();
+                    tracing::debug!(key = %key, shards, "Folded cooled-down counter");
+                    self.sharding.forget(key);
+                }
+            }
+            Err(e) => tracing::warn!(key = %key, error = ?e, "Failed to fold sharded counter"),
+        }
+    }
+
+    /// Queue an overwrite of a counter, dropping any shards (use an atomic pipe)
+    fn queue_set_counter(&self, pipe: &mut redis::Pipeline, key: &str, value: i64) {
+        pipe.cmd("EVAL")
+            .arg(sharded_counters::SET_SCRIPT)
+            .arg(2)
+            .arg(key)
+            .arg(sharded_counters::marker_key(key))
+            .arg(value)
+            .arg(Self::COUNTER_TTL_U64)
+            .ignore();
+        self.sharding.forget(key);
+    }
+
+    /// Overwrite a counter with a value from the source of truth
+    pub async fn set_counter(&self, key: &str, value: i64) -> Result<()> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        self.queue_set_counter(&mut pipe, key, value);
+        pipe.query_async::<_, ()>(&mut self.redis.clone())
+            .await
+            .context("Failed to set counter in Redis")?;
+        Ok(())
+    }
+
+    /// Repair a counter read as `observed` to `actual`
+    ///
+    /// Unsharded keys are compare-and-set (a racing write wins and is checked
+    /// again next pass); sharded keys are shifted by `actual - observed`.
+    pub async fn repair_counter(&self, key: &str, observed: i64, actual: i64) -> Result<()> {
+        let _: i64 = redis::Script::new(sharded_counters::REPAIR_SCRIPT)
+            .key(key)
+            .key(sharded_counters::marker_key(key))
+            .arg(observed)
+            .arg(actual)
+            .arg(Self::COUNTER_TTL_U64)
+            .invoke_async(&mut self.redis.clone())
+            .await
+            .context("Failed to repair counter in Redis")?;
+        self.sharding.forget(key);
+        Ok(())
+    }
+
+    // ========== Reaction Counter Operations ==========
+
+    fn reaction_key(target: ReactionTarget, target_id: Uuid) -> String {
+        format!("{}:{}:reactions", target.as_str(), target_id)
+    }
+
+    /// Apply a reaction add/switch/remove to the cached hash (called after PostgreSQL write)
+    ///
+    /// Only touches hashes that are already cached; a missing hash is loaded
+    /// from PostgreSQL on the next read, so a partial hash is never created.
+    pub async fn apply_reaction_change(
+        &self,
+        target: ReactionTarget,
+        target_id: Uuid,
+        previous: Option<&str>,
+        current: Option<&str>,
+    ) -> Result<()> {
+        if previous == current {
+            return Ok(());
+        }
+
+        let script = redis::Script::new(
+            r#"
+            if redis.call('EXISTS', KEYS[1]) == 0 then
+                return 0
+            end
+            if ARGV[1] ~= '' then
+                if redis.call('HINCRBY', KEYS[1], ARGV[1], -1) <= 0 then
+                    redis.call('HDEL', KEYS[1], ARGV[1])
+                end
+            end
+            if ARGV[2] ~= '' then
+                redis.call('HINCRBY', KEYS[1], ARGV[2], 1)
+            end
+            local total = tonumber(ARGV[3])
+            if redis.call('HINCRBY', KEYS[1], 'total', total) < 0 then
+                redis.call('HSET', KEYS[1], 'total', 0)
+            end
+            return 1
+            "#,
+        );
+        let total_delta = current.is_some() as i64 - previous.is_some() as i64;
+
+        let _: i64 = script
+            .key(Self::reaction_key(target, target_id))
+            .arg(previous.unwrap_or(""))
+            .arg(current.unwrap_or(""))
+            .arg(total_delta)
+            .invoke_async(&mut self.redis.clone())
+            .await
+            .context("Failed to update reaction counters")?;
+
+        Ok(())
+    }
+
+    /// Get per-reaction counts for a target (with PostgreSQL fallback)
+    pub async fn get_reaction_counts(
+        &self,
+        target: ReactionTarget,
+        target_id: Uuid,
+    ) -> Result<ReactionCounts> {
+        Ok(self
+            .batch_get_reaction_counts(target, &[target_id])
+            .await?
+            .remove(&target_id)
+            .unwrap_or_default())
+    }
+
+    /// Batch get per-reaction counts (Redis pipeline, PostgreSQL for misses)
+    pub async fn batch_get_reaction_counts(
+        &self,
+        target: ReactionTarget,
+        target_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, ReactionCounts>> {
+        if target_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for target_id in target_ids {
+            pipe.hgetall(Self::reaction_key(target, *target_id));
+        }
+        let redis_result: std::result::Result<Vec<HashMap<String, i64>>, _> =
+            pipe.query_async(&mut self.redis.clone()).await;
+
+        let hashes = match redis_result {
+            Ok(hashes) => hashes,
+            Err(redis_err) => {
+                tracing::warn!(
+                    error = ?redis_err,
+                    target_count = target_ids.len(),
+                    "Redis reaction counter read failed, falling back to PostgreSQL"
+                );
+                return self.load_reaction_counts_from_pg(target, target_ids).await;
+            }
+        };
+
+        let mut result = HashMap::new();
+        let mut missing = Vec::new();
+        for (target_id, hash) in target_ids.iter().zip(hashes) {
+            // Cached hashes always carry `total`, even when it is 0
+            if hash.is_empty() {
+                missing.push(*target_id);
+            } else {
+                result.insert(*target_id, ReactionCounts::from_hash(hash));
+            }
+        }
+
+        if !missing.is_empty() {
+            let loaded = self.load_reaction_counts_from_pg(target, &missing).await?;
+            if let Err(err) = self.warm_reaction_counts(target, &loaded).await {
+                tracing::warn!(
+                    error = ?err,
+                    target_count = missing.len(),
+                    "Failed to warm reaction counters"
+                );
+            }
+            result.extend(loaded);
+        }
+
+        Ok(result)
+    }
+
+    /// Write reaction hashes to Redis (replacing what is cached)
+    async fn warm_reaction_counts(
+        &self,
+        target: ReactionTarget,
+        counts: &HashMap<Uuid, ReactionCounts>,
+    ) -> Result<()> {
+        let mut pipe = redis::pipe();
+        for (target_id, counts) in counts {
+            let key = Self::reaction_key(target, *target_id);
+            pipe.del(&key).ignore();
+            pipe.hset_multiple(&key, &counts.to_hash_fields()).ignore();
+            pipe.expire(&key, Self::COUNTER_TTL_I64).ignore();
+        }
+
+        pipe.query_async::<_, ()>(&mut self.redis.clone())
+            .await
+            .context("Failed to warm reaction counters in Redis")?;
+
+        Ok(())
+    }
+
+    /// Refresh reaction counts from PostgreSQL and update Redis cache
+    pub async fn refresh_reaction_counts(
+        &self,
+        target: ReactionTarget,
+        target_id: Uuid,
+    ) -> Result<ReactionCounts> {
+        let mut loaded = self
+            .load_reaction_counts_from_pg(target, &[target_id])
+            .await?;
+
+        if let Err(e) = self.warm_reaction_counts(target, &loaded).await {
+            tracing::warn!(
+                target_id = %target_id,
+                error = ?e,
+                "Failed to update Redis cache for reaction counts"
+            );
+        }
+
+        Ok(loaded.remove(&target_id).unwrap_or_default())
+    }
+
     // ========== Batch Operations (MGET Optimization) ==========
 
     /// Batch get all counts for multiple posts (optimized with Redis MGET)
@@ -255,9 +658,8 @@ impl CounterService {
             keys.push(format!("post:{}:shares", post_id));
         }
 
-        // Try Redis first, fallback to PostgreSQL on error
-        let redis_result: std::result::Result<Vec<Option<i64>>, _> =
-            self.redis.clone().get(&keys).await;
+        // Try Redis first (sharded keys are summed), fallback to PostgreSQL on error
+        let redis_result = self.read_counters(&keys).await;
 
         let result = match redis_result {
             Ok(values) => {
@@ -363,25 +765,16 @@ impl CounterService {
 
         // Warm Redis cache using pipeline
         let mut pipe = redis::pipe();
+        pipe.atomic();
         for (post_id, counts) in pg_counters {
-            pipe.set_ex(
-                format!("post:{}:likes", post_id),
-                counts.like_count,
-                Self::COUNTER_TTL_U64,
-            )
-            .ignore();
-            pipe.set_ex(
-                format!("post:{}:comments", post_id),
-                counts.comment_count,
-                Self::COUNTER_TTL_U64,
-            )
-            .ignore();
-            pipe.set_ex(
-                format!("post:{}:shares", post_id),
-                counts.share_count,
-                Self::COUNTER_TTL_U64,
-            )
-            .ignore();
+            for (kind, value) in [
+                ("likes", counts.like_count),
+                ("comments", counts.comment_count),
+                ("shares", counts.share_count),
+            ] {
+                let key = format!("post:{}:{}", post_id, kind);
+                self.queue_set_counter(&mut pipe, &key, value);
+            }
         }
 
         pipe.query_async::<_, ()>(&mut self.redis.clone())
@@ -457,8 +850,93 @@ impl CounterService {
         Ok(result)
     }
 
+    /// Batch load reaction counts from PostgreSQL
+    ///
+    /// Every requested target gets an entry, zero counts included, so the
+    /// result can be cached as-is.
+    async fn load_reaction_counts_from_pg(
+        &self,
+        target: ReactionTarget,
+        target_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, ReactionCounts>> {
+        let rows = sqlx::query_as::<_, (Uuid, String, i64)>(
+            "SELECT target_id, reaction, count
+             FROM reaction_counters
+             WHERE target_type = $1 AND target_id = ANY($2) AND count > 0",
+        )
+        .bind(target.as_str())
+        .bind(target_ids)
+        .fetch_all(&self.pg_pool)
+        .await
+        .context("Failed to load reaction counts from PostgreSQL")?;
+
+        let mut result: HashMap<Uuid, ReactionCounts> = target_ids
+            .iter()
+            .map(|id| (*id, ReactionCounts::default()))
+            .collect();
+        for (target_id, reaction, count) in rows {
+            let counts = result.entry(target_id).or_default();
+            counts.total += count;
+            counts.counts.insert(reaction, count);
+        }
+
+        Ok(result)
+    }
+
     // ========== Reconciliation (Cron Job) ==========
 
+    /// Number of sorted sets the hot-post marks are spread across, so the
+    /// per-write ZADD does not concentrate on a single key
+    const HOT_POSTS_SHARDS: u128 = 16;
+
+    /// Sorted set of recently written posts (score: unix seconds of last write)
+    fn hot_posts_key(shard: u128) -> String {
+        format!("counters:hot_posts:{}", shard)
+    }
+
+    /// Record a counter write so the reconciler checks this post soon
+    ///
+    /// Best effort: a missed mark only delays repair until the rolling sample
+    /// reaches the post.
+    pub async fn mark_hot_post(&self, post_id: Uuid) {
+        let key = Self::hot_posts_key(post_id.as_u128() % Self::HOT_POSTS_SHARDS);
+        let result: redis::RedisResult<()> = self
+            .redis
+            .clone()
+            .zadd(key, post_id.to_string(), chrono::Utc::now().timestamp())
+            .await;
+        if let Err(e) = result {
+            tracing::debug!(post_id = %post_id, error = ?e, "Failed to mark hot post");
+        }
+    }
+
+    /// Pop the most recently written posts from every shard
+    ///
+    /// Takes roughly `limit` posts split evenly across shards (at least one
+    /// per shard); only the returned entries are removed, so a backlog larger
+    /// than `limit` stays queued for the next pass.
+    pub async fn take_hot_posts(&self, limit: usize) -> Result<Vec<Uuid>> {
+        let shards = Self::HOT_POSTS_SHARDS as usize;
+        let per_shard = limit.div_ceil(shards).max(1);
+
+        let mut pipe = redis::pipe();
+        for shard in 0..Self::HOT_POSTS_SHARDS {
+            pipe.cmd("ZPOPMAX")
+                .arg(Self::hot_posts_key(shard))
+                .arg(per_shard);
+        }
+        let popped: Vec<Vec<(String, f64)>> = pipe
+            .query_async(&mut self.redis.clone())
+            .await
+            .context("Failed to pop hot posts")?;
+
+        Ok(popped
+            .iter()
+            .flatten()
+            .filter_map(|(id, _)| Uuid::parse_str(id).ok())
+            .collect())
+    }
+
     /// Reconciliation: sync PostgreSQL counters to Redis (cron job)
     ///
     /// This should be called periodically to ensure Redis cache consistency
@@ -471,25 +949,16 @@ impl CounterService {
         let pg_counters = self.load_counters_from_pg(post_ids).await?;
 
         let mut pipe = redis::pipe();
+        pipe.atomic();
         for (post_id, counts) in &pg_counters {
-            pipe.set_ex(
-                format!("post:{}:likes", post_id),
-                counts.like_count,
-                Self::COUNTER_TTL_U64,
-            )
-            .ignore();
-            pipe.set_ex(
-                format!("post:{}:comments", post_id),
-                counts.comment_count,
-                Self::COUNTER_TTL_U64,
-            )
-            .ignore();
-            pipe.set_ex(
-                format!("post:{}:shares", post_id),
-                counts.share_count,
-                Self::COUNTER_TTL_U64,
-            )
-            .ignore();
+            for (kind, value) in [
+                ("likes", counts.like_count),
+                ("comments", counts.comment_count),
+                ("shares", counts.share_count),
+            ] {
+                let key = format!("post:{}:{}", post_id, kind);
+                self.queue_set_counter(&mut pipe, &key, value);
+            }
         }
 
         pipe.query_async::<_, ()>(&mut self.redis.clone())
@@ -509,37 +978,25 @@ impl CounterService {
     /// Set like count for a post (used for cache warming)
     pub async fn set_like_count(&self, post_id: Uuid, count: i64) -> Result<()> {
         let key = format!("post:{}:likes", post_id);
-        let _: () = self
-            .redis
-            .clone()
-            .set_ex(&key, count, Self::COUNTER_TTL_U64)
+        self.set_counter(&key, count)
             .await
-            .context("Failed to set like count")?;
-        Ok(())
+            .context("Failed to set like count")
     }
 
     /// Set comment count for a post (used for cache warming)
     pub async fn set_comment_count(&self, post_id: Uuid, count: i64) -> Result<()> {
         let key = format!("post:{}:comments", post_id);
-        let _: () = self
-            .redis
-            .clone()
-            .set_ex(&key, count, Self::COUNTER_TTL_U64)
+        self.set_counter(&key, count)
             .await
-            .context("Failed to set comment count")?;
-        Ok(())
+            .context("Failed to set comment count")
     }
 
     /// Set share count for a post (used for cache warming)
     pub async fn set_share_count(&self, post_id: Uuid, count: i64) -> Result<()> {
         let key = format!("post:{}:shares", post_id);
-        let _: () = self
-            .redis
-            .clone()
-            .set_ex(&key, count, Self::COUNTER_TTL_U64)
+        self.set_counter(&key, count)
             .await
-            .context("Failed to set share count")?;
-        Ok(())
+            .context("Failed to set share count")
     }
 
     /// Rate-limit key TTL for hot post refresh (5 seconds)
@@ -656,3 +1113,72 @@ pub struct PostCounts {
     pub share_count: i64,
     pub bookmark_count: i64,
 }
+
+/// Per-reaction counts of one post or comment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReactionCounts {
+    /// reaction name -> count (zero counts omitted)
+    pub counts: HashMap<String, i64>,
+    pub total: i64,
+}
+
+impl ReactionCounts {
+    /// Field cached alongside the reactions so empty targets still hit the cache
+    const TOTAL_FIELD: &'static str = "total";
+
+    fn from_hash(mut hash: HashMap<String, i64>) -> Self {
+        let total = hash.remove(Self::TOTAL_FIELD).unwrap_or(0).max(0);
+        hash.retain(|_, count| *count > 0);
+        Self {
+            counts: hash,
+            total,
+        }
+    }
+
+    fn to_hash_fields(&self) -> Vec<(String, i64)> {
+        let mut fields: Vec<(String, i64)> = self
+            .counts
+            .iter()
+            .map(|(reaction, count)| (reaction.clone(), *count))
+            .collect();
+        fields.push((Self::TOTAL_FIELD.to_string(), self.total));
+        fields
+    }
+
+    /// Reactions ordered by count (descending), ties by name
+    pub fn ranked(&self) -> Vec<(&str, i64)> {
+        let mut ranked: Vec<(&str, i64)> = self
+            .counts
+            .iter()
+            .map(|(reaction, count)| (reaction.as_str(), *count))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reaction_counts_hash_round_trip() {
+        let counts = ReactionCounts::from_hash(HashMap::from([
+            ("love".to_string(), 3),
+            ("haha".to_string(), 0),
+            ("wow".to_string(), 3),
+            ("like".to_string(), 5),
+            ("total".to_string(), 11),
+        ]));
+
+        assert_eq!(counts.total, 11);
+        assert_eq!(counts.ranked(), vec![("like", 5), ("love", 3), ("wow", 3)]);
+
+        let fields: HashMap<String, i64> = counts.to_hash_fields().into_iter().collect();
+        assert_eq!(ReactionCounts::from_hash(fields), counts);
+        assert_eq!(
+            ReactionCounts::from_hash(HashMap::from([("total".to_string(), 0)])),
+            ReactionCounts::default()
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use grpc_clients::nova::graph_service::v2::{
-    graph_service_client::GraphServiceClient, GetFollowersRequest, GetFollowingRequest,
-    IsFollowingRequest,
+    graph_service_client::GraphServiceClient, GetAccountPrivacyRequest, GetFollowersRequest,
+    GetFollowingRequest, IsFollowingRequest,
 };
 use tonic::transport::Channel;
 use uuid::Uuid;
@@ -128,4 +128,15 @@ impl FollowService {
             }
         }
     }
+
+    /// Whether the user is private (follows become requests) via graph-service gRPC
+    pub async fn is_account_private(&self, user_id: Uuid) -> anyhow::Result<bool> {
+        let mut client = self.graph_client.clone();
+        let request = tonic::Request::new(GetAccountPrivacyRequest {
+            user_id: user_id.to_string(),
+        });
+
+        let response = client.get_account_privacy(request).await?;
+        Ok(response.into_inner().is_private)
+    }
 }
@@ -2,6 +2,9 @@
 //!
 //! Publishes like/unlike events for downstream consumers (analytics, notifications, feed ranking)
 
+use super::ReactionCounts;
+use crate::config::ReactionConfig;
+use crate::domain::models::ReactionTarget;
 use anyhow::Result;
 use chrono::Utc;
 use event_schema::{EventEnvelope, LikeCreatedEvent, LikeDeletedEvent};
@@ -206,6 +209,7 @@ impl SocialEventProducer {
             body: format!("{} liked your post", username),
             data: Some(serde_json::json!({
                 "sender_id": liker_id.to_string(),
+                "sender_username": username,
                 "object_id": post_id.to_string(),
                 "object_type": "post",
                 "like_id": like_id.to_string(),
@@ -276,6 +280,7 @@ impl SocialEventProducer {
             body: format!("{} started following you", username),
             data: Some(serde_json::json!({
                 "sender_id": follower_id.to_string(),
+                "sender_username": username,
                 "object_id": follower_id.to_string(),
                 "object_type": "user",
             })),
@@ -370,6 +375,7 @@ impl SocialEventProducer {
             body,
             data: Some(serde_json::json!({
                 "sender_id": commenter_id.to_string(),
+                "sender_username": username,
                 "object_id": post_id.to_string(),
                 "object_type": "post",
                 "comment_id": comment_id.to_string(),
@@ -450,6 +456,7 @@ impl SocialEventProducer {
             body: format!("{} liked your comment", username),
             data: Some(serde_json::json!({
                 "sender_id": liker_id.to_string(),
+                "sender_username": username,
                 "object_id": comment_id.to_string(),
                 "object_type": "comment",
                 "like_id": like_id.to_string(),
@@ -491,6 +498,110 @@ impl SocialEventProducer {
         }
     }
 
+    /// Publish a reaction notification event to notification-service
+    ///
+    /// This sends a KafkaNotification to the ReactionAdded topic. The body
+    /// aggregates every reaction type on the target (e.g. "Alice reacted ❤️
+    /// to your post · ❤️ 3 😂 2") and `data.aggregation_key` identifies the
+    /// target so the notification-service can collapse repeated reactions.
+    ///
+    /// # Arguments
+    /// * `reaction_id` - The ID of the reaction
+    /// * `target` / `target_id` - The reacted post or comment
+    /// * `reactor_id` - The ID of the user who reacted
+    /// * `recipient_id` - The author of the target (notification recipient)
+    /// * `reaction` - Reaction name from the configured set
+    /// * `counts` - Reaction counts on the target after this reaction
+    #[allow(clippy::too_many_arguments)]
+    pub async fn publish_reaction_notification(
+        &self,
+        reaction_id: Uuid,
+        target: ReactionTarget,
+        target_id: Uuid,
+        reactor_id: Uuid,
+        recipient_id: Uuid,
+        reaction: &str,
+        counts: &ReactionCounts,
+        reaction_config: &ReactionConfig,
+        reactor_username: Option<String>,
+    ) -> Result<()> {
+        // Don't send notification if user reacted to their own content
+        if reactor_id == recipient_id {
+            info!(
+                reactor_id = %reactor_id,
+                target_id = %target_id,
+                "Skipping self-reaction notification"
+            );
+            return Ok(());
+        }
+
+        let username = reactor_username.unwrap_or_else(|| "Someone".to_string());
+        let reaction_counts: serde_json::Map<String, serde_json::Value> = counts
+            .counts
+            .iter()
+            .map(|(name, count)| (name.clone(), serde_json::Value::from(*count)))
+            .collect();
+
+        let notification = KafkaNotification {
+            id: reaction_id.to_string(),
+            user_id: recipient_id, // Recipient of the notification
+            event_type: "Reaction".to_string(),
+            title: "New Reaction".to_string(),
+            body: reaction_notification_body(
+                &username,
+                target,
+                reaction_config.emoji(reaction),
+                counts,
+                reaction_config,
+            ),
+            data: Some(serde_json::json!({
+                "sender_id": reactor_id.to_string(),
+                "sender_username": username,
+                "object_id": target_id.to_string(),
+                "object_type": target.as_str(),
+                "reaction_id": reaction_id.to_string(),
+                "reaction": reaction,
+                "reaction_counts": reaction_counts,
+                "reaction_total": counts.total,
+                "aggregation_key": format!("reaction:{}:{}", target.as_str(), target_id),
+            })),
+            timestamp: Utc::now().timestamp(),
+        };
+
+        let payload = serde_json::to_string(&notification)?;
+        let partition_key = recipient_id.to_string();
+
+        // Use "ReactionAdded" topic for reaction notifications
+        let reaction_topic = "ReactionAdded";
+
+        let record = FutureRecord::to(reaction_topic)
+            .key(&partition_key)
+            .payload(&payload);
+
+        match self.producer.send(record, Duration::from_secs(5)).await {
+            Ok(_) => {
+                info!(
+                    reaction_id = %reaction_id,
+                    target_id = %target_id,
+                    reactor_id = %reactor_id,
+                    recipient_id = %recipient_id,
+                    topic = %reaction_topic,
+                    "Published reaction notification to Kafka"
+                );
+                Ok(())
+            }
+            Err((err, _)) => {
+                warn!(
+                    error = ?err,
+                    reaction_id = %reaction_id,
+                    target_id = %target_id,
+                    "Failed to publish reaction notification to Kafka"
+                );
+                Err(anyhow::anyhow!("Failed to publish notification: {}", err))
+            }
+        }
+    }
+
     /// Publish a share notification event to notification-service
     ///
     /// This sends a KafkaNotification to the PostShared topic that the
@@ -530,6 +641,7 @@ impl SocialEventProducer {
             body: format!("{} shared your post", username),
             data: Some(serde_json::json!({
                 "sender_id": sharer_id.to_string(),
+                "sender_username": username,
                 "object_id": post_id.to_string(),
                 "object_type": "post",
                 "share_id": share_id.to_string(),
@@ -638,6 +750,7 @@ impl SocialEventProducer {
                 body,
                 data: Some(serde_json::json!({
                     "sender_id": author_id.to_string(),
+                    "sender_username": username,
                     "object_id": source_id.to_string(),
                     "object_type": source_type,
                 })),
@@ -676,3 +789,61 @@ impl SocialEventProducer {
         Ok(())
     }
 }
+
+/// Notification text for a reaction, summarizing all reaction types on the target
+///
+/// Shows up to three reaction types by count; the summary is omitted when
+/// this is the only reaction.
+fn reaction_notification_body(
+    username: &str,
+    target: ReactionTarget,
+    emoji: &str,
+    counts: &ReactionCounts,
+    reaction_config: &ReactionConfig,
+) -> String {
+    let mut body = format!("{} reacted {} to your {}", username, emoji, target.as_str());
+    if counts.total > 1 {
+        let summary: Vec<String> = counts
+            .ranked()
+            .into_iter()
+            .take(3)
+            .map(|(name, count)| format!("{} {}", reaction_config.emoji(name), count))
+            .collect();
+        body.push_str(" · ");
+        body.push_str(&summary.join(" "));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_reaction_notification_body_aggregates_types() {
+        let config = ReactionConfig::default();
+        let single = ReactionCounts {
+            counts: HashMap::from([("love".to_string(), 1)]),
+            total: 1,
+        };
+        assert_eq!(
+            reaction_notification_body("alice", ReactionTarget::Post, "❤️", &single, &config),
+            "alice reacted ❤️ to your post"
+        );
+
+        let many = ReactionCounts {
+            counts: HashMap::from([
+                ("love".to_string(), 3),
+                ("haha".to_string(), 2),
+                ("wow".to_string(), 1),
+                ("sad".to_string(), 1),
+            ]),
+            total: 7,
+        };
+        assert_eq!(
+            reaction_notification_body("bob", ReactionTarget::Comment, "😂", &many, &config),
+            "bob reacted 😂 to your comment · ❤️ 3 😂 2 😢 1"
+        );
+    }
+}
@@ -1,10 +1,15 @@
+pub mod counter_reconciler;
 pub mod counters;
 pub mod follow;
 pub mod kafka_events;
 pub mod mention_parser;
+pub mod polls;
+pub mod sharded_counters;
 
 #[allow(unused_imports)]
-pub use counters::{CounterService, PostCounts};
+pub use counter_reconciler::CounterReconciler;
+#[allow(unused_imports)]
+pub use counters::{CounterService, PostCounts, ReactionCounts};
 pub use follow::FollowService;
 pub use kafka_events::{KafkaEventProducerConfig, SocialEventProducer};
 pub use mention_parser::extract_mentions;
@@ -0,0 +1,673 @@
+//! Poll modes: ballot validation, scheduling and instant-runoff tallying
+//!
+//! Storage is mode-agnostic (one `poll_votes` row per choice, ordered by
+//! `choice_rank`); the rules for each `poll_type` live here.
+
+use crate::domain::models::{CandidateWithRank, Poll, PollCandidate};
+use crate::repository::PollRepository;
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Voting mode, stored in `polls.poll_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollMode {
+    /// One choice per voter
+    SingleChoice,
+    /// Up to `max_choices` choices per voter, each counted once
+    MultipleChoice,
+    /// Leaderboard voting: one choice per voter (the original poll type)
+    Ranking,
+    /// Voters rank up to `max_choices` candidates; tallied by instant runoff
+    RankedChoice,
+}
+
+impl PollMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "single_choice" => Some(PollMode::SingleChoice),
+            "multiple_choice" => Some(PollMode::MultipleChoice),
+            "ranking" => Some(PollMode::Ranking),
+            "ranked_choice" => Some(PollMode::RankedChoice),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PollMode::SingleChoice => "single_choice",
+            PollMode::MultipleChoice => "multiple_choice",
+            PollMode::Ranking => "ranking",
+            PollMode::RankedChoice => "ranked_choice",
+        }
+    }
+
+    /// Whether a ballot may hold more than one choice
+    pub fn allows_multiple(&self) -> bool {
+        matches!(self, PollMode::MultipleChoice | PollMode::RankedChoice)
+    }
+}
+
+/// Where a poll is in its schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollPhase {
+    /// Draft, or `starts_at` not reached yet
+    Scheduled,
+    Open,
+    /// Closed manually, archived, or past `ends_at`
+    Closed,
+}
+
+/// Schedule phase of a poll at `now`
+pub fn poll_phase(poll: &Poll, now: DateTime<Utc>) -> PollPhase {
+    match poll.status.as_str() {
+        "closed" | "archived" => return PollPhase::Closed,
+        "draft" => return PollPhase::Scheduled,
+        _ => {}
+    }
+    if poll.ends_at.map(|ends_at| ends_at <= now).unwrap_or(false) {
+        PollPhase::Closed
+    } else if poll
+        .starts_at
+        .map(|starts_at| starts_at > now)
+        .unwrap_or(false)
+    {
+        PollPhase::Scheduled
+    } else {
+        PollPhase::Open
+    }
+}
+
+/// Status reported to clients: the stored status adjusted for the schedule
+pub fn effective_status(poll: &Poll, now: DateTime<Utc>) -> String {
+    match (poll.status.as_str(), poll_phase(poll, now)) {
+        ("active", PollPhase::Scheduled) => "scheduled".to_string(),
+        ("active", PollPhase::Closed) => "closed".to_string(),
+        (status, _) => status.to_string(),
+    }
+}
+
+/// Whether vote counts may be shown to `viewer_id`
+///
+/// Hidden-results polls reveal counts to the creator only until they close.
+pub fn results_visible(poll: &Poll, viewer_id: Option<Uuid>, now: DateTime<Utc>) -> bool {
+    !poll.hide_results_until_close
+        || viewer_id == Some(poll.creator_id)
+        || poll_phase(poll, now) == PollPhase::Closed
+}
+
+/// Why a ballot was rejected
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BallotError {
+    #[error("Poll has not opened yet")]
+    NotOpenYet,
+
+    #[error("Poll is closed")]
+    Closed,
+
+    #[error("At least one candidate is required")]
+    Empty,
+
+    #[error("This poll accepts a single choice")]
+    SingleChoiceOnly,
+
+    #[error("At most {0} choices allowed")]
+    TooManyChoices(i32),
+
+    #[error("Each candidate may be chosen only once")]
+    DuplicateChoice,
+}
+
+impl From<BallotError> for tonic::Status {
+    fn from(err: BallotError) -> Self {
+        match err {
+            BallotError::NotOpenYet | BallotError::Closed => {
+                tonic::Status::failed_precondition(err.to_string())
+            }
+            _ => tonic::Status::invalid_argument(err.to_string()),
+        }
+    }
+}
+
+/// Check that the poll accepts votes at `now`
+pub fn ensure_open(poll: &Poll, now: DateTime<Utc>) -> Result<(), BallotError> {
+    match poll_phase(poll, now) {
+        PollPhase::Open => Ok(()),
+        PollPhase::Scheduled => Err(BallotError::NotOpenYet),
+        PollPhase::Closed => Err(BallotError::Closed),
+    }
+}
+
+/// Validate a ballot (choices in preference order) against the poll's mode
+///
+/// Candidate membership is checked by the repository when the votes are
+/// written.
+pub fn validate_ballot(
+    poll: &Poll,
+    choices: &[Uuid],
+    now: DateTime<Utc>,
+) -> Result<(), BallotError> {
+    ensure_open(poll, now)?;
+
+    if choices.is_empty() {
+        return Err(BallotError::Empty);
+    }
+
+    let mode = PollMode::parse(&poll.poll_type).unwrap_or(PollMode::Ranking);
+    if !mode.allows_multiple() && choices.len() > 1 {
+        return Err(BallotError::SingleChoiceOnly);
+    }
+    if let Some(max_choices) = poll.max_choices {
+        if choices.len() > max_choices.max(1) as usize {
+            return Err(BallotError::TooManyChoices(max_choices));
+        }
+    }
+
+    let distinct: HashSet<&Uuid> = choices.iter().collect();
+    if distinct.len() != choices.len() {
+        return Err(BallotError::DuplicateChoice);
+    }
+
+    Ok(())
+}
+
+/// Final standing of one candidate in an instant-runoff tally
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunoffStanding {
+    pub candidate_id: Uuid,
+    /// Votes in the last round the candidate took part in
+    pub votes: i64,
+    /// Non-exhausted ballots in that round (percentage denominator)
+    pub round_total: i64,
+    /// 1-based round of elimination; None if still standing at the end
+    pub eliminated_in_round: Option<usize>,
+}
+
+/// Instant-runoff result
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunoffResult {
+    /// Vote counts of each round, by continuing candidate
+    pub rounds: Vec<HashMap<Uuid, i64>>,
+    /// Winner first, then remaining candidates, then eliminated candidates
+    /// in reverse order of elimination
+    pub standings: Vec<RunoffStanding>,
+}
+
+/// Tally ranked ballots by instant runoff
+///
+/// Each round counts every ballot for its highest-ranked continuing
+/// candidate. Counting stops once a candidate holds a strict majority of
+/// the non-exhausted ballots or one candidate remains; otherwise the
+/// candidate with the fewest votes is eliminated. Ties for elimination go
+/// to the candidate with fewer votes in the earliest round where they
+/// differ, then to the later-listed candidate.
+///
+/// `candidates` are ids in listing order; ballot entries that are not
+/// candidates (e.g. removed ones) are skipped.
+pub fn instant_runoff(candidates: &[Uuid], ballots: &[Vec<Uuid>]) -> RunoffResult {
+    let position: HashMap<Uuid, usize> = candidates
+        .iter()
+        .enumerate()
+        .map(|(idx, id)| (*id, idx))
+        .collect();
+    let mut continuing: Vec<Uuid> = candidates.to_vec();
+    let mut rounds: Vec<HashMap<Uuid, i64>> = Vec::new();
+    let mut eliminated: Vec<RunoffStanding> = Vec::new();
+
+    loop {
+        let mut tally: HashMap<Uuid, i64> = continuing.iter().map(|id| (*id, 0)).collect();
+        for ballot in ballots {
+            if let Some(choice) = ballot.iter().find(|id| tally.contains_key(id)) {
+                *tally.entry(*choice).or_insert(0) += 1;
+            }
+        }
+        let active: i64 = tally.values().sum();
+        rounds.push(tally.clone());
+
+        let leader_votes = tally.values().copied().max().unwrap_or(0);
+        if continuing.len() <= 1 || active == 0 || leader_votes * 2 > active {
+            let mut standings: Vec<RunoffStanding> = continuing
+                .iter()
+                .map(|id| RunoffStanding {
+                    candidate_id: *id,
+                    votes: tally[id],
+                    round_total: active,
+                    eliminated_in_round: None,
+                })
+                .collect();
+            standings.sort_by(|a, b| {
+                b.votes
+                    .cmp(&a.votes)
+                    .then_with(|| position[&a.candidate_id].cmp(&position[&b.candidate_id]))
+            });
+            standings.extend(eliminated.into_iter().rev());
+            return RunoffResult { rounds, standings };
+        }
+
+        let loser = *continuing
+            .iter()
+            .min_by(|a, b| {
+                tally[*a]
+                    .cmp(&tally[*b])
+                    .then_with(|| {
+                        // Earliest round where the tied candidates differ
+                        rounds
+                            .iter()
+                            .map(|round| round[*a].cmp(&round[*b]))
+                            .find(|ordering| ordering.is_ne())
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    // Later-listed candidate loses a full tie
+                    .then_with(|| position[*b].cmp(&position[*a]))
+            })
+            .expect("at least two continuing candidates");
+
+        eliminated.push(RunoffStanding {
+            candidate_id: loser,
+            votes: tally[&loser],
+            round_total: active,
+            eliminated_in_round: Some(rounds.len()),
+        });
+        continuing.retain(|id| *id != loser);
+    }
+}
+
+/// Rankings page with poll-level totals
+#[derive(Debug, Clone)]
+pub struct PollRankings {
+    pub rankings: Vec<CandidateWithRank>,
+    pub total_candidates: i32,
+    pub total_votes: i64,
+    pub voter_count: i64,
+    /// Instant-runoff rounds counted (ranked_choice only)
+    pub runoff_rounds: i32,
+}
+
+/// Share of `denominator` in percent (0 when there is nothing to divide by)
+fn percentage(votes: i64, denominator: i64) -> f64 {
+    if denominator > 0 {
+        (votes as f64 / denominator as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Candidate listed without counts (results hidden until close)
+fn hidden_rank(c: PollCandidate, rank: i32) -> CandidateWithRank {
+    ranked(c, rank, 0, 0.0, None)
+}
+
+fn ranked(
+    c: PollCandidate,
+    rank: i32,
+    vote_count: i64,
+    vote_percentage: f64,
+    eliminated_in_round: Option<i32>,
+) -> CandidateWithRank {
+    CandidateWithRank {
+        id: c.id,
+        name: c.name,
+        avatar_url: c.avatar_url,
+        description: c.description,
+        user_id: c.user_id,
+        vote_count,
+        rank,
+        rank_change: 0, // TODO: Track rank changes over time
+        vote_percentage,
+        eliminated_in_round,
+    }
+}
+
+/// Instant-runoff tally cached alongside the poll version it was computed for
+#[derive(Serialize, Deserialize)]
+struct CachedRunoff {
+    version: String,
+    result: RunoffResult,
+}
+
+/// Computes poll rankings according to each poll's mode
+pub struct PollRankingService {
+    repo: PollRepository,
+    redis: ConnectionManager,
+}
+
+impl PollRankingService {
+    /// Cached runoff tallies expire after this long even if unchanged
+    const RUNOFF_CACHE_TTL_SECS: u64 = 3600;
+
+    pub fn new(repo: PollRepository, redis: ConnectionManager) -> Self {
+        Self { repo, redis }
+    }
+
+    /// Get rankings with pagination
+    ///
+    /// ranked_choice polls are tallied by instant runoff over all ballots;
+    /// other modes rank by stored vote counts. multiple_choice percentages
+    /// are shares of voters, since one voter counts toward several
+    /// candidates. With `results_visible` false candidates are listed in
+    /// position order without counts.
+    pub async fn get_rankings(
+        &self,
+        poll: &Poll,
+        limit: i32,
+        offset: i32,
+        results_visible: bool,
+    ) -> anyhow::Result<PollRankings> {
+        if !results_visible {
+            let candidates = self.repo.list_candidates_by_position(poll.id).await?;
+            let rankings = candidates
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .enumerate()
+                .map(|(idx, c)| hidden_rank(c, offset + (idx as i32) + 1))
+                .collect();
+            return Ok(PollRankings {
+                rankings,
+                total_candidates: poll.candidate_count,
+                total_votes: 0,
+                voter_count: poll.voter_count,
+                runoff_rounds: 0,
+            });
+        }
+
+        let mode = PollMode::parse(&poll.poll_type).unwrap_or(PollMode::Ranking);
+        if mode == PollMode::RankedChoice {
+            return self.get_runoff_rankings(poll, limit, offset).await;
+        }
+
+        let denominator = match mode {
+            PollMode::MultipleChoice => poll.voter_count,
+            _ => poll.total_votes,
+        };
+        let rankings = self
+            .repo
+            .list_candidates_by_votes(poll.id, limit, offset)
+            .await?
+            .into_iter()
+            .enumerate()
+            .map(|(idx, c)| {
+                let votes = c.vote_count;
+                ranked(
+                    c,
+                    offset + (idx as i32) + 1,
+                    votes,
+                    percentage(votes, denominator),
+                    None,
+                )
+            })
+            .collect();
+
+        Ok(PollRankings {
+            rankings,
+            total_candidates: poll.candidate_count,
+            total_votes: poll.total_votes,
+            voter_count: poll.voter_count,
+            runoff_rounds: 0,
+        })
+    }
+
+    /// Instant-runoff rankings for a ranked_choice poll
+    ///
+    /// Vote counts and percentages are from the last round each candidate
+    /// took part in.
+    async fn get_runoff_rankings(
+        &self,
+        poll: &Poll,
+        limit: i32,
+        offset: i32,
+    ) -> anyhow::Result<PollRankings> {
+        let candidates = self.repo.list_candidates_by_position(poll.id).await?;
+        let candidate_ids: Vec<Uuid> = candidates.iter().map(|c| c.id).collect();
+        let result = self.runoff(poll, &candidate_ids).await?;
+
+        let mut by_id: HashMap<Uuid, PollCandidate> =
+            candidates.into_iter().map(|c| (c.id, c)).collect();
+        let rankings = result
+            .standings
+            .into_iter()
+            .enumerate()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .filter_map(|(idx, standing)| {
+                let c = by_id.remove(&standing.candidate_id)?;
+                Some(ranked(
+                    c,
+                    (idx + 1) as i32,
+                    standing.votes,
+                    percentage(standing.votes, standing.round_total),
+                    standing.eliminated_in_round.map(|round| round as i32),
+                ))
+            })
+            .collect();
+
+        Ok(PollRankings {
+            rankings,
+            total_candidates: poll.candidate_count,
+            total_votes: poll.total_votes,
+            voter_count: poll.voter_count,
+            runoff_rounds: result.rounds.len() as i32,
+        })
+    }
+
+    /// Instant-runoff tally, cached in Redis per poll version
+    ///
+    /// Ballot and candidate changes bump `polls.updated_at`, so a cached
+    /// tally is reused only while the poll is unchanged.
+    async fn runoff(&self, poll: &Poll, candidate_ids: &[Uuid]) -> anyhow::Result<RunoffResult> {
+        let key = format!("poll:runoff:{}", poll.id);
+        let version = format!(
+            "{}:{}",
+            poll.updated_at.timestamp_micros(),
+            poll.candidate_count
+        );
+
+        let mut redis = self.redis.clone();
+        match redis.get::<_, Option<String>>(&key).await {
+            Ok(Some(json)) => match serde_json::from_str::<CachedRunoff>(&json) {
+                Ok(cached) if cached.version == version => return Ok(cached.result),
+                Ok(_) => {}
+                Err(e) => warn!(poll_id = %poll.id, "Discarding unreadable runoff cache: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => warn!(poll_id = %poll.id, "Runoff cache read failed: {}", e),
+        }
+
+        let ballots = self.repo.get_ballots(poll.id).await?;
+        let result = instant_runoff(candidate_ids, &ballots);
+
+        let cached = CachedRunoff { version, result };
+        match serde_json::to_string(&cached) {
+            Ok(json) => {
+                if let Err(e) = redis
+                    .set_ex::<_, _, ()>(&key, json, Self::RUNOFF_CACHE_TTL_SECS)
+                    .await
+                {
+                    warn!(poll_id = %poll.id, "Runoff cache write failed: {}", e);
+                }
+            }
+            Err(e) => warn!(poll_id = %poll.id, "Failed to serialize runoff tally: {}", e),
+        }
+
+        Ok(cached.result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn poll(poll_type: &str, max_choices: Option<i32>) -> Poll {
+        let now = Utc::now();
+        Poll {
+            id: Uuid::new_v4(),
+            title: "Best pastry".to_string(),
+            description: None,
+            cover_image_url: None,
+            creator_id: Uuid::new_v4(),
+            poll_type: poll_type.to_string(),
+            status: "active".to_string(),
+            total_votes: 0,
+            candidate_count: 4,
+            tags: vec![],
+            created_at: now,
+            updated_at: now,
+            ends_at: None,
+            is_deleted: false,
+            max_choices,
+            starts_at: None,
+            anonymous: false,
+            hide_results_until_close: false,
+            voter_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_schedule_phases() {
+        let now = Utc::now();
+        let mut scheduled = poll("single_choice", None);
+        scheduled.starts_at = Some(now + Duration::hours(1));
+        scheduled.ends_at = Some(now + Duration::hours(2));
+        assert_eq!(poll_phase(&scheduled, now), PollPhase::Scheduled);
+        assert_eq!(effective_status(&scheduled, now), "scheduled");
+        assert_eq!(
+            validate_ballot(&scheduled, &[Uuid::new_v4()], now),
+            Err(BallotError::NotOpenYet)
+        );
+
+        let open_at = now + Duration::minutes(90);
+        assert_eq!(poll_phase(&scheduled, open_at), PollPhase::Open);
+        assert_eq!(effective_status(&scheduled, open_at), "active");
+
+        let after = now + Duration::hours(3);
+        assert_eq!(effective_status(&scheduled, after), "closed");
+        assert_eq!(
+            validate_ballot(&scheduled, &[Uuid::new_v4()], after),
+            Err(BallotError::Closed)
+        );
+    }
+
+    #[test]
+    fn test_results_visibility() {
+        let now = Utc::now();
+        let mut hidden = poll("single_choice", None);
+        hidden.hide_results_until_close = true;
+        hidden.ends_at = Some(now + Duration::hours(1));
+
+        assert!(!results_visible(&hidden, None, now));
+        assert!(!results_visible(&hidden, Some(Uuid::new_v4()), now));
+        assert!(results_visible(&hidden, Some(hidden.creator_id), now));
+        assert!(results_visible(&hidden, None, now + Duration::hours(2)));
+    }
+
+    #[test]
+    fn test_ballot_validation_per_mode() {
+        let now = Utc::now();
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let single = poll("single_choice", None);
+        assert_eq!(validate_ballot(&single, &[a], now), Ok(()));
+        assert_eq!(
+            validate_ballot(&single, &[a, b], now),
+            Err(BallotError::SingleChoiceOnly)
+        );
+        assert_eq!(validate_ballot(&single, &[], now), Err(BallotError::Empty));
+
+        let multi = poll("multiple_choice", Some(2));
+        assert_eq!(validate_ballot(&multi, &[a, b], now), Ok(()));
+        assert_eq!(
+            validate_ballot(&multi, &[a, b, c], now),
+            Err(BallotError::TooManyChoices(2))
+        );
+        assert_eq!(
+            validate_ballot(&multi, &[a, a], now),
+            Err(BallotError::DuplicateChoice)
+        );
+
+        let ranked = poll("ranked_choice", None);
+        assert_eq!(validate_ballot(&ranked, &[c, a, b], now), Ok(()));
+    }
+
+    #[test]
+    fn test_instant_runoff_transfers_votes() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        // Round 1: a 4, b 3, c 2 -> c eliminated, c's ballots go to b
+        let mut ballots = vec![vec![a, b]; 4];
+        ballots.extend(vec![vec![b, a]; 3]);
+        ballots.extend(vec![vec![c, b]; 2]);
+
+        let result = instant_runoff(&[a, b, c], &ballots);
+
+        assert_eq!(result.rounds.len(), 2);
+        assert_eq!(result.rounds[0][&a], 4);
+        assert_eq!(result.rounds[1][&b], 5);
+        let order: Vec<Uuid> = result.standings.iter().map(|s| s.candidate_id).collect();
+        assert_eq!(order, vec![b, a, c]);
+        assert_eq!(result.standings[0].votes, 5);
+        assert_eq!(result.standings[0].round_total, 9);
+        assert_eq!(result.standings[2].eliminated_in_round, Some(1));
+    }
+
+    #[test]
+    fn test_instant_runoff_exhausted_ballots_and_ties() {
+        let (a, b, c, d) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        // c and d tie at 1 with no history; d (listed later) goes first.
+        // The d ballot is exhausted, then c's ballot moves to a.
+        let ballots = vec![vec![a], vec![a], vec![b], vec![b], vec![c, a], vec![d]];
+
+        let result = instant_runoff(&[a, b, c, d], &ballots);
+
+        let order: Vec<Uuid> = result.standings.iter().map(|s| s.candidate_id).collect();
+        assert_eq!(order, vec![a, b, c, d]);
+        assert_eq!(result.standings[0].votes, 3);
+        assert_eq!(result.standings[0].round_total, 5);
+        assert_eq!(result.standings[3].eliminated_in_round, Some(1));
+
+        let empty = instant_runoff(&[a, b], &[]);
+        assert_eq!(empty.rounds.len(), 1);
+        assert!(empty
+            .standings
+            .iter()
+            .all(|s| s.eliminated_in_round.is_none()));
+    }
+
+    #[test]
+    fn test_instant_runoff_tie_breaks_on_earliest_round() {
+        let (a, b, c, d, e) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        // Round 1: a=5 b=4 c=10 d=3 e=2 → e out, both e ballots move to b
+        // Round 2: a=5 b=6 c=10 d=3     → d out, 2 to a and 1 to b
+        // Round 3: a=7 b=7 c=10         → tie; b had fewer in round 1
+        let ballots = [
+            vec![vec![c]; 10],
+            vec![vec![a]; 5],
+            vec![vec![b]; 4],
+            vec![vec![e, b]; 2],
+            vec![vec![d, a]; 2],
+            vec![vec![d, b]],
+        ]
+        .concat();
+
+        let result = instant_runoff(&[a, b, c, d, e], &ballots);
+
+        let order: Vec<Uuid> = result.standings.iter().map(|s| s.candidate_id).collect();
+        assert_eq!(order, vec![c, a, b, d, e]);
+        assert_eq!(result.standings[2].eliminated_in_round, Some(3));
+    }
+}
@@ -0,0 +1,459 @@
+//! Adaptive sharding for hot Redis counters
+//!
+//! Every like/comment/share of a post lands on one key (`post:{id}:likes`, ...),
+//! which turns into a hotspot when a post goes viral. When the write rate of a
+//! key seen by this instance crosses `hot_writes_per_sec`, the key is split:
+//!
+//! - `{key}:shards` (marker) holds the shard count N
+//! - writes go to a random `{key}:shard:{0..N}` (or to the base key if the
+//!   marker is gone by the time the write runs)
+//! - `{key}:hot` is refreshed (TTL `cooldown`) while any instance still sees
+//!   a high write rate
+//!
+//! The logical value is always `base + sum(shards)`, so a write that still
+//! lands on the base key (an instance that has not seen the split yet) is
+//! counted too. Summed reads are cached in-process for `read_cache_ttl`. Once
+//! the hot flag expires, the next read folds the shards back into the base
+//! key and drops the marker, so cold posts go back to a single key.
+//!
+//! All of this is internal to `CounterService`: callers (`BatchGetCounts`,
+//! the reconciler) only ever see summed values.
+
+use prometheus::IntCounter;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Configuration for hot-key counter sharding
+#[derive(Debug, Clone)]
+pub struct ShardedCounterConfig {
+    pub enabled: bool,
+    /// Sub-keys per sharded counter
+    pub shards: u32,
+    /// Writes per second (per instance) that mark a key as hot
+    pub hot_writes_per_sec: u64,
+    /// How long a key stays sharded after its last hot second
+    pub cooldown: Duration,
+    /// Local cache TTL for summed reads of sharded keys
+    pub read_cache_ttl: Duration,
+    /// How long this instance trusts what it knows about a key's shard marker
+    pub state_cache_ttl: Duration,
+}
+
+impl Default for ShardedCounterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shards: 8,
+            hot_writes_per_sec: 50,
+            cooldown: Duration::from_secs(60),
+            read_cache_ttl: Duration::from_secs(1),
+            state_cache_ttl: Duration::from_secs(1),
+        }
+    }
+}
+
+impl ShardedCounterConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("SOCIAL_COUNTER_SHARDING_ENABLED")
+                .map(|v| v != "0" && !v.eq_ignore_ascii_case("false"))
+                .unwrap_or(defaults.enabled),
+            shards: std::env::var("SOCIAL_COUNTER_SHARDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n: &u32| *n >= 2)
+                .unwrap_or(defaults.shards),
+            hot_writes_per_sec: std::env::var("SOCIAL_COUNTER_HOT_WRITES_PER_SEC")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n: &u64| *n > 0)
+                .unwrap_or(defaults.hot_writes_per_sec),
+            cooldown: std::env::var("SOCIAL_COUNTER_HOT_COOLDOWN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.cooldown),
+            read_cache_ttl: std::env::var("SOCIAL_COUNTER_SHARD_READ_CACHE_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.read_cache_ttl),
+            state_cache_ttl: defaults.state_cache_ttl,
+        }
+    }
+}
+
+/// Marker key holding the shard count of a sharded counter
+pub fn marker_key(key: &str) -> String {
+    format!("{}:shards", key)
+}
+
+/// Flag kept alive while the counter is hot
+pub fn hot_key(key: &str) -> String {
+    format!("{}:hot", key)
+}
+
+pub fn shard_key(key: &str, shard: u32) -> String {
+    format!("{}:shard:{}", key, shard)
+}
+
+pub fn shard_keys(key: &str, shards: u32) -> Vec<String> {
+    (0..shards).map(|i| shard_key(key, i)).collect()
+}
+
+/// Random shard for the next write
+pub fn pick_shard(shards: u32) -> u32 {
+    (Uuid::new_v4().as_u128() % shards.max(1) as u128) as u32
+}
+
+/// Logical value of a sharded counter (never negative)
+///
+/// Individual shards can go negative when a decrement lands on a shard that
+/// never saw the matching increment.
+pub fn sum_shards(base: Option<i64>, shards: &[Option<i64>]) -> i64 {
+    (base.unwrap_or(0) + shards.iter().flatten().sum::<i64>()).max(0)
+}
+
+/// Splits a counter (or refreshes its hot flag if already split)
+///
+/// KEYS: base, marker, hot, shard keys; ARGV: shard count, counter TTL, cooldown.
+/// Leftovers from a previous split (writes that raced the last fold) are
+/// moved into the base key before the new marker is set.
+pub const SPLIT_SCRIPT: &str = r#"
+redis.call('SET', KEYS[3], 1, 'EX', ARGV[3])
+if redis.call('EXISTS', KEYS[2]) == 1 then
+    redis.call('EXPIRE', KEYS[2], ARGV[2])
+    return 0
+end
+local sum = 0
+for i = 4, #KEYS do
+    local v = redis.call('GET', KEYS[i])
+    if v then
+        sum = sum + tonumber(v)
+        redis.call('DEL', KEYS[i])
+    end
+end
+if sum ~= 0 then
+    redis.call('INCRBY', KEYS[1], sum)
+    redis.call('EXPIRE', KEYS[1], ARGV[2])
+end
+redis.call('SET', KEYS[2], ARGV[1], 'EX', ARGV[2])
+return 1
+"#;
+
+/// Folds shards back into the base key unless the counter is still hot
+///
+/// KEYS: base, marker, hot, shard keys; ARGV: counter TTL.
+pub const FOLD_SCRIPT: &str = r#"
+if redis.call('EXISTS', KEYS[3]) == 1 or redis.call('EXISTS', KEYS[2]) == 0 then
+    return 0
+end
+local sum = 0
+for i = 4, #KEYS do
+    local v = redis.call('GET', KEYS[i])
+    if v then
+        sum = sum + tonumber(v)
+        redis.call('DEL', KEYS[i])
+    end
+end
+redis.call('DEL', KEYS[2])
+if sum ~= 0 then
+    redis.call('INCRBY', KEYS[1], sum)
+    redis.call('EXPIRE', KEYS[1], ARGV[1])
+end
+return 1
+"#;
+
+/// Adds to one shard while the counter is still split, else to the base key
+///
+/// KEYS: base, marker, shard; ARGV: delta, counter TTL.
+/// An instance that cached the shard count can write after another instance
+/// folded the counter; checking the marker in the same script sends that
+/// write to the base key instead of an orphaned shard. Returns 1 if the shard
+/// was written.
+pub const SHARD_INCR_SCRIPT: &str = r#"
+local target = KEYS[1]
+local sharded = redis.call('EXISTS', KEYS[2])
+if sharded == 1 then
+    target = KEYS[3]
+end
+redis.call('INCRBY', target, ARGV[1])
+redis.call('EXPIRE', target, ARGV[2])
+return sharded
+"#;
+
+/// Overwrites a counter, dropping the shards named by its marker
+///
+/// KEYS: base, marker; ARGV: value, counter TTL.
+/// Shard keys are derived from the marker's count (see `shard_key`) rather
+/// than the local configuration, which may differ from the splitting instance.
+pub const SET_SCRIPT: &str = r#"
+local shards = tonumber(redis.call('GET', KEYS[2]) or '0') or 0
+for i = 0, shards - 1 do
+    redis.call('DEL', KEYS[1] .. ':shard:' .. i)
+end
+redis.call('DEL', KEYS[2])
+redis.call('SET', KEYS[1], ARGV[1], 'EX', ARGV[2])
+return 1
+"#;
+
+/// Replaces an observed counter value with the recounted one
+///
+/// KEYS: base, marker; ARGV: observed, actual, counter TTL.
+/// Unsharded keys use compare-and-set so a write racing the recount is not
+/// overwritten; sharded keys are shifted by the difference instead.
+pub const REPAIR_SCRIPT: &str = r#"
+if redis.call('EXISTS', KEYS[2]) == 1 then
+    redis.call('INCRBY', KEYS[1], tonumber(ARGV[2]) - tonumber(ARGV[1]))
+    redis.call('EXPIRE', KEYS[1], ARGV[3])
+    return 1
+end
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+    return 1
+end
+return 0
+"#;
+
+/// Per-instance write rate per key, in one-second windows
+#[derive(Debug)]
+pub struct HotKeyTracker {
+    threshold: u64,
+    windows: HashMap<String, (u64, u64)>,
+}
+
+impl HotKeyTracker {
+    /// Tracked keys before stale windows are pruned
+    const MAX_TRACKED: usize = 10_000;
+
+    pub fn new(threshold: u64) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Count a write in second `now`; true exactly once per window, when the
+    /// key reaches the threshold
+    pub fn record(&mut self, key: &str, now: u64) -> bool {
+        if self.windows.len() >= Self::MAX_TRACKED && !self.windows.contains_key(key) {
+            self.windows.retain(|_, (second, _)| *second == now);
+        }
+
+        let window = self.windows.entry(key.to_string()).or_insert((now, 0));
+        if window.0 != now {
+            *window = (now, 0);
+        }
+        window.1 += 1;
+        window.1 == self.threshold
+    }
+}
+
+/// In-process sharding state shared by `CounterService` clones
+#[derive(Debug)]
+pub struct ShardState {
+    pub config: ShardedCounterConfig,
+    tracker: Mutex<HotKeyTracker>,
+    /// key -> (shard count, 0 if not sharded; when learned)
+    markers: Mutex<HashMap<String, (u32, Instant)>>,
+    /// key -> (summed value, when read)
+    totals: Mutex<HashMap<String, (i64, Instant)>>,
+}
+
+impl ShardState {
+    pub fn new(config: ShardedCounterConfig) -> Self {
+        Self {
+            tracker: Mutex::new(HotKeyTracker::new(config.hot_writes_per_sec)),
+            markers: Mutex::new(HashMap::new()),
+            totals: Mutex::new(HashMap::new()),
+            config,
+        }
+    }
+
+    /// Count a write; true when the key just became hot on this instance
+    pub fn record_write(&self, key: &str) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        self.tracker
+            .lock()
+            .map(|mut tracker| tracker.record(key, now))
+            .unwrap_or(false)
+    }
+
+    /// Shard count if this instance recently saw the key's marker
+    pub fn shard_count(&self, key: &str) -> Option<u32> {
+        let markers = self.markers.lock().ok()?;
+        markers
+            .get(key)
+            .filter(|(shards, seen)| *shards > 0 && seen.elapsed() < self.config.state_cache_ttl)
+            .map(|(shards, _)| *shards)
+    }
+
+    /// Remember the key's marker (0 = not sharded)
+    pub fn set_shard_count(&self, key: &str, shards: u32) {
+        if let Ok(mut markers) = self.markers.lock() {
+            if shards == 0 {
+                markers.remove(key);
+            } else {
+                if markers.len() >= HotKeyTracker::MAX_TRACKED {
+                    let ttl = self.config.state_cache_ttl;
+                    markers.retain(|_, (_, seen)| seen.elapsed() < ttl);
+                }
+                markers.insert(key.to_string(), (shards, Instant::now()));
+            }
+        }
+    }
+
+    pub fn cached_total(&self, key: &str) -> Option<i64> {
+        let totals = self.totals.lock().ok()?;
+        totals
+            .get(key)
+            .filter(|(_, read)| read.elapsed() < self.config.read_cache_ttl)
+            .map(|(total, _)| *total)
+    }
+
+    pub fn cache_total(&self, key: &str, total: i64) {
+        if let Ok(mut totals) = self.totals.lock() {
+            if totals.len() >= HotKeyTracker::MAX_TRACKED {
+                let ttl = self.config.read_cache_ttl;
+                totals.retain(|_, (_, read)| read.elapsed() < ttl);
+            }
+            totals.insert(key.to_string(), (total, Instant::now()));
+        }
+    }
+
+    /// Apply a local write to the cached total (keeps the read cache monotonic
+    /// for the writing instance); returns the new total if one was cached
+    pub fn adjust_cached_total(&self, key: &str, delta: i64) -> Option<i64> {
+        let mut totals = self.totals.lock().ok()?;
+        let (total, read) = totals.get_mut(key)?;
+        if read.elapsed() >= self.config.read_cache_ttl {
+            return None;
+        }
+        *total = (*total + delta).max(0);
+        Some(*total)
+    }
+
+    /// Drop everything known about a key (after it was overwritten or folded)
+    pub fn forget(&self, key: &str) {
+        if let Ok(mut markers) = self.markers.lock() {
+            markers.remove(key);
+        }
+        if let Ok(mut totals) = self.totals.lock() {
+            totals.remove(key);
+        }
+    }
+}
+
+/// Prometheus metrics for counter sharding
+pub struct ShardMetrics {
+    pub splits_total: IntCounter,
+    pub folds_total: IntCounter,
+}
+
+impl ShardMetrics {
+    fn new() -> Self {
+        let registry = prometheus::default_registry();
+
+        let splits_total = IntCounter::new(
+            "social_counter_shard_splits_total",
+            "Hot counters split into sub-keys",
+        )
+        .expect("valid metric for social_counter_shard_splits_total");
+
+        let folds_total = IntCounter::new(
+            "social_counter_shard_folds_total",
+            "Sharded counters collapsed back into a single key",
+        )
+        .expect("valid metric for social_counter_shard_folds_total");
+
+        let _ = registry.register(Box::new(splits_total.clone()));
+        let _ = registry.register(Box::new(folds_total.clone()));
+
+        Self {
+            splits_total,
+            folds_total,
+        }
+    }
+
+    /// Process-wide metrics (registered once)
+    pub fn global() -> &'static ShardMetrics {
+        static METRICS: OnceLock<ShardMetrics> = OnceLock::new();
+        METRICS.get_or_init(ShardMetrics::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hot_key_tracker_fires_once_per_window() {
+        let mut tracker = HotKeyTracker::new(3);
+
+        assert!(!tracker.record("post:a:likes", 100));
+        assert!(!tracker.record("post:a:likes", 100));
+        assert!(tracker.record("post:a:likes", 100));
+        assert!(!tracker.record("post:a:likes", 100));
+        // Other keys are tracked separately
+        assert!(!tracker.record("post:b:likes", 100));
+
+        // New window starts over
+        assert!(!tracker.record("post:a:likes", 101));
+        assert!(!tracker.record("post:a:likes", 101));
+        assert!(tracker.record("post:a:likes", 101));
+    }
+
+    #[test]
+    fn test_shard_keys_and_sum() {
+        assert_eq!(marker_key("post:1:likes"), "post:1:likes:shards");
+        assert_eq!(hot_key("post:1:likes"), "post:1:likes:hot");
+        assert_eq!(
+            shard_keys("post:1:likes", 2),
+            vec!["post:1:likes:shard:0", "post:1:likes:shard:1"]
+        );
+        assert!(pick_shard(4) < 4);
+
+        assert_eq!(sum_shards(Some(10), &[Some(3), None, Some(-1)]), 12);
+        assert_eq!(sum_shards(None, &[Some(2)]), 2);
+        assert_eq!(sum_shards(Some(1), &[Some(-5)]), 0);
+    }
+
+    #[test]
+    fn test_shard_state_caches() {
+        let state = ShardState::new(ShardedCounterConfig {
+            hot_writes_per_sec: 2,
+            ..Default::default()
+        });
+
+        assert!(!state.record_write("k"));
+        // Second write in the same second crosses the threshold (unless the
+        // clock ticked over in between)
+        let _ = state.record_write("k");
+
+        assert_eq!(state.shard_count("k"), None);
+        state.set_shard_count("k", 8);
+        assert_eq!(state.shard_count("k"), Some(8));
+
+        assert_eq!(state.adjust_cached_total("k", 1), None);
+        state.cache_total("k", 5);
+        assert_eq!(state.adjust_cached_total("k", 2), Some(7));
+        assert_eq!(state.adjust_cached_total("k", -10), Some(0));
+
+        state.forget("k");
+        assert_eq!(state.shard_count("k"), None);
+        assert_eq!(state.cached_total("k"), None);
+
+        let disabled = ShardState::new(ShardedCounterConfig {
+            enabled: false,
+            hot_writes_per_sec: 1,
+            ..Default::default()
+        });
+        assert!(!disabled.record_write("k"));
+    }
+}
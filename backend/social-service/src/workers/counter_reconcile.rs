@@ -0,0 +1,143 @@
+//! Counter Reconciliation Background Job
+//!
+//! Each pass checks up to `hot_limit` recently written posts (the hot set
+//! kept by `CounterService::mark_hot_post`; any overflow waits for the next
+//! pass) plus the next page of a rolling
+//! sample over `post_counters`, so cold posts are eventually verified too.
+//! Drift is repaired and reported via `social_counter_drift_*` metrics.
+
+use crate::services::counter_reconciler::ReconcileMetrics;
+use crate::services::CounterReconciler;
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::time::sleep;
+use uuid::Uuid;
+
+/// Posts per recount query
+const BATCH_SIZE: usize = 100;
+
+/// Configuration for counter reconciliation
+#[derive(Debug, Clone)]
+pub struct CounterReconcileConfig {
+    pub enabled: bool,
+    pub interval: Duration,
+    /// Max hot posts checked per pass
+    pub hot_limit: usize,
+    /// Rolling sample size per pass
+    pub sample_size: i64,
+}
+
+impl Default for CounterReconcileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: Duration::from_secs(300),
+            hot_limit: 1000,
+            sample_size: 500,
+        }
+    }
+}
+
+impl CounterReconcileConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("SOCIAL_COUNTER_RECONCILE_ENABLED")
+                .map(|v| v != "0" && !v.eq_ignore_ascii_case("false"))
+                .unwrap_or(defaults.enabled),
+            interval: std::env::var("SOCIAL_COUNTER_RECONCILE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+            hot_limit: std::env::var("SOCIAL_COUNTER_RECONCILE_HOT_LIMIT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.hot_limit),
+            sample_size: std::env::var("SOCIAL_COUNTER_RECONCILE_SAMPLE_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.sample_size),
+        }
+    }
+}
+
+/// Start the counter reconciliation background job
+pub async fn start_counter_reconcile(
+    reconciler: CounterReconciler,
+    config: CounterReconcileConfig,
+) {
+    if !config.enabled {
+        tracing::info!("Counter reconciliation disabled by configuration");
+        return;
+    }
+
+    tracing::info!(
+        interval_secs = config.interval.as_secs(),
+        hot_limit = config.hot_limit,
+        sample_size = config.sample_size,
+        "Starting counter reconciliation background job"
+    );
+
+    let metrics = ReconcileMetrics::global();
+    let mut cursor: Option<Uuid> = None;
+
+    loop {
+        sleep(config.interval).await;
+
+        let hot = match reconciler.counters().take_hot_posts(config.hot_limit).await {
+            Ok(posts) => posts,
+            Err(e) => {
+                metrics.errors_total.inc();
+                tracing::warn!(error = ?e, "Failed to read hot posts");
+                Vec::new()
+            }
+        };
+
+        let sample = match reconciler.sample_posts(cursor, config.sample_size).await {
+            Ok(posts) => posts,
+            Err(e) => {
+                metrics.errors_total.inc();
+                tracing::warn!(error = ?e, "Failed to sample posts");
+                Vec::new()
+            }
+        };
+        // Wrap around once the sample reaches the end of the table
+        cursor = if (sample.len() as i64) < config.sample_size {
+            None
+        } else {
+            sample.last().copied()
+        };
+
+        let mut seen = HashSet::new();
+        let post_ids: Vec<Uuid> = hot
+            .iter()
+            .chain(sample.iter())
+            .filter(|id| seen.insert(**id))
+            .copied()
+            .collect();
+
+        let mut checked = 0;
+        let mut drifted = 0;
+        for batch in post_ids.chunks(BATCH_SIZE) {
+            match reconciler.reconcile_posts(batch).await {
+                Ok(report) => {
+                    checked += report.posts_checked;
+                    drifted += report.drifts.len();
+                }
+                Err(e) => {
+                    metrics.errors_total.inc();
+                    tracing::warn!(error = ?e, batch_size = batch.len(), "Counter reconciliation batch failed");
+                }
+            }
+        }
+
+        tracing::debug!(
+            hot = hot.len(),
+            sampled = sample.len(),
+            checked,
+            drifted,
+            "Counter reconciliation pass complete"
+        );
+    }
+}
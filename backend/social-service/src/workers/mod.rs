@@ -1,3 +1,4 @@
+pub mod counter_reconcile;
 pub mod graph_sync;
 pub mod outbox_worker;
 pub mod redis_health;